@@ -1,3 +1,39 @@
+use std::fs;
+use std::path::Path;
+
+#[path = "src/scorm/runtime_minify.rs"]
+mod runtime_minify;
+
 fn main() {
+    build_runtime_assets();
     tauri_build::build()
 }
+
+/// Minify the course runtime scripts into `OUT_DIR/runtime`, where
+/// `scorm::runtime_assets` embeds them with `include_bytes!`
+fn build_runtime_assets() {
+    let source_dir = Path::new("src/scorm/runtime");
+    let out_dir = Path::new(&std::env::var("OUT_DIR").unwrap()).join("runtime");
+    let version = std::env::var("CARGO_PKG_VERSION").unwrap();
+
+    println!("cargo:rerun-if-changed={}", source_dir.display());
+    fs::create_dir_all(&out_dir).expect("Failed to create runtime output directory");
+
+    for entry in fs::read_dir(source_dir).expect("Failed to read src/scorm/runtime") {
+        let path = entry.expect("Failed to read runtime entry").path();
+        if path.extension().and_then(|e| e.to_str()) != Some("js") {
+            continue;
+        }
+
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let source = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read runtime script {name}: {e}"));
+        let minified = format!(
+            "{}{}",
+            runtime_minify::runtime_banner(&name, &version),
+            runtime_minify::minify_js(&source)
+        );
+        fs::write(out_dir.join(&name), minified)
+            .unwrap_or_else(|e| panic!("Failed to write runtime script {name}: {e}"));
+    }
+}
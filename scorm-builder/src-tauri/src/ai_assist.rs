@@ -0,0 +1,511 @@
+//! Content generation through an AI provider, called from the backend.
+//!
+//! The provider key is saved once, encrypted next to the other API keys, and
+//! only ever read here: the webview can ask whether a key is set but never
+//! gets it back. Requests to OpenAI, Azure OpenAI or Anthropic are streamed;
+//! each piece of text is emitted as an `ai-content-delta` event as it
+//! arrives and the whole text is returned at the end. Rate limits, server
+//! errors and dropped connections are retried with backoff until the first
+//! text has arrived. Requests and tokens count towards the provider's daily
+//! limit in `api_quota`.
+
+use crate::api_keys::{get_encrypted_file_path, load_encrypted, save_encrypted};
+use crate::api_quota::{check_quota, record_usage, today};
+use crate::app_services::AppServices;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::time::Duration;
+use tauri::Emitter;
+
+const AI_PROVIDER_FILE: &str = "ai_provider.enc";
+/// Tries per request, including the first
+const MAX_ATTEMPTS: u32 = 3;
+/// Wait before the first retry; doubled for each one after
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const DEFAULT_MAX_TOKENS: u32 = 1024;
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const DEFAULT_AZURE_API_VERSION: &str = "2024-06-01";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProvider {
+    OpenAi,
+    Azure,
+    Anthropic,
+}
+
+impl AiProvider {
+    /// Name usage is counted and limited under
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Azure => "azure",
+            Self::Anthropic => "anthropic",
+        }
+    }
+}
+
+/// Provider, model and credentials, as saved by the settings screen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiProviderConfig {
+    pub provider: AiProvider,
+    pub api_key: String,
+    /// Model name; for Azure, the deployment name
+    pub model: String,
+    /// Azure resource endpoint, e.g. `https://my-resource.openai.azure.com`
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Azure API version
+    #[serde(default)]
+    pub api_version: Option<String>,
+}
+
+/// What the webview may know about the saved provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AiProviderStatus {
+    pub provider: AiProvider,
+    pub model: String,
+    pub endpoint: Option<String>,
+    pub has_api_key: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiContentRequest {
+    pub prompt: String,
+    #[serde(default)]
+    pub system: Option<String>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Echoed in `ai-content-delta` events so callers can tell concurrent requests apart
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AiContentResult {
+    pub text: String,
+    pub provider: AiProvider,
+    pub model: String,
+}
+
+/// An HTTP request to a provider, before it is sent
+#[derive(Debug, Clone, PartialEq)]
+struct ProviderRequest {
+    url: String,
+    headers: Vec<(&'static str, String)>,
+    body: Value,
+}
+
+/// One line of a provider's server-sent event stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StreamEvent {
+    Text(String),
+    /// Tokens reported as used, added up over the stream
+    Usage(u64),
+    Done,
+    Error(String),
+    Ignore,
+}
+
+fn build_request(
+    config: &AiProviderConfig,
+    request: &AiContentRequest,
+) -> Result<ProviderRequest, String> {
+    let max_tokens = request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    let mut chat_messages = Vec::new();
+    if let Some(system) = &request.system {
+        chat_messages.push(json!({ "role": "system", "content": system }));
+    }
+    chat_messages.push(json!({ "role": "user", "content": request.prompt }));
+
+    let (url, headers, mut body) = match config.provider {
+        AiProvider::OpenAi => (
+            "https://api.openai.com/v1/chat/completions".to_string(),
+            vec![("Authorization", format!("Bearer {}", config.api_key))],
+            json!({
+                "model": config.model,
+                "messages": chat_messages,
+                "max_tokens": max_tokens,
+                "stream_options": { "include_usage": true }
+            }),
+        ),
+        AiProvider::Azure => {
+            let endpoint = config
+                .endpoint
+                .as_deref()
+                .filter(|endpoint| !endpoint.trim().is_empty())
+                .ok_or("Azure OpenAI needs the resource endpoint")?;
+            (
+                format!(
+                    "{}/openai/deployments/{}/chat/completions?api-version={}",
+                    endpoint.trim().trim_end_matches('/'),
+                    config.model,
+                    config
+                        .api_version
+                        .as_deref()
+                        .unwrap_or(DEFAULT_AZURE_API_VERSION)
+                ),
+                vec![("api-key", config.api_key.clone())],
+                json!({ "messages": chat_messages, "max_tokens": max_tokens }),
+            )
+        }
+        AiProvider::Anthropic => {
+            let mut body = json!({
+                "model": config.model,
+                "messages": [{ "role": "user", "content": request.prompt }],
+                "max_tokens": max_tokens
+            });
+            if let Some(system) = &request.system {
+                body["system"] = json!(system);
+            }
+            (
+                "https://api.anthropic.com/v1/messages".to_string(),
+                vec![
+                    ("x-api-key", config.api_key.clone()),
+                    ("anthropic-version", ANTHROPIC_VERSION.to_string()),
+                ],
+                body,
+            )
+        }
+    };
+    body["stream"] = json!(true);
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    Ok(ProviderRequest { url, headers, body })
+}
+
+fn parse_stream_line(provider: AiProvider, line: &str) -> StreamEvent {
+    let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+        return StreamEvent::Ignore;
+    };
+    if data == "[DONE]" {
+        return StreamEvent::Done;
+    }
+    let Ok(event) = serde_json::from_str::<Value>(data) else {
+        return StreamEvent::Ignore;
+    };
+    if let Some(message) = event["error"]["message"].as_str() {
+        return StreamEvent::Error(message.to_string());
+    }
+    // OpenAI reports totals in a last chunk; Anthropic input tokens when the
+    // message starts and output tokens when it ends
+    let usage = event
+        .get("usage")
+        .or_else(|| event["message"].get("usage"))
+        .filter(|usage| usage.is_object());
+    if let Some(usage) = usage {
+        let count = |field: &str| usage[field].as_u64().unwrap_or(0);
+        return StreamEvent::Usage(
+            usage["total_tokens"]
+                .as_u64()
+                .unwrap_or_else(|| count("input_tokens") + count("output_tokens")),
+        );
+    }
+    let text = match provider {
+        AiProvider::OpenAi | AiProvider::Azure => event["choices"][0]["delta"]["content"].as_str(),
+        AiProvider::Anthropic => match event["type"].as_str() {
+            Some("message_stop") => return StreamEvent::Done,
+            Some("content_block_delta") => event["delta"]["text"].as_str(),
+            _ => None,
+        },
+    };
+    match text {
+        Some(text) if !text.is_empty() => StreamEvent::Text(text.to_string()),
+        _ => StreamEvent::Ignore,
+    }
+}
+
+/// Message in a provider's error response, or the start of the body
+fn provider_error(body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|value| value["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.chars().take(300).collect())
+}
+
+fn load_provider() -> Result<Option<AiProviderConfig>, String> {
+    let path = get_encrypted_file_path(AI_PROVIDER_FILE)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    load_encrypted(&path).map(Some)
+}
+
+/// Save the provider settings. The key is kept encrypted and never returned;
+/// an empty key keeps the stored one when the provider is unchanged.
+#[tauri::command]
+pub fn save_ai_provider(mut config: AiProviderConfig) -> Result<(), String> {
+    if config.api_key.trim().is_empty() {
+        if let Some(stored) = load_provider()?.filter(|stored| stored.provider == config.provider) {
+            config.api_key = stored.api_key;
+        }
+    }
+    if config.api_key.trim().is_empty() {
+        return Err("An API key is required".to_string());
+    }
+    if config.model.trim().is_empty() {
+        return Err("A model is required".to_string());
+    }
+    save_encrypted(&get_encrypted_file_path(AI_PROVIDER_FILE)?, &config)
+}
+
+#[tauri::command]
+pub fn get_ai_provider() -> Result<Option<AiProviderStatus>, String> {
+    Ok(load_provider()?.map(|config| AiProviderStatus {
+        provider: config.provider,
+        model: config.model,
+        endpoint: config.endpoint,
+        has_api_key: !config.api_key.is_empty(),
+    }))
+}
+
+#[tauri::command]
+pub fn delete_ai_provider() -> Result<(), String> {
+    let path = get_encrypted_file_path(AI_PROVIDER_FILE)?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to delete AI provider settings: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Generate text with the saved provider, streaming it as `ai-content-delta`
+/// events (`{ requestId, delta }`) and returning all of it
+#[tauri::command]
+pub async fn generate_content_with_ai(
+    app: tauri::AppHandle,
+    services: tauri::State<'_, AppServices>,
+    request: AiContentRequest,
+) -> Result<AiContentResult, String> {
+    let config = load_provider()?.ok_or("No AI provider is configured")?;
+    let day = today(&services);
+    check_quota(config.provider.name(), day)?;
+    let provider_request = build_request(&config, &request)?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let mut text = String::new();
+    let mut tokens = 0;
+    let mut attempt = 1;
+    let outcome = loop {
+        let mut http_request = client
+            .post(&provider_request.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(provider_request.body.to_string());
+        for (name, value) in &provider_request.headers {
+            http_request = http_request.header(*name, value);
+        }
+        let retry = match http_request.send().await {
+            Ok(response) if response.status().is_success() => {
+                let streamed = stream_response(
+                    &app,
+                    &request,
+                    config.provider,
+                    response,
+                    &mut text,
+                    &mut tokens,
+                )
+                .await;
+                match streamed {
+                    Ok(()) => break Ok(()),
+                    // Text already shown can't be taken back, so only retry before any arrived
+                    Err(error) if text.is_empty() => error,
+                    Err(error) => break Err(error),
+                }
+            }
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                let error = format!("AI provider returned {status}: {}", provider_error(&body));
+                if !(status.is_server_error() || status.as_u16() == 429) {
+                    break Err(error);
+                }
+                error
+            }
+            Err(e) => format!("Failed to reach the AI provider: {e}"),
+        };
+        if attempt >= MAX_ATTEMPTS {
+            break Err(retry);
+        }
+        tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+        attempt += 1;
+    };
+
+    // Azure doesn't report usage while streaming; about four characters make a token
+    if tokens == 0 && !text.is_empty() {
+        let characters =
+            request.prompt.len() + request.system.as_deref().map_or(0, str::len) + text.len();
+        tokens = characters.div_ceil(4) as u64;
+    }
+    // Failing to count usage doesn't take the generated text away
+    let _ = record_usage(config.provider.name(), day, u64::from(attempt), tokens);
+    outcome?;
+
+    Ok(AiContentResult {
+        text,
+        provider: config.provider,
+        model: config.model,
+    })
+}
+
+async fn stream_response(
+    app: &tauri::AppHandle,
+    request: &AiContentRequest,
+    provider: AiProvider,
+    mut response: reqwest::Response,
+    text: &mut String,
+    tokens: &mut u64,
+) -> Result<(), String> {
+    // Bytes, not text: a chunk can end inside a multi-byte character
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read the AI response: {e}"))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            match parse_stream_line(provider, String::from_utf8_lossy(&line).trim_end()) {
+                StreamEvent::Text(delta) => {
+                    text.push_str(&delta);
+                    let _ = app.emit(
+                        "ai-content-delta",
+                        json!({ "requestId": request.request_id, "delta": delta }),
+                    );
+                }
+                StreamEvent::Usage(count) => *tokens += count,
+                StreamEvent::Done => return Ok(()),
+                StreamEvent::Error(message) => return Err(format!("AI provider error: {message}")),
+                StreamEvent::Ignore => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: AiProvider) -> AiProviderConfig {
+        AiProviderConfig {
+            provider,
+            api_key: "sk-secret".to_string(),
+            model: "course-writer".to_string(),
+            endpoint: Some("https://acme.openai.azure.com/".to_string()),
+            api_version: None,
+        }
+    }
+
+    fn content_request() -> AiContentRequest {
+        AiContentRequest {
+            prompt: "Write a welcome page about ladder safety".to_string(),
+            system: Some("You write e-learning content.".to_string()),
+            max_tokens: None,
+            temperature: Some(0.2),
+            request_id: None,
+        }
+    }
+
+    #[test]
+    fn test_requests_carry_the_key_only_in_headers() {
+        let openai = build_request(&config(AiProvider::OpenAi), &content_request()).unwrap();
+        assert_eq!(openai.url, "https://api.openai.com/v1/chat/completions");
+        assert_eq!(
+            openai.headers,
+            [("Authorization", "Bearer sk-secret".to_string())]
+        );
+        assert_eq!(openai.body["messages"][0]["role"], "system");
+        assert_eq!(openai.body["stream"], true);
+
+        let azure = build_request(&config(AiProvider::Azure), &content_request()).unwrap();
+        assert_eq!(
+            azure.url,
+            "https://acme.openai.azure.com/openai/deployments/course-writer/chat/completions?api-version=2024-06-01"
+        );
+        assert_eq!(azure.headers[0].0, "api-key");
+        assert!(azure.body.get("model").is_none());
+
+        let anthropic = build_request(&config(AiProvider::Anthropic), &content_request()).unwrap();
+        assert_eq!(anthropic.body["system"], "You write e-learning content.");
+        assert_eq!(anthropic.body["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(anthropic.body["max_tokens"], DEFAULT_MAX_TOKENS);
+
+        for request in [openai, azure, anthropic] {
+            assert!(!request.url.contains("sk-secret"));
+            assert!(!request.body.to_string().contains("sk-secret"));
+        }
+        let status = serde_json::to_string(&AiProviderStatus {
+            provider: AiProvider::Anthropic,
+            model: "course-writer".to_string(),
+            endpoint: None,
+            has_api_key: true,
+        })
+        .unwrap();
+        assert!(status.contains(r#""provider":"anthropic""#) && !status.contains("sk-"));
+    }
+
+    #[test]
+    fn test_parses_streamed_text_from_each_provider() {
+        assert_eq!(
+            parse_stream_line(
+                AiProvider::OpenAi,
+                r#"data: {"choices":[{"delta":{"content":"Ladders"}}]}"#
+            ),
+            StreamEvent::Text("Ladders".to_string())
+        );
+        assert_eq!(
+            parse_stream_line(AiProvider::Azure, "data: [DONE]"),
+            StreamEvent::Done
+        );
+        assert_eq!(
+            parse_stream_line(
+                AiProvider::Anthropic,
+                r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":" slip"}}"#
+            ),
+            StreamEvent::Text(" slip".to_string())
+        );
+        assert_eq!(
+            parse_stream_line(AiProvider::Anthropic, "event: content_block_delta"),
+            StreamEvent::Ignore
+        );
+        assert_eq!(
+            parse_stream_line(AiProvider::Anthropic, r#"data: {"type":"message_stop"}"#),
+            StreamEvent::Done
+        );
+        assert_eq!(
+            parse_stream_line(
+                AiProvider::Anthropic,
+                r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#
+            ),
+            StreamEvent::Error("Overloaded".to_string())
+        );
+        assert_eq!(
+            parse_stream_line(
+                AiProvider::OpenAi,
+                r#"data: {"choices":[],"usage":{"prompt_tokens":20,"completion_tokens":80,"total_tokens":100}}"#
+            ),
+            StreamEvent::Usage(100)
+        );
+        assert_eq!(
+            parse_stream_line(
+                AiProvider::Anthropic,
+                r#"data: {"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":15}}"#
+            ),
+            StreamEvent::Usage(15)
+        );
+        assert_eq!(
+            provider_error(r#"{"error":{"message":"Invalid API key"}}"#),
+            "Invalid API key"
+        );
+    }
+}
@@ -4,24 +4,34 @@ use aes_gcm::{
     Aes256Gcm, Key, Nonce,
 };
 use base64::{engine::general_purpose, Engine as _};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ApiKeys {
     pub google_image_api_key: String,
     pub google_cse_id: String,
     pub youtube_api_key: String,
+    #[serde(default)]
+    pub unsplash_access_key: String,
+    #[serde(default)]
+    pub pexels_api_key: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct EncryptedApiKeys {
+struct EncryptedFile {
     nonce: String,
     ciphertext: String,
 }
 
 fn get_api_keys_path() -> Result<PathBuf, String> {
+    get_encrypted_file_path("api_keys.enc")
+}
+
+/// Path of an encrypted file in the app config directory
+pub fn get_encrypted_file_path(file_name: &str) -> Result<PathBuf, String> {
     let config_dir = dirs::config_dir().ok_or("Could not find config directory")?;
 
     let app_config_dir = config_dir.join("scorm-builder");
@@ -30,7 +40,7 @@ fn get_api_keys_path() -> Result<PathBuf, String> {
     fs::create_dir_all(&app_config_dir)
         .map_err(|e| format!("Failed to create config directory: {e}"))?;
 
-    Ok(app_config_dir.join("api_keys.enc"))
+    Ok(app_config_dir.join(file_name))
 }
 
 fn get_or_create_key() -> Result<Vec<u8>, String> {
@@ -63,9 +73,14 @@ fn get_or_create_key() -> Result<Vec<u8>, String> {
 // Commented out - using secure version from commands_secure.rs
 // #[tauri::command]
 pub fn save_api_keys(api_keys: ApiKeys) -> Result<(), String> {
-    // Serialize API keys to JSON
-    let json = serde_json::to_string(&api_keys)
-        .map_err(|e| format!("Failed to serialize API keys: {e}"))?;
+    save_encrypted(&get_api_keys_path()?, &api_keys)
+}
+
+/// Encrypt `value` as JSON with the app's key and write it to `path`
+pub fn save_encrypted<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    // Serialize the value to JSON
+    let json = serde_json::to_string(value)
+        .map_err(|e| format!("Failed to serialize {}: {e}", path.display()))?;
 
     // Get or create encryption key
     let key_bytes = get_or_create_key()?;
@@ -82,21 +97,20 @@ pub fn save_api_keys(api_keys: ApiKeys) -> Result<(), String> {
     // Encrypt
     let ciphertext = cipher
         .encrypt(nonce, json.as_bytes())
-        .map_err(|e| format!("Failed to encrypt API keys: {e}"))?;
+        .map_err(|e| format!("Failed to encrypt {}: {e}", path.display()))?;
 
     // Create encrypted structure
-    let encrypted = EncryptedApiKeys {
+    let encrypted = EncryptedFile {
         nonce: general_purpose::STANDARD.encode(nonce_bytes),
         ciphertext: general_purpose::STANDARD.encode(&ciphertext),
     };
 
     // Save to file
-    let path = get_api_keys_path()?;
     let encrypted_json = serde_json::to_string_pretty(&encrypted)
         .map_err(|e| format!("Failed to serialize encrypted data: {e}"))?;
 
-    fs::write(&path, encrypted_json)
-        .map_err(|e| format!("Failed to save encrypted API keys: {e}"))?;
+    fs::write(path, encrypted_json)
+        .map_err(|e| format!("Failed to save {}: {e}", path.display()))?;
 
     Ok(())
 }
@@ -110,12 +124,17 @@ pub fn load_api_keys() -> Result<ApiKeys, String> {
         return Err("API keys file not found".to_string());
     }
 
+    load_encrypted(&path)
+}
+
+/// Read and decrypt a file written by [`save_encrypted`]
+pub fn load_encrypted<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
     // Read encrypted file
     let encrypted_json =
-        fs::read_to_string(&path).map_err(|e| format!("Failed to read API keys file: {e}"))?;
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
 
-    let encrypted: EncryptedApiKeys = serde_json::from_str(&encrypted_json)
-        .map_err(|e| format!("Failed to parse encrypted data: {e}"))?;
+    let encrypted: EncryptedFile = serde_json::from_str(&encrypted_json)
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
 
     // Get encryption key
     let key_bytes = get_or_create_key()?;
@@ -135,16 +154,13 @@ pub fn load_api_keys() -> Result<ApiKeys, String> {
     let cipher = Aes256Gcm::new(key);
     let plaintext = cipher
         .decrypt(nonce, ciphertext.as_ref())
-        .map_err(|e| format!("Failed to decrypt API keys: {e}"))?;
+        .map_err(|e| format!("Failed to decrypt {}: {e}", path.display()))?;
 
     // Parse JSON
     let json = String::from_utf8(plaintext)
         .map_err(|e| format!("Failed to convert decrypted data to string: {e}"))?;
 
-    let api_keys: ApiKeys =
-        serde_json::from_str(&json).map_err(|e| format!("Failed to parse API keys: {e}"))?;
-
-    Ok(api_keys)
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
 }
 
 // Commented out - using secure version from commands_secure.rs
@@ -169,6 +185,8 @@ mod tests {
             google_image_api_key: "test_google_key".to_string(),
             google_cse_id: "test_cse_id".to_string(),
             youtube_api_key: "test_youtube_key".to_string(),
+            unsplash_access_key: String::new(),
+            pexels_api_key: String::new(),
         };
 
         // Save
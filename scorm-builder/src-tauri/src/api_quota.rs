@@ -0,0 +1,277 @@
+//! Daily usage and limits of external APIs.
+//!
+//! Teams often share one provider key, and a runaway batch can use up a
+//! month's budget in an afternoon. Every AI request and media download is
+//! counted per provider and day in `api_usage.json` next to the settings;
+//! limits on requests and tokens per day live in the app settings and are
+//! checked before a request is made. Days are local days read from
+//! [`AppServices`].
+
+use crate::app_services::AppServices;
+use chrono::{Duration, Local, NaiveDate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Provider name media downloads are counted under
+pub const MEDIA_DOWNLOAD_PROVIDER: &str = "media_download";
+/// Days of usage kept in the log
+const HISTORY_DAYS: i64 = 90;
+
+/// Serializes read-modify-write of the usage log between concurrent requests
+static USAGE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Most a provider may be used in a day; unset limits don't apply
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiLimit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_requests_per_day: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_day: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub requests: u64,
+    pub tokens: u64,
+}
+
+/// Usage by day (`YYYY-MM-DD`) and provider
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiUsageLog {
+    pub days: BTreeMap<String, BTreeMap<String, DailyUsage>>,
+}
+
+/// One provider's usage on one day, with its limit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProviderUsage {
+    pub date: String,
+    pub provider: String,
+    pub requests: u64,
+    pub tokens: u64,
+    pub limit: ApiLimit,
+}
+
+impl ApiUsageLog {
+    pub fn usage(&self, day: NaiveDate, provider: &str) -> DailyUsage {
+        self.days
+            .get(&day.to_string())
+            .and_then(|providers| providers.get(provider))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Err when `provider` has reached `limit` on `day`
+    pub fn check(&self, day: NaiveDate, provider: &str, limit: &ApiLimit) -> Result<(), String> {
+        let used = self.usage(day, provider);
+        if let Some(max) = limit
+            .max_requests_per_day
+            .filter(|max| used.requests >= *max)
+        {
+            return Err(format!(
+                "Daily limit of {max} requests for {provider} reached; it resets tomorrow"
+            ));
+        }
+        if let Some(max) = limit.max_tokens_per_day.filter(|max| used.tokens >= *max) {
+            return Err(format!(
+                "Daily limit of {max} tokens for {provider} reached; it resets tomorrow"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Add usage on `day` and forget days older than the history kept
+    pub fn record(&mut self, day: NaiveDate, provider: &str, requests: u64, tokens: u64) {
+        let usage = self
+            .days
+            .entry(day.to_string())
+            .or_default()
+            .entry(provider.to_string())
+            .or_default();
+        usage.requests += requests;
+        usage.tokens += tokens;
+        let oldest = (day - Duration::days(HISTORY_DAYS)).to_string();
+        self.days.retain(|date, _| *date >= oldest);
+    }
+
+    /// Usage of the `days` days up to `today`, newest first
+    pub fn report(
+        &self,
+        today: NaiveDate,
+        days: i64,
+        limits: &HashMap<String, ApiLimit>,
+    ) -> Vec<ProviderUsage> {
+        let oldest = (today - Duration::days(days - 1)).to_string();
+        self.days
+            .iter()
+            .rev()
+            .filter(|(date, _)| **date >= oldest && **date <= today.to_string())
+            .flat_map(|(date, providers)| {
+                providers
+                    .iter()
+                    .map(move |(provider, usage)| ProviderUsage {
+                        date: date.clone(),
+                        provider: provider.clone(),
+                        requests: usage.requests,
+                        tokens: usage.tokens,
+                        limit: limits.get(provider).copied().unwrap_or_default(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// The local day it is now, which usage is counted under
+pub fn today(services: &AppServices) -> NaiveDate {
+    services.now().with_timezone(&Local).date_naive()
+}
+
+fn usage_path() -> Result<PathBuf, String> {
+    Ok(crate::settings::get_app_config_dir()?.join("api_usage.json"))
+}
+
+fn load_usage() -> Result<ApiUsageLog, String> {
+    let path = usage_path()?;
+    if !path.exists() {
+        return Ok(ApiUsageLog::default());
+    }
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read API usage: {e}"))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse API usage: {e}"))
+}
+
+fn current_limits() -> HashMap<String, ApiLimit> {
+    crate::settings::load_settings()
+        .ok()
+        .and_then(|settings| settings.api_limits)
+        .unwrap_or_default()
+}
+
+/// Err when `provider` has used up its daily limit on `day`
+pub fn check_quota(provider: &str, day: NaiveDate) -> Result<(), String> {
+    let Some(limit) = current_limits().remove(provider) else {
+        return Ok(());
+    };
+    let _guard = USAGE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load_usage()?.check(day, provider, &limit)
+}
+
+/// Count requests and tokens used on `day`. The log is replaced through a
+/// temporary file so a crash can't leave it half written.
+pub fn record_usage(
+    provider: &str,
+    day: NaiveDate,
+    requests: u64,
+    tokens: u64,
+) -> Result<(), String> {
+    let _guard = USAGE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut usage = load_usage()?;
+    usage.record(day, provider, requests, tokens);
+    let json = serde_json::to_string_pretty(&usage)
+        .map_err(|e| format!("Failed to serialize API usage: {e}"))?;
+    let path = usage_path()?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| format!("Failed to write API usage: {e}"))?;
+    fs::rename(&temp_path, &path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to write API usage: {e}")
+    })
+}
+
+/// Usage per provider for the last `days` days (7 by default), newest first
+#[tauri::command]
+pub fn get_api_usage(
+    services: tauri::State<'_, AppServices>,
+    days: Option<u32>,
+) -> Result<Vec<ProviderUsage>, String> {
+    let _guard = USAGE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load_usage()?.report(
+        today(&services),
+        i64::from(days.unwrap_or(7).max(1)),
+        &current_limits(),
+    ))
+}
+
+#[tauri::command]
+pub fn get_api_limits() -> Result<HashMap<String, ApiLimit>, String> {
+    Ok(current_limits())
+}
+
+#[tauri::command]
+pub fn set_api_limits(limits: HashMap<String, ApiLimit>) -> Result<(), String> {
+    let mut settings = crate::settings::load_settings()?;
+    settings.api_limits = Some(limits);
+    crate::settings::save_settings(&settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn test_limits_apply_per_provider_and_day() {
+        let mut log = ApiUsageLog::default();
+        let limit = ApiLimit {
+            max_requests_per_day: Some(2),
+            max_tokens_per_day: Some(1000),
+        };
+        log.record(day("2026-03-02"), "openai", 1, 400);
+        assert!(log.check(day("2026-03-02"), "openai", &limit).is_ok());
+
+        log.record(day("2026-03-02"), "openai", 1, 700);
+        let error = log.check(day("2026-03-02"), "openai", &limit).unwrap_err();
+        assert!(error.contains("2 requests for openai"));
+        assert!(log.check(day("2026-03-02"), "anthropic", &limit).is_ok());
+        assert!(log.check(day("2026-03-03"), "openai", &limit).is_ok());
+
+        let tokens_only = ApiLimit {
+            max_tokens_per_day: Some(1000),
+            ..Default::default()
+        };
+        assert!(log
+            .check(day("2026-03-02"), "openai", &tokens_only)
+            .unwrap_err()
+            .contains("1000 tokens"));
+    }
+
+    #[test]
+    fn test_report_lists_recent_days_and_forgets_old_ones() {
+        let mut log = ApiUsageLog::default();
+        log.record(day("2025-10-01"), "openai", 5, 5000);
+        log.record(day("2026-02-27"), MEDIA_DOWNLOAD_PROVIDER, 3, 0);
+        log.record(day("2026-03-02"), "anthropic", 1, 250);
+        assert!(!log.days.contains_key("2025-10-01"));
+
+        let limits = HashMap::from([(
+            "anthropic".to_string(),
+            ApiLimit {
+                max_tokens_per_day: Some(10_000),
+                ..Default::default()
+            },
+        )]);
+        let report = log.report(day("2026-03-02"), 7, &limits);
+
+        let rows: Vec<_> = report
+            .iter()
+            .map(|usage| (usage.date.as_str(), usage.provider.as_str(), usage.requests))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("2026-03-02", "anthropic", 1),
+                ("2026-02-27", MEDIA_DOWNLOAD_PROVIDER, 3)
+            ]
+        );
+        assert_eq!(report[0].limit.max_tokens_per_day, Some(10_000));
+        assert_eq!(log.report(day("2026-03-02"), 1, &limits).len(), 1);
+    }
+}
@@ -0,0 +1,455 @@
+use crate::api_keys::{load_api_keys, save_api_keys, ApiKeys};
+use crate::app_services::AppServices;
+use crate::project_encryption::derive_passphrase_key;
+use crate::settings::{get_app_config_dir, load_settings, save_settings, AppSettings};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::State;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bumped whenever the layout of profile.json changes incompatibly
+pub const PROFILE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "profile.json";
+
+/// Folders under the app config directory that travel with a profile
+const PROFILE_FOLDERS: [&str; 2] = ["themes", "templates"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    pub settings: AppSettings,
+    pub api_keys: Option<ProfileApiKeys>,
+}
+
+/// API keys as stored in a profile. Encrypted keys use a key derived from the
+/// export passphrase, since the per-machine key in api_keys.rs doesn't travel.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ProfileApiKeys {
+    Plain {
+        keys: ApiKeys,
+    },
+    Encrypted {
+        salt: String,
+        nonce: String,
+        ciphertext: String,
+    },
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ExportProfileOptions {
+    #[serde(default)]
+    pub include_api_keys: bool,
+    /// Encrypts the API keys when set; ignored if keys aren't included
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileExportResult {
+    pub file_path: String,
+    pub files_included: usize,
+    pub api_keys_included: bool,
+    pub api_keys_encrypted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileImportResult {
+    pub files_restored: usize,
+    pub api_keys_imported: bool,
+}
+
+/// Bundle settings, themes, template overrides and (optionally) API keys into one file
+#[tauri::command]
+pub fn export_app_profile(
+    file_path: String,
+    options: ExportProfileOptions,
+    services: State<'_, AppServices>,
+) -> Result<ProfileExportResult, String> {
+    let api_keys = if options.include_api_keys {
+        let keys = load_api_keys()?;
+        Some(match options.passphrase.as_deref() {
+            Some(passphrase) if !passphrase.is_empty() => encrypt_api_keys(&keys, passphrase)?,
+            _ => ProfileApiKeys::Plain { keys },
+        })
+    } else {
+        None
+    };
+
+    let manifest = ProfileManifest {
+        format_version: PROFILE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: services.now(),
+        settings: load_settings()?,
+        api_keys,
+    };
+
+    write_profile(Path::new(&file_path), &manifest, &get_app_config_dir()?)
+}
+
+/// Restore a profile written by `export_app_profile` onto this workstation
+#[tauri::command]
+pub fn import_app_profile(
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<ProfileImportResult, String> {
+    let (manifest, files) = read_profile(Path::new(&file_path))?;
+
+    // Decrypt before touching anything so a wrong passphrase leaves the machine as it was
+    let api_keys = match &manifest.api_keys {
+        Some(stored) => Some(decrypt_api_keys(stored, passphrase.as_deref())?),
+        None => None,
+    };
+
+    let files_restored = restore_profile_files(&files, &get_app_config_dir()?)?;
+    save_settings(&manifest.settings)?;
+    if let Some(keys) = &api_keys {
+        save_api_keys(keys.clone())?;
+    }
+
+    Ok(ProfileImportResult {
+        files_restored,
+        api_keys_imported: api_keys.is_some(),
+    })
+}
+
+/// Write the profile ZIP: profile.json plus the profile folders from `config_dir`
+pub fn write_profile(
+    out_path: &Path,
+    manifest: &ProfileManifest,
+    config_dir: &Path,
+) -> Result<ProfileExportResult, String> {
+    let file =
+        fs::File::create(out_path).map_err(|e| format!("Failed to create profile file: {e}"))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let manifest_json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize profile: {e}"))?;
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| format!("Failed to write profile: {e}"))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| format!("Failed to write profile: {e}"))?;
+
+    let mut files_included = 0;
+    for folder in PROFILE_FOLDERS {
+        let root = config_dir.join(folder);
+        if !root.is_dir() {
+            continue;
+        }
+        for path in collect_files(&root)? {
+            let relative = path
+                .strip_prefix(config_dir)
+                .map_err(|e| format!("Failed to resolve profile path: {e}"))?;
+            let entry_name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let content =
+                fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            zip.start_file(entry_name, options)
+                .map_err(|e| format!("Failed to write profile: {e}"))?;
+            zip.write_all(&content)
+                .map_err(|e| format!("Failed to write profile: {e}"))?;
+            files_included += 1;
+        }
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize profile: {e}"))?;
+
+    let (api_keys_included, api_keys_encrypted) = match &manifest.api_keys {
+        Some(ProfileApiKeys::Encrypted { .. }) => (true, true),
+        Some(ProfileApiKeys::Plain { .. }) => (true, false),
+        None => (false, false),
+    };
+
+    Ok(ProfileExportResult {
+        file_path: out_path.to_string_lossy().to_string(),
+        files_included,
+        api_keys_included,
+        api_keys_encrypted,
+    })
+}
+
+/// Read a profile ZIP, returning the manifest and the folder files it carries
+pub fn read_profile(path: &Path) -> Result<(ProfileManifest, Vec<(PathBuf, Vec<u8>)>), String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open profile: {e}"))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Invalid profile file: {e}"))?;
+
+    let mut manifest = None;
+    let mut files = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read profile entry: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+
+        let name = entry.name().to_string();
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read profile entry {name}: {e}"))?;
+
+        if name == MANIFEST_NAME {
+            manifest = Some(
+                serde_json::from_slice::<ProfileManifest>(&content)
+                    .map_err(|e| format!("Failed to parse profile: {e}"))?,
+            );
+            continue;
+        }
+
+        let relative = safe_profile_path(&name)
+            .ok_or_else(|| format!("Profile contains an invalid path: {name}"))?;
+        files.push((relative, content));
+    }
+
+    let manifest = manifest.ok_or_else(|| format!("Profile is missing {MANIFEST_NAME}"))?;
+    if manifest.format_version > PROFILE_FORMAT_VERSION {
+        return Err(format!(
+            "Profile format version {} is newer than this app supports ({PROFILE_FORMAT_VERSION})",
+            manifest.format_version
+        ));
+    }
+
+    Ok((manifest, files))
+}
+
+/// Replace the profile folders under `config_dir` with the given files
+pub fn restore_profile_files(
+    files: &[(PathBuf, Vec<u8>)],
+    config_dir: &Path,
+) -> Result<usize, String> {
+    for folder in PROFILE_FOLDERS {
+        let dir = config_dir.join(folder);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to clear {}: {e}", dir.display()))?;
+        }
+    }
+
+    for (relative, content) in files {
+        let target = config_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
+        }
+        fs::write(&target, content)
+            .map_err(|e| format!("Failed to write {}: {e}", target.display()))?;
+    }
+
+    Ok(files.len())
+}
+
+/// Only relative paths inside one of the profile folders are accepted
+fn safe_profile_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    let mut components = path.components();
+    match components.next() {
+        Some(Component::Normal(first))
+            if first.to_str().is_some_and(|f| PROFILE_FOLDERS.contains(&f)) => {}
+        _ => return None,
+    }
+    if components.all(|c| matches!(c, Component::Normal(_))) {
+        Some(path.to_path_buf())
+    } else {
+        None
+    }
+}
+
+fn collect_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(collect_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+pub fn encrypt_api_keys(keys: &ApiKeys, passphrase: &str) -> Result<ProfileApiKeys, String> {
+    let json =
+        serde_json::to_vec(keys).map_err(|e| format!("Failed to serialize API keys: {e}"))?;
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+
+    let key_bytes = derive_passphrase_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), json.as_ref())
+        .map_err(|e| format!("Failed to encrypt API keys: {e}"))?;
+
+    Ok(ProfileApiKeys::Encrypted {
+        salt: general_purpose::STANDARD.encode(salt),
+        nonce: general_purpose::STANDARD.encode(nonce_bytes),
+        ciphertext: general_purpose::STANDARD.encode(ciphertext),
+    })
+}
+
+pub fn decrypt_api_keys(
+    stored: &ProfileApiKeys,
+    passphrase: Option<&str>,
+) -> Result<ApiKeys, String> {
+    let (salt, nonce, ciphertext) = match stored {
+        ProfileApiKeys::Plain { keys } => return Ok(keys.clone()),
+        ProfileApiKeys::Encrypted {
+            salt,
+            nonce,
+            ciphertext,
+        } => (salt, nonce, ciphertext),
+    };
+
+    let passphrase = passphrase.filter(|p| !p.is_empty()).ok_or_else(|| {
+        "This profile's API keys are encrypted; a passphrase is required".to_string()
+    })?;
+
+    let decode = |value: &str, what: &str| {
+        general_purpose::STANDARD
+            .decode(value)
+            .map_err(|e| format!("Failed to decode {what}: {e}"))
+    };
+    let salt = decode(salt, "salt")?;
+    let nonce_bytes = decode(nonce, "nonce")?;
+    let ciphertext = decode(ciphertext, "ciphertext")?;
+
+    let key_bytes = derive_passphrase_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce_bytes), ciphertext.as_ref())
+        .map_err(|_| {
+            "Failed to decrypt API keys: wrong passphrase or corrupted profile".to_string()
+        })?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse API keys: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_keys() -> ApiKeys {
+        ApiKeys {
+            google_image_api_key: "google".to_string(),
+            google_cse_id: "cse".to_string(),
+            youtube_api_key: "youtube".to_string(),
+            unsplash_access_key: String::new(),
+            pexels_api_key: String::new(),
+        }
+    }
+
+    fn sample_manifest(api_keys: Option<ProfileApiKeys>) -> ProfileManifest {
+        ProfileManifest {
+            format_version: PROFILE_FORMAT_VERSION,
+            app_version: "test".to_string(),
+            exported_at: Utc::now(),
+            settings: AppSettings {
+                projects_directory: Some("/work/SCORM Projects".to_string()),
+                recent_projects_count: Some(25),
+                locale: None,
+                media_policy: None,
+                metadata_policy: None,
+                archive_schedule: None,
+                api_limits: None,
+                time_display: None,
+                storage_budget: None,
+            },
+            api_keys,
+        }
+    }
+
+    #[test]
+    fn test_profile_round_trip() {
+        let source = TempDir::new().unwrap();
+        fs::create_dir_all(source.path().join("themes")).unwrap();
+        fs::create_dir_all(source.path().join("templates/partials")).unwrap();
+        fs::write(
+            source.path().join("themes/corporate.json"),
+            r#"{"primary":"navy"}"#,
+        )
+        .unwrap();
+        fs::write(
+            source.path().join("templates/partials/nav_bar.hbs"),
+            "<nav></nav>",
+        )
+        .unwrap();
+        // Files outside the profile folders stay behind
+        fs::write(source.path().join("settings.json"), "{}").unwrap();
+
+        let out = source.path().join("workstation.scormprofile");
+        let manifest = sample_manifest(Some(encrypt_api_keys(&sample_keys(), "s3cret").unwrap()));
+        let exported = write_profile(&out, &manifest, source.path()).unwrap();
+        assert_eq!(exported.files_included, 2);
+        assert!(exported.api_keys_encrypted);
+
+        let target = TempDir::new().unwrap();
+        fs::create_dir_all(target.path().join("themes")).unwrap();
+        fs::write(target.path().join("themes/stale.json"), "{}").unwrap();
+
+        let (read_back, files) = read_profile(&out).unwrap();
+        assert_eq!(read_back.settings.recent_projects_count, Some(25));
+        assert_eq!(restore_profile_files(&files, target.path()).unwrap(), 2);
+
+        assert!(target.path().join("themes/corporate.json").exists());
+        assert!(!target.path().join("themes/stale.json").exists());
+        assert_eq!(
+            fs::read_to_string(target.path().join("templates/partials/nav_bar.hbs")).unwrap(),
+            "<nav></nav>"
+        );
+
+        let keys = decrypt_api_keys(read_back.api_keys.as_ref().unwrap(), Some("s3cret")).unwrap();
+        assert_eq!(keys.youtube_api_key, "youtube");
+    }
+
+    #[test]
+    fn test_encrypted_keys_need_correct_passphrase() {
+        let stored = encrypt_api_keys(&sample_keys(), "right").unwrap();
+
+        assert!(decrypt_api_keys(&stored, None).is_err());
+        assert!(decrypt_api_keys(&stored, Some("wrong")).is_err());
+        assert_eq!(
+            decrypt_api_keys(&stored, Some("right"))
+                .unwrap()
+                .google_cse_id,
+            "cse"
+        );
+
+        let plain = ProfileApiKeys::Plain {
+            keys: sample_keys(),
+        };
+        assert!(decrypt_api_keys(&plain, None).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_paths_outside_profile_folders() {
+        assert!(safe_profile_path("themes/a.json").is_some());
+        assert!(safe_profile_path("templates/partials/x.hbs").is_some());
+        assert!(safe_profile_path("settings.json").is_none());
+        assert!(safe_profile_path("themes/../../evil").is_none());
+        assert!(safe_profile_path("/etc/passwd").is_none());
+    }
+}
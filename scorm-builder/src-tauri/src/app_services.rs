@@ -0,0 +1,188 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Source of the current time. Commands read time through this instead of
+/// calling `Utc::now()` directly so tests can pin timestamps.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Source of new project, page and media IDs.
+pub trait IdGenerator: Send + Sync {
+    fn new_project_id(&self) -> String;
+    /// `<kind>-<uuid>`, e.g. a stable topic ID
+    fn new_stable_id(&self, kind: &str) -> String;
+}
+
+/// Wall-clock time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Millisecond timestamp IDs (the historical 13-digit project ID format that
+/// file names like `Name_1234567890123.scormproj` depend on)
+pub struct TimestampIdGenerator {
+    clock: Arc<dyn Clock>,
+}
+
+impl TimestampIdGenerator {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+}
+
+impl IdGenerator for TimestampIdGenerator {
+    fn new_project_id(&self) -> String {
+        self.clock.now().timestamp_millis().to_string()
+    }
+
+    fn new_stable_id(&self, kind: &str) -> String {
+        format!("{}-{}", kind, Uuid::new_v4())
+    }
+}
+
+/// Clock and ID generator held in Tauri managed state
+#[derive(Clone)]
+pub struct AppServices {
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
+}
+
+impl AppServices {
+    pub fn new(clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Self {
+        Self { clock, ids }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub fn new_project_id(&self) -> String {
+        self.ids.new_project_id()
+    }
+
+    pub fn new_stable_id(&self, kind: &str) -> String {
+        self.ids.new_stable_id(kind)
+    }
+}
+
+impl Default for AppServices {
+    fn default() -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        Self {
+            ids: Arc::new(TimestampIdGenerator::new(clock.clone())),
+            clock,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+    use chrono::Duration;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+
+    /// Clock that only moves when told to
+    pub struct FixedClock {
+        now: Mutex<DateTime<Utc>>,
+    }
+
+    impl FixedClock {
+        pub fn new(now: DateTime<Utc>) -> Self {
+            Self {
+                now: Mutex::new(now),
+            }
+        }
+
+        pub fn advance(&self, duration: Duration) {
+            let mut now = self.now.lock().unwrap();
+            *now += duration;
+        }
+    }
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.now.lock().unwrap()
+        }
+    }
+
+    /// Hands out consecutive numeric IDs starting at `start`
+    pub struct SequentialIdGenerator {
+        next: AtomicU64,
+    }
+
+    impl SequentialIdGenerator {
+        pub fn new(start: u64) -> Self {
+            Self {
+                next: AtomicU64::new(start),
+            }
+        }
+    }
+
+    impl IdGenerator for SequentialIdGenerator {
+        fn new_project_id(&self) -> String {
+            self.next.fetch_add(1, Ordering::SeqCst).to_string()
+        }
+
+        fn new_stable_id(&self, kind: &str) -> String {
+            let n = self.next.fetch_add(1, Ordering::SeqCst);
+            format!("{}-{}", kind, Uuid::from_u128(u128::from(n)))
+        }
+    }
+
+    /// Deterministic services: 2024-01-01T00:00:00Z and IDs from 1700000000000
+    pub fn deterministic_services() -> (Arc<FixedClock>, AppServices) {
+        let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let clock = Arc::new(FixedClock::new(start));
+        let services = AppServices::new(
+            clock.clone(),
+            Arc::new(SequentialIdGenerator::new(1_700_000_000_000)),
+        );
+        (clock, services)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::*;
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_timestamp_ids_follow_clock() {
+        let clock = Arc::new(FixedClock::new(
+            DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        ));
+        let ids = TimestampIdGenerator::new(clock.clone());
+
+        assert_eq!(ids.new_project_id(), "1704067200000");
+        clock.advance(Duration::milliseconds(5));
+        assert_eq!(ids.new_project_id(), "1704067200005");
+    }
+
+    #[test]
+    fn test_deterministic_services() {
+        let (clock, services) = deterministic_services();
+
+        assert_eq!(services.new_project_id(), "1700000000000");
+        assert_eq!(services.new_project_id(), "1700000000001");
+        assert_eq!(
+            services.new_stable_id("topic"),
+            "topic-00000000-0000-0000-0000-018bcfe56802"
+        );
+
+        let before = services.now();
+        clock.advance(Duration::seconds(30));
+        assert_eq!(services.now() - before, Duration::seconds(30));
+    }
+}
@@ -0,0 +1,126 @@
+//! Author notes on pages and questions.
+//!
+//! Notes live in `authorNotes` next to the content they are about, so they are
+//! saved and exported with the project. The package generators only read the
+//! fields they render, so notes never reach a published course; this module
+//! gathers them into a Markdown document for reviews with the course team.
+
+use crate::course_content::{ContentPage, CourseContent, Question};
+use crate::media_usage::find_project_file;
+use crate::project_storage::load_project_file;
+
+/// Notes of one page or question
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorNote {
+    /// The page the notes are on, or the assessment
+    pub page: String,
+    /// Stem of the question the notes are on, if they're not on the page itself
+    pub question: Option<String>,
+    pub notes: String,
+}
+
+fn note(page: &str, question: Option<&Question>, notes: Option<&String>) -> Option<AuthorNote> {
+    let notes = notes.map(|notes| notes.trim()).filter(|notes| !notes.is_empty())?;
+    Some(AuthorNote {
+        page: page.to_string(),
+        question: question.map(|question| question.question.clone()),
+        notes: notes.to_string(),
+    })
+}
+
+fn page_notes(page: &ContentPage) -> impl Iterator<Item = AuthorNote> + '_ {
+    let questions = page
+        .knowledge_check
+        .as_ref()
+        .map(|check| check.all_questions())
+        .unwrap_or_default();
+    let page_note = note(page.name(), None, page.author_notes.as_ref());
+    let question_notes: Vec<_> = questions
+        .iter()
+        .filter_map(|question| note(page.name(), Some(question), question.author_notes.as_ref()))
+        .collect();
+    page_note.into_iter().chain(question_notes)
+}
+
+/// Every non-empty note, in course order
+pub fn collect_author_notes(content: &CourseContent) -> Vec<AuthorNote> {
+    let mut notes: Vec<_> = content.pages().flat_map(page_notes).collect();
+    for question in content.assessment.iter().flat_map(|assessment| &assessment.questions) {
+        notes.extend(note("Assessment", Some(question), question.author_notes.as_ref()));
+    }
+    notes
+}
+
+/// The notes as a Markdown document, one section per page
+pub fn author_notes_markdown(course_title: &str, notes: &[AuthorNote]) -> String {
+    let mut document = format!("# Author notes: {course_title}\n");
+    if notes.is_empty() {
+        document.push_str("\nThis course has no author notes.\n");
+        return document;
+    }
+
+    let mut current_page = None;
+    for note in notes {
+        if current_page != Some(&note.page) {
+            document.push_str(&format!("\n## {}\n", note.page));
+            current_page = Some(&note.page);
+        }
+        if let Some(question) = &note.question {
+            document.push_str(&format!("\n### Question: {question}\n"));
+        }
+        document.push_str(&format!("\n{}\n", note.notes));
+    }
+    document
+}
+
+/// Export the author notes of a project as a Markdown document
+#[tauri::command]
+pub fn export_author_notes(#[allow(non_snake_case)] projectId: String) -> Result<String, String> {
+    let project_path =
+        find_project_file(&projectId).ok_or_else(|| "Project file not found".to_string())?;
+    let project = load_project_file(&project_path)?;
+    let content = match &project.course_content {
+        Some(value) => CourseContent::from_value(value)?,
+        None => CourseContent::default(),
+    };
+    Ok(author_notes_markdown(&project.project.name, &collect_author_notes(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_notes_are_gathered_in_course_order() {
+        let content = CourseContent::from_value(&json!({
+            "welcomePage": { "id": "welcome", "title": "Welcome", "authorNotes": "Check tone with legal" },
+            "topics": [
+                {
+                    "id": "topic-0",
+                    "title": "Ladders",
+                    "authorNotes": "  ",
+                    "knowledgeCheck": {
+                        "questions": [{ "question": "Three points of contact?", "authorNotes": "Source: HSE INDG455" }]
+                    }
+                },
+                { "id": "topic-1", "title": "Scaffolds" }
+            ],
+            "assessment": {
+                "questions": [{ "question": "Max ladder angle?", "authorNotes": "Confirm 75 degrees" }]
+            }
+        }))
+        .unwrap();
+
+        let notes = collect_author_notes(&content);
+        assert_eq!(notes.len(), 3);
+        assert_eq!(notes[0].page, "Welcome");
+        assert_eq!(notes[1].question.as_deref(), Some("Three points of contact?"));
+        assert_eq!(notes[2].page, "Assessment");
+
+        let markdown = author_notes_markdown("Working at height", &notes);
+        assert!(markdown.starts_with("# Author notes: Working at height\n"));
+        assert!(markdown.contains("\n## Ladders\n\n### Question: Three points of contact?\n\nSource: HSE INDG455\n"));
+        assert!(!markdown.contains("Scaffolds"));
+    }
+}
@@ -2,6 +2,13 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tauri::{Emitter, State};
+use crate::app_services::AppServices;
+use crate::content_hash::{self, ArtifactStatus};
+use crate::content_pages;
+use crate::display_time::{LocalTimes, TimeFormatter};
+use crate::project_access;
+use ts_rs::TS;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RecoveryInfo {
@@ -11,18 +18,12 @@ pub struct RecoveryInfo {
     pub backup_timestamp: Option<String>,
     #[serde(rename = "backupPath")]
     pub backup_path: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CleanupResult {
-    #[serde(rename = "deletedCount")]
-    pub deleted_count: usize,
-    #[serde(rename = "keptCount")]
-    pub kept_count: usize,
+    /// `backupTimestamp` in local time
+    #[serde(rename = "localTimes", skip_serializing_if = "Option::is_none")]
+    pub local_times: Option<LocalTimes>,
 }
 
 /// Extract project ID from a path or return the ID if it's already just an ID
-#[allow(dead_code)]
 fn extract_project_id(project_id_or_path: &str) -> String {
     // If it contains .scormproj, extract the ID from the filename
     if project_id_or_path.contains(".scormproj") {
@@ -91,11 +92,11 @@ fn get_project_path(project_id_or_path: &str) -> PathBuf {
     }
 }
 
-/// Create a backup of the project file
+/// Create a backup of the project file, unless nothing changed since the last one
 #[tauri::command]
 pub fn create_backup(
     #[allow(non_snake_case)] projectId: String
-) -> Result<(), String> {
+) -> Result<ArtifactStatus, String> {
     let project_path = get_project_path(&projectId);
     
     // If the project file doesn't exist, nothing to backup
@@ -104,24 +105,92 @@ pub fn create_backup(
                  project_path.file_name()
                      .and_then(|n| n.to_str())
                      .unwrap_or(&project_path.to_string_lossy()));
-        return Ok(());
+        return Ok(ArtifactStatus::Skipped);
     }
     
+    project_access::ensure_writable(&project_path)?;
+
     // Create backup path
     let backup_path = project_path.with_extension("scormproj.backup");
+
+    // Split-layout projects are backed up with their pages inlined so the backup stands alone
+    let project_content = content_pages::read_project_for_export(&project_path);
+
+    // Skip the copy when the project and its media are unchanged since the last backup
+    let project_data = project_content
+        .as_ref()
+        .ok()
+        .and_then(|content| serde_json::from_slice(content).ok());
+    let hash = project_data.and_then(|data| project_hash(&project_path, &data));
+    if let Some((projects_dir, id, hash)) = &hash {
+        if backup_path.exists()
+            && content_hash::stored_hash(projects_dir, id, "backup").as_deref() == Some(hash)
+        {
+            println!("[backup] Backup is up to date: {:?}", backup_path);
+            return Ok(ArtifactStatus::UpToDate);
+        }
+    }
     
+    // Room for the backup, or the last good backup is kept as it is
+    if let Ok(content) = &project_content {
+        let dir = project_path.parent().unwrap_or(&project_path);
+        crate::disk_space::ensure_space(dir, content.len() as u64)?;
+    }
+
     // Copy the project file to backup
-    match fs::copy(&project_path, &backup_path) {
+    let copied = project_content
+        .and_then(|content| fs::write(&backup_path, content).map_err(|e| e.to_string()));
+    match copied {
         Ok(_) => {
             println!("[backup] Created backup: {:?}", backup_path);
-            Ok(())
         }
         Err(e) => {
             println!("[backup] Warning: Failed to create backup: {}", e);
             // Don't fail the operation, just log the warning
-            Ok(())
+            return Ok(ArtifactStatus::Skipped);
+        }
+    }
+
+    if let Some((projects_dir, id, hash)) = hash {
+        if let Err(e) = content_hash::store_hash(&projects_dir, &id, "backup", &hash) {
+            println!("[backup] Warning: Failed to record backup hash: {}", e);
         }
     }
+
+    Ok(ArtifactStatus::Written)
+}
+
+/// Timestamped history copy name, e.g. `Name_123.scormproj.backup.20240101-120000000`,
+/// kept when a recovery item replaces the project file or a newer backup is
+/// discarded. `cleanup_old_backups` prunes these along with the main `.backup` file.
+fn history_backup_path(project_path: &Path, now: DateTime<Utc>) -> PathBuf {
+    let file_name = project_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("project.scormproj");
+    project_path.with_file_name(format!(
+        "{}.backup.{}",
+        file_name,
+        now.format("%Y%m%d-%H%M%S%3f")
+    ))
+}
+
+/// Projects folder and project ID that content hashes for `project_path` are kept under
+fn hash_location(project_path: &Path) -> Option<(PathBuf, String)> {
+    let project_id = extract_project_id(&project_path.to_string_lossy());
+    let projects_dir = project_path.parent()?;
+    is_project_id(&project_id).then(|| (projects_dir.to_path_buf(), project_id))
+}
+
+/// Content hash of project data plus the project's media, with where to store it
+fn project_hash(
+    project_path: &Path,
+    project_data: &serde_json::Value,
+) -> Option<(PathBuf, String, String)> {
+    let (projects_dir, project_id) = hash_location(project_path)?;
+    let manifest = content_hash::media_manifest(&projects_dir, &project_id);
+    let hash = content_hash::project_content_hash(project_data, &manifest);
+    Some((projects_dir, project_id, hash))
 }
 
 /// Check if a recovery backup exists for the project
@@ -143,16 +212,20 @@ pub fn check_recovery(
             })
             .unwrap_or_else(|_| "Unknown".to_string());
         
+        let local_times = TimeFormatter::current()
+            .local_times_rfc3339([("backupTimestamp", Some(timestamp.as_str()))]);
         Ok(RecoveryInfo {
             has_recovery: true,
             backup_timestamp: Some(timestamp),
             backup_path: Some(backup_path.to_string_lossy().to_string()),
+            local_times: Some(local_times),
         })
     } else {
         Ok(RecoveryInfo {
             has_recovery: false,
             backup_timestamp: None,
             backup_path: None,
+            local_times: None,
         })
     }
 }
@@ -160,9 +233,18 @@ pub fn check_recovery(
 /// Recover project data from backup
 #[tauri::command]
 pub fn recover_from_backup(
-    #[allow(non_snake_case)] projectId: String
+    #[allow(non_snake_case)] projectId: String,
+    services: State<'_, AppServices>,
 ) -> Result<serde_json::Value, String> {
-    let project_path = get_project_path(&projectId);
+    recover_from_backup_with_services(projectId, &services)
+}
+
+/// Same as `recover_from_backup`, with the clock passed in explicitly
+pub fn recover_from_backup_with_services(
+    project_id: String,
+    services: &AppServices,
+) -> Result<serde_json::Value, String> {
+    let project_path = get_project_path(&project_id);
     let backup_path = project_path.with_extension("scormproj.backup");
     
     if !backup_path.exists() {
@@ -181,7 +263,7 @@ pub fn recover_from_backup(
     if let Some(obj) = project_data.as_object_mut() {
         let recovery_metadata = serde_json::json!({
             "recovered": true,
-            "timestamp": Utc::now().to_rfc3339()
+            "timestamp": services.now().to_rfc3339()
         });
         obj.insert("metadata".to_string(), recovery_metadata);
     }
@@ -189,63 +271,534 @@ pub fn recover_from_backup(
     Ok(project_data)
 }
 
-/// Cleanup old backup files, keeping only the specified number
+/// Backups kept when the caller doesn't say
+const DEFAULT_KEEP_BACKUPS: usize = 5;
+
+/// Why a backup is (or would be) deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum BackupDeletionReason {
+    /// Older than the newest `keepCount` backups
+    BeyondKeepCount,
+    /// Modified more than `maxAgeDays` ago
+    OlderThanMaxAge,
+}
+
+/// One backup file looked at by `cleanup_old_backups`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BackupFileReport {
+    pub path: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    pub modified: String,
+    #[ts(type = "number")]
+    pub age_days: u64,
+    /// Set for backups that were, or in a dry run would be, deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reason: Option<BackupDeletionReason>,
+    /// Why a backup chosen for deletion couldn't be removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<String>,
+    /// `modified` in local time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "{ timezone: string } & Record<string, string>")]
+    pub local_times: Option<LocalTimes>,
+}
+
+/// What `cleanup_old_backups` deleted and kept, also sent in the `backup-cleanup` event
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CleanupResult {
+    pub deleted_count: usize,
+    pub kept_count: usize,
+    /// Nothing was deleted; `deleted` lists what would have been
+    pub dry_run: bool,
+    /// Bytes deleted, or that a dry run would delete
+    #[ts(type = "number")]
+    pub freed_bytes: u64,
+    pub deleted: Vec<BackupFileReport>,
+    pub kept: Vec<BackupFileReport>,
+}
+
+/// `{file}.backup` and its timestamped history copies, newest first.
+/// Backups of other projects whose file name merely starts the same are left out.
+pub(crate) fn list_backups(project_path: &Path) -> Vec<(PathBuf, u64, std::time::SystemTime)> {
+    let Some(file_name) = project_path.file_name().and_then(|s| s.to_str()) else {
+        return Vec::new();
+    };
+    let main_backup = format!("{file_name}.backup");
+    let history_prefix = format!("{main_backup}.");
+    let dir = project_path.parent().unwrap_or(Path::new(""));
+
+    let mut backups: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name == main_backup || name.starts_with(&history_prefix)
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+        })
+        .collect();
+    backups.sort_by(|a, b| b.2.cmp(&a.2));
+    backups
+}
+
+/// Delete the backups of `project_path` beyond the newest `keep_count` or
+/// older than `max_age_days`, or with `dry_run` only report them. The main
+/// `.backup` file is what recovery restores from, so it is always kept.
+pub fn cleanup_backups_at(
+    project_path: &Path,
+    keep_count: usize,
+    max_age_days: Option<u64>,
+    dry_run: bool,
+    now: DateTime<Utc>,
+) -> CleanupResult {
+    let main_backup = project_path.with_extension("scormproj.backup");
+    let mut result = CleanupResult {
+        deleted_count: 0,
+        kept_count: 0,
+        dry_run,
+        freed_bytes: 0,
+        deleted: Vec::new(),
+        kept: Vec::new(),
+    };
+
+    for (index, (path, size, modified)) in list_backups(project_path).into_iter().enumerate() {
+        let modified: DateTime<Utc> = modified.into();
+        let age_days = (now - modified).num_days().max(0) as u64;
+        let reason = if path == main_backup {
+            None
+        } else if index >= keep_count {
+            Some(BackupDeletionReason::BeyondKeepCount)
+        } else if max_age_days.is_some_and(|max_age| age_days > max_age) {
+            Some(BackupDeletionReason::OlderThanMaxAge)
+        } else {
+            None
+        };
+        let mut report = BackupFileReport {
+            path: path.to_string_lossy().into_owned(),
+            size,
+            modified: modified.to_rfc3339(),
+            age_days,
+            reason,
+            error: None,
+            local_times: None,
+        };
+
+        if reason.is_none() {
+            result.kept.push(report);
+            continue;
+        }
+        if !dry_run {
+            if let Err(e) = fs::remove_file(&path) {
+                report.error = Some(e.to_string());
+                result.kept.push(report);
+                continue;
+            }
+            println!("[backup] Deleted old backup: {:?}", path);
+        }
+        result.freed_bytes += size;
+        result.deleted.push(report);
+    }
+
+    result.deleted_count = result.deleted.len();
+    result.kept_count = result.kept.len();
+    result
+}
+
+/// Cleanup old backup files, keeping the newest `keepCount` (default 5) and
+/// dropping any older than `maxAgeDays`. With `dryRun` nothing is deleted and
+/// the result lists what would be. The result is also emitted as a
+/// `backup-cleanup` event so the UI can log it.
 #[tauri::command]
 pub fn cleanup_old_backups(
+    app: tauri::AppHandle,
     #[allow(non_snake_case)] projectId: String,
-    #[allow(non_snake_case)] keepCount: Option<usize>
+    #[allow(non_snake_case)] keepCount: Option<usize>,
+    #[allow(non_snake_case)] maxAgeDays: Option<u64>,
+    #[allow(non_snake_case)] dryRun: Option<bool>,
+    services: State<'_, AppServices>,
 ) -> Result<CleanupResult, String> {
-    let keep_count = keepCount.unwrap_or(5);
     let project_path = get_project_path(&projectId);
-    let project_dir = project_path.parent()
-        .ok_or_else(|| "Invalid project path".to_string())?;
-    
-    // Find all backup files for this project
-    let project_name = project_path.file_stem()
+    if project_path.parent().is_none() || project_path.file_name().is_none() {
+        return Err("Invalid project path".to_string());
+    }
+    let mut result = cleanup_backups_at(
+        &project_path,
+        keepCount.unwrap_or(DEFAULT_KEEP_BACKUPS),
+        maxAgeDays,
+        dryRun.unwrap_or(false),
+        services.now(),
+    );
+    let formatter = TimeFormatter::current();
+    for report in result.deleted.iter_mut().chain(result.kept.iter_mut()) {
+        report.local_times =
+            Some(formatter.local_times_rfc3339([("modified", Some(report.modified.as_str()))]));
+    }
+    let _ = app.emit(
+        "backup-cleanup",
+        serde_json::json!({ "projectId": projectId, "result": &result }),
+    );
+    Ok(result)
+}
+
+/// Kind of leftover file a recovery item comes from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryItemKind {
+    /// `.scormproj.backup` newer than the project file (or the project file is gone)
+    NewerBackup,
+    /// `.scormproj.autosave` draft written after the last save
+    AutosaveDraft,
+    /// `.scormproj.tmp` left behind by a save that never completed
+    PartialWrite,
+}
+
+impl RecoveryItemKind {
+    const ALL: [RecoveryItemKind; 3] = [
+        RecoveryItemKind::NewerBackup,
+        RecoveryItemKind::AutosaveDraft,
+        RecoveryItemKind::PartialWrite,
+    ];
+
+    fn suffix(self) -> &'static str {
+        match self {
+            RecoveryItemKind::NewerBackup => ".scormproj.backup",
+            RecoveryItemKind::AutosaveDraft => ".scormproj.autosave",
+            RecoveryItemKind::PartialWrite => ".scormproj.tmp",
+        }
+    }
+
+    /// Kind and project file for a leftover file path
+    pub(crate) fn classify(path: &Path) -> Option<(RecoveryItemKind, PathBuf)> {
+        let file_name = path.file_name()?.to_str()?;
+        Self::ALL.into_iter().find_map(|kind| {
+            let stem = file_name.strip_suffix(kind.suffix())?;
+            Some((kind, path.with_file_name(format!("{stem}.scormproj"))))
+        })
+    }
+}
+
+/// Something `recover_all` / `accept_recovery_item` can restore
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryItem {
+    /// Path of the leftover file; pass it back to accept/discard
+    pub id: String,
+    pub kind: RecoveryItemKind,
+    pub project_path: String,
+    pub project_name: String,
+    pub modified: Option<String>,
+    pub project_modified: Option<String>,
+    pub size: u64,
+    /// Whether the file holds complete JSON and can be restored
+    pub readable: bool,
+    /// `modified` and `projectModified` in local time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_times: Option<LocalTimes>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryReport {
+    pub items: Vec<RecoveryItem>,
+    pub scanned_projects: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverAllResult {
+    /// Project files that were restored
+    pub recovered: Vec<String>,
+    /// Items left alone, with the reason
+    pub skipped: Vec<(String, String)>,
+}
+
+pub(crate) fn modified_time(path: &Path) -> Option<std::time::SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn to_rfc3339(time: std::time::SystemTime) -> String {
+    let datetime: DateTime<Utc> = time.into();
+    datetime.to_rfc3339()
+}
+
+/// Whether `s` looks like a project ID: the 13-digit timestamp format or a UUID
+pub(crate) fn is_project_id(s: &str) -> bool {
+    let is_timestamp = !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let is_uuid = s.len() == 36
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        });
+    is_timestamp || is_uuid
+}
+
+/// Name and ID from `Name_1234567890.scormproj`
+pub(crate) fn split_project_file_name(project_path: &Path) -> (String, Option<String>) {
+    let stem = project_path
+        .file_stem()
         .and_then(|s| s.to_str())
-        .ok_or_else(|| "Invalid project name".to_string())?;
-    
-    let mut backup_files: Vec<(PathBuf, std::time::SystemTime)> = Vec::new();
-    
-    // Look for backup files with pattern: projectname.backup.1, projectname.backup.2, etc.
-    if let Ok(entries) = fs::read_dir(project_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if let Some(file_name) = path.file_name().and_then(|s| s.to_str()) {
-                if file_name.starts_with(project_name) && file_name.contains(".backup") {
-                    if let Ok(metadata) = fs::metadata(&path) {
-                        if let Ok(modified) = metadata.modified() {
-                            backup_files.push((path, modified));
-                        }
-                    }
-                }
-            }
+        .unwrap_or_default();
+    match stem.rsplit_once('_') {
+        Some((name, id)) if is_project_id(id) => (name.to_string(), Some(id.to_string())),
+        _ if is_project_id(stem) => (stem.to_string(), Some(stem.to_string())),
+        _ => (stem.to_string(), None),
+    }
+}
+
+fn project_name_from_path(project_path: &Path) -> String {
+    split_project_file_name(project_path).0
+}
+
+/// Scan `projects_dir` for everything that could be recovered
+pub fn build_recovery_report(projects_dir: &Path) -> Result<RecoveryReport, String> {
+    let entries = fs::read_dir(projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+
+    let mut report = RecoveryReport::default();
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+
+    for path in paths {
+        if path.extension().and_then(|e| e.to_str()) == Some("scormproj") {
+            report.scanned_projects += 1;
+            continue;
+        }
+
+        let Some((kind, project_path)) = RecoveryItemKind::classify(&path) else {
+            continue;
+        };
+
+        let modified = modified_time(&path);
+        let project_modified = modified_time(&project_path);
+
+        // Backups and drafts only matter when they hold something the project file doesn't
+        let is_newer = match (modified, project_modified) {
+            (_, None) => true,
+            (Some(item), Some(project)) => item > project,
+            (None, Some(_)) => false,
+        };
+        if kind != RecoveryItemKind::PartialWrite && !is_newer {
+            continue;
         }
+
+        let readable = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .is_some();
+
+        report.items.push(RecoveryItem {
+            id: path.to_string_lossy().to_string(),
+            kind,
+            project_name: project_name_from_path(&project_path),
+            project_path: project_path.to_string_lossy().to_string(),
+            modified: modified.map(to_rfc3339),
+            project_modified: project_modified.map(to_rfc3339),
+            size: fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0),
+            readable,
+            local_times: None,
+        });
     }
-    
-    // Sort by modification time (newest first)
-    backup_files.sort_by(|a, b| b.1.cmp(&a.1));
-    
-    // Delete old backups beyond the keep count
-    let mut deleted_count = 0;
-    for (i, (path, _)) in backup_files.iter().enumerate() {
-        if i >= keep_count {
-            if fs::remove_file(path).is_ok() {
-                deleted_count += 1;
-                println!("[backup] Deleted old backup: {:?}", path);
+
+    Ok(report)
+}
+
+/// Resolve an item ID to its file and project, refusing anything outside
+/// `projects_dir` or that isn't a recovery file
+fn resolve_recovery_item(
+    projects_dir: &Path,
+    item_id: &str,
+) -> Result<(RecoveryItemKind, PathBuf, PathBuf), String> {
+    let path = PathBuf::from(item_id);
+    if path.parent() != Some(projects_dir) {
+        return Err("Recovery item is not in the projects directory".to_string());
+    }
+    let (kind, project_path) = RecoveryItemKind::classify(&path)
+        .ok_or_else(|| "Not a recovery file".to_string())?;
+    if !path.exists() {
+        return Err("Recovery item no longer exists".to_string());
+    }
+    Ok((kind, path, project_path))
+}
+
+/// Restore one item over its project file. The current project file is kept
+/// as a timestamped history backup first.
+pub fn accept_recovery_item_in(
+    projects_dir: &Path,
+    item_id: &str,
+    services: &AppServices,
+) -> Result<String, String> {
+    let (kind, path, project_path) = resolve_recovery_item(projects_dir, item_id)?;
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read recovery item: {}", e))?;
+    serde_json::from_str::<serde_json::Value>(&content)
+        .map_err(|e| format!("Recovery item is incomplete: {}", e))?;
+
+    if project_path.exists() {
+        let history_path = history_backup_path(&project_path, services.now());
+        fs::copy(&project_path, &history_path)
+            .map_err(|e| format!("Failed to keep current project file: {}", e))?;
+    }
+
+    fs::write(&project_path, content)
+        .map_err(|e| format!("Failed to restore project file: {}", e))?;
+
+    // The backup stays as the latest backup; drafts and temp files are used up
+    if kind != RecoveryItemKind::NewerBackup {
+        let _ = fs::remove_file(&path);
+    }
+
+    println!("[backup] Recovered {:?} from {:?}", project_path, path);
+    Ok(project_path.to_string_lossy().to_string())
+}
+
+/// Drop one item. Drafts and temp files are deleted; a newer backup is moved to
+/// the timestamped history so it stops being offered.
+pub fn discard_recovery_item_in(
+    projects_dir: &Path,
+    item_id: &str,
+    services: &AppServices,
+) -> Result<(), String> {
+    let (kind, path, project_path) = resolve_recovery_item(projects_dir, item_id)?;
+
+    if kind == RecoveryItemKind::NewerBackup {
+        fs::rename(&path, history_backup_path(&project_path, services.now()))
+            .map_err(|e| format!("Failed to discard backup: {}", e))?;
+    } else {
+        fs::remove_file(&path).map_err(|e| format!("Failed to discard recovery item: {}", e))?;
+    }
+
+    println!("[backup] Discarded recovery item {:?}", path);
+    Ok(())
+}
+
+/// Accept the newest readable item of every project in the report
+pub fn recover_all_in(
+    projects_dir: &Path,
+    services: &AppServices,
+) -> Result<RecoverAllResult, String> {
+    let report = build_recovery_report(projects_dir)?;
+    let mut result = RecoverAllResult::default();
+
+    let mut newest: Vec<&RecoveryItem> = Vec::new();
+    for item in &report.items {
+        if !item.readable {
+            result.skipped.push((item.id.clone(), "File is incomplete".to_string()));
+            continue;
+        }
+        match newest.iter_mut().find(|n| n.project_path == item.project_path) {
+            Some(current)
+                if modified_time(Path::new(&item.id)) > modified_time(Path::new(&current.id)) =>
+            {
+                result.skipped.push((current.id.clone(), "A newer item was recovered".to_string()));
+                *current = item;
             }
+            Some(_) => {
+                result.skipped.push((item.id.clone(), "A newer item was recovered".to_string()));
+            }
+            None => newest.push(item),
         }
     }
-    
-    Ok(CleanupResult {
-        deleted_count,
-        kept_count: backup_files.len().min(keep_count),
-    })
+
+    for item in newest {
+        match accept_recovery_item_in(projects_dir, &item.id, services) {
+            Ok(project_path) => result.recovered.push(project_path),
+            Err(e) => result.skipped.push((item.id.clone(), e)),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Detailed startup recovery check across every project
+#[tauri::command]
+pub fn get_recovery_report() -> Result<RecoveryReport, String> {
+    let mut report = build_recovery_report(&crate::settings::get_projects_directory()?)?;
+    let formatter = TimeFormatter::current();
+    for item in &mut report.items {
+        item.local_times = Some(formatter.local_times_rfc3339([
+            ("modified", item.modified.as_deref()),
+            ("projectModified", item.project_modified.as_deref()),
+        ]));
+    }
+    Ok(report)
+}
+
+/// Restore every project that has a newer backup, draft or temp file
+#[tauri::command]
+pub fn recover_all(services: State<'_, AppServices>) -> Result<RecoverAllResult, String> {
+    recover_all_in(&crate::settings::get_projects_directory()?, &services)
+}
+
+/// Restore one item from `get_recovery_report`
+#[tauri::command]
+pub fn accept_recovery_item(
+    #[allow(non_snake_case)] itemId: String,
+    services: State<'_, AppServices>,
+) -> Result<String, String> {
+    accept_recovery_item_in(&crate::settings::get_projects_directory()?, &itemId, &services)
+}
+
+/// Dismiss one item from `get_recovery_report`
+#[tauri::command]
+pub fn discard_recovery_item(
+    #[allow(non_snake_case)] itemId: String,
+    services: State<'_, AppServices>,
+) -> Result<(), String> {
+    discard_recovery_item_in(&crate::settings::get_projects_directory()?, &itemId, &services)
+}
+
+/// Write an autosave draft next to the project file without touching the project itself.
+/// The write is skipped when the draft already holds the same content and media.
+#[tauri::command]
+pub fn save_autosave_draft(
+    #[allow(non_snake_case)] projectId: String,
+    #[allow(non_snake_case)] projectData: serde_json::Value,
+) -> Result<ArtifactStatus, String> {
+    save_autosave_draft_at(&get_project_path(&projectId), &projectData)
+}
+
+fn save_autosave_draft_at(
+    project_path: &Path,
+    project_data: &serde_json::Value,
+) -> Result<ArtifactStatus, String> {
+    project_access::ensure_writable(project_path)?;
+    let draft_path = project_path.with_extension("scormproj.autosave");
+    let hash = project_hash(project_path, project_data);
+    if let Some((projects_dir, id, hash)) = &hash {
+        if draft_path.exists()
+            && content_hash::stored_hash(projects_dir, id, "autosave").as_deref() == Some(hash)
+        {
+            return Ok(ArtifactStatus::UpToDate);
+        }
+    }
+
+    let json = serde_json::to_string_pretty(project_data)
+        .map_err(|e| format!("Failed to serialize draft: {}", e))?;
+    fs::write(&draft_path, json).map_err(|e| format!("Failed to write draft: {}", e))?;
+
+    if let Some((projects_dir, id, hash)) = hash {
+        content_hash::store_hash(&projects_dir, &id, "autosave", &hash)?;
+    }
+    Ok(ArtifactStatus::Written)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app_services::testing::deterministic_services;
     use tempfile::TempDir;
     
     #[test]
@@ -288,11 +841,154 @@ mod tests {
         fs::write(&backup_file, test_data).unwrap();
         
         // Recover from backup
-        let result = recover_from_backup(project_file.to_string_lossy().to_string());
+        let (_clock, services) = deterministic_services();
+        let result =
+            recover_from_backup_with_services(project_file.to_string_lossy().to_string(), &services);
         assert!(result.is_ok());
         
         let recovered_data = result.unwrap();
         assert!(recovered_data["metadata"]["recovered"].as_bool().unwrap());
+        assert_eq!(
+            recovered_data["metadata"]["timestamp"],
+            "2024-01-01T00:00:00+00:00"
+        );
         assert!(recovered_data["pages"].is_array());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_backup_cleanup_reports_and_dry_run_deletes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_file = temp_dir.path().join("test_1234567890.scormproj");
+        let now = DateTime::parse_from_rfc3339("2024-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let backup = |name: &str, days_old: i64| {
+            let path = temp_dir.path().join(name);
+            let file = fs::File::create(&path).unwrap();
+            file.set_modified((now - chrono::Duration::days(days_old)).into()).unwrap();
+            path
+        };
+        let main = backup("test_1234567890.scormproj.backup", 40);
+        let recent = backup("test_1234567890.scormproj.backup.20240229-000000000", 1);
+        let old = backup("test_1234567890.scormproj.backup.20240101-000000000", 60);
+        let oldest = backup("test_1234567890.scormproj.backup.20231201-000000000", 90);
+        // Another project whose file name starts the same
+        let other = backup("test_12345678901.scormproj.backup", 90);
+
+        let dry = cleanup_backups_at(&project_file, 3, Some(30), true, now);
+        assert!(dry.dry_run);
+        assert_eq!((dry.deleted_count, dry.kept_count), (2, 2));
+        let reasons: Vec<_> = dry.deleted.iter().map(|file| (file.age_days, file.reason)).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (60, Some(BackupDeletionReason::OlderThanMaxAge)),
+                (90, Some(BackupDeletionReason::BeyondKeepCount)),
+            ]
+        );
+        assert!(old.exists() && oldest.exists());
+
+        let done = cleanup_backups_at(&project_file, 3, Some(30), false, now);
+        assert_eq!(done.deleted, dry.deleted);
+        assert!(!old.exists() && !oldest.exists());
+        assert!(main.exists() && recent.exists() && other.exists());
+    }
+
+    #[test]
+    fn test_unchanged_project_skips_backup_and_draft() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_file = temp_dir.path().join("test_1234567890.scormproj");
+        let project = project_file.to_string_lossy().to_string();
+        fs::write(&project_file, r#"{"project": {"id": "1234567890", "last_modified": "a"}}"#).unwrap();
+
+        let first = create_backup(project.clone()).unwrap();
+        assert_eq!(first, ArtifactStatus::Written);
+
+        // Only last_modified changed, so the existing backup still matches
+        fs::write(&project_file, r#"{"project": {"id": "1234567890", "last_modified": "b"}}"#).unwrap();
+        let second = create_backup(project.clone()).unwrap();
+        assert_eq!(second, ArtifactStatus::UpToDate);
+
+        // New media counts as a change
+        let media_dir = temp_dir.path().join("1234567890").join("media");
+        fs::create_dir_all(&media_dir).unwrap();
+        fs::write(media_dir.join("image-0.bin"), b"png").unwrap();
+        let third = create_backup(project).unwrap();
+        assert_eq!(third, ArtifactStatus::Written);
+
+        let draft = serde_json::json!({ "project": { "id": "1234567890" }, "pages": [] });
+        assert_eq!(save_autosave_draft_at(&project_file, &draft).unwrap(), ArtifactStatus::Written);
+        assert_eq!(save_autosave_draft_at(&project_file, &draft).unwrap(), ArtifactStatus::UpToDate);
+        fs::remove_file(project_file.with_extension("scormproj.autosave")).unwrap();
+        assert_eq!(save_autosave_draft_at(&project_file, &draft).unwrap(), ArtifactStatus::Written);
+    }
+
+    #[test]
+    fn test_recovery_report_lists_leftovers() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let project = dir.join("Safety_1234567890.scormproj");
+        let other = dir.join("Other_42.scormproj");
+        fs::write(&project, r#"{"v": 1}"#).unwrap();
+        fs::write(&other, r#"{"v": 1}"#).unwrap();
+
+        // Written after the project files so their mtimes are newer
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(dir.join("Safety_1234567890.scormproj.autosave"), r#"{"v": 2}"#).unwrap();
+        fs::write(dir.join("Other_42.scormproj.tmp"), r#"{"v": "#).unwrap();
+        // Backup of a project whose file is gone
+        fs::write(dir.join("Lost_7.scormproj.backup"), r#"{"v": 3}"#).unwrap();
+
+        let report = build_recovery_report(dir).unwrap();
+        assert_eq!(report.scanned_projects, 2);
+
+        let summary: Vec<_> = report
+            .items
+            .iter()
+            .map(|i| (i.project_name.as_str(), i.kind, i.readable))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Lost", RecoveryItemKind::NewerBackup, true),
+                ("Other", RecoveryItemKind::PartialWrite, false),
+                ("Safety", RecoveryItemKind::AutosaveDraft, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accept_and_discard_recovery_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let project = dir.join("Safety_1234567890.scormproj");
+        fs::write(&project, r#"{"v": 1}"#).unwrap();
+        let draft = dir.join("Safety_1234567890.scormproj.autosave");
+        fs::write(&draft, r#"{"v": 2}"#).unwrap();
+        let backup = dir.join("Safety_1234567890.scormproj.backup");
+        fs::write(&backup, r#"{"v": 0}"#).unwrap();
+
+        let (_clock, services) = deterministic_services();
+        let restored =
+            accept_recovery_item_in(dir, &draft.to_string_lossy(), &services).unwrap();
+
+        assert_eq!(restored, project.to_string_lossy());
+        assert_eq!(fs::read_to_string(&project).unwrap(), r#"{"v": 2}"#);
+        assert!(!draft.exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("Safety_1234567890.scormproj.backup.20240101-000000000"))
+                .unwrap(),
+            r#"{"v": 1}"#
+        );
+
+        discard_recovery_item_in(dir, &backup.to_string_lossy(), &services).unwrap();
+        assert!(!backup.exists());
+
+        // Only files in the projects directory can be touched
+        let outside = TempDir::new().unwrap();
+        let stray = outside.path().join("X_1.scormproj.tmp");
+        fs::write(&stray, "{}").unwrap();
+        assert!(discard_recovery_item_in(dir, &stray.to_string_lossy(), &services).is_err());
+        assert!(stray.exists());
+    }
+}
@@ -0,0 +1,421 @@
+use crate::backup_recovery::is_project_id;
+use crate::build_progress::PhaseDurations;
+use crate::content_hash::{canonical_json, hash_hex};
+use crate::display_time::{LocalTimes, TimeFormatter};
+use crate::project_storage::ScormConfig;
+use crate::scorm::package_version::{read_package_version, PackageVersion};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kept in each project folder next to `media/`
+const BUILD_HISTORY_FILE: &str = "build-history.json";
+
+/// Oldest builds are dropped beyond this many
+const MAX_BUILD_HISTORY: usize = 200;
+
+/// Request fields that are course content rather than settings
+const CONTENT_FIELDS: [&str; 8] = [
+    "course_title",
+    "course_description",
+    "welcome_page",
+    "learning_objectives_page",
+    "topics",
+    "assessment",
+    "question_bank",
+    "language_variants",
+];
+
+/// One SCORM generation, successful or not
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Short hash of `settings`; equal hashes mean the same settings were used
+    pub settings_hash: String,
+    pub settings: Value,
+    /// Hash of the course content and media the build ran with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Build id stamped into the package's `version.json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_id: Option<String>,
+    pub duration_ms: u64,
+    /// ZIP size in bytes, 0 when the build failed
+    pub output_size: u64,
+    pub success: bool,
+    pub warnings: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Time spent in each phase, recorded for successful builds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase_durations: Option<PhaseDurations>,
+    /// Set when reporting: `timestamp` in local time. Never written to the history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_times: Option<LocalTimes>,
+}
+
+/// The settings a build ran with: the generation request minus course content,
+/// the languages of any variants and the project's SCORM export config
+pub fn build_settings(course_data: &Value, scorm_config: Option<&ScormConfig>) -> Value {
+    let mut settings = Map::new();
+
+    if let Some(request) = course_data.as_object() {
+        for (key, value) in request {
+            if !CONTENT_FIELDS.contains(&key.as_str()) && !value.is_null() {
+                settings.insert(key.clone(), value.clone());
+            }
+        }
+        let languages: Vec<Value> = request
+            .get("language_variants")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|variant| variant.get("language").cloned())
+            .collect();
+        if !languages.is_empty() {
+            settings.insert("language_variants".to_string(), Value::Array(languages));
+        }
+    }
+    if let Some(config) = scorm_config.and_then(|config| serde_json::to_value(config).ok()) {
+        settings.insert("scorm_config".to_string(), config);
+    }
+
+    Value::Object(settings)
+}
+
+/// Short hash of the settings with keys sorted
+pub fn settings_hash(settings: &Value) -> String {
+    hash_hex(&canonical_json(settings))
+}
+
+/// Short hash of the full generation request plus the project's media manifest
+pub fn build_content_hash(course_data: &Value, media_manifest: &[Value]) -> String {
+    let content = serde_json::json!({ "course": course_data, "media": media_manifest });
+    hash_hex(&canonical_json(&content))
+}
+
+/// Most recent successful build with the same settings and content, if any.
+/// When there is one, building again would produce the same package.
+pub fn up_to_date_build<'a>(
+    history: &'a [BuildRecord],
+    settings_hash: &str,
+    content_hash: &str,
+) -> Option<&'a BuildRecord> {
+    history
+        .iter()
+        .rev()
+        .find(|build| build.success && build.settings_hash == settings_hash)
+        .filter(|build| build.content_hash.as_deref() == Some(content_hash))
+}
+
+fn history_path(projects_dir: &Path, project_id: &str) -> Result<PathBuf, String> {
+    if !is_project_id(project_id) {
+        return Err(format!("Invalid project ID: {project_id}"));
+    }
+    Ok(projects_dir.join(project_id).join(BUILD_HISTORY_FILE))
+}
+
+/// Builds of a project, oldest first. A missing file is an empty history.
+pub fn load_build_history(
+    projects_dir: &Path,
+    project_id: &str,
+) -> Result<Vec<BuildRecord>, String> {
+    let path = history_path(projects_dir, project_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read build history: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse build history: {e}"))
+}
+
+/// Append a build, dropping the oldest ones beyond the history limit
+pub fn record_build(
+    projects_dir: &Path,
+    project_id: &str,
+    mut record: BuildRecord,
+) -> Result<(), String> {
+    let path = history_path(projects_dir, project_id)?;
+    record.local_times = None;
+    // An unreadable history is replaced rather than blocking the build record
+    let mut history = load_build_history(projects_dir, project_id).unwrap_or_default();
+    history.push(record);
+    if history.len() > MAX_BUILD_HISTORY {
+        history.drain(..history.len() - MAX_BUILD_HISTORY);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create project directory: {e}"))?;
+    }
+    let json = serde_json::to_string_pretty(&history)
+        .map_err(|e| format!("Failed to serialize build history: {e}"))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write build history: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write build history: {e}"))
+}
+
+/// SCORM builds of a project, newest first
+#[tauri::command]
+pub fn get_build_history(
+    #[allow(non_snake_case)] projectId: String,
+    limit: Option<usize>,
+) -> Result<Vec<BuildRecord>, String> {
+    let projects_dir = crate::settings::get_projects_directory()?;
+    let mut history = load_build_history(&projects_dir, &projectId)?;
+    history.reverse();
+    if let Some(limit) = limit {
+        history.truncate(limit);
+    }
+    let formatter = TimeFormatter::current();
+    for record in &mut history {
+        record.local_times = Some(formatter.local_times([("timestamp", record.timestamp)]));
+    }
+    Ok(history)
+}
+
+/// A package ZIP traced back to its project build
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVersionInfo {
+    pub path: String,
+    /// None for packages built before version stamping
+    pub version: Option<PackageVersion>,
+    /// The project's record of the build, while it is still in the history
+    pub build: Option<BuildRecord>,
+    /// Whether the package is the project's most recent successful build
+    pub latest: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageComparison {
+    pub packages: Vec<PackageVersionInfo>,
+    /// Every package is the same build
+    pub same_build: bool,
+    /// Every package was built from the same course content and media, even
+    /// if at different times
+    pub same_content: bool,
+}
+
+/// Look up the build a package was stamped with in its project's history
+pub fn describe_package(
+    projects_dir: &Path,
+    path: String,
+    version: Option<PackageVersion>,
+) -> PackageVersionInfo {
+    let history = version
+        .as_ref()
+        .and_then(|version| load_build_history(projects_dir, &version.project_id).ok())
+        .unwrap_or_default();
+    let build_id = version.as_ref().map(|version| version.build_id.as_str());
+    let build = history
+        .iter()
+        .find(|build| build.build_id.is_some() && build.build_id.as_deref() == build_id)
+        .cloned();
+    let latest_success = history.iter().rev().find(|build| build.success);
+    let latest = build.is_some() && latest_success.and_then(|b| b.build_id.as_deref()) == build_id;
+
+    PackageVersionInfo {
+        path,
+        version,
+        build,
+        latest,
+    }
+}
+
+/// Compare packages by their stamps. Unstamped packages never match anything.
+pub fn compare_packages(packages: Vec<PackageVersionInfo>) -> PackageComparison {
+    let all_equal = |key: fn(&PackageVersion) -> &str| {
+        let mut keys = packages
+            .iter()
+            .map(|package| package.version.as_ref().map(key));
+        match keys.next() {
+            Some(Some(first)) => keys.all(|key| key == Some(first)),
+            _ => false,
+        }
+    };
+    let same_build = all_equal(|version| &version.build_id);
+    let same_content = all_equal(|version| &version.content_hash);
+
+    PackageComparison {
+        packages,
+        same_build,
+        same_content,
+    }
+}
+
+/// Which project builds the given package ZIPs are, for matching packages
+/// uploaded to an LMS with project revisions
+#[tauri::command]
+pub fn compare_package_versions(paths: Vec<String>) -> Result<PackageComparison, String> {
+    if paths.is_empty() {
+        return Err("No packages to compare".to_string());
+    }
+    let projects_dir = crate::settings::get_projects_directory()?;
+    let mut packages = Vec::new();
+    for path in paths {
+        let zip_data =
+            fs::read(&path).map_err(|e| format!("Failed to read package {path}: {e}"))?;
+        let version = read_package_version(&zip_data)?;
+        packages.push(describe_package(&projects_dir, path, version));
+    }
+    Ok(compare_packages(packages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn record(success: bool, minute: u32) -> BuildRecord {
+        let settings = json!({ "pass_mark": 80 });
+        BuildRecord {
+            timestamp: DateTime::parse_from_rfc3339(&format!("2024-01-01T00:{minute:02}:00Z"))
+                .unwrap()
+                .with_timezone(&Utc),
+            settings_hash: settings_hash(&settings),
+            settings,
+            content_hash: Some("0123456789abcdef".to_string()),
+            build_id: Some(format!("20240101-00{minute:02}00-01234567")),
+            duration_ms: 1200,
+            output_size: if success { 2048 } else { 0 },
+            success,
+            warnings: 0,
+            error: (!success).then(|| "boom".to_string()),
+            phase_durations: None,
+            local_times: None,
+        }
+    }
+
+    #[test]
+    fn test_settings_ignore_content_and_key_order() {
+        let a = json!({
+            "course_title": "Fire Safety",
+            "topics": [{ "id": "topic-1" }],
+            "pass_mark": 80,
+            "navigation_mode": "linear",
+            "language_variants": [{ "language": "fr", "course": { "topics": [] } }],
+            "debug_build": null
+        });
+        let b = json!({
+            "navigation_mode": "linear",
+            "pass_mark": 80,
+            "course_title": "Fire Safety, revised",
+            "topics": [],
+            "language_variants": [{ "language": "fr", "course": { "topics": [{}] } }]
+        });
+
+        let settings = build_settings(&a, None);
+        assert_eq!(
+            settings,
+            json!({ "pass_mark": 80, "navigation_mode": "linear", "language_variants": ["fr"] })
+        );
+        assert_eq!(
+            settings_hash(&settings),
+            settings_hash(&build_settings(&b, None))
+        );
+        assert_eq!(settings_hash(&settings).len(), 16);
+
+        let stricter = json!({ "pass_mark": 90, "navigation_mode": "linear" });
+        assert_ne!(
+            settings_hash(&settings),
+            settings_hash(&build_settings(&stricter, None))
+        );
+    }
+
+    #[test]
+    fn test_record_and_load_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        assert!(load_build_history(dir, "1234567890").unwrap().is_empty());
+
+        record_build(dir, "1234567890", record(true, 0)).unwrap();
+        record_build(dir, "1234567890", record(false, 5)).unwrap();
+
+        let history = load_build_history(dir, "1234567890").unwrap();
+        assert_eq!(history, vec![record(true, 0), record(false, 5)]);
+        assert!(dir.join("1234567890").join(BUILD_HISTORY_FILE).exists());
+
+        assert!(record_build(dir, "../elsewhere", record(true, 0)).is_err());
+    }
+
+    #[test]
+    fn test_up_to_date_build_needs_matching_hashes() {
+        let ok = record(true, 0);
+        let hash = ok.settings_hash.clone();
+        let history = vec![ok.clone(), record(false, 5)];
+
+        assert_eq!(
+            up_to_date_build(&history, &hash, "0123456789abcdef"),
+            Some(&ok)
+        );
+        assert_eq!(up_to_date_build(&history, &hash, "fedcba9876543210"), None);
+        assert_eq!(
+            up_to_date_build(&history, "other", "0123456789abcdef"),
+            None
+        );
+
+        // A newer build with different content supersedes the matching one
+        let mut changed = record(true, 10);
+        changed.content_hash = Some("fedcba9876543210".to_string());
+        let history = vec![ok, changed];
+        assert_eq!(up_to_date_build(&history, &hash, "0123456789abcdef"), None);
+    }
+
+    #[test]
+    fn test_history_keeps_the_newest_builds() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        for minute in 0..(MAX_BUILD_HISTORY as u32 + 3) {
+            let mut build = record(true, minute % 60);
+            build.duration_ms = u64::from(minute);
+            record_build(dir, "42", build).unwrap();
+        }
+
+        let history = load_build_history(dir, "42").unwrap();
+        assert_eq!(history.len(), MAX_BUILD_HISTORY);
+        assert_eq!(history[0].duration_ms, 3);
+        assert_eq!(
+            history.last().unwrap().duration_ms,
+            MAX_BUILD_HISTORY as u64 + 2
+        );
+    }
+
+    #[test]
+    fn test_packages_are_traced_to_builds() {
+        use crate::scorm::package_version::{PackageBuild, PackageVersion};
+
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        for minute in [0, 5, 10] {
+            record_build(dir, "1234567890", record(minute != 10, minute)).unwrap();
+        }
+
+        let stamp = |minute: u32| {
+            let built_at = record(true, minute).timestamp;
+            let build = PackageBuild::new("1234567890", "0123456789abcdef", built_at);
+            Some(PackageVersion::new("1.0", &build))
+        };
+        let older = describe_package(dir, "a.zip".to_string(), stamp(0));
+        let newer = describe_package(dir, "b.zip".to_string(), stamp(5));
+        assert_eq!(older.build, Some(record(true, 0)));
+        assert!(!older.latest);
+        assert!(newer.latest, "the failed build at minute 10 doesn't count");
+
+        let comparison = compare_packages(vec![older.clone(), newer]);
+        assert!(!comparison.same_build);
+        assert!(comparison.same_content);
+
+        let unstamped = describe_package(dir, "c.zip".to_string(), None);
+        assert_eq!(unstamped.build, None);
+        let comparison = compare_packages(vec![older, unstamped]);
+        assert!(!comparison.same_content);
+    }
+}
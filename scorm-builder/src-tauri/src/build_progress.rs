@@ -0,0 +1,204 @@
+//! Progress and time remaining for SCORM builds, from the project's earlier builds.
+//!
+//! A build gathers media, renders the pages into the package, then finishes
+//! the ZIP. Successful builds record how long each phase took in the build
+//! history. The next build expects each phase to take the median of the
+//! recent builds, which sets its share of the progress bar and the time
+//! remaining reported with each progress event. A project with no timed
+//! builds yet gets fixed shares and no time remaining.
+
+use crate::build_history::BuildRecord;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Successful builds the expected phase durations are taken from
+const RECENT_BUILDS: usize = 10;
+
+/// Progress bar shares of the phases for projects without timed builds
+const DEFAULT_SHARES: PhaseDurations = PhaseDurations {
+    media_ms: 40,
+    html_ms: 40,
+    zip_ms: 20,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildPhase {
+    /// Parsing the course and loading its media
+    Media,
+    /// Rendering pages and writing every file into the package
+    Html,
+    /// Finishing and validating the ZIP
+    Zip,
+}
+
+const PHASES: [BuildPhase; 3] = [BuildPhase::Media, BuildPhase::Html, BuildPhase::Zip];
+
+/// How long each phase of a build took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseDurations {
+    pub media_ms: u64,
+    pub html_ms: u64,
+    pub zip_ms: u64,
+}
+
+impl PhaseDurations {
+    /// Split a build's duration given the generator's own timings; anything
+    /// outside them counts as gathering media
+    pub fn from_build(duration_ms: u64, html_ms: u64, zip_ms: u64) -> Self {
+        Self {
+            media_ms: duration_ms.saturating_sub(html_ms + zip_ms),
+            html_ms,
+            zip_ms,
+        }
+    }
+
+    pub fn get(&self, phase: BuildPhase) -> u64 {
+        match phase {
+            BuildPhase::Media => self.media_ms,
+            BuildPhase::Html => self.html_ms,
+            BuildPhase::Zip => self.zip_ms,
+        }
+    }
+
+    pub fn total_ms(&self) -> u64 {
+        self.media_ms + self.html_ms + self.zip_ms
+    }
+}
+
+/// Expected phase durations of the next build of a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildEstimate {
+    expected: PhaseDurations,
+    /// Whether `expected` are real durations rather than the default shares
+    timed: bool,
+}
+
+impl Default for BuildEstimate {
+    fn default() -> Self {
+        Self {
+            expected: DEFAULT_SHARES,
+            timed: false,
+        }
+    }
+}
+
+impl BuildEstimate {
+    /// Median phase durations of the project's recent successful builds
+    pub fn from_history(history: &[BuildRecord]) -> Self {
+        let recent: Vec<PhaseDurations> = history
+            .iter()
+            .rev()
+            .filter(|build| build.success)
+            .filter_map(|build| build.phase_durations)
+            .take(RECENT_BUILDS)
+            .collect();
+        if recent.is_empty() {
+            return Self::default();
+        }
+        let median = |phase: BuildPhase| {
+            let mut values: Vec<u64> = recent.iter().map(|build| build.get(phase)).collect();
+            values.sort_unstable();
+            values[values.len() / 2]
+        };
+        let expected = PhaseDurations {
+            media_ms: median(BuildPhase::Media),
+            html_ms: median(BuildPhase::Html),
+            zip_ms: median(BuildPhase::Zip),
+        };
+        if expected.total_ms() == 0 {
+            return Self::default();
+        }
+        Self {
+            expected,
+            timed: true,
+        }
+    }
+
+    /// Percent done and milliseconds left once `fraction` of `phase` is done.
+    /// Never 100 percent; only the end of the build reports that.
+    pub fn progress(&self, phase: BuildPhase, fraction: f64) -> (u32, Option<u64>) {
+        let before: u64 = PHASES
+            .iter()
+            .take_while(|earlier| **earlier != phase)
+            .map(|earlier| self.expected.get(*earlier))
+            .sum();
+        let done = before as f64 + self.expected.get(phase) as f64 * fraction.clamp(0.0, 1.0);
+        let total = self.expected.total_ms() as f64;
+        let percent = ((done / total * 100.0).round() as u32).min(99);
+        let eta_ms = self.timed.then(|| (total - done).round() as u64);
+        (percent, eta_ms)
+    }
+
+    /// `progress`, `phase` and `etaMs` of a `scorm-generation-progress` event
+    pub fn event(&self, phase: BuildPhase, fraction: f64) -> Value {
+        let (progress, eta_ms) = self.progress(phase, fraction);
+        json!({ "progress": progress, "phase": phase, "etaMs": eta_ms })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn build(success: bool, media_ms: u64, html_ms: u64, zip_ms: u64) -> BuildRecord {
+        BuildRecord {
+            timestamp: Utc::now(),
+            settings_hash: String::new(),
+            settings: Value::Null,
+            content_hash: None,
+            build_id: None,
+            duration_ms: media_ms + html_ms + zip_ms,
+            output_size: 0,
+            success,
+            warnings: 0,
+            error: None,
+            phase_durations: Some(PhaseDurations {
+                media_ms,
+                html_ms,
+                zip_ms,
+            }),
+            local_times: None,
+        }
+    }
+
+    #[test]
+    fn test_without_timed_builds_uses_default_shares() {
+        let mut untimed = build(true, 0, 0, 0);
+        untimed.phase_durations = None;
+        let estimate = BuildEstimate::from_history(&[untimed]);
+
+        assert_eq!(estimate, BuildEstimate::default());
+        assert_eq!(estimate.progress(BuildPhase::Media, 0.5), (20, None));
+        assert_eq!(estimate.progress(BuildPhase::Zip, 0.0), (80, None));
+        assert_eq!(estimate.progress(BuildPhase::Zip, 1.0).0, 99);
+    }
+
+    #[test]
+    fn test_estimate_weights_phases_by_median_durations() {
+        let history = vec![
+            build(true, 1_000, 2_000, 1_000),
+            build(true, 3_000, 4_000, 1_000),
+            build(true, 2_000, 6_000, 2_000),
+            // Failed builds say nothing about how long a build takes
+            build(false, 50_000, 0, 0),
+        ];
+        let estimate = BuildEstimate::from_history(&history);
+
+        // Medians: media 2s, html 4s, zip 1s
+        assert_eq!(estimate.progress(BuildPhase::Media, 0.0), (0, Some(7_000)));
+        assert_eq!(estimate.progress(BuildPhase::Media, 0.5), (14, Some(6_000)));
+        assert_eq!(estimate.progress(BuildPhase::Html, 0.0), (29, Some(5_000)));
+        assert_eq!(estimate.progress(BuildPhase::Zip, 0.0), (86, Some(1_000)));
+
+        let event = estimate.event(BuildPhase::Html, 0.0);
+        assert_eq!(event["phase"], "html");
+        assert_eq!(event["etaMs"], 5_000);
+        assert_eq!(
+            PhaseDurations::from_build(10_000, 4_000, 1_000).media_ms,
+            5_000
+        );
+    }
+}
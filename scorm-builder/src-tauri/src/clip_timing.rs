@@ -0,0 +1,443 @@
+//! YouTube clip timing checked against the length of the video.
+//!
+//! `clip_start` and `clip_end` used to be stored as given, so a clip could
+//! end minutes after its video did and the player just stopped early. The
+//! length of each video is fetched once from the YouTube Data API with the
+//! key saved under API keys and cached in [`DURATION_CACHE_FILE`]; clips
+//! that start or end past it fail generation with the video's actual length.
+//! Storing or bulk editing media only checks against lengths already cached,
+//! since those commands run on the main thread. Without a key, or offline, a
+//! video's length is unknown and only the order of the two ends is checked.
+
+use crate::media_storage::MediaMetadata;
+use crate::scorm::generator_enhanced::{GenerateScormRequest, MediaItem};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use ts_rs::TS;
+use url::Url;
+
+/// Video lengths already fetched, by video id; kept in the app config directory
+pub const DURATION_CACHE_FILE: &str = "youtube-durations.json";
+
+const VIDEOS_ENDPOINT: &str = "https://www.googleapis.com/youtube/v3/videos";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+static CACHE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Looks up how long videos are, in seconds. `YouTubeDurations` is the real
+/// implementation; tests substitute a map.
+pub trait VideoDurations {
+    fn duration(&self, video_id: &str) -> Option<u32>;
+}
+
+#[cfg(test)]
+impl VideoDurations for HashMap<String, u32> {
+    fn duration(&self, video_id: &str) -> Option<u32> {
+        self.get(video_id).copied()
+    }
+}
+
+/// Lengths from the cache, or fetched from the YouTube Data API. Blocking;
+/// call it off the async runtime.
+pub struct YouTubeDurations {
+    api_key: Option<String>,
+    cache_path: Option<PathBuf>,
+}
+
+impl YouTubeDurations {
+    pub fn current() -> Self {
+        let api_key = crate::api_keys::load_api_keys()
+            .ok()
+            .map(|keys| keys.youtube_api_key)
+            .filter(|key| !key.trim().is_empty());
+        Self { api_key, ..Self::cached() }
+    }
+
+    /// Lengths already in the cache, never fetched; for synchronous commands,
+    /// which must not wait on the network
+    pub fn cached() -> Self {
+        let cache_path = crate::settings::get_app_config_dir()
+            .ok()
+            .map(|dir| dir.join(DURATION_CACHE_FILE));
+        Self { api_key: None, cache_path }
+    }
+
+    fn fetch(&self, video_id: &str) -> Result<Option<u32>, String> {
+        let Some(api_key) = &self.api_key else {
+            return Ok(None);
+        };
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        let body = client
+            .get(VIDEOS_ENDPOINT)
+            .query(&[("part", "contentDetails"), ("id", video_id), ("key", api_key)])
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .map_err(|e| format!("YouTube request failed: {e}"))?;
+        let response: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| format!("Unexpected YouTube response: {e}"))?;
+        Ok(response
+            .pointer("/items/0/contentDetails/duration")
+            .and_then(|duration| duration.as_str())
+            .and_then(parse_iso_duration))
+    }
+}
+
+fn load_cache(path: &Path) -> HashMap<String, u32> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+impl VideoDurations for YouTubeDurations {
+    fn duration(&self, video_id: &str) -> Option<u32> {
+        let cached = self
+            .cache_path
+            .as_deref()
+            .and_then(|path| load_cache(path).get(video_id).copied());
+        if cached.is_some() {
+            return cached;
+        }
+        let duration = match self.fetch(video_id) {
+            Ok(duration) => duration?,
+            Err(e) => {
+                eprintln!("[clip_timing] Length of {video_id} unknown: {e}");
+                return None;
+            }
+        };
+        if let Some(path) = &self.cache_path {
+            let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let mut cache = load_cache(path);
+            cache.insert(video_id.to_string(), duration);
+            let tmp_path = path.with_extension("json.tmp");
+            let written = serde_json::to_string_pretty(&cache)
+                .map_err(|e| e.to_string())
+                .and_then(|json| fs::write(&tmp_path, json).map_err(|e| e.to_string()))
+                .and_then(|_| fs::rename(&tmp_path, path).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                eprintln!("[clip_timing] Failed to cache the length of {video_id}: {e}");
+            }
+        }
+        Some(duration)
+    }
+}
+
+/// Seconds in an ISO 8601 duration as the API reports it, e.g. `PT1H2M3S`
+pub fn parse_iso_duration(duration: &str) -> Option<u32> {
+    let rest = duration.strip_prefix('P')?;
+    let (days, time) = rest.split_once('T').unwrap_or((rest, ""));
+    let mut seconds = 0u32;
+    for (part, units) in [(days, &[('D', 86_400)][..]), (time, &[('H', 3600), ('M', 60), ('S', 1)][..])] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let (_, unit) = units.iter().find(|(designator, _)| *designator == c)?;
+            seconds = seconds.checked_add(number.parse::<u32>().ok()?.checked_mul(*unit)?)?;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some(seconds)
+}
+
+/// The id of a YouTube video from its watch, short, embed or Shorts URL
+pub fn youtube_video_id(url: &str) -> Option<String> {
+    let url = Url::parse(url.trim()).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.").trim_start_matches("m.");
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    let id = match host {
+        "youtu.be" => segments.next()?.to_string(),
+        "youtube.com" | "youtube-nocookie.com" => match segments.next()? {
+            "watch" => url
+                .query_pairs()
+                .find(|(key, _)| key == "v")
+                .map(|(_, value)| value.into_owned())?,
+            "embed" | "shorts" | "v" | "live" => segments.next()?.to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let valid = id.len() == 11
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(id)
+}
+
+/// Why a clip can't be played as set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ClipProblem {
+    /// The end is at or before the start
+    EndsBeforeStart,
+    /// The start is at or past the end of the video
+    StartsAfterVideo,
+    /// The end is past the end of the video
+    EndsAfterVideo,
+}
+
+/// A clip whose timing doesn't fit its video
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct ClipTimingIssue {
+    /// Page the media is on, prefixed with the language of a variant
+    pub page_id: String,
+    pub media_id: String,
+    pub video_id: Option<String>,
+    pub clip_start: Option<u32>,
+    pub clip_end: Option<u32>,
+    /// Length of the video in seconds, when known
+    pub duration: Option<u32>,
+    pub problem: ClipProblem,
+}
+
+/// `seconds` as m:ss, or h:mm:ss from an hour
+fn clock(seconds: u32) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+impl ClipTimingIssue {
+    pub fn message(&self) -> String {
+        let start = clock(self.clip_start.unwrap_or(0));
+        let length = self.duration.map(clock).unwrap_or_default();
+        match self.problem {
+            ClipProblem::EndsBeforeStart => format!(
+                "clip starts at {start} but ends at {}",
+                clock(self.clip_end.unwrap_or(0))
+            ),
+            ClipProblem::StartsAfterVideo => {
+                format!("clip starts at {start} but the video is only {length} long")
+            }
+            ClipProblem::EndsAfterVideo => format!(
+                "clip ends at {} but the video is only {length} long",
+                clock(self.clip_end.unwrap_or(0))
+            ),
+        }
+    }
+}
+
+/// A media item with clip timing, wherever it is in a course or project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clip {
+    pub page_id: String,
+    pub media_id: String,
+    /// Embed or watch URL of the video
+    pub url: Option<String>,
+    pub start: Option<u32>,
+    pub end: Option<u32>,
+}
+
+impl Clip {
+    fn of_metadata(media_id: &str, metadata: &MediaMetadata) -> Self {
+        Self {
+            page_id: metadata.page_id.clone(),
+            media_id: media_id.to_string(),
+            url: metadata.embed_url.clone(),
+            start: metadata.clip_start,
+            end: metadata.clip_end,
+        }
+    }
+
+    fn of_item(page_id: &str, item: &MediaItem) -> Self {
+        Self {
+            page_id: page_id.to_string(),
+            media_id: item.id.clone(),
+            url: Some(item.embed_url.clone().unwrap_or_else(|| item.url.clone())),
+            start: item.clip_start,
+            end: item.clip_end,
+        }
+    }
+
+    /// What is wrong with the clip, if anything
+    pub fn check(&self, durations: &dyn VideoDurations) -> Option<ClipTimingIssue> {
+        if self.start.is_none() && self.end.is_none() {
+            return None;
+        }
+        let video_id = self.url.as_deref().and_then(youtube_video_id);
+        let issue = |problem, duration| ClipTimingIssue {
+            page_id: self.page_id.clone(),
+            media_id: self.media_id.clone(),
+            video_id: video_id.clone(),
+            clip_start: self.start,
+            clip_end: self.end,
+            duration,
+            problem,
+        };
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            if start >= end {
+                return Some(issue(ClipProblem::EndsBeforeStart, None));
+            }
+        }
+        let duration = durations.duration(video_id.as_deref()?)?;
+        if self.start.is_some_and(|start| start >= duration) {
+            return Some(issue(ClipProblem::StartsAfterVideo, Some(duration)));
+        }
+        if self.end.is_some_and(|end| end > duration) {
+            return Some(issue(ClipProblem::EndsAfterVideo, Some(duration)));
+        }
+        None
+    }
+}
+
+/// Check the clip of stored media metadata, as `store_media` and bulk edits do
+pub fn check_metadata_clip(
+    media_id: &str,
+    metadata: &MediaMetadata,
+    durations: &dyn VideoDurations,
+) -> Result<(), String> {
+    match Clip::of_metadata(media_id, metadata).check(durations) {
+        Some(issue) => Err(issue.message()),
+        None => Ok(()),
+    }
+}
+
+fn course_clips(request: &GenerateScormRequest, prefix: &str, clips: &mut Vec<Clip>) {
+    let mut pages: Vec<(String, Option<&Vec<MediaItem>>)> = Vec::new();
+    if let Some(welcome) = &request.welcome_page {
+        pages.push((format!("{prefix}welcome"), welcome.media.as_ref()));
+    }
+    if let Some(objectives) = &request.learning_objectives_page {
+        pages.push((format!("{prefix}objectives"), objectives.media.as_ref()));
+    }
+    for topic in &request.topics {
+        pages.push((format!("{prefix}{}", topic.id), topic.media.as_ref()));
+    }
+    for (page_id, items) in pages {
+        clips.extend(
+            items
+                .into_iter()
+                .flatten()
+                .filter(|item| item.clip_start.is_some() || item.clip_end.is_some())
+                .map(|item| Clip::of_item(&page_id, item)),
+        );
+    }
+}
+
+/// Clips of a course and its language variants
+pub fn request_clips(request: &GenerateScormRequest) -> Vec<Clip> {
+    let mut clips = Vec::new();
+    course_clips(request, "", &mut clips);
+    for variant in request.language_variants.iter().flatten() {
+        course_clips(&variant.course, &format!("{}/", variant.language), &mut clips);
+    }
+    clips
+}
+
+/// The clips that don't fit their video
+pub fn check_clips(clips: &[Clip], durations: &dyn VideoDurations) -> Vec<ClipTimingIssue> {
+    clips.iter().filter_map(|clip| clip.check(durations)).collect()
+}
+
+/// Clips of a project's stored media that don't fit their video
+#[tauri::command]
+pub async fn check_clip_timing(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<Vec<ClipTimingIssue>, String> {
+    let clips: Vec<Clip> = crate::media_storage::get_all_project_media_metadata(projectId)?
+        .iter()
+        .map(|item| Clip::of_metadata(&item.id, &item.metadata))
+        .collect();
+    tokio::task::spawn_blocking(move || check_clips(&clips, &YouTubeDurations::current()))
+        .await
+        .map_err(|e| format!("Clip timing check failed: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::Topic;
+
+    fn durations() -> HashMap<String, u32> {
+        HashMap::from([("dQw4w9WgXcQ".to_string(), 213)])
+    }
+
+    #[test]
+    fn test_video_ids_and_lengths_are_read() {
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=30",
+            "https://youtu.be/dQw4w9WgXcQ",
+            "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ?start=10",
+            "https://m.youtube.com/shorts/dQw4w9WgXcQ",
+        ] {
+            assert_eq!(youtube_video_id(url).as_deref(), Some("dQw4w9WgXcQ"), "{url}");
+        }
+        assert_eq!(youtube_video_id("https://vimeo.com/123456"), None);
+        assert_eq!(youtube_video_id("https://www.youtube.com/watch?v=short"), None);
+
+        assert_eq!(parse_iso_duration("PT3M33S"), Some(213));
+        assert_eq!(parse_iso_duration("PT1H2S"), Some(3602));
+        assert_eq!(parse_iso_duration("P1DT1M"), Some(86_460));
+        assert_eq!(parse_iso_duration("PT3X"), None);
+    }
+
+    #[test]
+    fn test_clips_past_the_video_are_reported_with_its_length() {
+        let clip = |url: &str, start, end| Clip {
+            page_id: "topic-0".to_string(),
+            media_id: "video-1".to_string(),
+            url: Some(url.to_string()),
+            start,
+            end,
+        };
+        let check = |start, end| {
+            clip("https://www.youtube.com/embed/dQw4w9WgXcQ", start, end).check(&durations())
+        };
+
+        assert_eq!(check(Some(30), Some(213)), None);
+        assert_eq!(check(None, None), None);
+        let past_end = check(Some(30), Some(250)).unwrap();
+        assert_eq!(past_end.problem, ClipProblem::EndsAfterVideo);
+        assert_eq!(past_end.duration, Some(213));
+        assert_eq!(past_end.message(), "clip ends at 4:10 but the video is only 3:33 long");
+        assert_eq!(check(Some(213), None).unwrap().problem, ClipProblem::StartsAfterVideo);
+        assert_eq!(check(Some(90), Some(60)).unwrap().problem, ClipProblem::EndsBeforeStart);
+        // Videos of unknown length only have the order of the ends checked
+        let unknown = clip("https://youtu.be/aaaaaaaaaaa", Some(30), Some(9000));
+        assert_eq!(unknown.check(&durations()), None);
+
+        let item: MediaItem = serde_json::from_value(serde_json::json!({
+            "id": "video-1",
+            "type": "youtube",
+            "url": "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "title": "Intro",
+            "clip_start": 200,
+            "clip_end": 400
+        }))
+        .unwrap();
+        let request = GenerateScormRequest {
+            topics: vec![Topic {
+                id: "topic-3".to_string(),
+                media: Some(vec![item]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let clips = request_clips(&request);
+        assert_eq!(clips.len(), 1);
+        let issues = check_clips(&clips, &durations());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].page_id, "topic-3");
+        assert_eq!(issues[0].video_id.as_deref(), Some("dQw4w9WgXcQ"));
+    }
+}
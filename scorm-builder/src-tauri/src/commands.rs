@@ -1,11 +1,25 @@
+use super::app_services::AppServices;
+use super::build_history;
+use super::build_progress::{BuildEstimate, BuildPhase, PhaseDurations};
+use super::content_hash;
+use super::disk_space::with_free_space;
 use super::project_storage::{self, ProjectFile};
 use super::scorm::generator::{GenerateScormRequest, ScormGenerationResult};
+use super::scorm::generator_enhanced::GenerationSummary;
+use super::scorm::media_transform::{FfmpegTranscoder, MediaTranscoder};
+use super::scorm::mixed_content::HttpUrlFetcher;
+use super::scorm::package_inspector::{inspect_package, IssueSeverity};
+use super::scorm::package_version::PackageBuild;
+use super::scorm::render_diagnostics::GenerationError;
+use super::scorm::source_snapshot::SourceSnapshot;
+use super::messages::Message;
+use super::project_operations::{begin_operation, OperationKind, ProjectOperations};
 use super::settings;
 use crate::commands_secure::log_debug;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use tauri::{command, Emitter};
+use tauri::{command, Emitter, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MediaFile {
@@ -21,12 +35,23 @@ pub struct MediaFile {
 // }
 
 #[command]
-pub fn create_project(name: String) -> Result<project_storage::ProjectMetadata, String> {
-    use chrono::Utc;
+pub fn create_project(
+    name: String,
+    services: State<'_, AppServices>,
+) -> Result<project_storage::ProjectMetadata, String> {
+    create_project_with_services(name, &services)
+}
+
+/// Creates a project using the given clock and ID generator
+pub fn create_project_with_services(
+    name: String,
+    services: &AppServices,
+) -> Result<project_storage::ProjectMetadata, String> {
     use std::fs;
 
     // Generate project ID
-    let project_id = format!("{}", Utc::now().timestamp_millis());
+    let project_id = services.new_project_id();
+    let created = services.now();
 
     // Get projects directory
     let projects_dir = project_storage::get_projects_directory()?;
@@ -60,9 +85,15 @@ pub fn create_project(name: String) -> Result<project_storage::ProjectMetadata,
     let project_metadata = project_storage::ProjectMetadata {
         id: project_id.clone(),
         name: name.clone(),
-        created: Utc::now(),
-        last_modified: Utc::now(),
+        created,
+        last_modified: created,
         path: Some(project_file_path.to_string_lossy().to_string()),
+        content_layout: None,
+        encryption: None,
+        is_writable: None,
+        tags: Vec::new(),
+        status: None,
+        local_times: None,
     };
 
     // Create course seed data with the project name
@@ -103,6 +134,8 @@ pub fn create_project(name: String) -> Result<project_storage::ProjectMetadata,
             version: "SCORM_2004".to_string(),
             completion_criteria: "all".to_string(),
             passing_score: 80,
+            media_transforms: None,
+            zip_compression_level: None,
         },
         // Initialize course_seed_data with the project name
         course_seed_data: Some(course_seed_data),
@@ -177,18 +210,148 @@ pub async fn generate_scorm_enhanced(
     project_id: String,
     media_files: Option<Vec<MediaFile>>,
     extension_map: Option<HashMap<String, String>>,
-) -> Result<Vec<u8>, String> {
+    debug_build: Option<bool>,
+    services: State<'_, AppServices>,
+    operations: State<'_, ProjectOperations>,
+) -> Result<Vec<u8>, Message> {
+    // Held until the build is recorded; a second build of the project is turned away
+    let _operation =
+        begin_operation(&operations, &services, &project_id, OperationKind::Generate)?;
+    let timestamp = services.now();
+    let started = std::time::Instant::now();
+    let settings = build_settings_for(&course_data, &project_id, debug_build);
+    let content_hash = build_content_hash_for(&course_data, &project_id);
+    let package_build = PackageBuild::new(&project_id, &content_hash, timestamp);
+    let build_id = package_build.build_id.clone();
+
+    let outcome = build_enhanced_package(
+        &app,
+        course_data,
+        &project_id,
+        media_files,
+        extension_map,
+        debug_build,
+        package_build,
+    )
+    .await;
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let record = build_history::BuildRecord {
+        timestamp,
+        settings_hash: build_history::settings_hash(&settings),
+        settings,
+        content_hash: Some(content_hash),
+        build_id: outcome.is_ok().then_some(build_id),
+        duration_ms,
+        output_size: outcome.as_ref().map_or(0, |(zip, _)| zip.len() as u64),
+        success: outcome.is_ok(),
+        warnings: outcome.as_ref().map_or(0, |(zip, summary)| build_warnings(zip, summary)),
+        error: outcome.as_ref().err().map(|e| e.to_string()),
+        phase_durations: outcome.as_ref().ok().map(|(_, summary)| {
+            PhaseDurations::from_build(duration_ms, summary.html_ms, summary.zip_ms)
+        }),
+        local_times: None,
+    };
+    // History is informational; a failure to write it must not fail the build
+    let recorded = project_storage::get_projects_directory()
+        .and_then(|dir| build_history::record_build(&dir, &project_id, record));
+    if let Err(e) = recorded {
+        eprintln!("[generate_scorm_enhanced] Failed to record build history: {e}");
+    }
+
+    outcome.map(|(zip, _)| zip)
+}
+
+/// Settings recorded in the build history for a `generate_scorm_enhanced` call
+fn build_settings_for(
+    course_data: &serde_json::Value,
+    project_id: &str,
+    debug_build: Option<bool>,
+) -> serde_json::Value {
+    let mut settings =
+        build_history::build_settings(course_data, project_scorm_config(project_id).as_ref());
+    if debug_build.unwrap_or(false) {
+        settings["debug_build"] = serde_json::Value::Bool(true);
+    }
+    settings
+}
+
+/// Hash of the course and the project's media folder as it is on disk
+fn build_content_hash_for(course_data: &serde_json::Value, project_id: &str) -> String {
+    let manifest = project_storage::get_projects_directory()
+        .map(|dir| content_hash::media_manifest(&dir, project_id))
+        .unwrap_or_default();
+    build_history::build_content_hash(course_data, &manifest)
+}
+
+/// The last successful build of this course if nothing changed since, so
+/// callers building many projects can skip it and report it as up to date
+#[command]
+pub fn check_build_up_to_date(
+    course_data: serde_json::Value,
+    project_id: String,
+    debug_build: Option<bool>,
+) -> Result<Option<build_history::BuildRecord>, String> {
+    let projects_dir = project_storage::get_projects_directory()?;
+    let history = build_history::load_build_history(&projects_dir, &project_id)?;
+    let settings = build_settings_for(&course_data, &project_id, debug_build);
+    let content_hash = build_content_hash_for(&course_data, &project_id);
+    Ok(build_history::up_to_date_build(
+        &history,
+        &build_history::settings_hash(&settings),
+        &content_hash,
+    )
+    .cloned())
+}
+
+/// Failed media transforms, missing media, content lint issues and the warnings the package
+/// inspector finds in the ZIP
+fn build_warnings(zip: &[u8], summary: &GenerationSummary) -> usize {
+    let inspector_warnings = inspect_package(zip)
+        .map(|inspection| {
+            inspection
+                .issues
+                .iter()
+                .filter(|issue| issue.severity == IssueSeverity::Warning)
+                .count()
+        })
+        .unwrap_or(0);
+    summary.failed_media_transforms()
+        + summary.missing_media.len()
+        + summary.content_lint.len()
+        + summary.unresolved_insecure_urls()
+        + inspector_warnings
+}
+
+/// Body of `generate_scorm_enhanced`: parse the course, gather media and build the ZIP
+async fn build_enhanced_package(
+    app: &tauri::AppHandle,
+    course_data: serde_json::Value,
+    project_id: &str,
+    media_files: Option<Vec<MediaFile>>,
+    extension_map: Option<HashMap<String, String>>,
+    debug_build: Option<bool>,
+    package_build: PackageBuild,
+) -> Result<(Vec<u8>, GenerationSummary), Message> {
     use crate::scorm::generator_enhanced::{
         EnhancedScormGenerator, GenerateScormRequest as EnhancedRequest,
     };
 
+    // Progress is weighted, and time left estimated, from the project's earlier builds
+    let estimate = project_storage::get_projects_directory()
+        .and_then(|dir| build_history::load_build_history(&dir, project_id))
+        .map(|history| BuildEstimate::from_history(&history))
+        .unwrap_or_default();
+    // The package is built in memory and checked for room when it is saved (ensure_free_space);
+    // meanwhile the projects disk is reported, where saves fall back to
+    let space_dir = project_storage::get_projects_directory().unwrap_or_else(|_| std::env::temp_dir());
+
     // Emit progress event
     let _ = app.emit(
         "scorm-generation-progress",
-        serde_json::json!({
-            "message": "Parsing course data...",
-            "progress": 10
-        }),
+        Message::new("progress.scorm.parsing")
+            .progress_event(with_free_space(estimate.event(BuildPhase::Media, 0.0), &space_dir)),
     );
 
     // Debug: Log the incoming course data
@@ -202,16 +365,56 @@ pub async fn generate_scorm_enhanced(
     );
 
     // Convert the course data to our enhanced request format
-    let enhanced_request: EnhancedRequest =
+    let mut enhanced_request: EnhancedRequest =
         serde_json::from_value(course_data.clone()).map_err(|e| {
             eprintln!("[generate_scorm_enhanced] Failed to parse course data: {e}");
             eprintln!(
                 "[generate_scorm_enhanced] Course data structure: {}",
                 serde_json::to_string_pretty(&course_data).unwrap_or_default()
             );
-            format!("Failed to parse course data: {e}")
+            Message::new("error.scorm.parse_course").with("error", e)
         })?;
 
+    // Debug builds carry the diagnostics overlay
+    if debug_build.unwrap_or(false) {
+        enhanced_request.debug_build = Some(true);
+    }
+
+    // The encrypted project goes in as it is on disk, not as the frontend sent it
+    if enhanced_request.source_snapshot == Some(true) {
+        let passphrase = crate::scorm::source_snapshot::stored_passphrase(project_id)?
+            .ok_or_else(|| "Set a project snapshot passphrase before packaging the project".to_string())?;
+        let project_path = crate::media_usage::find_project_file(project_id)
+            .ok_or_else(|| "Project file not found for the project snapshot".to_string())?;
+        let project = crate::content_pages::read_project_for_export(&project_path)?;
+        let project = crate::project_encryption::decrypt_for_export(&project_path, project)?;
+        let snapshot = SourceSnapshot::seal(&project, &passphrase, package_build.built_at)?;
+        enhanced_request.source_snapshot_file = Some(snapshot.to_json()?);
+    }
+
+    // version.json and the manifest identify the build for support tickets
+    enhanced_request.package_build = Some(package_build);
+
+    // Clips that start or end past their video fail the build with its length
+    let clips = crate::clip_timing::request_clips(&enhanced_request);
+    if !clips.is_empty() {
+        let issues = tokio::task::spawn_blocking(move || {
+            crate::clip_timing::check_clips(&clips, &crate::clip_timing::YouTubeDurations::current())
+        })
+        .await
+        .map_err(|e| Message::new("error.scorm.generate").with("error", e))?;
+        if !issues.is_empty() {
+            let clips = issues
+                .iter()
+                .map(|issue| format!("{} on {}: {}", issue.media_id, issue.page_id, issue.message()))
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(Message::new("error.scorm.clip_timing")
+                .with("count", issues.len())
+                .with("clips", clips));
+        }
+    }
+
     // Debug: Log knowledge check data
     eprintln!(
         "[generate_scorm_enhanced] Enhanced request has {} topics",
@@ -236,10 +439,8 @@ pub async fn generate_scorm_enhanced(
     // Emit progress event
     let _ = app.emit(
         "scorm-generation-progress",
-        serde_json::json!({
-            "message": "Processing media files...",
-            "progress": 30
-        }),
+        Message::new("progress.scorm.processing_media")
+            .progress_event(with_free_space(estimate.event(BuildPhase::Media, 0.0), &space_dir)),
     );
 
     // Use provided media files or load from disk
@@ -262,10 +463,9 @@ pub async fn generate_scorm_enhanced(
 
             let _ = app.emit(
                 "scorm-generation-progress",
-                serde_json::json!({
-                    "message": format!("Processing {} binary files...", files.len()),
-                    "progress": 40
-                }),
+                Message::new("progress.scorm.processing_binary")
+                    .with("count", files.len())
+                    .progress_event(with_free_space(estimate.event(BuildPhase::Media, 0.0), &space_dir)),
             );
 
             // Convert Vec<MediaFile> to HashMap<String, Vec<u8>>
@@ -273,11 +473,7 @@ pub async fn generate_scorm_enhanced(
             let total_files = files.len();
             for (idx, file) in files.into_iter().enumerate() {
                 // Ensure media files are prefixed with media/ directory
-                let path = if file.filename.starts_with("media/") {
-                    file.filename.clone()
-                } else {
-                    format!("media/{}", file.filename)
-                };
+                let path = media_package_path(&file.filename);
                 eprintln!(
                     "[generate_scorm_enhanced] Adding media file: {} (size: {} bytes)",
                     path,
@@ -287,11 +483,14 @@ pub async fn generate_scorm_enhanced(
 
                 // Emit progress for media processing
                 if idx % 5 == 0 || idx == total_files - 1 {
-                    let progress = 40 + ((idx as f32 / total_files as f32) * 20.0) as u32;
-                    let _ = app.emit("scorm-generation-progress", serde_json::json!({
-                    "message": format!("Processing media file {}/{}...", idx + 1, total_files),
-                    "progress": progress
-                }));
+                    let fraction = (idx + 1) as f64 / total_files as f64;
+                    let _ = app.emit(
+                        "scorm-generation-progress",
+                        Message::new("progress.scorm.processing_media_file")
+                            .with("current", idx + 1)
+                            .with("total", total_files)
+                            .progress_event(with_free_space(estimate.event(BuildPhase::Media, fraction), &space_dir)),
+                    );
                 }
             }
             map
@@ -300,7 +499,7 @@ pub async fn generate_scorm_enhanced(
             eprintln!("[generate_scorm_enhanced] ⚠️  No media files provided from TypeScript - falling back to disk loading");
             eprintln!("[generate_scorm_enhanced] 📁 Searching for media files in project directory: {}/media/", project_id);
             
-            let disk_files = load_project_media_files(&project_id).await?;
+            let disk_files = load_project_media_files(project_id).await?;
             eprintln!("[generate_scorm_enhanced] 💾 Found {} media files on disk", disk_files.len());
             
             if disk_files.len() > 0 {
@@ -318,22 +517,43 @@ pub async fn generate_scorm_enhanced(
     // Emit progress event
     let _ = app.emit(
         "scorm-generation-progress",
-        serde_json::json!({
-            "message": "Generating HTML content...",
-            "progress": 70
-        }),
+        Message::new("progress.scorm.generating_html")
+            .progress_event(with_free_space(estimate.event(BuildPhase::Html, 0.0), &space_dir)),
     );
 
+    // Project-level export settings; media rules only touch the copies written into the package
+    let scorm_config = project_scorm_config(project_id);
+    let media_transforms = scorm_config
+        .as_ref()
+        .and_then(|config| config.media_transforms.clone())
+        .filter(|spec| !spec.is_empty());
+    let compression_level = scorm_config.and_then(|config| config.zip_compression_level);
+
     // Create the generator inside async context
-    let generator = EnhancedScormGenerator::new()?;
+    let page_app = app.clone();
+    let page_space_dir = space_dir.clone();
+    let generator = EnhancedScormGenerator::new()?
+        .with_compression_level(compression_level)
+        .with_url_fetcher(Box::new(HttpUrlFetcher))
+        .with_page_progress(move |written, total| {
+            let fraction = written as f64 / total as f64;
+            let _ = page_app.emit(
+                "scorm-generation-progress",
+                Message::new("progress.scorm.rendering_page")
+                    .with("current", written)
+                    .with("total", total)
+                    .progress_event(with_free_space(
+                        estimate.event(BuildPhase::Html, fraction),
+                        &page_space_dir,
+                    )),
+            );
+        });
 
     // Emit progress event
     let _ = app.emit(
         "scorm-generation-progress",
-        serde_json::json!({
-            "message": "Creating SCORM package...",
-            "progress": 80
-        }),
+        Message::new("progress.scorm.creating_package")
+            .progress_event(with_free_space(estimate.event(BuildPhase::Html, 0.0), &space_dir)),
     );
 
     // Log extension map if provided
@@ -347,27 +567,269 @@ pub async fn generate_scorm_enhanced(
     }
 
     // Generate the SCORM package (synchronous)
-    let result = generator.generate_scorm_package(enhanced_request, media_files_map, extension_map)?;
+    if media_transforms.is_some() {
+        let _ = app.emit(
+            "scorm-generation-progress",
+            Message::new("progress.scorm.transforming_media")
+                .progress_event(with_free_space(estimate.event(BuildPhase::Html, 0.0), &space_dir)),
+        );
+    }
+    // Media transforms and http URL checks block, so keep them off the async workers
+    let (result, summary) = tokio::task::block_in_place(|| {
+        generator.generate_scorm_package_with_summary(
+            enhanced_request,
+            media_files_map,
+            extension_map,
+            media_transforms
+                .as_ref()
+                .map(|spec| (spec, &FfmpegTranscoder as &dyn MediaTranscoder)),
+        )
+    })
+    .map_err(|e| match e {
+        GenerationError::Render(errors) => {
+            let _ = app.emit("scorm-render-errors", &errors);
+            let pages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            Message::new("error.scorm.render")
+                .with("count", errors.len())
+                .with("error", pages.join("\n"))
+        }
+        GenerationError::Failed(e) => Message::new("error.scorm.generate").with("error", e),
+    })?;
+
+    if let Some(report) = &summary.media_transforms {
+        let _ = app.emit("scorm-media-transform-report", report);
+    }
+    // Reports on the finished package, sent with the progress it ended at
+    let report_fields = |name: &str, report: serde_json::Value| {
+        let mut fields = estimate.event(BuildPhase::Zip, 1.0);
+        fields[name] = report;
+        fields
+    };
+    if !summary.missing_media.is_empty() {
+        let _ = app.emit(
+            "scorm-generation-progress",
+            Message::new("progress.scorm.missing_media")
+                .with("count", summary.missing_media.len())
+                .progress_event(report_fields(
+                    "missingMedia",
+                    serde_json::json!(summary.missing_media),
+                )),
+        );
+    }
+    if !summary.insecure_urls.is_empty() {
+        let _ = app.emit("scorm-insecure-url-report", &summary.insecure_urls);
+    }
+    if summary.unresolved_insecure_urls() > 0 {
+        let _ = app.emit(
+            "scorm-generation-progress",
+            Message::new("progress.scorm.insecure_urls")
+                .with("count", summary.unresolved_insecure_urls())
+                .progress_event(report_fields(
+                    "insecureUrls",
+                    serde_json::json!(summary.insecure_urls),
+                )),
+        );
+    }
+    let (unnamed, unlicensed): (Vec<_>, Vec<_>) = summary
+        .content_lint
+        .iter()
+        .partition(|issue| issue.rule.is_accessibility());
+    for (key, issues) in [
+        ("progress.scorm.content_lint", unnamed),
+        ("progress.scorm.missing_license", unlicensed),
+    ] {
+        if issues.is_empty() {
+            continue;
+        }
+        let _ = app.emit(
+            "scorm-generation-progress",
+            Message::new(key)
+                .with("count", issues.len())
+                .progress_event(report_fields("contentLint", serde_json::json!(issues))),
+        );
+    }
+    eprintln!(
+        "[generate_scorm_enhanced] Stored {} already-compressed files ({} bytes), saving ~{} ms of compression",
+        summary.compression.stored_files,
+        summary.compression.stored_bytes,
+        summary.compression.estimated_time_saved_ms
+    );
+    let _ = app.emit("scorm-generation-summary", &summary);
 
     // Emit final progress event
     let _ = app.emit(
         "scorm-generation-progress",
-        serde_json::json!({
-            "message": "Finalizing package...",
-            "progress": 95
-        }),
+        Message::new("progress.scorm.finalizing")
+            .progress_event(with_free_space(estimate.event(BuildPhase::Zip, 1.0), &space_dir)),
     );
 
     // Emit 100% completion event
     let _ = app.emit(
         "scorm-generation-progress",
-        serde_json::json!({
-            "message": "SCORM package generated successfully!",
-            "progress": 100
-        }),
+        Message::new("progress.scorm.done")
+            .progress_event(serde_json::json!({ "progress": 100, "etaMs": 0 })),
     );
 
-    Ok(result)
+    Ok((result, summary))
+}
+
+/// Dry run of `generate_scorm_enhanced`: renders the package but returns the file
+/// list, sizes and page HTML instead of a ZIP
+#[command]
+pub async fn generate_scorm_preview(
+    course_data: serde_json::Value,
+    project_id: String,
+    media_files: Option<Vec<MediaFile>>,
+    extension_map: Option<HashMap<String, String>>,
+) -> Result<crate::scorm::package_sink::ScormPreview, String> {
+    use crate::scorm::generator_enhanced::{
+        EnhancedScormGenerator, GenerateScormRequest as EnhancedRequest,
+    };
+
+    let request: EnhancedRequest = serde_json::from_value(course_data)
+        .map_err(|e| format!("Failed to parse course data: {e}"))?;
+
+    let media_files_map = match media_files {
+        Some(files) => files
+            .into_iter()
+            .map(|file| (media_package_path(&file.filename), file.content))
+            .collect(),
+        None => load_project_media_files(&project_id).await?,
+    };
+
+    let generator = EnhancedScormGenerator::new()?;
+    generator.generate_scorm_preview(request, media_files_map, extension_map)
+}
+
+/// HTML of one page exactly as the package renders it, for the editor's preview
+/// pane. Media resolves to the project's stored files through the asset protocol
+/// unless `media_base_url` says otherwise. Broken template overrides in dev mode
+/// fall back to the built-in template; `template-dev-changed` reports them.
+#[command]
+pub async fn render_page_preview(
+    course_data: serde_json::Value,
+    project_id: String,
+    page_id: String,
+    extension_map: Option<HashMap<String, String>>,
+    media_base_url: Option<String>,
+) -> Result<String, String> {
+    use crate::scorm::generator_enhanced::{
+        EnhancedScormGenerator, GenerateScormRequest as EnhancedRequest,
+    };
+    use crate::scorm::html_generator_enhanced::HtmlGenerator;
+    use crate::scorm::page_preview::default_media_base_url;
+    use crate::scorm::template_dev;
+
+    let request: EnhancedRequest = serde_json::from_value(course_data)
+        .map_err(|e| format!("Failed to parse course data: {e}"))?;
+    let media_base_url = media_base_url.unwrap_or_else(|| default_media_base_url(&project_id));
+
+    let mut generator = EnhancedScormGenerator::new()?;
+    // In template dev mode, render with the templates being worked on
+    if template_dev::is_enabled() {
+        let (html_generator, _) = HtmlGenerator::with_template_dir(&template_dev::template_override_dir()?)?;
+        generator = generator.with_html_generator(html_generator);
+    }
+    generator.render_page_preview(request, &page_id, extension_map, &media_base_url)
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplatePartialInfo {
+    pub name: String,
+    /// Where an override lives, relative to the app config directory
+    pub override_path: String,
+    pub default_source: String,
+}
+
+/// Partials shared by the generated pages, for the template override system
+#[command]
+pub fn list_template_partials() -> Vec<TemplatePartialInfo> {
+    crate::scorm::html_generator_enhanced::TEMPLATE_PARTIALS
+        .iter()
+        .map(|(name, source)| TemplatePartialInfo {
+            name: name.to_string(),
+            override_path: format!("templates/partials/{name}.hbs"),
+            default_source: source.to_string(),
+        })
+        .collect()
+}
+
+/// Inspect a SCORM ZIP on disk, ours or a vendor's: manifest, SCOs, launch
+/// files, SCORM version, size by type and LMS compatibility problems
+#[command]
+pub async fn inspect_scorm_package(
+    path: String,
+) -> Result<crate::scorm::package_inspector::PackageInspection, String> {
+    let data = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read package {path}: {e}"))?;
+    crate::scorm::package_inspector::inspect_package(&data)
+}
+
+/// Readable progress report from a learner's cmi.suspend_data, pasted from the
+/// LMS when troubleshooting a completion dispute
+#[command]
+pub fn decode_suspend_data(
+    data: String,
+) -> Result<crate::scorm::suspend_data::SuspendDataReport, String> {
+    crate::scorm::suspend_data::decode_suspend_data(&data)
+}
+
+/// Restore the project snapshot packaged in a SCORM ZIP as a project in the
+/// projects folder, returning its path. Refuses to replace a project that is
+/// still there, so the original is never overwritten by an older build.
+#[command]
+pub async fn extract_source_snapshot(
+    package_path: String,
+    passphrase: String,
+) -> Result<String, String> {
+    let data = tokio::fs::read(&package_path)
+        .await
+        .map_err(|e| format!("Failed to read package {package_path}: {e}"))?;
+    let snapshot = SourceSnapshot::from_package(&data)?
+        .ok_or_else(|| "The package has no project snapshot".to_string())?;
+    let project = snapshot.open(&passphrase)?;
+    if !crate::backup_recovery::is_project_id(&snapshot.project_id) {
+        return Err("The project snapshot has no valid project id".to_string());
+    }
+
+    if let Some(existing) = crate::media_usage::find_project_file(&snapshot.project_id) {
+        return Err(format!(
+            "Project {} is still at {}; remove it first to restore the snapshot",
+            snapshot.project_id,
+            existing.display()
+        ));
+    }
+    let projects_dir = project_storage::get_projects_directory()?;
+    let file_name = format!(
+        "{}_{}.scormproj",
+        crate::file_names::portable_name(&snapshot.project_name),
+        snapshot.project_id
+    );
+    let project_path = projects_dir.join(file_name);
+    let json = serde_json::to_vec_pretty(&project)
+        .map_err(|e| format!("Failed to serialize project: {e}"))?;
+    tokio::fs::write(&project_path, json)
+        .await
+        .map_err(|e| format!("Failed to write project file: {e}"))?;
+    Ok(project_path.to_string_lossy().to_string())
+}
+
+/// The project's SCORM export settings, if its project file can be read
+fn project_scorm_config(project_id: &str) -> Option<project_storage::ScormConfig> {
+    let project_path = crate::media_usage::find_project_file(project_id)?;
+    project_storage::load_project_file(&project_path)
+        .ok()
+        .map(|project| project.scorm_config)
+}
+
+/// Media files live under media/ inside the package
+fn media_package_path(filename: &str) -> String {
+    if filename.starts_with("media/") {
+        filename.to_string()
+    } else {
+        format!("media/{filename}")
+    }
 }
 
 async fn load_project_media_files(project_id: &str) -> Result<HashMap<String, Vec<u8>>, String> {
@@ -391,13 +853,16 @@ async fn load_project_media_files(project_id: &str) -> Result<HashMap<String, Ve
         {
             let path = entry.path();
             if path.is_file() {
-                let file_name = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .ok_or_else(|| "Invalid file name".to_string())?;
+                // Pages can only reference UTF-8 names, so others are never course media
+                let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                    println!(
+                        "[load_project_media_files] Skipping {}: file name is not UTF-8",
+                        path.display()
+                    );
+                    continue;
+                };
 
-                let content = fs::read(&path)
-                    .await
+                let content = crate::project_encryption::read_media_file(&path)
                     .map_err(|e| format!("Failed to read file {file_name}: {e}"))?;
 
                 media_files.insert(format!("media/{file_name}"), content);
@@ -444,9 +909,10 @@ mod tests {
         settings.projects_directory = Some(temp_dir.path().to_string_lossy().to_string());
         settings::save_settings(&settings).unwrap();
 
-        // Create a new project
+        // Create a new project with deterministic IDs and timestamps
+        let (_clock, services) = crate::app_services::testing::deterministic_services();
         let project_name = "Test Course";
-        let result = create_project(project_name.to_string());
+        let result = create_project_with_services(project_name.to_string(), &services);
 
         // Clean up settings before assertions
         settings.projects_directory = None;
@@ -454,6 +920,13 @@ mod tests {
 
         assert!(result.is_ok(), "Project creation should succeed");
         let metadata = result.unwrap();
+        assert_eq!(metadata.id, "1700000000000");
+        assert_eq!(metadata.created.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        assert!(metadata
+            .path
+            .as_deref()
+            .unwrap()
+            .ends_with("Test_Course_1700000000000.scormproj"));
 
         // Load the created project file to check data consistency
         let project_file_path = PathBuf::from(metadata.path.unwrap());
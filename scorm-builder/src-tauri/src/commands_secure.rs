@@ -5,6 +5,12 @@ use crate::project_storage::{
     delete_project_file, get_projects_directory, list_project_files, load_project_file,
     save_project_file, ProjectFile, ProjectMetadata,
 };
+use crate::content_pages::ContentLayout;
+use crate::app_services::AppServices;
+use crate::instance_lock;
+use crate::project_access::{self, ProjectAccess};
+use crate::project_index::ProjectFilter;
+use crate::project_listing;
 use crate::scorm::{manifest, package};
 use chrono::Local;
 use once_cell::sync::OnceCell;
@@ -13,7 +19,7 @@ use std::fs::OpenOptions;
 use std::io::Write;
 use std::net::IpAddr;
 use std::path::PathBuf;
-use tauri::Emitter;
+use tauri::{Emitter, State};
 use url::Url;
 
 // Global AppHandle for logging to frontend
@@ -157,33 +163,8 @@ fn validate_image_url(url_str: &str) -> Result<Url, String> {
 
     // Block private IP ranges
     if let Ok(ip) = host.parse::<IpAddr>() {
-        if ip.is_loopback() {
-            return Err("Access to loopback addresses is not allowed".to_string());
-        }
-
-        // Check for private IP ranges manually
-        match ip {
-            IpAddr::V4(ipv4) => {
-                let octets = ipv4.octets();
-                // Check for private IPv4 ranges
-                if (octets[0] == 10) || // 10.0.0.0/8
-                   (octets[0] == 172 && octets[1] >= 16 && octets[1] <= 31) || // 172.16.0.0/12
-                   (octets[0] == 192 && octets[1] == 168) || // 192.168.0.0/16
-                   (octets[0] == 169 && octets[1] == 254)
-                // 169.254.0.0/16 (link-local)
-                {
-                    return Err("Access to private IP addresses is not allowed".to_string());
-                }
-            }
-            IpAddr::V6(ipv6) => {
-                // Check for IPv6 private/link-local ranges
-                if ipv6.segments()[0] & 0xfe00 == 0xfc00 || // Unique local addresses
-                   ipv6.segments()[0] & 0xffc0 == 0xfe80
-                // Link-local addresses
-                {
-                    return Err("Access to private IP addresses is not allowed".to_string());
-                }
-            }
+        if !crate::external_media::is_public_ip(ip) {
+            return Err("Access to private IP addresses is not allowed".to_string());
         }
     }
 
@@ -262,7 +243,7 @@ fn validate_package_output_path(path_str: &str) -> Result<PathBuf, String> {
 // Project Storage Commands with Security
 
 #[tauri::command]
-pub async fn save_project(project_data: ProjectFile, file_path: String) -> Result<(), String> {
+pub async fn save_project(mut project_data: ProjectFile, file_path: String) -> Result<(), String> {
     log_debug(&format!("save_project called with path: {file_path}"));
 
     // Log what we're saving
@@ -275,6 +256,12 @@ pub async fn save_project(project_data: ProjectFile, file_path: String) -> Resul
     }
 
     let path = validate_project_path(&file_path)?;
+    // Encryption is only turned off with its passphrase, never by a save without its settings
+    if project_data.project.encryption.is_none() && path.exists() {
+        project_data.project.encryption = crate::project_listing::read_project_header(&path)
+            .ok()
+            .and_then(|header| header.encryption);
+    }
     save_project_file(&project_data, &path)?;
 
     log_debug("Project saved successfully");
@@ -282,11 +269,22 @@ pub async fn save_project(project_data: ProjectFile, file_path: String) -> Resul
 }
 
 #[tauri::command]
-pub async fn load_project(file_path: String) -> Result<ProjectFile, String> {
+pub async fn load_project(
+    file_path: String,
+    services: State<'_, AppServices>,
+) -> Result<ProjectFile, String> {
     log_debug(&format!("load_project called with path: {file_path}"));
 
     let path = validate_project_path(&file_path)?;
-    let project = load_project_file(&path)?;
+    let mut project = load_project_file(&path)?;
+
+    // Check write access and take the instance lock up front, so a read-only share
+    // or a second app instance fails on open rather than mid-save
+    let writable = project_access::open_for_editing(&path, services.now())?;
+    project.project.is_writable = Some(writable);
+    if !writable {
+        log_debug(&format!("Project is not writable, opened read-only: {file_path}"));
+    }
 
     // Extract project ID for any future needs
     let _project_id = extract_project_id(&file_path);
@@ -308,6 +306,65 @@ pub async fn load_project(file_path: String) -> Result<ProjectFile, String> {
     Ok(project)
 }
 
+/// Open a project for viewing only; every command that would change it fails
+/// until the project is closed with `close_project`
+#[tauri::command]
+pub async fn open_project_readonly(file_path: String) -> Result<ProjectFile, String> {
+    log_debug(&format!("open_project_readonly called with path: {file_path}"));
+
+    let path = validate_project_path(&file_path)?;
+    let mut project = load_project_file(&path)?;
+    instance_lock::release(&path);
+    project_access::set_access(&path, ProjectAccess::ReadOnlyMode);
+    project.project.is_writable = Some(false);
+    Ok(project)
+}
+
+/// Open a project another instance has open, taking its lock. The other
+/// instance's next save fails instead of overwriting changes made here.
+#[tauri::command]
+pub async fn take_over_project(
+    file_path: String,
+    services: State<'_, AppServices>,
+) -> Result<ProjectFile, String> {
+    log_debug(&format!("take_over_project called with path: {file_path}"));
+
+    let path = validate_project_path(&file_path)?;
+    if !project_access::is_writable(&path) {
+        return Err("Project folder is not writable, so the project can only be opened read-only".to_string());
+    }
+    let mut project = load_project_file(&path)?;
+    instance_lock::take_over(&path, services.now())?;
+    project_access::set_access(&path, ProjectAccess::Writable);
+    project.project.is_writable = Some(true);
+    Ok(project)
+}
+
+/// Close a project in this instance so other instances can open it for editing
+#[tauri::command]
+pub async fn close_project(file_path: String) -> Result<(), String> {
+    log_debug(&format!("close_project called with path: {file_path}"));
+
+    let path = validate_project_path(&file_path)?;
+    project_access::close(&path);
+    Ok(())
+}
+
+/// Switch how a project's course content is stored: inline in the project file
+/// ("single") or as one file per page next to it ("split")
+#[tauri::command]
+pub async fn set_content_layout(file_path: String, layout: ContentLayout) -> Result<(), String> {
+    log_debug(&format!("set_content_layout called with path: {file_path}, layout: {layout:?}"));
+
+    let path = validate_project_path(&file_path)?;
+    let mut project = load_project_file(&path)?;
+    if layout == ContentLayout::Split && project.project.encryption.is_some() {
+        return Err("Encrypted projects keep their pages inline".to_string());
+    }
+    project.project.content_layout = Some(layout);
+    save_project_file(&project, &path)
+}
+
 #[tauri::command]
 pub async fn export_project_data(project_path: String) -> Result<serde_json::Value, String> {
     log_debug(&format!("export_project_data called with path: {project_path}"));
@@ -450,43 +507,20 @@ fn extract_project_id(project_path: &str) -> String {
     }
 }
 
+/// Projects with their tags and status, optionally only those with a tag or status
 #[tauri::command]
-pub async fn list_projects() -> Result<Vec<ProjectMetadata>, String> {
+pub async fn list_projects(filter: Option<ProjectFilter>) -> Result<Vec<ProjectMetadata>, String> {
     log_debug("list_projects called");
     eprintln!("[RUST] 🔍 list_projects command invoked");
 
-    let files = list_project_files()?;
-    eprintln!("[RUST] 📁 Found {} project files to process", files.len());
-    let mut projects = Vec::new();
-
-    for path in files {
-        log_debug(&format!("Processing project file: {}", path.display()));
-
-        match load_project_file(&path) {
-            Ok(project_file) => {
-                // Return only the metadata with the file path included
-                let mut metadata = project_file.project.clone();
-                metadata.path = Some(path.to_string_lossy().to_string());
-
-                log_debug(&format!(
-                    "Loaded project: id={}, name='{}', path='{}'",
-                    metadata.id,
-                    metadata.name,
-                    metadata.path.as_ref().unwrap_or(&String::from("unknown"))
-                ));
-
-                projects.push(metadata);
-            }
-            Err(e) => {
-                log_debug(&format!(
-                    "Failed to load project file '{}': {}",
-                    path.display(),
-                    e
-                ));
-                // Continue processing other files even if one fails
-            }
-        }
-    }
+    let filter = filter.unwrap_or_default();
+    let projects_dir = get_projects_directory()?;
+    // Only project headers are read, in parallel and cached by modification time
+    let projects = tokio::task::spawn_blocking(move || {
+        project_listing::list_project_headers(&projects_dir, &filter, &|_: project_listing::ProjectListUpdate| {})
+    })
+    .await
+    .map_err(|e| format!("Project listing failed: {e}"))??;
 
     log_debug(&format!("Returning {} projects", projects.len()));
     eprintln!("[RUST] ✅ Returning {} projects to frontend", projects.len());
@@ -503,7 +537,9 @@ pub async fn list_projects() -> Result<Vec<ProjectMetadata>, String> {
 #[tauri::command]
 pub async fn delete_project(file_path: String) -> Result<(), String> {
     let path = validate_project_path(&file_path)?;
-    delete_project_file(&path)
+    delete_project_file(&path)?;
+    project_access::close(&path);
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize)]
@@ -522,20 +558,20 @@ pub async fn check_project_exists(project_name: String) -> Result<ProjectExistsR
 
     // Check each project for matching name
     for path in files {
-        match load_project_file(&path) {
-            Ok(project_file) => {
+        match project_listing::read_project_header(&path) {
+            Ok(project) => {
                 // Compare project names (case-insensitive)
-                if project_file.project.name.to_lowercase() == project_name.to_lowercase() {
+                if project.name.to_lowercase() == project_name.to_lowercase() {
                     log_debug(&format!(
                         "Found existing project: id={}, name='{}', path='{}'",
-                        project_file.project.id,
-                        project_file.project.name,
+                        project.id,
+                        project.name,
                         path.display()
                     ));
 
                     return Ok(ProjectExistsResult {
                         exists: true,
-                        project_id: Some(project_file.project.id),
+                        project_id: Some(project.id),
                         project_path: Some(path.to_string_lossy().to_string()),
                     });
                 }
@@ -622,7 +658,11 @@ pub async fn append_to_log(content: String) -> Result<(), String> {
 // Project Rename Command
 
 #[tauri::command]
-pub async fn rename_project(file_path: String, new_name: String) -> Result<ProjectMetadata, String> {
+pub async fn rename_project(
+    file_path: String,
+    new_name: String,
+    services: State<'_, AppServices>,
+) -> Result<ProjectMetadata, String> {
     log_debug(&format!("rename_project called with path: {file_path}, new_name: {new_name}"));
     
     // Validate the new name
@@ -636,6 +676,7 @@ pub async fn rename_project(file_path: String, new_name: String) -> Result<Proje
     
     // Validate the project path
     let old_path = validate_project_path(&file_path)?;
+    project_access::ensure_writable(&old_path)?;
     
     // Load the project to update its metadata
     let mut project = load_project_file(&old_path)?;
@@ -676,6 +717,13 @@ pub async fn rename_project(file_path: String, new_name: String) -> Result<Proje
             log_debug(&format!("Warning: Could not delete old file: {e}"));
             // Not critical - continue anyway
         }
+
+        // The instance lock follows the project to its new name
+        let was_open = project_access::access(&old_path) == Some(ProjectAccess::Writable);
+        project_access::close(&old_path);
+        if was_open {
+            project_access::open_for_editing(&new_path, services.now())?;
+        }
     }
     
     // Update the path in the project metadata
@@ -688,14 +736,62 @@ pub async fn rename_project(file_path: String, new_name: String) -> Result<Proje
 
 // API Keys Commands
 
+/// Keys as seen by the webview. Only the search keys it uses directly are
+/// returned; the stock image keys are used by the backend and only reported
+/// as present or not.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebviewApiKeys {
+    pub google_image_api_key: String,
+    pub google_cse_id: String,
+    pub youtube_api_key: String,
+    pub has_unsplash_access_key: bool,
+    pub has_pexels_api_key: bool,
+}
+
+/// Keys sent by the settings page. A missing stock image key keeps the
+/// stored one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeysUpdate {
+    pub google_image_api_key: String,
+    pub google_cse_id: String,
+    pub youtube_api_key: String,
+    #[serde(default)]
+    pub unsplash_access_key: Option<String>,
+    #[serde(default)]
+    pub pexels_api_key: Option<String>,
+}
+
 #[tauri::command]
-pub async fn save_api_keys(api_keys: ApiKeys) -> Result<(), String> {
-    save_keys(api_keys)
+pub async fn save_api_keys(api_keys: ApiKeysUpdate) -> Result<(), String> {
+    let stored = load_keys().ok();
+    let keep = |update: Option<String>, current: Option<&String>| {
+        update.unwrap_or_else(|| current.cloned().unwrap_or_default())
+    };
+    save_keys(ApiKeys {
+        unsplash_access_key: keep(
+            api_keys.unsplash_access_key,
+            stored.as_ref().map(|keys| &keys.unsplash_access_key),
+        ),
+        pexels_api_key: keep(
+            api_keys.pexels_api_key,
+            stored.as_ref().map(|keys| &keys.pexels_api_key),
+        ),
+        google_image_api_key: api_keys.google_image_api_key,
+        google_cse_id: api_keys.google_cse_id,
+        youtube_api_key: api_keys.youtube_api_key,
+    })
 }
 
 #[tauri::command]
-pub async fn load_api_keys() -> Result<ApiKeys, String> {
-    load_keys()
+pub async fn load_api_keys() -> Result<WebviewApiKeys, String> {
+    let keys = load_keys()?;
+    Ok(WebviewApiKeys {
+        has_unsplash_access_key: !keys.unsplash_access_key.is_empty(),
+        has_pexels_api_key: !keys.pexels_api_key.is_empty(),
+        google_image_api_key: keys.google_image_api_key,
+        google_cse_id: keys.google_cse_id,
+        youtube_api_key: keys.youtube_api_key,
+    })
 }
 
 #[tauri::command]
@@ -703,7 +799,7 @@ pub async fn delete_api_keys() -> Result<(), String> {
     delete_keys()
 }
 
-// Image Download Command with Security
+// Downloaded media, as returned by download_external_media
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DownloadImageResponse {
@@ -711,203 +807,6 @@ pub struct DownloadImageResponse {
     pub content_type: String,
 }
 
-#[allow(dead_code)]
-#[tauri::command]
-pub async fn download_image(url: String) -> Result<DownloadImageResponse, String> {
-    // Validate URL first
-    let validated_url = validate_image_url(&url)?;
-
-    // Create a client with appropriate headers and limits
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-        .timeout(std::time::Duration::from_secs(30))
-        .redirect(reqwest::redirect::Policy::limited(3)) // Limit redirects
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
-
-    // Download the image
-    let response = client
-        .get(validated_url.as_str())
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch image: {e}"))?;
-
-    // Check status
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
-    }
-
-    // Get and verify content type
-    let content_type = response
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("application/octet-stream")
-        .to_string();
-
-    if !content_type.starts_with("image/") {
-        return Err(format!(
-            "Invalid content type: {content_type}. Only images are allowed"
-        ));
-    }
-
-    // Check content length (max 10MB)
-    const MAX_SIZE: u64 = 10 * 1024 * 1024;
-    if let Some(content_length) = response.content_length() {
-        if content_length > MAX_SIZE {
-            return Err(format!(
-                "Image too large: {content_length} bytes (max 10MB)"
-            ));
-        }
-    }
-
-    // Get the bytes with size limit
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read image data: {e}"))?;
-
-    // Double-check size after download
-    if bytes.len() > MAX_SIZE as usize {
-        return Err("Image too large: Maximum size is 10MB".to_string());
-    }
-
-    // Convert to base64
-    use base64::{engine::general_purpose, Engine as _};
-    let base64_data = general_purpose::STANDARD.encode(&bytes);
-
-    Ok(DownloadImageResponse {
-        base64_data,
-        content_type,
-    })
-}
-
-#[tauri::command]
-pub async fn unsafe_download_image(url: String) -> Result<DownloadImageResponse, String> {
-    log_debug(&format!("UNSAFE image download requested for: {}", url));
-    
-    // Parse URL without validation - allow any domain, HTTP/HTTPS
-    let parsed_url = Url::parse(&url).map_err(|e| format!("Invalid URL format: {e}"))?;
-    
-    // Create an extremely permissive HTTP client
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .timeout(std::time::Duration::from_secs(45)) // Longer timeout for slow corporate networks
-        .redirect(reqwest::redirect::Policy::limited(10)) // More redirects allowed
-        .danger_accept_invalid_certs(true) // Accept self-signed certs (corporate environments)
-        // Note: System proxy detection is automatic in most reqwest versions
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
-    
-    // Aggressive headers to mimic a real browser request
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("Accept", "image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8".parse().unwrap());
-    headers.insert("Accept-Language", "en-US,en;q=0.9".parse().unwrap());
-    headers.insert("Accept-Encoding", "gzip, deflate, br".parse().unwrap());
-    headers.insert("Sec-Fetch-Dest", "image".parse().unwrap());
-    headers.insert("Sec-Fetch-Mode", "no-cors".parse().unwrap());
-    headers.insert("Sec-Fetch-Site", "cross-site".parse().unwrap());
-    headers.insert("Cache-Control", "no-cache".parse().unwrap());
-    
-    // Add referrer if it's a different domain
-    if let Some(host) = parsed_url.host_str() {
-        let referer = format!("https://{}/", host);
-        if let Ok(referer_value) = referer.parse() {
-            headers.insert("Referer", referer_value);
-        }
-    }
-    
-    log_debug("Attempting unsafe image download with permissive client...");
-    
-    // Attempt download with aggressive settings
-    let response = client
-        .get(&url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch image (unsafe mode): {e}"))?;
-    
-    log_debug(&format!("Received response with status: {}", response.status()));
-    
-    // Accept any successful status code (2xx)
-    if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
-    }
-    
-    // Get content type - be more permissive
-    let content_type = response
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("image/png") // Default to image type
-        .to_string();
-    
-    // More permissive content type checking - accept anything that might be an image
-    let is_likely_image = content_type.starts_with("image/") 
-        || content_type.contains("octet-stream")
-        || content_type.is_empty()
-        || url.ends_with(".jpg") 
-        || url.ends_with(".jpeg") 
-        || url.ends_with(".png") 
-        || url.ends_with(".gif") 
-        || url.ends_with(".webp")
-        || url.ends_with(".svg");
-        
-    if !is_likely_image {
-        log_debug(&format!("Warning: Unusual content type '{}' - proceeding anyway", content_type));
-    }
-    
-    // More generous size limit for corporate environments (20MB)
-    const MAX_SIZE: u64 = 20 * 1024 * 1024;
-    if let Some(content_length) = response.content_length() {
-        if content_length > MAX_SIZE {
-            return Err(format!(
-                "Image too large: {} bytes (max 20MB in unsafe mode)", content_length
-            ));
-        }
-    }
-    
-    // Get the bytes
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read image data: {e}"))?;
-    
-    // Check size after download
-    if bytes.len() > MAX_SIZE as usize {
-        return Err("Image too large: Maximum size is 20MB in unsafe mode".to_string());
-    }
-    
-    // If content type wasn't image, try to detect from bytes
-    let final_content_type = if content_type.starts_with("image/") {
-        content_type
-    } else {
-        // Simple image format detection by magic bytes
-        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
-            "image/jpeg".to_string()
-        } else if bytes.starts_with(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]) {
-            "image/png".to_string()
-        } else if bytes.starts_with(&[0x47, 0x49, 0x46, 0x38]) {
-            "image/gif".to_string()
-        } else if bytes.starts_with(&[0x52, 0x49, 0x46, 0x46]) && bytes.len() > 12 && bytes[8..12] == [0x57, 0x45, 0x42, 0x50] {
-            "image/webp".to_string()
-        } else {
-            "image/png".to_string() // Default fallback
-        }
-    };
-    
-    // Convert to base64
-    use base64::{engine::general_purpose, Engine as _};
-    let base64_data = general_purpose::STANDARD.encode(&bytes);
-    
-    log_debug(&format!("Successfully downloaded {} bytes as {}", bytes.len(), final_content_type));
-    
-    Ok(DownloadImageResponse {
-        base64_data,
-        content_type: final_content_type,
-    })
-}
-
 // Diagnostic command for debugging project directory issues
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProjectDirectoryDiagnostics {
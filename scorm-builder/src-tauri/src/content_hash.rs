@@ -0,0 +1,194 @@
+//! Content hashes of a project (its file plus a manifest of its media folder)
+//! so backups, autosave drafts and builds can tell when nothing changed since
+//! their last run. The last hash of each artifact is kept per project in
+//! `{projects}/{id}/content-hashes.json`.
+
+use crate::backup_recovery::is_project_id;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const CONTENT_HASHES_FILE: &str = "content-hashes.json";
+
+/// What a backup or draft write did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArtifactStatus {
+    /// The artifact was (re)written
+    Written,
+    /// Nothing changed since the last run, so the existing artifact was kept
+    UpToDate,
+    /// There was nothing to write, e.g. the project file doesn't exist yet
+    Skipped,
+}
+
+/// JSON with object keys sorted, so equal values always hash the same
+pub(crate) fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| {
+                    format!(
+                        "{}:{}",
+                        Value::String(key.clone()),
+                        canonical_json(&map[key])
+                    )
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Name, size and modification time of every file in the project's media
+/// folder, sorted by name. Media files are not read, so this stays cheap for video.
+pub fn media_manifest(projects_dir: &Path, project_id: &str) -> Vec<Value> {
+    let media_dir = projects_dir.join(project_id).join("media");
+    let mut manifest: Vec<Value> = fs::read_dir(media_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_millis() as u64);
+            Some(serde_json::json!({
+                "name": entry.file_name().to_string_lossy(),
+                "size": metadata.len(),
+                "modified": modified,
+            }))
+        })
+        .collect();
+    manifest.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    manifest
+}
+
+/// Hash of project data plus its media manifest. `project.last_modified` is
+/// ignored because every save bumps it without changing the content.
+pub fn project_content_hash(project_data: &Value, media_manifest: &[Value]) -> String {
+    let mut project_data = project_data.clone();
+    if let Some(project) = project_data
+        .get_mut("project")
+        .and_then(Value::as_object_mut)
+    {
+        project.remove("last_modified");
+    }
+    let content = serde_json::json!({ "project": project_data, "media": media_manifest });
+    hash_hex(&canonical_json(&content))
+}
+
+/// First 16 hex digits of the SHA-256 of `text`
+pub(crate) fn hash_hex(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hashes_path(projects_dir: &Path, project_id: &str) -> Result<PathBuf, String> {
+    if !is_project_id(project_id) {
+        return Err(format!("Invalid project ID: {project_id}"));
+    }
+    Ok(projects_dir.join(project_id).join(CONTENT_HASHES_FILE))
+}
+
+/// Hash recorded for `artifact` (e.g. "backup") the last time it was written
+pub fn stored_hash(projects_dir: &Path, project_id: &str, artifact: &str) -> Option<String> {
+    let path = hashes_path(projects_dir, project_id).ok()?;
+    let content = fs::read_to_string(path).ok()?;
+    let hashes: Map<String, Value> = serde_json::from_str(&content).ok()?;
+    hashes.get(artifact)?.as_str().map(str::to_string)
+}
+
+/// Remember the hash `artifact` was last written with
+pub fn store_hash(
+    projects_dir: &Path,
+    project_id: &str,
+    artifact: &str,
+    hash: &str,
+) -> Result<(), String> {
+    let path = hashes_path(projects_dir, project_id)?;
+    let mut hashes: Map<String, Value> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    hashes.insert(artifact.to_string(), Value::String(hash.to_string()));
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create project directory: {e}"))?;
+    }
+    let json = serde_json::to_string_pretty(&hashes)
+        .map_err(|e| format!("Failed to serialize content hashes: {e}"))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write content hashes: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hash_ignores_last_modified_and_key_order() {
+        let a = json!({
+            "project": { "id": "42", "name": "Safety", "last_modified": "2024-01-01T00:00:00Z" },
+            "course_data": { "title": "Safety", "difficulty": 3 }
+        });
+        let b = json!({
+            "course_data": { "difficulty": 3, "title": "Safety" },
+            "project": { "last_modified": "2024-06-01T00:00:00Z", "name": "Safety", "id": "42" }
+        });
+        assert_eq!(project_content_hash(&a, &[]), project_content_hash(&b, &[]));
+
+        let renamed = json!({ "project": { "id": "42", "name": "Fire Safety" } });
+        assert_ne!(
+            project_content_hash(&a, &[]),
+            project_content_hash(&renamed, &[])
+        );
+    }
+
+    #[test]
+    fn test_media_changes_change_the_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let project = json!({ "project": { "id": "42" } });
+
+        let empty = project_content_hash(&project, &media_manifest(dir, "42"));
+
+        let media_dir = dir.join("42").join("media");
+        fs::create_dir_all(&media_dir).unwrap();
+        fs::write(media_dir.join("audio-0.bin"), b"abc").unwrap();
+        let manifest = media_manifest(dir, "42");
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest[0]["size"], 3);
+        assert_ne!(project_content_hash(&project, &manifest), empty);
+    }
+
+    #[test]
+    fn test_store_and_read_hashes() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        assert_eq!(stored_hash(dir, "42", "backup"), None);
+        store_hash(dir, "42", "backup", "aaaa").unwrap();
+        store_hash(dir, "42", "autosave", "bbbb").unwrap();
+        store_hash(dir, "42", "backup", "cccc").unwrap();
+
+        assert_eq!(stored_hash(dir, "42", "backup").as_deref(), Some("cccc"));
+        assert_eq!(stored_hash(dir, "42", "autosave").as_deref(), Some("bbbb"));
+        assert!(store_hash(dir, "../42", "backup", "aaaa").is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! Optional on-disk layout that keeps each page of `course_content` in its own
+//! JSON file, so saving a large course only rewrites the pages that changed.
+//!
+//! In the split layout the `.scormproj` holds an index: `course_content` with
+//! every page replaced by `{"$page": "<file>"}`, pointing into
+//! `{projects}/{id}/pages/`. Loading inlines the pages again, so the rest of
+//! the app only ever sees the single-blob form.
+
+use crate::backup_recovery::is_project_id;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+const PAGES_DIR: &str = "pages";
+
+/// Key of the reference object that stands in for a page in the index
+const PAGE_REF: &str = "$page";
+
+/// Single pages stored under their own name; topics are stored per topic
+const PAGE_KEYS: [&str; 3] = ["welcomePage", "learningObjectivesPage", "assessment"];
+
+/// How `course_content` is stored on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ContentLayout {
+    /// Everything inline in the `.scormproj` (the default)
+    Single,
+    /// One JSON file per page, with an index in the `.scormproj`
+    Split,
+}
+
+/// Folder holding the page files of the project saved at `project_path`.
+/// The ID in the file name wins over the one inside the file, matching the
+/// media folder of imported projects.
+pub fn pages_dir(project_path: &Path, project_id: &str) -> Result<PathBuf, String> {
+    let stem = project_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let id = match stem.rsplit_once('_') {
+        Some((_, id)) if is_project_id(id) => id,
+        _ if is_project_id(stem) => stem,
+        _ => project_id,
+    };
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid project ID: {id}"));
+    }
+    let parent = project_path.parent().unwrap_or(Path::new(""));
+    Ok(parent.join(id).join(PAGES_DIR))
+}
+
+/// Page files the index refers to are plain names inside the pages folder
+fn page_file_name(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{safe}.json")
+}
+
+fn page_ref(file: &str) -> Value {
+    json!({ PAGE_REF: file })
+}
+
+fn referenced_file(value: &Value) -> Option<&str> {
+    let map = value.as_object().filter(|map| map.len() == 1)?;
+    map.get(PAGE_REF)?.as_str()
+}
+
+/// Split `course_content` into an index and its page files. Topics are named
+/// after their ID so reordering them doesn't rename (and rewrite) every file.
+pub fn split_course_content(content: &Value) -> (Value, Vec<(String, Value)>) {
+    let Some(map) = content.as_object() else {
+        return (content.clone(), Vec::new());
+    };
+    let mut index = Map::new();
+    let mut pages = Vec::new();
+    let mut used = HashSet::new();
+
+    for (key, value) in map {
+        if PAGE_KEYS.contains(&key.as_str()) && value.is_object() {
+            let file = page_file_name(key);
+            used.insert(file.clone());
+            index.insert(key.clone(), page_ref(&file));
+            pages.push((file, value.clone()));
+        } else if key == "topics" && value.is_array() {
+            let topics = value.as_array().into_iter().flatten().enumerate();
+            let refs = topics
+                .map(|(position, topic)| {
+                    let id = topic.get("id").and_then(Value::as_str);
+                    let mut file = page_file_name(&format!(
+                        "topic-{}",
+                        id.map_or(position.to_string(), str::to_string)
+                    ));
+                    if !used.insert(file.clone()) {
+                        file = page_file_name(&format!("topic-{position}"));
+                        used.insert(file.clone());
+                    }
+                    pages.push((file.clone(), topic.clone()));
+                    page_ref(&file)
+                })
+                .collect();
+            index.insert(key.clone(), Value::Array(refs));
+        } else {
+            index.insert(key.clone(), value.clone());
+        }
+    }
+
+    (Value::Object(index), pages)
+}
+
+/// Whether `course_content` is an index whose pages live in separate files
+pub fn is_split(content: &Value) -> bool {
+    PAGE_KEYS
+        .iter()
+        .filter_map(|key| content.get(key))
+        .chain(
+            content
+                .get("topics")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten(),
+        )
+        .any(|value| referenced_file(value).is_some())
+}
+
+/// Replace every page reference in `content` with the page it points to
+pub fn inline_pages(content: &mut Value, pages_dir: &Path) -> Result<(), String> {
+    let load = |value: &mut Value| -> Result<(), String> {
+        if let Some(file) = referenced_file(value) {
+            if file.contains(['/', '\\']) || file.starts_with('.') {
+                return Err(format!("Invalid page file in project index: {file}"));
+            }
+            let text = fs::read_to_string(pages_dir.join(file))
+                .map_err(|e| format!("Failed to read page {file}: {e}"))?;
+            *value = serde_json::from_str(&text)
+                .map_err(|e| format!("Failed to parse page {file}: {e}"))?;
+        }
+        Ok(())
+    };
+
+    for key in PAGE_KEYS {
+        if let Some(value) = content.get_mut(key) {
+            load(value)?;
+        }
+    }
+    if let Some(topics) = content.get_mut("topics").and_then(Value::as_array_mut) {
+        for topic in topics {
+            load(topic)?;
+        }
+    }
+    Ok(())
+}
+
+/// Inline the pages of a raw project JSON saved at `project_path`, if it uses
+/// the split layout
+pub fn inline_project_pages(project_path: &Path, project: &mut Value) -> Result<(), String> {
+    let project_id = project
+        .pointer("/project/id")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    match project.get_mut("course_content") {
+        Some(content) if is_split(content) => {
+            inline_pages(content, &pages_dir(project_path, &project_id)?)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Write the page files, leaving files whose content is unchanged untouched.
+/// Returns how many files were written.
+pub fn write_pages(pages_dir: &Path, pages: &[(String, Value)]) -> Result<usize, String> {
+    fs::create_dir_all(pages_dir).map_err(|e| format!("Failed to create pages directory: {e}"))?;
+
+    let mut written = 0;
+    for (file, page) in pages {
+        let json = serde_json::to_string_pretty(page)
+            .map_err(|e| format!("Failed to serialize page {file}: {e}"))?;
+        let path = pages_dir.join(file);
+        if fs::read_to_string(&path).is_ok_and(|existing| existing == json) {
+            continue;
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, json).map_err(|e| format!("Failed to write page {file}: {e}"))?;
+        fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write page {file}: {e}"))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Delete page files the index no longer refers to. Runs after the index is
+/// saved so a crash mid-save never leaves the index pointing at missing pages.
+pub fn remove_stale_pages(pages_dir: &Path, pages: &[(String, Value)]) {
+    let keep: HashSet<&str> = pages.iter().map(|(file, _)| file.as_str()).collect();
+    for entry in fs::read_dir(pages_dir).into_iter().flatten().flatten() {
+        let name = entry.file_name();
+        if !keep.contains(name.to_string_lossy().as_ref()) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Project file contents for an export: the file as-is, or with its pages
+/// inlined when it uses the split layout, so the archive is self-contained
+pub fn read_project_for_export(project_path: &Path) -> Result<Vec<u8>, String> {
+    let raw = fs::read(project_path).map_err(|e| format!("Failed to read project file: {e}"))?;
+    let Ok(mut project) = serde_json::from_slice::<Value>(&raw) else {
+        return Ok(raw);
+    };
+    if !project.get("course_content").is_some_and(is_split) {
+        return Ok(raw);
+    }
+    inline_project_pages(project_path, &mut project)?;
+    serde_json::to_vec_pretty(&project).map_err(|e| format!("Failed to serialize project: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn course() -> Value {
+        json!({
+            "welcomePage": { "id": "welcome", "content": "<p>Hi</p>" },
+            "learningObjectivesPage": { "id": "objectives", "objectives": ["Stay safe"] },
+            "topics": [
+                { "id": "topic-1", "title": "Extinguishers" },
+                { "id": "topic-2", "title": "Exits" }
+            ],
+            "assessment": { "questions": [] },
+            "objectives": ["Stay safe"]
+        })
+    }
+
+    #[test]
+    fn test_split_and_inline_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("pages");
+
+        let (mut index, pages) = split_course_content(&course());
+        assert!(is_split(&index));
+        assert!(!is_split(&course()));
+        assert_eq!(index["topics"][1], json!({ "$page": "topic-topic-2.json" }));
+        assert_eq!(index["objectives"], json!(["Stay safe"]));
+        assert_eq!(pages.len(), 5);
+
+        write_pages(&dir, &pages).unwrap();
+        inline_pages(&mut index, &dir).unwrap();
+        assert_eq!(index, course());
+    }
+
+    #[test]
+    fn test_only_changed_pages_are_rewritten() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("pages");
+
+        let (_, pages) = split_course_content(&course());
+        assert_eq!(write_pages(&dir, &pages).unwrap(), 5);
+
+        let mut edited = course();
+        edited["topics"][0]["title"] = json!("Fire extinguishers");
+        edited["topics"].as_array_mut().unwrap().pop();
+        let (_, pages) = split_course_content(&edited);
+        assert_eq!(write_pages(&dir, &pages).unwrap(), 1);
+
+        remove_stale_pages(&dir, &pages);
+        assert!(dir.join("topic-topic-1.json").exists());
+        assert!(!dir.join("topic-topic-2.json").exists());
+    }
+
+    #[test]
+    fn test_pages_dir_and_unsafe_references() {
+        let path = Path::new("/projects/Fire_Safety_1234567890.scormproj");
+        assert_eq!(
+            pages_dir(path, "other").unwrap(),
+            Path::new("/projects/1234567890/pages")
+        );
+        assert!(pages_dir(Path::new("/projects/Untitled.scormproj"), "../x").is_err());
+
+        let mut content = json!({ "welcomePage": { "$page": "../secrets.json" } });
+        assert!(inline_pages(&mut content, Path::new("/projects/1/pages")).is_err());
+    }
+}
@@ -0,0 +1,417 @@
+//! Typed model of a project's `course_content`.
+//!
+//! The frontend owns the shape of this JSON (see `src/types/aiPrompt.ts`), so
+//! the structs here only name the fields the backend reads. Everything else is
+//! kept in each struct's `extra` map and written back untouched, which makes
+//! `from_value` followed by `to_value` lossless for content the app produced.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome_page: Option<ContentPage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub learning_objectives_page: Option<ContentPage>,
+    #[serde(default, deserialize_with = "pages_or_titles")]
+    pub topics: Vec<ContentPage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assessment: Option<Assessment>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The welcome page, the objectives page or a topic
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentPage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<Vec<MediaRef>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub knowledge_check: Option<KnowledgeCheck>,
+    /// Notes for the course team; never part of a generated package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_notes: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaRef {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub id: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeCheck {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub questions: Option<Vec<Question>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Question {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub question_type: Option<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub question: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct_answer: Option<Answer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    /// Notes for the course team; never part of a generated package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_notes: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The app stores the text of the correct option; older content used an
+/// option index, or a flag for true/false questions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Index(u64),
+    Flag(bool),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Assessment {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub questions: Vec<Question>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pass_mark: Option<u32>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Reads `null`, which older projects wrote for empty fields, as the default
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Early content listed topics by title alone
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PageOrTitle {
+    Page(ContentPage),
+    Title(String),
+}
+
+fn pages_or_titles<'de, D>(deserializer: D) -> Result<Vec<ContentPage>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let items: Vec<PageOrTitle> = null_as_default(deserializer)?;
+    Ok(items
+        .into_iter()
+        .map(|item| match item {
+            PageOrTitle::Page(page) => page,
+            PageOrTitle::Title(title) => ContentPage {
+                title: Some(title),
+                ..Default::default()
+            },
+        })
+        .collect())
+}
+
+impl CourseContent {
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        Self::deserialize(value).map_err(|e| format!("Failed to read course content: {e}"))
+    }
+
+    pub fn to_value(&self) -> Result<Value, String> {
+        serde_json::to_value(self).map_err(|e| format!("Failed to serialize course content: {e}"))
+    }
+
+    /// Welcome, objectives and topic pages, in course order
+    pub fn pages(&self) -> impl Iterator<Item = &ContentPage> {
+        self.welcome_page
+            .iter()
+            .chain(self.learning_objectives_page.iter())
+            .chain(self.topics.iter())
+    }
+
+    /// Every media ID a page points at: narration, captions and attached media
+    pub fn media_ids(&self) -> HashSet<String> {
+        let mut ids = HashSet::new();
+        for page in self.pages() {
+            ids.extend(page.audio_id.iter().cloned());
+            ids.extend(page.caption_id.iter().cloned());
+            ids.extend(page.media().iter().map(|media| media.id.clone()));
+        }
+        ids
+    }
+
+    /// Rejects content the generators can't make sense of
+    pub fn validate(&self) -> Result<(), String> {
+        let mut topic_ids = HashSet::new();
+        for (index, topic) in self.topics.iter().enumerate() {
+            if let Some(id) = &topic.id {
+                if !topic_ids.insert(id.as_str()) {
+                    return Err(format!("Topic {} reuses the ID '{id}'", index + 1));
+                }
+            }
+        }
+
+        for page in self.pages() {
+            let name = page.name();
+            if page.media().iter().any(|media| media.id.is_empty()) {
+                return Err(format!("Page '{name}' has media without an ID"));
+            }
+            if let Some(check) = &page.knowledge_check {
+                for question in check.all_questions() {
+                    question.validate(&format!("Knowledge check on '{name}'"))?;
+                }
+            }
+        }
+
+        if let Some(assessment) = &self.assessment {
+            if assessment.pass_mark.is_some_and(|mark| mark > 100) {
+                return Err("The assessment pass mark is over 100".to_string());
+            }
+            for question in &assessment.questions {
+                question.validate("Assessment")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ContentPage {
+    pub fn media(&self) -> &[MediaRef] {
+        self.media.as_deref().unwrap_or_default()
+    }
+
+    pub fn name(&self) -> &str {
+        self.title
+            .as_deref()
+            .or(self.id.as_deref())
+            .unwrap_or("untitled")
+    }
+}
+
+impl MediaRef {
+    pub fn kind(&self) -> &str {
+        self.media_type.as_deref().unwrap_or("")
+    }
+}
+
+impl KnowledgeCheck {
+    /// The check's questions, or the single question older content kept
+    /// inline on the knowledge check itself
+    pub fn all_questions(&self) -> Vec<Question> {
+        if self.questions.is_some() || !self.extra.contains_key("question") {
+            return self.questions.clone().unwrap_or_default();
+        }
+        Question::deserialize(Value::Object(self.extra.clone()))
+            .map(|question| vec![question])
+            .unwrap_or_default()
+    }
+}
+
+impl Question {
+    pub fn kind(&self) -> &str {
+        self.question_type.as_deref().unwrap_or("multiple-choice")
+    }
+
+    pub fn options(&self) -> &[String] {
+        self.options.as_deref().unwrap_or_default()
+    }
+
+    /// Index of the correct option, whichever way the answer was stored
+    pub fn correct_option(&self) -> Option<usize> {
+        match self.correct_answer.as_ref()? {
+            Answer::Index(index) => Some(*index as usize),
+            Answer::Text(text) => self.options().iter().position(|option| option == text),
+            Answer::Flag(_) => None,
+        }
+    }
+
+    /// The answer to a true/false question
+    pub fn correct_flag(&self) -> bool {
+        match &self.correct_answer {
+            Some(Answer::Flag(flag)) => *flag,
+            Some(Answer::Text(text)) => text.eq_ignore_ascii_case("true"),
+            _ => false,
+        }
+    }
+
+    fn validate(&self, context: &str) -> Result<(), String> {
+        if let Some(Answer::Index(index)) = self.correct_answer {
+            if self.kind() == "multiple-choice" && index as usize >= self.options().len() {
+                return Err(format!(
+                    "{context}: '{}' marks option {index} correct but has {} options",
+                    self.question,
+                    self.options().len()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the `course_content` of a serialized project file, if it has one
+pub fn validate_project_file(raw: &[u8]) -> Result<(), String> {
+    let project: Value =
+        serde_json::from_slice(raw).map_err(|e| format!("Failed to parse project file: {e}"))?;
+    match project.get("course_content") {
+        Some(content) if !content.is_null() => CourseContent::from_value(content)?.validate(),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "welcomePage": {
+                "id": "welcome",
+                "title": "Welcome",
+                "content": "<p>Hi</p>",
+                "narration": "Hello there",
+                "audioId": "audio-0",
+                "captionId": "caption-0"
+            },
+            "topics": [{
+                "id": "topic-0",
+                "title": "Safety",
+                "content": "<p>Be safe</p>",
+                "media": [{ "id": "image-0", "type": "image", "url": "media/image-0.jpg", "title": "Sign", "isYouTube": false }],
+                "knowledgeCheck": {
+                    "questions": [{ "id": "kc-1", "type": "true-false", "question": "Safe?", "correctAnswer": "true" }]
+                }
+            }],
+            "assessment": {
+                "questions": [{ "type": "multiple-choice", "question": "Pick", "options": ["A", "B"], "correctAnswer": 1 }],
+                "passMark": 80,
+                "narration": null
+            },
+            "objectives": ["Stay safe"]
+        })
+    }
+
+    #[test]
+    fn test_round_trip_keeps_unknown_fields() {
+        let value = sample();
+        let content = CourseContent::from_value(&value).unwrap();
+
+        assert_eq!(content.topics[0].media()[0].kind(), "image");
+        assert_eq!(content.assessment.as_ref().unwrap().pass_mark, Some(80));
+        assert_eq!(
+            content.welcome_page.as_ref().unwrap().extra["narration"],
+            "Hello there"
+        );
+        assert_eq!(content.to_value().unwrap(), value);
+    }
+
+    #[test]
+    fn test_media_ids_cover_every_page() {
+        let content = CourseContent::from_value(&sample()).unwrap();
+        let ids = content.media_ids();
+
+        assert_eq!(ids.len(), 3);
+        for id in ["audio-0", "caption-0", "image-0"] {
+            assert!(ids.contains(id), "missing {id}");
+        }
+
+        let legacy = ContentPage {
+            knowledge_check: Some(
+                serde_json::from_value(json!({ "question": "Inline?", "correctAnswer": "Yes" }))
+                    .unwrap(),
+            ),
+            ..Default::default()
+        };
+        let questions = legacy.knowledge_check.unwrap().all_questions();
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].question, "Inline?");
+    }
+
+    #[test]
+    fn test_validate_rejects_broken_content() {
+        assert!(CourseContent::from_value(&sample())
+            .unwrap()
+            .validate()
+            .is_ok());
+        assert!(CourseContent::from_value(&json!({ "topics": {} })).is_err());
+
+        let mut value = sample();
+        let topic = value["topics"][0].clone();
+        value["topics"].as_array_mut().unwrap().push(topic);
+        let error = CourseContent::from_value(&value)
+            .unwrap()
+            .validate()
+            .unwrap_err();
+        assert!(error.contains("reuses the ID 'topic-0'"));
+
+        let mut value = sample();
+        value["assessment"]["questions"][0]["correctAnswer"] = json!(4);
+        let error = CourseContent::from_value(&value)
+            .unwrap()
+            .validate()
+            .unwrap_err();
+        assert!(error.contains("marks option 4 correct"));
+
+        let mut value = sample();
+        value["topics"][0]["media"][0]["id"] = json!("");
+        assert!(validate_project_file(
+            &serde_json::to_vec(&json!({ "course_content": value })).unwrap()
+        )
+        .is_err());
+        assert!(validate_project_file(br#"{"course_content": null}"#).is_ok());
+    }
+
+    #[test]
+    fn test_reads_legacy_content() {
+        let content = CourseContent::from_value(&json!({
+            "topics": ["Introduction", { "title": "Safety", "media": [{ "id": null }] }],
+            "assessment": { "questions": null, "passMark": null }
+        }))
+        .unwrap();
+
+        assert_eq!(content.topics[0].name(), "Introduction");
+        assert_eq!(content.topics[1].media()[0].id, "");
+        assert!(content.assessment.unwrap().questions.is_empty());
+        assert!(validate_project_file(br#"{"course_content": {"topics": null}}"#).is_ok());
+    }
+}
@@ -0,0 +1,593 @@
+use crate::course_structure::{edit_topics, MediaChanges, TopicEdit};
+use crate::media_storage::get_media_directory;
+use crate::media_usage::find_project_file;
+use crate::project_storage::{load_project_file, save_project_file};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::path::Path;
+
+const OUTLINE_HEADER: &str = "\
+# Course outline. Rename, reorder or add topics and edit question stems, then import it back.
+# Keep the id of existing items. Leave the id out for new topics.
+";
+
+/// Structure of a course without page bodies, for bulk editing as YAML
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CourseOutline {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome: Option<OutlinePage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objectives_page: Option<OutlinePage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objectives: Vec<String>,
+    #[serde(default)]
+    pub topics: Vec<OutlineTopic>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assessment: Vec<OutlineQuestion>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutlinePage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutlineTopic {
+    /// Missing for topics added in the outline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub questions: Vec<OutlineQuestion>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutlineQuestion {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub question: String,
+}
+
+/// What importing an outline changed
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineMergeResult {
+    /// Page titles and question stems that changed
+    pub renamed: usize,
+    pub added_topics: Vec<String>,
+    pub reordered: bool,
+    pub objectives_updated: bool,
+    /// Parts of the outline that could not be applied as written
+    pub warnings: Vec<String>,
+    /// False for a dry run
+    pub applied: bool,
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(|v| v.as_str())
+}
+
+fn question_stem(question: &Value) -> &str {
+    str_field(question, "question")
+        .or_else(|| str_field(question, "text"))
+        .unwrap_or_default()
+}
+
+fn outline_page(page: &Value) -> OutlinePage {
+    OutlinePage {
+        id: str_field(page, "id").map(str::to_string),
+        title: str_field(page, "title").unwrap_or_default().to_string(),
+    }
+}
+
+fn outline_questions(questions: Option<&Value>) -> Vec<OutlineQuestion> {
+    questions
+        .and_then(|q| q.as_array())
+        .map(|questions| {
+            questions
+                .iter()
+                .map(|question| OutlineQuestion {
+                    id: str_field(question, "id").map(str::to_string),
+                    question: question_stem(question).to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Outline of `course_content`: titles, objectives and question stems only
+pub fn build_outline(course_content: &Value) -> CourseOutline {
+    let topics = course_content
+        .get("topics")
+        .and_then(|t| t.as_array())
+        .map(|topics| {
+            topics
+                .iter()
+                .map(|topic| OutlineTopic {
+                    id: str_field(topic, "id").map(str::to_string),
+                    title: str_field(topic, "title").unwrap_or_default().to_string(),
+                    questions: outline_questions(topic.pointer("/knowledgeCheck/questions")),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    CourseOutline {
+        welcome: course_content.get("welcomePage").map(outline_page),
+        objectives_page: course_content
+            .get("learningObjectivesPage")
+            .map(outline_page),
+        objectives: course_content
+            .get("objectives")
+            .and_then(|o| o.as_array())
+            .map(|o| {
+                o.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        topics,
+        assessment: outline_questions(course_content.pointer("/assessment/questions")),
+    }
+}
+
+pub fn outline_to_yaml(outline: &CourseOutline) -> Result<String, String> {
+    let yaml = serde_yaml::to_string(outline)
+        .map_err(|e| format!("Failed to serialize course outline: {}", e))?;
+    Ok(format!("{OUTLINE_HEADER}{yaml}"))
+}
+
+pub fn outline_from_yaml(yaml: &str) -> Result<CourseOutline, String> {
+    serde_yaml::from_str(yaml).map_err(|e| format!("Failed to parse course outline: {}", e))
+}
+
+fn set_title(page: &mut Value, title: &str, result: &mut OutlineMergeResult) {
+    if str_field(page, "title") != Some(title) {
+        page["title"] = json!(title);
+        result.renamed += 1;
+    }
+}
+
+/// Apply edited stems to `questions` by ID. Questions can't be created from a
+/// stem alone, so new ones are reported instead of added.
+fn merge_questions(
+    questions: Option<&mut Value>,
+    outline: &[OutlineQuestion],
+    context: &str,
+    result: &mut OutlineMergeResult,
+) {
+    let mut questions = questions.and_then(|q| q.as_array_mut());
+    for item in outline {
+        let Some(id) = &item.id else {
+            result.warnings.push(format!(
+                "{context}: new question \"{}\" needs answers and was not added; add it in the editor",
+                item.question
+            ));
+            continue;
+        };
+        let existing = questions
+            .as_deref_mut()
+            .and_then(|qs| qs.iter_mut().find(|q| str_field(q, "id") == Some(id)));
+        match existing {
+            Some(question) if question_stem(question) != item.question => {
+                let key = if question.get("question").is_none() && question.get("text").is_some() {
+                    "text"
+                } else {
+                    "question"
+                };
+                question[key] = json!(item.question);
+                result.renamed += 1;
+            }
+            Some(_) => {}
+            None => result.warnings.push(format!(
+                "{context}: question '{id}' no longer exists; its stem was not applied"
+            )),
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Topic with the same defaults the editor uses for topics created from a title
+pub(crate) fn new_topic(id: &str, title: &str) -> Value {
+    json!({
+        "id": id,
+        "title": title,
+        "content": format!("<p>Content for {}</p>", escape_html(title)),
+        "narration": "",
+        "imageKeywords": [],
+        "imagePrompts": [],
+        "videoSearchTerms": [],
+        "duration": 5
+    })
+}
+
+/// Where a topic goes in the merged course
+enum OutlineSlot {
+    Existing(String),
+    New(String),
+}
+
+fn topic_ids(course_content: &Value) -> Vec<Option<String>> {
+    course_content
+        .get("topics")
+        .and_then(|t| t.as_array())
+        .map(|topics| {
+            topics
+                .iter()
+                .map(|t| str_field(t, "id").map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Merge an edited outline into `course_content`. Topics are matched by ID,
+/// renamed and put in outline order; topics without an ID are added. Nothing is
+/// deleted: topics missing from the outline are kept at the end with a warning.
+///
+/// Moves and additions go through `edit_topics` one at a time, so the media in
+/// `media_dir` are renumbered with their topics. The caller must `commit` the
+/// returned changes once the project is saved, or `rollback` them. On error the
+/// media are already put back and `course_content` must be discarded.
+pub fn merge_outline(
+    course_content: &mut Value,
+    outline: &CourseOutline,
+    media_dir: &Path,
+) -> Result<(OutlineMergeResult, MediaChanges), String> {
+    let mut result = OutlineMergeResult::default();
+
+    for (key, page) in [
+        ("welcomePage", &outline.welcome),
+        ("learningObjectivesPage", &outline.objectives_page),
+    ] {
+        if let (Some(current), Some(page)) = (course_content.get_mut(key), page) {
+            set_title(current, &page.title, &mut result);
+        }
+    }
+
+    if !outline.objectives.is_empty()
+        && build_outline(course_content).objectives != outline.objectives
+    {
+        course_content["objectives"] = json!(outline.objectives);
+        result.objectives_updated = true;
+    }
+
+    // Titles and question stems first, in place
+    let original_order = topic_ids(course_content);
+    let mut seen = HashSet::new();
+    let mut slots = Vec::new();
+    for item in &outline.topics {
+        let existing = match &item.id {
+            Some(id) if !seen.insert(id.clone()) => {
+                result.warnings.push(format!(
+                    "Topic '{id}' appears more than once in the outline; only the first entry was used"
+                ));
+                continue;
+            }
+            Some(id) => course_content
+                .get_mut("topics")
+                .and_then(|t| t.as_array_mut())
+                .and_then(|topics| topics.iter_mut().find(|t| str_field(t, "id") == Some(id))),
+            None => None,
+        };
+
+        match (existing, &item.id) {
+            (Some(topic), Some(id)) => {
+                set_title(topic, &item.title, &mut result);
+                let context = format!("Topic '{}'", item.title);
+                merge_questions(
+                    topic.pointer_mut("/knowledgeCheck/questions"),
+                    &item.questions,
+                    &context,
+                    &mut result,
+                );
+                slots.push(OutlineSlot::Existing(id.clone()));
+            }
+            (_, id) => {
+                if let Some(id) = id {
+                    result.warnings.push(format!(
+                        "Topic '{id}' is not in the course; \"{}\" was added as a new topic",
+                        item.title
+                    ));
+                }
+                if !item.questions.is_empty() {
+                    result.warnings.push(format!(
+                        "Topic '{}': questions of new topics need answers and were not added",
+                        item.title
+                    ));
+                }
+                slots.push(OutlineSlot::New(item.title.clone()));
+            }
+        }
+    }
+
+    if let Some(topics) = course_content.get("topics").and_then(|t| t.as_array()) {
+        for topic in topics {
+            let listed = str_field(topic, "id").is_some_and(|id| seen.contains(id));
+            if !listed {
+                result.warnings.push(format!(
+                    "Topic \"{}\" is missing from the outline and was kept at the end",
+                    str_field(topic, "title").unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    // Then the structure, one topic edit per position, which leaves the topics
+    // missing from the outline after the ones it lists
+    let mut changes = MediaChanges::default();
+    for (position, slot) in slots.into_iter().enumerate() {
+        let edit = match slot {
+            OutlineSlot::New(title) => TopicEdit::Insert {
+                index: position,
+                title,
+            },
+            OutlineSlot::Existing(id) => {
+                let Some(from) = topic_ids(course_content)
+                    .iter()
+                    .position(|t| t.as_deref() == Some(id.as_str()))
+                else {
+                    changes.rollback();
+                    return Err(format!("Topic '{id}' disappeared during the merge"));
+                };
+                if from == position {
+                    continue;
+                }
+                TopicEdit::Move { from, to: position }
+            }
+        };
+        match edit_topics(course_content, media_dir, edit) {
+            Ok((edit_result, edit_changes)) => {
+                changes.extend(edit_changes);
+                result.added_topics.extend(edit_result.inserted_topic);
+            }
+            Err(e) => {
+                changes.rollback();
+                return Err(e);
+            }
+        }
+    }
+
+    let kept_order: Vec<Option<String>> = topic_ids(course_content)
+        .into_iter()
+        .filter(|id| original_order.contains(id))
+        .collect();
+    result.reordered = kept_order != original_order;
+
+    merge_questions(
+        course_content.pointer_mut("/assessment/questions"),
+        &outline.assessment,
+        "Assessment",
+        &mut result,
+    );
+
+    Ok((result, changes))
+}
+
+/// Export the course structure of a project as a YAML outline
+#[tauri::command]
+pub fn export_course_outline(#[allow(non_snake_case)] projectId: String) -> Result<String, String> {
+    let project_path =
+        find_project_file(&projectId).ok_or_else(|| "Project file not found".to_string())?;
+    let project = load_project_file(&project_path)?;
+    let course_content = project
+        .course_content
+        .ok_or_else(|| "Project has no course content yet".to_string())?;
+    outline_to_yaml(&build_outline(&course_content))
+}
+
+/// Merge an edited YAML outline back into a project's course content.
+/// With `dryRun` the result is reported without saving.
+#[tauri::command]
+pub fn import_course_outline(
+    #[allow(non_snake_case)] projectId: String,
+    outline: String,
+    #[allow(non_snake_case)] dryRun: Option<bool>,
+) -> Result<OutlineMergeResult, String> {
+    let outline = outline_from_yaml(&outline)?;
+    let project_path =
+        find_project_file(&projectId).ok_or_else(|| "Project file not found".to_string())?;
+    let mut project = load_project_file(&project_path)?;
+    let course_content = project
+        .course_content
+        .as_mut()
+        .ok_or_else(|| "Project has no course content yet".to_string())?;
+
+    if dryRun.unwrap_or(false) {
+        // Merge into a copy, renumbering media in an empty scratch directory
+        let scratch = tempfile::tempdir()
+            .map_err(|e| format!("Failed to create scratch directory: {}", e))?;
+        let (result, _) = merge_outline(&mut course_content.clone(), &outline, scratch.path())?;
+        return Ok(result);
+    }
+
+    crate::project_access::ensure_writable(&project_path)?;
+    let media_dir = get_media_directory(&projectId)?;
+    let (mut result, changes) = merge_outline(course_content, &outline, &media_dir)?;
+    if let Err(e) = save_project_file(&project, &project_path) {
+        changes.rollback();
+        return Err(e);
+    }
+    changes.commit();
+    result.applied = true;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sample_content() -> Value {
+        json!({
+            "welcomePage": { "id": "content-0", "title": "Welcome", "content": "<p>Hi</p>" },
+            "learningObjectivesPage": { "id": "content-1", "title": "Objectives" },
+            "objectives": ["Spot hazards"],
+            "topics": [
+                {
+                    "id": "topic-0",
+                    "title": "Hazards",
+                    "content": "<p>Body</p>",
+                    "knowledgeCheck": {
+                        "questions": [{ "id": "kc-1", "question": "What is a hazard?", "correctAnswer": "A" }]
+                    }
+                },
+                { "id": "topic-1", "title": "Controls", "content": "<p>Body</p>" }
+            ],
+            "assessment": {
+                "questions": [{ "id": "q-1", "question": "Pick one", "correctAnswer": "B" }],
+                "passMark": 80
+            }
+        })
+    }
+
+    #[test]
+    fn test_outline_leaves_out_bodies() {
+        let outline = build_outline(&sample_content());
+
+        assert_eq!(outline.welcome.as_ref().unwrap().title, "Welcome");
+        assert_eq!(outline.objectives, vec!["Spot hazards"]);
+        assert_eq!(outline.topics.len(), 2);
+        assert_eq!(outline.topics[0].questions[0].question, "What is a hazard?");
+        assert_eq!(outline.assessment[0].id.as_deref(), Some("q-1"));
+
+        let yaml = outline_to_yaml(&outline).unwrap();
+        assert!(yaml.starts_with("# Course outline"));
+        assert!(!yaml.contains("<p>"));
+        assert_eq!(outline_from_yaml(&yaml).unwrap(), outline);
+    }
+
+    #[test]
+    fn test_merge_reorders_renames_and_adds() {
+        let mut content = sample_content();
+        let mut outline = build_outline(&content);
+        outline.topics.swap(0, 1);
+        outline.topics[1].title = "Workplace hazards".to_string();
+        outline.topics[1].questions[0].question = "Which is a hazard?".to_string();
+        outline.topics.push(OutlineTopic {
+            id: None,
+            title: "Reporting".to_string(),
+            questions: vec![],
+        });
+        outline.assessment.push(OutlineQuestion {
+            id: None,
+            question: "New stem".to_string(),
+        });
+
+        let temp_dir = TempDir::new().unwrap();
+        let (result, changes) = merge_outline(&mut content, &outline, temp_dir.path()).unwrap();
+        changes.commit();
+
+        assert!(result.reordered);
+        assert_eq!(result.renamed, 2);
+        assert_eq!(result.added_topics.len(), 1);
+        assert!(crate::stable_ids::legacy_topic_index(&result.added_topics[0]).is_none());
+        assert_eq!(result.warnings.len(), 1, "{:?}", result.warnings);
+
+        let titles: Vec<_> = content["topics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, vec!["Controls", "Workplace hazards", "Reporting"]);
+        assert_eq!(content["topics"][1]["content"], "<p>Body</p>");
+        assert_eq!(
+            content["topics"][1]["knowledgeCheck"]["questions"][0]["question"],
+            "Which is a hazard?"
+        );
+        assert_eq!(
+            content["assessment"]["questions"].as_array().unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_topics_missing_from_outline() {
+        let mut content = sample_content();
+        let mut outline = build_outline(&content);
+        outline.topics.remove(0);
+        outline.topics.push(outline.topics[0].clone());
+
+        let temp_dir = TempDir::new().unwrap();
+        let (result, _) = merge_outline(&mut content, &outline, temp_dir.path()).unwrap();
+
+        assert_eq!(content["topics"].as_array().unwrap().len(), 2);
+        assert_eq!(content["topics"][1]["id"], "topic-0");
+        assert!(result.reordered);
+        assert_eq!(result.warnings.len(), 2, "{:?}", result.warnings);
+        assert!(result.warnings.iter().any(|w| w.contains("more than once")));
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("kept at the end")));
+    }
+
+    #[test]
+    fn test_merge_renumbers_media_with_topics() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        for (id, page_id) in [
+            ("audio-2", "topic-0"),
+            ("audio-3", "topic-1"),
+            ("image-7", "topic-1"),
+        ] {
+            fs::write(dir.join(format!("{id}.bin")), id).unwrap();
+            fs::write(
+                dir.join(format!("{id}.json")),
+                json!({ "page_id": page_id }).to_string(),
+            )
+            .unwrap();
+        }
+        let mut content = sample_content();
+        content["topics"][0]["audioId"] = json!("audio-2");
+        content["topics"][1]["audioId"] = json!("audio-3");
+        let mut outline = build_outline(&content);
+        outline.topics.insert(
+            0,
+            OutlineTopic {
+                id: None,
+                title: "Introduction".to_string(),
+                questions: vec![],
+            },
+        );
+        outline.topics.swap(1, 2);
+
+        let (result, changes) = merge_outline(&mut content, &outline, dir).unwrap();
+        changes.commit();
+
+        let ids: Vec<_> = content["topics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![result.added_topics[0].as_str(), "topic-1", "topic-0"]
+        );
+        // Audio follows its topic to the new position, images by page_id
+        assert_eq!(content["topics"][1]["audioId"], "audio-3");
+        assert_eq!(content["topics"][2]["audioId"], "audio-4");
+        assert_eq!(
+            fs::read_to_string(dir.join("audio-3.bin")).unwrap(),
+            "audio-3"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("audio-4.bin")).unwrap(),
+            "audio-2"
+        );
+        let image: Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("image-7.json")).unwrap()).unwrap();
+        assert_eq!(image["page_id"], "topic-1");
+    }
+}
@@ -0,0 +1,610 @@
+use crate::course_outline::new_topic;
+use crate::media_storage::get_media_directory;
+use crate::media_usage::find_project_file;
+use crate::project_encryption::{open_media_data, write_media_file};
+use crate::project_storage::{load_project_file, save_project_file};
+use crate::stable_ids::{legacy_media_index, legacy_topic_index, new_topic_id};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Audio and captions are numbered by page: welcome=0, objectives=1, topics from 2
+const FIRST_TOPIC_MEDIA_INDEX: usize = 2;
+
+/// Structural change to the topic list
+#[derive(Debug, Clone, PartialEq)]
+pub enum TopicEdit {
+    /// Move the topic at `from` so that it ends up at position `to`
+    Move {
+        from: usize,
+        to: usize,
+    },
+    Delete {
+        index: usize,
+    },
+    Insert {
+        index: usize,
+        title: String,
+    },
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicEditResult {
+    /// Topic IDs in their new order
+    pub topic_ids: Vec<String>,
+    pub inserted_topic: Option<String>,
+    /// Media files that were renumbered, as (old ID, new ID)
+    pub renamed_media: Vec<(String, String)>,
+    /// Media files whose page_id now points at a different topic
+    pub reassigned_media: Vec<String>,
+    pub removed_media: Vec<String>,
+}
+
+/// File operations done for a topic edit, kept in the order they were done so
+/// they can be undone if the project file cannot be saved afterwards
+#[derive(Debug, Default)]
+pub struct MediaChanges {
+    done: Vec<MediaChange>,
+}
+
+#[derive(Debug)]
+enum MediaChange {
+    Renamed { from: PathBuf, to: PathBuf },
+    Rewritten { path: PathBuf, original: Vec<u8> },
+    Trashed { path: PathBuf, trash_path: PathBuf },
+}
+
+impl MediaChanges {
+    pub(crate) fn rename(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        fs::rename(from, to)
+            .map_err(|e| format!("Failed to rename {:?} to {:?}: {}", from, to, e))?;
+        self.done.push(MediaChange::Renamed {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Set aside a file to delete on commit
+    pub(crate) fn trash(&mut self, path: &Path) -> Result<(), String> {
+        let trash_path = with_suffix(path, "deleted");
+        fs::rename(path, &trash_path).map_err(|e| format!("Failed to remove {:?}: {}", path, e))?;
+        self.done.push(MediaChange::Trashed {
+            path: path.to_path_buf(),
+            trash_path,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn rewrite(
+        &mut self,
+        path: &Path,
+        original: Vec<u8>,
+        content: &[u8],
+    ) -> Result<(), String> {
+        write_media_file(path, content)?;
+        self.done.push(MediaChange::Rewritten {
+            path: path.to_path_buf(),
+            original,
+        });
+        Ok(())
+    }
+
+    /// Add the changes of an edit made after these, so that one commit or
+    /// rollback covers both
+    pub(crate) fn extend(&mut self, later: MediaChanges) {
+        self.done.extend(later.done);
+    }
+
+    /// Delete the files set aside by the edit
+    pub fn commit(self) {
+        for change in self.done {
+            if let MediaChange::Trashed { trash_path, .. } = change {
+                if let Err(e) = fs::remove_file(&trash_path) {
+                    println!(
+                        "[course_structure] Failed to remove {:?}: {}",
+                        trash_path, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Put every media file back the way it was before the edit, undoing the
+    /// operations in reverse
+    pub fn rollback(self) {
+        for change in self.done.into_iter().rev() {
+            match change {
+                MediaChange::Renamed { from, to } => {
+                    fs::rename(&to, &from).ok();
+                }
+                MediaChange::Rewritten { path, original } => {
+                    fs::write(&path, original).ok();
+                }
+                MediaChange::Trashed { path, trash_path } => {
+                    fs::rename(&trash_path, &path).ok();
+                }
+            }
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// For each position in the new topic list, the old index of the topic there
+/// (`None` for an inserted topic)
+fn topic_order(len: usize, edit: &TopicEdit) -> Result<Vec<Option<usize>>, String> {
+    let mut order: Vec<Option<usize>> = (0..len).map(Some).collect();
+    match *edit {
+        TopicEdit::Move { from, to } => {
+            if from >= len || to >= len {
+                return Err(format!(
+                    "Cannot move topic {} to {}: course has {} topics",
+                    from, to, len
+                ));
+            }
+            let topic = order.remove(from);
+            order.insert(to, topic);
+        }
+        TopicEdit::Delete { index } => {
+            if index >= len {
+                return Err(format!(
+                    "Cannot delete topic {}: course has {} topics",
+                    index, len
+                ));
+            }
+            order.remove(index);
+        }
+        TopicEdit::Insert { index, .. } => {
+            if index > len {
+                return Err(format!(
+                    "Cannot insert topic at {}: course has {} topics",
+                    index, len
+                ));
+            }
+            order.insert(index, None);
+        }
+    }
+    Ok(order)
+}
+
+/// New ID for an audio or caption ID that belongs to a moved topic
+fn remap_media_id(id: &str, old_to_new: &[Option<usize>]) -> Option<String> {
+    let (kind, n) = legacy_media_index(id)?;
+    let old = n.checked_sub(FIRST_TOPIC_MEDIA_INDEX)?;
+    let new = (*old_to_new.get(old)?)?;
+    (new != old).then(|| format!("{}-{}", kind, new + FIRST_TOPIC_MEDIA_INDEX))
+}
+
+fn remap_topic_references(topic: &mut Value, old_to_new: &[Option<usize>]) {
+    for key in ["audioId", "captionId"] {
+        let new_id = topic
+            .get(key)
+            .and_then(Value::as_str)
+            .and_then(|id| remap_media_id(id, old_to_new));
+        if let Some(new_id) = new_id {
+            topic[key] = Value::String(new_id);
+        }
+    }
+    if let Some(media) = topic.get_mut("media").and_then(Value::as_array_mut) {
+        for item in media {
+            for key in ["id", "storageId"] {
+                let new_id = item
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .and_then(|id| remap_media_id(id, old_to_new));
+                if let Some(new_id) = new_id {
+                    item[key] = Value::String(new_id);
+                }
+            }
+        }
+    }
+}
+
+fn media_files(media_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if !media_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(media_dir).map_err(|e| format!("Failed to read media directory: {}", e))?;
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("bin") | Some("json")
+                )
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+fn media_id(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Metadata of a media file, with the file as stored for a rollback
+fn read_metadata(json_path: &Path) -> Option<(Vec<u8>, Value)> {
+    let original = fs::read(json_path).ok()?;
+    let metadata: Value =
+        serde_json::from_slice(&open_media_data(json_path, original.clone()).ok()?).ok()?;
+    Some((original, metadata))
+}
+
+/// Renumber, reassign and remove media files to match the new topic order
+fn apply_media_changes(
+    media_dir: &Path,
+    old_to_new: &[Option<usize>],
+    deleted_ids: &HashSet<String>,
+    changes: &mut MediaChanges,
+    result: &mut TopicEditResult,
+) -> Result<(), String> {
+    let files = media_files(media_dir)?;
+
+    // Media of deleted topics: legacy audio/captions by number, everything
+    // else by page_id
+    let is_deleted = |old: usize| old_to_new.get(old) == Some(&None);
+    for path in &files {
+        let id = media_id(path);
+        let deleted = match legacy_media_index(&id) {
+            Some((_, n)) => n
+                .checked_sub(FIRST_TOPIC_MEDIA_INDEX)
+                .is_some_and(is_deleted),
+            None if path.extension().and_then(|e| e.to_str()) == Some("json") => {
+                read_metadata(path)
+                    .and_then(|(_, metadata)| metadata["page_id"].as_str().map(str::to_string))
+                    .is_some_and(|page_id| {
+                        deleted_ids.contains(&page_id)
+                            || legacy_topic_index(&page_id).is_some_and(is_deleted)
+                    })
+            }
+            None => false,
+        };
+        if deleted {
+            let bin = media_dir.join(format!("{}.bin", id));
+            let json = media_dir.join(format!("{}.json", id));
+            for file in [bin, json] {
+                if file.exists() {
+                    changes.trash(&file)?;
+                }
+            }
+            if !result.removed_media.contains(&id) {
+                println!("[course_structure] Removing {} with its deleted topic", id);
+                result.removed_media.push(id);
+            }
+        }
+    }
+
+    // Move audio/captions of moved topics out of the way first so a rename
+    // never lands on a file that is about to move itself
+    let mut staged = Vec::new();
+    for path in media_files(media_dir)? {
+        let id = media_id(&path);
+        if let Some(new_id) = remap_media_id(&id, old_to_new) {
+            let staging = with_suffix(&path, "moving");
+            changes.rename(&path, &staging)?;
+            let target = path.with_file_name(format!(
+                "{}.{}",
+                new_id,
+                path.extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or_default()
+            ));
+            staged.push((staging, target));
+            if !result.renamed_media.iter().any(|(from, _)| from == &id) {
+                result.renamed_media.push((id, new_id));
+            }
+        }
+    }
+    for (staging, target) in staged {
+        // Anything still at the target belongs to no topic, e.g. audio left
+        // past the end of the list by an earlier edit
+        if target.exists() {
+            changes.trash(&target)?;
+            result.removed_media.push(media_id(&target));
+        }
+        changes.rename(&staging, &target)?;
+    }
+
+    // Point page_id of every remaining file at its topic's new position
+    for path in media_files(media_dir)? {
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some((original, mut metadata)) = read_metadata(&path) else {
+            continue;
+        };
+        let new_index = metadata["page_id"]
+            .as_str()
+            .and_then(legacy_topic_index)
+            .and_then(|old| {
+                old_to_new
+                    .get(old)
+                    .copied()
+                    .flatten()
+                    .filter(|new| *new != old)
+            });
+        if let Some(new) = new_index {
+            metadata["page_id"] = Value::String(format!("topic-{}", new));
+            let content = serde_json::to_vec_pretty(&metadata)
+                .map_err(|e| format!("Failed to serialize media metadata: {}", e))?;
+            changes.rewrite(&path, original, &content)?;
+            result.reassigned_media.push(media_id(&path));
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a structural edit to the topics of `course_content` and renumber the
+/// media in `media_dir` to match. On error every file is put back and
+/// `course_content` is left untouched. On success the caller must `commit` the
+/// returned changes once the project is saved, or `rollback` them.
+pub fn edit_topics(
+    course_content: &mut Value,
+    media_dir: &Path,
+    edit: TopicEdit,
+) -> Result<(TopicEditResult, MediaChanges), String> {
+    let topics = course_content
+        .get("topics")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "Course content has no topics".to_string())?;
+    let order = topic_order(topics.len(), &edit)?;
+
+    let mut old_to_new = vec![None; topics.len()];
+    for (new, old) in order.iter().enumerate() {
+        if let Some(old) = old {
+            old_to_new[*old] = Some(new);
+        }
+    }
+
+    let mut result = TopicEditResult::default();
+    let mut new_topics = Vec::with_capacity(order.len());
+    for old in &order {
+        let topic = match (old, &edit) {
+            (Some(old), _) => {
+                let mut topic = topics[*old].clone();
+                remap_topic_references(&mut topic, &old_to_new);
+                topic
+            }
+            (None, TopicEdit::Insert { title, .. }) => {
+                let id = new_topic_id();
+                result.inserted_topic = Some(id.clone());
+                new_topic(&id, title)
+            }
+            (None, _) => unreachable!("only inserts add topics"),
+        };
+        new_topics.push(topic);
+    }
+    result.topic_ids = new_topics
+        .iter()
+        .map(|t| {
+            t.get("id")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect();
+
+    let deleted_ids: HashSet<String> = old_to_new
+        .iter()
+        .zip(topics)
+        .filter(|(new, _)| new.is_none())
+        .filter_map(|(_, topic)| topic.get("id").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+
+    let mut changes = MediaChanges::default();
+    if let Err(e) = apply_media_changes(
+        media_dir,
+        &old_to_new,
+        &deleted_ids,
+        &mut changes,
+        &mut result,
+    ) {
+        changes.rollback();
+        return Err(e);
+    }
+
+    course_content["topics"] = Value::Array(new_topics);
+    Ok((result, changes))
+}
+
+fn edit_project_topics(project_id: &str, edit: TopicEdit) -> Result<TopicEditResult, String> {
+    let project_path =
+        find_project_file(project_id).ok_or_else(|| "Project file not found".to_string())?;
+    crate::project_access::ensure_writable(&project_path)?;
+    let mut project = load_project_file(&project_path)?;
+    let course_content = project
+        .course_content
+        .as_mut()
+        .ok_or_else(|| "Project has no course content yet".to_string())?;
+    let media_dir = get_media_directory(project_id)?;
+
+    let (result, changes) = edit_topics(course_content, &media_dir, edit)?;
+    if let Err(e) = save_project_file(&project, &project_path) {
+        changes.rollback();
+        return Err(e);
+    }
+    changes.commit();
+    Ok(result)
+}
+
+/// Move a topic to a new position, renumbering topic audio and captions
+#[tauri::command]
+pub fn move_topic(
+    #[allow(non_snake_case)] projectId: String,
+    #[allow(non_snake_case)] fromIndex: usize,
+    #[allow(non_snake_case)] toIndex: usize,
+) -> Result<TopicEditResult, String> {
+    edit_project_topics(
+        &projectId,
+        TopicEdit::Move {
+            from: fromIndex,
+            to: toIndex,
+        },
+    )
+}
+
+/// Delete a topic together with its media
+#[tauri::command]
+pub fn delete_topic(
+    #[allow(non_snake_case)] projectId: String,
+    index: usize,
+) -> Result<TopicEditResult, String> {
+    edit_project_topics(&projectId, TopicEdit::Delete { index })
+}
+
+/// Insert an empty topic, shifting the media of the topics after it
+#[tauri::command]
+pub fn insert_topic(
+    #[allow(non_snake_case)] projectId: String,
+    index: usize,
+    title: String,
+) -> Result<TopicEditResult, String> {
+    edit_project_topics(&projectId, TopicEdit::Insert { index, title })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn topic(id: &str, n: usize) -> Value {
+        json!({
+            "id": id,
+            "title": id,
+            "audioId": format!("audio-{}", n),
+            "captionId": format!("caption-{}", n),
+            "media": [{ "id": format!("image-{}", n), "type": "image" }]
+        })
+    }
+
+    fn write_media(dir: &Path, id: &str, page_id: &str) {
+        fs::write(dir.join(format!("{}.bin", id)), id).unwrap();
+        fs::write(
+            dir.join(format!("{}.json", id)),
+            json!({ "page_id": page_id, "type": "audio", "original_name": id }).to_string(),
+        )
+        .unwrap();
+    }
+
+    fn course(dir: &Path) -> Value {
+        write_media(dir, "audio-0", "welcome");
+        for i in 0..3 {
+            let n = i + FIRST_TOPIC_MEDIA_INDEX;
+            let page_id = format!("topic-{}", i);
+            write_media(dir, &format!("audio-{}", n), &page_id);
+            write_media(dir, &format!("caption-{}", n), &page_id);
+            write_media(dir, &format!("image-{}", n), &page_id);
+        }
+        json!({ "topics": [topic("a", 2), topic("b", 3), topic("c", 4)] })
+    }
+
+    fn page_id(dir: &Path, id: &str) -> String {
+        let json = fs::read_to_string(dir.join(format!("{}.json", id))).unwrap();
+        serde_json::from_str::<Value>(&json).unwrap()["page_id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn audio(dir: &Path, id: &str) -> String {
+        fs::read_to_string(dir.join(format!("{}.bin", id))).unwrap()
+    }
+
+    #[test]
+    fn test_move_topic_renumbers_media() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let mut content = course(dir);
+
+        let (result, changes) =
+            edit_topics(&mut content, dir, TopicEdit::Move { from: 2, to: 0 }).unwrap();
+        changes.commit();
+
+        assert_eq!(result.topic_ids, vec!["c", "a", "b"]);
+        assert_eq!(content["topics"][0]["audioId"], "audio-2");
+        assert_eq!(content["topics"][1]["captionId"], "caption-3");
+        // Images keep their ID and follow their topic by page_id
+        assert_eq!(content["topics"][0]["media"][0]["id"], "image-4");
+        assert_eq!(page_id(dir, "image-4"), "topic-0");
+        assert_eq!(audio(dir, "audio-2"), "audio-4");
+        assert_eq!(audio(dir, "audio-3"), "audio-2");
+        assert_eq!(audio(dir, "caption-4"), "caption-3");
+        assert_eq!(page_id(dir, "audio-2"), "topic-0");
+        assert_eq!(page_id(dir, "audio-0"), "welcome");
+        // No staging or trash files are left behind
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 20);
+    }
+
+    #[test]
+    fn test_delete_and_insert_topic() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let mut content = course(dir);
+
+        let (result, changes) =
+            edit_topics(&mut content, dir, TopicEdit::Delete { index: 0 }).unwrap();
+        changes.commit();
+        assert_eq!(result.topic_ids, vec!["b", "c"]);
+        assert_eq!(
+            result.removed_media,
+            vec!["audio-2", "caption-2", "image-2"]
+        );
+        assert!(!dir.join("image-2.bin").exists());
+        assert!(!dir.join("audio-4.bin").exists());
+        assert_eq!(audio(dir, "audio-2"), "audio-3");
+        assert_eq!(content["topics"][1]["audioId"], "audio-3");
+
+        let (result, changes) = edit_topics(
+            &mut content,
+            dir,
+            TopicEdit::Insert {
+                index: 0,
+                title: "Intro".to_string(),
+            },
+        )
+        .unwrap();
+        changes.commit();
+        let inserted = result.inserted_topic.unwrap();
+        assert!(legacy_topic_index(&inserted).is_none());
+        assert_eq!(result.topic_ids, vec![inserted.as_str(), "b", "c"]);
+        assert_eq!(content["topics"][0]["duration"], 5);
+        assert!(!dir.join("audio-2.bin").exists());
+        assert_eq!(audio(dir, "audio-3"), "audio-3");
+        assert_eq!(page_id(dir, "image-4"), "topic-2");
+    }
+
+    #[test]
+    fn test_rollback_restores_media() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let mut content = course(dir);
+        let before: Vec<_> = media_files(dir).unwrap();
+
+        let (_, changes) = edit_topics(&mut content, dir, TopicEdit::Delete { index: 1 }).unwrap();
+        changes.rollback();
+
+        assert_eq!(media_files(dir).unwrap(), before);
+        assert_eq!(audio(dir, "audio-3"), "audio-3");
+        assert_eq!(page_id(dir, "image-4"), "topic-2");
+
+        let error = edit_topics(&mut content, dir, TopicEdit::Move { from: 0, to: 3 });
+        assert!(error.is_err());
+    }
+}
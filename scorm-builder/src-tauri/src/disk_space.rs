@@ -0,0 +1,110 @@
+//! Free space on the disks the app writes to.
+//!
+//! Exports and backups check the disk they write to for room before they
+//! start, from an estimate of what they will write, and packages are checked
+//! when they are saved, so a full disk fails them with a clear message
+//! instead of halfway through with an IO error. Long operations also
+//! report the free space in their progress events.
+
+use crate::messages::Message;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Space left free after an operation, for everything else on the disk
+const SAFETY_MARGIN: u64 = 50 * 1024 * 1024;
+
+/// Bytes available to the app on the disk holding `path`, or None when the
+/// system can't tell (e.g. some network shares)
+pub fn available_space(path: &Path) -> Option<u64> {
+    // The path itself may not exist yet; its nearest existing ancestor is on the same disk
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    fs2::available_space(existing).ok()
+}
+
+/// Bytes on disk of a file, or of everything inside a folder
+pub fn path_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| path_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// `bytes` for messages, e.g. "1.5 GB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Whether `free` bytes on the disk of `dir` leave room for `needed` more
+fn check_space(dir: &Path, needed: u64, free: u64) -> Result<(), Message> {
+    if free >= needed.saturating_add(SAFETY_MARGIN) {
+        return Ok(());
+    }
+    Err(Message::new("error.disk_space")
+        .with("path", dir.display())
+        .with("needed", format_size(needed))
+        .with("free", format_size(free)))
+}
+
+/// Fail early when the disk holding `dir` has no room for `needed` bytes.
+/// Passes when the free space is unknown, rather than blocking the operation.
+pub fn ensure_space(dir: &Path, needed: u64) -> Result<(), Message> {
+    match available_space(dir) {
+        Some(free) => check_space(dir, needed, free),
+        None => Ok(()),
+    }
+}
+
+/// Check the disk that will hold `path`, a file the user picked to save to, has room
+/// for `bytes` more
+#[tauri::command]
+pub fn ensure_free_space(path: String, bytes: u64) -> Result<(), String> {
+    let path = Path::new(&path);
+    ensure_space(path.parent().unwrap_or(path), bytes).map_err(String::from)
+}
+
+/// Progress event `fields` with the free space on the disk of `dir` as `freeSpace`
+pub fn with_free_space(mut fields: Value, dir: &Path) -> Value {
+    if let Value::Object(fields) = &mut fields {
+        fields.insert("freeSpace".to_string(), available_space(dir).into());
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations_need_room_for_their_output_and_the_margin() {
+        let dir = Path::new("/projects");
+        assert!(check_space(dir, 10 * 1024 * 1024, 100 * 1024 * 1024).is_ok());
+
+        let error = check_space(dir, 10 * 1024 * 1024, 55 * 1024 * 1024).unwrap_err();
+        assert_eq!(error.key, "error.disk_space");
+        assert!(error.render("en").contains("10.0 MB needed, 55.0 MB free"));
+
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536 * 1024 * 1024), "1.5 GB");
+    }
+}
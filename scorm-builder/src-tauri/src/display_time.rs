@@ -0,0 +1,212 @@
+//! Local-time renderings of the UTC timestamps commands report.
+//!
+//! Projects, backups and builds keep their times in UTC, which the UI showed
+//! as is and left people working out what "14:03" meant. Reporting commands
+//! now add a `localTimes` object beside them: each timestamp formatted in the
+//! machine's time zone with the display format from the app settings, plus
+//! the IANA name of that zone (e.g. "Europe/Berlin"). The stored UTC values
+//! are unchanged.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Built-in display formats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeFormatPreset {
+    /// 2024-03-01 14:03
+    #[default]
+    #[serde(rename = "datetime_24h")]
+    DateTime24h,
+    /// 2024-03-01 02:03 PM
+    #[serde(rename = "datetime_12h")]
+    DateTime12h,
+    /// Friday 1 March 2024, 14:03
+    Long,
+    /// 2024-03-01T14:03:00+01:00
+    Iso,
+}
+
+impl TimeFormatPreset {
+    fn pattern(self) -> &'static str {
+        match self {
+            TimeFormatPreset::DateTime24h => "%Y-%m-%d %H:%M",
+            TimeFormatPreset::DateTime12h => "%Y-%m-%d %I:%M %p",
+            TimeFormatPreset::Long => "%A %-d %B %Y, %H:%M",
+            TimeFormatPreset::Iso => "%Y-%m-%dT%H:%M:%S%:z",
+        }
+    }
+}
+
+/// How reporting commands format local times
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeDisplay {
+    #[serde(default)]
+    pub preset: TimeFormatPreset,
+    /// strftime pattern used instead of the preset, e.g. "%d/%m/%Y %H:%M"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl TimeDisplay {
+    fn pattern(&self) -> &str {
+        self.pattern.as_deref().unwrap_or(self.preset.pattern())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let Some(pattern) = &self.pattern else {
+            return Ok(());
+        };
+        if pattern.trim().is_empty() {
+            return Err("The time format is empty".to_string());
+        }
+        if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+            return Err(format!("'{pattern}' is not a valid time format"));
+        }
+        Ok(())
+    }
+}
+
+/// Local renderings of a response's timestamps, keyed by the field they render
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalTimes {
+    /// IANA name of the zone, or its UTC offset when the name is unknown
+    pub timezone: String,
+    #[serde(flatten)]
+    pub times: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+enum Zone {
+    Local,
+    #[cfg(test)]
+    Fixed(chrono::FixedOffset),
+}
+
+/// Formats UTC timestamps for display
+#[derive(Debug, Clone)]
+pub struct TimeFormatter {
+    display: TimeDisplay,
+    zone: Zone,
+    timezone: String,
+}
+
+impl TimeFormatter {
+    /// The machine's time zone with the display format from the app settings
+    pub fn current() -> Self {
+        let display = crate::settings::load_settings()
+            .ok()
+            .and_then(|settings| settings.time_display)
+            .filter(|display| display.validate().is_ok())
+            .unwrap_or_default();
+        let timezone = iana_time_zone::get_timezone()
+            .unwrap_or_else(|_| Local::now().format("UTC%:z").to_string());
+        Self {
+            display,
+            zone: Zone::Local,
+            timezone,
+        }
+    }
+
+    #[cfg(test)]
+    fn fixed(display: TimeDisplay, offset: chrono::FixedOffset, timezone: &str) -> Self {
+        Self {
+            display,
+            zone: Zone::Fixed(offset),
+            timezone: timezone.to_string(),
+        }
+    }
+
+    pub fn format(&self, time: DateTime<Utc>) -> String {
+        let pattern = self.display.pattern();
+        match &self.zone {
+            Zone::Local => time.with_timezone(&Local).format(pattern).to_string(),
+            #[cfg(test)]
+            Zone::Fixed(offset) => time.with_timezone(offset).format(pattern).to_string(),
+        }
+    }
+
+    /// `times` rendered in this zone
+    pub fn local_times<'a>(&self, times: impl IntoIterator<Item = (&'a str, DateTime<Utc>)>) -> LocalTimes {
+        LocalTimes {
+            timezone: self.timezone.clone(),
+            times: times
+                .into_iter()
+                .map(|(field, time)| (field.to_string(), self.format(time)))
+                .collect(),
+        }
+    }
+
+    /// `local_times` for timestamps kept as RFC 3339 strings; missing or
+    /// unparseable ones are left out
+    pub fn local_times_rfc3339<'a>(
+        &self,
+        times: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+    ) -> LocalTimes {
+        self.local_times(times.into_iter().filter_map(|(field, time)| {
+            let time = DateTime::parse_from_rfc3339(time?).ok()?;
+            Some((field, time.with_timezone(&Utc)))
+        }))
+    }
+}
+
+#[tauri::command]
+pub fn get_time_display() -> Result<TimeDisplay, String> {
+    Ok(crate::settings::load_settings()?.time_display.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_time_display(display: TimeDisplay) -> Result<(), String> {
+    display.validate()?;
+    let mut settings = crate::settings::load_settings()?;
+    settings.time_display = Some(display);
+    crate::settings::save_settings(&settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_times_are_rendered_in_the_zone_with_the_chosen_format() {
+        let time = DateTime::parse_from_rfc3339("2024-03-01T13:03:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let berlin = chrono::FixedOffset::east_opt(3600).unwrap();
+        let formatter = |display: TimeDisplay| TimeFormatter::fixed(display, berlin, "Europe/Berlin");
+
+        assert_eq!(formatter(TimeDisplay::default()).format(time), "2024-03-01 14:03");
+        let twelve_hour = TimeDisplay {
+            preset: TimeFormatPreset::DateTime12h,
+            pattern: None,
+        };
+        assert_eq!(formatter(twelve_hour).format(time), "2024-03-01 02:03 PM");
+        let custom = TimeDisplay {
+            preset: TimeFormatPreset::Iso,
+            pattern: Some("%d/%m/%Y %H:%M".to_string()),
+        };
+        assert_eq!(formatter(custom).format(time), "01/03/2024 14:03");
+
+        let local = formatter(TimeDisplay::default())
+            .local_times_rfc3339([("modified", Some("2024-03-01T13:03:00+00:00")), ("created", Some("Unknown"))]);
+        assert_eq!(local.timezone, "Europe/Berlin");
+        assert_eq!(
+            serde_json::to_value(&local).unwrap(),
+            serde_json::json!({ "timezone": "Europe/Berlin", "modified": "2024-03-01 14:03" })
+        );
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_rejected() {
+        for pattern in ["", "  ", "%Q %Y"] {
+            let display = TimeDisplay {
+                preset: TimeFormatPreset::default(),
+                pattern: Some(pattern.to_string()),
+            };
+            assert!(display.validate().is_err(), "{pattern:?}");
+        }
+    }
+}
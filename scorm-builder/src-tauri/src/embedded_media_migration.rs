@@ -0,0 +1,346 @@
+//! Moves media embedded in legacy project files into the media folder.
+//!
+//! Early `.scormproj` files kept images, audio and captions inline as
+//! `base64_data` on their `MediaItem`s, which makes the project file huge and
+//! slow to save. The migration decodes each blob into `{id}.bin` (with a
+//! `{id}.json` metadata file when there is none yet), points the item at it
+//! with `relative_path` and drops the inline copy. Items whose file can't be
+//! written keep their data, so nothing is lost when a migration stops halfway.
+//! Embedded videos stay in the project file and are listed as skipped.
+
+use crate::media_storage::{get_media_directory, MediaMetadata};
+use crate::media_usage::find_project_file;
+use crate::messages::Message;
+use crate::project_encryption::{read_media_file, write_media_file};
+use crate::project_storage::{
+    load_project_file, save_project_file, MediaData, MediaItem, ProjectFile,
+};
+use crate::stable_ids::is_plain_media_id;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tauri::Emitter;
+
+pub const PROGRESS_EVENT: &str = "embedded-media-migration-progress";
+
+/// An item whose data moved, or would move on a dry run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedMediaChange {
+    pub media_id: String,
+    pub media_type: String,
+    /// Decoded size of the media
+    pub size: u64,
+    pub relative_path: String,
+}
+
+/// An item left embedded, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedMediaError {
+    pub media_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedMediaReport {
+    /// Nothing was written
+    pub dry_run: bool,
+    pub changes: Vec<EmbeddedMediaChange>,
+    pub errors: Vec<EmbeddedMediaError>,
+    /// Embedded items the migration doesn't move
+    pub skipped: Vec<EmbeddedMediaError>,
+    /// Size of the project file before and after (expected after, on a dry run)
+    pub project_size_before: u64,
+    pub project_size_after: u64,
+}
+
+/// Package-relative path an extracted item points at
+fn relative_path(media_id: &str) -> String {
+    format!("media/{media_id}.bin")
+}
+
+/// The bytes of `base64_data`, which older versions stored as a data URL
+fn decode(base64_data: &str) -> Result<(Vec<u8>, Option<String>), String> {
+    let (mime_type, payload) = match base64_data.strip_prefix("data:") {
+        Some(url) => {
+            let (header, payload) = url.split_once(',').ok_or("Malformed data URL")?;
+            let mime_type = header.strip_suffix(";base64").unwrap_or(header);
+            (Some(mime_type.to_string()).filter(|m| !m.is_empty()), payload)
+        }
+        None => (None, base64_data),
+    };
+    let cleaned: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(cleaned)
+        .map_err(|e| format!("Invalid base64 data: {e}"))?;
+    Ok((data, mime_type))
+}
+
+fn metadata_str<'a>(metadata: Option<&'a Value>, keys: &[&str]) -> Option<&'a str> {
+    let metadata = metadata?;
+    keys.iter().find_map(|key| metadata.get(*key).and_then(Value::as_str))
+}
+
+/// Metadata for an extracted item, from what the legacy item recorded
+fn media_metadata(item: &MediaItem, media_type: &str, mime_type: Option<String>) -> MediaMetadata {
+    let metadata = item.metadata.as_ref();
+    MediaMetadata {
+        page_id: metadata_str(metadata, &["page_id", "pageId"]).unwrap_or_default().to_string(),
+        media_type: media_type.to_string(),
+        original_name: item.filename.clone(),
+        mime_type: mime_type.or_else(|| {
+            metadata_str(metadata, &["mime_type", "mimeType"]).map(str::to_string)
+        }),
+        source: None,
+        embed_url: None,
+        title: metadata_str(metadata, &["title"]).map(str::to_string),
+        clip_start: None,
+        clip_end: None,
+        alt_text: metadata_str(metadata, &["alt_text", "altText"]).map(str::to_string),
+        attribution: None,
+    }
+}
+
+/// Write `data` as `{id}.bin` unless the same bytes are already there, and the
+/// metadata file unless one exists
+fn extract(
+    media_dir: &Path,
+    item: &MediaItem,
+    data: &[u8],
+    metadata: &MediaMetadata,
+) -> Result<(), String> {
+    let data_path = media_dir.join(format!("{}.bin", item.id));
+    match read_media_file(&data_path) {
+        Ok(existing) if existing == data => {}
+        Ok(_) => return Err(format!("{} already exists with different content", data_path.display())),
+        Err(_) => write_media_file(&data_path, data)?,
+    }
+
+    let metadata_path = media_dir.join(format!("{}.json", item.id));
+    if !metadata_path.exists() {
+        let json = serde_json::to_string_pretty(metadata)
+            .map_err(|e| format!("Failed to serialize metadata: {e}"))?;
+        write_media_file(&metadata_path, json.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Move the embedded data of `media` into `media_dir`, calling `progress` with
+/// (done, total) after each item. A dry run only decodes.
+pub fn migrate_media_data(
+    media: &mut MediaData,
+    media_dir: &Path,
+    dry_run: bool,
+    mut progress: impl FnMut(usize, usize),
+) -> EmbeddedMediaReport {
+    let mut report = EmbeddedMediaReport {
+        dry_run,
+        skipped: media
+            .videos
+            .iter()
+            .filter(|item| item.base64_data.as_deref().is_some_and(|data| !data.is_empty()))
+            .map(|item| EmbeddedMediaError {
+                media_id: item.id.clone(),
+                error: "Embedded videos are left in the project file".to_string(),
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let items: Vec<(&str, &mut MediaItem)> = media
+        .images
+        .iter_mut()
+        .map(|item| ("image", item))
+        .chain(media.audio.iter_mut().map(|item| ("audio", item)))
+        .chain(media.captions.iter_mut().map(|item| ("caption", item)))
+        .filter(|(_, item)| item.base64_data.as_deref().is_some_and(|data| !data.is_empty()))
+        .collect();
+
+    let total = items.len();
+    for (done, (media_type, item)) in items.into_iter().enumerate() {
+        // The id names the file written to the media folder
+        let result = if is_plain_media_id(&item.id) {
+            decode(item.base64_data.as_deref().unwrap_or_default())
+        } else {
+            Err(format!("Invalid media id: {}", item.id))
+        };
+        let result = result.and_then(|(data, mime_type)| {
+            if !dry_run {
+                extract(media_dir, item, &data, &media_metadata(item, media_type, mime_type))?;
+            }
+            Ok(data.len() as u64)
+        });
+        match result {
+            Ok(size) => {
+                let path = relative_path(&item.id);
+                report.changes.push(EmbeddedMediaChange {
+                    media_id: item.id.clone(),
+                    media_type: media_type.to_string(),
+                    size,
+                    relative_path: path.clone(),
+                });
+                if !dry_run {
+                    item.base64_data = None;
+                    item.relative_path = Some(path);
+                }
+            }
+            Err(error) => report.errors.push(EmbeddedMediaError {
+                media_id: item.id.clone(),
+                error,
+            }),
+        }
+        progress(done + 1, total);
+    }
+    report
+}
+
+/// How many bytes the serialized project shrinks by once `report` is applied
+fn size_saved(project: &ProjectFile, report: &EmbeddedMediaReport) -> u64 {
+    let mut migrated = project.clone();
+    for item in migrated
+        .media
+        .images
+        .iter_mut()
+        .chain(&mut migrated.media.audio)
+        .chain(&mut migrated.media.captions)
+    {
+        if let Some(change) = report.changes.iter().find(|change| change.media_id == item.id) {
+            item.base64_data = None;
+            item.relative_path = Some(change.relative_path.clone());
+        }
+    }
+    let size = |project: &ProjectFile| serde_json::to_vec_pretty(project).map_or(0, |json| json.len() as u64);
+    size(project).saturating_sub(size(&migrated))
+}
+
+/// Move the base64 media embedded in a legacy project file into its media
+/// folder, emitting `embedded-media-migration-progress`. With `dry_run` only
+/// reports what would move and how much smaller the project file would get.
+#[tauri::command]
+pub async fn migrate_embedded_media(
+    app: tauri::AppHandle,
+    project_id: String,
+    dry_run: Option<bool>,
+) -> Result<EmbeddedMediaReport, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let project_path =
+        find_project_file(&project_id).ok_or_else(|| "Project file not found".to_string())?;
+    let size_before = fs::metadata(&project_path)
+        .map(|metadata| metadata.len())
+        .map_err(|e| format!("Failed to read project file: {e}"))?;
+    let mut project = load_project_file(&project_path)?;
+    crate::project_access::ensure_writable(&project_path)?;
+    let media_dir = get_media_directory(&project.project.id)?;
+
+    let original = project.clone();
+    let mut report = migrate_media_data(&mut project.media, &media_dir, dry_run, |current, total| {
+        let _ = app.emit(
+            PROGRESS_EVENT,
+            Message::new("progress.embedded_media.item")
+                .with("current", current)
+                .with("total", total)
+                .progress_event(serde_json::json!({
+                    "progress": current * 100 / total.max(1),
+                    "current": current,
+                    "total": total
+                })),
+        );
+    });
+    report.project_size_before = size_before;
+
+    if dry_run || report.changes.is_empty() {
+        report.project_size_after = size_before.saturating_sub(size_saved(&original, &report));
+    } else {
+        save_project_file(&project, &project_path)?;
+        report.project_size_after = fs::metadata(&project_path)
+            .map(|metadata| metadata.len())
+            .unwrap_or_default();
+    }
+
+    let _ = app.emit(
+        PROGRESS_EVENT,
+        Message::new("progress.embedded_media.done")
+            .with("count", report.changes.len())
+            .progress_event(serde_json::json!({
+                "progress": 100,
+                "dryRun": dry_run,
+                "failed": report.errors.len(),
+                "skipped": report.skipped.len()
+            })),
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn item(id: &str, base64_data: Option<&str>) -> MediaItem {
+        MediaItem {
+            id: id.to_string(),
+            filename: format!("{id}.png"),
+            base64_data: base64_data.map(str::to_string),
+            relative_path: None,
+            metadata: Some(serde_json::json!({ "pageId": "welcome", "mimeType": "image/png" })),
+        }
+    }
+
+    fn media() -> MediaData {
+        MediaData {
+            images: vec![
+                item("image-0", Some("data:image/png;base64,aGVsbG8=")),
+                item("image-1", Some("not base64!")),
+                item("image-2", None),
+                item("../image-3", Some("aGVsbG8=")),
+            ],
+            videos: vec![item("video-0", Some("aGVsbG8="))],
+            audio: vec![],
+            captions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_dry_run_reports_without_writing() {
+        let dir = TempDir::new().unwrap();
+        let mut media = media();
+
+        let report = migrate_media_data(&mut media, dir.path(), true, |_, _| {});
+
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].size, 5);
+        assert_eq!(report.errors[0].media_id, "image-1");
+        assert_eq!(report.errors[1].error, "Invalid media id: ../image-3");
+        assert_eq!(report.skipped[0].media_id, "video-0");
+        assert!(media.images[0].base64_data.is_some());
+        assert!(fs::read_dir(dir.path()).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn test_embedded_data_moves_to_the_media_folder() {
+        let dir = TempDir::new().unwrap();
+        let mut media = media();
+        let mut calls = Vec::new();
+
+        let report = migrate_media_data(&mut media, dir.path(), false, |done, total| calls.push((done, total)));
+
+        assert_eq!(calls, vec![(1, 3), (2, 3), (3, 3)]);
+        assert_eq!(report.changes[0].relative_path, "media/image-0.bin");
+        assert_eq!(fs::read(dir.path().join("image-0.bin")).unwrap(), b"hello");
+        let metadata: MediaMetadata =
+            serde_json::from_str(&fs::read_to_string(dir.path().join("image-0.json")).unwrap()).unwrap();
+        assert_eq!(metadata.page_id, "welcome");
+        assert_eq!(metadata.mime_type.as_deref(), Some("image/png"));
+
+        assert_eq!(media.images[0].base64_data, None);
+        assert_eq!(media.images[0].relative_path.as_deref(), Some("media/image-0.bin"));
+        // Undecodable data stays where it was
+        assert!(media.images[1].base64_data.is_some());
+        assert!(media.images[3].base64_data.is_some());
+        assert!(!dir.path().parent().unwrap().join("image-3.bin").exists());
+        assert!(media.videos[0].base64_data.is_some());
+    }
+}
@@ -0,0 +1,500 @@
+//! Fetching media from URLs the user pastes or picks from search results.
+//!
+//! The app runs with the user's network access, so a URL handed to it must
+//! not be able to reach anything a web page couldn't: only http(s), only
+//! hosts that resolve to public addresses, with the connection pinned to the
+//! addresses that were checked and every redirect checked again. Downloads
+//! are capped in size and time and must look like media by their content,
+//! whatever the server claims. Failures come back as an
+//! [`ExternalMediaError`] saying which of those rules stopped them.
+
+use crate::api_quota::{check_quota, record_usage, today, MEDIA_DOWNLOAD_PROVIDER};
+use crate::app_services::AppServices;
+use crate::commands_secure::DownloadImageResponse;
+use crate::media_download::require_license;
+use crate::media_storage::MediaAttribution;
+use serde::Serialize;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::time::Duration;
+use url::Url;
+
+/// Schemes a media URL may use
+pub const ALLOWED_SCHEMES: [&str; 2] = ["https", "http"];
+/// Largest file `download_external_media` accepts
+pub const MAX_EXTERNAL_MEDIA_BYTES: usize = 25 * 1024 * 1024;
+/// Redirects followed before giving up
+pub const MAX_REDIRECTS: usize = 5;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalMediaErrorKind {
+    InvalidUrl,
+    SchemeNotAllowed,
+    /// The host is, or resolves to, a loopback, private or otherwise internal address
+    AddressNotAllowed,
+    DnsFailed,
+    TooManyRedirects,
+    Timeout,
+    Network,
+    HttpStatus,
+    TooLarge,
+    /// The content isn't an image, audio, video or caption file
+    TypeNotAllowed,
+    /// The daily media download limit has been reached
+    QuotaExceeded,
+}
+
+/// Returned (as JSON) by commands that refused or failed to fetch a URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalMediaError {
+    pub message: String,
+    /// Always `"externalMedia"`, so the UI can tell these from other errors
+    pub error_type: &'static str,
+    pub kind: ExternalMediaErrorKind,
+    pub url: String,
+    /// Response status of an `httpStatus` error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+}
+
+impl ExternalMediaError {
+    fn new(kind: ExternalMediaErrorKind, url: &str, message: String) -> Self {
+        Self {
+            message,
+            error_type: "externalMedia",
+            kind,
+            url: url.to_string(),
+            status: None,
+        }
+    }
+
+    /// Whether trying again later might succeed
+    pub fn is_transient(&self) -> bool {
+        match self.kind {
+            ExternalMediaErrorKind::Timeout
+            | ExternalMediaErrorKind::Network
+            | ExternalMediaErrorKind::DnsFailed => true,
+            ExternalMediaErrorKind::HttpStatus => self
+                .status
+                .is_some_and(|status| status >= 500 || matches!(status, 408 | 429)),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ExternalMediaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<ExternalMediaError> for String {
+    fn from(error: ExternalMediaError) -> String {
+        serde_json::to_string(&error).unwrap_or(error.message)
+    }
+}
+
+/// Body of a fetched URL and the media type its content was identified as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalMedia {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (b & 0xfe) == 18)
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(ipv4);
+    }
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // Documentation, 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// Whether `ip` is reachable on the public internet, rather than the local
+/// machine, the local network or a reserved range
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+/// Parse `url` and check its scheme and host
+pub fn check_url(url: &str) -> Result<Url, ExternalMediaError> {
+    use ExternalMediaErrorKind::*;
+    let parsed = Url::parse(url.trim())
+        .map_err(|e| ExternalMediaError::new(InvalidUrl, url, format!("Invalid URL: {e}")))?;
+    if !ALLOWED_SCHEMES.contains(&parsed.scheme()) {
+        return Err(ExternalMediaError::new(
+            SchemeNotAllowed,
+            url,
+            format!("{} URLs can't be downloaded", parsed.scheme()),
+        ));
+    }
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err(ExternalMediaError::new(
+            InvalidUrl,
+            url,
+            "URLs with credentials can't be downloaded".to_string(),
+        ));
+    }
+    match parsed.host() {
+        None => Err(ExternalMediaError::new(
+            InvalidUrl,
+            url,
+            "Invalid URL: no host".to_string(),
+        )),
+        Some(url::Host::Ipv4(ip)) if !is_public_ipv4(ip) => {
+            Err(address_not_allowed(url, &ip.to_string()))
+        }
+        Some(url::Host::Ipv6(ip)) if !is_public_ipv6(ip) => {
+            Err(address_not_allowed(url, &ip.to_string()))
+        }
+        Some(_) => Ok(parsed),
+    }
+}
+
+fn address_not_allowed(url: &str, address: &str) -> ExternalMediaError {
+    ExternalMediaError::new(
+        ExternalMediaErrorKind::AddressNotAllowed,
+        url,
+        format!("{address} is a local or private address"),
+    )
+}
+
+/// Addresses `url`'s host resolves to, all of which must be public. The
+/// lookup blocks; async code goes through `resolve_public_async`.
+pub fn resolve_public(url: &Url) -> Result<Vec<SocketAddr>, ExternalMediaError> {
+    let host = url.host_str().unwrap_or_default();
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<SocketAddr> = (host.trim_matches(['[', ']']), port)
+        .to_socket_addrs()
+        .map_err(|e| {
+            ExternalMediaError::new(
+                ExternalMediaErrorKind::DnsFailed,
+                url.as_str(),
+                format!("Failed to resolve {host}: {e}"),
+            )
+        })?
+        .collect();
+    // One internal address is enough to refuse: the client could pick it
+    if let Some(internal) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(address_not_allowed(
+            url.as_str(),
+            &internal.ip().to_string(),
+        ));
+    }
+    if addrs.is_empty() {
+        return Err(ExternalMediaError::new(
+            ExternalMediaErrorKind::DnsFailed,
+            url.as_str(),
+            format!("{host} has no addresses"),
+        ));
+    }
+    Ok(addrs)
+}
+
+/// [`resolve_public`] on the blocking thread pool
+async fn resolve_public_async(url: &Url) -> Result<Vec<SocketAddr>, ExternalMediaError> {
+    let owned = url.clone();
+    tokio::task::spawn_blocking(move || resolve_public(&owned))
+        .await
+        .map_err(|e| {
+            ExternalMediaError::new(
+                ExternalMediaErrorKind::DnsFailed,
+                url.as_str(),
+                format!("DNS lookup task failed: {e}"),
+            )
+        })?
+}
+
+/// Media type of `data` judged by its content; the server's content type is
+/// only trusted for captions, which have no reliable signature
+pub fn sniff_media_type(data: &[u8], declared: Option<&str>) -> Option<String> {
+    let sniffed = crate::media_policy::sniff_mime(data).or_else(|| {
+        // MP3 without an ID3 tag starts with a frame header
+        (data.len() >= 2 && data[0] == 0xFF && data[1] & 0xE0 == 0xE0).then_some("audio/mpeg")
+    });
+    match sniffed {
+        Some(mime)
+            if ["image/", "audio/", "video/"]
+                .iter()
+                .any(|family| mime.starts_with(family))
+                || mime == "text/vtt" =>
+        {
+            Some(mime.to_string())
+        }
+        Some(_) => None,
+        None => declared
+            .map(|mime| mime.split(';').next().unwrap_or_default().trim())
+            .filter(|mime| mime.eq_ignore_ascii_case("text/vtt"))
+            .filter(|_| std::str::from_utf8(data).is_ok())
+            .map(str::to_ascii_lowercase),
+    }
+}
+
+fn request_error(url: &Url, error: reqwest::Error) -> ExternalMediaError {
+    let kind = if error.is_timeout() {
+        ExternalMediaErrorKind::Timeout
+    } else {
+        ExternalMediaErrorKind::Network
+    };
+    ExternalMediaError::new(
+        kind,
+        url.as_str(),
+        format!("Failed to fetch {url}: {error}"),
+    )
+}
+
+/// Request `url` under the rules above, following redirects, and return the
+/// successful response with the URL that served it
+async fn get_public(url: &str) -> Result<(Url, reqwest::Response), ExternalMediaError> {
+    use ExternalMediaErrorKind::*;
+    let mut current = check_url(url)?;
+    for _ in 0..=MAX_REDIRECTS {
+        let addrs = resolve_public_async(&current).await?;
+        // Redirects are followed here, not by the client, so each hop is
+        // checked; pinning the checked addresses stops a second DNS answer
+        // from pointing somewhere else
+        let client = reqwest::Client::builder()
+            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+            .timeout(FETCH_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(current.host_str().unwrap_or_default(), &addrs)
+            .build()
+            .map_err(|e| {
+                ExternalMediaError::new(Network, url, format!("Failed to create HTTP client: {e}"))
+            })?;
+        let response = client
+            .get(current.clone())
+            .send()
+            .await
+            .map_err(|e| request_error(&current, e))?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| current.join(location).ok())
+                .ok_or_else(|| {
+                    ExternalMediaError::new(
+                        InvalidUrl,
+                        current.as_str(),
+                        format!("{status} redirect without a valid location"),
+                    )
+                })?;
+            current = check_url(location.as_str())?;
+            continue;
+        }
+        if !status.is_success() {
+            let mut error = ExternalMediaError::new(
+                HttpStatus,
+                current.as_str(),
+                format!("HTTP error: {status}"),
+            );
+            error.status = Some(status.as_u16());
+            return Err(error);
+        }
+        return Ok((current, response));
+    }
+    Err(ExternalMediaError::new(
+        TooManyRedirects,
+        url,
+        format!("More than {MAX_REDIRECTS} redirects"),
+    ))
+}
+
+/// Whether `url` answers with a success status, under the same rules as a
+/// download; the body isn't read
+pub async fn is_reachable(url: &str) -> bool {
+    get_public(url).await.is_ok()
+}
+
+/// Download `url` under the rules above, accepting at most `max_bytes`
+pub async fn fetch_external_media(
+    url: &str,
+    max_bytes: usize,
+) -> Result<ExternalMedia, ExternalMediaError> {
+    use ExternalMediaErrorKind::*;
+    let too_large = || {
+        ExternalMediaError::new(
+            TooLarge,
+            url,
+            format!("File too large (max {max_bytes} bytes)"),
+        )
+    };
+
+    let (current, mut response) = get_public(url).await?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+    let declared = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    // Read in chunks so a server that doesn't announce its length is cut off at the cap
+    let mut data = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| request_error(&current, e))?
+    {
+        if data.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    let mime_type = sniff_media_type(&data, declared.as_deref()).ok_or_else(|| {
+        ExternalMediaError::new(
+            TypeNotAllowed,
+            current.as_str(),
+            format!(
+                "Content served as {} isn't an image, audio, video or caption file",
+                declared.as_deref().unwrap_or("unknown type")
+            ),
+        )
+    })?;
+    Ok(ExternalMedia { data, mime_type })
+}
+
+/// Download an image or other media file from a public URL, returned as
+/// base64. `attribution` must name a license. Fetch errors, and reaching
+/// the daily download limit, are [`ExternalMediaError`] JSON.
+#[tauri::command]
+pub async fn download_external_media(
+    services: tauri::State<'_, AppServices>,
+    url: String,
+    attribution: Option<MediaAttribution>,
+) -> Result<DownloadImageResponse, String> {
+    use base64::{engine::general_purpose, Engine as _};
+    require_license(&url, attribution.as_ref())?;
+    let day = today(&services);
+    check_quota(MEDIA_DOWNLOAD_PROVIDER, day).map_err(|message| {
+        ExternalMediaError::new(ExternalMediaErrorKind::QuotaExceeded, &url, message)
+    })?;
+    let media = fetch_external_media(&url, MAX_EXTERNAL_MEDIA_BYTES).await;
+    let _ = record_usage(MEDIA_DOWNLOAD_PROVIDER, day, 1, 0);
+    let media = media?;
+    Ok(DownloadImageResponse {
+        base64_data: general_purpose::STANDARD.encode(&media.data),
+        content_type: media.mime_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_must_be_public_http() {
+        assert!(check_url("https://images.example.com/photo.jpg").is_ok());
+        assert!(check_url("http://93.184.216.34/photo.jpg").is_ok());
+
+        let kind = |url: &str| check_url(url).unwrap_err().kind;
+        assert_eq!(
+            kind("file:///etc/passwd"),
+            ExternalMediaErrorKind::SchemeNotAllowed
+        );
+        assert_eq!(
+            kind("ftp://example.com/a.png"),
+            ExternalMediaErrorKind::SchemeNotAllowed
+        );
+        assert_eq!(kind("not a url"), ExternalMediaErrorKind::InvalidUrl);
+        assert_eq!(
+            kind("https://user:pw@example.com/a.png"),
+            ExternalMediaErrorKind::InvalidUrl
+        );
+        for internal in [
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:192.168.0.1]/",
+        ] {
+            assert_eq!(
+                kind(internal),
+                ExternalMediaErrorKind::AddressNotAllowed,
+                "{internal}"
+            );
+        }
+
+        let json = String::from(check_url("http://127.0.0.1/a.png").unwrap_err());
+        assert!(json.contains(r#""errorType":"externalMedia""#));
+        assert!(json.contains(r#""kind":"addressNotAllowed""#));
+    }
+
+    #[tokio::test]
+    async fn test_names_resolving_to_internal_addresses_are_refused() {
+        let url = check_url("http://localhost:8080/admin.png").unwrap();
+
+        let error = resolve_public_async(&url).await.unwrap_err();
+
+        assert_eq!(error.kind, ExternalMediaErrorKind::AddressNotAllowed);
+        assert!(!error.is_transient());
+    }
+
+    #[test]
+    fn test_content_must_look_like_media() {
+        assert_eq!(
+            sniff_media_type(b"\x89PNG\r\n\x1a\n....", Some("text/html")).as_deref(),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniff_media_type(&[0xFF, 0xFB, 0x90, 0x00], None).as_deref(),
+            Some("audio/mpeg")
+        );
+        assert_eq!(
+            sniff_media_type(
+                b"1\n00:00.000 --> 00:01.000\nHi",
+                Some("text/vtt; charset=utf-8")
+            )
+            .as_deref(),
+            Some("text/vtt")
+        );
+        // Served as an image, but a page or a program
+        assert_eq!(
+            sniff_media_type(b"<html><body>Login</body></html>", Some("image/png")),
+            None
+        );
+        assert_eq!(sniff_media_type(b"MZ\x90\x00", Some("image/jpeg")), None);
+    }
+}
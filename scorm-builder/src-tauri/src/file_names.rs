@@ -0,0 +1,230 @@
+//! File names that survive a trip through a ZIP.
+//!
+//! Media folders can hold names that aren't valid UTF-8, or that contain
+//! control characters, invisible formatting characters or characters Windows
+//! refuses in file names. Exports don't fail on them: the ZIP entry gets a
+//! transliterated name with a short hash of the original to keep it unique,
+//! and the original goes into `file-names.json` at the root of the ZIP so
+//! imports can restore it.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+
+/// Entry at the root of exported ZIPs mapping entry paths to original names
+pub const FILE_NAME_MAP: &str = "file-names.json";
+
+/// Characters Windows doesn't allow in file names
+const RESERVED_CHARS: &str = r#"<>:"/\|?*"#;
+
+/// A file name as the operating system spelled it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "encoding", content = "name", rename_all = "snake_case")]
+pub enum OriginalName {
+    Utf8(String),
+    /// Bytes of a Unix name, base64 encoded
+    UnixBytes(String),
+    /// UTF-16 code units of a Windows name
+    WindowsWide(Vec<u16>),
+}
+
+impl OriginalName {
+    pub fn of(name: &OsStr) -> Self {
+        match name.to_str() {
+            Some(text) => Self::Utf8(text.to_string()),
+            None => Self::not_utf8(name),
+        }
+    }
+
+    #[cfg(unix)]
+    fn not_utf8(name: &OsStr) -> Self {
+        use base64::Engine;
+        use std::os::unix::ffi::OsStrExt;
+        Self::UnixBytes(base64::engine::general_purpose::STANDARD.encode(name.as_bytes()))
+    }
+
+    #[cfg(windows)]
+    fn not_utf8(name: &OsStr) -> Self {
+        use std::os::windows::ffi::OsStrExt;
+        Self::WindowsWide(name.encode_wide().collect())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn not_utf8(name: &OsStr) -> Self {
+        Self::Utf8(name.to_string_lossy().into_owned())
+    }
+
+    /// The name on this platform, or None when it can't be spelled here
+    /// (Unix bytes on Windows, for example)
+    pub fn to_os_string(&self) -> Option<OsString> {
+        match self {
+            Self::Utf8(text) => Some(OsString::from(text)),
+            Self::UnixBytes(encoded) => unix_name(encoded),
+            Self::WindowsWide(wide) => windows_name(wide),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn unix_name(encoded: &str) -> Option<OsString> {
+    use base64::Engine;
+    use std::os::unix::ffi::OsStringExt;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+        .map(OsString::from_vec)
+}
+
+#[cfg(not(unix))]
+fn unix_name(encoded: &str) -> Option<OsString> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()?;
+    String::from_utf8(bytes).ok().map(OsString::from)
+}
+
+#[cfg(windows)]
+fn windows_name(wide: &[u16]) -> Option<OsString> {
+    use std::os::windows::ffi::OsStringExt;
+    Some(OsString::from_wide(wide))
+}
+
+#[cfg(not(windows))]
+fn windows_name(wide: &[u16]) -> Option<OsString> {
+    String::from_utf16(wide).ok().map(OsString::from)
+}
+
+/// Zero-width and bidirectional formatting characters, which make a name
+/// display differently from what it is
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FEFF}')
+}
+
+fn is_portable_char(c: char) -> bool {
+    !c.is_control() && !is_invisible(c) && !RESERVED_CHARS.contains(c) && c != '\u{FFFD}'
+}
+
+/// Whether `name` can be a ZIP entry name as it is and extract the same everywhere
+fn is_portable(name: &str) -> bool {
+    !name.is_empty() && !name.ends_with(['.', ' ']) && name.chars().all(is_portable_char)
+}
+
+/// `name` with the characters a ZIP entry can't portably hold replaced by `_`
+pub fn portable_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if is_portable_char(c) { c } else { '_' })
+        .collect();
+    cleaned.trim_end_matches(['.', ' ']).to_string()
+}
+
+/// Portable spelling of `name`: offending characters become `_` and a hash of
+/// the original goes before the extension, so two names never collide
+fn transliterate(name: &OsStr, original: &OriginalName) -> String {
+    let cleaned = portable_name(&name.to_string_lossy());
+    let key = serde_json::to_string(original).unwrap_or_default();
+    let hash: String = Sha256::digest(key.as_bytes())[..4]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    match cleaned.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.contains(' ') => {
+            format!("{stem}~{hash}.{extension}")
+        }
+        _ => format!("{cleaned}~{hash}"),
+    }
+}
+
+/// Original names of ZIP entries that had to be renamed, by entry path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FileNameMap(BTreeMap<String, OriginalName>);
+
+impl FileNameMap {
+    /// Path of the entry for `name` inside `dir` (`""` for the root),
+    /// remembering the original when the name had to change
+    pub fn entry_path(&mut self, dir: &str, name: &OsStr) -> String {
+        let join = |file_name: &str| {
+            if dir.is_empty() {
+                file_name.to_string()
+            } else {
+                format!("{}/{file_name}", dir.trim_end_matches('/'))
+            }
+        };
+        if let Some(text) = name.to_str().filter(|text| is_portable(text)) {
+            return join(text);
+        }
+        let original = OriginalName::of(name);
+        let path = join(&transliterate(name, &original));
+        self.0.insert(path.clone(), original);
+        path
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize file names: {e}"))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse {FILE_NAME_MAP}: {e}"))
+    }
+
+    /// The name to restore for the entry at `path`; None keeps the entry's own name
+    pub fn original(&self, path: &str) -> Option<OsString> {
+        self.0.get(path).and_then(OriginalName::to_os_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_names_are_kept_and_others_mapped() {
+        let mut names = FileNameMap::default();
+        assert_eq!(
+            names.entry_path("42/media", OsStr::new("image-0.bin")),
+            "42/media/image-0.bin"
+        );
+        assert_eq!(
+            names.entry_path("42/media", OsStr::new("café déjà vu.png")),
+            "42/media/café déjà vu.png"
+        );
+        assert!(names.is_empty());
+
+        let tricky = OsStr::new("report\u{202E}fdp.exe:v2.png");
+        let path = names.entry_path("42/media", tricky);
+        assert!(path.starts_with("42/media/report_fdp.exe_v2~"), "{path}");
+        assert!(path.ends_with(".png"));
+        assert_ne!(
+            path,
+            names.entry_path("42/media", OsStr::new("report\u{200B}fdp.exe?v2.png"))
+        );
+        assert_eq!(names.original(&path), Some(tricky.to_os_string()));
+
+        let restored = FileNameMap::from_json(&names.to_json().unwrap()).unwrap();
+        assert_eq!(restored, names);
+        assert_eq!(restored.original("42/media/image-0.bin"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_names_round_trip() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let latin1 = OsStr::from_bytes(b"r\xe9sum\xe9.pdf");
+        let mut names = FileNameMap::default();
+        let path = names.entry_path("", latin1);
+        assert!(
+            path.starts_with("r_sum_~") && path.ends_with(".pdf"),
+            "{path}"
+        );
+        assert_eq!(names.original(&path).as_deref(), Some(latin1));
+    }
+}
@@ -0,0 +1,264 @@
+//! Per-project lock files so two app instances never edit the same project.
+//!
+//! Opening a project for editing writes `Name_id.scormproj.lock` next to it,
+//! naming this instance. The holder refreshes the lock every
+//! `LOCK_HEARTBEAT_SECS`; a lock that hasn't been refreshed for
+//! `LOCK_STALE_SECS` was left behind by an instance that crashed and may be
+//! claimed. Another instance can take a live lock over explicitly, after which
+//! the previous holder's saves fail instead of overwriting the newer work.
+
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// How often the holder rewrites its locks
+pub const LOCK_HEARTBEAT_SECS: u64 = 30;
+
+/// Locks older than this are considered abandoned
+const LOCK_STALE_SECS: i64 = 120;
+
+/// Identifies this running app instance in lock files
+static INSTANCE_ID: Lazy<String> = Lazy::new(|| uuid::Uuid::new_v4().to_string());
+
+/// Projects this instance has locked
+static HELD_LOCKS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockInfo {
+    pub instance_id: String,
+    pub hostname: String,
+    pub pid: u32,
+    pub acquired_at: DateTime<Utc>,
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+impl LockInfo {
+    fn is_ours(&self) -> bool {
+        self.instance_id == *INSTANCE_ID
+    }
+
+    fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now - self.heartbeat_at > Duration::seconds(LOCK_STALE_SECS)
+    }
+}
+
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .or_else(|_| fs::read_to_string("/etc/hostname").map(|name| name.trim().to_string()))
+        .unwrap_or_else(|_| "unknown host".to_string())
+}
+
+fn held_locks() -> std::sync::MutexGuard<'static, HashSet<PathBuf>> {
+    HELD_LOCKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn lock_path(project_path: &Path) -> PathBuf {
+    project_path.with_extension("scormproj.lock")
+}
+
+/// The current lock on a project. An unreadable lock file counts as no lock.
+pub fn read_lock(project_path: &Path) -> Option<LockInfo> {
+    let content = fs::read_to_string(lock_path(project_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn open_elsewhere_error(holder: &LockInfo) -> String {
+    format!(
+        "Project is open in another instance on {} (since {}). \
+         Open it read-only or take it over to continue.",
+        holder.hostname,
+        holder.acquired_at.format("%Y-%m-%d %H:%M UTC")
+    )
+}
+
+fn lock_json(acquired_at: DateTime<Utc>, now: DateTime<Utc>) -> Result<String, String> {
+    let info = LockInfo {
+        instance_id: INSTANCE_ID.clone(),
+        hostname: hostname(),
+        pid: std::process::id(),
+        acquired_at,
+        heartbeat_at: now,
+    };
+    serde_json::to_string_pretty(&info).map_err(|e| format!("Failed to serialize lock: {e}"))
+}
+
+fn write_lock(
+    project_path: &Path,
+    acquired_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let json = lock_json(acquired_at, now)?;
+    let path = lock_path(project_path);
+    let tmp_path = path.with_extension("lock.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write project lock: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write project lock: {e}"))?;
+    held_locks().insert(path);
+    Ok(())
+}
+
+/// Lock the project for this instance. Fails with a "project is open in another
+/// instance" error while a live lock of another instance exists.
+pub fn acquire(project_path: &Path, now: DateTime<Utc>) -> Result<(), String> {
+    match read_lock(project_path) {
+        Some(holder) if holder.is_ours() => write_lock(project_path, holder.acquired_at, now),
+        Some(holder) if !holder.is_stale(now) => Err(open_elsewhere_error(&holder)),
+        Some(_) => write_lock(project_path, now, now),
+        None => {
+            // Write the lock beside its final name and hard link it into place:
+            // linking fails if the lock exists, so two instances opening at the
+            // same moment can't both win, and nobody reads a half-written lock
+            let path = lock_path(project_path);
+            let tmp_path = path.with_extension(format!("lock.{}.tmp", *INSTANCE_ID));
+            fs::write(&tmp_path, lock_json(now, now)?)
+                .map_err(|e| format!("Failed to write project lock: {e}"))?;
+            let linked = fs::hard_link(&tmp_path, &path);
+            let _ = fs::remove_file(&tmp_path);
+            match linked {
+                Ok(()) => {
+                    held_locks().insert(path);
+                    Ok(())
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    match read_lock(project_path) {
+                        Some(holder) if !holder.is_ours() => Err(open_elsewhere_error(&holder)),
+                        _ => write_lock(project_path, now, now),
+                    }
+                }
+                // File systems without hard links (FAT, some network shares)
+                Err(_) => write_lock(project_path, now, now),
+            }
+        }
+    }
+}
+
+/// Lock the project for this instance even though another instance holds it
+pub fn take_over(project_path: &Path, now: DateTime<Utc>) -> Result<(), String> {
+    if let Some(holder) = read_lock(project_path).filter(|holder| !holder.is_ours()) {
+        crate::commands_secure::log_debug(&format!(
+            "[instance_lock] Taking over {} from instance on {}",
+            project_path.display(),
+            holder.hostname
+        ));
+    }
+    write_lock(project_path, now, now)
+}
+
+/// Fails once another instance has taken over a project this instance locked
+pub fn ensure_still_held(project_path: &Path) -> Result<(), String> {
+    let path = lock_path(project_path);
+    if !held_locks().contains(&path) {
+        return Ok(());
+    }
+    match read_lock(project_path) {
+        Some(holder) if !holder.is_ours() => Err(format!(
+            "Project was taken over by another instance on {}. \
+             Your changes were not saved; reopen the project to continue.",
+            holder.hostname
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Give up this instance's lock on the project
+pub fn release(project_path: &Path) {
+    let path = lock_path(project_path);
+    held_locks().remove(&path);
+    if read_lock(project_path).is_some_and(|holder| holder.is_ours()) {
+        let _ = fs::remove_file(&path);
+    }
+}
+
+/// Keep this instance's locks from going stale; locks taken over by another
+/// instance are left alone
+pub fn refresh_held_locks(now: DateTime<Utc>) {
+    let held: Vec<PathBuf> = held_locks().iter().cloned().collect();
+    for path in held {
+        let project_path = path.with_extension("");
+        if let Some(lock) = read_lock(&project_path).filter(LockInfo::is_ours) {
+            if let Err(e) = write_lock(&project_path, lock.acquired_at, now) {
+                eprintln!("[instance_lock] {e}");
+            }
+        }
+    }
+}
+
+/// Drop every lock this instance holds, e.g. when the app exits
+pub fn release_all() {
+    let held: Vec<PathBuf> = held_locks().drain().collect();
+    for path in held {
+        if read_lock(&path.with_extension("")).is_some_and(|holder| holder.is_ours()) {
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn other_instance(project: &Path, heartbeat_at: DateTime<Utc>) {
+        let info = LockInfo {
+            instance_id: "other".to_string(),
+            hostname: "LAPTOP-2".to_string(),
+            pid: 1,
+            acquired_at: heartbeat_at,
+            heartbeat_at,
+        };
+        fs::write(lock_path(project), serde_json::to_string(&info).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_live_lock_of_another_instance_blocks_opening() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("Safety_1234567890.scormproj");
+        let now = Utc::now();
+
+        acquire(&project, now).unwrap();
+        assert!(read_lock(&project).unwrap().is_ours());
+        // Reopening in the same instance is fine
+        acquire(&project, now).unwrap();
+
+        other_instance(&project, now);
+        let error = acquire(&project, now).unwrap_err();
+        assert!(error.contains("open in another instance on LAPTOP-2"));
+
+        // ...until its heartbeat goes stale
+        acquire(&project, now + Duration::seconds(LOCK_STALE_SECS + 1)).unwrap();
+        release(&project);
+        assert!(!lock_path(&project).exists());
+    }
+
+    #[test]
+    fn test_take_over_stops_the_previous_holder() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("Safety_42.scormproj");
+        let now = Utc::now();
+
+        acquire(&project, now).unwrap();
+        assert!(ensure_still_held(&project).is_ok());
+
+        // Another instance takes the project over
+        other_instance(&project, now);
+        let error = ensure_still_held(&project).unwrap_err();
+        assert!(error.contains("taken over"));
+
+        // Our heartbeat must not steal it back, and releasing leaves their lock
+        refresh_held_locks(now);
+        assert_eq!(read_lock(&project).unwrap().instance_id, "other");
+        release(&project);
+        assert!(lock_path(&project).exists());
+
+        take_over(&project, now).unwrap();
+        assert!(read_lock(&project).unwrap().is_ours());
+        release(&project);
+    }
+}
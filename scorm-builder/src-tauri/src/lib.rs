@@ -1,44 +1,146 @@
+mod ai_assist;
 mod api_keys;
+mod api_quota;
+mod app_profile;
+mod app_services;
+mod author_notes;
 mod backup_recovery;
+mod build_history;
+mod build_progress;
+mod clip_timing;
 mod commands;
 mod commands_secure;
+mod content_hash;
+mod content_pages;
+mod course_content;
+mod course_outline;
+mod course_structure;
+mod disk_space;
+mod display_time;
+mod embedded_media_migration;
+mod external_media;
+mod file_names;
+mod instance_lock;
+mod link_checker;
 mod localstorage_migration;
+mod media_alignment;
+mod media_download;
+mod media_names;
+mod media_policy;
 mod media_storage;
+mod media_usage;
+mod messages;
+mod narration_ssml;
+mod metadata_policy;
 mod media_page_id_migration;
+mod nightly_archive;
+mod page_thumbnails;
+mod project_encryption;
+mod project_index;
+mod project_listing;
+mod project_operations;
+mod project_statistics;
 mod project_storage;
+mod project_access;
+mod project_adoption;
 mod project_export_import;
+mod publish;
+mod publish_webhook;
+mod question_gift;
 mod scorm;
 mod settings;
+mod stable_ids;
+mod stock_images;
+mod storage_budget;
+mod workspace_cleanup;
+mod workspace_health;
+mod workspace_statistics;
+
+// Extension points of the SCORM generator, for crates built with `generator-plugins`
+#[cfg(feature = "generator-plugins")]
+pub use scorm::generator_enhanced::GenerateScormRequest;
+#[cfg(feature = "generator-plugins")]
+pub use scorm::plugins::{register_plugin, GeneratorPlugin};
+
+use tauri::Manager;
 
 // Import only non-duplicate commands from commands.rs
 use commands::{
-    create_project, generate_scorm, generate_scorm_enhanced, get_app_settings, save_app_settings,
+    check_build_up_to_date, create_project, generate_scorm, generate_scorm_enhanced, generate_scorm_preview, render_page_preview, decode_suspend_data, extract_source_snapshot, get_app_settings, inspect_scorm_package, list_template_partials, save_app_settings,
     set_projects_dir, take_screenshot, save_workflow_data, get_projects_directory, read_file_binary,
     clean_workflow_files, export_workflow_zip, save_workflow_json,
 };
 // Import secure versions of project commands and other secure commands
 use commands_secure::{
     append_to_log, check_project_exists, delete_api_keys, delete_project, get_cli_args, get_projects_dir, list_projects,
-    load_api_keys, load_project, export_project_data, get_media_for_export, rename_project, save_api_keys, save_project, unsafe_download_image,
+    load_api_keys, load_project, open_project_readonly, take_over_project, close_project, export_project_data, get_media_for_export, rename_project, save_api_keys, save_project, set_content_layout,
     diagnose_projects_directory,
 };
+use app_profile::{export_app_profile, import_app_profile};
+use author_notes::export_author_notes;
+use build_history::{compare_package_versions, get_build_history};
+use course_outline::{export_course_outline, import_course_outline};
+use course_structure::{delete_topic, insert_topic, move_topic};
 use backup_recovery::{
-    check_recovery, cleanup_old_backups, create_backup, recover_from_backup,
+    accept_recovery_item, check_recovery, cleanup_old_backups, create_backup, discard_recovery_item,
+    get_recovery_report, recover_all, recover_from_backup, save_autosave_draft,
 };
 use localstorage_migration::{
     clear_recent_files, migrate_from_localstorage,
 };
 use media_storage::{
     delete_media, get_all_project_media, get_all_project_media_metadata, get_media, store_media, store_media_base64,
-    get_media_batch, media_exists_batch, clean_duplicate_media,
+    get_media_batch, media_exists_batch, clean_duplicate_media, update_media_alt_text,
+    update_media_metadata_batch, find_animated_gifs,
 };
+use link_checker::check_external_links;
+use page_thumbnails::render_page_thumbnail;
+use project_adoption::adopt_external_project;
+use project_operations::{cancel_project_operation, get_project_operation};
+use narration_ssml::{add_lexicon_entry, generate_narration_ssml, list_lexicon, remove_lexicon_entry};
+use media_alignment::check_project_integrity;
+use media_policy::{get_media_policy, set_media_policy};
+use storage_budget::{get_storage_budget, set_storage_budget};
+use metadata_policy::{get_metadata_audit, get_metadata_policy, set_metadata_policy};
+use display_time::{get_time_display, set_time_display};
+use disk_space::ensure_free_space;
+use media_usage::get_media_usage;
+use clip_timing::check_clip_timing;
+use ai_assist::{delete_ai_provider, generate_content_with_ai, get_ai_provider, save_ai_provider};
+use api_quota::{get_api_limits, get_api_usage, set_api_limits};
+use embedded_media_migration::migrate_embedded_media;
+use external_media::download_external_media;
+use media_download::download_media_batch;
+use project_encryption::{
+    disable_project_encryption, enable_project_encryption, get_project_encryption, lock_project,
+    unlock_project,
+};
+use project_index::{get_project_tags, list_project_tags, set_project_status, set_project_tags};
+use project_listing::refresh_project_list;
+use project_statistics::get_project_statistics;
+use question_gift::{export_questions_gift, import_questions_gift};
+use messages::get_message_catalog;
+use nightly_archive::{get_backup_status, set_archive_schedule};
 use media_page_id_migration::{
     migrate_media_page_ids, validate_media_page_ids
 };
 use project_export_import::{
-    create_project_zip, create_project_zip_with_progress, extract_project_zip,
-    save_project_with_media, update_imported_media_paths,
+    create_project_zip, create_project_zip_with_progress, export_all_projects, extract_project_zip,
+    extract_project_zip_with_progress, save_project_with_media, update_imported_media_paths,
+};
+use publish::{delete_sftp_credentials, publish_package, save_sftp_credentials};
+use publish_webhook::{
+    delete_publish_webhook, get_publish_webhook, get_publish_webhook_log, save_publish_webhook,
 };
+use stable_ids::migrate_stable_ids;
+use stock_images::search_stock_images;
+use workspace_cleanup::cleanup_workspace;
+use workspace_health::workspace_health_check;
+use workspace_statistics::get_workspace_statistics;
+use scorm::source_snapshot::{
+    delete_snapshot_passphrase, has_snapshot_passphrase, save_snapshot_passphrase,
+};
+use scorm::template_dev::set_template_dev_mode;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -53,6 +155,8 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_persisted_scope::init())
+        .manage(app_services::AppServices::default())
+        .manage(project_operations::ProjectOperations::default())
         .setup(|app| {
             // Initialize the frontend logger with the app handle
             commands_secure::init_frontend_logger(app.handle().clone());
@@ -60,16 +164,52 @@ pub fn run() {
             // Test that logging is working
             commands_secure::log_to_frontend("INFO", "SCORM Builder starting up - Rust logger initialized");
 
+            // Keep this instance's project locks fresh so other instances don't treat them as abandoned
+            std::thread::spawn(|| loop {
+                std::thread::sleep(std::time::Duration::from_secs(instance_lock::LOCK_HEARTBEAT_SECS));
+                instance_lock::refresh_held_locks(chrono::Utc::now());
+            });
+
+            // Notice edits to template overrides while template dev mode is on
+            let template_app = app.handle().clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(std::time::Duration::from_millis(scorm::template_dev::POLL_INTERVAL_MS));
+                scorm::template_dev::poll(&template_app);
+            });
+
+            // Make the nightly archive of the projects directory when one is scheduled
+            let archive_app = app.handle().clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(std::time::Duration::from_secs(nightly_archive::CHECK_INTERVAL_SECS));
+                let now = archive_app.state::<app_services::AppServices>().now();
+                nightly_archive::run_if_due(now.with_timezone(&chrono::Local));
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet,
             create_project,
             save_project,
+            set_content_layout,
             load_project,
+            get_project_encryption,
+            enable_project_encryption,
+            unlock_project,
+            lock_project,
+            disable_project_encryption,
+            open_project_readonly,
+            take_over_project,
+            close_project,
             export_project_data,
+            adopt_external_project,
             get_media_for_export,
             list_projects,
+            refresh_project_list,
+            get_project_tags,
+            set_project_tags,
+            set_project_status,
+            list_project_tags,
             check_project_exists,
             delete_project,
             rename_project,
@@ -77,13 +217,49 @@ pub fn run() {
             set_projects_dir,
             get_app_settings,
             save_app_settings,
+            get_message_catalog,
+            get_backup_status,
+            set_archive_schedule,
+            export_app_profile,
+            import_app_profile,
             get_cli_args,
             store_media,
             store_media_base64,
+            check_project_integrity,
+            check_external_links,
+            list_lexicon,
+            add_lexicon_entry,
+            remove_lexicon_entry,
+            generate_narration_ssml,
+            get_media_policy,
+            set_media_policy,
+            get_storage_budget,
+            set_storage_budget,
+            get_metadata_policy,
+            set_metadata_policy,
+            get_metadata_audit,
+            get_time_display,
+            set_time_display,
             get_all_project_media,
             get_all_project_media_metadata,
             delete_media,
+            get_media_usage,
+            get_project_statistics,
+            download_media_batch,
+            export_course_outline,
+            import_course_outline,
+            export_questions_gift,
+            import_questions_gift,
+            export_author_notes,
+            move_topic,
+            delete_topic,
+            insert_topic,
+            migrate_stable_ids,
             get_media,
+            update_media_alt_text,
+            update_media_metadata_batch,
+            check_clip_timing,
+            find_animated_gifs,
             get_media_batch,
             media_exists_batch,
             save_api_keys,
@@ -91,16 +267,50 @@ pub fn run() {
             delete_api_keys,
             generate_scorm,
             generate_scorm_enhanced,
+            get_project_operation,
+            cancel_project_operation,
+            generate_scorm_preview,
+            render_page_preview,
+            render_page_thumbnail,
+            list_template_partials,
+            set_template_dev_mode,
+            inspect_scorm_package,
+            decode_suspend_data,
+            extract_source_snapshot,
+            save_snapshot_passphrase,
+            delete_snapshot_passphrase,
+            has_snapshot_passphrase,
+            get_build_history,
+            compare_package_versions,
+            check_build_up_to_date,
             append_to_log,
             create_backup,
             check_recovery,
             recover_from_backup,
             cleanup_old_backups,
+            get_recovery_report,
+            recover_all,
+            accept_recovery_item,
+            discard_recovery_item,
+            save_autosave_draft,
+            cleanup_workspace,
+            workspace_health_check,
+            ensure_free_space,
+            get_workspace_statistics,
+            publish_package,
+            save_publish_webhook,
+            get_publish_webhook,
+            delete_publish_webhook,
+            get_publish_webhook_log,
+            save_sftp_credentials,
+            delete_sftp_credentials,
             migrate_from_localstorage,
             clear_recent_files,
             create_project_zip,
             create_project_zip_with_progress,
+            export_all_projects,
             extract_project_zip,
+            extract_project_zip_with_progress,
             save_project_with_media,
             update_imported_media_paths,
             take_screenshot,
@@ -110,12 +320,26 @@ pub fn run() {
             clean_workflow_files,
             export_workflow_zip,
             save_workflow_json,
-            unsafe_download_image,
+            download_external_media,
+            save_ai_provider,
+            get_ai_provider,
+            delete_ai_provider,
+            generate_content_with_ai,
+            get_api_usage,
+            get_api_limits,
+            set_api_limits,
+            search_stock_images,
             diagnose_projects_directory,
             migrate_media_page_ids,
             validate_media_page_ids,
+            migrate_embedded_media,
             clean_duplicate_media
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                instance_lock::release_all();
+            }
+        });
 }
@@ -0,0 +1,464 @@
+//! Dead external links in course content.
+//!
+//! Courses cite articles and embed videos that move or disappear long after
+//! the course is written. `check_external_links` finds every http(s) URL in a
+//! project's pages, media and questions and asks each server about it once,
+//! several at a time, following redirects by hand so the chain is known.
+//! Servers that refuse automated requests are reported as blocked rather
+//! than broken, since a browser usually gets through. Hosts on the local
+//! machine or network are never contacted: an imported project could
+//! otherwise probe the author's LAN.
+
+use crate::course_content::CourseContent;
+use crate::external_media::{resolve_public, ExternalMediaError, ExternalMediaErrorKind};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use ts_rs::TS;
+use url::Url;
+
+/// Links checked at once
+const MAX_CHECKS: usize = 8;
+
+/// Redirects followed before a link counts as broken
+const MAX_REDIRECTS: usize = 5;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a link's server said about it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum LinkStatus {
+    Ok,
+    /// Answers after one or more redirects; worth updating to `final_url`
+    Redirected,
+    /// Missing, failing, unreachable or redirecting in circles
+    Broken,
+    /// The server refused the check (401, 403, 429), or the host is local or
+    /// private and was not contacted
+    Blocked,
+}
+
+/// One answer from a server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeResponse {
+    pub status: u16,
+    /// `Location` of a redirect
+    pub location: Option<String>,
+}
+
+/// Why a probe got no answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeError {
+    /// The host resolves to a local or private address and was not contacted
+    NotPublic(String),
+    Failed(String),
+}
+
+/// A single request without following redirects. `HttpLinkProbe` is the real
+/// implementation; tests substitute a fake.
+pub trait LinkProbe: Sync {
+    fn probe(&self, url: &str) -> Result<ProbeResponse, ProbeError>;
+}
+
+impl From<ExternalMediaError> for ProbeError {
+    fn from(error: ExternalMediaError) -> Self {
+        match error.kind {
+            ExternalMediaErrorKind::AddressNotAllowed => ProbeError::NotPublic(error.message),
+            _ => ProbeError::Failed(error.message),
+        }
+    }
+}
+
+/// Blocking HTTP client; call it off the async runtime
+pub struct HttpLinkProbe;
+
+impl HttpLinkProbe {
+    pub fn new() -> Result<Self, String> {
+        Ok(Self)
+    }
+
+    /// A client that only connects to the addresses already checked, so a
+    /// second DNS answer can't point somewhere else
+    fn client(
+        &self,
+        url: &Url,
+        addrs: &[SocketAddr],
+    ) -> Result<reqwest::blocking::Client, ProbeError> {
+        reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent(concat!("SCORM Builder link checker/", env!("CARGO_PKG_VERSION")))
+            .resolve_to_addrs(url.host_str().unwrap_or_default(), addrs)
+            .build()
+            .map_err(|e| ProbeError::Failed(format!("Failed to create HTTP client: {e}")))
+    }
+
+    fn send(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<ProbeResponse, ProbeError> {
+        let response = request.send().map_err(|e| {
+            ProbeError::Failed(if e.is_timeout() {
+                "Timed out".to_string()
+            } else {
+                format!("Request failed: {e}")
+            })
+        })?;
+        Ok(ProbeResponse {
+            status: response.status().as_u16(),
+            location: response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .map(String::from),
+        })
+    }
+}
+
+impl LinkProbe for HttpLinkProbe {
+    fn probe(&self, url: &str) -> Result<ProbeResponse, ProbeError> {
+        let parsed = Url::parse(url).map_err(|e| ProbeError::Failed(format!("Invalid URL: {e}")))?;
+        let addrs = resolve_public(&parsed)?;
+        let client = self.client(&parsed, &addrs)?;
+        let response = self.send(client.head(parsed.clone()))?;
+        // Some servers refuse HEAD but serve the page
+        if matches!(response.status, 403 | 404 | 405 | 501) {
+            return self.send(client.get(parsed));
+        }
+        Ok(response)
+    }
+}
+
+/// An external link and what its server said about it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct LinkCheck {
+    pub url: String,
+    pub status: LinkStatus,
+    /// Status of the last answer, when there was one
+    pub http_status: Option<u16>,
+    /// Where the redirects ended, for `redirected` links
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub final_url: Option<String>,
+    /// Every hop after `url`, in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[ts(as = "Option<Vec<String>>", optional)]
+    pub redirects: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<String>,
+    /// Pages the link appears on (`assessment` for the assessment questions)
+    pub pages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+pub struct LinkCheckReport {
+    pub links: Vec<LinkCheck>,
+    pub ok: usize,
+    pub redirected: usize,
+    pub broken: usize,
+    pub blocked: usize,
+}
+
+impl LinkCheckReport {
+    fn new(links: Vec<LinkCheck>) -> Self {
+        let count = |status| links.iter().filter(|link| link.status == status).count();
+        Self {
+            ok: count(LinkStatus::Ok),
+            redirected: count(LinkStatus::Redirected),
+            broken: count(LinkStatus::Broken),
+            blocked: count(LinkStatus::Blocked),
+            links,
+        }
+    }
+}
+
+/// Characters that end a URL written in text or an attribute
+fn ends_url(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`')
+}
+
+/// http(s) URLs in `text`, with HTML-escaped ampersands restored and
+/// trailing punctuation left out
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("http://").into_iter().chain(rest.find("https://")).min() {
+        let candidate = &rest[start..];
+        let end = candidate.find(ends_url).unwrap_or(candidate.len());
+        let url = candidate[..end]
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}'])
+            .replace("&amp;", "&");
+        if Url::parse(&url).is_ok_and(|parsed| parsed.host_str().is_some()) {
+            urls.push(url);
+        }
+        rest = &candidate[end.max(1)..];
+    }
+    urls
+}
+
+fn collect_urls(value: &Value, urls: &mut Vec<String>) {
+    match value {
+        Value::String(text) => urls.extend(extract_urls(text)),
+        Value::Array(items) => items.iter().for_each(|item| collect_urls(item, urls)),
+        Value::Object(fields) => fields.values().for_each(|field| collect_urls(field, urls)),
+        _ => {}
+    }
+}
+
+/// Every external link in the course, with the pages it appears on, in URL order
+pub fn find_links(course_value: &Value) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let course = CourseContent::from_value(course_value)?;
+    let mut sections = Vec::new();
+    for (index, page) in course.pages().enumerate() {
+        let page_id = page.id.clone().unwrap_or_else(|| format!("page-{index}"));
+        sections.push((page_id, serde_json::to_value(page).map_err(|e| e.to_string())?));
+    }
+    if let Some(assessment) = &course.assessment {
+        let value = serde_json::to_value(assessment).map_err(|e| e.to_string())?;
+        sections.push(("assessment".to_string(), value));
+    }
+
+    let mut links: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (page_id, value) in sections {
+        let mut urls = Vec::new();
+        collect_urls(&value, &mut urls);
+        for url in urls {
+            let pages = links.entry(url).or_default();
+            if !pages.contains(&page_id) {
+                pages.push(page_id.clone());
+            }
+        }
+    }
+    Ok(links)
+}
+
+/// Follow `url` through its redirects and classify where it ends up
+pub fn check_link(url: &str, probe: &dyn LinkProbe) -> LinkCheck {
+    let mut check = LinkCheck {
+        url: url.to_string(),
+        status: LinkStatus::Broken,
+        http_status: None,
+        final_url: None,
+        redirects: Vec::new(),
+        error: None,
+        pages: Vec::new(),
+    };
+
+    let mut current = url.to_string();
+    loop {
+        let response = match probe.probe(&current) {
+            Ok(response) => response,
+            Err(ProbeError::NotPublic(error)) => {
+                check.status = LinkStatus::Blocked;
+                check.error = Some(error);
+                return check;
+            }
+            Err(ProbeError::Failed(error)) => {
+                check.error = Some(error);
+                return check;
+            }
+        };
+        check.http_status = Some(response.status);
+
+        match (response.status, response.location) {
+            (300..=399, Some(location)) => {
+                if check.redirects.len() == MAX_REDIRECTS {
+                    check.error = Some(format!("More than {MAX_REDIRECTS} redirects"));
+                    return check;
+                }
+                let next = match Url::parse(&current).and_then(|base| base.join(&location)) {
+                    Ok(next) => next.to_string(),
+                    Err(e) => {
+                        check.error = Some(format!("Invalid redirect to '{location}': {e}"));
+                        return check;
+                    }
+                };
+                if next == check.url || check.redirects.contains(&next) {
+                    check.error = Some("Redirect loop".to_string());
+                    return check;
+                }
+                check.redirects.push(next.clone());
+                current = next;
+            }
+            (200..=299, _) => {
+                if !check.redirects.is_empty() {
+                    check.status = LinkStatus::Redirected;
+                    check.final_url = Some(current);
+                } else {
+                    check.status = LinkStatus::Ok;
+                }
+                return check;
+            }
+            (401 | 403 | 429 | 999, _) => {
+                check.status = LinkStatus::Blocked;
+                return check;
+            }
+            (status, _) => {
+                check.error = Some(format!("HTTP {status}"));
+                return check;
+            }
+        }
+    }
+}
+
+/// Check each link once on a few threads, returning the results in URL order
+pub fn check_links(links: BTreeMap<String, Vec<String>>, probe: &dyn LinkProbe) -> LinkCheckReport {
+    let links: Vec<(String, Vec<String>)> = links.into_iter().collect();
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(links.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..links.len().min(MAX_CHECKS) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some((url, pages)) = links.get(index) else {
+                    break;
+                };
+                let check = LinkCheck {
+                    pages: pages.clone(),
+                    ..check_link(url, probe)
+                };
+                results.lock().unwrap_or_else(|e| e.into_inner()).push((index, check));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+    results.sort_by_key(|(index, _)| *index);
+    LinkCheckReport::new(results.into_iter().map(|(_, check)| check).collect())
+}
+
+/// Check every http(s) link in a project's course content
+#[tauri::command]
+pub async fn check_external_links(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<LinkCheckReport, String> {
+    let project_path = crate::media_usage::find_project_file(&projectId)
+        .ok_or_else(|| format!("Project not found: {projectId}"))?;
+    let project = crate::project_storage::load_project_file(&project_path)?;
+    let Some(course_value) = project.course_content else {
+        return Ok(LinkCheckReport::default());
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let links = find_links(&course_value)?;
+        let probe = HttpLinkProbe::new()?;
+        Ok(check_links(links, &probe))
+    })
+    .await
+    .map_err(|e| format!("Link check failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Answers from a fixed table; anything else is unreachable
+    struct FakeProbe(Vec<(&'static str, u16, Option<&'static str>)>);
+
+    impl LinkProbe for FakeProbe {
+        fn probe(&self, url: &str) -> Result<ProbeResponse, ProbeError> {
+            if url.starts_with("http://192.168.") {
+                return Err(ProbeError::NotPublic(
+                    "192.168.1.1 is a local or private address".to_string(),
+                ));
+            }
+            self.0
+                .iter()
+                .find(|(known, _, _)| *known == url)
+                .map(|(_, status, location)| ProbeResponse {
+                    status: *status,
+                    location: location.map(String::from),
+                })
+                .ok_or_else(|| ProbeError::Failed("Request failed: dns error".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_urls_are_found_in_html_and_text() {
+        assert_eq!(
+            extract_urls(r#"<a href="https://example.com/a?x=1&amp;y=2">docs</a> (see http://example.org/b)."#),
+            vec!["https://example.com/a?x=1&y=2", "http://example.org/b"]
+        );
+        assert!(extract_urls("media/image-0.png and https:// alone").is_empty());
+
+        let course = json!({
+            "welcomePage": { "id": "welcome", "content": "<p>Read https://example.com/a</p>" },
+            "topics": [{
+                "id": "topic-1",
+                "content": "<p>https://example.com/a</p>",
+                "media": [{ "id": "video-1", "type": "video", "url": "https://youtu.be/abc" }]
+            }],
+            "assessment": { "questions": [{ "question": "See https://example.com/q" }] }
+        });
+        let links = find_links(&course).unwrap();
+        assert_eq!(links["https://example.com/a"], vec!["welcome", "topic-1"]);
+        assert_eq!(links["https://youtu.be/abc"], vec!["topic-1"]);
+        assert_eq!(links["https://example.com/q"], vec!["assessment"]);
+    }
+
+    #[test]
+    fn test_links_are_classified() {
+        let probe = FakeProbe(vec![
+            ("https://ok.example/", 200, None),
+            ("http://moved.example/", 301, Some("https://moved.example/new")),
+            ("https://moved.example/new", 200, None),
+            ("https://gone.example/", 404, None),
+            ("https://private.example/", 403, None),
+            ("https://loop.example/a", 302, Some("/b")),
+            ("https://loop.example/b", 302, Some("/a")),
+            ("https://lan.example/", 302, Some("http://192.168.1.1/admin")),
+        ]);
+        let links: BTreeMap<String, Vec<String>> = [
+            "https://ok.example/",
+            "http://moved.example/",
+            "https://gone.example/",
+            "https://private.example/",
+            "https://loop.example/a",
+            "https://down.example/",
+            "https://lan.example/",
+        ]
+        .into_iter()
+        .map(|url| (url.to_string(), vec!["topic-1".to_string()]))
+        .collect();
+
+        let report = check_links(links, &probe);
+
+        let statuses: BTreeMap<&str, LinkStatus> = report
+            .links
+            .iter()
+            .map(|link| (link.url.as_str(), link.status))
+            .collect();
+        assert_eq!(statuses["https://ok.example/"], LinkStatus::Ok);
+        assert_eq!(statuses["http://moved.example/"], LinkStatus::Redirected);
+        assert_eq!(statuses["https://gone.example/"], LinkStatus::Broken);
+        assert_eq!(statuses["https://private.example/"], LinkStatus::Blocked);
+        assert_eq!(statuses["https://loop.example/a"], LinkStatus::Broken);
+        assert_eq!(statuses["https://down.example/"], LinkStatus::Broken);
+        assert_eq!(statuses["https://lan.example/"], LinkStatus::Blocked);
+        assert_eq!((report.ok, report.redirected, report.broken, report.blocked), (1, 1, 3, 2));
+
+        let moved = report.links.iter().find(|link| link.url == "http://moved.example/").unwrap();
+        assert_eq!(moved.final_url.as_deref(), Some("https://moved.example/new"));
+        assert_eq!(moved.pages, vec!["topic-1"]);
+        let looping = report.links.iter().find(|link| link.url == "https://loop.example/a").unwrap();
+        assert_eq!(looping.error.as_deref(), Some("Redirect loop"));
+    }
+
+    #[test]
+    fn test_local_hosts_are_not_contacted() {
+        let check = check_link("http://127.0.0.1:8080/status", &HttpLinkProbe::new().unwrap());
+        assert_eq!(check.status, LinkStatus::Blocked);
+        assert_eq!(check.http_status, None);
+        assert!(check.error.unwrap().contains("local or private"));
+    }
+}
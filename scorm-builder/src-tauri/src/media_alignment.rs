@@ -0,0 +1,192 @@
+//! Media placed on the wrong page of a project.
+//!
+//! Older exports duplicated the objectives audio and captions into the first
+//! topic, and could drop a topic's audio from its media list while keeping the
+//! file. Both are found from the course content and the media metadata, which
+//! names each item's page. Import fixes them (unless asked not to), and
+//! `check_project_integrity` reports them for an existing project without
+//! changing it; either way each correction is listed. The integrity check
+//! also lists media metadata whose page_id doesn't match its media ID.
+
+use crate::course_content::CourseContent;
+use crate::media_page_id_migration::{validate_media_dir, PageIdValidationReport};
+use crate::stable_ids::attach_media_by_page_id;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum MediaCorrectionAction {
+    /// Objectives audio or captions also listed on a topic, removed there
+    RemovedDuplicate,
+    /// Audio or captions whose metadata names a topic missing from its media list
+    AttachedFromMetadata,
+}
+
+/// One media item moved, or to be moved, to its page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MediaCorrection {
+    pub page_id: String,
+    pub media_id: String,
+    pub media_type: String,
+    pub action: MediaCorrectionAction,
+}
+
+/// Remove objectives media duplicated onto topics and attach media to the
+/// topic its metadata in `media_dir` names, returning what changed
+pub fn fix_media_alignment(
+    course_value: &mut Value,
+    media_dir: Option<&Path>,
+) -> Result<Vec<MediaCorrection>, String> {
+    let mut course = CourseContent::from_value(course_value)?;
+    let mut corrections = Vec::new();
+
+    let objectives_media: Vec<String> = course
+        .learning_objectives_page
+        .iter()
+        .flat_map(|page| page.media())
+        .filter(|media| matches!(media.kind(), "audio" | "caption"))
+        .map(|media| media.id.clone())
+        .collect();
+
+    for (topic_index, topic) in course.topics.iter_mut().enumerate() {
+        let page_id = topic
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("topic-{topic_index}"));
+        if let Some(media) = &mut topic.media {
+            media.retain(|item| {
+                if !objectives_media.contains(&item.id) {
+                    return true;
+                }
+                corrections.push(MediaCorrection {
+                    page_id: page_id.clone(),
+                    media_id: item.id.clone(),
+                    media_type: item.kind().to_string(),
+                    action: MediaCorrectionAction::RemovedDuplicate,
+                });
+                false
+            });
+        }
+    }
+
+    course.validate()?;
+    *course_value = course.to_value()?;
+
+    if let Some(media_dir) = media_dir {
+        corrections.extend(attach_media_by_page_id(course_value, media_dir).into_iter().map(
+            |attached| MediaCorrection {
+                page_id: attached.page_id,
+                media_id: attached.media_id,
+                media_type: attached.media_type,
+                action: MediaCorrectionAction::AttachedFromMetadata,
+            },
+        ));
+    }
+    Ok(corrections)
+}
+
+/// What `fix_media_alignment` would change, leaving `course_value` as it is
+pub fn check_media_alignment(
+    course_value: &Value,
+    media_dir: Option<&Path>,
+) -> Result<Vec<MediaCorrection>, String> {
+    fix_media_alignment(&mut course_value.clone(), media_dir)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectIntegrityReport {
+    pub project_id: String,
+    /// Corrections an import would make to the project's media
+    pub media_alignment: Vec<MediaCorrection>,
+    /// Media metadata naming the wrong page; `migrate_media_page_ids` fixes them
+    pub media_page_ids: PageIdValidationReport,
+}
+
+/// Check a saved project for media on the wrong page without changing it
+#[tauri::command]
+pub fn check_project_integrity(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<ProjectIntegrityReport, String> {
+    let project_path = crate::media_usage::find_project_file(&projectId)
+        .ok_or_else(|| format!("Project not found: {projectId}"))?;
+    let project = crate::project_storage::load_project_file(&project_path)?;
+    let media_dir = crate::media_storage::get_media_directory(&project.project.id)?;
+    let media_alignment = match &project.course_content {
+        Some(course_value) => check_media_alignment(course_value, Some(&media_dir))?,
+        None => Vec::new(),
+    };
+    let media_page_ids = validate_media_dir(&media_dir)?;
+    Ok(ProjectIntegrityReport {
+        project_id: project.project.id,
+        media_alignment,
+        media_page_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn course() -> Value {
+        json!({
+            "learningObjectivesPage": {
+                "id": "objectives",
+                "media": [{ "id": "audio-1", "type": "audio" }]
+            },
+            "topics": [
+                {
+                    "id": "topic-a",
+                    "media": [{ "id": "audio-1", "type": "audio" }, { "id": "image-3", "type": "image" }]
+                },
+                { "id": "topic-b", "media": [] }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_corrections_are_listed_and_check_leaves_content_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("audio-x.json"),
+            r#"{"type": "audio", "page_id": "topic-b"}"#,
+        )
+        .unwrap();
+        let original = course();
+
+        let checked = check_media_alignment(&original, Some(temp_dir.path())).unwrap();
+        assert_eq!(original, course());
+
+        let mut fixed = course();
+        let corrections = fix_media_alignment(&mut fixed, Some(temp_dir.path())).unwrap();
+        assert_eq!(corrections, checked);
+        assert_eq!(
+            corrections,
+            vec![
+                MediaCorrection {
+                    page_id: "topic-a".to_string(),
+                    media_id: "audio-1".to_string(),
+                    media_type: "audio".to_string(),
+                    action: MediaCorrectionAction::RemovedDuplicate,
+                },
+                MediaCorrection {
+                    page_id: "topic-b".to_string(),
+                    media_id: "audio-x".to_string(),
+                    media_type: "audio".to_string(),
+                    action: MediaCorrectionAction::AttachedFromMetadata,
+                },
+            ]
+        );
+        assert_eq!(fixed["topics"][0]["media"], json!([{ "id": "image-3", "type": "image" }]));
+        assert!(fix_media_alignment(&mut fixed, Some(temp_dir.path())).unwrap().is_empty());
+    }
+}
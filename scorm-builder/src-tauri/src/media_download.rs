@@ -0,0 +1,448 @@
+//! Bulk import of remote media into a project.
+//!
+//! Pasting a list of image or audio URLs used to mean one download call per
+//! URL from the frontend, each returning the whole file as base64 to be
+//! stored in a second round trip. A batch is now
+//! downloaded here, a few files at a time, with transient failures retried,
+//! files over the size cap refused, and every download stored through
+//! `media_storage` as soon as it arrives. The result lists each URL with the
+//! media ID it was stored under or why it wasn't.
+//!
+//! Media from the web belongs to someone, so every item must say who and
+//! under which license before anything is fetched.
+
+use crate::api_quota::{check_quota, record_usage, today, MEDIA_DOWNLOAD_PROVIDER};
+use crate::app_services::AppServices;
+use crate::external_media::fetch_external_media;
+use crate::media_storage::{store_media, MediaAttribution, MediaMetadata};
+use crate::messages::Message;
+use crate::stable_ids::new_media_id;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use url::Url;
+
+/// Downloads in flight at once
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 4;
+/// Tries per URL, including the first
+pub const MAX_ATTEMPTS: u32 = 3;
+/// Largest file accepted
+pub const MAX_DOWNLOAD_BYTES: usize = 100 * 1024 * 1024;
+/// Wait before the first retry; doubled for each one after
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// A remote file to import onto a page
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadItem {
+    pub url: String,
+    pub page_id: String,
+    /// `image`, `audio`, `video` or `caption`
+    #[serde(rename = "type")]
+    pub media_type: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Credit and license from the search result it was picked from; required
+    #[serde(default)]
+    pub attribution: Option<MediaAttribution>,
+}
+
+/// Body and content type of a fetched URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Downloaded {
+    pub data: Vec<u8>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    /// Worth another try: network errors, timeouts, 408, 429 and 5xx
+    Transient(String),
+    Permanent(String),
+}
+
+/// What happened to one item of a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadOutcome {
+    pub url: String,
+    pub page_id: String,
+    /// ID the file was stored under; absent when it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    pub size: usize,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DownloadOutcome {
+    fn failed(item: &DownloadItem, error: String, attempts: u32) -> Self {
+        Self {
+            url: item.url.clone(),
+            page_id: item.page_id.clone(),
+            media_id: None,
+            mime_type: None,
+            size: 0,
+            attempts,
+            error: Some(error),
+        }
+    }
+}
+
+/// Fetch `url` under the same address, size and content checks and daily
+/// limit as `download_external_media`, counted on `day`
+async fn fetch_url(url: String, day: NaiveDate) -> Result<Downloaded, FetchError> {
+    check_quota(MEDIA_DOWNLOAD_PROVIDER, day).map_err(FetchError::Permanent)?;
+    let fetched = fetch_external_media(&url, MAX_DOWNLOAD_BYTES).await;
+    let _ = record_usage(MEDIA_DOWNLOAD_PROVIDER, day, 1, 0);
+    match fetched {
+        Ok(media) => Ok(Downloaded {
+            data: media.data,
+            mime_type: Some(media.mime_type),
+        }),
+        Err(error) if error.is_transient() => Err(FetchError::Transient(error.message)),
+        Err(error) => Err(FetchError::Permanent(error.message)),
+    }
+}
+
+/// Fetch `url`, retrying transient failures. Returns the result and the number of tries.
+async fn fetch_with_retries<F, Fut>(fetch: &F, url: &str) -> (Result<Downloaded, String>, u32)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Downloaded, FetchError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match fetch(url.to_string()).await {
+            Ok(downloaded) => return (Ok(downloaded), attempt),
+            Err(FetchError::Transient(_)) if attempt < MAX_ATTEMPTS => {
+                tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+            }
+            Err(FetchError::Transient(error) | FetchError::Permanent(error)) => {
+                return (Err(error), attempt)
+            }
+        }
+    }
+}
+
+/// Fetch every item, at most `MAX_CONCURRENT_DOWNLOADS` at a time, handing
+/// each result to `finish` as it arrives. Outcomes are in the order of `items`.
+pub async fn download_all<F, Fut>(
+    items: &[DownloadItem],
+    fetch: F,
+    mut finish: impl FnMut(&DownloadItem, Result<Downloaded, String>, u32) -> DownloadOutcome,
+) -> Vec<DownloadOutcome>
+where
+    F: Fn(String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Downloaded, FetchError>> + Send + 'static,
+{
+    let fetch = Arc::new(fetch);
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS));
+    let mut tasks = JoinSet::new();
+    for (index, item) in items.iter().enumerate() {
+        let fetch = Arc::clone(&fetch);
+        let permits = Arc::clone(&permits);
+        let url = item.url.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            (index, fetch_with_retries(&*fetch, &url).await)
+        });
+    }
+
+    let mut outcomes: Vec<Option<DownloadOutcome>> = vec![None; items.len()];
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, (result, attempts))) = joined {
+            outcomes[index] = Some(finish(&items[index], result, attempts));
+        }
+    }
+    outcomes
+        .into_iter()
+        .zip(items)
+        .map(|(outcome, item)| {
+            outcome.unwrap_or_else(|| {
+                DownloadOutcome::failed(item, "Download was interrupted".to_string(), 0)
+            })
+        })
+        .collect()
+}
+
+/// Err unless `attribution` names the license media from `url` is used under.
+/// Every command that fetches media from the web checks this first.
+pub fn require_license(url: &str, attribution: Option<&MediaAttribution>) -> Result<(), String> {
+    if attribution.is_some_and(MediaAttribution::has_license) {
+        return Ok(());
+    }
+    Err(format!(
+        "A license is required for media imported from the web; none was given for {url}"
+    ))
+}
+
+/// Last path segment of `url`, used as the stored file's original name
+fn file_name(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()?
+                .rev()
+                .find(|segment| !segment.is_empty())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| "download".to_string())
+}
+
+fn store_download(
+    project_id: &str,
+    item: &DownloadItem,
+    result: Result<Downloaded, String>,
+    attempts: u32,
+) -> DownloadOutcome {
+    let downloaded = match result {
+        Ok(downloaded) => downloaded,
+        Err(error) => return DownloadOutcome::failed(item, error, attempts),
+    };
+    let media_id = new_media_id(&item.media_type);
+    let size = downloaded.data.len();
+    let metadata = MediaMetadata {
+        page_id: item.page_id.clone(),
+        media_type: item.media_type.clone(),
+        original_name: file_name(&item.url),
+        mime_type: downloaded.mime_type.clone(),
+        source: Some(item.url.clone()),
+        embed_url: None,
+        title: item.title.clone(),
+        clip_start: None,
+        clip_end: None,
+        alt_text: None,
+        attribution: item.attribution.clone(),
+    };
+    match store_media(
+        media_id.clone(),
+        project_id.to_string(),
+        downloaded.data,
+        metadata,
+    ) {
+        Ok(()) => DownloadOutcome {
+            url: item.url.clone(),
+            page_id: item.page_id.clone(),
+            media_id: Some(media_id),
+            mime_type: downloaded.mime_type,
+            size,
+            attempts,
+            error: None,
+        },
+        Err(error) => DownloadOutcome::failed(item, error, attempts),
+    }
+}
+
+/// Download remote files into a project's media, reporting progress as
+/// `media-download-progress` events. Download failures and items without a
+/// license are reported per item; the rest of the batch goes ahead.
+#[tauri::command]
+pub async fn download_media_batch(
+    app: tauri::AppHandle,
+    services: tauri::State<'_, AppServices>,
+    #[allow(non_snake_case)] projectId: String,
+    items: Vec<DownloadItem>,
+) -> Result<Vec<DownloadOutcome>, String> {
+    let total = items.len();
+    let mut completed = 0;
+    let mut report = |outcome: &DownloadOutcome| {
+        completed += 1;
+        let _ = app.emit(
+            "media-download-progress",
+            Message::new("progress.media_download.item")
+                .with("current", completed)
+                .with("total", total)
+                .progress_event(serde_json::json!({
+                    "progress": completed * 100 / total,
+                    "current": completed,
+                    "total": total,
+                    "outcome": outcome
+                })),
+        );
+    };
+
+    let (licensed, unlicensed): (Vec<_>, Vec<_>) = items
+        .iter()
+        .map(|item| (item, require_license(&item.url, item.attribution.as_ref())))
+        .partition(|(_, license)| license.is_ok());
+    let skipped: Vec<DownloadOutcome> = unlicensed
+        .into_iter()
+        .map(|(item, license)| DownloadOutcome::failed(item, license.unwrap_err(), 0))
+        .collect();
+    skipped.iter().for_each(&mut report);
+    let licensed: Vec<DownloadItem> = licensed.into_iter().map(|(item, _)| item.clone()).collect();
+
+    let day = today(&services);
+    let fetch = move |url: String| fetch_url(url, day);
+    let downloaded = download_all(&licensed, fetch, |item, result, attempts| {
+        let outcome = store_download(&projectId, item, result, attempts);
+        report(&outcome);
+        outcome
+    })
+    .await;
+
+    // Back into the order of `items`
+    let mut downloaded = downloaded.into_iter();
+    let mut skipped = skipped.into_iter();
+    let outcomes: Vec<DownloadOutcome> = items
+        .iter()
+        .filter_map(|item| {
+            if require_license(&item.url, item.attribution.as_ref()).is_ok() {
+                downloaded.next()
+            } else {
+                skipped.next()
+            }
+        })
+        .collect();
+
+    let stored = outcomes
+        .iter()
+        .filter(|outcome| outcome.media_id.is_some())
+        .count();
+    let _ = app.emit(
+        "media-download-progress",
+        Message::new("progress.media_download.done")
+            .with("stored", stored)
+            .with("total", total)
+            .progress_event(serde_json::json!({
+                "progress": 100,
+                "failed": total - stored
+            })),
+    );
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn item(url: &str) -> DownloadItem {
+        DownloadItem {
+            url: url.to_string(),
+            page_id: "topic-1".to_string(),
+            media_type: "image".to_string(),
+            title: None,
+            attribution: None,
+        }
+    }
+
+    fn keep(
+        item: &DownloadItem,
+        result: Result<Downloaded, String>,
+        attempts: u32,
+    ) -> DownloadOutcome {
+        match result {
+            Ok(downloaded) => DownloadOutcome {
+                url: item.url.clone(),
+                page_id: item.page_id.clone(),
+                media_id: Some(format!("image-{}", file_name(&item.url))),
+                mime_type: downloaded.mime_type,
+                size: downloaded.data.len(),
+                attempts,
+                error: None,
+            },
+            Err(error) => DownloadOutcome::failed(item, error, attempts),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures_only() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let fetch = move |url: String| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if url.contains("flaky") && call == 0 {
+                    Err(FetchError::Transient("HTTP error: 503".to_string()))
+                } else if url.contains("missing") {
+                    Err(FetchError::Permanent("HTTP error: 404".to_string()))
+                } else {
+                    Ok(Downloaded {
+                        data: vec![0; 10],
+                        mime_type: Some("image/png".to_string()),
+                    })
+                }
+            }
+        };
+        let items = [
+            item("https://cdn.example/flaky.png"),
+            item("https://cdn.example/missing.png"),
+        ];
+
+        let outcomes = download_all(&items, fetch, keep).await;
+
+        assert_eq!(outcomes[0].media_id.as_deref(), Some("image-flaky.png"));
+        assert_eq!((outcomes[0].attempts, outcomes[0].size), (2, 10));
+        assert_eq!(outcomes[1].error.as_deref(), Some("HTTP error: 404"));
+        assert_eq!(outcomes[1].attempts, 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_limits_concurrent_downloads_and_keeps_order() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (running, highest) = (Arc::clone(&in_flight), Arc::clone(&peak));
+        let fetch = move |url: String| {
+            let (running, highest) = (Arc::clone(&running), Arc::clone(&highest));
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                highest.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(Downloaded {
+                    data: url.into_bytes(),
+                    mime_type: None,
+                })
+            }
+        };
+        let items: Vec<_> = (0..10)
+            .map(|i| item(&format!("https://cdn.example/{i}.jpg")))
+            .collect();
+
+        let outcomes = download_all(&items, fetch, keep).await;
+
+        assert!(peak.load(Ordering::SeqCst) <= MAX_CONCURRENT_DOWNLOADS);
+        let urls: Vec<_> = outcomes
+            .iter()
+            .map(|outcome| outcome.url.as_str())
+            .collect();
+        let expected: Vec<_> = items.iter().map(|item| item.url.as_str()).collect();
+        assert_eq!(urls, expected);
+        assert!(outcomes.iter().all(|outcome| outcome.error.is_none()));
+        assert_eq!(
+            file_name("https://cdn.example/a/photo.jpg?w=200"),
+            "photo.jpg"
+        );
+        assert_eq!(file_name("https://cdn.example/"), "download");
+    }
+
+    #[test]
+    fn test_media_from_the_web_needs_a_license() {
+        let licensed = |url: &str, license: &str| DownloadItem {
+            attribution: Some(MediaAttribution {
+                provider: "unsplash".to_string(),
+                license: Some(license.to_string()),
+                ..Default::default()
+            }),
+            ..item(url)
+        };
+        let check = |item: DownloadItem| require_license(&item.url, item.attribution.as_ref());
+        assert!(check(licensed("https://cdn.example/a.jpg", "Unsplash License")).is_ok());
+        assert!(check(licensed("https://cdn.example/b.jpg", "  "))
+            .unwrap_err()
+            .ends_with("https://cdn.example/b.jpg"));
+        assert!(check(item("https://cdn.example/c.jpg")).is_err());
+    }
+}
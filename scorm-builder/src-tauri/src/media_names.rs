@@ -0,0 +1,231 @@
+//! Project exports that name media by the file name it was added with.
+//!
+//! Media is stored as `{id}.bin` next to its `{id}.json` metadata, which means
+//! nothing to a vendor unpacking an export. In this mode the data file goes
+//! into the ZIP under the `original_name` from its metadata instead, made
+//! portable and numbered `name (2).ext` when two files share a name. The
+//! metadata files keep their names, and the export manifest at the root of the
+//! ZIP maps each renamed entry back to its media id, so importing the ZIP
+//! stores the media under its id again.
+
+use crate::file_names::portable_name;
+use crate::media_storage::MediaMetadata;
+use crate::narration_ssml::LexiconEntry;
+use crate::project_encryption::read_metadata_file;
+use crate::stable_ids::is_plain_media_id;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// Entry at the root of exported ZIPs describing what an import needs to restore
+pub const EXPORT_MANIFEST: &str = "manifest.json";
+
+/// The export manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    /// Media stored under its original name, by entry path
+    #[serde(default, skip_serializing_if = "MediaNameMap::is_empty")]
+    pub media_names: MediaNameMap,
+    /// The project's pronunciation lexicon, which lives beside the project file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pronunciation_lexicon: Vec<LexiconEntry>,
+}
+
+impl ExportManifest {
+    pub fn is_empty(&self) -> bool {
+        self.media_names.is_empty() && self.pronunciation_lexicon.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize {EXPORT_MANIFEST}: {e}"))
+    }
+
+    /// Parse a manifest from an archive, refusing media ids that aren't plain ids
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let manifest: Self = serde_json::from_str(json)
+            .map_err(|e| format!("Failed to parse {EXPORT_MANIFEST}: {e}"))?;
+        if let Some(entry) = manifest
+            .media_names
+            .0
+            .values()
+            .find(|entry| !is_plain_media_id(&entry.media_id))
+        {
+            return Err(format!(
+                "Invalid media id in {EXPORT_MANIFEST}: {}",
+                entry.media_id
+            ));
+        }
+        Ok(manifest)
+    }
+}
+
+/// Media stored under a name other than its id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaNameEntry {
+    pub media_id: String,
+    pub original_name: String,
+}
+
+/// Renamed media by entry path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MediaNameMap(BTreeMap<String, MediaNameEntry>);
+
+impl MediaNameMap {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The media stored at entry `path`, if it was renamed
+    pub fn get(&self, path: &str) -> Option<&MediaNameEntry> {
+        self.0.get(path)
+    }
+
+    /// The name `{id}.bin` to store the entry at `path` under; None keeps its own name
+    pub fn storage_name(&self, path: &str) -> Option<String> {
+        self.get(path).map(|entry| format!("{}.bin", entry.media_id))
+    }
+}
+
+/// Picks the entry names of one media folder in an export
+pub struct OriginalNamer {
+    dir: String,
+    /// Names taken in the folder, lowercased since some file systems ignore case
+    used: HashSet<String>,
+    map: MediaNameMap,
+}
+
+impl OriginalNamer {
+    /// For the media folder at entry path `dir` holding `file_names`, which stay
+    /// reserved so a renamed file never shadows a metadata file
+    pub fn new<'a>(dir: &str, file_names: impl IntoIterator<Item = &'a OsStr>) -> Self {
+        Self {
+            dir: dir.trim_end_matches('/').to_string(),
+            used: file_names
+                .into_iter()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .collect(),
+            map: MediaNameMap::default(),
+        }
+    }
+
+    /// Entry path for the data file `{id}.bin` in `media_dir`, named after the
+    /// original name in its metadata. None for other files and for media whose
+    /// metadata has no usable name; they keep their stored name.
+    pub fn entry_path(&mut self, media_dir: &Path, file_name: &OsStr) -> Option<String> {
+        let media_id = file_name.to_str()?.strip_suffix(".bin")?;
+        let metadata = read_metadata_file(&media_dir.join(format!("{media_id}.json"))).ok()?;
+        let metadata: MediaMetadata = serde_json::from_str(&metadata).ok()?;
+        let original = portable_name(metadata.original_name.trim());
+        if original.is_empty() {
+            return None;
+        }
+
+        let name = self.unused_name(&original);
+        let path = format!("{}/{name}", self.dir);
+        self.map.0.insert(
+            path.clone(),
+            MediaNameEntry {
+                media_id: media_id.to_string(),
+                original_name: metadata.original_name,
+            },
+        );
+        Some(path)
+    }
+
+    /// `name`, or `name (2)`, `name (3)`... before the extension
+    fn unused_name(&mut self, name: &str) -> String {
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+            _ => (name, String::new()),
+        };
+        let mut candidate = name.to_string();
+        let mut number = 2;
+        while !self.used.insert(candidate.to_lowercase()) {
+            candidate = format!("{stem} ({number}){extension}");
+            number += 1;
+        }
+        candidate
+    }
+
+    pub fn into_map(self) -> MediaNameMap {
+        self.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn store(dir: &Path, id: &str, original_name: &str) {
+        fs::write(dir.join(format!("{id}.bin")), b"data").unwrap();
+        let metadata = serde_json::json!({
+            "page_id": "welcome",
+            "type": "image",
+            "original_name": original_name,
+            "mime_type": "image/png",
+            "source": null,
+            "embed_url": null,
+            "title": null,
+            "clip_start": null,
+            "clip_end": null,
+            "alt_text": null
+        });
+        fs::write(dir.join(format!("{id}.json")), metadata.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_media_is_named_after_its_original_name_without_collisions() {
+        let dir = TempDir::new().unwrap();
+        store(dir.path(), "image-0", "Site Plan.png");
+        store(dir.path(), "image-1", "site plan.PNG");
+        store(dir.path(), "image-2", "image-0.json");
+        store(dir.path(), "image-3", "");
+
+        let names: Vec<_> = ["image-0.bin", "image-0.json", "image-1.bin", "image-2.bin", "image-3.bin"]
+            .into_iter()
+            .map(OsStr::new)
+            .collect();
+        let mut namer = OriginalNamer::new("42/media", names.iter().copied());
+
+        let paths: Vec<_> = names
+            .iter()
+            .map(|name| namer.entry_path(dir.path(), name))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                Some("42/media/Site Plan.png".to_string()),
+                None,
+                Some("42/media/site plan (2).PNG".to_string()),
+                Some("42/media/image-0 (2).json".to_string()),
+                None,
+            ]
+        );
+
+        let manifest = ExportManifest {
+            media_names: namer.into_map(),
+            ..ExportManifest::default()
+        };
+        let map = ExportManifest::from_json(&manifest.to_json().unwrap()).unwrap().media_names;
+        assert_eq!(map.storage_name("42/media/site plan (2).PNG").as_deref(), Some("image-1.bin"));
+        assert_eq!(map.get("42/media/Site Plan.png").unwrap().original_name, "Site Plan.png");
+        assert_eq!(map.storage_name("42/media/image-0.json"), None);
+    }
+
+    #[test]
+    fn test_manifest_refuses_media_ids_that_are_not_plain() {
+        let json = r#"{"mediaNames":{"42/media/a.png":{"mediaId":"../../escape","originalName":"a.png"}}}"#;
+        let error = ExportManifest::from_json(json).unwrap_err();
+        assert!(error.starts_with("Invalid media id in manifest.json"));
+
+        let json = r#"{"mediaNames":{"42/media/a.png":{"mediaId":"image-0","originalName":"a.png"}}}"#;
+        assert!(ExportManifest::from_json(json).is_ok());
+    }
+}
@@ -1,8 +1,15 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use serde_json::{Value, Map};
 
 use crate::media_storage::get_media_directory;
+use crate::project_encryption::{read_metadata_file, write_media_file};
+use crate::stable_ids::is_stable_media_id;
+
+/// Folder next to `media/` that holds the metadata files as they were before each migration
+const BACKUP_DIR_NAME: &str = "media-page-id-backups";
 
 // Debug logging for migration issues
 fn debug_log(message: &str) {
@@ -10,89 +17,62 @@ fn debug_log(message: &str) {
     eprintln!("[DEBUG] Media Migration: {}", message);
 }
 
-/// Migrates media metadata files to fix incorrect page_id assignments
-/// This fixes the issue where objectives media was marked as "topic-0" instead of "objectives"
-#[tauri::command]
-pub async fn migrate_media_page_ids(project_id: String) -> Result<serde_json::Value, String> {
-    debug_log(&format!("Starting media page_id migration for project: {}", project_id));
-
-    let media_dir = get_media_directory(&project_id)
-        .map_err(|e| format!("Failed to get media directory: {}", e))?;
-
-    if !media_dir.exists() {
-        return Ok(serde_json::json!({
-            "success": true,
-            "message": "No media directory found, nothing to migrate",
-            "fixes_made": 0
-        }));
-    }
-
-    let mut fixes_made = 0;
-    let mut migration_log = Vec::new();
-
-    // Read all JSON metadata files
-    let entries = fs::read_dir(&media_dir)
-        .map_err(|e| format!("Failed to read media directory: {}", e))?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
-
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "json") {
-            if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                // Only process media metadata files (skip other JSON files)
-                if file_name.starts_with("audio-") || file_name.starts_with("caption-") ||
-                   file_name.starts_with("image-") || file_name.starts_with("video-") {
-
-                    match fix_media_metadata_file(&path, file_name) {
-                        Ok(Some(fix_info)) => {
-                            fixes_made += 1;
-                            migration_log.push(fix_info);
-                            debug_log(&format!("Fixed metadata for: {}", file_name));
-                        }
-                        Ok(None) => {
-                            // No fix needed, already correct
-                        }
-                        Err(e) => {
-                            let error_msg = format!("Failed to process {}: {}", file_name, e);
-                            debug_log(&error_msg);
-                            migration_log.push(error_msg);
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    debug_log(&format!("Media page_id migration completed. Fixes made: {}", fixes_made));
+/// A metadata file whose page_id doesn't match its media ID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PageIdChange {
+    pub file: String,
+    pub media_id: String,
+    /// Empty when the file has no page_id at all
+    pub current_page_id: String,
+    pub expected_page_id: String,
+}
 
-    Ok(serde_json::json!({
-        "success": true,
-        "fixes_made": fixes_made,
-        "migration_log": migration_log,
-        "message": format!("Migration completed. Fixed {} media files.", fixes_made)
-    }))
+/// A metadata file that couldn't be checked or rewritten
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PageIdFileError {
+    pub file: String,
+    pub error: String,
 }
 
-/// Fix a single media metadata file if it has incorrect page_id
-fn fix_media_metadata_file(file_path: &Path, file_name: &str) -> Result<Option<String>, String> {
-    // Read the current metadata
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PageIdValidationReport {
+    pub valid_files: usize,
+    pub invalid_files: usize,
+    /// Files with the wrong page_id
+    pub changes: Vec<PageIdChange>,
+    /// Files that couldn't be read or whose media ID has no known page
+    pub errors: Vec<PageIdFileError>,
+    /// One line per invalid file, for logs
+    pub issues: Vec<String>,
+    pub message: String,
+}
 
-    let mut metadata: Map<String, Value> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PageIdMigrationReport {
+    /// Nothing was written or backed up
+    pub dry_run: bool,
+    /// Files rewritten, or that would be rewritten on a dry run
+    pub changes: Vec<PageIdChange>,
+    pub errors: Vec<PageIdFileError>,
+    /// Where the changed files were copied before being rewritten
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_dir: Option<String>,
+    pub fixes_made: usize,
+    pub message: String,
+}
 
-    // Extract media ID from filename (e.g., "audio-1.json" -> "audio-1")
-    let media_id = file_name.trim_end_matches(".json");
+/// Page a legacy media ID belongs to, or `None` for stable IDs whose page_id is authoritative
+pub(crate) fn expected_page_id(media_id: &str) -> Result<Option<String>, String> {
+    // Stable IDs say nothing about their page; their page_id is authoritative
+    if is_stable_media_id(media_id) {
+        return Ok(None);
+    }
 
-    // Determine the correct page_id based on media ID
-    let correct_page_id = match media_id {
+    let page_id = match media_id {
         id if id.ends_with("-0") => "welcome".to_string(),
         // FIXED: Only match exactly audio-1 and caption-1, not audio-10-1, audio-11-1, etc.
         "audio-1" | "caption-1" => "objectives".to_string(),
-        id if id.starts_with("audio-") || id.starts_with("caption-") ||
-              id.starts_with("image-") || id.starts_with("video-") => {
+        id if is_media_metadata_name(id) => {
             // Extract the number and calculate topic index
             if let Some(dash_pos) = id.rfind('-') {
                 if let Ok(num) = id[dash_pos + 1..].parse::<i32>() {
@@ -110,159 +90,230 @@ fn fix_media_metadata_file(file_path: &Path, file_name: &str) -> Result<Option<S
         }
         _ => return Err("Unrecognized media ID format".to_string())
     };
-
-    // Check if current page_id is incorrect
-    let current_page_id = metadata.get("page_id")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-
-    if current_page_id != correct_page_id {
-        // Update the page_id
-        metadata.insert("page_id".to_string(), Value::String(correct_page_id.to_string()));
-
-        // Write back the corrected metadata
-        let updated_content = serde_json::to_string_pretty(&metadata)
-            .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
-
-        fs::write(file_path, updated_content)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
-
-        Ok(Some(format!(
-            "{}: {} -> {}",
-            media_id, current_page_id, correct_page_id
-        )))
-    } else {
-        // Already correct
-        Ok(None)
-    }
+    Ok(Some(page_id))
 }
 
-/// Validates all media metadata files in a project for correct page_id assignments
-#[tauri::command]
-pub async fn validate_media_page_ids(project_id: String) -> Result<serde_json::Value, String> {
-    debug_log(&format!("Validating media page_ids for project: {}", project_id));
-
-    let media_dir = get_media_directory(&project_id)
-        .map_err(|e| format!("Failed to get media directory: {}", e))?;
-
-    if !media_dir.exists() {
-        return Ok(serde_json::json!({
-            "success": true,
-            "message": "No media directory found",
-            "issues": [],
-            "valid_files": 0,
-            "invalid_files": 0
-        }));
-    }
-
-    let mut issues = Vec::new();
-    let mut valid_files = 0;
-    let mut invalid_files = 0;
+fn is_media_metadata_name(name: &str) -> bool {
+    name.starts_with("audio-") || name.starts_with("caption-") ||
+        name.starts_with("image-") || name.starts_with("video-")
+}
 
-    // Read all JSON metadata files
-    let entries = fs::read_dir(&media_dir)
+/// Media metadata files in `media_dir`, sorted by name so reports are stable
+fn metadata_files(media_dir: &Path) -> Result<Vec<(PathBuf, String)>, String> {
+    let entries = fs::read_dir(media_dir)
         .map_err(|e| format!("Failed to read media directory: {}", e))?;
 
+    let mut files = Vec::new();
     for entry in entries {
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
         let path = entry.path();
 
         if path.is_file() && path.extension().map_or(false, |ext| ext == "json") {
             if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                // Only process media metadata files
-                if file_name.starts_with("audio-") || file_name.starts_with("caption-") ||
-                   file_name.starts_with("image-") || file_name.starts_with("video-") {
-
-                    match validate_media_metadata_file(&path, file_name) {
-                        Ok(None) => {
-                            valid_files += 1;
-                        }
-                        Ok(Some(issue)) => {
-                            invalid_files += 1;
-                            issues.push(issue);
-                        }
-                        Err(e) => {
-                            invalid_files += 1;
-                            issues.push(format!("Error reading {}: {}", file_name, e));
-                        }
-                    }
+                // Only process media metadata files (skip other JSON files)
+                if is_media_metadata_name(file_name) {
+                    files.push((path.clone(), file_name.to_string()));
                 }
             }
         }
     }
-
-    Ok(serde_json::json!({
-        "success": true,
-        "valid_files": valid_files,
-        "invalid_files": invalid_files,
-        "issues": issues,
-        "message": if invalid_files > 0 {
-            format!("Found {} files with incorrect page_id assignments", invalid_files)
-        } else {
-            "All media files have correct page_id assignments".to_string()
-        }
-    }))
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(files)
 }
 
-/// Validate a single media metadata file
-fn validate_media_metadata_file(file_path: &Path, file_name: &str) -> Result<Option<String>, String> {
-    // Read the current metadata
-    let content = fs::read_to_string(file_path)
+/// Check a single metadata file, returning the change it needs if any
+fn check_media_metadata_file(file_path: &Path, file_name: &str) -> Result<Option<PageIdChange>, String> {
+    let content = read_metadata_file(file_path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
     let metadata: Map<String, Value> = serde_json::from_str(&content)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
-    // Extract media ID from filename
+    // Extract media ID from filename (e.g., "audio-1.json" -> "audio-1")
     let media_id = file_name.trim_end_matches(".json");
 
-    // Determine the correct page_id based on media ID
-    let correct_page_id = match media_id {
-        id if id.ends_with("-0") => "welcome".to_string(),
-        // FIXED: Only match exactly audio-1 and caption-1, not audio-10-1, audio-11-1, etc.
-        "audio-1" | "caption-1" => "objectives".to_string(),
-        id if id.starts_with("audio-") || id.starts_with("caption-") ||
-              id.starts_with("image-") || id.starts_with("video-") => {
-            // Extract the number and calculate topic index
-            if let Some(dash_pos) = id.rfind('-') {
-                if let Ok(num) = id[dash_pos + 1..].parse::<i32>() {
-                    if num >= 2 {
-                        format!("topic-{}", num - 2)
-                    } else {
-                        return Err("Invalid media number".to_string());
-                    }
-                } else {
-                    return Err("Could not parse media number".to_string());
-                }
-            } else {
-                return Err("Could not find dash in media ID".to_string());
-            }
-        }
-        _ => return Err("Unrecognized media ID format".to_string())
+    let Some(expected) = expected_page_id(media_id)? else {
+        return Ok(None);
     };
 
-    // Check current page_id
     let current_page_id = metadata.get("page_id")
         .and_then(|v| v.as_str())
         .unwrap_or("")
         .to_string();
 
-    if current_page_id != correct_page_id {
-        Ok(Some(format!(
-            "{}: expected '{}', found '{}'",
-            media_id, correct_page_id, current_page_id
-        )))
+    if current_page_id == expected {
+        return Ok(None);
+    }
+    Ok(Some(PageIdChange {
+        file: file_name.to_string(),
+        media_id: media_id.to_string(),
+        current_page_id,
+        expected_page_id: expected,
+    }))
+}
+
+/// Rewrite the page_id of a metadata file, keeping every other field
+fn apply_change(file_path: &Path, change: &PageIdChange) -> Result<(), String> {
+    let content = read_metadata_file(file_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let mut metadata: Map<String, Value> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    metadata.insert("page_id".to_string(), Value::String(change.expected_page_id.clone()));
+
+    let updated_content = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+
+    write_media_file(file_path, updated_content.as_bytes())
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Validate every metadata file in `media_dir`
+pub fn validate_media_dir(media_dir: &Path) -> Result<PageIdValidationReport, String> {
+    let mut report = PageIdValidationReport::default();
+    if !media_dir.exists() {
+        report.message = "No media directory found".to_string();
+        return Ok(report);
+    }
+
+    for (path, file_name) in metadata_files(media_dir)? {
+        match check_media_metadata_file(&path, &file_name) {
+            Ok(None) => report.valid_files += 1,
+            Ok(Some(change)) => {
+                report.invalid_files += 1;
+                report.issues.push(format!(
+                    "{}: expected '{}', found '{}'",
+                    change.media_id, change.expected_page_id, change.current_page_id
+                ));
+                report.changes.push(change);
+            }
+            Err(e) => {
+                report.invalid_files += 1;
+                report.issues.push(format!("Error reading {}: {}", file_name, e));
+                report.errors.push(PageIdFileError { file: file_name, error: e });
+            }
+        }
+    }
+
+    report.message = if report.invalid_files > 0 {
+        format!("Found {} files with incorrect page_id assignments", report.invalid_files)
     } else {
-        Ok(None)
+        "All media files have correct page_id assignments".to_string()
+    };
+    Ok(report)
+}
+
+/// Migrate every metadata file in `media_dir`. Unless `dry_run`, the files about to change
+/// are first copied to a timestamped folder under `backup_root`; nothing is rewritten if
+/// that copy fails.
+pub fn migrate_media_dir(
+    media_dir: &Path,
+    backup_root: &Path,
+    dry_run: bool,
+    now: DateTime<Utc>,
+) -> Result<PageIdMigrationReport, String> {
+    let validation = validate_media_dir(media_dir)?;
+    let mut report = PageIdMigrationReport {
+        dry_run,
+        changes: validation.changes,
+        errors: validation.errors,
+        ..Default::default()
+    };
+
+    if report.changes.is_empty() {
+        report.message = if media_dir.exists() {
+            "All media files have correct page_id assignments".to_string()
+        } else {
+            "No media directory found, nothing to migrate".to_string()
+        };
+        return Ok(report);
+    }
+
+    if dry_run {
+        report.message = format!("Dry run: {} media files would be fixed", report.changes.len());
+        return Ok(report);
+    }
+
+    let backup_dir = backup_root.join(now.format("%Y%m%d-%H%M%S%3f").to_string());
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    for change in &report.changes {
+        fs::copy(media_dir.join(&change.file), backup_dir.join(&change.file))
+            .map_err(|e| format!("Failed to back up {}: {}", change.file, e))?;
+    }
+    report.backup_dir = Some(backup_dir.to_string_lossy().to_string());
+
+    let mut applied = Vec::with_capacity(report.changes.len());
+    for change in std::mem::take(&mut report.changes) {
+        match apply_change(&media_dir.join(&change.file), &change) {
+            Ok(()) => {
+                debug_log(&format!(
+                    "Fixed metadata for {}: {} -> {}",
+                    change.file, change.current_page_id, change.expected_page_id
+                ));
+                applied.push(change);
+            }
+            Err(e) => {
+                debug_log(&format!("Failed to process {}: {}", change.file, e));
+                report.errors.push(PageIdFileError { file: change.file, error: e });
+            }
+        }
     }
+    report.fixes_made = applied.len();
+    report.changes = applied;
+    report.message = format!("Migration completed. Fixed {} media files.", report.fixes_made);
+    Ok(report)
+}
+
+/// Migrates media metadata files to fix incorrect page_id assignments
+/// This fixes the issue where objectives media was marked as "topic-0" instead of "objectives".
+/// With `dry_run` the report lists what would change without touching anything.
+#[tauri::command]
+pub async fn migrate_media_page_ids(
+    project_id: String,
+    dry_run: Option<bool>,
+) -> Result<PageIdMigrationReport, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    debug_log(&format!(
+        "Starting media page_id migration for project: {} (dry run: {})",
+        project_id, dry_run
+    ));
+
+    let media_dir = get_media_directory(&project_id)
+        .map_err(|e| format!("Failed to get media directory: {}", e))?;
+    let backup_root = media_dir
+        .parent()
+        .map(|project_dir| project_dir.join(BACKUP_DIR_NAME))
+        .ok_or_else(|| "Media directory has no project folder".to_string())?;
+
+    let report = migrate_media_dir(&media_dir, &backup_root, dry_run, Utc::now())?;
+    debug_log(&format!("Media page_id migration completed. Fixes made: {}", report.fixes_made));
+    Ok(report)
+}
+
+/// Validates all media metadata files in a project for correct page_id assignments
+#[tauri::command]
+pub async fn validate_media_page_ids(project_id: String) -> Result<PageIdValidationReport, String> {
+    debug_log(&format!("Validating media page_ids for project: {}", project_id));
+
+    let media_dir = get_media_directory(&project_id)
+        .map_err(|e| format!("Failed to get media directory: {}", e))?;
+    validate_media_dir(&media_dir)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     use tempfile::TempDir;
 
+    fn page_id(path: &Path) -> String {
+        let metadata: Map<String, Value> =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        metadata.get("page_id").unwrap().as_str().unwrap().to_string()
+    }
+
     #[test]
     fn test_media_id_to_page_id_mapping() {
         // Create test metadata files
@@ -272,13 +323,64 @@ mod tests {
         let audio1_path = temp_dir.path().join("audio-1.json");
         fs::write(&audio1_path, r#"{"page_id": "topic-0", "type": "audio"}"#).unwrap();
 
-        let result = fix_media_metadata_file(&audio1_path, "audio-1.json").unwrap();
-        assert!(result.is_some());
-        assert!(result.unwrap().contains("topic-0 -> objectives"));
+        let change = check_media_metadata_file(&audio1_path, "audio-1.json").unwrap().unwrap();
+        assert_eq!(change.current_page_id, "topic-0");
+        assert_eq!(change.expected_page_id, "objectives");
+        apply_change(&audio1_path, &change).unwrap();
 
         // Verify the file was updated
-        let updated_content = fs::read_to_string(&audio1_path).unwrap();
-        let updated_metadata: Map<String, Value> = serde_json::from_str(&updated_content).unwrap();
-        assert_eq!(updated_metadata.get("page_id").unwrap().as_str().unwrap(), "objectives");
+        assert_eq!(page_id(&audio1_path), "objectives");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_dry_run_reports_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path().join("media");
+        fs::create_dir(&media_dir).unwrap();
+        fs::write(media_dir.join("audio-1.json"), r#"{"page_id": "topic-0"}"#).unwrap();
+        fs::write(media_dir.join("image-3.json"), r#"{"page_id": "topic-1"}"#).unwrap();
+        let backup_root = temp_dir.path().join(BACKUP_DIR_NAME);
+
+        let report = migrate_media_dir(&media_dir, &backup_root, true, Utc::now()).unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.fixes_made, 0);
+        assert_eq!(report.changes, vec![PageIdChange {
+            file: "audio-1.json".to_string(),
+            media_id: "audio-1".to_string(),
+            current_page_id: "topic-0".to_string(),
+            expected_page_id: "objectives".to_string(),
+        }]);
+        assert_eq!(page_id(&media_dir.join("audio-1.json")), "topic-0");
+        assert!(!backup_root.exists());
+    }
+
+    #[test]
+    fn test_migration_backs_up_changed_files_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path().join("media");
+        fs::create_dir(&media_dir).unwrap();
+        let original = r#"{"page_id": "topic-0", "type": "audio"}"#;
+        fs::write(media_dir.join("audio-1.json"), original).unwrap();
+        fs::write(media_dir.join("image-3.json"), r#"{"page_id": "topic-1"}"#).unwrap();
+        fs::write(media_dir.join("video-x.json"), "not json").unwrap();
+        let backup_root = temp_dir.path().join(BACKUP_DIR_NAME);
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        let report = migrate_media_dir(&media_dir, &backup_root, false, now).unwrap();
+
+        assert_eq!(report.fixes_made, 1);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].file, "video-x.json");
+        assert_eq!(page_id(&media_dir.join("audio-1.json")), "objectives");
+
+        let backup_dir = backup_root.join("20240102-030405000");
+        assert_eq!(report.backup_dir, Some(backup_dir.to_string_lossy().to_string()));
+        assert_eq!(fs::read_to_string(backup_dir.join("audio-1.json")).unwrap(), original);
+        assert!(!backup_dir.join("image-3.json").exists());
+
+        let validation = validate_media_dir(&media_dir).unwrap();
+        assert_eq!(validation.valid_files, 2);
+        assert!(validation.changes.is_empty());
+    }
+}
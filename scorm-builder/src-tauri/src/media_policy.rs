@@ -0,0 +1,384 @@
+//! Policy applied to media before it is stored, whether added in the editor
+//! (`store_media`) or brought in with an imported project (`extract_project_zip`).
+//!
+//! The policy limits file size per media type, restricts MIME types and can run
+//! an external scanner on every file. It lives in the app settings; files that
+//! break it are rejected with a [`PolicyError`] listing every violation.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+const MB: u64 = 1024 * 1024;
+
+/// MIME type of content that couldn't be identified
+const UNKNOWN_MIME: &str = "application/octet-stream";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MediaPolicy {
+    /// Largest accepted file per media type (`image`, `audio`, ...), in bytes.
+    /// Types without an entry have no limit.
+    pub max_size_bytes: HashMap<String, u64>,
+    /// Accepted MIME types; `image/*` accepts every image type and an empty
+    /// list accepts anything
+    pub allowed_mime_types: Vec<String>,
+    /// Scanner run on every file, e.g. `["clamscan", "--no-summary"]`. The file
+    /// path is appended; a non-zero exit status rejects the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanner_command: Option<Vec<String>>,
+}
+
+impl Default for MediaPolicy {
+    fn default() -> Self {
+        let limits = [
+            ("image", 50 * MB),
+            ("audio", 200 * MB),
+            ("video", 2048 * MB),
+            ("caption", 5 * MB),
+            ("youtube", MB),
+        ];
+        Self {
+            max_size_bytes: limits
+                .into_iter()
+                .map(|(media_type, limit)| (media_type.to_string(), limit))
+                .collect(),
+            allowed_mime_types: ["image/*", "audio/*", "video/*", "text/vtt", "text/plain"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            scanner_command: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ViolationKind {
+    TooLarge,
+    TypeNotAllowed,
+    ScannerRejected,
+    ScannerFailed,
+}
+
+/// One file breaking the policy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyViolation {
+    /// Media ID or file name
+    pub file: String,
+    pub kind: ViolationKind,
+    pub message: String,
+}
+
+/// Returned (as JSON) by commands that refused media because of the policy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyError {
+    pub message: String,
+    /// Always `"mediaPolicy"`, so the UI can tell these from other errors
+    pub error_type: &'static str,
+    pub violations: Vec<PolicyViolation>,
+}
+
+impl PolicyError {
+    pub fn new(violations: Vec<PolicyViolation>) -> Self {
+        let message = match violations.as_slice() {
+            [violation] => violation.message.clone(),
+            _ => format!("{} media files break the media policy", violations.len()),
+        };
+        Self {
+            message,
+            error_type: "mediaPolicy",
+            violations,
+        }
+    }
+}
+
+impl From<PolicyError> for String {
+    fn from(error: PolicyError) -> String {
+        serde_json::to_string(&error).unwrap_or(error.message)
+    }
+}
+
+/// MIME type guessed from the first bytes of the content
+pub fn sniff_mime(data: &[u8]) -> Option<&'static str> {
+    let signatures: [(&[u8], &str); 11] = [
+        (b"MZ", "application/x-msdownload"),
+        (b"\x7fELF", "application/x-executable"),
+        (b"\x89PNG", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF8", "image/gif"),
+        (b"ID3", "audio/mpeg"),
+        (b"OggS", "audio/ogg"),
+        (b"fLaC", "audio/flac"),
+        (b"\x1a\x45\xdf\xa3", "video/webm"),
+        (b"WEBVTT", "text/vtt"),
+        (b"%PDF", "application/pdf"),
+    ];
+    if let Some((_, mime)) = signatures.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return Some(mime);
+    }
+    match (data.get(..4), data.get(8..12)) {
+        (Some(b"RIFF"), Some(b"WEBP")) => Some("image/webp"),
+        (Some(b"RIFF"), Some(b"WAVE")) => Some("audio/wav"),
+        (_, _) if data.get(4..8) == Some(b"ftyp") => Some("video/mp4"),
+        _ => None,
+    }
+}
+
+fn is_executable(mime: &str) -> bool {
+    matches!(
+        mime,
+        "application/x-msdownload" | "application/x-executable"
+    )
+}
+
+/// MIME type the policy checks: the declared type unless the content is an
+/// executable, otherwise what the content looks like
+pub fn effective_mime(declared: Option<&str>, data: &[u8]) -> String {
+    let sniffed = sniff_mime(data);
+    if let Some(mime) = sniffed.filter(|mime| is_executable(mime)) {
+        return mime.to_string();
+    }
+    match declared.map(str::trim) {
+        Some(mime) if !mime.is_empty() && mime != UNKNOWN_MIME => mime.to_ascii_lowercase(),
+        _ => sniffed.unwrap_or(UNKNOWN_MIME).to_string(),
+    }
+}
+
+fn mime_allowed(allowed: &[String], mime: &str) -> bool {
+    allowed.is_empty()
+        || allowed
+            .iter()
+            .any(|pattern| match pattern.strip_suffix("/*") {
+                Some(family) => mime.split('/').next() == Some(family),
+                None => pattern.eq_ignore_ascii_case(mime),
+            })
+}
+
+impl MediaPolicy {
+    /// Size and type checks for one file; the scanner is run separately
+    pub fn check(
+        &self,
+        file: &str,
+        media_type: &str,
+        declared_mime: Option<&str>,
+        data: &[u8],
+    ) -> Vec<PolicyViolation> {
+        let mut violations = Vec::new();
+        let size = data.len() as u64;
+        if let Some(&limit) = self.max_size_bytes.get(media_type) {
+            if size > limit {
+                violations.push(PolicyViolation {
+                    file: file.to_string(),
+                    kind: ViolationKind::TooLarge,
+                    message: format!(
+                        "{file} is {:.1} MB, larger than the {:.1} MB allowed for {media_type} files",
+                        size as f64 / MB as f64,
+                        limit as f64 / MB as f64
+                    ),
+                });
+            }
+        }
+        let mime = effective_mime(declared_mime, data);
+        if !mime_allowed(&self.allowed_mime_types, &mime) || is_executable(&mime) {
+            violations.push(PolicyViolation {
+                file: file.to_string(),
+                kind: ViolationKind::TypeNotAllowed,
+                message: format!("{file} has type {mime}, which the media policy doesn't allow"),
+            });
+        }
+        violations
+    }
+
+    /// Run the configured scanner on a file on disk
+    pub fn scan(&self, file: &str, path: &Path) -> Option<PolicyViolation> {
+        let (program, args) = self.scanner_command.as_deref()?.split_first()?;
+        let violation = |kind, message| {
+            Some(PolicyViolation {
+                file: file.to_string(),
+                kind,
+                message,
+            })
+        };
+        match Command::new(program).args(args).arg(path).output() {
+            Ok(output) if output.status.success() => None,
+            Ok(output) => {
+                let report = String::from_utf8_lossy(&output.stdout);
+                let report = report.lines().find(|line| !line.trim().is_empty());
+                violation(
+                    ViolationKind::ScannerRejected,
+                    format!(
+                        "{file} was rejected by the media scanner{}",
+                        report.map_or(String::new(), |line| format!(": {}", line.trim()))
+                    ),
+                )
+            }
+            // Fail closed: a configured scanner that can't run must not let files through
+            Err(e) => violation(
+                ViolationKind::ScannerFailed,
+                format!("Failed to run media scanner {program}: {e}"),
+            ),
+        }
+    }
+
+    /// Check and scan data that isn't on disk yet
+    pub fn check_data(
+        &self,
+        file: &str,
+        media_type: &str,
+        declared_mime: Option<&str>,
+        data: &[u8],
+    ) -> Result<(), PolicyError> {
+        let mut violations = self.check(file, media_type, declared_mime, data);
+        if violations.is_empty() && self.scanner_command.is_some() {
+            let scanned = tempfile::NamedTempFile::new()
+                .and_then(|mut tmp| tmp.write_all(data).map(|_| tmp))
+                .map_err(|e| PolicyViolation {
+                    file: file.to_string(),
+                    kind: ViolationKind::ScannerFailed,
+                    message: format!("Failed to prepare {file} for scanning: {e}"),
+                });
+            match scanned {
+                Ok(tmp) => violations.extend(self.scan(file, tmp.path())),
+                Err(violation) => violations.push(violation),
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PolicyError::new(violations))
+        }
+    }
+}
+
+/// The policy from the app settings, or the default one
+pub fn current_policy() -> MediaPolicy {
+    crate::settings::load_settings()
+        .ok()
+        .and_then(|settings| settings.media_policy)
+        .unwrap_or_default()
+}
+
+/// Check every media file in an extracted project's media folder, using the
+/// `{id}.json` metadata next to each `{id}.bin`
+pub fn check_media_dir(policy: &MediaPolicy, media_dir: &Path) -> Result<(), PolicyError> {
+    let mut violations = Vec::new();
+    for entry in fs::read_dir(media_dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("bin") {
+            continue;
+        }
+        let file = entry.file_name().to_string_lossy().to_string();
+        let metadata: serde_json::Value =
+            crate::project_encryption::read_metadata_file(&path.with_extension("json"))
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+        let media_type = metadata["type"].as_str().unwrap_or_default();
+        let declared_mime = metadata["mime_type"].as_str();
+        let data = crate::project_encryption::read_media_file(&path).unwrap_or_default();
+
+        let file_violations = policy.check(&file, media_type, declared_mime, &data);
+        if file_violations.is_empty() {
+            violations.extend(policy.scan(&file, &path));
+        } else {
+            violations.extend(file_violations);
+        }
+    }
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(PolicyError::new(violations))
+    }
+}
+
+#[tauri::command]
+pub fn get_media_policy() -> Result<MediaPolicy, String> {
+    Ok(current_policy())
+}
+
+#[tauri::command]
+pub fn set_media_policy(policy: MediaPolicy) -> Result<(), String> {
+    let mut settings = crate::settings::load_settings()?;
+    settings.media_policy = Some(policy);
+    crate::settings::save_settings(&settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_size_and_type_limits() {
+        let policy = MediaPolicy::default();
+        let png = b"\x89PNG\r\n\x1a\nrest".to_vec();
+
+        assert!(policy
+            .check("image-0", "image", Some("image/png"), &png)
+            .is_empty());
+        // Missing MIME types are taken from the content
+        assert!(policy.check("image-0", "image", None, &png).is_empty());
+        assert!(policy
+            .check(
+                "video-0",
+                "youtube",
+                Some("text/plain"),
+                b"https://youtu.be/x"
+            )
+            .is_empty());
+
+        let strict = MediaPolicy {
+            max_size_bytes: HashMap::from([("image".to_string(), 4)]),
+            ..MediaPolicy::default()
+        };
+        let violations = strict.check("image-0", "image", Some("image/png"), &png);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::TooLarge);
+
+        let violations = policy.check("doc-0", "image", Some("application/zip"), b"PK");
+        assert_eq!(violations[0].kind, ViolationKind::TypeNotAllowed);
+    }
+
+    #[test]
+    fn test_disguised_executables_are_rejected() {
+        let policy = MediaPolicy {
+            allowed_mime_types: Vec::new(),
+            ..MediaPolicy::default()
+        };
+        let error = policy
+            .check_data("image-0", "image", Some("image/png"), b"MZ\x90\x00")
+            .unwrap_err();
+        assert_eq!(error.violations[0].kind, ViolationKind::TypeNotAllowed);
+        assert!(error.message.contains("application/x-msdownload"));
+
+        let json: serde_json::Value = serde_json::from_str(&String::from(error)).unwrap();
+        assert_eq!(json["errorType"], "mediaPolicy");
+        assert_eq!(json["violations"][0]["kind"], "typeNotAllowed");
+    }
+
+    #[test]
+    fn test_imported_media_folder_is_checked() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path();
+        fs::write(media_dir.join("audio-0.bin"), b"ID3\x03audio").unwrap();
+        fs::write(
+            media_dir.join("audio-0.json"),
+            r#"{"type":"audio","mime_type":"audio/mpeg"}"#,
+        )
+        .unwrap();
+        let policy = MediaPolicy::default();
+        assert!(check_media_dir(&policy, media_dir).is_ok());
+
+        fs::write(media_dir.join("image-1.bin"), b"\x7fELF\x02").unwrap();
+        fs::write(media_dir.join("image-1.json"), r#"{"type":"image"}"#).unwrap();
+        let error = check_media_dir(&policy, media_dir).unwrap_err();
+        assert_eq!(error.violations.len(), 1);
+        assert_eq!(error.violations[0].file, "image-1.bin");
+    }
+}
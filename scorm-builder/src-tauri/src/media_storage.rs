@@ -1,23 +1,106 @@
+use crate::clip_timing::{check_metadata_clip, VideoDurations, YouTubeDurations};
+use crate::metadata_policy::{MetadataAuditEntry, MetadataField, MetadataPolicy};
+use crate::project_encryption::{
+    open_media_data, read_media_file, read_metadata_file, write_media_file,
+};
 use crate::project_storage::get_projects_directory;
+use crate::scorm::animated_gifs::{gif_frame_count, LARGE_ANIMATED_GIF_BYTES};
+use crate::stable_ids::is_stable_media_id;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+// Missing optional fields deserialize as `None`, so the frontend may leave them out
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, TS)]
+#[ts(export)]
 pub struct MediaMetadata {
     pub page_id: String,
     #[serde(rename = "type")]
     pub media_type: String,
     pub original_name: String,
+    #[ts(optional = nullable)]
     pub mime_type: Option<String>,
+    #[ts(optional = nullable)]
     pub source: Option<String>,
+    #[ts(optional = nullable)]
     pub embed_url: Option<String>,
+    #[ts(optional = nullable)]
     pub title: Option<String>,
+    #[ts(optional = nullable)]
     pub clip_start: Option<u32>,
+    #[ts(optional = nullable)]
     pub clip_end: Option<u32>,
+    /// Text alternative used for `alt` on images and `aria-label` on players
+    #[ts(optional = nullable)]
+    pub alt_text: Option<String>,
+    /// Author and license of media found through a stock image search
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub attribution: Option<MediaAttribution>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Who made a piece of media and under which license it may be used
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, TS)]
+#[ts(export)]
+pub struct MediaAttribution {
+    /// Where it was found: `google`, `unsplash` or `pexels`
+    pub provider: String,
+    #[ts(optional = nullable)]
+    pub author: Option<String>,
+    #[ts(optional = nullable)]
+    pub author_url: Option<String>,
+    /// Page the media is published on
+    #[ts(optional = nullable)]
+    pub source_url: Option<String>,
+    #[ts(optional = nullable)]
+    pub license: Option<String>,
+    #[ts(optional = nullable)]
+    pub license_url: Option<String>,
+}
+
+impl MediaAttribution {
+    /// Whether the license the media is used under is recorded
+    pub fn has_license(&self) -> bool {
+        self.license
+            .as_deref()
+            .is_some_and(|license| !license.trim().is_empty())
+    }
+}
+
+/// Apply the metadata policy to metadata about to be stored, recording in
+/// the project's audit what it cleaned or warned about
+fn apply_metadata_policy(
+    project_id: &str,
+    media_id: &str,
+    metadata: MediaMetadata,
+) -> Result<MediaMetadata, String> {
+    let (metadata, entry) = crate::metadata_policy::current_policy()
+        .apply(media_id, metadata, "store")
+        .map_err(|e| format!("{media_id}: {e}"))?;
+    if let Some(entry) = entry {
+        println!(
+            "🚨 [media_storage] Metadata policy {:?} on {media_id}: {:?}",
+            entry.action, entry.fields
+        );
+        record_metadata_audit(project_id, vec![entry]);
+    }
+    Ok(metadata)
+}
+
+/// Append to the project's metadata audit; a failed write is logged, not fatal
+fn record_metadata_audit(project_id: &str, entries: Vec<crate::metadata_policy::MetadataAuditEntry>) {
+    let written = get_media_directory(project_id).and_then(|media_dir| {
+        let project_dir = media_dir.parent().unwrap_or(&media_dir).to_path_buf();
+        crate::metadata_policy::append_audit(&project_dir, entries)
+    });
+    if let Err(e) = written {
+        println!("[media_storage] Warning: failed to record metadata audit: {e}");
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct MediaData {
     pub id: String,
     pub data: Vec<u8>,
@@ -109,50 +192,43 @@ pub fn store_media(
 ) -> Result<(), String> {
     // Extract actual project ID in case a path was passed
     let actual_project_id = extract_project_id(&projectId);
-    
-    // 🚨 ROOT CAUSE FIX: Validate metadata consistency to prevent contamination
-    let has_youtube_metadata = metadata.source.as_ref().map_or(false, |s| s == "youtube") ||
-                               metadata.embed_url.is_some() ||
-                               metadata.clip_start.is_some() ||
-                               metadata.clip_end.is_some();
-    
-    if has_youtube_metadata && metadata.media_type != "video" && metadata.media_type != "youtube" {
-        println!(
-            "🚨 [media_storage] CONTAMINATION PREVENTED! Attempted to store {} with YouTube metadata",
-            metadata.media_type
+    crate::project_access::ensure_project_writable(&actual_project_id)?;
+    crate::media_policy::current_policy().check_data(
+        &id,
+        &metadata.media_type,
+        metadata.mime_type.as_deref(),
+        &data,
+    )?;
+    crate::storage_budget::check_media_budget(&actual_project_id, &id, data.len() as u64)?;
+    if let Some(gif) = animated_gif_info(&id, &data).filter(|gif| gif.large) {
+        eprintln!(
+            "[media_storage] ⚠️  {id} is an animated GIF of {} frames and {} bytes; consider packaging it as video",
+            gif.frames, gif.size
         );
-        println!("   Media ID: {}", id);
-        println!("   Media Type: {}", metadata.media_type);
-        println!("   Source: {:?}", metadata.source);
-        println!("   Has embed_url: {}", metadata.embed_url.is_some());
-        println!("   Has clip timing: {}", metadata.clip_start.is_some() || metadata.clip_end.is_some());
-        println!("   🔧 Cleaning metadata to prevent UI contamination...");
-        
-        // Create clean metadata without YouTube fields for non-video media
-        let clean_metadata = MediaMetadata {
-            page_id: metadata.page_id,
-            media_type: metadata.media_type,
-            original_name: metadata.original_name,
-            mime_type: metadata.mime_type,
-            source: None, // Clear contaminated source
-            embed_url: None, // Clear contaminated YouTube URL
-            title: metadata.title,
-            clip_start: None, // Clear contaminated clip timing
-            clip_end: None, // Clear contaminated clip timing
-        };
-        
-        println!("   ✅ Metadata cleaned - storing without YouTube contamination");
-        return store_media_internal(id, actual_project_id, data, clean_metadata);
     }
     
+    // 🚨 ROOT CAUSE FIX: YouTube fields on non-video media are cleaned (or
+    // rejected, or warned about) as the metadata policy says
+    let metadata = apply_metadata_policy(&actual_project_id, &id, metadata)?;
+    check_stored_clip(&id, &metadata)?;
+
     println!(
         "[media_storage] Storing media {id} for project {projectId} (extracted: {actual_project_id})"
     );
-    
-    // If metadata is clean, store normally
+
     store_media_internal(id, actual_project_id, data, metadata)
 }
 
+/// Refuse clip timing that doesn't fit the video, as far as its cached length
+/// tells; generation checks again with the length fetched
+fn check_stored_clip(media_id: &str, metadata: &MediaMetadata) -> Result<(), String> {
+    if metadata.clip_start.is_none() && metadata.clip_end.is_none() {
+        return Ok(());
+    }
+    check_metadata_clip(media_id, metadata, &YouTubeDurations::cached())
+        .map_err(|e| format!("{media_id}: {e}"))
+}
+
 /// Internal function that does the actual storage without validation
 fn store_media_internal(
     id: String,
@@ -163,13 +239,13 @@ fn store_media_internal(
 
     // Store the binary data
     let data_path = get_media_path(&actual_project_id, &id)?;
-    fs::write(&data_path, &data).map_err(|e| format!("Failed to write media data: {e}"))?;
+    write_media_file(&data_path, &data).map_err(|e| format!("Failed to write media data: {e}"))?;
 
     // Store the metadata
     let metadata_path = get_metadata_path(&actual_project_id, &id)?;
     let metadata_json = serde_json::to_string_pretty(&metadata)
         .map_err(|e| format!("Failed to serialize metadata: {e}"))?;
-    fs::write(&metadata_path, metadata_json)
+    write_media_file(&metadata_path, metadata_json.as_bytes())
         .map_err(|e| format!("Failed to write metadata: {e}"))?;
 
     println!(
@@ -189,6 +265,7 @@ pub fn store_media_base64(
 ) -> Result<(), String> {
     // Extract actual project ID in case a path was passed
     let actual_project_id = extract_project_id(&projectId);
+    crate::project_access::ensure_project_writable(&actual_project_id)?;
     println!(
         "[media_storage] Storing media {id} from base64 for project {projectId} (extracted: {actual_project_id})"
     );
@@ -201,7 +278,7 @@ pub fn store_media_base64(
         println!("[media_storage] ⚡ EFFICIENCY: Media {} already exists, skipping base64 decode", id);
         
         // Verify metadata matches (update if needed)
-        match fs::read_to_string(&metadata_path) {
+        match read_metadata_file(&metadata_path) {
             Ok(existing_metadata_json) => {
                 if let Ok(existing_metadata) = serde_json::from_str::<MediaMetadata>(&existing_metadata_json) {
                     // If metadata is identical, skip entirely
@@ -212,33 +289,13 @@ pub fn store_media_base64(
                         println!("[media_storage] ⚡ EFFICIENCY: Updating metadata only (no base64 decode)");
                         
                         // Update metadata without touching binary data (apply same contamination prevention)
-                        let sanitized_metadata = if metadata.source.as_ref().map_or(false, |s| s == "youtube") ||
-                                                      metadata.embed_url.is_some() ||
-                                                      metadata.clip_start.is_some() ||
-                                                      metadata.clip_end.is_some() {
-                            if metadata.media_type != "video" && metadata.media_type != "youtube" {
-                                // Clean contaminated metadata
-                                MediaMetadata {
-                                    page_id: metadata.page_id.clone(),
-                                    media_type: metadata.media_type.clone(),
-                                    original_name: metadata.original_name.clone(),
-                                    mime_type: metadata.mime_type.clone(),
-                                    source: None,
-                                    embed_url: None,
-                                    title: metadata.title.clone(),
-                                    clip_start: None,
-                                    clip_end: None,
-                                }
-                            } else {
-                                metadata.clone()
-                            }
-                        } else {
-                            metadata.clone()
-                        };
+                        let sanitized_metadata =
+                            apply_metadata_policy(&actual_project_id, &id, metadata.clone())?;
+                        check_stored_clip(&id, &sanitized_metadata)?;
                         let metadata_json = serde_json::to_string_pretty(&sanitized_metadata)
                             .map_err(|e| format!("Failed to serialize metadata: {e}"))?;
                         
-                        fs::write(&metadata_path, metadata_json)
+                        write_media_file(&metadata_path, metadata_json.as_bytes())
                             .map_err(|e| format!("Failed to update metadata: {e}"))?;
                         
                         println!("[media_storage] ⚡ EFFICIENCY: Metadata updated without base64 operations");
@@ -296,13 +353,14 @@ pub fn get_all_project_media(
 
         // Only process .json files
         if path.extension() == Some(std::ffi::OsStr::new("json")) {
-            let media_id = path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| "Invalid file name".to_string())?;
+            // Media IDs are always UTF-8, so anything else isn't ours
+            let Some(media_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                println!("[media_storage] Skipping {}: file name is not UTF-8", path.display());
+                continue;
+            };
 
             // Read metadata
-            let metadata_json = fs::read_to_string(&path)
+            let metadata_json = read_metadata_file(&path)
                 .map_err(|e| format!("Failed to read metadata for {media_id}: {e}"))?;
             let metadata: MediaMetadata = serde_json::from_str(&metadata_json)
                 .map_err(|e| format!("Failed to parse metadata for {media_id}: {e}"))?;
@@ -310,7 +368,7 @@ pub fn get_all_project_media(
             // Read binary data - THIS IS THE SLOW PART!
             let data_path = get_media_path(&actual_project_id, media_id)?;
             if data_path.exists() {
-                let data = fs::read(&data_path)
+                let data = read_media_file(&data_path)
                     .map_err(|e| format!("Failed to read media data for {media_id}: {e}"))?;
 
                 let data_len = data.len();
@@ -335,10 +393,12 @@ pub fn get_all_project_media(
 }
 
 // New structure for metadata-only responses
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct MediaMetadataInfo {
     pub id: String,
     pub metadata: MediaMetadata,
+    #[ts(type = "number")]
     pub size: u64, // File size in bytes
 }
 
@@ -371,13 +431,14 @@ pub fn get_all_project_media_metadata(
 
         // Only process .json files
         if path.extension() == Some(std::ffi::OsStr::new("json")) {
-            let media_id = path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| "Invalid file name".to_string())?;
+            // Media IDs are always UTF-8, so anything else isn't ours
+            let Some(media_id) = path.file_stem().and_then(|s| s.to_str()) else {
+                println!("[media_storage] Skipping {}: file name is not UTF-8", path.display());
+                continue;
+            };
 
             // Read metadata
-            let metadata_json = fs::read_to_string(&path)
+            let metadata_json = read_metadata_file(&path)
                 .map_err(|e| format!("Failed to read metadata for {media_id}: {e}"))?;
             let metadata: MediaMetadata = serde_json::from_str(&metadata_json)
                 .map_err(|e| format!("Failed to parse metadata for {media_id}: {e}"))?;
@@ -409,17 +470,43 @@ pub fn get_all_project_media_metadata(
     Ok(media_list)
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct DeleteMediaResult {
+    pub deleted: bool,
+    /// Pages whose saved content still references the media
+    pub referenced_by: Vec<String>,
+}
+
+/// Delete a media item. Media still referenced by the saved course content is
+/// kept unless `force` is set; the referencing page IDs are returned either way.
 #[tauri::command]
 pub fn delete_media(
     #[allow(non_snake_case)] projectId: String,
     #[allow(non_snake_case)] mediaId: String,
-) -> Result<(), String> {
+    force: Option<bool>,
+) -> Result<DeleteMediaResult, String> {
     // Extract actual project ID in case a path was passed
     let actual_project_id = extract_project_id(&projectId);
+    crate::project_access::ensure_project_writable(&actual_project_id)?;
     println!(
         "[media_storage] Deleting media {mediaId} from project {projectId} (extracted: {actual_project_id})"
     );
 
+    let referenced_by = crate::media_usage::load_media_usage(&projectId)?
+        .remove(&mediaId)
+        .unwrap_or_default();
+
+    if !referenced_by.is_empty() && !force.unwrap_or(false) {
+        println!(
+            "[media_storage] Refusing to delete {mediaId}: still referenced by {referenced_by:?}"
+        );
+        return Ok(DeleteMediaResult {
+            deleted: false,
+            referenced_by,
+        });
+    }
+
     // Delete data file
     let data_path = get_media_path(&actual_project_id, &mediaId)?;
     if data_path.exists() {
@@ -433,7 +520,10 @@ pub fn delete_media(
     }
 
     println!("[media_storage] Successfully deleted media {mediaId}");
-    Ok(())
+    Ok(DeleteMediaResult {
+        deleted: true,
+        referenced_by,
+    })
 }
 
 #[tauri::command]
@@ -450,13 +540,13 @@ pub fn get_media(
     // Read metadata
     let metadata_path = get_metadata_path(&actual_project_id, &mediaId)?;
     let metadata_json =
-        fs::read_to_string(&metadata_path).map_err(|e| format!("Failed to read metadata: {e}"))?;
+        read_metadata_file(&metadata_path).map_err(|e| format!("Failed to read metadata: {e}"))?;
     let metadata: MediaMetadata = serde_json::from_str(&metadata_json)
         .map_err(|e| format!("Failed to parse metadata: {e}"))?;
 
     // Read binary data
     let data_path = get_media_path(&actual_project_id, &mediaId)?;
-    let data = fs::read(&data_path).map_err(|e| format!("Failed to read media data: {e}"))?;
+    let data = read_media_file(&data_path).map_err(|e| format!("Failed to read media data: {e}"))?;
 
     Ok(MediaData {
         id: mediaId,
@@ -465,6 +555,266 @@ pub fn get_media(
     })
 }
 
+/// An image stored as an animated GIF
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct AnimatedGifInfo {
+    pub media_id: String,
+    pub frames: usize,
+    #[ts(type = "number")]
+    pub size: u64,
+    /// Big enough to be worth packaging as video
+    pub large: bool,
+    /// ffmpeg is installed, so builds can package it as MP4 or WebM
+    pub can_convert: bool,
+}
+
+/// Frame count and size of `data` when it's an animated GIF
+fn animated_gif_info(media_id: &str, data: &[u8]) -> Option<AnimatedGifInfo> {
+    let frames = gif_frame_count(data);
+    if frames < 2 {
+        return None;
+    }
+    let size = data.len() as u64;
+    Some(AnimatedGifInfo {
+        media_id: media_id.to_string(),
+        frames,
+        size,
+        large: size >= LARGE_ANIMATED_GIF_BYTES,
+        can_convert: false,
+    })
+}
+
+/// The project's images that are animated GIFs, biggest first, so the editor
+/// can warn about them and offer to package them as video
+#[tauri::command]
+pub fn find_animated_gifs(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<Vec<AnimatedGifInfo>, String> {
+    let actual_project_id = extract_project_id(&projectId);
+    let can_convert = crate::scorm::media_transform::FfmpegTranscoder::is_available();
+
+    let mut gifs = Vec::new();
+    for info in get_all_project_media_metadata(actual_project_id.clone())? {
+        if info.metadata.media_type != "image" {
+            continue;
+        }
+        let data_path = get_media_path(&actual_project_id, &info.id)?;
+        let Ok(data) = read_media_file(&data_path) else {
+            continue;
+        };
+        if let Some(gif) = animated_gif_info(&info.id, &data) {
+            gifs.push(AnimatedGifInfo { can_convert, ..gif });
+        }
+    }
+    gifs.sort_by(|a, b| b.size.cmp(&a.size));
+    Ok(gifs)
+}
+
+/// Set or clear a media item's alt text without rewriting its data
+#[tauri::command]
+pub fn update_media_alt_text(
+    #[allow(non_snake_case)] projectId: String,
+    #[allow(non_snake_case)] mediaId: String,
+    #[allow(non_snake_case)] altText: Option<String>,
+) -> Result<MediaMetadata, String> {
+    let actual_project_id = extract_project_id(&projectId);
+    crate::project_access::ensure_project_writable(&actual_project_id)?;
+
+    let metadata_path = get_metadata_path(&actual_project_id, &mediaId)?;
+    let metadata_json =
+        read_metadata_file(&metadata_path).map_err(|e| format!("Failed to read metadata: {e}"))?;
+    let mut metadata: MediaMetadata = serde_json::from_str(&metadata_json)
+        .map_err(|e| format!("Failed to parse metadata: {e}"))?;
+
+    metadata.alt_text = altText
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty());
+    let metadata_json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("Failed to serialize metadata: {e}"))?;
+    write_media_file(&metadata_path, metadata_json.as_bytes())
+        .map_err(|e| format!("Failed to write metadata: {e}"))?;
+
+    println!("[media_storage] Updated alt text for media {mediaId}");
+    Ok(metadata)
+}
+
+/// Clip timing for one item, in seconds; `None` ends are cleared
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, TS)]
+#[ts(export)]
+pub struct ClipTiming {
+    #[ts(optional = nullable)]
+    pub start: Option<u32>,
+    #[ts(optional = nullable)]
+    pub end: Option<u32>,
+}
+
+/// Changes to one media item's metadata; unset fields are kept
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct MediaMetadataUpdate {
+    pub media_id: String,
+    /// An empty title clears it
+    #[ts(optional = nullable)]
+    pub title: Option<String>,
+    /// Empty alt text clears it
+    #[ts(optional = nullable)]
+    pub alt_text: Option<String>,
+    /// Replaces both ends of the clip
+    #[ts(optional = nullable)]
+    pub clip_timing: Option<ClipTiming>,
+    #[ts(optional = nullable)]
+    pub page_id: Option<String>,
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// `metadata` with `update` applied and the metadata policy checked, or why
+/// the update can't be applied
+fn apply_metadata_update(
+    policy: &MetadataPolicy,
+    durations: &dyn VideoDurations,
+    mut metadata: MediaMetadata,
+    update: &MediaMetadataUpdate,
+) -> Result<(MediaMetadata, Option<MetadataAuditEntry>), String> {
+    if let Some(title) = &update.title {
+        metadata.title = non_empty(title);
+    }
+    if let Some(alt_text) = &update.alt_text {
+        metadata.alt_text = non_empty(alt_text);
+    }
+    if let Some(timing) = update.clip_timing {
+        let forbidden = policy.forbidden(&metadata.media_type);
+        if (timing.start.is_some() && forbidden.contains(&MetadataField::ClipStart))
+            || (timing.end.is_some() && forbidden.contains(&MetadataField::ClipEnd))
+        {
+            return Err(format!("{} media can't have clip timing", metadata.media_type));
+        }
+        metadata.clip_start = timing.start;
+        metadata.clip_end = timing.end;
+        check_metadata_clip(&update.media_id, &metadata, durations)?;
+    }
+    if let Some(page_id) = &update.page_id {
+        let page_id = non_empty(page_id).ok_or("page ID is empty")?;
+        // A legacy ID names its page; moving it would be undone as contamination
+        if let Ok(Some(expected)) =
+            crate::media_page_id_migration::expected_page_id(&update.media_id)
+        {
+            if expected != page_id {
+                return Err(format!(
+                    "legacy media ID belongs to {expected}; migrate to stable IDs to move it"
+                ));
+            }
+        }
+        metadata.page_id = page_id;
+    }
+    policy.apply(&update.media_id, metadata, "bulk_edit")
+}
+
+/// Apply `updates` to the metadata in `media_dir`, all of them or none. Files are
+/// replaced by rename after all are staged; if a rename fails the ones already
+/// replaced are written back, which is best effort on a failing disk.
+fn update_metadata_in_dir(
+    media_dir: &Path,
+    updates: &[MediaMetadataUpdate],
+    policy: &MetadataPolicy,
+    durations: &dyn VideoDurations,
+) -> Result<(Vec<MediaMetadataInfo>, Vec<MetadataAuditEntry>), String> {
+    let mut errors = Vec::new();
+    let mut updated = Vec::new();
+    let mut audit = Vec::new();
+    for (index, update) in updates.iter().enumerate() {
+        if updates[..index].iter().any(|earlier| earlier.media_id == update.media_id) {
+            errors.push(format!("{}: listed more than once", update.media_id));
+            continue;
+        }
+        let metadata_path = media_dir.join(format!("{}.json", update.media_id));
+        // The file as stored, encrypted or not, is what a failed batch puts back
+        let metadata = fs::read(&metadata_path)
+            .map_err(|_| "no such media".to_string())
+            .and_then(|original| {
+                let json = open_media_data(&metadata_path, original.clone())?;
+                let metadata = serde_json::from_slice::<MediaMetadata>(&json)
+                    .map_err(|e| format!("failed to parse metadata: {e}"))?;
+                Ok((original, apply_metadata_update(policy, durations, metadata, update)?))
+            });
+        match metadata {
+            Ok((original, (metadata, entry))) => {
+                audit.extend(entry);
+                updated.push((metadata_path, update.media_id.clone(), original, metadata));
+            }
+            Err(e) => errors.push(format!("{}: {e}", update.media_id)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(format!("No media was updated: {}", errors.join("; ")));
+    }
+
+    // Everything is written aside first, so a failed write leaves no item changed
+    let mut staged = Vec::new();
+    for (metadata_path, _, _, metadata) in &updated {
+        let staging_path = metadata_path.with_extension("json.tmp");
+        let written = serde_json::to_string_pretty(metadata)
+            .map_err(|e| format!("Failed to serialize metadata: {e}"))
+            .and_then(|json| write_media_file(&staging_path, json.as_bytes()));
+        if let Err(e) = written {
+            for path in staged.iter().chain([&staging_path]) {
+                let _ = fs::remove_file(path);
+            }
+            return Err(e);
+        }
+        staged.push(staging_path);
+    }
+    // A failed rename puts back the files already replaced and drops the rest of the staging
+    for (index, (staging_path, (metadata_path, _, _, _))) in
+        staged.iter().zip(&updated).enumerate()
+    {
+        if let Err(e) = fs::rename(staging_path, metadata_path) {
+            for (metadata_path, _, original, _) in &updated[..index] {
+                let _ = fs::write(metadata_path, original);
+            }
+            for path in &staged[index..] {
+                let _ = fs::remove_file(path);
+            }
+            return Err(format!("Failed to write metadata: {e}"));
+        }
+    }
+
+    let updated = updated
+        .into_iter()
+        .map(|(_, id, _, metadata)| MediaMetadataInfo {
+            size: fs::metadata(media_dir.join(format!("{id}.bin")))
+                .map(|file| file.len())
+                .unwrap_or(0),
+            id,
+            metadata,
+        })
+        .collect();
+    Ok((updated, audit))
+}
+
+/// Change titles, alt text, clip timing and pages of many media items in one
+/// call, without touching their data. Every update is checked first, against
+/// the metadata policy too; if any is invalid nothing changes and the error
+/// lists each problem.
+#[tauri::command]
+pub fn update_media_metadata_batch(
+    #[allow(non_snake_case)] projectId: String,
+    updates: Vec<MediaMetadataUpdate>,
+) -> Result<Vec<MediaMetadataInfo>, String> {
+    let actual_project_id = extract_project_id(&projectId);
+    crate::project_access::ensure_project_writable(&actual_project_id)?;
+    let media_dir = get_media_directory(&actual_project_id)?;
+    let policy = crate::metadata_policy::current_policy();
+    let durations = YouTubeDurations::cached();
+    let (updated, audit) = update_metadata_in_dir(&media_dir, &updates, &policy, &durations)?;
+    record_metadata_audit(&actual_project_id, audit);
+    println!("[media_storage] Updated metadata of {} media items", updated.len());
+    Ok(updated)
+}
+
 // 🚀 CRITICAL FIX: True parallel batch operation for efficient bulk media loading
 #[tauri::command]
 pub fn get_media_batch(
@@ -562,6 +912,7 @@ pub fn media_exists_batch(
 #[tauri::command]
 pub async fn repair_shifted_audio(project_id: String) -> Result<serde_json::Value, String> {
     println!("[REPAIR] 🔧 Starting audio shift repair for project: {}", project_id);
+    crate::project_access::ensure_project_writable(&project_id)?;
 
     let media_dir = get_media_directory(&project_id)
         .map_err(|e| format!("Failed to get media directory: {}", e))?;
@@ -701,10 +1052,10 @@ fn perform_audio_swap(media_dir: &Path, from_id: &str, to_id: &str) -> Result<bo
         .map_err(|e| format!("Failed to move {} to {}: {}", from_id, to_id, e))?;
 
     // Update metadata for the moved file
-    if let Ok(mut metadata_content) = fs::read_to_string(&from_json) {
+    if let Ok(mut metadata_content) = read_metadata_file(&from_json) {
         // Update the ID in the metadata to match the new location
         metadata_content = metadata_content.replace(&format!("\"{}\"", from_id), &format!("\"{}\"", to_id));
-        fs::write(&to_json, metadata_content)
+        write_media_file(&to_json, metadata_content.as_bytes())
             .map_err(|e| format!("Failed to update metadata for {}: {}", to_id, e))?;
     }
 
@@ -736,7 +1087,10 @@ fn remove_duplicate_files(media_dir: &Path) -> Result<(), String> {
 
         if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
             // Remove any files with -1, -2, etc. suffixes (but preserve audio-1, caption-1 which are valid)
-            if filename.contains("-") && (filename.ends_with(".bin") || filename.ends_with(".json")) {
+            let is_stable = filename
+                .rsplit_once('.')
+                .is_some_and(|(stem, _)| is_stable_media_id(stem));
+            if !is_stable && filename.contains("-") && (filename.ends_with(".bin") || filename.ends_with(".json")) {
                 let name_parts: Vec<&str> = filename.split('-').collect();
                 if name_parts.len() >= 3 {
                     // Check if last part before extension is a number
@@ -796,6 +1150,8 @@ mod tests {
             title: None,
             clip_start: None,
             clip_end: None,
+            alt_text: None,
+            attribution: None,
         };
 
         // Create the media files directly for testing
@@ -809,7 +1165,7 @@ mod tests {
         
         // Set the test directory for this specific test
         std::env::set_var("SCORM_BUILDER_TEST_DIR", temp_dir.path());
-        let result = delete_media(scormproj_filename, media_id.to_string());
+        let result = delete_media(scormproj_filename, media_id.to_string(), None);
         std::env::remove_var("SCORM_BUILDER_TEST_DIR");
 
         // This works correctly - extract_project_id handles .scormproj filenames
@@ -821,6 +1177,65 @@ mod tests {
         assert!(!metadata_path.exists(), "Metadata file should be deleted");
     }
 
+    #[test]
+    fn test_delete_media_blocks_referenced_media_unless_forced() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let project_id = "1234567891";
+        let media_dir = temp_dir.path().join(project_id).join("media");
+        fs::create_dir_all(&media_dir).unwrap();
+        fs::write(media_dir.join("image-0.bin"), b"test data").unwrap();
+        fs::write(media_dir.join("image-0.json"), "{}").unwrap();
+
+        let project_path = temp_dir
+            .path()
+            .join(format!("InUse_{project_id}.scormproj"));
+        let project = serde_json::json!({
+            "project": {
+                "id": project_id,
+                "name": "InUse",
+                "created": "2024-01-01T00:00:00Z",
+                "last_modified": "2024-01-01T00:00:00Z"
+            },
+            "course_data": {
+                "title": "InUse",
+                "difficulty": 1,
+                "template": "standard",
+                "topics": [],
+                "custom_topics": null
+            },
+            "ai_prompt": null,
+            "course_content": {
+                "topics": [{ "id": "topic-3", "media": [{ "id": "image-0" }] }]
+            },
+            "media": { "images": [], "videos": [], "audio": [] },
+            "audio_settings": { "voice": "default", "speed": 1.0, "pitch": 1.0 },
+            "scorm_config": {
+                "version": "1.2",
+                "completion_criteria": "all",
+                "passing_score": 80
+            }
+        });
+        fs::write(&project_path, project.to_string()).unwrap();
+        let project_arg = project_path.to_string_lossy().to_string();
+
+        std::env::set_var("SCORM_BUILDER_TEST_DIR", temp_dir.path());
+        let blocked = delete_media(project_arg.clone(), "image-0".to_string(), None).unwrap();
+        let blocked_exists = media_dir.join("image-0.bin").exists();
+        let forced = delete_media(project_arg, "image-0".to_string(), Some(true)).unwrap();
+        std::env::remove_var("SCORM_BUILDER_TEST_DIR");
+
+        assert!(!blocked.deleted);
+        assert_eq!(blocked.referenced_by, vec!["topic-3"]);
+        assert!(blocked_exists, "Referenced media must not be deleted");
+
+        assert!(forced.deleted);
+        assert_eq!(forced.referenced_by, vec!["topic-3"]);
+        assert!(!media_dir.join("image-0.bin").exists());
+    }
+
     #[test]
     fn test_get_media_with_scormproj_filename() {
         use std::fs;
@@ -849,6 +1264,8 @@ mod tests {
             title: None,
             clip_start: None,
             clip_end: None,
+            alt_text: None,
+            attribution: None,
         };
 
         let test_data = b"test image data";
@@ -912,6 +1329,8 @@ mod tests {
                 title: None,
                 clip_start: None,
                 clip_end: None,
+                alt_text: None,
+                attribution: None,
             },
         );
 
@@ -971,6 +1390,8 @@ mod tests {
                 title: None,
                 clip_start: None,
                 clip_end: None,
+                alt_text: None,
+                attribution: None,
             },
         );
 
@@ -1031,6 +1452,8 @@ mod clip_timing_tests {
             title: Some("Test YouTube Video".to_string()),
             clip_start: Some(90),   // 1:30
             clip_end: Some(225),    // 3:45
+            alt_text: None,
+            attribution: None,
         };
         
         // Serialize to JSON (simulates what happens when storing to filesystem)
@@ -1114,6 +1537,7 @@ mod clip_timing_tests {
 #[cfg(test)]
 mod contamination_prevention_tests {
     use super::*;
+    use std::collections::HashMap;
     use std::fs;
     use tempfile::TempDir;
     
@@ -1141,6 +1565,8 @@ mod contamination_prevention_tests {
             title: Some("Test Image".to_string()),
             clip_start: Some(30), // WRONG for image
             clip_end: Some(60), // WRONG for image
+            alt_text: None,
+            attribution: None,
         };
         
         // This should trigger contamination prevention and store clean metadata
@@ -1207,6 +1633,8 @@ mod contamination_prevention_tests {
             title: Some("Real YouTube Video".to_string()),
             clip_start: Some(15),
             clip_end: Some(90),
+            alt_text: None,
+            attribution: None,
         };
         
         // This should store without any cleaning
@@ -1269,6 +1697,8 @@ mod contamination_prevention_tests {
             title: Some("Audio File".to_string()),
             clip_start: Some(10), // WRONG for audio
             clip_end: Some(50), // WRONG for audio
+            alt_text: None,
+            attribution: None,
         };
         
         // This should trigger contamination prevention via store_media_base64 -> store_media
@@ -1301,6 +1731,144 @@ mod contamination_prevention_tests {
         // Cleanup
         std::env::remove_var("SCORM_BUILDER_TEST_DIR");
     }
+
+    fn write_metadata(media_dir: &Path, media_id: &str, media_type: &str, page_id: &str) {
+        let metadata = serde_json::json!({
+            "page_id": page_id,
+            "type": media_type,
+            "original_name": format!("{media_id}.bin"),
+            "mime_type": null,
+            "source": null,
+            "embed_url": null,
+            "title": null,
+            "clip_start": null,
+            "clip_end": null,
+            "alt_text": null
+        });
+        fs::write(media_dir.join(format!("{media_id}.json")), metadata.to_string()).unwrap();
+    }
+
+    fn stored(media_dir: &Path, media_id: &str) -> MediaMetadata {
+        serde_json::from_str(&fs::read_to_string(media_dir.join(format!("{media_id}.json"))).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_metadata_batch_updates_every_item() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path();
+        write_metadata(media_dir, "image-m1a2b3", "image", "welcome");
+        write_metadata(media_dir, "video-m4c5d6", "video", "topic-0");
+
+        let (updated, audit) = update_metadata_in_dir(
+            media_dir,
+            &[
+                MediaMetadataUpdate {
+                    media_id: "image-m1a2b3".to_string(),
+                    alt_text: Some(" Ladder against a wall ".to_string()),
+                    page_id: Some("topic-1".to_string()),
+                    ..Default::default()
+                },
+                MediaMetadataUpdate {
+                    media_id: "video-m4c5d6".to_string(),
+                    title: Some("Safe climbing".to_string()),
+                    clip_timing: Some(ClipTiming { start: Some(30), end: Some(90) }),
+                    ..Default::default()
+                },
+            ],
+            &MetadataPolicy::default(),
+            &HashMap::<String, u32>::new(),
+        )
+        .unwrap();
+
+        assert_eq!(updated.len(), 2);
+        assert!(audit.is_empty());
+        let image = stored(media_dir, "image-m1a2b3");
+        assert_eq!(image.alt_text.as_deref(), Some("Ladder against a wall"));
+        assert_eq!(image.page_id, "topic-1");
+        let video = stored(media_dir, "video-m4c5d6");
+        assert_eq!((video.clip_start, video.clip_end), (Some(30), Some(90)));
+        assert_eq!(video.title.as_deref(), Some("Safe climbing"));
+    }
+
+    #[test]
+    fn test_metadata_batch_with_an_invalid_update_changes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path();
+        write_metadata(media_dir, "image-m1a2b3", "image", "welcome");
+        write_metadata(media_dir, "image-0", "image", "welcome");
+        write_metadata(media_dir, "image-m7e8f9", "image", "welcome");
+
+        let error = update_metadata_in_dir(
+            media_dir,
+            &[
+                MediaMetadataUpdate {
+                    media_id: "image-m1a2b3".to_string(),
+                    title: Some("Renamed".to_string()),
+                    ..Default::default()
+                },
+                MediaMetadataUpdate {
+                    media_id: "image-m1a2b3".to_string(),
+                    clip_timing: Some(ClipTiming { start: Some(5), end: None }),
+                    ..Default::default()
+                },
+                MediaMetadataUpdate {
+                    media_id: "image-0".to_string(),
+                    page_id: Some("topic-3".to_string()),
+                    ..Default::default()
+                },
+                MediaMetadataUpdate {
+                    media_id: "image-m7e8f9".to_string(),
+                    clip_timing: Some(ClipTiming { start: Some(5), end: Some(10) }),
+                    ..Default::default()
+                },
+                MediaMetadataUpdate {
+                    media_id: "audio-missing".to_string(),
+                    ..Default::default()
+                },
+            ],
+            &MetadataPolicy::default(),
+            &HashMap::<String, u32>::new(),
+        )
+        .unwrap_err();
+
+        assert!(error.contains("image-m1a2b3: listed more than once"), "{error}");
+        assert!(error.contains("image-0: legacy media ID belongs to welcome"), "{error}");
+        assert!(error.contains("image-m7e8f9: image media can't have clip timing"), "{error}");
+        assert!(error.contains("audio-missing: no such media"), "{error}");
+        assert_eq!(stored(media_dir, "image-m1a2b3").title, None);
+        assert_eq!(fs::read_dir(media_dir).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_metadata_batch_refuses_clips_past_the_video() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path();
+        write_metadata(media_dir, "video-m4c5d6", "video", "topic-0");
+        let mut video = stored(media_dir, "video-m4c5d6");
+        video.embed_url = Some("https://www.youtube.com/embed/dQw4w9WgXcQ".to_string());
+        fs::write(media_dir.join("video-m4c5d6.json"), serde_json::to_string(&video).unwrap()).unwrap();
+        let durations = HashMap::from([("dQw4w9WgXcQ".to_string(), 213)]);
+        let clip = |start, end| MediaMetadataUpdate {
+            media_id: "video-m4c5d6".to_string(),
+            clip_timing: Some(ClipTiming { start, end }),
+            ..Default::default()
+        };
+
+        let update = |change| update_metadata_in_dir(media_dir, &[change], &MetadataPolicy::default(), &durations);
+
+        let error = update(clip(Some(30), Some(250))).unwrap_err();
+        assert!(
+            error.contains("video-m4c5d6: clip ends at 4:10 but the video is only 3:33 long"),
+            "{error}"
+        );
+        let error = update(clip(Some(90), Some(60))).unwrap_err();
+        assert!(error.contains("clip starts at 1:30 but ends at 1:00"), "{error}");
+        assert_eq!(stored(media_dir, "video-m4c5d6").clip_end, None);
+
+        update(clip(Some(30), Some(213))).unwrap();
+        assert_eq!(stored(media_dir, "video-m4c5d6").clip_end, Some(213));
+    }
 }
 
 /// Clean duplicate media files with -1 suffix (except valid audio-1/caption-1)
@@ -1309,6 +1877,7 @@ pub async fn clean_duplicate_media(project_id: String) -> Result<serde_json::Val
     println!("[media_storage] 🧹 Starting duplicate media cleanup for project: {}", project_id);
 
     let actual_project_id = extract_project_id(&project_id);
+    crate::project_access::ensure_project_writable(&actual_project_id)?;
     let media_dir = get_media_directory(&actual_project_id)?;
 
     if !media_dir.exists() {
@@ -1433,6 +2002,8 @@ mod efficiency_integration_tests {
             title: None,
             clip_start: None,
             clip_end: None,
+            alt_text: None,
+            attribution: None,
         };
         
         // First call - should perform full base64 decode and store
@@ -1467,4 +2038,34 @@ mod efficiency_integration_tests {
         
         std::env::remove_var("SCORM_BUILDER_TEST_DIR");
     }
+
+    #[test]
+    fn test_update_media_alt_text_persists_into_metadata() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let project_id = "1234567892";
+        let media_id = "image-0";
+        let media_dir = temp_dir.path().join(project_id).join("media");
+        fs::create_dir_all(&media_dir).unwrap();
+        // Metadata written before alt text existed
+        fs::write(
+            media_dir.join(format!("{media_id}.json")),
+            r#"{"page_id":"welcome","type":"image","original_name":"chart.png","mime_type":"image/png","source":null,"embed_url":null,"title":"Chart","clip_start":null,"clip_end":null}"#,
+        )
+        .unwrap();
+
+        std::env::set_var("SCORM_BUILDER_TEST_DIR", temp_dir.path());
+        let updated = update_media_alt_text(
+            project_id.to_string(),
+            media_id.to_string(),
+            Some("  Sales by quarter  ".to_string()),
+        );
+        let stored = get_all_project_media_metadata(project_id.to_string())
+            .map(|media| media[0].metadata.alt_text.clone());
+        std::env::remove_var("SCORM_BUILDER_TEST_DIR");
+
+        assert_eq!(updated.unwrap().alt_text.as_deref(), Some("Sales by quarter"));
+        assert_eq!(stored.unwrap().as_deref(), Some("Sales by quarter"));
+    }
 }
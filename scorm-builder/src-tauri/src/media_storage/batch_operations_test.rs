@@ -36,6 +36,8 @@ mod batch_efficiency_tests {
                 title: None,
                 clip_start: None,
                 clip_end: None,
+                alt_text: None,
+                attribution: None,
             };
             
             let result = store_media_base64(
@@ -129,6 +131,8 @@ mod batch_efficiency_tests {
             title: None,
             clip_start: None,
             clip_end: None,
+            alt_text: None,
+            attribution: None,
         };
         
         let result = store_media_base64(
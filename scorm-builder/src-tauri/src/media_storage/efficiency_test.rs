@@ -28,6 +28,8 @@ mod efficiency_tests {
             title: None,
             clip_start: None,
             clip_end: None,
+            alt_text: None,
+            attribution: None,
         };
         
         // FIRST CALL - This should store the media
@@ -98,6 +100,8 @@ mod efficiency_tests {
             title: None,
             clip_start: None,
             clip_end: None,
+            alt_text: None,
+            attribution: None,
         };
         
         // Store multiple times with the same data
@@ -27,6 +27,8 @@ mod tests {
             title: Some("Test YouTube Video".to_string()),
             clip_start: Some(90),   // 1:30
             clip_end: Some(225),    // 3:45
+            alt_text: None,
+            attribution: None,
         };
         
         println!("[RUST TEST] 📊 Original metadata: {:#?}", metadata);
@@ -68,6 +70,8 @@ mod tests {
             title: Some("Test Image".to_string()),
             clip_start: None,
             clip_end: None,
+            alt_text: None,
+            attribution: None,
         };
         
         // Serialize and deserialize
@@ -163,6 +167,8 @@ mod tests {
             title: Some("Store Test Video".to_string()),
             clip_start: Some(60),   // 1:00
             clip_end: Some(300),    // 5:00
+            alt_text: None,
+            attribution: None,
         };
         
         // Create test data (YouTube URL as base64)
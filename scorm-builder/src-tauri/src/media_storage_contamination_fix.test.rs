@@ -28,6 +28,8 @@ mod contamination_fix_tests {
             title: Some("Test Image".to_string()),
             clip_start: Some(30), // WRONG for image
             clip_end: Some(60), // WRONG for image
+            alt_text: None,
+            attribution: None,
         };
         
         // This should trigger contamination prevention and store clean metadata
@@ -104,6 +106,8 @@ mod contamination_fix_tests {
             title: Some("Real YouTube Video".to_string()),
             clip_start: Some(15),
             clip_end: Some(90),
+            alt_text: None,
+            attribution: None,
         };
         
         // This should store without any cleaning
@@ -164,6 +168,8 @@ mod contamination_fix_tests {
             title: Some("Audio File".to_string()),
             clip_start: Some(10), // WRONG for audio
             clip_end: Some(50), // WRONG for audio
+            alt_text: None,
+            attribution: None,
         };
         
         // This should trigger contamination prevention via store_media_base64 -> store_media
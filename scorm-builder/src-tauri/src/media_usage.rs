@@ -0,0 +1,203 @@
+use crate::project_storage::load_project_file;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Keys whose string value is a media ID wherever they appear on a page
+const MEDIA_ID_KEYS: [&str; 4] = ["audioId", "captionId", "mediaId", "storageId"];
+
+/// Map of media ID -> IDs of the pages that reference it
+pub type MediaUsageMap = BTreeMap<String, Vec<String>>;
+
+/// Scan course content for media references.
+///
+/// Looks at the welcome page, learning objectives, every topic and the assessment,
+/// including nested structures such as knowledge check questions.
+pub fn scan_media_usage(course_content: &Value) -> MediaUsageMap {
+    let mut usage = MediaUsageMap::new();
+
+    if let Some(welcome) = course_content.get("welcomePage") {
+        record_page(&page_id_or(welcome, "welcome"), welcome, &mut usage);
+    }
+
+    if let Some(objectives) = course_content.get("learningObjectivesPage") {
+        record_page(
+            &page_id_or(objectives, "objectives"),
+            objectives,
+            &mut usage,
+        );
+    }
+
+    if let Some(topics) = course_content.get("topics").and_then(|v| v.as_array()) {
+        for (index, topic) in topics.iter().enumerate() {
+            record_page(
+                &page_id_or(topic, &format!("topic-{index}")),
+                topic,
+                &mut usage,
+            );
+        }
+    }
+
+    if let Some(assessment) = course_content.get("assessment") {
+        record_page("assessment", assessment, &mut usage);
+    }
+
+    usage
+}
+
+/// Page IDs referencing `media_id`, in course order
+pub fn find_media_references(course_content: &Value, media_id: &str) -> Vec<String> {
+    scan_media_usage(course_content)
+        .remove(media_id)
+        .unwrap_or_default()
+}
+
+fn page_id_or(page: &Value, fallback: &str) -> String {
+    page.get("id")
+        .and_then(|v| v.as_str())
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+fn record_page(page_id: &str, page: &Value, usage: &mut MediaUsageMap) {
+    let mut ids = Vec::new();
+    collect_media_ids(page, &mut ids);
+
+    for id in ids {
+        let pages = usage.entry(id).or_default();
+        if !pages.iter().any(|p| p == page_id) {
+            pages.push(page_id.to_string());
+        }
+    }
+}
+
+fn collect_media_ids(value: &Value, ids: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if MEDIA_ID_KEYS.contains(&key.as_str()) {
+                    if let Some(id) = child.as_str().filter(|id| !id.is_empty()) {
+                        ids.push(id.to_string());
+                    }
+                    continue;
+                }
+
+                if key == "media" {
+                    if let Some(items) = child.as_array() {
+                        for item in items {
+                            if let Some(id) = item.get("id").and_then(|v| v.as_str()) {
+                                ids.push(id.to_string());
+                            }
+                        }
+                    }
+                }
+
+                collect_media_ids(child, ids);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_media_ids(item, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Locate the .scormproj file for a project ID or path, if it exists on disk
+pub fn find_project_file(project_id_or_path: &str) -> Option<PathBuf> {
+    if project_id_or_path.contains(".scormproj") {
+        let path = PathBuf::from(project_id_or_path);
+        return path.exists().then_some(path);
+    }
+
+    let projects_dir = match std::env::var("SCORM_BUILDER_TEST_DIR") {
+        Ok(test_dir) => PathBuf::from(test_dir),
+        Err(_) => crate::project_storage::get_projects_directory().ok()?,
+    };
+
+    let suffix = format!("_{project_id_or_path}.scormproj");
+    fs::read_dir(projects_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| name.ends_with(&suffix))
+        })
+}
+
+/// Scan the saved course content of a project. Projects that haven't been saved
+/// yet (or have no content) have no references.
+pub fn load_media_usage(project_id_or_path: &str) -> Result<MediaUsageMap, String> {
+    let Some(project_path) = find_project_file(project_id_or_path) else {
+        return Ok(MediaUsageMap::new());
+    };
+
+    let project = load_project_file(&project_path)?;
+    Ok(project
+        .course_content
+        .as_ref()
+        .map(scan_media_usage)
+        .unwrap_or_default())
+}
+
+/// Report which pages reference each media item of a project
+#[tauri::command]
+pub fn get_media_usage(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<MediaUsageMap, String> {
+    load_media_usage(&projectId)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_content() -> Value {
+        json!({
+            "welcomePage": {
+                "id": "welcome",
+                "media": [{ "id": "image-0", "type": "image" }],
+                "audioId": "audio-0"
+            },
+            "learningObjectivesPage": {
+                "id": "objectives",
+                "audioId": "audio-1",
+                "captionId": "caption-1"
+            },
+            "topics": [
+                {
+                    "id": "topic-0",
+                    "media": [{ "id": "image-0" }, { "id": "video-1" }],
+                    "knowledgeCheck": {
+                        "questions": [{ "feedback": { "mediaId": "image-7" } }]
+                    }
+                },
+                { "media": [{ "id": "image-0" }] }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_scan_media_usage() {
+        let usage = scan_media_usage(&sample_content());
+
+        assert_eq!(usage["image-0"], vec!["welcome", "topic-0", "topic-1"]);
+        assert_eq!(usage["audio-0"], vec!["welcome"]);
+        assert_eq!(usage["caption-1"], vec!["objectives"]);
+        assert_eq!(usage["image-7"], vec!["topic-0"]);
+        assert!(!usage.contains_key("audio-2"));
+    }
+
+    #[test]
+    fn test_find_media_references() {
+        let content = sample_content();
+
+        assert_eq!(find_media_references(&content, "video-1"), vec!["topic-0"]);
+        assert!(find_media_references(&content, "image-99").is_empty());
+    }
+}
@@ -0,0 +1,451 @@
+//! Key-based catalog for user-facing error and progress messages.
+//!
+//! Commands describe what happened with a [`Message`] (a catalog key plus
+//! parameters). It is rendered in the locale from the app settings, and the key
+//! and parameters travel alongside the text so the UI can localize it itself.
+//!
+//! The catalog covers progress events, warnings and the errors that stop a
+//! whole operation and tell the user what to do next: generation failures,
+//! disk space, operations already running, storage budgets and locked
+//! projects. Other command errors are still English strings. Most of them
+//! carry an OS, parser or HTTP error that has no translation, and some are
+//! matched by their text in the UI, such as the missing-license refusal.
+//! Where one of them ends up in a [`Message`] it goes through `error.detail`.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+
+/// Locale used when the setting is missing or has no catalog
+pub const DEFAULT_LOCALE: &str = "en";
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+#[rustfmt::skip]
+const EN: Catalog = &[
+    ("error.detail", "{detail}"),
+    ("error.scorm.parse_course", "Failed to parse course data: {error}"),
+    ("error.scorm.generate", "Failed to generate SCORM package: {error}"),
+    ("error.scorm.render", "{count} page(s) failed to render: {error}"),
+    ("error.scorm.clip_timing", "{count} YouTube clip(s) don't fit their video: {clips}. Fix the clip start and end times and try again."),
+    ("error.disk_space", "Not enough disk space on {path}: about {needed} needed, {free} free. Free up space and try again."),
+    ("error.operation.generate_running", "A SCORM package is already being generated for this project (operation {id}). Wait for it to finish."),
+    ("error.operation.export_running", "This project is already being exported (operation {id}). Wait for it to finish."),
+    ("error.operation.import_running", "This project is already being imported (operation {id}). Wait for it to finish."),
+    ("error.import.cancelled", "Import cancelled. No project was added."),
+    ("error.storage_budget.project", "Storing {file} would bring this project's media to {size}, over its {limit} budget. Most space is taken by {largest}. Remove unused media or compress large files and try again."),
+    ("error.storage_budget.media_type", "Storing {file} would bring this project's {type} files to {size}, over the {limit} allowed for them. Most space is taken by {largest}. Remove unused media or compress large files and try again."),
+    ("warning.storage_budget", "This project's media takes {size}, past its {limit} budget level. Most space is taken by {largest}."),
+    ("error.project.locked", "The project {name} is encrypted and locked. Unlock it with its passphrase and try again."),
+    ("warning.project.encryption_recovery", "Encrypted projects can only be opened with their passphrase. It is not stored anywhere and cannot be reset: if it is lost, the project's content and media cannot be recovered. Keep it somewhere safe."),
+    ("warning.project.unencrypted_exports", "Exports made before encryption was turned on are not encrypted. Delete them if they must not stay readable."),
+    ("progress.scorm.parsing", "Parsing course data..."),
+    ("progress.scorm.processing_media", "Processing media files..."),
+    ("progress.scorm.processing_binary", "Processing {count} binary files..."),
+    ("progress.scorm.processing_media_file", "Processing media file {current}/{total}..."),
+    ("progress.scorm.generating_html", "Generating HTML content..."),
+    ("progress.scorm.rendering_page", "Rendering page {current}/{total}..."),
+    ("progress.scorm.creating_package", "Creating SCORM package..."),
+    ("progress.scorm.transforming_media", "Applying media transformations..."),
+    ("progress.scorm.missing_media", "{count} referenced media files are missing; check the warnings before publishing"),
+    ("progress.scorm.content_lint", "{count} images or videos have no alt text; add it so screen readers can describe them"),
+    ("progress.scorm.missing_license", "{count} credited media files have no license recorded; check the terms they may be used under"),
+    ("progress.scorm.insecure_urls", "{count} images or embeds are loaded over http and will be blocked on https portals"),
+    ("progress.scorm.finalizing", "Finalizing package..."),
+    ("progress.scorm.done", "SCORM package generated successfully!"),
+    ("progress.export.loading", "Loading project file..."),
+    ("progress.export.validating", "Validating project data..."),
+    ("progress.export.scanning", "Scanning media directory..."),
+    ("progress.export.processing_media", "Processing {count} media files..."),
+    ("progress.export.processing_media_file", "Processing media files ({current}/{total})"),
+    ("progress.export.creating_archive", "Creating archive..."),
+    ("progress.export.finalizing", "Finalizing export..."),
+    ("progress.export.done", "Export completed successfully!"),
+    ("progress.import.extracting", "Extracting files ({current}/{total})"),
+    ("progress.import.validating", "Checking project data..."),
+    ("progress.import.copying_media", "Copying media files ({current}/{total})"),
+    ("progress.import.completing", "Saving imported project..."),
+    ("progress.archive.project", "Archiving {name} ({current} of {total})"),
+    ("progress.archive.done", "Archived {exported} of {total} projects"),
+    ("progress.media_download.item", "Downloaded {current} of {total} files"),
+    ("progress.media_download.done", "Stored {stored} of {total} downloaded files"),
+    ("progress.embedded_media.item", "Extracted {current} of {total} embedded media files"),
+    ("progress.embedded_media.done", "Moved {count} embedded media files to the media folder"),
+];
+
+#[rustfmt::skip]
+const ES: Catalog = &[
+    ("error.detail", "{detail}"),
+    ("error.scorm.parse_course", "No se pudieron leer los datos del curso: {error}"),
+    ("error.scorm.generate", "No se pudo generar el paquete SCORM: {error}"),
+    ("error.scorm.render", "No se pudieron generar {count} página(s): {error}"),
+    ("error.scorm.clip_timing", "{count} clip(s) de YouTube no encajan en su vídeo: {clips}. Corrija los tiempos de inicio y fin del clip e inténtelo de nuevo."),
+    ("error.disk_space", "No hay suficiente espacio en disco en {path}: se necesitan unos {needed} y hay {free} libres. Libere espacio e inténtelo de nuevo."),
+    ("error.operation.generate_running", "Ya se está generando un paquete SCORM para este proyecto (operación {id}). Espere a que termine."),
+    ("error.operation.export_running", "Este proyecto ya se está exportando (operación {id}). Espere a que termine."),
+    ("error.operation.import_running", "Este proyecto ya se está importando (operación {id}). Espere a que termine."),
+    ("error.import.cancelled", "Importación cancelada. No se ha añadido ningún proyecto."),
+    ("error.storage_budget.project", "Guardar {file} llevaría los medios de este proyecto a {size}, por encima de su límite de {limit}. La mayor parte del espacio lo ocupan: {largest}. Elimine medios sin usar o comprima los archivos grandes e inténtelo de nuevo."),
+    ("error.storage_budget.media_type", "Guardar {file} llevaría los archivos {type} de este proyecto a {size}, por encima de los {limit} permitidos. La mayor parte del espacio lo ocupan: {largest}. Elimine medios sin usar o comprima los archivos grandes e inténtelo de nuevo."),
+    ("warning.storage_budget", "Los medios de este proyecto ocupan {size}, por encima del nivel de {limit} de su presupuesto. La mayor parte del espacio lo ocupan: {largest}."),
+    ("error.project.locked", "El proyecto {name} está cifrado y bloqueado. Desbloquéelo con su frase de contraseña e inténtelo de nuevo."),
+    ("warning.project.encryption_recovery", "Los proyectos cifrados solo se pueden abrir con su frase de contraseña. No se guarda en ningún sitio y no se puede restablecer: si se pierde, el contenido y los medios del proyecto no se pueden recuperar. Guárdela en un lugar seguro."),
+    ("warning.project.unencrypted_exports", "Las exportaciones hechas antes de activar el cifrado no están cifradas. Elimínelas si no deben seguir siendo legibles."),
+    ("progress.scorm.parsing", "Leyendo los datos del curso..."),
+    ("progress.scorm.processing_media", "Procesando archivos multimedia..."),
+    ("progress.scorm.processing_binary", "Procesando {count} archivos binarios..."),
+    ("progress.scorm.processing_media_file", "Procesando archivo multimedia {current}/{total}..."),
+    ("progress.scorm.generating_html", "Generando contenido HTML..."),
+    ("progress.scorm.rendering_page", "Generando página {current}/{total}..."),
+    ("progress.scorm.creating_package", "Creando paquete SCORM..."),
+    ("progress.scorm.transforming_media", "Aplicando transformaciones multimedia..."),
+    ("progress.scorm.missing_media", "Faltan {count} archivos multimedia referenciados; revise los avisos antes de publicar"),
+    ("progress.scorm.content_lint", "{count} imágenes o vídeos no tienen texto alternativo; añádalo para que los lectores de pantalla puedan describirlos"),
+    ("progress.scorm.missing_license", "{count} archivos multimedia acreditados no tienen licencia registrada; compruebe las condiciones de uso"),
+    ("progress.scorm.insecure_urls", "{count} imágenes o contenidos incrustados se cargan por http y se bloquearán en portales https"),
+    ("progress.scorm.finalizing", "Finalizando paquete..."),
+    ("progress.scorm.done", "¡Paquete SCORM generado correctamente!"),
+    ("progress.export.loading", "Cargando archivo del proyecto..."),
+    ("progress.export.validating", "Validando datos del proyecto..."),
+    ("progress.export.scanning", "Examinando la carpeta multimedia..."),
+    ("progress.export.processing_media", "Procesando {count} archivos multimedia..."),
+    ("progress.export.processing_media_file", "Procesando archivos multimedia ({current}/{total})"),
+    ("progress.export.creating_archive", "Creando archivo comprimido..."),
+    ("progress.export.finalizing", "Finalizando exportación..."),
+    ("progress.export.done", "¡Exportación completada!"),
+    ("progress.import.extracting", "Extrayendo archivos ({current}/{total})"),
+    ("progress.import.validating", "Comprobando los datos del proyecto..."),
+    ("progress.import.copying_media", "Copiando archivos multimedia ({current}/{total})"),
+    ("progress.import.completing", "Guardando el proyecto importado..."),
+    ("progress.archive.project", "Archivando {name} ({current} de {total})"),
+    ("progress.archive.done", "Se archivaron {exported} de {total} proyectos"),
+    ("progress.media_download.item", "Descargados {current} de {total} archivos"),
+    ("progress.media_download.done", "Se guardaron {stored} de {total} archivos descargados"),
+    ("progress.embedded_media.item", "Extraídos {current} de {total} archivos multimedia incrustados"),
+    ("progress.embedded_media.done", "Se movieron {count} archivos multimedia incrustados a la carpeta multimedia"),
+];
+
+#[rustfmt::skip]
+const FR: Catalog = &[
+    ("error.detail", "{detail}"),
+    ("error.scorm.parse_course", "Impossible de lire les données du cours : {error}"),
+    ("error.scorm.generate", "Impossible de générer le paquet SCORM : {error}"),
+    ("error.scorm.render", "Impossible de générer {count} page(s) : {error}"),
+    ("error.scorm.clip_timing", "{count} extrait(s) YouTube ne tiennent pas dans leur vidéo : {clips}. Corrigez les temps de début et de fin de l'extrait et réessayez."),
+    ("error.disk_space", "Espace disque insuffisant sur {path} : environ {needed} nécessaires, {free} disponibles. Libérez de l'espace et réessayez."),
+    ("error.operation.generate_running", "Un paquet SCORM est déjà en cours de génération pour ce projet (opération {id}). Attendez qu'elle se termine."),
+    ("error.operation.export_running", "Ce projet est déjà en cours d'export (opération {id}). Attendez qu'il se termine."),
+    ("error.operation.import_running", "Ce projet est déjà en cours d'import (opération {id}). Attendez qu'il se termine."),
+    ("error.import.cancelled", "Import annulé. Aucun projet n'a été ajouté."),
+    ("error.storage_budget.project", "Enregistrer {file} porterait les médias de ce projet à {size}, au-delà de sa limite de {limit}. L'essentiel de l'espace est occupé par : {largest}. Supprimez les médias inutilisés ou compressez les fichiers volumineux et réessayez."),
+    ("error.storage_budget.media_type", "Enregistrer {file} porterait les fichiers {type} de ce projet à {size}, au-delà des {limit} autorisés. L'essentiel de l'espace est occupé par : {largest}. Supprimez les médias inutilisés ou compressez les fichiers volumineux et réessayez."),
+    ("warning.storage_budget", "Les médias de ce projet occupent {size}, au-delà du seuil de {limit} de son budget. L'essentiel de l'espace est occupé par : {largest}."),
+    ("error.project.locked", "Le projet {name} est chiffré et verrouillé. Déverrouillez-le avec sa phrase secrète et réessayez."),
+    ("warning.project.encryption_recovery", "Un projet chiffré ne peut être ouvert qu'avec sa phrase secrète. Elle n'est enregistrée nulle part et ne peut pas être réinitialisée : si elle est perdue, le contenu et les médias du projet sont irrécupérables. Conservez-la en lieu sûr."),
+    ("warning.project.unencrypted_exports", "Les exports faits avant l'activation du chiffrement ne sont pas chiffrés. Supprimez-les s'ils ne doivent pas rester lisibles."),
+    ("progress.scorm.parsing", "Lecture des données du cours..."),
+    ("progress.scorm.processing_media", "Traitement des fichiers multimédias..."),
+    ("progress.scorm.processing_binary", "Traitement de {count} fichiers binaires..."),
+    ("progress.scorm.processing_media_file", "Traitement du fichier multimédia {current}/{total}..."),
+    ("progress.scorm.generating_html", "Génération du contenu HTML..."),
+    ("progress.scorm.rendering_page", "Génération de la page {current}/{total}..."),
+    ("progress.scorm.creating_package", "Création du paquet SCORM..."),
+    ("progress.scorm.transforming_media", "Application des transformations multimédias..."),
+    ("progress.scorm.missing_media", "{count} fichiers multimédias référencés sont manquants ; vérifiez les avertissements avant de publier"),
+    ("progress.scorm.content_lint", "{count} images ou vidéos n'ont pas de texte alternatif ; ajoutez-le pour que les lecteurs d'écran puissent les décrire"),
+    ("progress.scorm.missing_license", "{count} fichiers multimédias crédités n'ont pas de licence enregistrée ; vérifiez leurs conditions d'utilisation"),
+    ("progress.scorm.insecure_urls", "{count} images ou contenus intégrés sont chargés en http et seront bloqués sur les portails https"),
+    ("progress.scorm.finalizing", "Finalisation du paquet..."),
+    ("progress.scorm.done", "Paquet SCORM généré avec succès !"),
+    ("progress.export.loading", "Chargement du fichier de projet..."),
+    ("progress.export.validating", "Validation des données du projet..."),
+    ("progress.export.scanning", "Analyse du dossier multimédia..."),
+    ("progress.export.processing_media", "Traitement de {count} fichiers multimédias..."),
+    ("progress.export.processing_media_file", "Traitement des fichiers multimédias ({current}/{total})"),
+    ("progress.export.creating_archive", "Création de l'archive..."),
+    ("progress.export.finalizing", "Finalisation de l'export..."),
+    ("progress.export.done", "Export terminé avec succès !"),
+    ("progress.import.extracting", "Extraction des fichiers ({current}/{total})"),
+    ("progress.import.validating", "Vérification des données du projet..."),
+    ("progress.import.copying_media", "Copie des fichiers multimédias ({current}/{total})"),
+    ("progress.import.completing", "Enregistrement du projet importé..."),
+    ("progress.archive.project", "Archivage de {name} ({current} sur {total})"),
+    ("progress.archive.done", "{exported} projets sur {total} archivés"),
+    ("progress.media_download.item", "{current} fichiers sur {total} téléchargés"),
+    ("progress.media_download.done", "{stored} fichiers téléchargés sur {total} enregistrés"),
+    ("progress.embedded_media.item", "{current} fichiers multimédias intégrés sur {total} extraits"),
+    ("progress.embedded_media.done", "{count} fichiers multimédias intégrés déplacés dans le dossier multimédia"),
+];
+
+#[rustfmt::skip]
+const DE: Catalog = &[
+    ("error.detail", "{detail}"),
+    ("error.scorm.parse_course", "Kursdaten konnten nicht gelesen werden: {error}"),
+    ("error.scorm.generate", "SCORM-Paket konnte nicht erstellt werden: {error}"),
+    ("error.scorm.render", "{count} Seite(n) konnten nicht erstellt werden: {error}"),
+    ("error.scorm.clip_timing", "{count} YouTube-Ausschnitt/-Ausschnitte passen nicht in ihr Video: {clips}. Korrigieren Sie Start- und Endzeit des Ausschnitts und versuchen Sie es erneut."),
+    ("error.disk_space", "Nicht genug Speicherplatz auf {path}: etwa {needed} benötigt, {free} frei. Geben Sie Speicherplatz frei und versuchen Sie es erneut."),
+    ("error.operation.generate_running", "Für dieses Projekt wird bereits ein SCORM-Paket erstellt (Vorgang {id}). Warten Sie, bis er abgeschlossen ist."),
+    ("error.operation.export_running", "Dieses Projekt wird bereits exportiert (Vorgang {id}). Warten Sie, bis der Export abgeschlossen ist."),
+    ("error.operation.import_running", "Dieses Projekt wird bereits importiert (Vorgang {id}). Warten Sie, bis der Import abgeschlossen ist."),
+    ("error.import.cancelled", "Import abgebrochen. Es wurde kein Projekt hinzugefügt."),
+    ("error.storage_budget.project", "Durch das Speichern von {file} würden die Medien dieses Projekts {size} belegen, mehr als das Limit von {limit}. Den meisten Platz belegen: {largest}. Entfernen Sie ungenutzte Medien oder komprimieren Sie große Dateien und versuchen Sie es erneut."),
+    ("error.storage_budget.media_type", "Durch das Speichern von {file} würden die {type}-Dateien dieses Projekts {size} belegen, mehr als die erlaubten {limit}. Den meisten Platz belegen: {largest}. Entfernen Sie ungenutzte Medien oder komprimieren Sie große Dateien und versuchen Sie es erneut."),
+    ("warning.storage_budget", "Die Medien dieses Projekts belegen {size}, mehr als die Warnschwelle von {limit}. Den meisten Platz belegen: {largest}."),
+    ("error.project.locked", "Das Projekt {name} ist verschlüsselt und gesperrt. Entsperren Sie es mit seiner Passphrase und versuchen Sie es erneut."),
+    ("warning.project.encryption_recovery", "Verschlüsselte Projekte lassen sich nur mit ihrer Passphrase öffnen. Sie wird nirgends gespeichert und kann nicht zurückgesetzt werden: Geht sie verloren, sind Inhalte und Medien des Projekts nicht wiederherstellbar. Bewahren Sie sie sicher auf."),
+    ("warning.project.unencrypted_exports", "Exporte von vor dem Aktivieren der Verschlüsselung sind nicht verschlüsselt. Löschen Sie sie, wenn sie nicht lesbar bleiben dürfen."),
+    ("progress.scorm.parsing", "Kursdaten werden gelesen..."),
+    ("progress.scorm.processing_media", "Mediendateien werden verarbeitet..."),
+    ("progress.scorm.processing_binary", "{count} Binärdateien werden verarbeitet..."),
+    ("progress.scorm.processing_media_file", "Mediendatei {current}/{total} wird verarbeitet..."),
+    ("progress.scorm.generating_html", "HTML-Inhalte werden erstellt..."),
+    ("progress.scorm.rendering_page", "Seite {current}/{total} wird erstellt..."),
+    ("progress.scorm.creating_package", "SCORM-Paket wird erstellt..."),
+    ("progress.scorm.transforming_media", "Medienumwandlungen werden angewendet..."),
+    ("progress.scorm.missing_media", "{count} referenzierte Mediendateien fehlen; prüfen Sie die Warnungen vor der Veröffentlichung"),
+    ("progress.scorm.content_lint", "{count} Bilder oder Videos haben keinen Alternativtext; ergänzen Sie ihn, damit Screenreader sie beschreiben können"),
+    ("progress.scorm.missing_license", "{count} Mediendateien mit Quellenangabe haben keine erfasste Lizenz; prüfen Sie die Nutzungsbedingungen"),
+    ("progress.scorm.insecure_urls", "{count} Bilder oder Einbettungen werden über http geladen und auf https-Portalen blockiert"),
+    ("progress.scorm.finalizing", "Paket wird abgeschlossen..."),
+    ("progress.scorm.done", "SCORM-Paket erfolgreich erstellt!"),
+    ("progress.export.loading", "Projektdatei wird geladen..."),
+    ("progress.export.validating", "Projektdaten werden geprüft..."),
+    ("progress.export.scanning", "Medienordner wird durchsucht..."),
+    ("progress.export.processing_media", "{count} Mediendateien werden verarbeitet..."),
+    ("progress.export.processing_media_file", "Mediendateien werden verarbeitet ({current}/{total})"),
+    ("progress.export.creating_archive", "Archiv wird erstellt..."),
+    ("progress.export.finalizing", "Export wird abgeschlossen..."),
+    ("progress.export.done", "Export erfolgreich abgeschlossen!"),
+    ("progress.import.extracting", "Dateien werden entpackt ({current}/{total})"),
+    ("progress.import.validating", "Projektdaten werden geprüft..."),
+    ("progress.import.copying_media", "Mediendateien werden kopiert ({current}/{total})"),
+    ("progress.import.completing", "Importiertes Projekt wird gespeichert..."),
+    ("progress.archive.project", "{name} wird archiviert ({current} von {total})"),
+    ("progress.archive.done", "{exported} von {total} Projekten archiviert"),
+    ("progress.media_download.item", "{current} von {total} Dateien heruntergeladen"),
+    ("progress.media_download.done", "{stored} von {total} heruntergeladenen Dateien gespeichert"),
+    ("progress.embedded_media.item", "{current} von {total} eingebetteten Mediendateien extrahiert"),
+    ("progress.embedded_media.done", "{count} eingebettete Mediendateien in den Medienordner verschoben"),
+];
+
+const CATALOGS: &[(&str, Catalog)] = &[("en", EN), ("es", ES), ("fr", FR), ("de", DE)];
+
+/// Locale set in the app settings, cached after the first lookup
+static LOCALE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Catalog for `locale`, matching `fr-CA` to `fr` and falling back to English
+fn catalog(locale: &str) -> Catalog {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    CATALOGS
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(language))
+        .map(|(_, catalog)| *catalog)
+        .unwrap_or(EN)
+}
+
+fn lookup(catalog: Catalog, key: &str) -> Option<&'static str> {
+    catalog
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, text)| *text)
+}
+
+/// Locale messages are currently rendered in
+pub fn current_locale() -> String {
+    if let Some(locale) = LOCALE.read().ok().and_then(|locale| locale.clone()) {
+        return locale;
+    }
+    let locale = crate::settings::load_settings()
+        .ok()
+        .and_then(|settings| settings.locale);
+    set_locale(locale)
+}
+
+/// Called whenever the settings are saved so the next message uses the new locale
+pub fn set_locale(locale: Option<String>) -> String {
+    let locale = locale.unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+    if let Ok(mut current) = LOCALE.write() {
+        *current = Some(locale.clone());
+    }
+    locale
+}
+
+/// A catalog key with its parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub key: &'static str,
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            params: Vec::new(),
+        }
+    }
+
+    /// Add a `{name}` parameter
+    pub fn with(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    /// Text in `locale`, falling back to English and then to the key itself.
+    /// Placeholders are filled in one pass, so a value containing `{name}` is
+    /// kept as it is.
+    pub fn render(&self, locale: &str) -> String {
+        let template = lookup(catalog(locale), self.key)
+            .or_else(|| lookup(EN, self.key))
+            .unwrap_or(self.key);
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            text.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let param = after.find('}').and_then(|close| {
+                let name = &after[..close];
+                self.params
+                    .iter()
+                    .find(|(param, _)| *param == name)
+                    .map(|(_, value)| (close, value))
+            });
+            match param {
+                Some((close, value)) => {
+                    text.push_str(value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = after;
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+
+    /// Payload for a progress event: `fields` plus `message`, `messageKey` and `params`
+    pub fn progress_event(&self, fields: serde_json::Value) -> serde_json::Value {
+        let mut event = serde_json::to_value(self).unwrap_or_default();
+        if let (Some(event), serde_json::Value::Object(fields)) = (event.as_object_mut(), fields) {
+            event.extend(fields);
+        }
+        event
+    }
+
+    fn params_map(&self) -> HashMap<&'static str, &str> {
+        self.params
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect()
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(&current_locale()))
+    }
+}
+
+/// Serialized as `{ message, messageKey, params }` so callers that only read
+/// `message` keep working
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Message", 3)?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("messageKey", self.key)?;
+        state.serialize_field("params", &self.params_map())?;
+        state.end()
+    }
+}
+
+/// Errors that aren't in the catalog yet are passed through as `error.detail`
+impl From<String> for Message {
+    fn from(detail: String) -> Self {
+        Message::new("error.detail").with("detail", detail)
+    }
+}
+
+impl From<Message> for String {
+    fn from(message: Message) -> String {
+        message.to_string()
+    }
+}
+
+/// Catalog for the UI, so it can render message keys without a round trip
+#[tauri::command]
+pub fn get_message_catalog(locale: Option<String>) -> HashMap<&'static str, &'static str> {
+    let locale = locale.unwrap_or_else(current_locale);
+    let catalog = catalog(&locale);
+    EN.iter()
+        .map(|(key, text)| (*key, lookup(catalog, key).unwrap_or(text)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_locale_covers_the_english_catalog() {
+        for (code, catalog) in CATALOGS {
+            for (key, text) in EN {
+                let translated =
+                    lookup(catalog, key).unwrap_or_else(|| panic!("{code} is missing {key}"));
+                for param in ["{count}", "{current}", "{total}", "{error}", "{detail}"] {
+                    assert_eq!(
+                        text.contains(param),
+                        translated.contains(param),
+                        "{code} {key} should use the same parameters as English"
+                    );
+                }
+            }
+            assert_eq!(catalog.len(), EN.len(), "{code} has keys English doesn't");
+        }
+    }
+
+    #[test]
+    fn test_render_with_params_and_fallbacks() {
+        let message = Message::new("progress.scorm.processing_media_file")
+            .with("current", 3)
+            .with("total", 10);
+
+        assert_eq!(message.render("en"), "Processing media file 3/10...");
+        assert_eq!(
+            message.render("fr-CA"),
+            "Traitement du fichier multimédia 3/10..."
+        );
+        assert_eq!(message.render("ja"), "Processing media file 3/10...");
+        assert_eq!(Message::new("unknown.key").render("de"), "unknown.key");
+    }
+
+    #[test]
+    fn test_param_values_are_not_substituted_again() {
+        let message = Message::new("progress.archive.project")
+            .with("name", "Course {total} {unknown")
+            .with("current", 1)
+            .with("total", 2);
+
+        assert_eq!(
+            message.render("en"),
+            "Archiving Course {total} {unknown (1 of 2)"
+        );
+    }
+
+    #[test]
+    fn test_progress_event_carries_key_and_params() {
+        let event = Message::new("progress.export.processing_media")
+            .with("count", 4)
+            .progress_event(serde_json::json!({ "progress": 30, "phase": "processing" }));
+
+        assert_eq!(event["messageKey"], "progress.export.processing_media");
+        assert_eq!(event["params"]["count"], "4");
+        assert_eq!(event["progress"], 30);
+        assert_eq!(event["phase"], "processing");
+        assert!(event["message"].is_string());
+    }
+}
@@ -0,0 +1,396 @@
+//! Rules for which metadata fields each media type may carry.
+//!
+//! Images and audio used to pick up a YouTube item's source, embed URL and
+//! clip timing, and then showed up as players. The policy names, per media
+//! type, the fields it must not have and what happens when it does: the
+//! metadata is rejected, cleaned (the default) or kept with a warning. It is
+//! applied wherever metadata is written: `store_media`, the batch metadata
+//! editor and project import. Every cleaned or warned item is appended to
+//! [`AUDIT_FILE`] in the project folder, so an author can see what changed.
+
+use crate::media_storage::MediaMetadata;
+use crate::project_encryption::{read_metadata_file, write_media_file};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+/// Kept in each project folder next to `media/`
+pub const AUDIT_FILE: &str = "metadata-audit.json";
+
+/// Oldest entries are dropped beyond this many
+const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// Key of the rule for media types without one of their own
+const OTHER_TYPES: &str = "*";
+
+static AUDIT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// A metadata field a rule can forbid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum MetadataField {
+    /// `source` set to `youtube`
+    YoutubeSource,
+    EmbedUrl,
+    ClipStart,
+    ClipEnd,
+}
+
+impl MetadataField {
+    pub const ALL: [MetadataField; 4] = [
+        MetadataField::YoutubeSource,
+        MetadataField::EmbedUrl,
+        MetadataField::ClipStart,
+        MetadataField::ClipEnd,
+    ];
+
+    /// As written in the settings
+    pub fn name(self) -> &'static str {
+        match self {
+            MetadataField::YoutubeSource => "youtube_source",
+            MetadataField::EmbedUrl => "embed_url",
+            MetadataField::ClipStart => "clip_start",
+            MetadataField::ClipEnd => "clip_end",
+        }
+    }
+
+    fn is_set(self, metadata: &MediaMetadata) -> bool {
+        match self {
+            MetadataField::YoutubeSource => metadata.source.as_deref() == Some("youtube"),
+            MetadataField::EmbedUrl => metadata.embed_url.is_some(),
+            MetadataField::ClipStart => metadata.clip_start.is_some(),
+            MetadataField::ClipEnd => metadata.clip_end.is_some(),
+        }
+    }
+
+    fn clear(self, metadata: &mut MediaMetadata) {
+        match self {
+            MetadataField::YoutubeSource => metadata.source = None,
+            MetadataField::EmbedUrl => metadata.embed_url = None,
+            MetadataField::ClipStart => metadata.clip_start = None,
+            MetadataField::ClipEnd => metadata.clip_end = None,
+        }
+    }
+}
+
+/// What happens to metadata carrying a forbidden field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ViolationAction {
+    /// Refuse to write it
+    Reject,
+    /// Drop the forbidden fields
+    #[default]
+    Clean,
+    /// Keep it as it is, recording a warning
+    Warn,
+}
+
+/// Which metadata fields each media type may carry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct MetadataPolicy {
+    pub action: ViolationAction,
+    /// Fields each media type may not carry. The `*` entry applies to types
+    /// without their own; types covered by neither may carry anything.
+    pub forbidden_fields: HashMap<String, Vec<MetadataField>>,
+}
+
+impl Default for MetadataPolicy {
+    fn default() -> Self {
+        Self {
+            action: ViolationAction::default(),
+            forbidden_fields: HashMap::from([
+                (OTHER_TYPES.to_string(), MetadataField::ALL.to_vec()),
+                ("video".to_string(), Vec::new()),
+                ("youtube".to_string(), Vec::new()),
+            ]),
+        }
+    }
+}
+
+/// An item the policy changed or warned about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MetadataAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub media_id: String,
+    pub media_type: String,
+    pub action: ViolationAction,
+    pub fields: Vec<MetadataField>,
+    /// Where the metadata came from: `store`, `bulk_edit` or `import`
+    #[ts(type = "\"store\" | \"bulk_edit\" | \"import\"")]
+    pub origin: String,
+}
+
+impl MetadataPolicy {
+    /// Fields `media_type` may not carry
+    pub fn forbidden(&self, media_type: &str) -> &[MetadataField] {
+        self.forbidden_fields
+            .get(media_type)
+            .or_else(|| self.forbidden_fields.get(OTHER_TYPES))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Forbidden fields `metadata` carries
+    pub fn violations(&self, metadata: &MediaMetadata) -> Vec<MetadataField> {
+        self.forbidden(&metadata.media_type)
+            .iter()
+            .copied()
+            .filter(|field| field.is_set(metadata))
+            .collect()
+    }
+
+    /// `metadata` as it may be written, with an audit entry when the policy
+    /// cleaned or warned about it. Rejected metadata is an error.
+    pub fn apply(
+        &self,
+        media_id: &str,
+        mut metadata: MediaMetadata,
+        origin: &str,
+    ) -> Result<(MediaMetadata, Option<MetadataAuditEntry>), String> {
+        let fields = self.violations(&metadata);
+        if fields.is_empty() {
+            return Ok((metadata, None));
+        }
+        if self.action == ViolationAction::Reject {
+            return Err(format!(
+                "{} metadata may not have {}",
+                metadata.media_type,
+                field_names(&fields)
+            ));
+        }
+        if self.action == ViolationAction::Clean {
+            fields.iter().for_each(|field| field.clear(&mut metadata));
+        }
+        let entry = MetadataAuditEntry {
+            timestamp: Utc::now(),
+            media_id: media_id.to_string(),
+            media_type: metadata.media_type.clone(),
+            action: self.action,
+            fields,
+            origin: origin.to_string(),
+        };
+        Ok((metadata, Some(entry)))
+    }
+}
+
+fn field_names(fields: &[MetadataField]) -> String {
+    fields
+        .iter()
+        .map(|field| field.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Apply the policy to every `{id}.json` in an imported project's media
+/// folder, rewriting cleaned files. Rejected items fail the whole import.
+pub fn apply_to_media_dir(
+    policy: &MetadataPolicy,
+    media_dir: &Path,
+    origin: &str,
+) -> Result<Vec<MetadataAuditEntry>, String> {
+    let mut entries = Vec::new();
+    let mut rejected = Vec::new();
+    let mut files: Vec<_> = fs::read_dir(media_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+    files.sort();
+    for path in files {
+        let media_id = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        // Files that aren't media metadata are left to the import's other checks
+        let Some(metadata) = read_metadata_file(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<MediaMetadata>(&json).ok())
+        else {
+            continue;
+        };
+        match policy.apply(&media_id, metadata, origin) {
+            Ok((metadata, Some(entry))) => {
+                if entry.action == ViolationAction::Clean {
+                    let json = serde_json::to_string_pretty(&metadata)
+                        .map_err(|e| format!("Failed to serialize metadata: {e}"))?;
+                    write_media_file(&path, json.as_bytes())
+                        .map_err(|e| format!("Failed to write metadata: {e}"))?;
+                }
+                entries.push(entry);
+            }
+            Ok((_, None)) => {}
+            Err(e) => rejected.push(format!("{media_id}: {e}")),
+        }
+    }
+    if !rejected.is_empty() {
+        return Err(format!(
+            "The metadata policy rejected {} media item(s): {}",
+            rejected.len(),
+            rejected.join("; ")
+        ));
+    }
+    Ok(entries)
+}
+
+/// Audit entries of the project in `project_dir`, oldest first
+pub fn load_audit(project_dir: &Path) -> Result<Vec<MetadataAuditEntry>, String> {
+    let path = project_dir.join(AUDIT_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read metadata audit: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse metadata audit: {e}"))
+}
+
+/// Append `entries` to the audit of the project in `project_dir`
+pub fn append_audit(project_dir: &Path, entries: Vec<MetadataAuditEntry>) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    // An unreadable audit is started over rather than blocking the write
+    let mut audit = load_audit(project_dir).unwrap_or_default();
+    audit.extend(entries);
+    if audit.len() > MAX_AUDIT_ENTRIES {
+        audit.drain(..audit.len() - MAX_AUDIT_ENTRIES);
+    }
+    fs::create_dir_all(project_dir)
+        .map_err(|e| format!("Failed to create project directory: {e}"))?;
+    let path = project_dir.join(AUDIT_FILE);
+    let json = serde_json::to_string_pretty(&audit)
+        .map_err(|e| format!("Failed to serialize metadata audit: {e}"))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write metadata audit: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write metadata audit: {e}"))
+}
+
+/// The policy from the app settings, or the default one
+pub fn current_policy() -> MetadataPolicy {
+    crate::settings::load_settings()
+        .ok()
+        .and_then(|settings| settings.metadata_policy)
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_metadata_policy() -> Result<MetadataPolicy, String> {
+    Ok(current_policy())
+}
+
+#[tauri::command]
+pub fn set_metadata_policy(policy: MetadataPolicy) -> Result<(), String> {
+    let mut settings = crate::settings::load_settings()?;
+    settings.metadata_policy = Some(policy);
+    crate::settings::save_settings(&settings)
+}
+
+/// Metadata the policy cleaned or warned about in a project, newest first
+#[tauri::command]
+pub fn get_metadata_audit(
+    #[allow(non_snake_case)] projectId: String,
+    limit: Option<usize>,
+) -> Result<Vec<MetadataAuditEntry>, String> {
+    if !crate::backup_recovery::is_project_id(&projectId) {
+        return Err(format!("Invalid project ID: {projectId}"));
+    }
+    let projects_dir = crate::settings::get_projects_directory()?;
+    let mut audit = load_audit(&projects_dir.join(&projectId))?;
+    audit.reverse();
+    if let Some(limit) = limit {
+        audit.truncate(limit);
+    }
+    Ok(audit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn metadata(media_type: &str) -> MediaMetadata {
+        MediaMetadata {
+            page_id: "welcome".to_string(),
+            media_type: media_type.to_string(),
+            original_name: "clip.bin".to_string(),
+            mime_type: None,
+            source: Some("youtube".to_string()),
+            embed_url: Some("https://www.youtube.com/embed/abc".to_string()),
+            title: None,
+            clip_start: Some(30),
+            clip_end: None,
+            alt_text: None,
+            attribution: None,
+        }
+    }
+
+    #[test]
+    fn test_actions_per_media_type() {
+        let policy = MetadataPolicy::default();
+        let (video, entry) = policy.apply("video-0", metadata("video"), "store").unwrap();
+        assert_eq!(video.clip_start, Some(30));
+        assert!(entry.is_none());
+
+        let (image, entry) = policy.apply("image-0", metadata("image"), "store").unwrap();
+        assert_eq!((image.source, image.embed_url, image.clip_start), (None, None, None));
+        assert_eq!(
+            entry.unwrap().fields,
+            vec![MetadataField::YoutubeSource, MetadataField::EmbedUrl, MetadataField::ClipStart]
+        );
+
+        let warn = MetadataPolicy {
+            action: ViolationAction::Warn,
+            ..MetadataPolicy::default()
+        };
+        let (audio, entry) = warn.apply("audio-0", metadata("audio"), "store").unwrap();
+        assert_eq!(audio.clip_start, Some(30));
+        assert_eq!(entry.unwrap().action, ViolationAction::Warn);
+
+        let reject = MetadataPolicy {
+            action: ViolationAction::Reject,
+            forbidden_fields: HashMap::from([("image".to_string(), vec![MetadataField::ClipStart])]),
+        };
+        let error = reject.apply("image-0", metadata("image"), "store").unwrap_err();
+        assert_eq!(error, "image metadata may not have clip_start");
+        // No rule for audio and no `*` rule
+        assert!(reject.apply("audio-0", metadata("audio"), "store").is_ok());
+    }
+
+    #[test]
+    fn test_imported_media_is_cleaned_and_audited() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path().join("media");
+        fs::create_dir_all(&media_dir).unwrap();
+        for media_type in ["image", "video"] {
+            fs::write(
+                media_dir.join(format!("{media_type}-0.json")),
+                serde_json::to_string(&metadata(media_type)).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let entries =
+            apply_to_media_dir(&MetadataPolicy::default(), &media_dir, "import").unwrap();
+        assert_eq!(entries.len(), 1);
+        let image: MediaMetadata =
+            serde_json::from_str(&fs::read_to_string(media_dir.join("image-0.json")).unwrap())
+                .unwrap();
+        assert_eq!(image.embed_url, None);
+
+        append_audit(temp_dir.path(), entries).unwrap();
+        let audit = load_audit(temp_dir.path()).unwrap();
+        assert_eq!(audit[0].media_id, "image-0");
+        assert_eq!(audit[0].origin, "import");
+    }
+}
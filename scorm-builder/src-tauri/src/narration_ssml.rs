@@ -0,0 +1,419 @@
+//! SSML for page narration, with a per-project pronunciation lexicon.
+//!
+//! Text-to-speech reads product names and acronyms the way they're spelled,
+//! so "SQL" or "kubectl" come out wrong. Authors list such terms in the
+//! project's lexicon ([`LEXICON_FILE`]) with either a spoken alias or an IPA
+//! pronunciation. `generate_narration_ssml` turns each page's narration text
+//! into a `<speak>` document for the TTS pipeline, wrapping every lexicon term
+//! in `<sub>` or `<phoneme>`. The lexicon travels with exported projects in
+//! the export manifest (see `media_names`).
+
+use crate::course_content::{ContentPage, CourseContent};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+/// Kept in each project folder next to `media/`
+pub const LEXICON_FILE: &str = "pronunciation-lexicon.json";
+
+/// Longest term or pronunciation the lexicon accepts
+const MAX_ENTRY_CHARS: usize = 200;
+
+static LEXICON_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// How one term should be spoken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LexiconEntry {
+    pub term: String,
+    /// Text spoken in place of the term, e.g. "sequel" for "SQL"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub alias: Option<String>,
+    /// IPA pronunciation, used when there is no alias
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub phoneme: Option<String>,
+    /// Only match the term with exactly this capitalisation
+    #[serde(default)]
+    #[ts(as = "Option<bool>", optional)]
+    pub case_sensitive: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl LexiconEntry {
+    fn validate(&self) -> Result<(), String> {
+        if self.term.trim().is_empty() {
+            return Err("The lexicon term is empty".to_string());
+        }
+        let pronunciation = match (&self.alias, &self.phoneme) {
+            (Some(alias), None) => alias,
+            (None, Some(phoneme)) => phoneme,
+            _ => {
+                return Err(format!(
+                    "'{}' needs an alias or a phoneme, but not both",
+                    self.term
+                ))
+            }
+        };
+        if pronunciation.trim().is_empty() {
+            return Err(format!("The pronunciation of '{}' is empty", self.term));
+        }
+        for value in [&self.term, pronunciation] {
+            if value.chars().count() > MAX_ENTRY_CHARS {
+                return Err(format!(
+                    "'{}' is longer than {MAX_ENTRY_CHARS} characters",
+                    self.term
+                ));
+            }
+            if value.chars().any(char::is_control) {
+                return Err(format!(
+                    "'{}' contains a line break or control character",
+                    self.term
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn same_term(&self, term: &str) -> bool {
+        self.term.trim().eq_ignore_ascii_case(term.trim())
+    }
+
+    /// Length in bytes of the term if it starts `text`
+    fn match_len(&self, text: &str) -> Option<usize> {
+        let term = self.term.trim();
+        let candidate = text.get(..term.len())?;
+        let matched = if self.case_sensitive {
+            candidate == term
+        } else {
+            candidate.eq_ignore_ascii_case(term)
+        };
+        matched.then_some(term.len())
+    }
+
+    fn ssml(&self, spoken: &str) -> String {
+        match (&self.alias, &self.phoneme) {
+            (Some(alias), _) => format!(
+                r#"<sub alias="{}">{}</sub>"#,
+                escape_xml(alias),
+                escape_xml(spoken)
+            ),
+            (None, Some(phoneme)) => format!(
+                r#"<phoneme alphabet="ipa" ph="{}">{}</phoneme>"#,
+                escape_xml(phoneme),
+                escape_xml(spoken)
+            ),
+            (None, None) => escape_xml(spoken),
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// `text` as SSML content, with lexicon terms matched as whole words. Longer
+/// terms win, so "SQL Server" can be spoken differently from "SQL".
+fn apply_lexicon(text: &str, lexicon: &[LexiconEntry]) -> String {
+    let mut entries: Vec<&LexiconEntry> = lexicon.iter().collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.term.trim().len()));
+
+    let mut out = String::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+    let mut previous: Option<char> = None;
+    while let Some(c) = text[pos..].chars().next() {
+        if !previous.is_some_and(is_word_char) {
+            let rest = &text[pos..];
+            let found = entries.iter().find_map(|entry| {
+                let len = entry.match_len(rest)?;
+                let ends_word = !rest[len..].chars().next().is_some_and(is_word_char);
+                ends_word.then_some((*entry, len))
+            });
+            if let Some((entry, len)) = found {
+                out.push_str(&escape_xml(&text[plain_start..pos]));
+                out.push_str(&entry.ssml(&rest[..len]));
+                previous = rest[..len].chars().last();
+                pos += len;
+                plain_start = pos;
+                continue;
+            }
+        }
+        previous = Some(c);
+        pos += c.len_utf8();
+    }
+    out.push_str(&escape_xml(&text[plain_start..]));
+    out
+}
+
+/// A `<speak>` document for `narration`, one `<p>` per blank-line separated
+/// paragraph
+pub fn narration_to_ssml(narration: &str, lexicon: &[LexiconEntry]) -> String {
+    let paragraphs: Vec<String> = narration
+        .split("\n\n")
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p>{}</p>", apply_lexicon(&paragraph, lexicon)))
+        .collect();
+    format!(
+        r#"<speak xmlns="http://www.w3.org/2001/10/synthesis" version="1.1">{}</speak>"#,
+        paragraphs.concat()
+    )
+}
+
+/// SSML of one page's narration
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct PageSsml {
+    pub page_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub title: Option<String>,
+    /// `<speak>` document with lexicon terms wrapped in `<sub>` or `<phoneme>`
+    pub ssml: String,
+}
+
+fn page_narration(page: &ContentPage) -> Option<&str> {
+    page.extra
+        .get("narration")
+        .and_then(|narration| narration.as_str())
+        .filter(|narration| !narration.trim().is_empty())
+}
+
+/// SSML for every page with narration, or only `page_id`
+pub fn course_ssml(
+    content: &CourseContent,
+    page_id: Option<&str>,
+    lexicon: &[LexiconEntry],
+) -> Vec<PageSsml> {
+    content
+        .pages()
+        .filter(|page| page_id.is_none() || page.id.as_deref() == page_id)
+        .filter_map(|page| {
+            Some(PageSsml {
+                page_id: page.id.clone()?,
+                title: page.title.clone(),
+                ssml: narration_to_ssml(page_narration(page)?, lexicon),
+            })
+        })
+        .collect()
+}
+
+/// Lexicon of the project in `project_dir`, sorted by term
+pub fn load_lexicon(project_dir: &Path) -> Result<Vec<LexiconEntry>, String> {
+    let path = project_dir.join(LEXICON_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read pronunciation lexicon: {e}"))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse pronunciation lexicon: {e}"))
+}
+
+fn save_lexicon(project_dir: &Path, lexicon: &mut Vec<LexiconEntry>) -> Result<(), String> {
+    lexicon.sort_by_key(|entry| entry.term.to_lowercase());
+    fs::create_dir_all(project_dir)
+        .map_err(|e| format!("Failed to create project directory: {e}"))?;
+    let path = project_dir.join(LEXICON_FILE);
+    let json = serde_json::to_string_pretty(lexicon)
+        .map_err(|e| format!("Failed to serialize pronunciation lexicon: {e}"))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)
+        .map_err(|e| format!("Failed to write pronunciation lexicon: {e}"))?;
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to write pronunciation lexicon: {e}"))
+}
+
+/// Add `entry` to the lexicon in `project_dir`, replacing any entry for the same term
+pub fn upsert_entry(
+    project_dir: &Path,
+    mut entry: LexiconEntry,
+) -> Result<Vec<LexiconEntry>, String> {
+    entry.validate()?;
+    entry.term = entry.term.trim().to_string();
+    entry.updated_at = Some(Utc::now());
+
+    let _guard = LEXICON_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut lexicon = load_lexicon(project_dir)?;
+    lexicon.retain(|existing| !existing.same_term(&entry.term));
+    lexicon.push(entry);
+    save_lexicon(project_dir, &mut lexicon)?;
+    Ok(lexicon)
+}
+
+/// Replace the lexicon in `project_dir` with one brought in by an import,
+/// checking each entry as `upsert_entry` would
+pub fn import_lexicon(project_dir: &Path, mut lexicon: Vec<LexiconEntry>) -> Result<(), String> {
+    for entry in &mut lexicon {
+        entry.validate()?;
+        entry.term = entry.term.trim().to_string();
+    }
+    let _guard = LEXICON_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    save_lexicon(project_dir, &mut lexicon)
+}
+
+/// Remove `term` from the lexicon in `project_dir`, returning whether it was there
+pub fn remove_entry(project_dir: &Path, term: &str) -> Result<bool, String> {
+    let _guard = LEXICON_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut lexicon = load_lexicon(project_dir)?;
+    let before = lexicon.len();
+    lexicon.retain(|existing| !existing.same_term(term));
+    if lexicon.len() == before {
+        return Ok(false);
+    }
+    save_lexicon(project_dir, &mut lexicon)?;
+    Ok(true)
+}
+
+fn project_dir(project_id: &str) -> Result<PathBuf, String> {
+    if !crate::backup_recovery::is_project_id(project_id) {
+        return Err(format!("Invalid project ID: {project_id}"));
+    }
+    Ok(crate::settings::get_projects_directory()?.join(project_id))
+}
+
+#[tauri::command]
+pub fn list_lexicon(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<Vec<LexiconEntry>, String> {
+    load_lexicon(&project_dir(&projectId)?)
+}
+
+/// Add or replace a term, returning the updated lexicon
+#[tauri::command]
+pub fn add_lexicon_entry(
+    #[allow(non_snake_case)] projectId: String,
+    entry: LexiconEntry,
+) -> Result<Vec<LexiconEntry>, String> {
+    upsert_entry(&project_dir(&projectId)?, entry)
+}
+
+#[tauri::command]
+pub fn remove_lexicon_entry(
+    #[allow(non_snake_case)] projectId: String,
+    term: String,
+) -> Result<bool, String> {
+    remove_entry(&project_dir(&projectId)?, &term)
+}
+
+/// SSML of the project's narration, for every page or only `pageId`
+#[tauri::command]
+pub fn generate_narration_ssml(
+    #[allow(non_snake_case)] projectId: String,
+    #[allow(non_snake_case)] pageId: Option<String>,
+) -> Result<Vec<PageSsml>, String> {
+    let project_path = crate::media_usage::find_project_file(&projectId)
+        .ok_or_else(|| format!("Project not found: {projectId}"))?;
+    let project = crate::project_storage::load_project_file(&project_path)?;
+    let lexicon = load_lexicon(&project_dir(&project.project.id)?)?;
+    let Some(course_value) = &project.course_content else {
+        return Ok(Vec::new());
+    };
+    let content = CourseContent::from_value(course_value)?;
+    let pages = course_ssml(&content, pageId.as_deref(), &lexicon);
+    if let Some(page_id) = &pageId {
+        if pages.is_empty() && !content.pages().any(|page| page.id.as_ref() == Some(page_id)) {
+            return Err(format!("Page not found: {page_id}"));
+        }
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn entry(term: &str, alias: Option<&str>, phoneme: Option<&str>) -> LexiconEntry {
+        LexiconEntry {
+            term: term.to_string(),
+            alias: alias.map(str::to_string),
+            phoneme: phoneme.map(str::to_string),
+            case_sensitive: false,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_lexicon_terms_are_wrapped_as_whole_words() {
+        let lexicon = vec![
+            entry("SQL", Some("sequel"), None),
+            entry("SQL Server", Some("sequel server"), None),
+            entry("kubectl", None, Some("ˈkuːb kənˌtroʊl")),
+        ];
+
+        let ssml = narration_to_ssml(
+            "Run kubectl, then open SQL Server.\n\nMySQL & sql <differ>.",
+            &lexicon,
+        );
+
+        assert_eq!(
+            ssml,
+            concat!(
+                r#"<speak xmlns="http://www.w3.org/2001/10/synthesis" version="1.1">"#,
+                r#"<p>Run <phoneme alphabet="ipa" ph="ˈkuːb kənˌtroʊl">kubectl</phoneme>, "#,
+                r#"then open <sub alias="sequel server">SQL Server</sub>.</p>"#,
+                r#"<p>MySQL &amp; <sub alias="sequel">sql</sub> &lt;differ&gt;.</p>"#,
+                "</speak>"
+            )
+        );
+    }
+
+    #[test]
+    fn test_lexicon_entries_are_validated_and_replaced_by_term() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        assert!(upsert_entry(dir, entry(" ", Some("x"), None)).is_err());
+        assert!(upsert_entry(dir, entry("SQL", None, None)).is_err());
+        assert!(upsert_entry(dir, entry("SQL", Some("a"), Some("b"))).is_err());
+
+        upsert_entry(dir, entry("SQL", Some("S Q L"), None)).unwrap();
+        upsert_entry(dir, entry("API", Some("A P I"), None)).unwrap();
+        let lexicon = upsert_entry(dir, entry("sql ", Some("sequel"), None)).unwrap();
+
+        assert_eq!(load_lexicon(dir).unwrap(), lexicon);
+        let terms: Vec<_> = lexicon.iter().map(|e| (e.term.as_str(), e.alias.as_deref())).collect();
+        assert_eq!(terms, vec![("API", Some("A P I")), ("sql", Some("sequel"))]);
+
+        assert!(remove_entry(dir, "API").unwrap());
+        assert!(!remove_entry(dir, "API").unwrap());
+        assert_eq!(load_lexicon(dir).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_pages_without_narration_are_skipped() {
+        let content = CourseContent::from_value(&json!({
+            "welcomePage": { "id": "welcome", "title": "Welcome", "narration": "Hi" },
+            "topics": [
+                { "id": "topic-1", "title": "One", "narration": "  " },
+                { "id": "topic-2", "title": "Two", "narration": "Two" }
+            ]
+        }))
+        .unwrap();
+
+        let all = course_ssml(&content, None, &[]);
+        let ids: Vec<_> = all.iter().map(|page| page.page_id.as_str()).collect();
+        assert_eq!(ids, vec!["welcome", "topic-2"]);
+        assert_eq!(course_ssml(&content, Some("topic-2"), &[]), all[1..].to_vec());
+    }
+}
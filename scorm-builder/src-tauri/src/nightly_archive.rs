@@ -0,0 +1,417 @@
+//! Rotating nightly archive of the whole projects directory.
+//!
+//! Per-project backups (`create_backup`) guard against a bad save; this guards
+//! against losing the directory itself. When the schedule in the app settings
+//! is enabled, a background thread zips the projects directory once a night
+//! into the destination folder and deletes all but the newest `keep` archives.
+//! The outcome of the last run is kept next to the settings and reported by
+//! `get_backup_status`.
+
+use crate::app_services::AppServices;
+use crate::disk_space::{ensure_space, path_size};
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// How often the scheduler thread checks whether tonight's archive is due
+pub const CHECK_INTERVAL_SECS: u64 = 15 * 60;
+
+const ARCHIVE_PREFIX: &str = "projects-";
+const ARCHIVE_EXTENSION: &str = ".zip";
+const STATUS_FILE: &str = "archive-status.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveSchedule {
+    pub enabled: bool,
+    /// Folder the archives are written to; nothing is archived while unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// Local hour (0-23) from which the night's archive is made
+    pub hour: u32,
+    /// Archives kept in the destination; older ones are deleted after each run
+    pub keep: usize,
+}
+
+impl Default for ArchiveSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            destination: None,
+            hour: 2,
+            keep: 7,
+        }
+    }
+}
+
+fn ran_today(last_run: Option<DateTime<Local>>, now: DateTime<Local>) -> bool {
+    last_run.is_some_and(|last| last.date_naive() >= now.date_naive())
+}
+
+impl ArchiveSchedule {
+    /// Whether a run is due at `now`, given the last one: once per local day,
+    /// at or after `hour`
+    pub fn is_due(&self, last_run: Option<DateTime<Local>>, now: DateTime<Local>) -> bool {
+        self.enabled
+            && self.destination.is_some()
+            && now.time() >= self.start_time()
+            && !ran_today(last_run, now)
+    }
+
+    /// When the scheduler will next make an archive
+    pub fn next_run(
+        &self,
+        last_run: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        if !self.enabled || self.destination.is_none() {
+            return None;
+        }
+        if self.is_due(last_run, now) {
+            return Some(now);
+        }
+        let day = if ran_today(last_run, now) || now.time() >= self.start_time() {
+            now.date_naive() + Duration::days(1)
+        } else {
+            now.date_naive()
+        };
+        Local
+            .from_local_datetime(&day.and_time(self.start_time()))
+            .earliest()
+    }
+
+    fn start_time(&self) -> NaiveTime {
+        NaiveTime::from_hms_opt(self.hour.min(23), 0, 0).unwrap_or_default()
+    }
+}
+
+/// Outcome of the last scheduled run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArchiveRunStatus {
+    pub last_run: Option<DateTime<Local>>,
+    pub last_archive: Option<String>,
+    pub last_size: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupStatus {
+    pub schedule: ArchiveSchedule,
+    #[serde(flatten)]
+    pub last: ArchiveRunStatus,
+    pub next_run: Option<DateTime<Local>>,
+    /// Archives currently kept in the destination, newest first
+    pub archives: Vec<String>,
+}
+
+fn add_directory(
+    zip: &mut ZipWriter<File>,
+    root: &Path,
+    dir: &Path,
+    skip: &Path,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {e}", dir.display()))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read directory entry: {e}"))?
+            .path();
+        if path == skip {
+            continue;
+        }
+        let name = path
+            .strip_prefix(root)
+            .map_err(|e| format!("Failed to name {} in archive: {e}", path.display()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        if path.is_dir() {
+            zip.add_directory(format!("{name}/"), FileOptions::default())
+                .map_err(|e| format!("Failed to add {name} to archive: {e}"))?;
+            add_directory(zip, root, &path, skip)?;
+        } else {
+            let mut file =
+                File::open(&path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            let options = FileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .large_file(size >= u32::MAX as u64);
+            zip.start_file(&name, options)
+                .map_err(|e| format!("Failed to add {name} to archive: {e}"))?;
+            io::copy(&mut file, zip).map_err(|e| format!("Failed to archive {name}: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Zip `projects_dir` into `destination`, named after `now`. The archive is
+/// written under a temporary name first so a failed run never leaves a
+/// truncated archive behind that retention would count.
+pub fn write_archive(
+    projects_dir: &Path,
+    destination: &Path,
+    now: DateTime<Local>,
+) -> Result<PathBuf, String> {
+    fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create archive destination: {e}"))?;
+    // Media barely compresses, so the archive needs about the size of the projects
+    // directory, less earlier archives when they are kept inside it
+    let mut needed = path_size(projects_dir);
+    if destination.starts_with(projects_dir) {
+        needed = needed.saturating_sub(path_size(destination));
+    }
+    ensure_space(destination, needed)?;
+    let name = format!(
+        "{ARCHIVE_PREFIX}{}{ARCHIVE_EXTENSION}",
+        now.format("%Y%m%d-%H%M%S")
+    );
+    let archive_path = destination.join(&name);
+    let partial_path = destination.join(format!("{name}.partial"));
+
+    let result = (|| {
+        let file =
+            File::create(&partial_path).map_err(|e| format!("Failed to create archive: {e}"))?;
+        let mut zip = ZipWriter::new(file);
+        // The destination may live inside the projects directory
+        let skip = destination
+            .canonicalize()
+            .unwrap_or_else(|_| destination.to_path_buf());
+        let root = projects_dir
+            .canonicalize()
+            .map_err(|e| format!("Failed to read projects directory: {e}"))?;
+        add_directory(&mut zip, &root, &root, &skip)?;
+        zip.finish()
+            .map_err(|e| format!("Failed to finish archive: {e}"))?;
+        fs::rename(&partial_path, &archive_path)
+            .map_err(|e| format!("Failed to finish archive: {e}"))
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&partial_path);
+    }
+    result.map(|_| archive_path)
+}
+
+/// Archives in `destination`, newest first
+pub fn list_archives(destination: &Path) -> Vec<PathBuf> {
+    let mut archives: Vec<PathBuf> = fs::read_dir(destination)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                        n.starts_with(ARCHIVE_PREFIX) && n.ends_with(ARCHIVE_EXTENSION)
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Names embed the timestamp, so they sort chronologically
+    archives.sort();
+    archives.reverse();
+    archives
+}
+
+/// Delete all but the newest `keep` archives in `destination`
+pub fn prune_archives(destination: &Path, keep: usize) -> Result<(), String> {
+    for old in list_archives(destination).into_iter().skip(keep.max(1)) {
+        fs::remove_file(&old)
+            .map_err(|e| format!("Failed to delete old archive {}: {e}", old.display()))?;
+    }
+    Ok(())
+}
+
+/// Make an archive for `schedule` now and apply its retention
+pub fn run_archive(
+    schedule: &ArchiveSchedule,
+    projects_dir: &Path,
+    now: DateTime<Local>,
+) -> Result<ArchiveRunStatus, String> {
+    let destination = schedule
+        .destination
+        .as_deref()
+        .map(Path::new)
+        .ok_or_else(|| "No archive destination configured".to_string())?;
+    let archive = write_archive(projects_dir, destination, now)?;
+    prune_archives(destination, schedule.keep)?;
+    Ok(ArchiveRunStatus {
+        last_run: Some(now),
+        last_size: fs::metadata(&archive).map(|m| m.len()).ok(),
+        last_archive: Some(archive.to_string_lossy().to_string()),
+        last_error: None,
+    })
+}
+
+fn status_path() -> Result<PathBuf, String> {
+    Ok(crate::settings::get_app_config_dir()?.join(STATUS_FILE))
+}
+
+fn load_status() -> ArchiveRunStatus {
+    status_path()
+        .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        .unwrap_or_default()
+}
+
+fn save_status(status: &ArchiveRunStatus) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(status)
+        .map_err(|e| format!("Failed to serialize archive status: {e}"))?;
+    fs::write(status_path()?, json).map_err(|e| format!("Failed to write archive status: {e}"))
+}
+
+fn current_schedule() -> ArchiveSchedule {
+    crate::settings::load_settings()
+        .ok()
+        .and_then(|settings| settings.archive_schedule)
+        .unwrap_or_default()
+}
+
+/// Called periodically by the scheduler thread; archives when a run is due
+pub fn run_if_due(now: DateTime<Local>) {
+    let schedule = current_schedule();
+    let status = load_status();
+    if !schedule.is_due(status.last_run, now) {
+        return;
+    }
+    let result = crate::settings::get_projects_directory()
+        .and_then(|projects_dir| run_archive(&schedule, &projects_dir, now));
+    // A failed run keeps the last successful one, so the next check retries
+    let status = match result {
+        Ok(status) => {
+            if let Some(archive) = &status.last_archive {
+                crate::commands_secure::log_to_frontend(
+                    "INFO",
+                    &format!("Nightly archive written to {archive}"),
+                );
+            }
+            status
+        }
+        Err(error) => {
+            crate::commands_secure::log_to_frontend(
+                "ERROR",
+                &format!("Nightly archive failed: {error}"),
+            );
+            ArchiveRunStatus {
+                last_error: Some(error),
+                ..status
+            }
+        }
+    };
+    if let Err(error) = save_status(&status) {
+        crate::commands_secure::log_to_frontend("ERROR", &error);
+    }
+}
+
+#[tauri::command]
+pub fn get_backup_status(services: State<'_, AppServices>) -> Result<BackupStatus, String> {
+    let schedule = current_schedule();
+    let last = load_status();
+    let archives = schedule
+        .destination
+        .as_deref()
+        .map(|destination| {
+            list_archives(Path::new(destination))
+                .into_iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(BackupStatus {
+        next_run: schedule.next_run(last.last_run, services.now().with_timezone(&Local)),
+        schedule,
+        last,
+        archives,
+    })
+}
+
+#[tauri::command]
+pub fn set_archive_schedule(schedule: ArchiveSchedule) -> Result<(), String> {
+    if schedule.hour > 23 {
+        return Err(format!("Invalid archive hour: {}", schedule.hour));
+    }
+    let mut settings = crate::settings::load_settings()?;
+    settings.archive_schedule = Some(schedule);
+    crate::settings::save_settings(&settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 6, day, hour, 30, 0).unwrap()
+    }
+
+    fn schedule(destination: &Path, keep: usize) -> ArchiveSchedule {
+        ArchiveSchedule {
+            enabled: true,
+            destination: Some(destination.to_string_lossy().to_string()),
+            hour: 2,
+            keep,
+        }
+    }
+
+    #[test]
+    fn test_runs_once_a_night_after_the_scheduled_hour() {
+        let schedule = schedule(Path::new("/backups"), 7);
+
+        assert!(!schedule.is_due(None, at(10, 1)));
+        assert!(schedule.is_due(None, at(10, 2)));
+        assert!(!schedule.is_due(Some(at(10, 2)), at(10, 23)));
+        assert!(schedule.is_due(Some(at(10, 2)), at(11, 3)));
+        assert_eq!(
+            schedule
+                .next_run(Some(at(10, 2)), at(10, 23))
+                .map(|next| next.date_naive()),
+            Some(at(11, 0).date_naive())
+        );
+
+        let disabled = ArchiveSchedule {
+            enabled: false,
+            ..schedule
+        };
+        assert!(!disabled.is_due(None, at(10, 3)));
+        assert_eq!(disabled.next_run(None, at(10, 3)), None);
+    }
+
+    #[test]
+    fn test_archive_contains_projects_and_keeps_newest() {
+        let projects = TempDir::new().unwrap();
+        fs::write(projects.path().join("Course_1.scormproj"), b"{}").unwrap();
+        fs::create_dir_all(projects.path().join("1/media")).unwrap();
+        fs::write(projects.path().join("1/media/image-0.bin"), b"png").unwrap();
+        // Destination inside the projects directory is left out of the archive
+        let destination = projects.path().join("archives");
+        let schedule = schedule(&destination, 2);
+
+        for day in 10..13 {
+            let status = run_archive(&schedule, projects.path(), at(day, 2)).unwrap();
+            assert!(status.last_error.is_none(), "{:?}", status.last_error);
+        }
+
+        let archives = list_archives(&destination);
+        assert_eq!(archives.len(), 2);
+        assert!(archives[0].ends_with("projects-20240612-023000.zip"));
+        assert!(archives[1].ends_with("projects-20240611-023000.zip"));
+
+        let zip = zip::ZipArchive::new(File::open(&archives[0]).unwrap()).unwrap();
+        let mut names: Vec<String> = zip.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "1/",
+                "1/media/",
+                "1/media/image-0.bin",
+                "Course_1.scormproj"
+            ]
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! PNG thumbnails of course pages for the outline.
+//!
+//! A page is rendered to the same standalone HTML as the editor preview (see
+//! `scorm::page_preview`), with media pointing at the project's stored files,
+//! and screenshotted by a headless Chromium-family browser found on the
+//! machine; Tauri's webviews can't render offscreen or capture themselves.
+//! The browser is looked up once per run, and a render that hangs is stopped
+//! after [`SCREENSHOT_TIMEOUT`]. Thumbnails are cached in the project's [`THUMBNAIL_DIR`] under
+//! the hash of the page HTML and the media folder, so an unchanged page is
+//! never rendered twice and an edited one is. Like media, they are encrypted
+//! in encrypted projects.
+
+use crate::content_hash::{canonical_json, hash_hex};
+use crate::project_encryption::{read_media_file, write_media_file};
+use base64::{engine::general_purpose, Engine as _};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Kept in each project folder next to `media/`
+pub const THUMBNAIL_DIR: &str = "thumbnails";
+
+/// Size pages are laid out at before scaling down
+const VIEWPORT: (u32, u32) = (1280, 800);
+
+/// Thumbnails are a quarter of the viewport: 320x200
+const THUMBNAIL_SCALE: f64 = 0.25;
+
+/// Time the browser lets the page load images and lay out, in milliseconds
+const RENDER_BUDGET_MS: u32 = 3000;
+
+/// Longest a screenshot may take before the browser is stopped
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(30);
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Browser executables looked up on PATH, in order of preference
+const BROWSER_NAMES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "microsoft-edge",
+    "msedge",
+    "chrome",
+];
+
+/// Install locations of browsers that usually aren't on PATH
+const BROWSER_PATHS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+    r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+];
+
+/// `name` in one of the PATH directories, with `.exe` on Windows
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{name}{}", std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// The browser to render with. Browsers are never run to find one: on
+/// Windows `chrome.exe --version` opens a window instead of answering.
+static BROWSER: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    let names = BROWSER_NAMES.iter().copied().filter_map(find_on_path);
+    let paths = BROWSER_PATHS.iter().map(PathBuf::from).filter(|path| path.is_file());
+    names.chain(paths).next()
+});
+
+/// Screenshots an HTML file. `HeadlessBrowser` is the real implementation;
+/// tests substitute a fake.
+pub trait PageRenderer {
+    fn screenshot(&self, html_path: &Path) -> Result<Vec<u8>, String>;
+}
+
+/// Runs Chrome, Chromium or Edge in headless mode
+pub struct HeadlessBrowser {
+    binary: PathBuf,
+}
+
+impl HeadlessBrowser {
+    /// The first browser installed, or `None` if there is none
+    pub fn find() -> Option<Self> {
+        BROWSER.clone().map(|binary| Self { binary })
+    }
+}
+
+impl PageRenderer for HeadlessBrowser {
+    fn screenshot(&self, html_path: &Path) -> Result<Vec<u8>, String> {
+        let dir = tempfile::tempdir()
+            .map_err(|e| format!("Failed to create screenshot directory: {e}"))?;
+        let output_path = dir.path().join("page.png");
+        let log_path = dir.path().join("browser.log");
+        let log = fs::File::create(&log_path)
+            .map_err(|e| format!("Failed to create browser log: {e}"))?;
+        let page_url = url::Url::from_file_path(html_path)
+            .map_err(|_| format!("Can't open {} in a browser", html_path.display()))?;
+
+        let mut child = Command::new(&self.binary)
+            .arg("--headless=new")
+            .arg("--disable-gpu")
+            .arg("--hide-scrollbars")
+            .arg("--no-first-run")
+            .arg("--mute-audio")
+            .arg("--allow-file-access-from-files")
+            .arg(format!("--user-data-dir={}", dir.path().join("profile").display()))
+            .arg(format!("--window-size={},{}", VIEWPORT.0, VIEWPORT.1))
+            .arg(format!("--force-device-scale-factor={THUMBNAIL_SCALE}"))
+            .arg(format!("--virtual-time-budget={RENDER_BUDGET_MS}"))
+            .arg(format!("--screenshot={}", output_path.display()))
+            .arg(page_url.as_str())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            // A file rather than a pipe, which a chatty browser could fill and block on
+            .stderr(log)
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {e}", self.binary.display()))?;
+
+        let deadline = Instant::now() + SCREENSHOT_TIMEOUT;
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(100))
+                }
+                Ok(None) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(format!(
+                        "The browser took more than {} seconds to render the page",
+                        SCREENSHOT_TIMEOUT.as_secs()
+                    ));
+                }
+                Err(e) => return Err(format!("Failed to wait for {}: {e}", self.binary.display())),
+            }
+        }
+
+        let png = fs::read(&output_path).map_err(|_| {
+            format!(
+                "The browser didn't produce a screenshot: {}",
+                fs::read_to_string(&log_path).unwrap_or_default().trim()
+            )
+        })?;
+        if !png.starts_with(PNG_SIGNATURE) {
+            return Err("The browser's screenshot isn't a PNG".to_string());
+        }
+        Ok(png)
+    }
+}
+
+/// A page thumbnail ready to put in an `<img>`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageThumbnail {
+    pub page_id: String,
+    /// Hash of the page HTML and media the thumbnail was rendered from
+    pub content_hash: String,
+    /// Whether the thumbnail came from the cache rather than a new render
+    pub cached: bool,
+    /// `data:image/png;base64,...`
+    pub data_url: String,
+}
+
+fn thumbnail_name(page_id: &str, content_hash: &str) -> String {
+    format!("{page_id}.{content_hash}.png")
+}
+
+/// Page IDs become file names, so only plain IDs are accepted
+fn check_page_id(page_id: &str) -> Result<(), String> {
+    let plain = !page_id.is_empty()
+        && page_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if plain {
+        Ok(())
+    } else {
+        Err(format!("Invalid page ID: {page_id}"))
+    }
+}
+
+/// Remove thumbnails of `page_id` other than `keep`
+fn prune_thumbnails(cache_dir: &Path, page_id: &str, keep: &str) {
+    let prefix = format!("{page_id}.");
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with(&prefix) && name.ends_with(".png") && name != keep {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Thumbnail of `page_id` from `cache_dir` if one was rendered from this
+/// `preview_html` and `media_manifest`, otherwise rendered and cached
+pub fn page_thumbnail(
+    cache_dir: &Path,
+    page_id: &str,
+    preview_html: &str,
+    media_manifest: &[Value],
+    renderer: &dyn PageRenderer,
+) -> Result<PageThumbnail, String> {
+    check_page_id(page_id)?;
+    let content_hash = hash_hex(&format!(
+        "{preview_html}\n{}",
+        canonical_json(&Value::from(media_manifest.to_vec()))
+    ));
+    let name = thumbnail_name(page_id, &content_hash);
+    let path = cache_dir.join(&name);
+
+    let (png, cached) = match read_media_file(&path) {
+        Ok(png) => (png, true),
+        Err(_) => {
+            let render_dir = tempfile::tempdir()
+                .map_err(|e| format!("Failed to create render directory: {e}"))?;
+            let html_path = render_dir.path().join("page.html");
+            fs::write(&html_path, preview_html)
+                .map_err(|e| format!("Failed to write page HTML: {e}"))?;
+            let png = renderer.screenshot(&html_path)?;
+
+            fs::create_dir_all(cache_dir)
+                .map_err(|e| format!("Failed to create thumbnail directory: {e}"))?;
+            let tmp_path = path.with_extension("png.tmp");
+            write_media_file(&tmp_path, &png)
+                .map_err(|e| format!("Failed to write thumbnail: {e}"))?;
+            fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write thumbnail: {e}"))?;
+            prune_thumbnails(cache_dir, page_id, &name);
+            (png, false)
+        }
+    };
+
+    Ok(PageThumbnail {
+        page_id: page_id.to_string(),
+        content_hash,
+        cached,
+        data_url: format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(png)),
+    })
+}
+
+/// Render one page of the course to a PNG thumbnail, reusing the cached one
+/// while the page and the project's media are unchanged
+#[tauri::command]
+pub async fn render_page_thumbnail(
+    course_data: Value,
+    project_id: String,
+    page_id: String,
+    extension_map: Option<HashMap<String, String>>,
+) -> Result<PageThumbnail, String> {
+    // The ID becomes part of the cache path
+    if !crate::backup_recovery::is_project_id(&project_id) {
+        return Err(format!("Invalid project ID: {project_id}"));
+    }
+    let projects_dir = crate::project_storage::get_projects_directory()?;
+    let media_dir = crate::media_storage::get_media_directory(&project_id)?;
+    let media_base_url = url::Url::from_directory_path(&media_dir)
+        .map_err(|_| format!("Can't link to media in {}", media_dir.display()))?;
+
+    let preview_html = crate::commands::render_page_preview(
+        course_data,
+        project_id.clone(),
+        page_id.clone(),
+        extension_map,
+        Some(media_base_url.to_string()),
+    )
+    .await?;
+
+    tokio::task::spawn_blocking(move || {
+        let media_manifest = crate::content_hash::media_manifest(&projects_dir, &project_id);
+        let cache_dir = projects_dir.join(&project_id).join(THUMBNAIL_DIR);
+        let renderer = HeadlessBrowser::find().ok_or(
+            "Page thumbnails need Chrome, Chromium or Edge installed, and none was found",
+        )?;
+        page_thumbnail(&cache_dir, &page_id, &preview_html, &media_manifest, &renderer)
+    })
+    .await
+    .map_err(|e| format!("Thumbnail task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+
+    /// Counts renders and returns a PNG signature followed by the page's HTML
+    #[derive(Default)]
+    struct FakeRenderer {
+        renders: Cell<usize>,
+    }
+
+    impl PageRenderer for FakeRenderer {
+        fn screenshot(&self, html_path: &Path) -> Result<Vec<u8>, String> {
+            self.renders.set(self.renders.get() + 1);
+            let mut png = PNG_SIGNATURE.to_vec();
+            png.extend(fs::read(html_path).unwrap());
+            Ok(png)
+        }
+    }
+
+    #[test]
+    fn test_thumbnails_are_cached_until_the_page_or_media_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join(THUMBNAIL_DIR);
+        let renderer = FakeRenderer::default();
+        let media = vec![json!({ "name": "image-1.bin", "size": 10 })];
+
+        let first = page_thumbnail(&cache_dir, "topic-1", "<p>A</p>", &media, &renderer).unwrap();
+        let again = page_thumbnail(&cache_dir, "topic-1", "<p>A</p>", &media, &renderer).unwrap();
+        assert!(!first.cached);
+        assert!(again.cached);
+        assert_eq!(again.data_url, first.data_url);
+        assert!(first.data_url.starts_with("data:image/png;base64,"));
+        assert_eq!(renderer.renders.get(), 1);
+
+        let edited = page_thumbnail(&cache_dir, "topic-1", "<p>B</p>", &media, &renderer).unwrap();
+        let new_media = page_thumbnail(&cache_dir, "topic-1", "<p>B</p>", &[], &renderer).unwrap();
+        assert!(!edited.cached);
+        assert_ne!(new_media.content_hash, edited.content_hash);
+        assert_eq!(renderer.renders.get(), 3);
+
+        // Only the latest thumbnail of a page is kept
+        let files: Vec<_> = fs::read_dir(&cache_dir).unwrap().flatten().collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            files[0].file_name().to_string_lossy(),
+            thumbnail_name("topic-1", &new_media.content_hash)
+        );
+
+        assert!(page_thumbnail(&cache_dir, "../topic-1", "<p>A</p>", &media, &renderer).is_err());
+    }
+}
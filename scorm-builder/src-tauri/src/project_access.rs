@@ -0,0 +1,214 @@
+//! Read-only project tracking. Projects are checked for write access when
+//! they are opened; ones that can't be written (or were opened with
+//! `open_project_readonly`) are remembered here so every mutating command
+//! fails up front instead of partway through a write.
+
+use crate::instance_lock;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// How this instance has a project open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectAccess {
+    /// Open for editing, with this instance holding the project lock
+    Writable,
+    /// The storage doesn't allow writes
+    NotWritable,
+    /// Opened with `open_project_readonly`, e.g. because another instance has it
+    ReadOnlyMode,
+}
+
+static OPEN_PROJECTS: Lazy<Mutex<HashMap<PathBuf, ProjectAccess>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn open_projects() -> std::sync::MutexGuard<'static, HashMap<PathBuf, ProjectAccess>> {
+    OPEN_PROJECTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Same file, however the path was spelled
+fn registry_key(project_path: &Path) -> PathBuf {
+    project_path
+        .canonicalize()
+        .unwrap_or_else(|_| project_path.to_path_buf())
+}
+
+/// Whether the project file and its folder can be written. The folder is
+/// probed with a real file because network shares often report permissions
+/// they don't enforce, and saves go through a temp file next to the project.
+pub fn is_writable(project_path: &Path) -> bool {
+    if fs::metadata(project_path).is_ok_and(|meta| meta.permissions().readonly()) {
+        return false;
+    }
+    let Some(dir) = project_path.parent() else {
+        return false;
+    };
+    let file_name = project_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let probe = dir.join(format!(".{file_name}.write-check"));
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        // A probe left behind by a crash still proves the folder was writable
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => fs::remove_file(&probe).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// How the project at `project_path` is open, if it has been opened
+pub fn access(project_path: &Path) -> Option<ProjectAccess> {
+    let projects = open_projects();
+    if projects.is_empty() {
+        return None;
+    }
+    projects.get(&registry_key(project_path)).copied()
+}
+
+pub fn set_access(project_path: &Path, access: ProjectAccess) {
+    open_projects().insert(registry_key(project_path), access);
+}
+
+/// Forget the project, e.g. when it is closed
+pub fn forget(project_path: &Path) {
+    open_projects().remove(&registry_key(project_path));
+}
+
+pub fn is_read_only(project_path: &Path) -> bool {
+    matches!(
+        access(project_path),
+        Some(ProjectAccess::NotWritable | ProjectAccess::ReadOnlyMode)
+    )
+}
+
+/// Open a project for editing in this instance: check write access the first
+/// time and take the project lock. Returns whether changes can be saved.
+/// Fails when another instance has the project open.
+pub fn open_for_editing(project_path: &Path, now: DateTime<Utc>) -> Result<bool, String> {
+    match access(project_path) {
+        Some(ProjectAccess::Writable) => {
+            if instance_lock::ensure_still_held(project_path).is_ok() {
+                return Ok(true);
+            }
+            // Another instance took the project over; it stays viewable here
+            set_access(project_path, ProjectAccess::ReadOnlyMode);
+            Ok(false)
+        }
+        Some(_) => Ok(false),
+        None => {
+            if !is_writable(project_path) {
+                set_access(project_path, ProjectAccess::NotWritable);
+                return Ok(false);
+            }
+            instance_lock::acquire(project_path, now)?;
+            set_access(project_path, ProjectAccess::Writable);
+            Ok(true)
+        }
+    }
+}
+
+/// Close the project in this instance, releasing its lock
+pub fn close(project_path: &Path) {
+    instance_lock::release(project_path);
+    forget(project_path);
+}
+
+/// Error out if the project at `project_path` is open read-only
+pub fn ensure_writable(project_path: &Path) -> Result<(), String> {
+    if is_read_only(project_path) {
+        let name = project_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| project_path.display().to_string());
+        return Err(format!(
+            "Project is open read-only and cannot be changed: {name}. \
+             Reopen it from a writable location to edit it."
+        ));
+    }
+    instance_lock::ensure_still_held(project_path)
+}
+
+/// `ensure_writable` for commands that take a project ID or path
+pub fn ensure_project_writable(project_id_or_path: &str) -> Result<(), String> {
+    // Skip the project lookup in the common case of nothing being read-only
+    let any_read_only = open_projects()
+        .values()
+        .any(|access| *access != ProjectAccess::Writable);
+    if !any_read_only {
+        return Ok(());
+    }
+    match crate::media_usage::find_project_file(project_id_or_path) {
+        Some(project_path) => ensure_writable(&project_path),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_writable_folder_and_read_only_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("Safety_1234567890.scormproj");
+        fs::write(&project, "{}").unwrap();
+        assert!(is_writable(&project));
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+        let mut permissions = fs::metadata(&project).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&project, permissions).unwrap();
+        assert!(!is_writable(&project));
+    }
+
+    #[test]
+    fn test_read_only_projects_reject_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("Safety_42.scormproj");
+        fs::write(&project, "{}").unwrap();
+
+        assert!(ensure_writable(&project).is_ok());
+        set_access(&project, ProjectAccess::ReadOnlyMode);
+        let error = ensure_writable(&project).unwrap_err();
+        assert!(error.contains("read-only"));
+        assert!(error.contains("Safety_42.scormproj"));
+
+        // The same file through a different spelling of its path
+        let dotted = temp_dir.path().join(".").join("Safety_42.scormproj");
+        assert!(ensure_writable(&dotted).is_err());
+
+        set_access(&project, ProjectAccess::Writable);
+        assert!(ensure_writable(&project).is_ok());
+        forget(&project);
+    }
+
+    #[test]
+    fn test_open_for_editing_takes_the_project_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("Safety_7.scormproj");
+        fs::write(&project, "{}").unwrap();
+
+        assert_eq!(open_for_editing(&project, Utc::now()), Ok(true));
+        assert!(instance_lock::lock_path(&project).exists());
+        assert_eq!(access(&project), Some(ProjectAccess::Writable));
+        // Opening again in this instance is a no-op
+        assert_eq!(open_for_editing(&project, Utc::now()), Ok(true));
+
+        close(&project);
+        assert_eq!(access(&project), None);
+        assert!(!instance_lock::lock_path(&project).exists());
+    }
+}
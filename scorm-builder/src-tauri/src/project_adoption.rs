@@ -0,0 +1,451 @@
+//! Bringing loose project files into the workspace.
+//!
+//! Projects copied between machines by hand arrive as a `.scormproj` file and
+//! a media folder that may no longer match the file's name, its project ID or
+//! the workspace's `Name_{id}.scormproj` + `{id}/media/` layout, and their
+//! saved paths still point at the old machine. Adoption validates the file,
+//! keeps its ID unless another project has it, copies its project folder to
+//! `{projects}/{id}/`, rewrites references to the old ID and workspace, and
+//! gives it an entry in the projects index. The source files are left alone,
+//! except that a loose file already in the projects directory is replaced by
+//! the adopted one.
+
+use crate::app_services::AppServices;
+use crate::backup_recovery::{is_project_id, split_project_file_name};
+use crate::course_content::validate_project_file;
+use crate::project_index::{load_project_index, update_project_tags, ProjectTags};
+use crate::project_storage::{load_project_file, save_project_file, ProjectFile};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Folders of a project's directory that are rebuilt rather than copied
+const REBUILT_FOLDERS: [&str; 2] = ["pages", "thumbnails"];
+
+/// A project brought into the workspace
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptedProject {
+    pub source_path: String,
+    pub project_path: String,
+    pub project_id: String,
+    /// ID the file had; differs from `project_id` when another project had it
+    pub previous_id: String,
+    /// Media files copied into `{id}/media/`
+    pub media_files: usize,
+    /// Saved paths and URLs pointed at the new ID and workspace
+    pub rewritten_references: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptionFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptionReport {
+    pub adopted: Vec<AdoptedProject>,
+    /// Project files already laid out the way the workspace expects
+    pub skipped: Vec<String>,
+    pub failed: Vec<AdoptionFailure>,
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Project folder of the file at `source`: `{id}/` next to it named after
+/// the saved ID or the ID in its file name, or a folder named like the file
+fn find_project_folder(source: &Path, project_id: &str) -> Option<PathBuf> {
+    let dir = source.parent()?;
+    let (_, file_id) = split_project_file_name(source);
+    let stem = source.file_stem()?.to_string_lossy().into_owned();
+    [Some(project_id.to_string()), file_id, Some(stem)]
+        .into_iter()
+        .flatten()
+        .map(|name| dir.join(name))
+        .find(|folder| folder.join("media").is_dir())
+}
+
+/// Whether a project other than the one at `source` uses `project_id`
+fn id_taken(projects_dir: &Path, project_id: &str, source: &Path, source_folder: Option<&Path>) -> bool {
+    let folder = projects_dir.join(project_id);
+    if folder.exists() && !source_folder.is_some_and(|source_folder| same_path(source_folder, &folder)) {
+        return true;
+    }
+    fs::read_dir(projects_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("scormproj"))
+        .any(|path| {
+            split_project_file_name(&path).1.as_deref() == Some(project_id) && !same_path(&path, source)
+        })
+}
+
+/// Copy `from` into `to`, leaving out rebuilt folders, returning how many
+/// files of `media/` were copied
+fn copy_project_folder(from: &Path, to: &Path) -> Result<usize, String> {
+    let mut media_files = 0;
+    let mut stack = vec![(from.to_path_buf(), to.to_path_buf())];
+    while let Some((from_dir, to_dir)) = stack.pop() {
+        fs::create_dir_all(&to_dir)
+            .map_err(|e| format!("Failed to create {}: {e}", to_dir.display()))?;
+        let entries = fs::read_dir(&from_dir)
+            .map_err(|e| format!("Failed to read {}: {e}", from_dir.display()))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let path = entry.path();
+            if path.is_dir() {
+                let top_level = from_dir == from;
+                if !(top_level && REBUILT_FOLDERS.iter().any(|folder| name == *folder)) {
+                    stack.push((path, to_dir.join(&name)));
+                }
+                continue;
+            }
+            fs::copy(&path, to_dir.join(&name))
+                .map_err(|e| format!("Failed to copy {}: {e}", path.display()))?;
+            if from_dir == from.join("media") {
+                media_files += 1;
+            }
+        }
+    }
+    Ok(media_files)
+}
+
+/// Replace every `(from, to)` in the strings of `value`, returning how many
+/// strings changed
+fn rewrite_strings(value: &mut Value, replacements: &[(String, String)]) -> usize {
+    match value {
+        Value::String(text) => {
+            let rewritten = replacements
+                .iter()
+                .fold(text.clone(), |text, (from, to)| text.replace(from.as_str(), to));
+            let changed = rewritten != *text;
+            *text = rewritten;
+            usize::from(changed)
+        }
+        Value::Array(items) => items.iter_mut().map(|item| rewrite_strings(item, replacements)).sum(),
+        Value::Object(fields) => fields
+            .values_mut()
+            .map(|field| rewrite_strings(field, replacements))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// Point the project at `project_id` and `projects_dir`, returning how many
+/// references changed
+fn rewrite_references(
+    project: &mut ProjectFile,
+    previous_id: &str,
+    previous_workspace: Option<String>,
+    project_id: &str,
+    projects_dir: &Path,
+) -> Result<usize, String> {
+    let mut replacements = Vec::new();
+    if let Some(previous_workspace) = previous_workspace {
+        let workspace = projects_dir.to_string_lossy().into_owned();
+        if !previous_workspace.is_empty() && previous_workspace != workspace {
+            replacements.push((previous_workspace, workspace));
+        }
+    }
+    if previous_id != project_id {
+        replacements.push((format!("/{previous_id}/media/"), format!("/{project_id}/media/")));
+        replacements.push((format!("\\{previous_id}\\media\\"), format!("\\{project_id}\\media\\")));
+    }
+
+    // Media is stored as `media/{file}` relative to the project folder
+    let mut rewritten = 0;
+    let media = &mut project.media;
+    for item in media.images.iter_mut().chain(&mut media.audio).chain(&mut media.captions) {
+        let Some(relative_path) = &item.relative_path else {
+            continue;
+        };
+        let file_name = relative_path.rsplit(['/', '\\']).next().unwrap_or_default();
+        let normalized = format!("media/{file_name}");
+        if !file_name.is_empty() && *relative_path != normalized {
+            item.relative_path = Some(normalized);
+            rewritten += 1;
+        }
+    }
+
+    if replacements.is_empty() {
+        return Ok(rewritten);
+    }
+    let mut value =
+        serde_json::to_value(&*project).map_err(|e| format!("Failed to read project: {e}"))?;
+    // The metadata is set directly below
+    if let Some(metadata) = value.get_mut("project") {
+        *metadata = Value::Null;
+    }
+    rewritten += rewrite_strings(&mut value, &replacements);
+    value["project"] = serde_json::to_value(&project.project)
+        .map_err(|e| format!("Failed to read project: {e}"))?;
+    *project = serde_json::from_value(value).map_err(|e| format!("Failed to update project: {e}"))?;
+    Ok(rewritten)
+}
+
+/// Whether the file at `source` already sits in `projects_dir` as `Name_{id}.scormproj`
+fn is_in_place(source: &Path, project_id: &str, projects_dir: &Path) -> bool {
+    source.parent().is_some_and(|dir| same_path(dir, projects_dir))
+        && split_project_file_name(source).1.as_deref() == Some(project_id)
+}
+
+/// Adopt the project file at `source` into `projects_dir`, or `None` when it
+/// is already part of it
+pub fn adopt_project(
+    source: &Path,
+    projects_dir: &Path,
+    services: &AppServices,
+) -> Result<Option<AdoptedProject>, String> {
+    let raw = fs::read(source).map_err(|e| format!("Failed to read project file: {e}"))?;
+    validate_project_file(&raw)?;
+    let mut project = load_project_file(source)?;
+    if project.project.name.trim().is_empty() {
+        return Err("The project has no name".to_string());
+    }
+
+    let previous_id = project.project.id.clone();
+    if is_in_place(source, &previous_id, projects_dir) {
+        return Ok(None);
+    }
+    let previous_workspace = serde_json::from_slice::<Value>(&raw)
+        .ok()
+        .and_then(|saved| saved["project"]["path"].as_str().map(str::to_string))
+        .and_then(|path| {
+            let separator = path.rfind(['/', '\\'])?;
+            Some(path[..separator].to_string())
+        });
+    let source_folder = find_project_folder(source, &previous_id);
+    let project_id = if is_project_id(&previous_id)
+        && !id_taken(projects_dir, &previous_id, source, source_folder.as_deref())
+    {
+        previous_id.clone()
+    } else {
+        services.new_project_id()
+    };
+
+    let safe_name = project
+        .project
+        .name
+        .replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
+    let project_path = projects_dir.join(format!("{safe_name}_{project_id}.scormproj"));
+    let project_folder = projects_dir.join(&project_id);
+
+    let media_files = match &source_folder {
+        Some(folder) if !same_path(folder, &project_folder) => {
+            copy_project_folder(folder, &project_folder)?
+        }
+        Some(folder) => fs::read_dir(folder.join("media")).map_or(0, |entries| entries.count()),
+        None => {
+            fs::create_dir_all(project_folder.join("media"))
+                .map_err(|e| format!("Failed to create media folder: {e}"))?;
+            0
+        }
+    };
+
+    let rewritten_references = rewrite_references(
+        &mut project,
+        &previous_id,
+        previous_workspace,
+        &project_id,
+        projects_dir,
+    )?;
+    project.project.id = project_id.clone();
+    project.project.path = Some(project_path.to_string_lossy().into_owned());
+    let tags = ProjectTags {
+        tags: project.project.tags.clone(),
+        status: project.project.status.unwrap_or_default(),
+    };
+    save_project_file(&project, &project_path)?;
+
+    // Tags and status follow the project from the index it came with
+    let source_dir = source.parent().unwrap_or(Path::new(""));
+    let carried = if same_path(source_dir, projects_dir) {
+        None
+    } else {
+        load_project_index(source_dir)
+            .ok()
+            .and_then(|index| index.projects.get(&previous_id).cloned())
+    };
+    update_project_tags(projects_dir, &project_id, |entry| {
+        if let Some(carried) = carried {
+            *entry = carried;
+        } else if tags != ProjectTags::default() {
+            *entry = tags;
+        }
+    })?;
+
+    if same_path(source_dir, projects_dir) && !same_path(source, &project_path) {
+        fs::remove_file(source).map_err(|e| format!("Failed to remove the loose project file: {e}"))?;
+    }
+
+    Ok(Some(AdoptedProject {
+        source_path: source.to_string_lossy().into_owned(),
+        project_path: project_path.to_string_lossy().into_owned(),
+        project_id,
+        previous_id,
+        media_files,
+        rewritten_references,
+    }))
+}
+
+/// Adopt the project file at `path`, or every project file in the folder at `path`
+pub fn adopt_projects(
+    path: &Path,
+    projects_dir: &Path,
+    services: &AppServices,
+) -> Result<AdoptionReport, String> {
+    let files = if path.is_dir() {
+        let mut files: Vec<PathBuf> = fs::read_dir(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|file| file.extension().and_then(|ext| ext.to_str()) == Some("scormproj"))
+            .collect();
+        files.sort();
+        files
+    } else if path.extension().and_then(|ext| ext.to_str()) == Some("scormproj") {
+        vec![path.to_path_buf()]
+    } else {
+        return Err(format!("Not a project file or folder: {}", path.display()));
+    };
+
+    let mut report = AdoptionReport::default();
+    for file in files {
+        match adopt_project(&file, projects_dir, services) {
+            Ok(Some(adopted)) => report.adopted.push(adopted),
+            Ok(None) => report.skipped.push(file.to_string_lossy().into_owned()),
+            Err(error) => report.failed.push(AdoptionFailure {
+                path: file.to_string_lossy().into_owned(),
+                error,
+            }),
+        }
+    }
+    Ok(report)
+}
+
+/// Bring a `.scormproj` file, or a folder of them, copied in by hand into the workspace
+#[tauri::command]
+pub async fn adopt_external_project(
+    path: String,
+    services: State<'_, AppServices>,
+) -> Result<AdoptionReport, String> {
+    let services = services.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let projects_dir = crate::project_storage::get_projects_directory()?;
+        adopt_projects(Path::new(&path), &projects_dir, &services)
+    })
+    .await
+    .map_err(|e| format!("Adoption task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_services::testing::deterministic_services;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn write_project(path: &Path, id: &str) {
+        let project = json!({
+            "project": {
+                "id": id,
+                "name": "Fire Safety",
+                "created": "2024-01-01T00:00:00Z",
+                "last_modified": "2024-01-01T00:00:00Z",
+                "path": format!("C:\\Users\\sam\\Projects\\Fire_Safety_{id}.scormproj")
+            },
+            "course_data": { "title": "Fire", "difficulty": 1, "template": "standard", "topics": [] },
+            "course_content": {
+                "welcomePage": {
+                    "id": "welcome",
+                    "content": format!("<img src=\"asset://localhost/{id}/media/image-0.bin\">")
+                },
+                "topics": []
+            },
+            "media": {
+                "images": [{ "id": "image-0", "filename": "fire.png", "relative_path": "C:\\Users\\sam\\Projects\\old\\media\\image-0.bin" }],
+                "videos": [],
+                "audio": [],
+                "captions": []
+            },
+            "audio_settings": { "voice": "default", "speed": 1.0, "pitch": 1.0 },
+            "scorm_config": { "version": "1.2", "completion_criteria": "pages_viewed", "passing_score": 80 }
+        });
+        fs::write(path, serde_json::to_vec(&project).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_loose_project_is_laid_out_and_rewritten() {
+        let (_, services) = deterministic_services();
+        let source_dir = TempDir::new().unwrap();
+        let projects = TempDir::new().unwrap();
+
+        // Copied by hand: a renamed file, its media folder named after the file
+        let source = source_dir.path().join("fire copy.scormproj");
+        write_project(&source, "1600000000000");
+        fs::create_dir_all(source_dir.path().join("fire copy/media")).unwrap();
+        fs::write(source_dir.path().join("fire copy/media/image-0.bin"), b"png").unwrap();
+        fs::create_dir_all(source_dir.path().join("fire copy/thumbnails")).unwrap();
+        fs::write(
+            source_dir.path().join("projects-index.json"),
+            r#"{"projects": {"1600000000000": {"tags": ["safety"], "status": "in_review"}}}"#,
+        )
+        .unwrap();
+        // The workspace already has a project with that ID
+        write_project(&projects.path().join("Other_1600000000000.scormproj"), "1600000000000");
+
+        let report = adopt_projects(source_dir.path(), projects.path(), &services).unwrap();
+
+        assert!(report.failed.is_empty(), "{:?}", report.failed);
+        let adopted = &report.adopted[0];
+        assert_eq!(adopted.previous_id, "1600000000000");
+        assert_eq!(adopted.project_id, "1700000000000");
+        assert_eq!(adopted.media_files, 1);
+        assert!(projects.path().join("1700000000000/media/image-0.bin").exists());
+        assert!(!projects.path().join("1700000000000/thumbnails").exists());
+        assert!(source.exists());
+
+        let project = load_project_file(Path::new(&adopted.project_path)).unwrap();
+        assert!(adopted.project_path.ends_with("Fire_Safety_1700000000000.scormproj"));
+        assert_eq!(project.project.id, "1700000000000");
+        assert_eq!(project.media.images[0].relative_path.as_deref(), Some("media/image-0.bin"));
+        assert_eq!(
+            project.course_content.unwrap()["welcomePage"]["content"],
+            "<img src=\"asset://localhost/1700000000000/media/image-0.bin\">"
+        );
+
+        let index = load_project_index(projects.path()).unwrap();
+        assert_eq!(index.projects["1700000000000"].tags, vec!["safety".to_string()]);
+
+        // Adopting the workspace itself finds nothing left to do
+        let again = adopt_projects(projects.path(), projects.path(), &services).unwrap();
+        assert!(again.adopted.is_empty());
+        assert_eq!(again.skipped.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_project_file_is_reported() {
+        let (_, services) = deterministic_services();
+        let source_dir = TempDir::new().unwrap();
+        let projects = TempDir::new().unwrap();
+        fs::write(source_dir.path().join("Broken_1.scormproj"), b"{").unwrap();
+
+        let report = adopt_projects(source_dir.path(), projects.path(), &services).unwrap();
+
+        assert!(report.adopted.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        assert!(adopt_projects(&source_dir.path().join("notes.txt"), projects.path(), &services).is_err());
+    }
+}
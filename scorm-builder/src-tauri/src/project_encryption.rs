@@ -0,0 +1,650 @@
+//! Optional encryption at rest of a project's content, with a passphrase set
+//! per project.
+//!
+//! An encrypted project keeps its `project` header readable, so it is still
+//! listed and can be recognised, and moves every other field of the
+//! `.scormproj` into `encrypted_payload`. Every file of its `media/` and
+//! `thumbnails/` folders is encrypted too, metadata included, and so are the
+//! backups of the project file written before encryption was turned on. The
+//! key is derived from the passphrase with Argon2id, using the salt and cost
+//! stored in the header's `encryption` settings, and data is sealed with
+//! AES-256-GCM.
+//!
+//! Unlocking keeps the key in memory until the project is locked or the app
+//! closes. Until then loading, saving, media and exports work as for any
+//! project. Exports are written decrypted, so the ZIP opens anywhere; backups
+//! and archives stay encrypted. The passphrase is not stored anywhere and
+//! can't be reset: without it the content can't be recovered.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose, Engine as _};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use crate::content_pages;
+use crate::messages::Message;
+use crate::project_listing::read_project_header;
+use crate::project_storage::{load_project_file, save_project_file};
+
+pub const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+/// Start of every encrypted blob, followed by the nonce and the ciphertext
+const MAGIC: &[u8] = b"SBENC\x01";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const KDF: &str = "argon2id";
+/// Known text sealed with the key, to tell a wrong passphrase from damaged content
+const KEY_CHECK: &[u8] = b"scorm-builder project key";
+/// Field of the project file holding everything but the header
+const PAYLOAD_FIELD: &str = "encrypted_payload";
+
+/// Argon2id cost: the OWASP minimum of 19 MiB and two passes
+const MEMORY_KIB: u32 = 19 * 1024;
+const ITERATIONS: u32 = 2;
+const PARALLELISM: u32 = 1;
+
+type ProjectKey = [u8; 32];
+
+fn argon2id(
+    passphrase: &str,
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> Result<ProjectKey, String> {
+    let params = Params::new(memory_kib, iterations, parallelism, Some(32))
+        .map_err(|e| format!("Invalid key derivation settings: {e}"))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {e}"))?;
+    Ok(key)
+}
+
+/// Key for `passphrase` and `salt` at the default Argon2id cost, for the other
+/// passphrase-protected files: source snapshots and profile API keys
+pub(crate) fn derive_passphrase_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    argon2id(passphrase, salt, MEMORY_KIB, ITERATIONS, PARALLELISM)
+}
+
+/// Keys of the unlocked projects, by the name of their folder
+static UNLOCKED: Lazy<Mutex<HashMap<String, ProjectKey>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// How an encrypted project's key is derived, kept in its readable header
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectEncryption {
+    pub kdf: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub salt: String,
+    /// `KEY_CHECK` sealed with the key
+    pub key_check: String,
+}
+
+impl ProjectEncryption {
+    /// New settings with a random salt, and the key `passphrase` gives with them
+    fn create(
+        passphrase: &str,
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    ) -> Result<(Self, ProjectKey), String> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+            return Err(format!(
+                "The passphrase must be at least {MIN_PASSPHRASE_LENGTH} characters"
+            ));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut encryption = Self {
+            kdf: KDF.to_string(),
+            memory_kib,
+            iterations,
+            parallelism,
+            salt: general_purpose::STANDARD.encode(salt),
+            key_check: String::new(),
+        };
+        let key = encryption.derive_key(passphrase)?;
+        encryption.key_check = general_purpose::STANDARD.encode(seal(&key, KEY_CHECK)?);
+        Ok((encryption, key))
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<ProjectKey, String> {
+        if self.kdf != KDF {
+            return Err(format!("Unsupported key derivation: {}", self.kdf));
+        }
+        let salt = general_purpose::STANDARD
+            .decode(&self.salt)
+            .map_err(|e| format!("Failed to decode salt: {e}"))?;
+        argon2id(passphrase, &salt, self.memory_kib, self.iterations, self.parallelism)
+    }
+
+    /// The key for `passphrase`, refusing a wrong one
+    fn unlock(&self, passphrase: &str) -> Result<ProjectKey, String> {
+        let key = self.derive_key(passphrase)?;
+        let check = general_purpose::STANDARD
+            .decode(&self.key_check)
+            .map_err(|e| format!("Failed to decode key check: {e}"))?;
+        match open(&key, &check) {
+            Ok(text) if text == KEY_CHECK => Ok(key),
+            _ => Err("Wrong passphrase for this project".to_string()),
+        }
+    }
+}
+
+/// Whether `data` was sealed by this module
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+fn seal(key: &ProjectKey, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|e| format!("Failed to encrypt: {e}"))?;
+    Ok([MAGIC, &nonce[..], &ciphertext[..]].concat())
+}
+
+fn open(key: &ProjectKey, sealed: &[u8]) -> Result<Vec<u8>, String> {
+    let body = sealed
+        .strip_prefix(MAGIC)
+        .filter(|body| body.len() > NONCE_LEN)
+        .ok_or_else(|| "Not encrypted project data".to_string())?;
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt: wrong key or damaged data".to_string())
+}
+
+fn unlocked_key(folder: &str) -> Option<ProjectKey> {
+    UNLOCKED.lock().ok()?.get(folder).copied()
+}
+
+fn set_unlocked_key(folder: &str, key: Option<ProjectKey>) {
+    if let Ok(mut keys) = UNLOCKED.lock() {
+        match key {
+            Some(key) => keys.insert(folder.to_string(), key),
+            None => keys.remove(folder),
+        };
+    }
+}
+
+fn locked_error(name: &str) -> String {
+    Message::new("error.project.locked").with("name", name).into()
+}
+
+pub fn recovery_warning() -> String {
+    Message::new("warning.project.encryption_recovery").to_string()
+}
+
+/// Name of the folder beside the project file that holds its media and pages,
+/// which its key is kept under
+fn project_folder(project_path: &Path, project_id: &str) -> Result<String, String> {
+    let pages_dir = content_pages::pages_dir(project_path, project_id)?;
+    pages_dir
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("No project folder for {}", project_path.display()))
+}
+
+fn project_id_of(project: &Value) -> Result<&str, String> {
+    project["project"]["id"]
+        .as_str()
+        .ok_or_else(|| "Project file has no project ID".to_string())
+}
+
+/// Move every field of `project` but its header into `encrypted_payload`.
+/// Fails while the project is locked.
+pub fn seal_project(project_path: &Path, project: &mut Value) -> Result<(), String> {
+    let folder = project_folder(project_path, project_id_of(project)?)?;
+    let key = unlocked_key(&folder).ok_or_else(|| locked_error(&folder))?;
+    let Value::Object(fields) = project else {
+        return Err("Project file is not a JSON object".to_string());
+    };
+    let names: Vec<String> = fields.keys().filter(|name| *name != "project").cloned().collect();
+    let payload: Map<String, Value> = names
+        .iter()
+        .filter_map(|name| fields.remove_entry(name))
+        .collect();
+    let payload =
+        serde_json::to_vec(&payload).map_err(|e| format!("Failed to serialize project: {e}"))?;
+    let sealed = general_purpose::STANDARD.encode(seal(&key, &payload)?);
+    fields.insert(PAYLOAD_FIELD.to_string(), Value::String(sealed));
+    Ok(())
+}
+
+/// Put back the fields `seal_project` moved; projects without them are left
+/// as they are. Fails while the project is locked.
+pub fn open_project(project_path: &Path, project: &mut Value) -> Result<(), String> {
+    let Some(sealed) = project.get(PAYLOAD_FIELD).and_then(Value::as_str) else {
+        return Ok(());
+    };
+    let folder = project_folder(project_path, project_id_of(project)?)?;
+    let key = unlocked_key(&folder).ok_or_else(|| locked_error(&folder))?;
+    let sealed = general_purpose::STANDARD
+        .decode(sealed)
+        .map_err(|e| format!("Failed to decode project content: {e}"))?;
+    let payload: Map<String, Value> = serde_json::from_slice(&open(&key, &sealed)?)
+        .map_err(|e| format!("Failed to parse project content: {e}"))?;
+    if let Value::Object(fields) = project {
+        fields.remove(PAYLOAD_FIELD);
+        fields.extend(payload);
+    }
+    Ok(())
+}
+
+/// `content` of the project file as it goes into an export: decrypted and
+/// without its encryption settings, so the ZIP opens without the passphrase.
+/// Fails while the project is locked.
+pub fn decrypt_for_export(project_path: &Path, content: Vec<u8>) -> Result<Vec<u8>, String> {
+    let Ok(mut project) = serde_json::from_slice::<Value>(&content) else {
+        return Ok(content);
+    };
+    if project.get(PAYLOAD_FIELD).is_none() {
+        return Ok(content);
+    }
+    open_project(project_path, &mut project)?;
+    if let Some(header) = project["project"].as_object_mut() {
+        header.remove("encryption");
+    }
+    serde_json::to_vec_pretty(&project).map_err(|e| format!("Failed to serialize project: {e}"))
+}
+
+/// Folder name of the project a media file at `{folder}/media/{id}.bin` belongs to
+fn media_folder(path: &Path) -> Option<String> {
+    let folder = path.parent()?.parent()?.file_name()?;
+    Some(folder.to_string_lossy().to_string())
+}
+
+/// Key to write the media of `folder` with; none when the project isn't encrypted
+fn media_key(folder: &str) -> Result<Option<ProjectKey>, String> {
+    if let Some(key) = unlocked_key(folder) {
+        return Ok(Some(key));
+    }
+    let encrypted = crate::media_usage::find_project_file(folder)
+        .and_then(|path| read_project_header(&path).ok())
+        .is_some_and(|header| header.encryption.is_some());
+    if encrypted {
+        Err(locked_error(folder))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Whether the file at `path` is encrypted
+pub fn is_sealed_file(path: &Path) -> bool {
+    let mut start = [0u8; MAGIC.len()];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut start))
+        .is_ok_and(|_| is_sealed(&start))
+}
+
+/// `data` as read from the media file at `path`, decrypted when it is sealed
+pub fn open_media_data(path: &Path, data: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !is_sealed(&data) {
+        return Ok(data);
+    }
+    let folder = media_folder(path).unwrap_or_default();
+    let key = unlocked_key(&folder).ok_or_else(|| locked_error(&folder))?;
+    open(&key, &data)
+}
+
+/// Contents of a stored media file, decrypted when its project is encrypted
+pub fn read_media_file(path: &Path) -> Result<Vec<u8>, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    open_media_data(path, data)
+}
+
+/// Text of a media `.json` metadata file, decrypted when its project is encrypted
+pub fn read_metadata_file(path: &Path) -> Result<String, String> {
+    String::from_utf8(read_media_file(path)?)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+/// Store a media file, encrypted when its project is
+pub fn write_media_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    let key = match media_folder(path) {
+        Some(folder) => media_key(&folder)?,
+        None => None,
+    };
+    let write = |data: &[u8]| {
+        fs::write(path, data).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    };
+    match key {
+        Some(key) => write(&seal(&key, data)?),
+        None => write(data),
+    }
+}
+
+/// `path` with `.tmp` added, written first and renamed over `path`
+fn temp_path_of(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    PathBuf::from(temp_path)
+}
+
+/// Encrypt (or decrypt) the files of a folder that aren't yet, each through
+/// a temporary file. Returns how many were converted.
+fn convert_media(media_dir: &Path, key: &ProjectKey, encrypt: bool) -> Result<usize, String> {
+    let Ok(entries) = fs::read_dir(media_dir) else {
+        return Ok(0);
+    };
+    let mut converted = 0;
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) == Some("tmp") {
+            continue;
+        }
+        let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let data = match (encrypt, is_sealed(&data)) {
+            (true, false) => seal(key, &data)?,
+            (false, true) => open(key, &data)?,
+            _ => continue,
+        };
+        let temp_path = temp_path_of(&path);
+        fs::write(&temp_path, data)
+            .and_then(|_| fs::rename(&temp_path, &path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp_path);
+                format!("Failed to write {}: {e}", path.display())
+            })?;
+        converted += 1;
+    }
+    Ok(converted)
+}
+
+/// Whether a project is encrypted and unlocked, with what to tell its author
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct EncryptionStatus {
+    pub encrypted: bool,
+    pub unlocked: bool,
+    /// What the author must be told, starting with the no-recovery warning
+    pub warnings: Vec<String>,
+}
+
+struct EncryptedProject {
+    path: PathBuf,
+    folder: String,
+    encryption: Option<ProjectEncryption>,
+}
+
+impl EncryptedProject {
+    fn find(project_id_or_path: &str) -> Result<Self, String> {
+        let path = crate::media_usage::find_project_file(project_id_or_path)
+            .ok_or_else(|| format!("Project file not found: {project_id_or_path}"))?;
+        let header = read_project_header(&path)?;
+        Ok(Self {
+            folder: project_folder(&path, &header.id)?,
+            encryption: header.encryption,
+            path,
+        })
+    }
+
+    fn media_dir(&self) -> PathBuf {
+        let parent = self.path.parent().unwrap_or(Path::new(""));
+        parent.join(&self.folder).join("media")
+    }
+
+    /// Folders whose every file is encrypted with the project
+    fn sealed_dirs(&self) -> [PathBuf; 2] {
+        let thumbnails = self.media_dir().with_file_name(crate::page_thumbnails::THUMBNAIL_DIR);
+        [self.media_dir(), thumbnails]
+    }
+
+    fn convert_files(&self, key: &ProjectKey, encrypt: bool) -> Result<usize, String> {
+        let mut converted = 0;
+        for dir in self.sealed_dirs() {
+            converted += convert_media(&dir, key, encrypt)?;
+        }
+        Ok(converted)
+    }
+
+    fn settings(&self) -> Result<&ProjectEncryption, String> {
+        self.encryption
+            .as_ref()
+            .ok_or_else(|| "This project is not encrypted".to_string())
+    }
+
+    fn status(&self) -> EncryptionStatus {
+        let encrypted = self.encryption.is_some();
+        EncryptionStatus {
+            encrypted,
+            unlocked: encrypted && unlocked_key(&self.folder).is_some(),
+            warnings: if encrypted { vec![recovery_warning()] } else { Vec::new() },
+        }
+    }
+}
+
+/// Encrypt a project's content and media with `passphrase`. Refused unless
+/// the author has acknowledged that a lost passphrase can't be recovered.
+fn enable_encryption(
+    project_id_or_path: &str,
+    passphrase: &str,
+    acknowledged: bool,
+    memory_kib: u32,
+) -> Result<EncryptionStatus, String> {
+    if !acknowledged {
+        return Err(recovery_warning());
+    }
+    let mut target = EncryptedProject::find(project_id_or_path)?;
+    if target.encryption.is_some() {
+        return Err("This project is already encrypted".to_string());
+    }
+    let mut project = load_project_file(&target.path)?;
+    let (encryption, key) = ProjectEncryption::create(passphrase, memory_kib, ITERATIONS, PARALLELISM)?;
+
+    // Pages of the split layout would stay readable, so content goes inline
+    project.project.content_layout = None;
+    project.project.encryption = Some(encryption.clone());
+    set_unlocked_key(&target.folder, Some(key));
+    if let Err(e) = save_project_file(&project, &target.path) {
+        set_unlocked_key(&target.folder, None);
+        return Err(e);
+    }
+    // With the settings saved first, media left over by an interruption is
+    // encrypted on the next unlock
+    target.convert_files(&key, true)?;
+    seal_backups(&target.path, &encryption)?;
+
+    target.encryption = Some(encryption);
+    let mut status = target.status();
+    status.warnings.push(Message::new("warning.project.unencrypted_exports").to_string());
+    Ok(status)
+}
+
+/// Encrypt the backups and history copies of the project file written before
+/// it was encrypted. A backup that isn't a project file can't be restored
+/// from, so it is deleted rather than left readable. Returns how many changed.
+fn seal_backups(project_path: &Path, encryption: &ProjectEncryption) -> Result<usize, String> {
+    let settings =
+        serde_json::to_value(encryption).map_err(|e| format!("Failed to serialize settings: {e}"))?;
+    let mut converted = 0;
+    for (backup_path, ..) in crate::backup_recovery::list_backups(project_path) {
+        let content = fs::read(&backup_path)
+            .map_err(|e| format!("Failed to read {}: {e}", backup_path.display()))?;
+        let mut project = match serde_json::from_slice::<Value>(&content) {
+            Ok(project) if project.get(PAYLOAD_FIELD).is_some() => continue,
+            Ok(project) if project["project"].is_object() => project,
+            _ => {
+                fs::remove_file(&backup_path)
+                    .map_err(|e| format!("Failed to delete {}: {e}", backup_path.display()))?;
+                converted += 1;
+                continue;
+            }
+        };
+        project["project"]["encryption"] = settings.clone();
+        seal_project(project_path, &mut project)?;
+        let sealed = serde_json::to_vec_pretty(&project)
+            .map_err(|e| format!("Failed to serialize project: {e}"))?;
+        let temp_path = temp_path_of(&backup_path);
+        fs::write(&temp_path, sealed)
+            .and_then(|_| fs::rename(&temp_path, &backup_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp_path);
+                format!("Failed to write {}: {e}", backup_path.display())
+            })?;
+        converted += 1;
+    }
+    Ok(converted)
+}
+
+fn unlock(project_id_or_path: &str, passphrase: &str) -> Result<EncryptionStatus, String> {
+    let target = EncryptedProject::find(project_id_or_path)?;
+    let key = target.settings()?.unlock(passphrase)?;
+    set_unlocked_key(&target.folder, Some(key));
+    target.convert_files(&key, true)?;
+    Ok(target.status())
+}
+
+fn disable_encryption(project_id_or_path: &str, passphrase: &str) -> Result<EncryptionStatus, String> {
+    let mut target = EncryptedProject::find(project_id_or_path)?;
+    let key = target.settings()?.unlock(passphrase)?;
+    set_unlocked_key(&target.folder, Some(key));
+    let mut project = load_project_file(&target.path)?;
+
+    // Media first: until the project is saved without its settings, both
+    // encrypted and plain media can be read
+    target.convert_files(&key, false)?;
+    project.project.encryption = None;
+    save_project_file(&project, &target.path)?;
+    set_unlocked_key(&target.folder, None);
+
+    target.encryption = None;
+    Ok(target.status())
+}
+
+#[tauri::command]
+pub fn get_project_encryption(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<EncryptionStatus, String> {
+    Ok(EncryptedProject::find(&projectId)?.status())
+}
+
+#[tauri::command]
+pub async fn enable_project_encryption(
+    #[allow(non_snake_case)] projectId: String,
+    passphrase: String,
+    #[allow(non_snake_case)] acknowledgeNoRecovery: bool,
+) -> Result<EncryptionStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        enable_encryption(&projectId, &passphrase, acknowledgeNoRecovery, MEMORY_KIB)
+    })
+    .await
+    .map_err(|e| format!("Encryption task failed: {e}"))?
+}
+
+#[tauri::command]
+pub async fn unlock_project(
+    #[allow(non_snake_case)] projectId: String,
+    passphrase: String,
+) -> Result<EncryptionStatus, String> {
+    tokio::task::spawn_blocking(move || unlock(&projectId, &passphrase))
+        .await
+        .map_err(|e| format!("Unlock task failed: {e}"))?
+}
+
+#[tauri::command]
+pub fn lock_project(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<EncryptionStatus, String> {
+    let target = EncryptedProject::find(&projectId)?;
+    set_unlocked_key(&target.folder, None);
+    Ok(target.status())
+}
+
+#[tauri::command]
+pub async fn disable_project_encryption(
+    #[allow(non_snake_case)] projectId: String,
+    passphrase: String,
+) -> Result<EncryptionStatus, String> {
+    tokio::task::spawn_blocking(move || disable_encryption(&projectId, &passphrase))
+        .await
+        .map_err(|e| format!("Decryption task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Small enough to keep tests fast
+    const TEST_MEMORY_KIB: u32 = 64;
+
+    #[test]
+    fn test_only_the_right_passphrase_unlocks() {
+        let (encryption, key) =
+            ProjectEncryption::create("correct horse", TEST_MEMORY_KIB, 1, 1).unwrap();
+        assert_eq!(encryption.unlock("correct horse").unwrap(), key);
+        assert!(encryption.unlock("wrong horse").is_err());
+        assert!(ProjectEncryption::create("short", TEST_MEMORY_KIB, 1, 1).is_err());
+
+        let sealed = seal(&key, b"hello").unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(open(&key, &sealed).unwrap(), b"hello");
+        let (_, other_key) = ProjectEncryption::create("correct horse", TEST_MEMORY_KIB, 1, 1).unwrap();
+        assert!(open(&other_key, &sealed).is_err());
+    }
+
+    #[test]
+    fn test_project_content_and_media_are_unreadable_until_unlocked() {
+        let dir = TempDir::new().unwrap();
+        let project_path = dir.path().join("Safety_1700000000001.scormproj");
+        let project = serde_json::json!({
+            "project": { "id": "1700000000001", "name": "Safety" },
+            "course_content": { "topics": [{ "id": "topic-0", "content": "Wear gloves" }] }
+        });
+        let (encryption, key) = ProjectEncryption::create("correct horse", TEST_MEMORY_KIB, 1, 1).unwrap();
+        set_unlocked_key("1700000000001", Some(key));
+
+        let mut sealed = project.clone();
+        sealed["project"]["encryption"] = serde_json::to_value(&encryption).unwrap();
+        seal_project(&project_path, &mut sealed).unwrap();
+        let raw = serde_json::to_vec(&sealed).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("Wear gloves"));
+        assert_eq!(sealed["project"]["name"], "Safety");
+
+        let media_dir = dir.path().join("1700000000001").join("media");
+        fs::create_dir_all(&media_dir).unwrap();
+        let media_path = media_dir.join("image-0.bin");
+        write_media_file(&media_path, b"png").unwrap();
+        assert!(is_sealed_file(&media_path));
+        assert_eq!(read_media_file(&media_path).unwrap(), b"png");
+
+        let exported: Value =
+            serde_json::from_slice(&decrypt_for_export(&project_path, raw.clone()).unwrap()).unwrap();
+        assert_eq!(exported["course_content"], project["course_content"]);
+        assert!(exported["project"].get("encryption").is_none());
+
+        set_unlocked_key("1700000000001", None);
+        let mut locked = sealed.clone();
+        assert!(open_project(&project_path, &mut locked).is_err());
+        assert!(read_media_file(&media_path).is_err());
+        assert!(decrypt_for_export(&project_path, raw).is_err());
+
+        set_unlocked_key("1700000000001", Some(encryption.unlock("correct horse").unwrap()));
+        open_project(&project_path, &mut sealed).unwrap();
+        assert_eq!(sealed["course_content"], project["course_content"]);
+        assert_eq!(convert_media(&media_dir, &key, false).unwrap(), 1);
+        assert_eq!(fs::read(&media_path).unwrap(), b"png");
+        set_unlocked_key("1700000000001", None);
+    }
+}
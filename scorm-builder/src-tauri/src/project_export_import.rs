@@ -1,11 +1,22 @@
+use crate::app_services::AppServices;
+use crate::disk_space::{ensure_space, path_size, with_free_space};
+use crate::file_names::{FileNameMap, FILE_NAME_MAP};
+use crate::media_names::{ExportManifest, MediaNameMap, OriginalNamer, EXPORT_MANIFEST};
 use crate::media_storage::{get_media_directory, MediaData};
+use crate::messages::Message;
+use crate::narration_ssml::{import_lexicon, load_lexicon, LexiconEntry};
+use crate::project_encryption::{decrypt_for_export, read_media_file};
+use crate::project_operations::{begin_operation, OperationKind, ProjectOperations};
 use crate::project_storage::{save_project_file, ProjectFile};
+use crate::media_alignment::{check_media_alignment, fix_media_alignment, MediaCorrection};
+use crate::stable_ids::is_stable_media_id;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{Read, Write};
-use std::path::Path;
-use tauri::Emitter;
+use std::path::{Component, Path, PathBuf};
+use tauri::{Emitter, State};
 use tempfile::TempDir;
+use ts_rs::TS;
 use zip::write::FileOptions;
 use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
@@ -26,6 +37,11 @@ fn is_duplicate_media_file(file_name: &str) -> bool {
         file_name
     };
 
+    // Stable IDs contain dashes of their own
+    if is_stable_media_id(name_without_ext) {
+        return false;
+    }
+
     // Look for pattern: {type}-{number}-{suffix} (e.g., audio-1-1, caption-2-1)
     // ALL such patterns are invalid duplicates - no exceptions
     let parts: Vec<&str> = name_without_ext.split('-').collect();
@@ -40,8 +56,39 @@ fn is_duplicate_media_file(file_name: &str) -> bool {
     false
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Upper bound of the ZIP of a project: the project file plus, optionally, its media
+fn export_size(project_path: &Path, project_id: &str, include_media: bool) -> u64 {
+    let media_size = if include_media {
+        get_media_directory(project_id).map_or(0, |dir| path_size(&dir))
+    } else {
+        0
+    };
+    path_size(project_path) + media_size
+}
+
+/// Folder an export is saved to: the one the caller names, or else the downloads
+/// folder the webview saves to
+fn export_destination(destination: Option<String>) -> PathBuf {
+    destination
+        .map(PathBuf::from)
+        .or_else(dirs::download_dir)
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Fail early when the disk of `destination` has no room to save an export of the project
+fn ensure_export_space(
+    destination: &Path,
+    project_path: &Path,
+    project_id: &str,
+    include_media: bool,
+) -> Result<(), String> {
+    ensure_space(destination, export_size(project_path, project_id, include_media))
+        .map_err(String::from)
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 pub struct ZipExportResult {
     pub zip_data: Vec<u8>,
     pub file_count: usize,
@@ -54,12 +101,74 @@ pub struct ExtractedProject {
     pub media_files: Vec<MediaData>,
 }
 
-/// Creates a ZIP file containing the project and its media files
+/// Pronunciation lexicon of `project_id`, which export carries in the manifest
+fn export_lexicon(project_id: &str) -> Result<Vec<LexiconEntry>, String> {
+    match get_media_directory(project_id)?.parent() {
+        Some(project_dir) => load_lexicon(project_dir),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Creates a ZIP file containing the project and its media files.
+///
+/// With `name_media_by_original` media is named in the ZIP after the file it was
+/// added from rather than its storage id (see `media_names`). `destination` is the
+/// folder the ZIP will be saved to, checked for room first. An encrypted
+/// project is exported decrypted. The project is held for the export, so it
+/// is refused while another build, export or import of it runs.
 #[tauri::command]
 pub async fn create_project_zip(
     project_path: String,
     project_id: String,
     include_media: bool,
+    name_media_by_original: Option<bool>,
+    destination: Option<String>,
+    services: State<'_, AppServices>,
+    operations: State<'_, ProjectOperations>,
+) -> Result<ZipExportResult, String> {
+    create_project_zip_with_services(
+        project_path,
+        project_id,
+        include_media,
+        name_media_by_original,
+        destination,
+        &operations,
+        &services,
+    )
+    .await
+}
+
+/// Same as `create_project_zip`, with the operations and services passed in explicitly
+pub async fn create_project_zip_with_services(
+    project_path: String,
+    project_id: String,
+    include_media: bool,
+    name_media_by_original: Option<bool>,
+    destination: Option<String>,
+    operations: &ProjectOperations,
+    services: &AppServices,
+) -> Result<ZipExportResult, String> {
+    let _operation = begin_operation(operations, services, &project_id, OperationKind::Export)?;
+    build_project_zip(
+        project_path,
+        project_id,
+        include_media,
+        name_media_by_original,
+        destination,
+        false,
+    )
+    .await
+}
+
+/// `create_project_zip`, with `keep_sealed` copying an encrypted project's
+/// file and media as they are stored, so the ZIP stays encrypted
+async fn build_project_zip(
+    project_path: String,
+    project_id: String,
+    include_media: bool,
+    name_media_by_original: Option<bool>,
+    destination: Option<String>,
+    keep_sealed: bool,
 ) -> Result<ZipExportResult, String> {
 
     debug_log(&format!("Starting export for project_id: {}, path: {}, include_media: {}",
@@ -80,25 +189,30 @@ pub async fn create_project_zip(
             .compression_method(CompressionMethod::Deflated)
             .unix_permissions(0o755);
 
-        // Add the actual project file to ZIP (not parsed, just the raw file)
+        // Add the actual project file to ZIP (the raw file, unless its pages are split out)
         let project_path_obj = Path::new(&project_path);
         if !project_path_obj.exists() {
             return Err(format!("Project file not found: {}", project_path));
         }
+        ensure_export_space(&export_destination(destination), project_path_obj, &project_id, include_media)?;
         
+        // Names a ZIP can't hold as they are are renamed, and restored on import
+        let mut file_names = FileNameMap::default();
         let project_filename = project_path_obj
             .file_name()
-            .and_then(|n| n.to_str())
+            .map(|name| file_names.entry_path("", name))
             .ok_or_else(|| "Invalid project filename".to_string())?;
         
         
-        // Read the project file as-is
-        let project_content = fs::read(&project_path)
-            .map_err(|e| format!("Failed to read project file: {}", e))?;
+        // Read the project file as-is, with its pages inlined if it uses the split layout
+        let mut project_content = crate::content_pages::read_project_for_export(project_path_obj)?;
+        if !keep_sealed {
+            project_content = decrypt_for_export(project_path_obj, project_content)?;
+        }
         
         
         // Add to ZIP with original filename
-        zip.start_file(project_filename, options)
+        zip.start_file(&project_filename, options)
             .map_err(|e| format!("Failed to start project file in ZIP: {}", e))?;
         zip.write_all(&project_content)
             .map_err(|e| format!("Failed to write project to ZIP: {}", e))?;
@@ -107,26 +221,23 @@ pub async fn create_project_zip(
         file_count += 1;
         total_size += project_content.len();
 
+        // Set when media is named after its original file
+        let mut media_names: Option<OriginalNamer> = None;
+
         // Add media files if requested
         if include_media {
             debug_log(&format!("Media inclusion requested for project_id: {}", project_id));
 
             // Validate media page_id assignments before export
-            if let Ok(validation_result) = crate::media_page_id_migration::validate_media_page_ids(project_id.clone()).await {
-                if let Some(invalid_files) = validation_result.get("invalid_files").and_then(|v| v.as_u64()) {
-                    if invalid_files > 0 {
-                        debug_log(&format!("WARNING: Found {} media files with incorrect page_id assignments in project {}", invalid_files, project_id));
-                        debug_log("Consider running migrate_media_page_ids to fix these issues before export");
-                        if let Some(issues) = validation_result.get("issues").and_then(|v| v.as_array()) {
-                            for issue in issues.iter().take(3) { // Show first 3 issues
-                                if let Some(issue_str) = issue.as_str() {
-                                    debug_log(&format!("  - {}", issue_str));
-                                }
-                            }
-                            if issues.len() > 3 {
-                                debug_log(&format!("  ... and {} more issues", issues.len() - 3));
-                            }
-                        }
+            if let Ok(validation) = crate::media_page_id_migration::validate_media_page_ids(project_id.clone()).await {
+                if validation.invalid_files > 0 {
+                    debug_log(&format!("WARNING: Found {} media files with incorrect page_id assignments in project {}", validation.invalid_files, project_id));
+                    debug_log("Consider running migrate_media_page_ids to fix these issues before export");
+                    for issue in validation.issues.iter().take(3) { // Show first 3 issues
+                        debug_log(&format!("  - {}", issue));
+                    }
+                    if validation.issues.len() > 3 {
+                        debug_log(&format!("  ... and {} more issues", validation.issues.len() - 3));
                     }
                 }
             }
@@ -182,29 +293,47 @@ pub async fn create_project_zip(
             if media_dir.exists() {
                 // Read all media files
                 let entries = fs::read_dir(&media_dir)
-                    .map_err(|e| format!("Failed to read media directory: {}", e))?;
+                    .map_err(|e| format!("Failed to read media directory: {}", e))?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("Failed to read directory entry: {}", e))?;
+                if name_media_by_original.unwrap_or(false) {
+                    let names: Vec<_> = entries.iter().map(|entry| entry.file_name()).collect();
+                    media_names = Some(OriginalNamer::new(
+                        &format!("{}/media", effective_project_id),
+                        names.iter().map(|name| name.as_os_str()),
+                    ));
+                }
 
                 let mut media_files_found = 0;
                 for entry in entries {
-                    let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
                     let path = entry.path();
 
                     debug_log(&format!("Found entry: {} (is_file: {})", path.display(), path.is_file()));
 
                     if path.is_file() {
-                        let file_name = path
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .ok_or_else(|| "Invalid file name".to_string())?;
+                        let os_file_name = entry.file_name();
+                        let file_name = os_file_name.to_string_lossy();
 
                         // Read file content
-                        let file_content = fs::read(&path)
-                            .map_err(|e| format!("Failed to read media file {}: {}", file_name, e))?;
+                        let file_content = if keep_sealed {
+                            fs::read(&path).map_err(|e| e.to_string())
+                        } else {
+                            read_media_file(&path)
+                        }
+                        .map_err(|e| format!("Failed to read media file {}: {}", file_name, e))?;
 
                         debug_log(&format!("Adding media file to ZIP: {} ({} bytes)", file_name, file_content.len()));
 
                         // Add to ZIP with the media folder structure (use effective project ID)
-                        let zip_path = format!("{}/media/{}", effective_project_id, file_name);
+                        let zip_path = media_names
+                            .as_mut()
+                            .and_then(|namer| namer.entry_path(&media_dir, &os_file_name))
+                            .unwrap_or_else(|| {
+                                file_names.entry_path(
+                                    &format!("{}/media", effective_project_id),
+                                    &os_file_name,
+                                )
+                            });
                         zip.start_file(&zip_path, options)
                             .map_err(|e| format!("Failed to start media file in ZIP: {}", e))?;
                         zip.write_all(&file_content)
@@ -225,6 +354,23 @@ pub async fn create_project_zip(
             debug_log("Media inclusion not requested");
         }
 
+        if !file_names.is_empty() {
+            zip.start_file(FILE_NAME_MAP, options)
+                .map_err(|e| format!("Failed to start {FILE_NAME_MAP} in ZIP: {e}"))?;
+            zip.write_all(file_names.to_json()?.as_bytes())
+                .map_err(|e| format!("Failed to write {FILE_NAME_MAP} to ZIP: {e}"))?;
+        }
+        let manifest = ExportManifest {
+            media_names: media_names.map(OriginalNamer::into_map).unwrap_or_default(),
+            pronunciation_lexicon: export_lexicon(&project_id)?,
+        };
+        if !manifest.is_empty() {
+            zip.start_file(EXPORT_MANIFEST, options)
+                .map_err(|e| format!("Failed to start {EXPORT_MANIFEST} in ZIP: {e}"))?;
+            zip.write_all(manifest.to_json()?.as_bytes())
+                .map_err(|e| format!("Failed to write {EXPORT_MANIFEST} to ZIP: {e}"))?;
+        }
+
         // Finish the ZIP - this is important to flush all data
         zip.finish()
             .map_err(|e| format!("Failed to finish ZIP: {}", e))?;
@@ -237,27 +383,32 @@ pub async fn create_project_zip(
     })
 }
 
-/// Creates a ZIP file with progress reporting
+/// Creates a ZIP file with progress reporting, checking `destination` as `create_project_zip` does
 #[tauri::command]
 pub async fn create_project_zip_with_progress(
     app: tauri::AppHandle,
     project_path: String,
     project_id: String,
     include_media: bool,
+    name_media_by_original: Option<bool>,
+    destination: Option<String>,
+    services: State<'_, AppServices>,
+    operations: State<'_, ProjectOperations>,
 ) -> Result<ZipExportResult, String> {
+    let _operation = begin_operation(&operations, &services, &project_id, OperationKind::Export)?;
     debug_log(&format!("Starting export with progress for project_id: {}, path: {}, include_media: {}",
                       project_id, project_path, include_media));
 
     // Phase 1: Preparing
     let _ = app.emit(
         "export-progress",
-        serde_json::json!({
-            "phase": "preparing",
-            "progress": 5,
-            "message": "Loading project file...",
-            "filesProcessed": 0,
-            "totalFiles": 0
-        }),
+        Message::new("progress.export.loading")
+            .progress_event(serde_json::json!({
+                "phase": "preparing",
+                "progress": 5,
+                "filesProcessed": 0,
+                "totalFiles": 0
+            })),
     );
 
     let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
@@ -270,24 +421,29 @@ pub async fn create_project_zip_with_progress(
 
     // Read the project file
     let project_path_obj = std::path::Path::new(&project_path);
+    // Names a ZIP can't hold as they are are renamed, and restored on import
+    let mut file_names = FileNameMap::default();
     let project_file_name = project_path_obj
         .file_name()
-        .and_then(|n| n.to_str())
+        .map(|name| file_names.entry_path("", name))
         .ok_or_else(|| "Invalid project file name".to_string())?;
 
-    let project_content = std::fs::read(&project_path)
-        .map_err(|e| format!("Failed to read project file: {}", e))?;
+    let space_dir = export_destination(destination);
+    ensure_export_space(&space_dir, project_path_obj, &project_id, include_media)?;
+
+    let project_content = crate::content_pages::read_project_for_export(project_path_obj)?;
+    let project_content = decrypt_for_export(project_path_obj, project_content)?;
 
     // Phase 2: Validating
     let _ = app.emit(
         "export-progress",
-        serde_json::json!({
-            "phase": "validating",
-            "progress": 15,
-            "message": "Validating project data...",
-            "filesProcessed": 0,
-            "totalFiles": 1
-        }),
+        Message::new("progress.export.validating")
+            .progress_event(with_free_space(serde_json::json!({
+                "phase": "validating",
+                "progress": 15,
+                "filesProcessed": 0,
+                "totalFiles": 1
+            }), &space_dir)),
     );
 
     // Validate project content
@@ -296,7 +452,7 @@ pub async fn create_project_zip_with_progress(
     }
 
     // Add project file to ZIP
-    zip.start_file(project_file_name, options)
+    zip.start_file(&project_file_name, options)
         .map_err(|e| format!("Failed to start project file in ZIP: {}", e))?;
     zip.write_all(&project_content)
         .map_err(|e| format!("Failed to write project file to ZIP: {}", e))?;
@@ -306,17 +462,20 @@ pub async fn create_project_zip_with_progress(
 
     debug_log(&format!("Added project file {} to ZIP ({} bytes)", project_file_name, project_content.len()));
 
+    // Set when media is named after its original file
+    let mut media_names: Option<OriginalNamer> = None;
+
     // Phase 3: Processing media files
     if include_media {
         let _ = app.emit(
             "export-progress",
-            serde_json::json!({
-                "phase": "processing",
-                "progress": 25,
-                "message": "Scanning media directory...",
-                "filesProcessed": 1,
-                "totalFiles": 1
-            }),
+            Message::new("progress.export.scanning")
+                .progress_event(with_free_space(serde_json::json!({
+                    "phase": "processing",
+                    "progress": 25,
+                    "filesProcessed": 1,
+                    "totalFiles": 1
+                }), &space_dir)),
         );
 
         let mut effective_project_id = project_id.clone();
@@ -361,7 +520,7 @@ pub async fn create_project_zip_with_progress(
                                 }
                                 if !media_files_list.is_empty() {
                                     effective_project_id = potential_id.to_string();
-                                    // Note: media_dir is not needed after this point
+                                    media_dir = fallback_media_dir;
                                 }
                             }
                         }
@@ -373,28 +532,41 @@ pub async fn create_project_zip_with_progress(
         let total_media_files = media_files_list.len();
         debug_log(&format!("Found {} media files to process", total_media_files));
 
+        if name_media_by_original.unwrap_or(false) {
+            media_names = Some(OriginalNamer::new(
+                &format!("{}/media", effective_project_id),
+                media_files_list.iter().filter_map(|path| path.file_name()),
+            ));
+        }
+
         let _ = app.emit(
             "export-progress",
-            serde_json::json!({
-                "phase": "processing",
-                "progress": 30,
-                "message": format!("Processing {} media files...", total_media_files),
-                "filesProcessed": 1,
-                "totalFiles": total_media_files + 1
-            }),
+            Message::new("progress.export.processing_media")
+                .with("count", total_media_files)
+                .progress_event(with_free_space(serde_json::json!({
+                    "phase": "processing",
+                    "progress": 30,
+                    "filesProcessed": 1,
+                    "totalFiles": total_media_files + 1
+                }), &space_dir)),
         );
 
         // Process media files with progress updates
         for (idx, media_file_path) in media_files_list.iter().enumerate() {
-            let file_name = media_file_path
+            let os_file_name = media_file_path
                 .file_name()
-                .and_then(|n| n.to_str())
                 .ok_or_else(|| "Invalid media file name".to_string())?;
+            let file_name = os_file_name.to_string_lossy();
 
-            let file_content = std::fs::read(&media_file_path)
+            let file_content = read_media_file(media_file_path)
                 .map_err(|e| format!("Failed to read media file {}: {}", file_name, e))?;
 
-            let zip_path = format!("{}/media/{}", effective_project_id, file_name);
+            let zip_path = media_names
+                .as_mut()
+                .and_then(|namer| namer.entry_path(&media_dir, os_file_name))
+                .unwrap_or_else(|| {
+                    file_names.entry_path(&format!("{}/media", effective_project_id), os_file_name)
+                });
             zip.start_file(&zip_path, options)
                 .map_err(|e| format!("Failed to start media file in ZIP: {}", e))?;
             zip.write_all(&file_content)
@@ -408,14 +580,16 @@ pub async fn create_project_zip_with_progress(
                 let progress = 30 + ((idx as f32 / total_media_files as f32) * 45.0) as u32; // 30-75% range
                 let _ = app.emit(
                     "export-progress",
-                    serde_json::json!({
-                        "phase": "processing",
-                        "progress": progress,
-                        "message": format!("Processing media files ({}/{})", idx + 1, total_media_files),
-                        "currentFile": file_name,
-                        "filesProcessed": idx + 2, // +1 for project file, +1 for current
-                        "totalFiles": total_media_files + 1
-                    }),
+                    Message::new("progress.export.processing_media_file")
+                        .with("current", idx + 1)
+                        .with("total", total_media_files)
+                        .progress_event(with_free_space(serde_json::json!({
+                            "phase": "processing",
+                            "progress": progress,
+                            "currentFile": file_name,
+                            "filesProcessed": idx + 2, // +1 for project file, +1 for current
+                            "totalFiles": total_media_files + 1
+                        }), &space_dir)),
                 );
             }
 
@@ -425,16 +599,33 @@ pub async fn create_project_zip_with_progress(
         debug_log(&format!("Total media files added: {}", total_media_files));
     }
 
+    if !file_names.is_empty() {
+        zip.start_file(FILE_NAME_MAP, options)
+            .map_err(|e| format!("Failed to start {FILE_NAME_MAP} in ZIP: {e}"))?;
+        zip.write_all(file_names.to_json()?.as_bytes())
+            .map_err(|e| format!("Failed to write {FILE_NAME_MAP} to ZIP: {e}"))?;
+    }
+    let manifest = ExportManifest {
+        media_names: media_names.map(OriginalNamer::into_map).unwrap_or_default(),
+        pronunciation_lexicon: export_lexicon(&project_id)?,
+    };
+    if !manifest.is_empty() {
+        zip.start_file(EXPORT_MANIFEST, options)
+            .map_err(|e| format!("Failed to start {EXPORT_MANIFEST} in ZIP: {e}"))?;
+        zip.write_all(manifest.to_json()?.as_bytes())
+            .map_err(|e| format!("Failed to write {EXPORT_MANIFEST} to ZIP: {e}"))?;
+    }
+
     // Phase 4: Creating archive
     let _ = app.emit(
         "export-progress",
-        serde_json::json!({
-            "phase": "creating",
-            "progress": 80,
-            "message": "Creating archive...",
-            "filesProcessed": file_count,
-            "totalFiles": file_count
-        }),
+        Message::new("progress.export.creating_archive")
+            .progress_event(with_free_space(serde_json::json!({
+                "phase": "creating",
+                "progress": 80,
+                "filesProcessed": file_count,
+                "totalFiles": file_count
+            }), &space_dir)),
     );
 
     // Finalize the ZIP
@@ -445,13 +636,13 @@ pub async fn create_project_zip_with_progress(
     // Phase 5: Completing
     let _ = app.emit(
         "export-progress",
-        serde_json::json!({
-            "phase": "completing",
-            "progress": 95,
-            "message": "Finalizing export...",
-            "filesProcessed": file_count,
-            "totalFiles": file_count
-        }),
+        Message::new("progress.export.finalizing")
+            .progress_event(with_free_space(serde_json::json!({
+                "phase": "completing",
+                "progress": 95,
+                "filesProcessed": file_count,
+                "totalFiles": file_count
+            }), &space_dir)),
     );
 
     let result = ZipExportResult {
@@ -463,13 +654,13 @@ pub async fn create_project_zip_with_progress(
     // Emit completion
     let _ = app.emit(
         "export-progress",
-        serde_json::json!({
-            "phase": "completing",
-            "progress": 100,
-            "message": "Export completed successfully!",
-            "filesProcessed": file_count,
-            "totalFiles": file_count
-        }),
+        Message::new("progress.export.done")
+            .progress_event(with_free_space(serde_json::json!({
+                "phase": "completing",
+                "progress": 100,
+                "filesProcessed": file_count,
+                "totalFiles": file_count
+            }), &space_dir)),
     );
 
     debug_log(&format!("Export completed successfully: {} files, {} bytes total", file_count, total_size));
@@ -477,138 +668,364 @@ pub async fn create_project_zip_with_progress(
     Ok(result)
 }
 
-/// Fixes media alignment issues when importing projects
-/// This prevents objectives media from being duplicated to the first topic
-fn fix_media_alignment_on_import(project_data: &mut ProjectFile) -> Result<(), String> {
-    // Check if we have course content to validate
-    if let Some(course_content) = &mut project_data.course_content {
-        if let Some(course_obj) = course_content.as_object_mut() {
-            let mut corrections_made = 0;
-
-            // First, get the expected learning objectives media IDs
-            let mut objectives_audio_id: Option<String> = None;
-            let mut objectives_caption_id: Option<String> = None;
-
-            if let Some(objectives) = course_obj.get("learningObjectivesPage").and_then(|v| v.as_object()) {
-                if let Some(media_array) = objectives.get("media").and_then(|v| v.as_array()) {
-                    for media in media_array {
-                        if let Some(media_obj) = media.as_object() {
-                            if let (Some(id), Some(media_type)) = (
-                                media_obj.get("id").and_then(|v| v.as_str()),
-                                media_obj.get("type").and_then(|v| v.as_str())
-                            ) {
-                                if media_type == "audio" {
-                                    objectives_audio_id = Some(id.to_string());
-                                } else if media_type == "caption" {
-                                    objectives_caption_id = Some(id.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+/// File the archive index is written to, next to the project ZIPs
+pub const ARCHIVE_INDEX_FILE: &str = "project-archive-index.csv";
 
-            // Check topics for duplicated objectives media
-            if let Some(topics) = course_obj.get_mut("topics").and_then(|v| v.as_array_mut()) {
-                for (topic_index, topic) in topics.iter_mut().enumerate() {
-                    if let Some(topic_obj) = topic.as_object_mut() {
-                        if let Some(media_array) = topic_obj.get_mut("media").and_then(|v| v.as_array_mut()) {
-                            // Remove any media that duplicates objectives media
-                            let mut items_to_remove = Vec::new();
-
-                            for (media_index, media) in media_array.iter().enumerate() {
-                                if let Some(media_obj) = media.as_object() {
-                                    if let Some(id) = media_obj.get("id").and_then(|v| v.as_str()) {
-                                        // Check if this topic media duplicates objectives media
-                                        if let Some(ref obj_audio_id) = objectives_audio_id {
-                                            if id == obj_audio_id {
-                                                println!("[IMPORT_FIX] Removing duplicated objectives audio {} from topic {}", id, topic_index);
-                                                items_to_remove.push(media_index);
-                                                corrections_made += 1;
-                                            }
-                                        }
-                                        if let Some(ref obj_caption_id) = objectives_caption_id {
-                                            if id == obj_caption_id {
-                                                println!("[IMPORT_FIX] Removing duplicated objectives caption {} from topic {}", id, topic_index);
-                                                items_to_remove.push(media_index);
-                                                corrections_made += 1;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
+/// One project's row in the archive index
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedProject {
+    pub project: String,
+    pub project_id: String,
+    /// ZIP written to the output directory, unset when the export failed
+    pub zip_file: Option<String>,
+    pub size: u64,
+    /// SHA-256 of the ZIP, hex encoded
+    pub checksum: Option<String>,
+    pub error: Option<String>,
+}
 
-                            // Remove duplicated items (in reverse order to maintain indices)
-                            for &index in items_to_remove.iter().rev() {
-                                media_array.remove(index);
-                            }
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectArchiveResult {
+    pub index_path: String,
+    pub exported: usize,
+    pub failed: usize,
+    pub projects: Vec<ArchivedProject>,
+}
 
-                            // After removing duplicates, add the correct media if missing
-                            let expected_audio_id = format!("audio-{}", topic_index + 2);  // topic-0 = audio-2
-                            let expected_caption_id = format!("caption-{}", topic_index + 2);  // topic-0 = caption-2
-
-                            // Check if expected audio exists
-                            let has_expected_audio = media_array.iter().any(|media| {
-                                media.as_object()
-                                    .and_then(|obj| obj.get("id"))
-                                    .and_then(|id| id.as_str())
-                                    .map(|id| id == expected_audio_id)
-                                    .unwrap_or(false)
-                            });
+/// Quote a CSV field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
 
-                            // Check if expected caption exists
-                            let has_expected_caption = media_array.iter().any(|media| {
-                                media.as_object()
-                                    .and_then(|obj| obj.get("id"))
-                                    .and_then(|id| id.as_str())
-                                    .map(|id| id == expected_caption_id)
-                                    .unwrap_or(false)
-                            });
+/// Export `project_path` to a ZIP in `output_dir`, returning the file name, size and
+/// checksum. Encrypted projects stay encrypted in their ZIP.
+async fn archive_project(
+    project_path: &Path,
+    project_id: &str,
+    output_dir: &Path,
+    include_media: bool,
+) -> Result<(String, u64, String), String> {
+    use sha2::{Digest, Sha256};
+
+    let result = build_project_zip(
+        project_path.to_string_lossy().to_string(),
+        project_id.to_string(),
+        include_media,
+        None,
+        Some(output_dir.to_string_lossy().to_string()),
+        true,
+    )
+    .await?;
+    let stem = project_path
+        .file_stem()
+        .map_or_else(|| project_id.to_string(), |s| s.to_string_lossy().into_owned());
+    let zip_file = format!("{stem}.zip");
+    fs::write(output_dir.join(&zip_file), &result.zip_data)
+        .map_err(|e| format!("Failed to write {zip_file}: {e}"))?;
+    let checksum = Sha256::digest(&result.zip_data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Ok((zip_file, result.zip_data.len() as u64, checksum))
+}
 
-                            // Add missing audio if not present
-                            if !has_expected_audio {
-                                let audio_media = serde_json::json!({
-                                    "id": expected_audio_id,
-                                    "type": "audio",
-                                    "storageId": expected_audio_id,
-                                    "title": "",
-                                    "url": ""
-                                });
-                                media_array.push(audio_media);
-                                println!("[IMPORT_FIX] Added missing audio {} to topic {}", expected_audio_id, topic_index);
-                                corrections_made += 1;
-                            }
+/// Export every project in `project_files` to its own ZIP in `output_dir` and
+/// write an index of them. A project that fails to export, or is held by
+/// another operation, is recorded in the index with its error and the rest
+/// carry on.
+pub async fn archive_projects(
+    project_files: &[std::path::PathBuf],
+    output_dir: &Path,
+    include_media: bool,
+    operations: &ProjectOperations,
+    services: &AppServices,
+    mut on_progress: impl FnMut(usize, usize, &str),
+) -> Result<ProjectArchiveResult, String> {
+    fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create archive directory: {e}"))?;
+    let needed = project_files
+        .iter()
+        .map(|path| {
+            let project_id = crate::backup_recovery::split_project_file_name(path).1;
+            export_size(path, project_id.as_deref().unwrap_or_default(), include_media)
+        })
+        .sum();
+    ensure_space(output_dir, needed).map_err(String::from)?;
+
+    let mut projects = Vec::with_capacity(project_files.len());
+    for (index, project_path) in project_files.iter().enumerate() {
+        let stem = project_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let (project, project_id) = match crate::project_storage::load_project_file(project_path) {
+            Ok(file) => (file.project.name, file.project.id),
+            Err(_) => (stem.clone(), stem),
+        };
+        on_progress(index + 1, project_files.len(), &project);
 
-                            // Add missing caption if not present
-                            if !has_expected_caption {
-                                let caption_media = serde_json::json!({
-                                    "id": expected_caption_id,
-                                    "type": "caption",
-                                    "storageId": expected_caption_id,
-                                    "title": "",
-                                    "url": ""
-                                });
-                                media_array.push(caption_media);
-                                println!("[IMPORT_FIX] Added missing caption {} to topic {}", expected_caption_id, topic_index);
-                                corrections_made += 1;
-                            }
-                        }
-                    }
+        let archived =
+            match begin_operation(operations, services, &project_id, OperationKind::Export) {
+                Ok(_operation) => {
+                    archive_project(project_path, &project_id, output_dir, include_media).await
+                }
+                Err(running) => Err(running.to_string()),
+            };
+        let entry = match archived {
+            Ok((zip_file, size, checksum)) => ArchivedProject {
+                project,
+                project_id,
+                zip_file: Some(zip_file),
+                size,
+                checksum: Some(checksum),
+                error: None,
+            },
+            Err(error) => {
+                debug_log(&format!("Archiving {} failed: {error}", project_path.display()));
+                ArchivedProject {
+                    project,
+                    project_id,
+                    zip_file: None,
+                    size: 0,
+                    checksum: None,
+                    error: Some(error),
                 }
             }
+        };
+        projects.push(entry);
+    }
 
-            if corrections_made > 0 {
-                println!("[IMPORT_FIX] Fixed {} media alignment issues during import", corrections_made);
+    let mut index = String::from("project,project_id,file,size,sha256,error\n");
+    for entry in &projects {
+        let row = [
+            csv_field(&entry.project),
+            csv_field(&entry.project_id),
+            csv_field(entry.zip_file.as_deref().unwrap_or_default()),
+            entry.size.to_string(),
+            entry.checksum.clone().unwrap_or_default(),
+            csv_field(entry.error.as_deref().unwrap_or_default()),
+        ];
+        index.push_str(&row.join(","));
+        index.push('\n');
+    }
+    let index_path = output_dir.join(ARCHIVE_INDEX_FILE);
+    fs::write(&index_path, index).map_err(|e| format!("Failed to write archive index: {e}"))?;
+
+    let failed = projects.iter().filter(|entry| entry.error.is_some()).count();
+    Ok(ProjectArchiveResult {
+        index_path: index_path.to_string_lossy().to_string(),
+        exported: projects.len() - failed,
+        failed,
+        projects,
+    })
+}
+
+/// Export every project in the projects directory to `output_dir` for archival;
+/// projects busy with another operation are listed as failed
+#[tauri::command]
+pub async fn export_all_projects(
+    app: tauri::AppHandle,
+    output_dir: String,
+    include_media: bool,
+    services: State<'_, AppServices>,
+    operations: State<'_, ProjectOperations>,
+) -> Result<ProjectArchiveResult, String> {
+    let project_files = crate::project_storage::list_project_files()?;
+    let result = archive_projects(
+        &project_files,
+        Path::new(&output_dir),
+        include_media,
+        &operations,
+        &services,
+        |current, total, project| {
+            let _ = app.emit(
+                "archive-progress",
+                Message::new("progress.archive.project")
+                    .with("name", project)
+                    .with("current", current)
+                    .with("total", total)
+                    .progress_event(with_free_space(
+                        serde_json::json!({
+                            "progress": (current - 1) * 100 / total,
+                            "current": current,
+                            "total": total
+                        }),
+                        Path::new(&output_dir),
+                    )),
+            );
+        },
+    )
+    .await?;
+
+    let _ = app.emit(
+        "archive-progress",
+        Message::new("progress.archive.done")
+            .with("exported", result.exported)
+            .with("total", result.projects.len())
+            .progress_event(serde_json::json!({
+                "progress": 100,
+                "failed": result.failed
+            })),
+    );
+    Ok(result)
+}
+
+/// Where an import has got to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ImportPhase {
+    /// Unpacking the ZIP entries to a temporary folder
+    Extracting,
+    /// Reading the project and checking its media against the policies
+    Validating,
+    /// Copying media into the new project's folder
+    CopyingMedia,
+    /// Writing the project file
+    Completing,
+}
+
+/// Progress of an import, reported after each entry and media file
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ImportProgress {
+    pub phase: ImportPhase,
+    pub entries_processed: usize,
+    pub total_entries: usize,
+    #[ts(type = "number")]
+    pub bytes_written: u64,
+    pub media_copied: usize,
+    pub total_media: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub current_file: Option<String>,
+}
+
+impl ImportProgress {
+    /// Overall percentage: extraction takes 0-60, media copying 70-95
+    fn percent(&self) -> u32 {
+        let share = |done: usize, total: usize, span: f32| {
+            if total == 0 {
+                span as u32
+            } else {
+                (done as f32 / total as f32 * span) as u32
             }
+        };
+        match self.phase {
+            ImportPhase::Extracting => share(self.entries_processed, self.total_entries, 60.0),
+            ImportPhase::Validating => 65,
+            ImportPhase::CopyingMedia => 70 + share(self.media_copied, self.total_media, 25.0),
+            ImportPhase::Completing => 98,
         }
     }
 
-    Ok(())
+    fn message(&self) -> Message {
+        match self.phase {
+            ImportPhase::Extracting => Message::new("progress.import.extracting")
+                .with("current", self.entries_processed)
+                .with("total", self.total_entries),
+            ImportPhase::Validating => Message::new("progress.import.validating"),
+            ImportPhase::CopyingMedia => Message::new("progress.import.copying_media")
+                .with("current", self.media_copied)
+                .with("total", self.total_media),
+            ImportPhase::Completing => Message::new("progress.import.completing"),
+        }
+    }
 }
 
-/// Extracts a project and its media from a ZIP file and saves to the projects directory
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ImportResult {
+    pub project_path: String,
+    pub project_id: String,
+    pub project_name: String,
+    /// Media moved to its page on import or, with auto-fixing off, that would
+    /// have been
+    pub media_corrections: Vec<MediaCorrection>,
+    /// Whether `media_corrections` were applied
+    pub media_fixed: bool,
+}
+
+/// Extracts a project and its media from a ZIP file and saves to the projects
+/// directory. Media on the wrong page is fixed unless `auto_fix` is false.
 #[tauri::command]
-pub async fn extract_project_zip(zip_data: Vec<u8>) -> Result<serde_json::Value, String> {
+pub async fn extract_project_zip(
+    zip_data: Vec<u8>,
+    auto_fix: Option<bool>,
+    services: State<'_, AppServices>,
+) -> Result<ImportResult, String> {
+    extract_project_zip_with_services(zip_data, &services, auto_fix.unwrap_or(true)).await
+}
+
+/// `extract_project_zip` emitting `import-progress` events as entries are
+/// extracted and media is copied. The first event carries the `operationId`
+/// that `cancel_project_operation` stops the import with; a cancelled import
+/// leaves nothing behind.
+#[tauri::command]
+pub async fn extract_project_zip_with_progress(
+    app: tauri::AppHandle,
+    zip_data: Vec<u8>,
+    auto_fix: Option<bool>,
+    services: State<'_, AppServices>,
+    operations: State<'_, ProjectOperations>,
+) -> Result<ImportResult, String> {
+    let new_project_id = services.new_project_id();
+    let operation = begin_operation(&operations, &services, &new_project_id, OperationKind::Import)?;
+    let projects_dir = crate::project_storage::get_projects_directory()
+        .map_err(|e| format!("Failed to get projects directory: {}", e))?;
+    let auto_fix = auto_fix.unwrap_or(true);
+
+    tokio::task::spawn_blocking(move || {
+        let operation_id = operation.operation_id().to_string();
+        let space_dir = projects_dir.clone();
+        let mut report = |progress: &ImportProgress| -> Result<(), String> {
+            if operation.is_cancelled() {
+                return Err(Message::new("error.import.cancelled").into());
+            }
+            let mut fields = serde_json::to_value(progress).unwrap_or_default();
+            fields["progress"] = progress.percent().into();
+            fields["operationId"] = operation_id.clone().into();
+            let _ = app.emit(
+                "import-progress",
+                progress.message().progress_event(with_free_space(fields, &space_dir)),
+            );
+            Ok(())
+        };
+        import_project_zip(zip_data, &projects_dir, new_project_id, auto_fix, &mut report)
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {e}"))?
+}
+
+/// Same as `extract_project_zip`, with the ID generator passed in explicitly
+pub async fn extract_project_zip_with_services(
+    zip_data: Vec<u8>,
+    services: &AppServices,
+    auto_fix: bool,
+) -> Result<ImportResult, String> {
+    let projects_dir = crate::project_storage::get_projects_directory()
+        .map_err(|e| format!("Failed to get projects directory: {}", e))?;
+    import_project_zip(zip_data, &projects_dir, services.new_project_id(), auto_fix, &mut |_| Ok(()))
+}
+
+/// Import a project ZIP into `projects_dir` as `new_project_id`, calling
+/// `report` after each step. An error from `report` stops the import; media
+/// already copied is removed and the project file, written last, never is.
+fn import_project_zip(
+    zip_data: Vec<u8>,
+    projects_dir: &Path,
+    new_project_id: String,
+    auto_fix: bool,
+    report: &mut dyn FnMut(&ImportProgress) -> Result<(), String>,
+) -> Result<ImportResult, String> {
     // Create a temp directory for extraction
     let temp_dir = TempDir::new()
         .map_err(|e| format!("Failed to create temp directory: {}", e))?;
@@ -620,6 +1037,18 @@ pub async fn extract_project_zip(zip_data: Vec<u8>) -> Result<serde_json::Value,
 
     let mut project_file_path = None;
     let mut project_id_from_media = None;
+    let mut file_names = FileNameMap::default();
+    let mut manifest = ExportManifest::default();
+    let mut progress = ImportProgress {
+        phase: ImportPhase::Extracting,
+        entries_processed: 0,
+        total_entries: archive.len(),
+        bytes_written: 0,
+        media_copied: 0,
+        total_media: 0,
+        current_file: None,
+    };
+    report(&progress)?;
     
     // Extract all files
     for i in 0..archive.len() {
@@ -628,11 +1057,31 @@ pub async fn extract_project_zip(zip_data: Vec<u8>) -> Result<serde_json::Value,
             .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
         
         let file_name = file.name().to_string();
+        progress.entries_processed = i + 1;
         
         // Skip directories
         if file_name.ends_with('/') {
             continue;
         }
+
+        // Original spellings of renamed entries, applied when media is copied
+        if file_name == FILE_NAME_MAP {
+            let mut json = String::new();
+            file.read_to_string(&mut json)
+                .map_err(|e| format!("Failed to read {FILE_NAME_MAP}: {e}"))?;
+            file_names = FileNameMap::from_json(&json)?;
+            continue;
+        }
+
+        // Media named after its original file, stored under its id again on
+        // copy, and the project's pronunciation lexicon
+        if file_name == EXPORT_MANIFEST {
+            let mut json = String::new();
+            file.read_to_string(&mut json)
+                .map_err(|e| format!("Failed to read {EXPORT_MANIFEST}: {e}"))?;
+            manifest = ExportManifest::from_json(&json)?;
+            continue;
+        }
         
         // Determine output path
         let output_path = temp_dir.path().join(&file_name);
@@ -651,6 +1100,9 @@ pub async fn extract_project_zip(zip_data: Vec<u8>) -> Result<serde_json::Value,
             .map_err(|e| format!("Failed to read from ZIP: {}", e))?;
         output_file.write_all(&content)
             .map_err(|e| format!("Failed to write file: {}", e))?;
+        progress.bytes_written += content.len() as u64;
+        progress.current_file = Some(file_name.clone());
+        report(&progress)?;
         
         // Track the project file
         if file_name.ends_with(".scormproj") {
@@ -668,13 +1120,9 @@ pub async fn extract_project_zip(zip_data: Vec<u8>) -> Result<serde_json::Value,
     // Find the project file
     let project_file = project_file_path
         .ok_or_else(|| "No .scormproj file found in ZIP".to_string())?;
-    
-    // Generate new project ID (timestamp)
-    let new_project_id = chrono::Utc::now().timestamp_millis().to_string();
-    
-    // Get projects directory
-    let projects_dir = crate::project_storage::get_projects_directory()
-        .map_err(|e| format!("Failed to get projects directory: {}", e))?;
+    progress.phase = ImportPhase::Validating;
+    progress.current_file = None;
+    report(&progress)?;
     
     // Read and parse the project file to get the project name
     let project_content = fs::read_to_string(&project_file)
@@ -683,7 +1131,73 @@ pub async fn extract_project_zip(zip_data: Vec<u8>) -> Result<serde_json::Value,
         .map_err(|e| format!("Failed to parse project file: {}", e))?;
 
     // Validate and fix media alignment issues during import
-    fix_media_alignment_on_import(&mut project_data)?;
+    let extracted_media_dir = project_id_from_media
+        .as_ref()
+        .map(|old_id| temp_dir.path().join(old_id).join("media"));
+    let media_corrections = match project_data.course_content.as_mut() {
+        Some(course_value) if auto_fix => {
+            fix_media_alignment(course_value, extracted_media_dir.as_deref())?
+        }
+        Some(course_value) => check_media_alignment(course_value, extracted_media_dir.as_deref())?,
+        None => Vec::new(),
+    };
+    if !media_corrections.is_empty() {
+        println!(
+            "[IMPORT_FIX] {} {} media alignment issues during import",
+            if auto_fix { "Fixed" } else { "Found" },
+            media_corrections.len()
+        );
+    }
+
+    // Refuse the whole import rather than bring in media the policy doesn't allow
+    if let Some(media_dir) = &extracted_media_dir {
+        crate::media_policy::check_media_dir(&crate::media_policy::current_policy(), media_dir)?;
+    }
+    // Metadata breaking the metadata policy is cleaned (or refused) before anything is copied
+    let metadata_audit = match &extracted_media_dir {
+        Some(media_dir) => crate::metadata_policy::apply_to_media_dir(
+            &crate::metadata_policy::current_policy(),
+            media_dir,
+            "import",
+        )?,
+        None => Vec::new(),
+    };
+    
+    // Copy media files if they exist, undoing the copy when it fails or is cancelled
+    if let Some(old_id) = project_id_from_media {
+        let old_media_dir = temp_dir.path().join(&old_id).join("media");
+        if old_media_dir.exists() {
+            let new_media_dir = projects_dir.join(&new_project_id).join("media");
+            let copied = copy_imported_media(
+                &old_id,
+                &old_media_dir,
+                &new_media_dir,
+                &file_names,
+                &manifest.media_names,
+                &mut progress,
+                report,
+            );
+            if let Err(e) = copied {
+                let _ = fs::remove_dir_all(projects_dir.join(&new_project_id));
+                return Err(e);
+            }
+        }
+    }
+
+    if !manifest.pronunciation_lexicon.is_empty() {
+        let project_dir = projects_dir.join(&new_project_id);
+        if let Err(e) = import_lexicon(&project_dir, manifest.pronunciation_lexicon) {
+            let _ = fs::remove_dir_all(&project_dir);
+            return Err(e);
+        }
+    }
+
+    progress.phase = ImportPhase::Completing;
+    progress.current_file = None;
+    if let Err(e) = report(&progress) {
+        let _ = fs::remove_dir_all(projects_dir.join(&new_project_id));
+        return Err(e);
+    }
     
     // Create new project filename
     let project_name = project_data.project.name.replace(" ", "_");
@@ -695,54 +1209,92 @@ pub async fn extract_project_zip(zip_data: Vec<u8>) -> Result<serde_json::Value,
         .map_err(|e| format!("Failed to serialize corrected project data: {}", e))?;
     fs::write(&new_project_path, corrected_project_json)
         .map_err(|e| format!("Failed to write corrected project file: {}", e))?;
-    
-    // Copy media files if they exist
-    if let Some(old_id) = project_id_from_media {
-        let old_media_dir = temp_dir.path().join(&old_id).join("media");
-        if old_media_dir.exists() {
-            let new_media_dir = projects_dir.join(&new_project_id).join("media");
-            fs::create_dir_all(&new_media_dir)
-                .map_err(|e| format!("Failed to create media directory: {}", e))?;
-            
-            // Copy all media files with deduplication
-            let entries = fs::read_dir(&old_media_dir)
-                .map_err(|e| format!("Failed to read media directory: {}", e))?;
-
-            let mut skipped_duplicates = Vec::new();
-
-            for entry in entries {
-                let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-                let file_name_os = entry.file_name();
-                let file_name = file_name_os.to_string_lossy();
-                let src = entry.path();
-                let dst = new_media_dir.join(&file_name_os);
-
-                // Check if this is a duplicate file (has -1, -2, etc. suffix)
-                if is_duplicate_media_file(&file_name) {
-                    // Skip duplicates during import to prevent confusion
-                    println!("[IMPORT_DEDUP] Skipping duplicate media file: {}", file_name);
-                    skipped_duplicates.push(file_name.to_string());
-                    continue;
-                }
 
-                fs::copy(&src, &dst)
-                    .map_err(|e| format!("Failed to copy media file: {}", e))?;
-            }
+    if let Err(e) =
+        crate::metadata_policy::append_audit(&projects_dir.join(&new_project_id), metadata_audit)
+    {
+        println!("[IMPORT] Failed to record metadata audit: {}", e);
+    }
 
-            if !skipped_duplicates.is_empty() {
-                println!("[IMPORT_DEDUP] Skipped {} duplicate media files: {:?}",
-                        skipped_duplicates.len(), skipped_duplicates);
-            }
+    Ok(ImportResult {
+        project_path: new_project_path.to_string_lossy().to_string(),
+        project_id: new_project_id,
+        project_name,
+        media_corrections,
+        media_fixed: auto_fix,
+    })
+}
 
-            // Import completed successfully
+/// Copy the extracted media of `old_id` into `new_media_dir` under its stored
+/// names, skipping numbered duplicates and reporting each file copied
+fn copy_imported_media(
+    old_id: &str,
+    old_media_dir: &Path,
+    new_media_dir: &Path,
+    file_names: &FileNameMap,
+    media_names: &MediaNameMap,
+    progress: &mut ImportProgress,
+    report: &mut dyn FnMut(&ImportProgress) -> Result<(), String>,
+) -> Result<(), String> {
+    fs::create_dir_all(new_media_dir)
+        .map_err(|e| format!("Failed to create media directory: {}", e))?;
+
+    // Copy all media files with deduplication
+    let entries = fs::read_dir(old_media_dir)
+        .map_err(|e| format!("Failed to read media directory: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read directory entry: {}", e))?;
+    progress.phase = ImportPhase::CopyingMedia;
+    progress.total_media = entries.len();
+    report(progress)?;
+
+    let mut skipped_duplicates = Vec::new();
+
+    for entry in entries {
+        let file_name_os = entry.file_name();
+        let file_name = file_name_os.to_string_lossy();
+        let src = entry.path();
+        let entry_path = format!("{}/media/{}", old_id, file_name);
+        let original_name = match media_names.storage_name(&entry_path) {
+            Some(storage_name) => storage_name.into(),
+            None => file_names
+                .original(&entry_path)
+                .unwrap_or_else(|| file_name_os.clone()),
+        };
+        // The name comes from the archive, so it must not lead out of the media directory
+        let mut components = Path::new(&original_name).components();
+        if !matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        ) {
+            return Err(format!(
+                "Invalid media file name in archive: {}",
+                original_name.to_string_lossy()
+            ));
+        }
+        let dst = new_media_dir.join(&original_name);
+        progress.media_copied += 1;
+        progress.current_file = Some(file_name.to_string());
+
+        // Check if this is a duplicate file (has -1, -2, etc. suffix)
+        if is_duplicate_media_file(&original_name.to_string_lossy()) {
+            // Skip duplicates during import to prevent confusion
+            println!("[IMPORT_DEDUP] Skipping duplicate media file: {}", file_name);
+            skipped_duplicates.push(file_name.to_string());
+            report(progress)?;
+            continue;
         }
+
+        progress.bytes_written += fs::copy(&src, &dst)
+            .map_err(|e| format!("Failed to copy media file: {}", e))?;
+        report(progress)?;
     }
 
-    Ok(serde_json::json!({
-        "projectPath": new_project_path.to_string_lossy(),
-        "projectId": new_project_id,
-        "projectName": project_name
-    }))
+    if !skipped_duplicates.is_empty() {
+        println!("[IMPORT_DEDUP] Skipped {} duplicate media files: {:?}",
+                skipped_duplicates.len(), skipped_duplicates);
+    }
+    Ok(())
 }
 
 /// Saves a project with its media files
@@ -824,6 +1376,12 @@ mod tests {
                 created: chrono::Utc::now(),
                 last_modified: chrono::Utc::now(),
                 path: None,
+                content_layout: None,
+                encryption: None,
+                is_writable: None,
+                tags: Vec::new(),
+                status: None,
+                local_times: None,
             },
             course_data: crate::project_storage::CourseData {
                 title: "Test Course".to_string(),
@@ -851,6 +1409,8 @@ mod tests {
                 version: "1.2".to_string(),
                 completion_criteria: "pages_viewed".to_string(),
                 passing_score: 80,
+                media_transforms: None,
+                zip_compression_level: None,
             },
             course_seed_data: None,
             json_import_data: None,
@@ -863,10 +1423,14 @@ mod tests {
         save_project_file(&project, project_path.as_path()).unwrap();
         
         // Create ZIP without media
-        let result = create_project_zip(
+        let result = create_project_zip_with_services(
             project_path.to_str().unwrap().to_string(),
             "test123".to_string(),
             false,
+            None,
+            None,
+            &ProjectOperations::default(),
+            &AppServices::default(),
         )
         .await;
         
@@ -889,6 +1453,12 @@ mod tests {
                 created: chrono::Utc::now(),
                 last_modified: chrono::Utc::now(),
                 path: None,
+                content_layout: None,
+                encryption: None,
+                is_writable: None,
+                tags: Vec::new(),
+                status: None,
+                local_times: None,
             },
             course_data: crate::project_storage::CourseData {
                 title: "Test Course".to_string(),
@@ -916,6 +1486,8 @@ mod tests {
                 version: "1.2".to_string(),
                 completion_criteria: "pages_viewed".to_string(),
                 passing_score: 80,
+                media_transforms: None,
+                zip_compression_level: None,
             },
             course_seed_data: None,
             json_import_data: None,
@@ -927,24 +1499,143 @@ mod tests {
         
         save_project_file(&project, project_path.as_path()).unwrap();
         
-        let zip_result = create_project_zip(
+        let zip_result = create_project_zip_with_services(
             project_path.to_str().unwrap().to_string(),
             "test123".to_string(),
             false,
+            None,
+            None,
+            &ProjectOperations::default(),
+            &AppServices::default(),
         )
         .await
         .unwrap();
         
         // Now extract it
-        let extracted = extract_project_zip(zip_result.zip_data).await;
+        let (_clock, services) = crate::app_services::testing::deterministic_services();
+        let extracted = extract_project_zip_with_services(zip_result.zip_data, &services, true).await;
         
         assert!(extracted.is_ok());
         let extracted_project = extracted.unwrap();
+        assert_eq!(extracted_project.project_id, "1700000000000");
+        assert!(extracted_project
+            .project_path
+            .ends_with("Test_Project_1700000000000.scormproj"));
         // TODO: Fix test structure
         // assert_eq!(extracted_project.project_data.project.name, "Test Project");
         // assert_eq!(extracted_project.media_files.len(), 0);
     }
 
+    fn import_zip() -> Vec<u8> {
+        import_zip_with(&[])
+    }
+
+    fn import_zip_with(extra: &[(&str, &str)]) -> Vec<u8> {
+        let project = serde_json::json!({
+            "project": {
+                "id": "1700000000001",
+                "name": "Safety",
+                "created": "2024-01-01T00:00:00Z",
+                "last_modified": "2024-01-01T00:00:00Z"
+            },
+            "course_data": { "title": "Safety", "difficulty": 1, "template": "standard", "topics": [] },
+            "media": { "images": [], "videos": [], "audio": [], "captions": [] },
+            "audio_settings": { "voice": "default", "speed": 1.0, "pitch": 1.0 },
+            "scorm_config": { "version": "1.2", "completion_criteria": "pages_viewed", "passing_score": 80 }
+        });
+        let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+        zip.start_file("Safety_1700000000001.scormproj", options).unwrap();
+        zip.write_all(&serde_json::to_vec(&project).unwrap()).unwrap();
+        for id in ["image-0", "image-1"] {
+            zip.start_file(format!("1700000000001/media/{id}.bin"), options).unwrap();
+            zip.write_all(b"\x89PNG\r\n\x1a\n").unwrap();
+        }
+        for (name, contents) in extra {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_import_reports_each_entry_and_media_file() {
+        let projects_dir = TempDir::new().unwrap();
+        let mut events = Vec::new();
+        let result = import_project_zip(
+            import_zip(),
+            projects_dir.path(),
+            "1700000000005".to_string(),
+            true,
+            &mut |progress| {
+                events.push(progress.clone());
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert!(Path::new(&result.project_path).exists());
+        let phases: Vec<ImportPhase> = events.iter().map(|event| event.phase).collect();
+        assert_eq!(phases.iter().filter(|phase| **phase == ImportPhase::Extracting).count(), 4);
+        assert_eq!(phases.last(), Some(&ImportPhase::Completing));
+        let copied = events.iter().filter(|event| event.phase == ImportPhase::CopyingMedia).last().unwrap();
+        assert_eq!((copied.media_copied, copied.total_media), (2, 2));
+        assert!(copied.bytes_written > 16);
+        assert!(events.windows(2).all(|pair| pair[0].percent() <= pair[1].percent()));
+    }
+
+    #[test]
+    fn test_cancelled_import_leaves_nothing_behind() {
+        let projects_dir = TempDir::new().unwrap();
+        let result = import_project_zip(
+            import_zip(),
+            projects_dir.path(),
+            "1700000000006".to_string(),
+            true,
+            &mut |progress| match progress.media_copied {
+                1 => Err("cancelled".to_string()),
+                _ => Ok(()),
+            },
+        );
+
+        assert_eq!(result.unwrap_err(), "cancelled");
+        assert_eq!(fs::read_dir(projects_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_import_refuses_media_names_outside_the_media_directory() {
+        let projects_dir = TempDir::new().unwrap();
+        let file_names = r#"{"1700000000001/media/image-0.bin":{"encoding":"utf8","name":"../escape.bin"}}"#;
+        let result = import_project_zip(
+            import_zip_with(&[(FILE_NAME_MAP, file_names)]),
+            projects_dir.path(),
+            "1700000000007".to_string(),
+            true,
+            &mut |_| Ok(()),
+        );
+
+        assert!(result.unwrap_err().starts_with("Invalid media file name in archive"));
+        assert_eq!(fs::read_dir(projects_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_import_restores_the_pronunciation_lexicon() {
+        let projects_dir = TempDir::new().unwrap();
+        let manifest = r#"{"pronunciationLexicon":[{"term":" SQL ","alias":"sequel"}]}"#;
+        import_project_zip(
+            import_zip_with(&[(EXPORT_MANIFEST, manifest)]),
+            projects_dir.path(),
+            "1700000000008".to_string(),
+            true,
+            &mut |_| Ok(()),
+        )
+        .unwrap();
+
+        let lexicon = load_lexicon(&projects_dir.path().join("1700000000008")).unwrap();
+        assert_eq!(lexicon.len(), 1);
+        assert_eq!((lexicon[0].term.as_str(), lexicon[0].alias.as_deref()), ("SQL", Some("sequel")));
+    }
+
     #[tokio::test]
     async fn test_save_project_with_media() {
         let temp_dir = TempDir::new().unwrap();
@@ -957,6 +1648,12 @@ mod tests {
                 created: chrono::Utc::now(),
                 last_modified: chrono::Utc::now(),
                 path: None,
+                content_layout: None,
+                encryption: None,
+                is_writable: None,
+                tags: Vec::new(),
+                status: None,
+                local_times: None,
             },
             course_data: crate::project_storage::CourseData {
                 title: "Imported Course".to_string(),
@@ -984,6 +1681,8 @@ mod tests {
                 version: "1.2".to_string(),
                 completion_criteria: "pages_viewed".to_string(),
                 passing_score: 80,
+                media_transforms: None,
+                zip_compression_level: None,
             },
             course_seed_data: None,
             json_import_data: None,
@@ -1007,6 +1706,8 @@ mod tests {
                     title: None,
                     clip_start: None,
                     clip_end: None,
+                    alt_text: None,
+                    attribution: None,
                 },
             },
         ];
@@ -1052,6 +1753,12 @@ mod tests {
                 created: chrono::Utc::now(),
                 last_modified: chrono::Utc::now(),
                 path: None,
+                content_layout: None,
+                encryption: None,
+                is_writable: None,
+                tags: Vec::new(),
+                status: None,
+                local_times: None,
             },
             course_data: crate::project_storage::CourseData {
                 title: "Test Course".to_string(),
@@ -1079,6 +1786,8 @@ mod tests {
                 version: "1.2".to_string(),
                 completion_criteria: "pages_viewed".to_string(),
                 passing_score: 80,
+                media_transforms: None,
+                zip_compression_level: None,
             },
             course_seed_data: None,
             json_import_data: None,
@@ -1091,10 +1800,14 @@ mod tests {
         let project_json = serde_json::to_string_pretty(&project).unwrap();
         std::fs::write(&project_path, project_json).unwrap();
 
-        let result = create_project_zip(
+        let result = create_project_zip_with_services(
             project_path.to_string_lossy().to_string(),
             "1756944132721".to_string(),
             false,
+            None,
+            None,
+            &ProjectOperations::default(),
+            &AppServices::default(),
         ).await;
 
         assert!(result.is_ok(), "Export should succeed");
@@ -1123,6 +1836,12 @@ mod tests {
                 created: chrono::Utc::now(),
                 last_modified: chrono::Utc::now(),
                 path: None,
+                content_layout: None,
+                encryption: None,
+                is_writable: None,
+                tags: Vec::new(),
+                status: None,
+                local_times: None,
             },
             course_data: crate::project_storage::CourseData {
                 title: "Test Course".to_string(),
@@ -1150,6 +1869,8 @@ mod tests {
                 version: "1.2".to_string(),
                 completion_criteria: "pages_viewed".to_string(),
                 passing_score: 80,
+                media_transforms: None,
+                zip_compression_level: None,
             },
             course_seed_data: None,
             json_import_data: None,
@@ -1162,10 +1883,14 @@ mod tests {
         let project_json = serde_json::to_string_pretty(&project).unwrap();
         std::fs::write(&project_path, project_json).unwrap();
 
-        let result = create_project_zip(
+        let result = create_project_zip_with_services(
             project_path.to_string_lossy().to_string(),
             "1756944132722".to_string(),
             false,
+            None,
+            None,
+            &ProjectOperations::default(),
+            &AppServices::default(),
         ).await;
 
         assert!(result.is_ok(), "Export should succeed");
@@ -1205,6 +1930,12 @@ mod tests {
                 created: chrono::Utc::now(),
                 last_modified: chrono::Utc::now(),
                 path: None,
+                content_layout: None,
+                encryption: None,
+                is_writable: None,
+                tags: Vec::new(),
+                status: None,
+                local_times: None,
             },
             course_data: crate::project_storage::CourseData {
                 title: "Round Trip Course".to_string(),
@@ -1238,6 +1969,8 @@ mod tests {
                 version: "2004".to_string(),
                 completion_criteria: "score_based".to_string(),
                 passing_score: 85,
+                media_transforms: None,
+                zip_compression_level: None,
             },
             course_seed_data: Some(serde_json::json!({
                 "seed": "test_seed_data"
@@ -1253,10 +1986,14 @@ mod tests {
         std::fs::write(&project_path, project_json).unwrap();
 
         // Export the project
-        let export_result = create_project_zip(
+        let export_result = create_project_zip_with_services(
             project_path.to_string_lossy().to_string(),
             "1756944132723".to_string(),
             false,
+            None,
+            None,
+            &ProjectOperations::default(),
+            &AppServices::default(),
         ).await;
 
         assert!(export_result.is_ok(), "Export should succeed");
@@ -1264,13 +2001,13 @@ mod tests {
         assert!(!zip_result.zip_data.is_empty(), "Exported ZIP should not be empty");
 
         // Try to import the project - this will fail if ZIP is empty
-        let import_result = extract_project_zip(zip_result.zip_data).await;
+        let import_result = extract_project_zip_with_services(zip_result.zip_data, &AppServices::default(), true).await;
         assert!(import_result.is_ok(), "Import should succeed");
 
         let import_data = import_result.unwrap();
-        assert!(import_data["projectPath"].is_string(), "Should return project path");
-        assert!(import_data["projectId"].is_string(), "Should return project ID");
-        assert!(import_data["projectName"].is_string(), "Should return project name");
+        assert!(!import_data.project_path.is_empty(), "Should return project path");
+        assert!(!import_data.project_id.is_empty(), "Should return project ID");
+        assert!(!import_data.project_name.is_empty(), "Should return project name");
     }
 
     #[tokio::test]
@@ -1281,10 +2018,14 @@ mod tests {
         if std::path::Path::new(real_project_path).exists() {
             println!("[TEST] Testing with real project file: {}", real_project_path);
 
-            let result = create_project_zip(
+            let result = create_project_zip_with_services(
                 real_project_path.to_string(),
                 "1756944197691".to_string(),
                 false, // Start without media to isolate the issue
+                None,
+                None,
+                &ProjectOperations::default(),
+                &AppServices::default(),
             ).await;
 
             assert!(result.is_ok(), "Export should succeed");
@@ -1322,10 +2063,14 @@ mod tests {
             println!("[TEST] Testing complete export/import cycle with Project 02");
 
             // Step 1: Export the project
-            let export_result = create_project_zip(
+            let export_result = create_project_zip_with_services(
                 real_project_path.to_string(),
                 "1756944132721".to_string(),
                 true, // Include media files
+                None,
+                None,
+                &ProjectOperations::default(),
+                &AppServices::default(),
             ).await;
 
             assert!(export_result.is_ok(), "Export should succeed");
@@ -1340,19 +2085,19 @@ mod tests {
             assert!(zip_result.file_count >= 1, "Should contain at least the project file");
 
             // Step 2: Try to import the ZIP
-            let import_result = extract_project_zip(zip_result.zip_data).await;
+            let import_result = extract_project_zip_with_services(zip_result.zip_data, &AppServices::default(), true).await;
             assert!(import_result.is_ok(), "Import should succeed, got: {:?}", import_result);
 
             let import_data = import_result.unwrap();
             println!("[TEST] Import successful - new project: {:?}", import_data);
 
             // Verify import returned valid data
-            assert!(import_data["projectPath"].is_string(), "Should return project path");
-            assert!(import_data["projectId"].is_string(), "Should return project ID");
-            assert!(import_data["projectName"].is_string(), "Should return project name");
+            assert!(!import_data.project_path.is_empty(), "Should return project path");
+            assert!(!import_data.project_id.is_empty(), "Should return project ID");
+            assert!(!import_data.project_name.is_empty(), "Should return project name");
 
             // Verify the imported project file exists
-            let imported_project_path = import_data["projectPath"].as_str().unwrap();
+            let imported_project_path = import_data.project_path.as_str();
             assert!(std::path::Path::new(imported_project_path).exists(),
                     "Imported project file should exist at: {}", imported_project_path);
 
@@ -1360,7 +2105,7 @@ mod tests {
 
             // Clean up the imported project to avoid cluttering
             let _ = std::fs::remove_file(imported_project_path);
-            let imported_id = import_data["projectId"].as_str().unwrap();
+            let imported_id = import_data.project_id.as_str();
             let projects_dir = std::path::Path::new(r"C:\Users\sierr\Documents\SCORM Projects");
             let media_dir = projects_dir.join(imported_id).join("media");
             if media_dir.exists() {
@@ -1371,6 +2116,125 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_archive_projects_isolates_failures_and_writes_index() {
+        use sha2::{Digest, Sha256};
+
+        let temp_dir = TempDir::new().unwrap();
+        let good = temp_dir.path().join("Safety, Basics_1700000000001.scormproj");
+        let broken = temp_dir.path().join("Broken_1700000000002.scormproj");
+        let project = serde_json::json!({
+            "project": {
+                "id": "1700000000001",
+                "name": "Safety, Basics",
+                "created": "2024-01-01T00:00:00Z",
+                "last_modified": "2024-01-01T00:00:00Z"
+            },
+            "course_data": { "title": "Safety", "difficulty": 1, "template": "standard", "topics": [] },
+            "media": { "images": [], "videos": [], "audio": [], "captions": [] },
+            "audio_settings": { "voice": "default", "speed": 1.0, "pitch": 1.0 },
+            "scorm_config": { "version": "1.2", "completion_criteria": "pages_viewed", "passing_score": 80 }
+        });
+        fs::write(&good, serde_json::to_vec(&project).unwrap()).unwrap();
+        fs::write(&broken, b"not a project").unwrap();
+
+        let output_dir = temp_dir.path().join("archive");
+        let mut progress = Vec::new();
+        let result = archive_projects(
+            &[good, broken],
+            &output_dir,
+            false,
+            &ProjectOperations::default(),
+            &AppServices::default(),
+            |current, total, name| progress.push(format!("{current}/{total} {name}")),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(progress, vec!["1/2 Safety, Basics", "2/2 Broken_1700000000002"]);
+        assert_eq!((result.exported, result.failed), (1, 1));
+        let zip_data = fs::read(output_dir.join("Safety, Basics_1700000000001.zip")).unwrap();
+        let checksum: String = Sha256::digest(&zip_data).iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(result.projects[0].checksum.as_deref(), Some(checksum.as_str()));
+        assert!(result.projects[1].error.is_some());
+
+        let index = fs::read_to_string(output_dir.join(ARCHIVE_INDEX_FILE)).unwrap();
+        let rows: Vec<&str> = index.lines().collect();
+        assert_eq!(rows[0], "project,project_id,file,size,sha256,error");
+        assert_eq!(
+            rows[1],
+            format!(
+                "\"Safety, Basics\",1700000000001,\"Safety, Basics_1700000000001.zip\",{},{checksum},",
+                zip_data.len()
+            )
+        );
+        assert!(rows[2].starts_with("Broken_1700000000002,Broken_1700000000002,,0,,"));
+    }
+
+    #[tokio::test]
+    async fn test_export_of_a_project_in_use_gets_the_running_operation_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_path = temp_dir.path().join("Busy_1700000000003.scormproj");
+        let project = serde_json::json!({
+            "project": {
+                "id": "1700000000003",
+                "name": "Busy",
+                "created": "2024-01-01T00:00:00Z",
+                "last_modified": "2024-01-01T00:00:00Z"
+            },
+            "course_data": { "title": "Busy", "difficulty": 1, "template": "standard", "topics": [] },
+            "media": { "images": [], "videos": [], "audio": [], "captions": [] },
+            "audio_settings": { "voice": "default", "speed": 1.0, "pitch": 1.0 },
+            "scorm_config": { "version": "1.2", "completion_criteria": "pages_viewed", "passing_score": 80 }
+        });
+        fs::write(&project_path, serde_json::to_vec(&project).unwrap()).unwrap();
+        let operations = ProjectOperations::default();
+        let services = AppServices::default();
+        let project_id = "1700000000003";
+        let running =
+            begin_operation(&operations, &services, project_id, OperationKind::Export).unwrap();
+
+        let error = create_project_zip_with_services(
+            project_path.to_string_lossy().to_string(),
+            project_id.to_string(),
+            false,
+            None,
+            None,
+            &operations,
+            &services,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.contains(running.operation_id()), "{error}");
+
+        let output_dir = temp_dir.path().join("archive");
+        let result = archive_projects(
+            &[project_path.clone()],
+            &output_dir,
+            false,
+            &operations,
+            &services,
+            |_, _, _| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!((result.exported, result.failed), (0, 1));
+        assert!(result.projects[0].error.as_deref().unwrap().contains(running.operation_id()));
+
+        drop(running);
+        assert!(create_project_zip_with_services(
+            project_path.to_string_lossy().to_string(),
+            project_id.to_string(),
+            false,
+            None,
+            None,
+            &operations,
+            &services,
+        )
+        .await
+        .is_ok());
+    }
+
     // Include media export tests
     include!("project_export_import_media_test.rs");
 
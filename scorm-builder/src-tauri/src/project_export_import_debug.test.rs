@@ -40,6 +40,8 @@ mod tests {
             project_path.to_string_lossy().to_string(),
             "test-project-123".to_string(),
             false, // No media for now
+            None,
+            None,
         ).await;
 
         println!("Export result: {:?}", result);
@@ -115,6 +117,8 @@ mod tests {
             project_path.to_string_lossy().to_string(),
             "test-project-media-456".to_string(),
             true, // Include media
+            None,
+            None,
         ).await;
 
         println!("Export with media result: {:?}", result);
@@ -163,6 +167,8 @@ mod tests {
             project_path.to_string_lossy().to_string(),
             "buffer-test-789".to_string(),
             false,
+            None,
+            None,
         ).await;
 
         assert!(result.is_ok(), "Buffer test export should succeed");
@@ -98,10 +98,14 @@ async fn test_export_with_mismatched_project_id() {
 
     // Test the export function
     println!("\n=== Starting Export Test ===");
-    let result = create_project_zip(
+    let result = create_project_zip_with_services(
         project_path.to_string_lossy().to_string(),
         file_project_id.to_string(), // Use the file-based ID (which has no media)
         true, // include_media = true
+        None,
+        None,
+        &ProjectOperations::default(),
+        &AppServices::default(),
     ).await;
 
     println!("Export result: {:?}", result.is_ok());
@@ -206,10 +210,14 @@ async fn test_export_with_matching_project_id() {
     }
 
     // Test the export function
-    let result = create_project_zip(
+    let result = create_project_zip_with_services(
         project_path.to_string_lossy().to_string(),
         project_id.to_string(),
         true,
+        None,
+        None,
+        &ProjectOperations::default(),
+        &AppServices::default(),
     ).await;
 
     assert!(result.is_ok(), "Export should succeed: {:?}", result);
@@ -0,0 +1,294 @@
+//! Tags and review status of projects.
+//!
+//! Teams managing dozens of courses organize the dashboard by tag and by
+//! where each course is in review. Both are kept for every project in one
+//! `projects-index.json` at the top of the projects directory rather than in
+//! the project files, so tagging a project never rewrites it and read-only
+//! projects can be tagged too. `list_projects` fills them into each
+//! project's metadata and filters on them.
+
+use crate::backup_recovery::is_project_id;
+use crate::project_storage::ProjectMetadata;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+/// Kept in the projects directory next to the project folders
+const PROJECT_INDEX_FILE: &str = "projects-index.json";
+/// Longest tag accepted, in characters
+pub const MAX_TAG_LENGTH: usize = 40;
+/// Most tags a project may have
+pub const MAX_TAGS: usize = 20;
+
+/// Serializes read-modify-write of the index between commands
+static INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Where a course is on its way to learners
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ProjectStatus {
+    #[default]
+    Draft,
+    InReview,
+    Published,
+}
+
+/// What the index records about one project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectTags {
+    pub tags: Vec<String>,
+    pub status: ProjectStatus,
+}
+
+/// Tags and status by project ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectIndex {
+    pub projects: BTreeMap<String, ProjectTags>,
+}
+
+/// Which projects `list_projects` returns; unset fields match every project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ProjectFilter {
+    /// Matched ignoring case
+    pub tag: Option<String>,
+    pub status: Option<ProjectStatus>,
+}
+
+impl ProjectIndex {
+    pub fn get(&self, project_id: &str) -> ProjectTags {
+        self.projects.get(project_id).cloned().unwrap_or_default()
+    }
+
+    /// Copy a project's tags and status into its listed metadata
+    pub fn annotate(&self, metadata: &mut ProjectMetadata) {
+        let entry = self.get(&metadata.id);
+        metadata.tags = entry.tags;
+        metadata.status = Some(entry.status);
+    }
+
+    /// Every tag in use, sorted, each spelled as it was first entered
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: BTreeMap<String, String> = BTreeMap::new();
+        for tag in self.projects.values().flat_map(|entry| &entry.tags) {
+            tags.entry(tag.to_ascii_lowercase())
+                .or_insert_with(|| tag.clone());
+        }
+        tags.into_values().collect()
+    }
+}
+
+impl ProjectFilter {
+    /// Whether annotated `metadata` passes the filter
+    pub fn matches(&self, metadata: &ProjectMetadata) -> bool {
+        let tag_matches = self.tag.as_deref().map(str::trim).is_none_or(|wanted| {
+            wanted.is_empty()
+                || metadata
+                    .tags
+                    .iter()
+                    .any(|tag| tag.eq_ignore_ascii_case(wanted))
+        });
+        let status_matches = self
+            .status
+            .is_none_or(|wanted| metadata.status.unwrap_or_default() == wanted);
+        tag_matches && status_matches
+    }
+}
+
+/// Trim tags, drop empty ones and repeats (ignoring case), keeping the order given
+pub fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() || normalized.iter().any(|seen| seen.eq_ignore_ascii_case(tag)) {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(format!(
+                "Tag '{tag}' is longer than {MAX_TAG_LENGTH} characters"
+            ));
+        }
+        normalized.push(tag.to_string());
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(format!("A project can have at most {MAX_TAGS} tags"));
+    }
+    Ok(normalized)
+}
+
+fn index_path(projects_dir: &Path) -> PathBuf {
+    projects_dir.join(PROJECT_INDEX_FILE)
+}
+
+/// The index of `projects_dir`. A missing file is an empty index.
+pub fn load_project_index(projects_dir: &Path) -> Result<ProjectIndex, String> {
+    let path = index_path(projects_dir);
+    if !path.exists() {
+        return Ok(ProjectIndex::default());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read projects index: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse projects index: {e}"))
+}
+
+fn save_project_index(projects_dir: &Path, index: &ProjectIndex) -> Result<(), String> {
+    fs::create_dir_all(projects_dir)
+        .map_err(|e| format!("Failed to create projects directory: {e}"))?;
+    let path = index_path(projects_dir);
+    let json = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize projects index: {e}"))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write projects index: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write projects index: {e}"))
+}
+
+/// Change one project's entry and save the index, returning the new entry
+pub fn update_project_tags(
+    projects_dir: &Path,
+    project_id: &str,
+    update: impl FnOnce(&mut ProjectTags),
+) -> Result<ProjectTags, String> {
+    if !is_project_id(project_id) {
+        return Err(format!("Invalid project ID: {project_id}"));
+    }
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = load_project_index(projects_dir)?;
+    let entry = index.projects.entry(project_id.to_string()).or_default();
+    update(entry);
+    let entry = entry.clone();
+    save_project_index(projects_dir, &index)?;
+    Ok(entry)
+}
+
+/// Drop a deleted project's entry; a project that had none is left alone
+pub fn forget_project(projects_dir: &Path, project_id: &str) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = load_project_index(projects_dir)?;
+    if index.projects.remove(project_id).is_some() {
+        save_project_index(projects_dir, &index)?;
+    }
+    Ok(())
+}
+
+/// Tags and status of a project; untagged projects are drafts without tags
+#[tauri::command]
+pub fn get_project_tags(#[allow(non_snake_case)] projectId: String) -> Result<ProjectTags, String> {
+    let projects_dir = crate::settings::get_projects_directory()?;
+    Ok(load_project_index(&projects_dir)?.get(&projectId))
+}
+
+/// Replace a project's tags
+#[tauri::command]
+pub fn set_project_tags(
+    #[allow(non_snake_case)] projectId: String,
+    tags: Vec<String>,
+) -> Result<ProjectTags, String> {
+    let tags = normalize_tags(tags)?;
+    let projects_dir = crate::settings::get_projects_directory()?;
+    update_project_tags(&projects_dir, &projectId, |entry| entry.tags = tags)
+}
+
+/// Move a project to draft, in review or published
+#[tauri::command]
+pub fn set_project_status(
+    #[allow(non_snake_case)] projectId: String,
+    status: ProjectStatus,
+) -> Result<ProjectTags, String> {
+    let projects_dir = crate::settings::get_projects_directory()?;
+    update_project_tags(&projects_dir, &projectId, |entry| entry.status = status)
+}
+
+/// Every tag used on any project, for the dashboard's filter
+#[tauri::command]
+pub fn list_project_tags() -> Result<Vec<String>, String> {
+    let projects_dir = crate::settings::get_projects_directory()?;
+    Ok(load_project_index(&projects_dir)?.all_tags())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn metadata(id: &str) -> ProjectMetadata {
+        ProjectMetadata {
+            id: id.to_string(),
+            name: format!("Project {id}"),
+            created: Utc::now(),
+            last_modified: Utc::now(),
+            path: None,
+            content_layout: None,
+            encryption: None,
+            is_writable: None,
+            tags: Vec::new(),
+            status: None,
+            local_times: None,
+        }
+    }
+
+    #[test]
+    fn test_tags_are_trimmed_and_deduplicated() {
+        let tags = vec![
+            " Safety ".to_string(),
+            "safety".to_string(),
+            "".to_string(),
+            "Onboarding".to_string(),
+        ];
+        assert_eq!(normalize_tags(tags).unwrap(), ["Safety", "Onboarding"]);
+        assert!(normalize_tags(vec!["x".repeat(MAX_TAG_LENGTH + 1)]).is_err());
+    }
+
+    #[test]
+    fn test_index_round_trip_and_filtering() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        update_project_tags(dir, "1234567890", |entry| {
+            entry.tags = vec!["Safety".to_string(), "Q3".to_string()]
+        })
+        .unwrap();
+        update_project_tags(dir, "1234567891", |entry| {
+            entry.status = ProjectStatus::Published
+        })
+        .unwrap();
+        assert!(update_project_tags(dir, "../elsewhere", |_| {}).is_err());
+
+        let index = load_project_index(dir).unwrap();
+        assert_eq!(index.all_tags(), ["Q3", "Safety"]);
+
+        let mut tagged = metadata("1234567890");
+        let mut published = metadata("1234567891");
+        let mut untracked = metadata("1234567892");
+        for project in [&mut tagged, &mut published, &mut untracked] {
+            index.annotate(project);
+        }
+        assert_eq!(untracked.status, Some(ProjectStatus::Draft));
+
+        let by_tag = ProjectFilter {
+            tag: Some("safety".to_string()),
+            status: None,
+        };
+        let drafts = ProjectFilter {
+            tag: None,
+            status: Some(ProjectStatus::Draft),
+        };
+        assert!(by_tag.matches(&tagged) && !by_tag.matches(&published));
+        assert!(drafts.matches(&tagged) && drafts.matches(&untracked));
+        assert!(!drafts.matches(&published));
+        assert!(ProjectFilter::default().matches(&published));
+
+        forget_project(dir, "1234567890").unwrap();
+        assert_eq!(
+            load_project_index(dir).unwrap().all_tags(),
+            Vec::<String>::new()
+        );
+    }
+}
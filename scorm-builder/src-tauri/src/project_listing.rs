@@ -0,0 +1,311 @@
+//! Fast project listing for big workspaces.
+//!
+//! Listing used to load every `.scormproj` in full, one after another, which
+//! takes half a minute for a few hundred projects on a network drive. Only
+//! the `project` header is needed for the list, and the app writes it first,
+//! so [`read_project_header`] stops reading once it has it. Files are read on
+//! a few threads at once, and headers are cached by modification time and
+//! size, so a file is only read again after it changed.
+
+use crate::display_time::TimeFormatter;
+use crate::project_index::{load_project_index, ProjectFilter};
+use crate::project_storage::{get_projects_directory, ProjectMetadata};
+use once_cell::sync::Lazy;
+use serde::de::{self, Deserializer as _, IgnoredAny, MapAccess, Visitor};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::Emitter;
+
+pub const UPDATE_EVENT: &str = "project-list-update";
+
+/// Files read at once; more only queue up on the same drive
+const MAX_READERS: usize = 8;
+
+/// Stands in for an error once the header is read, to stop the parser there
+const HEADER_READ: &str = "project header read";
+
+/// Size and modification time of a project file when its header was read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn of(metadata: &fs::Metadata) -> Self {
+        Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        }
+    }
+}
+
+static HEADER_CACHE: Lazy<Mutex<HashMap<PathBuf, (FileStamp, ProjectMetadata)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// One project read during a refresh, as it arrives
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectListUpdate {
+    pub path: String,
+    /// None when the file couldn't be read; see `error`
+    pub project: Option<ProjectMetadata>,
+    pub error: Option<String>,
+    /// Whether the header came from the cache
+    pub cached: bool,
+    pub done: usize,
+    pub total: usize,
+}
+
+struct HeaderVisitor<'a>(&'a mut Option<ProjectMetadata>);
+
+impl<'de> Visitor<'de> for HeaderVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a project file")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "project" {
+                *self.0 = Some(map.next_value()?);
+                return Err(de::Error::custom(HEADER_READ));
+            }
+            map.next_value::<IgnoredAny>()?;
+        }
+        Ok(())
+    }
+}
+
+/// The `project` metadata of a project file, reading no further than needed
+pub fn read_project_header(path: &Path) -> Result<ProjectMetadata, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to read file: {e}"))?;
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+    let mut header = None;
+    let result = (&mut deserializer).deserialize_map(HeaderVisitor(&mut header));
+    match (header, result) {
+        (Some(mut metadata), _) => {
+            metadata.path = Some(path.to_string_lossy().to_string());
+            Ok(metadata)
+        }
+        (None, Err(e)) => Err(format!("Failed to parse project file: {e}")),
+        (None, Ok(())) => Err("Failed to parse project file: no project metadata".to_string()),
+    }
+}
+
+/// Project files in `projects_dir` with their stamps, newest first
+fn scan_project_files(projects_dir: &Path) -> Result<Vec<(PathBuf, FileStamp)>, String> {
+    let entries = fs::read_dir(projects_dir).map_err(|e| {
+        format!("Failed to read projects directory '{}': {e}", projects_dir.display())
+    })?;
+    let mut files: Vec<(PathBuf, FileStamp)> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().and_then(|s| s.to_str()) == Some("scormproj"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((entry.path(), FileStamp::of(&metadata)))
+        })
+        .collect();
+    files.sort_by(|a, b| b.1.modified.cmp(&a.1.modified));
+    Ok(files)
+}
+
+/// Headers of `files` in their order, from the cache or read in parallel.
+/// `on_update` is called for each file as soon as its header is known.
+fn read_headers(
+    projects_dir: &Path,
+    files: &[(PathBuf, FileStamp)],
+    on_update: &(dyn Fn(ProjectListUpdate) + Sync),
+) -> Vec<Result<ProjectMetadata, String>> {
+    let total = files.len();
+    let done = AtomicUsize::new(0);
+    let report = |path: &Path, result: &Result<ProjectMetadata, String>, cached: bool| {
+        on_update(ProjectListUpdate {
+            path: path.to_string_lossy().to_string(),
+            project: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().cloned(),
+            cached,
+            done: done.fetch_add(1, Ordering::SeqCst) + 1,
+            total,
+        });
+    };
+
+    let mut results: Vec<Option<Result<ProjectMetadata, String>>> = vec![None; total];
+    {
+        let mut cache = HEADER_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let listed: HashSet<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+        cache.retain(|path, _| path.parent() != Some(projects_dir) || listed.contains(path));
+        for (slot, (path, stamp)) in results.iter_mut().zip(files) {
+            if let Some((cached_stamp, metadata)) = cache.get(path) {
+                if cached_stamp == stamp {
+                    let result = Ok(metadata.clone());
+                    report(path, &result, true);
+                    *slot = Some(result);
+                }
+            }
+        }
+    }
+
+    let pending: Vec<usize> = (0..total).filter(|&i| results[i].is_none()).collect();
+    let next = AtomicUsize::new(0);
+    let read = Mutex::new(Vec::with_capacity(pending.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..pending.len().min(MAX_READERS) {
+            scope.spawn(|| loop {
+                let Some(&index) = pending.get(next.fetch_add(1, Ordering::SeqCst)) else {
+                    break;
+                };
+                let (path, stamp) = &files[index];
+                let result = read_project_header(path);
+                report(path, &result, false);
+                if let Ok(metadata) = &result {
+                    HEADER_CACHE
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(path.clone(), (*stamp, metadata.clone()));
+                }
+                read.lock().unwrap_or_else(|e| e.into_inner()).push((index, result));
+            });
+        }
+    });
+    for (index, result) in read.into_inner().unwrap_or_else(|e| e.into_inner()) {
+        results[index] = Some(result);
+    }
+    results.into_iter().map(|result| result.unwrap_or_else(|| Err("Not read".to_string()))).collect()
+}
+
+/// Projects in `projects_dir`, newest first, with their tags and status from
+/// the index and their times in local time. Unreadable files are left out.
+pub fn list_project_headers(
+    projects_dir: &Path,
+    filter: &ProjectFilter,
+    on_update: &(dyn Fn(ProjectListUpdate) + Sync),
+) -> Result<Vec<ProjectMetadata>, String> {
+    let files = scan_project_files(projects_dir)?;
+    // An unreadable index lists every project untagged rather than none
+    let index = load_project_index(projects_dir).unwrap_or_default();
+    let formatter = TimeFormatter::current();
+    let describe = |metadata: &mut ProjectMetadata| {
+        index.annotate(metadata);
+        metadata.local_times = Some(formatter.local_times([
+            ("created", metadata.created),
+            ("last_modified", metadata.last_modified),
+        ]));
+    };
+    let annotate = |mut update: ProjectListUpdate| {
+        if let Some(metadata) = update.project.as_mut() {
+            describe(metadata);
+        }
+        on_update(update);
+    };
+    let projects = read_headers(projects_dir, &files, &annotate)
+        .into_iter()
+        .zip(&files)
+        .filter_map(|(result, (path, _))| match result {
+            Ok(mut metadata) => {
+                describe(&mut metadata);
+                Some(metadata)
+            }
+            Err(e) => {
+                crate::commands_secure::log_debug(&format!(
+                    "Failed to load project file '{}': {e}",
+                    path.display()
+                ));
+                None
+            }
+        })
+        .filter(|metadata| filter.matches(metadata))
+        .collect();
+    Ok(projects)
+}
+
+/// List the projects like `list_projects`, emitting `project-list-update` for
+/// each project as it is read, cached ones first, so a long list fills in
+/// as it arrives
+#[tauri::command]
+pub async fn refresh_project_list(
+    app: tauri::AppHandle,
+    filter: Option<ProjectFilter>,
+) -> Result<Vec<ProjectMetadata>, String> {
+    let filter = filter.unwrap_or_default();
+    let projects_dir = get_projects_directory()?;
+    tokio::task::spawn_blocking(move || {
+        list_project_headers(&projects_dir, &filter, &|update: ProjectListUpdate| {
+            let visible = update.project.as_ref().is_none_or(|metadata| filter.matches(metadata));
+            if visible {
+                let _ = app.emit(UPDATE_EVENT, update);
+            }
+        })
+    })
+    .await
+    .map_err(|e| format!("Project listing failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_project(dir: &Path, id: &str, name: &str) -> PathBuf {
+        let path = dir.join(format!("{name}_{id}.scormproj"));
+        let project = serde_json::json!({
+            "project": {
+                "id": id,
+                "name": name,
+                "created": "2024-01-01T00:00:00Z",
+                "last_modified": "2024-01-01T00:00:00Z"
+            },
+            "course_data": { "title": name }
+        });
+        fs::write(&path, serde_json::to_vec(&project).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_header_is_read_without_parsing_the_rest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("Truncated_1700000000000.scormproj");
+        fs::write(
+            &path,
+            r#"{"project": {"id": "1700000000000", "name": "Truncated", "created": "2024-01-01T00:00:00Z", "last_modified": "2024-01-01T00:00:00Z"}, "course_data": {"tit"#,
+        )
+        .unwrap();
+
+        let metadata = read_project_header(&path).unwrap();
+
+        assert_eq!(metadata.name, "Truncated");
+        assert_eq!(metadata.path.as_deref(), Some(path.to_string_lossy().as_ref()));
+        fs::write(&path, "{}").unwrap();
+        assert!(read_project_header(&path).is_err());
+    }
+
+    #[test]
+    fn test_listing_reports_every_file_and_reuses_the_cache() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..12 {
+            write_project(dir.path(), &format!("17000000000{i:02}"), &format!("Course{i}"));
+        }
+        fs::write(dir.path().join("Broken_1700000000099.scormproj"), "not json").unwrap();
+        let updates = Mutex::new(Vec::new());
+        let record = |update: ProjectListUpdate| updates.lock().unwrap().push(update);
+
+        let projects = list_project_headers(dir.path(), &ProjectFilter::default(), &record).unwrap();
+        assert_eq!(projects.len(), 12);
+        let first = updates.lock().unwrap().drain(..).collect::<Vec<_>>();
+        assert_eq!(first.len(), 13);
+        assert_eq!(first.iter().filter(|update| update.error.is_some()).count(), 1);
+        assert!(first.iter().all(|update| !update.cached));
+
+        list_project_headers(dir.path(), &ProjectFilter::default(), &record).unwrap();
+        let second = updates.lock().unwrap();
+        assert_eq!(second.iter().filter(|update| update.cached).count(), 12);
+        assert_eq!(second.iter().map(|update| update.done).max(), Some(13));
+    }
+}
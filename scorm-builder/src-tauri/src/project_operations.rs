@@ -0,0 +1,216 @@
+//! One build or export at a time per project.
+//!
+//! Clicking "Generate" twice used to start two overlapping builds that raced
+//! on the same temp files. Long-running project operations now register in
+//! [`ProjectOperations`] (Tauri managed state) for as long as they run; a
+//! second request for the same project is turned away with the ID of the
+//! operation already running, which `get_project_operation` also reports.
+//! Operations that check for it can be stopped with `cancel_project_operation`.
+
+use crate::app_services::AppServices;
+use crate::messages::Message;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum OperationKind {
+    /// SCORM package generation
+    Generate,
+    /// Project ZIP export
+    Export,
+    /// Project ZIP import, held under the ID of the project it creates
+    Import,
+}
+
+impl OperationKind {
+    fn running_message_key(self) -> &'static str {
+        match self {
+            OperationKind::Generate => "error.operation.generate_running",
+            OperationKind::Export => "error.operation.export_running",
+            OperationKind::Import => "error.operation.import_running",
+        }
+    }
+}
+
+/// An operation holding a project
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RunningOperation {
+    pub operation_id: String,
+    pub project_id: String,
+    pub kind: OperationKind,
+    pub started_at: DateTime<Utc>,
+}
+
+impl RunningOperation {
+    /// Error returned to a request turned away because this is running
+    pub fn already_running(&self) -> Message {
+        Message::new(self.kind.running_message_key()).with("id", &self.operation_id)
+    }
+}
+
+/// Operations running per project ID, held in Tauri managed state
+#[derive(Debug, Clone, Default)]
+pub struct ProjectOperations {
+    running: Arc<Mutex<HashMap<String, RunningOperation>>>,
+    /// IDs of running operations asked to stop
+    cancelled: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ProjectOperations {
+    /// Register an operation on `project_id`, or return the one already
+    /// running there. The project is released when the guard is dropped.
+    pub fn begin(
+        &self,
+        project_id: &str,
+        kind: OperationKind,
+        started_at: DateTime<Utc>,
+    ) -> Result<OperationGuard, RunningOperation> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(existing) = running.get(project_id) {
+            return Err(existing.clone());
+        }
+        let operation = RunningOperation {
+            operation_id: uuid::Uuid::new_v4().to_string(),
+            project_id: project_id.to_string(),
+            kind,
+            started_at,
+        };
+        running.insert(project_id.to_string(), operation.clone());
+        Ok(OperationGuard {
+            running: self.running.clone(),
+            cancelled: self.cancelled.clone(),
+            operation,
+        })
+    }
+
+    pub fn get(&self, project_id: &str) -> Option<RunningOperation> {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        running.get(project_id).cloned()
+    }
+
+    /// Ask the running operation `operation_id` to stop. False when no such
+    /// operation is running.
+    pub fn cancel(&self, operation_id: &str) -> bool {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if !running.values().any(|operation| operation.operation_id == operation_id) {
+            return false;
+        }
+        let mut cancelled = self.cancelled.lock().unwrap_or_else(|e| e.into_inner());
+        cancelled.insert(operation_id.to_string());
+        true
+    }
+}
+
+/// Holds a project for an operation until dropped
+#[derive(Debug)]
+pub struct OperationGuard {
+    running: Arc<Mutex<HashMap<String, RunningOperation>>>,
+    cancelled: Arc<Mutex<HashSet<String>>>,
+    operation: RunningOperation,
+}
+
+impl OperationGuard {
+    pub fn operation_id(&self) -> &str {
+        &self.operation.operation_id
+    }
+
+    /// Whether `cancel_project_operation` was called for this operation
+    pub fn is_cancelled(&self) -> bool {
+        let cancelled = self.cancelled.lock().unwrap_or_else(|e| e.into_inner());
+        cancelled.contains(&self.operation.operation_id)
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if running.get(&self.operation.project_id) == Some(&self.operation) {
+            running.remove(&self.operation.project_id);
+        }
+        let mut cancelled = self.cancelled.lock().unwrap_or_else(|e| e.into_inner());
+        cancelled.remove(&self.operation.operation_id);
+    }
+}
+
+/// Hold `project_id` for an operation started by a command, or the
+/// "already running" error to return from it
+pub fn begin_operation(
+    operations: &ProjectOperations,
+    services: &AppServices,
+    project_id: &str,
+    kind: OperationKind,
+) -> Result<OperationGuard, Message> {
+    operations
+        .begin(project_id, kind, services.now())
+        .map_err(|existing| existing.already_running())
+}
+
+/// The build or export running on a project, if any
+#[tauri::command]
+pub fn get_project_operation(
+    #[allow(non_snake_case)] projectId: String,
+    operations: State<'_, ProjectOperations>,
+) -> Option<RunningOperation> {
+    operations.get(&projectId)
+}
+
+/// Ask a running operation to stop; only operations that check for it do
+/// (imports). False when it isn't running.
+#[tauri::command]
+pub fn cancel_project_operation(
+    #[allow(non_snake_case)] operationId: String,
+    operations: State<'_, ProjectOperations>,
+) -> bool {
+    operations.cancel(&operationId)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_operation_on_a_project_gets_the_running_id() {
+        let operations = ProjectOperations::default();
+        let now = Utc::now();
+
+        let build = operations.begin("1", OperationKind::Generate, now).unwrap();
+        let existing = operations.begin("1", OperationKind::Export, now).unwrap_err();
+        assert_eq!(existing.operation_id, build.operation_id());
+        assert_eq!(existing.kind, OperationKind::Generate);
+        assert_eq!(existing.already_running().params, vec![("id", build.operation_id().to_string())]);
+
+        let other = operations.begin("2", OperationKind::Generate, now).unwrap();
+        assert_ne!(other.operation_id(), build.operation_id());
+
+        drop(build);
+        assert_eq!(operations.get("1"), None);
+        assert!(operations.begin("1", OperationKind::Export, now).is_ok());
+        assert!(operations.get("2").is_some());
+    }
+
+    #[test]
+    fn test_only_the_cancelled_operation_sees_it() {
+        let operations = ProjectOperations::default();
+        let now = Utc::now();
+        let import = operations.begin("1", OperationKind::Import, now).unwrap();
+        let export = operations.begin("2", OperationKind::Export, now).unwrap();
+
+        assert!(!operations.cancel("not-running"));
+        assert!(operations.cancel(import.operation_id()));
+        assert!(import.is_cancelled());
+        assert!(!export.is_cancelled());
+
+        let id = import.operation_id().to_string();
+        drop(import);
+        assert!(!operations.cancel(&id));
+        assert!(operations.cancelled.lock().unwrap().is_empty());
+    }
+}
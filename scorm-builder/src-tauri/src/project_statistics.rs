@@ -0,0 +1,242 @@
+use crate::course_content::{ContentPage, CourseContent, Question};
+use crate::media_storage::get_media_directory;
+use crate::media_usage::find_project_file;
+use crate::project_encryption::read_media_file;
+use crate::project_storage::load_project_file;
+use crate::storage_budget::{storage_report, StorageReport};
+use crate::scorm::page_effort::{
+    content_clip_seconds, count_words, mp3_duration_seconds, PageEffort,
+};
+use serde::Serialize;
+
+/// A page's estimated effort and its share of the course's
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageStatistics {
+    #[serde(flatten)]
+    pub effort: PageEffort,
+    /// Percentage of the course's estimated time, as the progress indicator counts it
+    pub weight: f64,
+}
+
+/// Size and estimated duration of a course
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProjectStatistics {
+    pub pages: Vec<PageStatistics>,
+    pub total_words: usize,
+    pub total_questions: usize,
+    pub media_seconds: u32,
+    pub estimated_seconds: u32,
+    /// Media size against the project's storage budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<StorageReport>,
+}
+
+fn question_words(questions: &[Question]) -> usize {
+    questions
+        .iter()
+        .map(|question| {
+            count_words(&question.question)
+                + question
+                    .options()
+                    .iter()
+                    .map(|option| count_words(option))
+                    .sum::<usize>()
+        })
+        .sum()
+}
+
+fn page_effort(
+    page: &ContentPage,
+    fallback_id: &str,
+    fallback_title: &str,
+    narration_seconds: &dyn Fn(&str) -> u32,
+) -> PageEffort {
+    let questions = page
+        .knowledge_check
+        .as_ref()
+        .map(|check| check.all_questions())
+        .unwrap_or_default();
+    // The objectives page keeps its list beside the content
+    let objectives: usize = page
+        .extra
+        .get("objectives")
+        .and_then(|objectives| objectives.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|objective| objective.as_str())
+        .map(count_words)
+        .sum();
+    let words = count_words(page.content.as_deref().unwrap_or_default())
+        + objectives
+        + question_words(&questions);
+    let media_seconds = page.audio_id.as_deref().map_or(0, narration_seconds)
+        + page
+            .media()
+            .iter()
+            .map(|media| content_clip_seconds(&media.extra))
+            .sum::<u32>();
+    PageEffort::new(
+        page.id.as_deref().unwrap_or(fallback_id),
+        page.title.as_deref().unwrap_or(fallback_title),
+        words,
+        questions.len(),
+        media_seconds,
+    )
+}
+
+/// Statistics of `content`, with `narration_seconds` giving the length of an audio media ID
+pub fn course_statistics(
+    content: &CourseContent,
+    narration_seconds: &dyn Fn(&str) -> u32,
+) -> ProjectStatistics {
+    let mut pages = Vec::new();
+    if let Some(welcome) = &content.welcome_page {
+        pages.push(page_effort(
+            welcome,
+            "welcome",
+            "Welcome",
+            narration_seconds,
+        ));
+    }
+    if let Some(objectives) = &content.learning_objectives_page {
+        pages.push(page_effort(
+            objectives,
+            "objectives",
+            "Learning Objectives",
+            narration_seconds,
+        ));
+    }
+    for (index, topic) in content.topics.iter().enumerate() {
+        pages.push(page_effort(
+            topic,
+            &format!("topic-{index}"),
+            &format!("Topic {}", index + 1),
+            narration_seconds,
+        ));
+    }
+    if let Some(assessment) = &content.assessment {
+        pages.push(PageEffort::new(
+            "assessment",
+            "Assessment",
+            question_words(&assessment.questions),
+            assessment.questions.len(),
+            0,
+        ));
+    }
+
+    let estimated_seconds: u32 = pages.iter().map(|page| page.estimated_seconds).sum();
+    ProjectStatistics {
+        total_words: pages.iter().map(|page| page.words).sum(),
+        total_questions: pages.iter().map(|page| page.questions).sum(),
+        media_seconds: pages.iter().map(|page| page.media_seconds).sum(),
+        estimated_seconds,
+        storage: None,
+        pages: pages
+            .into_iter()
+            .map(|effort| PageStatistics {
+                weight: if estimated_seconds == 0 {
+                    0.0
+                } else {
+                    (f64::from(effort.estimated_seconds) * 1000.0 / f64::from(estimated_seconds))
+                        .round()
+                        / 10.0
+                },
+                effort,
+            })
+            .collect(),
+    }
+}
+
+/// Word counts, question counts and estimated time per page of a project,
+/// weighted the way the generated course's progress indicator is, and its
+/// media size against its storage budget
+#[tauri::command]
+pub fn get_project_statistics(
+    #[allow(non_snake_case)] projectId: String,
+) -> Result<ProjectStatistics, String> {
+    let project_path =
+        find_project_file(&projectId).ok_or_else(|| "Project file not found".to_string())?;
+    let project = load_project_file(&project_path)?;
+    let media_dir = get_media_directory(&project.project.id)?;
+    let mut statistics = match &project.course_content {
+        Some(course_content) => {
+            let content = CourseContent::from_value(course_content)?;
+            let narration_seconds = |audio_id: &str| {
+                read_media_file(&media_dir.join(format!("{audio_id}.bin")))
+                    .ok()
+                    .and_then(|data| mp3_duration_seconds(&data))
+                    .unwrap_or(0)
+            };
+            course_statistics(&content, &narration_seconds)
+        }
+        None => ProjectStatistics::default(),
+    };
+    statistics.storage = Some(storage_report(&media_dir));
+    Ok(statistics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn content() -> CourseContent {
+        CourseContent::from_value(&json!({
+            "welcomePage": { "id": "welcome", "title": "Welcome", "content": "<p>Hello there</p>" },
+            "learningObjectivesPage": {
+                "id": "objectives",
+                "title": "Objectives",
+                "objectives": ["Name the hazards", "Choose protection"]
+            },
+            "topics": [{
+                "id": "topic-0",
+                "title": "Hazards",
+                "content": "word ".repeat(600),
+                "audioId": "audio-2",
+                "media": [{ "id": "video-0", "type": "youtube", "clipStart": 10, "clipEnd": 70 }],
+                "knowledgeCheck": {
+                    "questions": [{ "type": "true-false", "question": "Gloves are optional", "correctAnswer": "False" }]
+                }
+            }],
+            "assessment": {
+                "questions": [{ "question": "Pick one", "options": ["Gloves", "Goggles"], "correctAnswer": "Gloves" }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_statistics_estimate_each_page() {
+        let statistics = course_statistics(&content(), &|id| if id == "audio-2" { 120 } else { 0 });
+
+        let ids: Vec<_> = statistics
+            .pages
+            .iter()
+            .map(|page| page.effort.page_id.as_str())
+            .collect();
+        assert_eq!(ids, ["welcome", "objectives", "topic-0", "assessment"]);
+        assert_eq!(statistics.pages[1].effort.words, 5);
+        let topic = &statistics.pages[2].effort;
+        assert_eq!(
+            (topic.words, topic.questions, topic.media_seconds),
+            (603, 1, 180)
+        );
+        // 603 words take 181s to read, longer than the 180s of narration and clip
+        assert_eq!(topic.estimated_seconds, 211);
+        assert_eq!(statistics.total_questions, 2);
+        assert_eq!(statistics.estimated_seconds, 15 + 15 + 211 + 32);
+    }
+
+    #[test]
+    fn test_weights_add_up_to_the_course() {
+        let statistics = course_statistics(&content(), &|_| 0);
+
+        let total: f64 = statistics.pages.iter().map(|page| page.weight).sum();
+        assert!((total - 100.0).abs() < 0.5);
+        assert!(statistics.pages[2].weight > statistics.pages[0].weight);
+        assert_eq!(
+            course_statistics(&CourseContent::default(), &|_| 0),
+            ProjectStatistics::default()
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use crate::content_pages::{self, ContentLayout};
+use crate::display_time::LocalTimes;
+use crate::project_encryption::{self, ProjectEncryption};
+use crate::project_index::ProjectStatus;
 use chrono::{DateTime, Utc};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -6,12 +10,14 @@ use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use ts_rs::TS;
 
 // Global mutex map for file locking
 static FILE_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct ProjectFile {
     pub project: ProjectMetadata,
     pub course_data: CourseData,
@@ -22,30 +28,64 @@ pub struct ProjectFile {
     pub scorm_config: ScormConfig,
     // New fields for complete data persistence
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub course_seed_data: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub json_import_data: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub activities_data: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub media_enhancements: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub content_edits: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub current_step: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct ProjectMetadata {
     pub id: String,
     pub name: String,
     pub created: DateTime<Utc>,
     pub last_modified: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub path: Option<String>,
+    /// How course_content is stored on disk; absent means inline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub content_layout: Option<ContentLayout>,
+    /// Set when the project's content is encrypted (see `project_encryption`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub encryption: Option<ProjectEncryption>,
+    /// Set when loading: whether changes can be saved. Never written to disk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub is_writable: Option<bool>,
+    /// Set when listing, from the projects index. Never written to the project file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[ts(as = "Option<Vec<String>>", optional)]
+    pub tags: Vec<String>,
+    /// Set when listing, from the projects index. Never written to the project file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub status: Option<ProjectStatus>,
+    /// Set when listing: `created` and `last_modified` in local time. Never
+    /// written to the project file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(type = "{ timezone: string } & Record<string, string>", optional)]
+    pub local_times: Option<LocalTimes>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct CourseData {
     pub title: String,
     pub difficulty: u8,
@@ -54,51 +94,69 @@ pub struct CourseData {
     pub custom_topics: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct AiPromptData {
     pub prompt: String,
     pub generated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+// Named apart from `media_storage::MediaData` on the TypeScript side
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export, rename = "ProjectMediaData")]
 pub struct MediaData {
     pub images: Vec<MediaItem>,
     pub videos: Vec<VideoItem>,
     pub audio: Vec<MediaItem>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[ts(as = "Option<Vec<MediaItem>>", optional)]
     pub captions: Vec<MediaItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct MediaItem {
     pub id: String,
     pub filename: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub base64_data: Option<String>, // Now optional for backward compatibility
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub relative_path: Option<String>, // New field for file-based storage
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct VideoItem {
     pub id: String,
     pub youtube_url: String,
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct AudioSettings {
     pub voice: String,
     pub speed: f32,
     pub pitch: f32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct ScormConfig {
     pub version: String,
     pub completion_criteria: String,
     pub passing_score: u8,
+    /// Media rules applied to the packaged copies of the media
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub media_transforms: Option<crate::scorm::media_transform::MediaTransformSpec>,
+    /// Deflate level (0-9) for text assets in the package; already-compressed media is stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub zip_compression_level: Option<i32>,
 }
 
 /// Get the projects directory from settings or default
@@ -108,6 +166,8 @@ pub fn get_projects_directory() -> Result<PathBuf, String> {
 
 /// Save a project file to disk with file locking
 pub fn save_project_file(project: &ProjectFile, file_path: &Path) -> Result<(), String> {
+    crate::project_access::ensure_writable(file_path)?;
+
     // Get or create a lock for this specific file
     let file_path_buf = file_path.to_path_buf();
     let file_lock = {
@@ -136,13 +196,39 @@ pub fn save_project_file(project: &ProjectFile, file_path: &Path) -> Result<(),
     // Update last modified timestamp
     let mut project = project.clone();
     project.project.last_modified = Utc::now();
+    project.project.is_writable = None;
+    project.project.tags.clear();
+    project.project.status = None;
+    project.project.local_times = None;
 
     // Ensure data consistency before saving
     ensure_data_consistency(&mut project);
 
-    // Serialize to pretty JSON
-    let json = serde_json::to_string_pretty(&project)
-        .map_err(|e| format!("Failed to serialize project: {e}"))?;
+    // In the split layout pages go to their own files and the project keeps an index.
+    // Encrypted projects are always inline, so no page is left readable.
+    let encrypted = project.project.encryption.is_some();
+    let split = project.project.content_layout == Some(ContentLayout::Split) && !encrypted;
+    let pages_dir = content_pages::pages_dir(file_path, &project.project.id);
+    let mut pages = Vec::new();
+    if split {
+        if let Some(content) = &project.course_content {
+            let (index, split_pages) = content_pages::split_course_content(content);
+            content_pages::write_pages(pages_dir.as_ref().map_err(String::clone)?, &split_pages)?;
+            project.course_content = Some(index);
+            pages = split_pages;
+        }
+    }
+
+    // Serialize to pretty JSON, with everything but the header sealed when encrypted
+    let json = if encrypted {
+        let mut value = serde_json::to_value(&project)
+            .map_err(|e| format!("Failed to serialize project: {e}"))?;
+        project_encryption::seal_project(file_path, &mut value)?;
+        serde_json::to_string_pretty(&value)
+    } else {
+        serde_json::to_string_pretty(&project)
+    }
+    .map_err(|e| format!("Failed to serialize project: {e}"))?;
 
     // Create parent directory if needed
     if let Some(parent) = file_path.parent() {
@@ -170,6 +256,13 @@ pub fn save_project_file(project: &ProjectFile, file_path: &Path) -> Result<(),
         format!("Failed to rename temp file to final location: {e}")
     })?;
 
+    // Drop pages the index no longer uses, or all of them after switching back to inline
+    if let Ok(pages_dir) = &pages_dir {
+        if pages_dir.exists() {
+            content_pages::remove_stale_pages(pages_dir, &pages);
+        }
+    }
+
     Ok(())
 }
 
@@ -182,8 +275,16 @@ pub fn load_project_file(file_path: &Path) -> Result<ProjectFile, String> {
     let contents =
         fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {e}"))?;
 
-    let mut project: ProjectFile = serde_json::from_str(&contents)
+    let mut value: serde_json::Value = serde_json::from_str(&contents)
         .map_err(|e| format!("Failed to parse project file: {e}"))?;
+    project_encryption::open_project(file_path, &mut value)?;
+    let mut project: ProjectFile = serde_json::from_value(value)
+        .map_err(|e| format!("Failed to parse project file: {e}"))?;
+
+    if let Some(content) = project.course_content.as_mut().filter(|c| content_pages::is_split(c)) {
+        let pages_dir = content_pages::pages_dir(file_path, &project.project.id)?;
+        content_pages::inline_pages(content, &pages_dir)?;
+    }
 
     project.project.path = Some(file_path.to_string_lossy().to_string());
 
@@ -222,10 +323,9 @@ pub fn list_project_files() -> Result<Vec<PathBuf>, String> {
     crate::commands_secure::log_to_frontend("INFO", &format!("Found {} project files", project_files.len()));
 
     // Sort by modification time (newest first)
-    project_files.sort_by(|a, b| {
-        let a_time = fs::metadata(a).and_then(|m| m.modified()).ok();
-        let b_time = fs::metadata(b).and_then(|m| m.modified()).ok();
-        b_time.cmp(&a_time)
+    // (each file's time is read once; on a network drive every read is a round trip)
+    project_files.sort_by_cached_key(|path| {
+        std::cmp::Reverse(fs::metadata(path).and_then(|m| m.modified()).ok())
     });
 
     Ok(project_files)
@@ -236,6 +336,7 @@ pub fn delete_project_file(file_path: &Path) -> Result<(), String> {
     if !file_path.exists() {
         return Err(format!("Project file not found: {}", file_path.display()));
     }
+    crate::project_access::ensure_writable(file_path)?;
 
     // Try to load the project to get its ID for folder deletion
     let project_id = match load_project_file(file_path) {
@@ -261,6 +362,8 @@ pub fn delete_project_file(file_path: &Path) -> Result<(), String> {
                 fs::remove_dir_all(&uuid_folder)
                     .map_err(|e| format!("Failed to delete project UUID folder: {e}"))?;
             }
+            // A stale tag entry only clutters the tag list, so failing to drop it is not an error
+            let _ = crate::project_index::forget_project(&projects_dir, &id);
         }
     }
 
@@ -323,6 +426,12 @@ mod tests {
                 created: Utc::now(),
                 last_modified: Utc::now(),
                 path: None,
+                content_layout: None,
+                encryption: None,
+                is_writable: None,
+                tags: Vec::new(),
+                status: None,
+                local_times: None,
             },
             course_data: CourseData {
                 title: "Test Course".to_string(),
@@ -348,6 +457,8 @@ mod tests {
                 version: "2004".to_string(),
                 completion_criteria: "all_pages".to_string(),
                 passing_score: 80,
+                media_transforms: None,
+                zip_compression_level: None,
             },
             course_seed_data: None,
             json_import_data: None,
@@ -417,6 +528,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_split_layout_is_transparent_to_load_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("Split_1234567890.scormproj");
+        let pages_dir = temp_dir.path().join("1234567890").join("pages");
+
+        let mut project = create_test_project();
+        project.project.content_layout = Some(ContentLayout::Split);
+        let content = serde_json::json!({
+            "welcomePage": { "id": "welcome", "content": "<p>Hi</p>" },
+            "topics": [{ "id": "topic-1", "content": "<p>One</p>" }]
+        });
+        project.course_content = Some(content.clone());
+        save_project_file(&project, &file_path).unwrap();
+
+        let raw = fs::read_to_string(&file_path).unwrap();
+        assert!(!raw.contains("<p>One</p>"));
+        assert!(pages_dir.join("topic-topic-1.json").exists());
+        let loaded = load_project_file(&file_path).unwrap();
+        assert_eq!(loaded.course_content, Some(content.clone()));
+
+        // Switching back to inline puts everything in the project file again
+        project.project.content_layout = Some(ContentLayout::Single);
+        save_project_file(&project, &file_path).unwrap();
+        assert!(fs::read_to_string(&file_path).unwrap().contains("<p>One</p>"));
+        assert_eq!(fs::read_dir(&pages_dir).unwrap().count(), 0);
+        let loaded = load_project_file(&file_path).unwrap();
+        assert_eq!(loaded.course_content, Some(content));
+    }
+
+    #[test]
+    fn test_read_only_project_is_left_untouched() {
+        use crate::project_access::ProjectAccess;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("ReadOnly_1234567890.scormproj");
+        let mut project = create_test_project();
+        save_project_file(&project, &file_path).unwrap();
+        let saved = fs::read_to_string(&file_path).unwrap();
+
+        crate::project_access::set_access(&file_path, ProjectAccess::ReadOnlyMode);
+        project.project.name = "Changed".to_string();
+        assert!(save_project_file(&project, &file_path).is_err());
+        assert!(delete_project_file(&file_path).is_err());
+        crate::project_access::forget(&file_path);
+
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), saved);
+    }
+
     #[test]
     fn test_list_project_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,561 @@
+//! Publishing built packages to the LMS content server.
+//!
+//! A package goes to a local folder, a Windows share (UNC path) or an SFTP
+//! server. It is uploaded under a `.part` name, read back and compared by
+//! SHA-256, and only then renamed into place, so the LMS never picks up a
+//! half-written ZIP. Failed uploads are retried with backoff. SFTP passwords
+//! and key passphrases are kept encrypted next to the API keys, one entry
+//! per `user@host:port`, and are never returned to the frontend. Once a
+//! package is in place the configured webhook is told (see `publish_webhook`).
+
+use crate::api_keys::{get_encrypted_file_path, load_encrypted, save_encrypted};
+use crate::publish_webhook::{notify_published, WebhookDelivery};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ssh2::{OpenFlags, OpenType, Session, Sftp};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const CREDENTIALS_FILE: &str = "publish_credentials.enc";
+/// Uploads tried before giving up
+pub const MAX_ATTEMPTS: u32 = 3;
+/// Wait before the first retry, doubled for each one after
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+const SFTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const SFTP_TIMEOUT_MS: u32 = 60_000;
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+/// Where a package is published
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PublishDestination {
+    /// A folder on this machine or a mapped drive
+    Local { path: String },
+    /// A UNC path such as `\\server\courses`
+    Share { path: String },
+    Sftp {
+        host: String,
+        #[serde(default = "default_sftp_port")]
+        port: u16,
+        username: String,
+        remote_dir: String,
+        /// `SHA256:...` as printed by `ssh-keygen -lf`. Publishing to a
+        /// server without one fails with the server's fingerprint, so the
+        /// user can confirm it once.
+        #[serde(default)]
+        host_fingerprint: Option<String>,
+    },
+}
+
+/// How to log in to an SFTP server; either a password or a private key file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SftpCredential {
+    pub password: Option<String>,
+    pub private_key_path: Option<String>,
+    pub passphrase: Option<String>,
+}
+
+/// What was published where
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PublishReceipt {
+    pub destination: String,
+    pub path: String,
+    pub size: u64,
+    /// SHA-256 of the package, hex encoded, as read back from the destination
+    pub sha256: String,
+    pub attempts: u32,
+    /// The publish webhook call, when one is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookDelivery>,
+}
+
+/// A folder packages can be written to, by file name
+trait PublishTarget {
+    /// Shown in receipts and errors
+    fn describe(&self, file_name: &str) -> String;
+    fn write(&mut self, file_name: &str, data: &[u8]) -> Result<(), String>;
+    fn read(&mut self, file_name: &str) -> Result<Vec<u8>, String>;
+    /// Replaces `to` if it exists
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), String>;
+    fn remove(&mut self, file_name: &str) -> Result<(), String>;
+}
+
+struct LocalTarget {
+    dir: PathBuf,
+}
+
+impl PublishTarget for LocalTarget {
+    fn describe(&self, file_name: &str) -> String {
+        self.dir.join(file_name).to_string_lossy().to_string()
+    }
+
+    fn write(&mut self, file_name: &str, data: &[u8]) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {e}", self.dir.display()))?;
+        fs::write(self.dir.join(file_name), data)
+            .map_err(|e| format!("Failed to write {}: {e}", self.describe(file_name)))
+    }
+
+    fn read(&mut self, file_name: &str) -> Result<Vec<u8>, String> {
+        fs::read(self.dir.join(file_name))
+            .map_err(|e| format!("Failed to read {}: {e}", self.describe(file_name)))
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        fs::rename(self.dir.join(from), self.dir.join(to))
+            .map_err(|e| format!("Failed to rename {}: {e}", self.describe(from)))
+    }
+
+    fn remove(&mut self, file_name: &str) -> Result<(), String> {
+        fs::remove_file(self.dir.join(file_name))
+            .map_err(|e| format!("Failed to delete {}: {e}", self.describe(file_name)))
+    }
+}
+
+struct SftpTarget {
+    host: String,
+    port: u16,
+    username: String,
+    remote_dir: String,
+    host_fingerprint: Option<String>,
+    credential: SftpCredential,
+    /// Dropped after any failure so the next attempt reconnects
+    connection: Option<(Session, Sftp)>,
+}
+
+/// `SHA256:<base64>`, the way OpenSSH prints host key fingerprints
+fn fingerprint(session: &Session) -> Result<String, String> {
+    let hash = session
+        .host_key_hash(ssh2::HashType::Sha256)
+        .ok_or("The server did not send a host key")?;
+    Ok(format!(
+        "SHA256:{}",
+        base64::engine::general_purpose::STANDARD_NO_PAD.encode(hash)
+    ))
+}
+
+impl SftpTarget {
+    fn remote_path(&self, file_name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "{}/{file_name}",
+            self.remote_dir.trim_end_matches('/')
+        ))
+    }
+
+    fn connect(&self) -> Result<(Session, Sftp), String> {
+        let server = format!("{}:{}", self.host, self.port);
+        let address = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Failed to resolve {server}: {e}"))?
+            .next()
+            .ok_or_else(|| format!("Failed to resolve {server}"))?;
+        let tcp = TcpStream::connect_timeout(&address, SFTP_CONNECT_TIMEOUT)
+            .map_err(|e| format!("Failed to connect to {server}: {e}"))?;
+        let mut session = Session::new().map_err(|e| format!("Failed to start SSH: {e}"))?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(SFTP_TIMEOUT_MS);
+        session
+            .handshake()
+            .map_err(|e| format!("SSH handshake with {server} failed: {e}"))?;
+
+        let actual = fingerprint(&session)?;
+        match self.host_fingerprint.as_deref().map(str::trim) {
+            Some(expected) if expected == actual => {}
+            Some(expected) => {
+                return Err(format!(
+                    "Host key of {server} is {actual}, expected {expected}. \
+                     The server may have been replaced; confirm its fingerprint before publishing."
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "Unknown host key for {server}: {actual}. \
+                     Confirm the fingerprint and save it with the destination."
+                ))
+            }
+        }
+
+        let credential = &self.credential;
+        let login = if let Some(password) = &credential.password {
+            session.userauth_password(&self.username, password)
+        } else if let Some(key_path) = &credential.private_key_path {
+            session.userauth_pubkey_file(
+                &self.username,
+                None,
+                Path::new(key_path),
+                credential.passphrase.as_deref(),
+            )
+        } else {
+            return Err(format!(
+                "No SFTP credentials saved for {}@{server}",
+                self.username
+            ));
+        };
+        login.map_err(|e| format!("SFTP login to {server} failed: {e}"))?;
+
+        let sftp = session
+            .sftp()
+            .map_err(|e| format!("Failed to start SFTP on {server}: {e}"))?;
+        Ok((session, sftp))
+    }
+
+    /// Run `op` on the open connection, connecting first if needed
+    fn with_sftp<T>(
+        &mut self,
+        op: impl FnOnce(&Sftp) -> std::io::Result<T>,
+        action: &str,
+    ) -> Result<T, String> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect()?);
+        }
+        let (_, sftp) = self.connection.as_ref().expect("connected above");
+        let result = op(sftp).map_err(|e| format!("Failed to {action}: {e}"));
+        if result.is_err() {
+            self.connection = None;
+        }
+        result
+    }
+}
+
+impl PublishTarget for SftpTarget {
+    fn describe(&self, file_name: &str) -> String {
+        format!(
+            "sftp://{}@{}:{}{}",
+            self.username,
+            self.host,
+            self.port,
+            self.remote_path(file_name).display()
+        )
+    }
+
+    fn write(&mut self, file_name: &str, data: &[u8]) -> Result<(), String> {
+        let path = self.remote_path(file_name);
+        let action = format!("upload {}", self.describe(file_name));
+        self.with_sftp(
+            |sftp| {
+                let mut file = sftp.open_mode(
+                    &path,
+                    OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+                    0o644,
+                    OpenType::File,
+                )?;
+                file.write_all(data)?;
+                Ok(())
+            },
+            &action,
+        )
+    }
+
+    fn read(&mut self, file_name: &str) -> Result<Vec<u8>, String> {
+        let path = self.remote_path(file_name);
+        let action = format!("read back {}", self.describe(file_name));
+        self.with_sftp(
+            |sftp| {
+                let mut data = Vec::new();
+                sftp.open(&path)?.read_to_end(&mut data)?;
+                Ok(data)
+            },
+            &action,
+        )
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let (from_path, to_path) = (self.remote_path(from), self.remote_path(to));
+        let action = format!("rename {}", self.describe(from));
+        self.with_sftp(
+            |sftp| {
+                // SFTP v3 servers refuse to rename over an existing file
+                let _ = sftp.unlink(&to_path);
+                Ok(sftp.rename(&from_path, &to_path, None)?)
+            },
+            &action,
+        )
+    }
+
+    fn remove(&mut self, file_name: &str) -> Result<(), String> {
+        let path = self.remote_path(file_name);
+        let action = format!("delete {}", self.describe(file_name));
+        self.with_sftp(|sftp| Ok(sftp.unlink(&path)?), &action)
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Upload, verify and move into place, retrying failed uploads and mismatched checksums
+fn publish_to(
+    target: &mut dyn PublishTarget,
+    file_name: &str,
+    data: &[u8],
+    retry_delay: Duration,
+) -> Result<PublishReceipt, String> {
+    let expected = sha256_hex(data);
+    let part_name = format!("{file_name}.part");
+    let mut attempt = 1;
+    loop {
+        let uploaded = target
+            .write(&part_name, data)
+            .and_then(|_| target.read(&part_name))
+            .and_then(|uploaded| {
+                let actual = sha256_hex(&uploaded);
+                if actual == expected {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Checksum mismatch after upload: expected {expected}, got {actual}"
+                    ))
+                }
+            });
+        match uploaded {
+            Ok(()) => break,
+            Err(_) if attempt < MAX_ATTEMPTS => {
+                std::thread::sleep(retry_delay * 2u32.pow(attempt - 1));
+                attempt += 1;
+            }
+            Err(e) => {
+                let _ = target.remove(&part_name);
+                return Err(format!(
+                    "Publishing failed after {MAX_ATTEMPTS} attempts: {e}"
+                ));
+            }
+        }
+    }
+    target.rename(&part_name, file_name)?;
+    Ok(PublishReceipt {
+        destination: target.describe(""),
+        path: target.describe(file_name),
+        size: data.len() as u64,
+        sha256: expected,
+        attempts: attempt,
+        webhook: None,
+    })
+}
+
+fn is_unc_path(path: &str) -> bool {
+    let rest = path.strip_prefix(r"\\").or_else(|| path.strip_prefix("//"));
+    rest.and_then(|rest| rest.split(['\\', '/']).next())
+        .is_some_and(|server| !server.is_empty())
+}
+
+fn credential_key(username: &str, host: &str, port: u16) -> String {
+    format!("{username}@{}:{port}", host.to_ascii_lowercase())
+}
+
+fn load_credentials() -> Result<BTreeMap<String, SftpCredential>, String> {
+    let path = get_encrypted_file_path(CREDENTIALS_FILE)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    load_encrypted(&path)
+}
+
+fn open_target(destination: &PublishDestination) -> Result<Box<dyn PublishTarget>, String> {
+    match destination {
+        PublishDestination::Local { path } => {
+            if path.trim().is_empty() {
+                return Err("A destination folder is required".to_string());
+            }
+            Ok(Box::new(LocalTarget {
+                dir: PathBuf::from(path),
+            }))
+        }
+        PublishDestination::Share { path } => {
+            if !is_unc_path(path) {
+                return Err(format!(
+                    "Not a network share: {path}. Use a path like \\\\server\\share\\folder"
+                ));
+            }
+            Ok(Box::new(LocalTarget {
+                dir: PathBuf::from(path),
+            }))
+        }
+        PublishDestination::Sftp {
+            host,
+            port,
+            username,
+            remote_dir,
+            host_fingerprint,
+        } => {
+            if host.trim().is_empty() || username.trim().is_empty() {
+                return Err("An SFTP host and username are required".to_string());
+            }
+            let credential = load_credentials()?
+                .remove(&credential_key(username, host, *port))
+                .unwrap_or_default();
+            Ok(Box::new(SftpTarget {
+                host: host.clone(),
+                port: *port,
+                username: username.clone(),
+                remote_dir: remote_dir.clone(),
+                host_fingerprint: host_fingerprint.clone(),
+                credential,
+                connection: None,
+            }))
+        }
+    }
+}
+
+/// Publish a built package, verifying it by checksum at the destination, then
+/// call the publish webhook. A package stamped with another project's ID is refused.
+#[tauri::command]
+pub async fn publish_package(
+    #[allow(non_snake_case)] projectId: String,
+    #[allow(non_snake_case)] packagePath: String,
+    destination: PublishDestination,
+) -> Result<PublishReceipt, String> {
+    let file_name = Path::new(&packagePath)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Not a package file: {packagePath}"))?
+        .to_string();
+    let data = tokio::fs::read(&packagePath)
+        .await
+        .map_err(|e| format!("Failed to read package {packagePath}: {e}"))?;
+    let version = crate::scorm::package_version::read_package_version(&data)?;
+    if let Some(version) = &version {
+        if version.project_id != projectId {
+            return Err(format!(
+                "{file_name} was built from project {}, not {projectId}",
+                version.project_id
+            ));
+        }
+    }
+    tokio::task::spawn_blocking(move || {
+        let mut target = open_target(&destination)?;
+        let mut receipt = publish_to(target.as_mut(), &file_name, &data, RETRY_DELAY)?;
+        receipt.webhook = notify_published(
+            &projectId,
+            version.as_ref(),
+            &receipt.sha256,
+            receipt.size,
+            &receipt.destination,
+            &receipt.path,
+        );
+        Ok(receipt)
+    })
+    .await
+    .map_err(|e| format!("Publishing stopped unexpectedly: {e}"))?
+}
+
+/// Save the login for an SFTP server. Secrets are kept encrypted and never returned.
+#[tauri::command]
+pub fn save_sftp_credentials(
+    host: String,
+    port: Option<u16>,
+    username: String,
+    credential: SftpCredential,
+) -> Result<(), String> {
+    let has_password = credential
+        .password
+        .as_deref()
+        .is_some_and(|p| !p.is_empty());
+    let has_key = credential
+        .private_key_path
+        .as_deref()
+        .is_some_and(|p| !p.trim().is_empty());
+    if has_password == has_key {
+        return Err("Provide either a password or a private key file".to_string());
+    }
+    let mut credentials = load_credentials()?;
+    credentials.insert(
+        credential_key(&username, &host, port.unwrap_or(default_sftp_port())),
+        credential,
+    );
+    save_encrypted(&get_encrypted_file_path(CREDENTIALS_FILE)?, &credentials)
+}
+
+#[tauri::command]
+pub fn delete_sftp_credentials(
+    host: String,
+    port: Option<u16>,
+    username: String,
+) -> Result<(), String> {
+    let mut credentials = load_credentials()?;
+    let key = credential_key(&username, &host, port.unwrap_or(default_sftp_port()));
+    if credentials.remove(&key).is_some() {
+        save_encrypted(&get_encrypted_file_path(CREDENTIALS_FILE)?, &credentials)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Corrupts the first `corrupt_reads` read-backs
+    struct FlakyTarget {
+        inner: LocalTarget,
+        corrupt_reads: u32,
+    }
+
+    impl PublishTarget for FlakyTarget {
+        fn describe(&self, file_name: &str) -> String {
+            self.inner.describe(file_name)
+        }
+        fn write(&mut self, file_name: &str, data: &[u8]) -> Result<(), String> {
+            self.inner.write(file_name, data)
+        }
+        fn read(&mut self, file_name: &str) -> Result<Vec<u8>, String> {
+            let mut data = self.inner.read(file_name)?;
+            if self.corrupt_reads > 0 {
+                self.corrupt_reads -= 1;
+                data.push(0);
+            }
+            Ok(data)
+        }
+        fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+            self.inner.rename(from, to)
+        }
+        fn remove(&mut self, file_name: &str) -> Result<(), String> {
+            self.inner.remove(file_name)
+        }
+    }
+
+    #[test]
+    fn test_publish_verifies_and_retries_on_checksum_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("courses");
+        let mut target = FlakyTarget {
+            inner: LocalTarget { dir: dir.clone() },
+            corrupt_reads: 1,
+        };
+
+        let receipt = publish_to(&mut target, "course.zip", b"package", Duration::ZERO).unwrap();
+        assert_eq!(receipt.attempts, 2);
+        assert_eq!(receipt.sha256, sha256_hex(b"package"));
+        assert_eq!(fs::read(dir.join("course.zip")).unwrap(), b"package");
+        assert!(!dir.join("course.zip.part").exists());
+
+        target.corrupt_reads = MAX_ATTEMPTS;
+        let error = publish_to(&mut target, "other.zip", b"package", Duration::ZERO).unwrap_err();
+        assert!(error.contains("Checksum mismatch"));
+        assert!(!dir.join("other.zip").exists());
+        assert!(!dir.join("other.zip.part").exists());
+    }
+
+    #[test]
+    fn test_share_destinations_must_be_unc_paths() {
+        assert!(is_unc_path(r"\\lms-content\courses"));
+        assert!(is_unc_path("//lms-content/courses"));
+        assert!(!is_unc_path(r"C:\courses"));
+        assert!(!is_unc_path(r"\\"));
+        let share = PublishDestination::Share {
+            path: "/mnt/courses".to_string(),
+        };
+        assert!(open_target(&share).is_err());
+    }
+}
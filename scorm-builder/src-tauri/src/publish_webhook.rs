@@ -0,0 +1,425 @@
+//! Webhook called after a package is published.
+//!
+//! LMS admins want to hear when a new course version lands on the content
+//! server. With a webhook configured, `publish_package` POSTs the project ID,
+//! course version, package checksum and destination to it once the package
+//! is in place. The body is signed with HMAC-SHA256 under the webhook secret,
+//! sent as `X-Scorm-Builder-Signature: sha256=<hex>`, so the receiver can tell
+//! the call came from the app. URL and secret are kept encrypted next to the
+//! API keys. Failed calls are retried with backoff and every delivery is
+//! written to an audit log in the app config folder. A webhook that can't be
+//! reached never fails the publish itself.
+
+use crate::api_keys::{get_encrypted_file_path, load_encrypted, save_encrypted};
+use crate::scorm::package_version::PackageVersion;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use url::Url;
+
+const WEBHOOK_FILE: &str = "publish_webhook.enc";
+const AUDIT_LOG_FILE: &str = "publish-webhook-log.json";
+/// Oldest deliveries are dropped beyond this many
+const AUDIT_LOG_MAX_ENTRIES: usize = 500;
+/// Calls tried before giving up
+pub const MAX_ATTEMPTS: u32 = 3;
+/// Wait before the first retry, doubled for each one after
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+pub const SIGNATURE_HEADER: &str = "X-Scorm-Builder-Signature";
+pub const PUBLISHED_EVENT: &str = "package.published";
+
+static AUDIT_LOG_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Where to send publish notifications, and the key they are signed with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishWebhook {
+    pub url: String,
+    pub secret: String,
+}
+
+/// The configured webhook as the frontend may see it, without the secret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PublishWebhookInfo {
+    pub url: String,
+}
+
+/// Body of the webhook call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishEvent {
+    pub event: String,
+    pub project_id: String,
+    /// From the package's version.json; None for packages built without one
+    pub course_version: Option<String>,
+    pub build_id: Option<String>,
+    /// Hex SHA-256 of the package as read back from the destination
+    pub sha256: String,
+    pub size: u64,
+    pub destination: String,
+    pub path: String,
+    pub published_at: DateTime<Utc>,
+}
+
+/// How the webhook call went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub url: String,
+    pub delivered: bool,
+    pub attempts: u32,
+    /// HTTP status of the last attempt, when the server answered
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// One delivery in the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub project_id: String,
+    pub sha256: String,
+    pub destination: String,
+    #[serde(flatten)]
+    pub delivery: WebhookDelivery,
+}
+
+/// Sends the webhook request
+pub trait WebhookSender {
+    /// POST `body` as JSON with extra `headers`, returning the response status
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &[u8]) -> Result<u16, String>;
+}
+
+pub struct HttpWebhookSender;
+
+impl WebhookSender for HttpWebhookSender {
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &[u8]) -> Result<u16, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        let mut request = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_vec());
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        let response = request
+            .send()
+            .map_err(|e| format!("Failed to call {url}: {e}"))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// `sha256=<hex>` HMAC of `body` under `secret`
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex(&mac.finalize().into_bytes()))
+}
+
+/// Only https, or http to this machine for testing a receiver
+fn validate_url(url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid webhook URL {url}: {e}"))?;
+    let local = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    match parsed.scheme() {
+        "https" => Ok(()),
+        "http" if local => Ok(()),
+        _ => Err(format!("The webhook URL must use https: {url}")),
+    }
+}
+
+/// Call the webhook with `event`, retrying network errors, 429 and 5xx answers
+pub fn deliver(
+    sender: &dyn WebhookSender,
+    webhook: &PublishWebhook,
+    event: &PublishEvent,
+    retry_delay: Duration,
+) -> WebhookDelivery {
+    let mut delivery = WebhookDelivery {
+        url: webhook.url.clone(),
+        delivered: false,
+        attempts: 0,
+        status: None,
+        error: None,
+    };
+    let body = match serde_json::to_vec(event) {
+        Ok(body) => body,
+        Err(e) => {
+            delivery.error = Some(format!("Failed to serialize webhook body: {e}"));
+            return delivery;
+        }
+    };
+    let headers = [
+        (SIGNATURE_HEADER, signature(&webhook.secret, &body)),
+        ("X-Scorm-Builder-Event", event.event.clone()),
+    ];
+
+    while delivery.attempts < MAX_ATTEMPTS {
+        if delivery.attempts > 0 {
+            std::thread::sleep(retry_delay * 2u32.pow(delivery.attempts - 1));
+        }
+        delivery.attempts += 1;
+        let retry = match sender.post(&webhook.url, &headers, &body) {
+            Ok(status) if (200..300).contains(&status) => {
+                delivery.status = Some(status);
+                delivery.delivered = true;
+                delivery.error = None;
+                return delivery;
+            }
+            Ok(status) => {
+                delivery.status = Some(status);
+                delivery.error = Some(format!("The webhook answered HTTP {status}"));
+                status == 429 || status >= 500
+            }
+            Err(e) => {
+                delivery.status = None;
+                delivery.error = Some(e);
+                true
+            }
+        };
+        if !retry {
+            break;
+        }
+    }
+    delivery
+}
+
+fn webhook_path() -> Result<PathBuf, String> {
+    get_encrypted_file_path(WEBHOOK_FILE)
+}
+
+/// The configured webhook, if there is one
+pub fn load_webhook() -> Result<Option<PublishWebhook>, String> {
+    let path = webhook_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    load_encrypted(&path).map(Some)
+}
+
+fn audit_log_path() -> Result<PathBuf, String> {
+    Ok(crate::settings::get_app_config_dir()?.join(AUDIT_LOG_FILE))
+}
+
+fn load_audit_log(path: &Path) -> Result<Vec<WebhookAuditEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read webhook log: {e}"))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse webhook log: {e}"))
+}
+
+/// Add `entry` to the log at `path`, keeping the newest entries. The log is
+/// replaced through a temporary file so a crash can't leave it half written.
+pub fn append_audit_entry(path: &Path, entry: WebhookAuditEntry) -> Result<(), String> {
+    let _guard = AUDIT_LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries = load_audit_log(path)?;
+    entries.push(entry);
+    let excess = entries.len().saturating_sub(AUDIT_LOG_MAX_ENTRIES);
+    entries.drain(..excess);
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("Failed to serialize webhook log: {e}"))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| format!("Failed to write webhook log: {e}"))?;
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to write webhook log: {e}")
+    })
+}
+
+/// Tell the configured webhook about a published package and log the delivery.
+/// None when no webhook is configured.
+pub fn notify_published(
+    project_id: &str,
+    version: Option<&PackageVersion>,
+    sha256: &str,
+    size: u64,
+    destination: &str,
+    path: &str,
+) -> Option<WebhookDelivery> {
+    let webhook = match load_webhook() {
+        Ok(webhook) => webhook?,
+        Err(e) => {
+            eprintln!("[publish_webhook] Failed to load the webhook: {e}");
+            return None;
+        }
+    };
+    let event = PublishEvent {
+        event: PUBLISHED_EVENT.to_string(),
+        project_id: project_id.to_string(),
+        course_version: version.map(|version| version.course_version.clone()),
+        build_id: version.map(|version| version.build_id.clone()),
+        sha256: sha256.to_string(),
+        size,
+        destination: destination.to_string(),
+        path: path.to_string(),
+        published_at: Utc::now(),
+    };
+    let delivery = deliver(&HttpWebhookSender, &webhook, &event, RETRY_DELAY);
+
+    let entry = WebhookAuditEntry {
+        timestamp: Utc::now(),
+        project_id: event.project_id,
+        sha256: event.sha256,
+        destination: event.destination,
+        delivery: delivery.clone(),
+    };
+    if let Err(e) = audit_log_path().and_then(|log| append_audit_entry(&log, entry)) {
+        eprintln!("[publish_webhook] Failed to log the delivery: {e}");
+    }
+    Some(delivery)
+}
+
+/// Save the webhook called after each publish. The secret is kept encrypted
+/// and never returned.
+#[tauri::command]
+pub fn save_publish_webhook(url: String, secret: String) -> Result<(), String> {
+    validate_url(&url)?;
+    if secret.trim().is_empty() {
+        return Err("A webhook secret is required to sign the calls".to_string());
+    }
+    save_encrypted(
+        &webhook_path()?,
+        &PublishWebhook {
+            url: url.trim().to_string(),
+            secret,
+        },
+    )
+}
+
+#[tauri::command]
+pub fn get_publish_webhook() -> Result<Option<PublishWebhookInfo>, String> {
+    Ok(load_webhook()?.map(|webhook| PublishWebhookInfo { url: webhook.url }))
+}
+
+#[tauri::command]
+pub fn delete_publish_webhook() -> Result<(), String> {
+    let path = webhook_path()?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete the webhook: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Webhook deliveries, newest first (the last 50 by default)
+#[tauri::command]
+pub fn get_publish_webhook_log(limit: Option<usize>) -> Result<Vec<WebhookAuditEntry>, String> {
+    let _guard = AUDIT_LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries = load_audit_log(&audit_log_path()?)?;
+    entries.reverse();
+    entries.truncate(limit.unwrap_or(50));
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    /// Answers with `responses` in turn, recording the signature it was sent
+    struct ScriptedSender {
+        responses: RefCell<Vec<Result<u16, String>>>,
+        signatures: RefCell<Vec<String>>,
+    }
+
+    impl WebhookSender for ScriptedSender {
+        fn post(&self, _url: &str, headers: &[(&str, String)], _body: &[u8]) -> Result<u16, String> {
+            let signature = headers.iter().find(|(name, _)| *name == SIGNATURE_HEADER).unwrap();
+            self.signatures.borrow_mut().push(signature.1.clone());
+            self.responses.borrow_mut().remove(0)
+        }
+    }
+
+    fn sender(responses: Vec<Result<u16, String>>) -> ScriptedSender {
+        ScriptedSender {
+            responses: RefCell::new(responses),
+            signatures: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn event() -> PublishEvent {
+        PublishEvent {
+            event: PUBLISHED_EVENT.to_string(),
+            project_id: "1700000000000".to_string(),
+            course_version: Some("1.2".to_string()),
+            build_id: Some("20240101-093000-0123abcd".to_string()),
+            sha256: "ab".repeat(32),
+            size: 1024,
+            destination: r"\\lms-content\courses".to_string(),
+            path: r"\\lms-content\courses\safety.zip".to_string(),
+            published_at: Utc::now(),
+        }
+    }
+
+    fn webhook() -> PublishWebhook {
+        PublishWebhook {
+            url: "https://lms.example.com/hooks/courses".to_string(),
+            secret: "s3cret".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_delivery_is_signed_and_retried_until_accepted() {
+        let sender = sender(vec![Err("connection reset".to_string()), Ok(503), Ok(204)]);
+        let event = event();
+
+        let delivery = deliver(&sender, &webhook(), &event, Duration::ZERO);
+
+        assert!(delivery.delivered);
+        assert_eq!(delivery.attempts, 3);
+        assert_eq!(delivery.status, Some(204));
+        assert_eq!(delivery.error, None);
+        let expected = signature("s3cret", &serde_json::to_vec(&event).unwrap());
+        assert!(expected.starts_with("sha256="));
+        assert!(sender.signatures.borrow().iter().all(|sent| *sent == expected));
+
+        // Client errors are not retried
+        let sender = self::sender(vec![Ok(401), Ok(204)]);
+        let delivery = deliver(&sender, &webhook(), &event, Duration::ZERO);
+        assert!(!delivery.delivered);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.error.as_deref(), Some("The webhook answered HTTP 401"));
+    }
+
+    #[test]
+    fn test_audit_log_keeps_newest_entries_and_urls_need_https() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+        let delivery = deliver(&sender(vec![Ok(200)]), &webhook(), &event(), Duration::ZERO);
+        for index in 0..AUDIT_LOG_MAX_ENTRIES + 2 {
+            let entry = WebhookAuditEntry {
+                timestamp: Utc::now(),
+                project_id: index.to_string(),
+                sha256: String::new(),
+                destination: String::new(),
+                delivery: delivery.clone(),
+            };
+            append_audit_entry(&path, entry).unwrap();
+        }
+
+        let entries = load_audit_log(&path).unwrap();
+        assert_eq!(entries.len(), AUDIT_LOG_MAX_ENTRIES);
+        assert_eq!(entries[0].project_id, "2");
+        assert!(entries[0].delivery.delivered);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        assert!(validate_url("https://lms.example.com/hook").is_ok());
+        assert!(validate_url("http://localhost:8080/hook").is_ok());
+        assert!(validate_url("http://lms.example.com/hook").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+}
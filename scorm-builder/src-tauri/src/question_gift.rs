@@ -0,0 +1,755 @@
+//! Question bank import and export in Moodle's GIFT format.
+//!
+//! GIFT keeps one question per paragraph: an optional `::title::`, the
+//! question text and an answer block in braces. Multiple choice
+//! (`{=right ~wrong}`), true/false (`{T}`) and short answer (`{=answer}`)
+//! questions map to our multiple-choice, true-false and fill-in-the-blank
+//! types; the title carries the bank id, so a bank exported, edited in Moodle
+//! and imported again updates its questions instead of duplicating them.
+//! Essay, matching and numeric questions and partial credit have no
+//! equivalent here and are skipped with a warning.
+
+use crate::media_usage::find_project_file;
+use crate::project_storage::{load_project_file, save_project_file};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Characters with a meaning in GIFT, written with a backslash in text
+const SPECIAL_CHARS: [char; 6] = ['~', '=', '#', '{', '}', ':'];
+
+/// Stands for the answer of a missing-word question (`text {=word} text`)
+const BLANK: &str = "_____";
+
+/// Text formats a question may declare; others are read as plain text too
+const PLAIN_FORMATS: [&str; 2] = ["plain", "moodle"];
+
+/// A bank exported as GIFT
+#[derive(Debug, Default, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GiftExport {
+    pub gift: String,
+    pub exported: usize,
+    /// Questions left out, and parts of questions GIFT can't hold
+    pub warnings: Vec<String>,
+}
+
+/// What importing a GIFT file changed
+#[derive(Debug, Default, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct GiftImportResult {
+    /// Ids of the questions added to the bank
+    pub added: Vec<String>,
+    /// Ids of bank questions replaced by a question titled with their id
+    pub updated: Vec<String>,
+    /// Questions left out, and parts of questions that could not be kept
+    pub warnings: Vec<String>,
+    /// False for a dry run
+    pub applied: bool,
+}
+
+/// A question read from GIFT, in the shape the course content stores
+#[derive(Debug, Clone, PartialEq)]
+struct GiftQuestion {
+    title: Option<String>,
+    question: Value,
+    /// Parts of the question that were dropped
+    notes: Vec<String>,
+}
+
+/// One `=` or `~` answer of an answer block
+#[derive(Debug, Clone, PartialEq)]
+struct GiftAnswer {
+    correct: bool,
+    /// Percentage after the marker, as in `~%50%`
+    weight: Option<f64>,
+    text: String,
+    feedback: Option<String>,
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            '\\' => escaped.push_str("\\\\"),
+            _ if SPECIAL_CHARS.contains(&c) => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().copied()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                plain.push('\n');
+            }
+            ('\\', Some(next)) if next == '\\' || SPECIAL_CHARS.contains(&next) => {
+                chars.next();
+                plain.push(next);
+            }
+            _ => plain.push(c),
+        }
+    }
+    plain.trim().to_string()
+}
+
+/// Byte offset of the first `pattern` in `text` that isn't escaped
+fn find_unescaped(text: &str, pattern: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if text[index..].starts_with(pattern) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// `text` split at every unescaped `separator`
+fn split_unescaped(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(index) = find_unescaped(rest, &separator.to_string()) {
+        parts.push(&rest[..index]);
+        rest = &rest[index + separator.len_utf8()..];
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Paragraphs of a GIFT file with the line each starts on, leaving out
+/// comments and category lines
+fn question_blocks(gift: &str) -> Vec<(usize, String)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for (index, line) in gift.trim_start_matches('\u{feff}').lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("//") || trimmed.starts_with("$CATEGORY:") {
+            continue;
+        }
+        if trimmed.is_empty() {
+            blocks.extend(current.take());
+            continue;
+        }
+        let (_, block) = current.get_or_insert_with(|| (index + 1, String::new()));
+        if !block.is_empty() {
+            block.push('\n');
+        }
+        block.push_str(line);
+    }
+    blocks.extend(current);
+    blocks
+}
+
+/// The `=` and `~` answers of an answer block
+fn parse_answers(body: &str) -> Result<Vec<GiftAnswer>, String> {
+    let mut starts = Vec::new();
+    let mut escaped = false;
+    for (index, c) in body.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '=' || c == '~' {
+            starts.push(index);
+        }
+    }
+    if !starts.first().is_some_and(|&first| body[..first].trim().is_empty()) {
+        return Err("its answers don't start with = or ~".to_string());
+    }
+
+    let ends = starts.iter().skip(1).copied().chain([body.len()]);
+    starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| {
+            let correct = body[start..].starts_with('=');
+            let mut answer = &body[start + 1..end];
+            if find_unescaped(answer, "->").is_some() {
+                return Err("matching questions aren't supported".to_string());
+            }
+            let mut weight = None;
+            if let Some(rest) = answer.trim_start().strip_prefix('%') {
+                let (percent, rest) = rest
+                    .split_once('%')
+                    .ok_or_else(|| "an answer weight has no closing %".to_string())?;
+                weight = Some(
+                    percent
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| format!("'{percent}' isn't an answer weight"))?,
+                );
+                answer = rest;
+            }
+            let mut parts = split_unescaped(answer, '#').into_iter();
+            let text = unescape(parts.next().unwrap_or_default());
+            let feedback = parts
+                .next()
+                .map(unescape)
+                .filter(|feedback| !feedback.is_empty());
+            Ok(GiftAnswer {
+                correct,
+                weight,
+                text,
+                feedback,
+            })
+        })
+        .collect()
+}
+
+fn feedback_value(correct: Option<String>, incorrect: Option<String>) -> Option<Value> {
+    (correct.is_some() || incorrect.is_some()).then(|| {
+        json!({
+            "correct": correct.unwrap_or_default(),
+            "incorrect": incorrect.unwrap_or_default(),
+        })
+    })
+}
+
+/// Question fields of a `{T}` / `{FALSE#...#...}` answer block, or `None`
+/// when the block isn't a true/false one
+fn true_false(body: &str) -> Option<Map<String, Value>> {
+    let mut parts = split_unescaped(body, '#').into_iter();
+    let answer = match parts.next()?.trim().to_ascii_uppercase().as_str() {
+        "T" | "TRUE" => "true",
+        "F" | "FALSE" => "false",
+        _ => return None,
+    };
+    // The first feedback is shown for the wrong answer, the second for the right one
+    let mut feedback = parts.map(unescape).map(|text| Some(text).filter(|text| !text.is_empty()));
+    let incorrect = feedback.next().flatten();
+    let correct = feedback.next().flatten();
+
+    let mut fields = Map::new();
+    fields.insert("type".to_string(), json!("true-false"));
+    fields.insert("correctAnswer".to_string(), json!(answer));
+    if let Some(feedback) = feedback_value(correct, incorrect) {
+        fields.insert("feedback".to_string(), feedback);
+    }
+    Some(fields)
+}
+
+/// Question fields of a block of `=` and `~` answers
+fn choice_or_short_answer(
+    body: &str,
+    notes: &mut Vec<String>,
+) -> Result<Map<String, Value>, String> {
+    let answers = parse_answers(body)?;
+    let partial_credit = answers.iter().any(|answer| match answer.weight {
+        Some(weight) if answer.correct => (weight - 100.0).abs() > f64::EPSILON,
+        Some(weight) => weight.abs() > f64::EPSILON,
+        None => false,
+    });
+    let mut fields = Map::new();
+
+    if answers.iter().all(|answer| answer.correct) {
+        // Only right answers: a short answer question, of which we keep the first
+        let (first, others) = answers.split_first().ok_or("it has no answers")?;
+        if partial_credit {
+            return Err("answers with partial credit aren't supported".to_string());
+        }
+        if !others.is_empty() {
+            notes.push(format!(
+                "only the first accepted answer '{}' was kept",
+                first.text
+            ));
+        }
+        fields.insert("type".to_string(), json!("fill-in-the-blank"));
+        fields.insert("correctAnswer".to_string(), json!(first.text));
+        if let Some(feedback) = feedback_value(first.feedback.clone(), None) {
+            fields.insert("feedback".to_string(), feedback);
+        }
+        return Ok(fields);
+    }
+
+    if partial_credit {
+        return Err("answers with partial credit aren't supported".to_string());
+    }
+    let right: Vec<&GiftAnswer> = answers.iter().filter(|answer| answer.correct).collect();
+    let [right] = right.as_slice() else {
+        return Err(format!(
+            "multiple choice questions need one right answer, and it has {}",
+            right.len()
+        ));
+    };
+    let wrong_feedback: Vec<&String> = answers
+        .iter()
+        .filter(|answer| !answer.correct)
+        .filter_map(|answer| answer.feedback.as_ref())
+        .collect();
+    if wrong_feedback.iter().any(|feedback| *feedback != wrong_feedback[0]) {
+        notes.push("only the feedback of the first wrong answer with any was kept".to_string());
+    }
+
+    fields.insert("type".to_string(), json!("multiple-choice"));
+    fields.insert(
+        "options".to_string(),
+        json!(answers.iter().map(|answer| &answer.text).collect::<Vec<_>>()),
+    );
+    fields.insert("correctAnswer".to_string(), json!(right.text));
+    let incorrect = wrong_feedback.first().map(|feedback| feedback.to_string());
+    if let Some(feedback) = feedback_value(right.feedback.clone(), incorrect) {
+        fields.insert("feedback".to_string(), feedback);
+    }
+    Ok(fields)
+}
+
+/// One paragraph of a GIFT file as a bank question; `Err` gives the reason
+/// it was skipped
+fn parse_question(block: &str) -> Result<GiftQuestion, String> {
+    let open = find_unescaped(block, "{").ok_or("it has no answer block")?;
+    let close = open
+        + find_unescaped(&block[open..], "}").ok_or("its answer block has no closing }")?;
+    let mut before = block[..open].trim();
+    let after = unescape(&block[close + 1..]);
+    let mut notes = Vec::new();
+
+    let mut title = None;
+    if let Some(rest) = before.strip_prefix("::") {
+        let end = find_unescaped(rest, "::").ok_or("its title has no closing ::")?;
+        title = Some(unescape(&rest[..end])).filter(|title| !title.is_empty());
+        before = rest[end + 2..].trim_start();
+    }
+    if let Some(rest) = before.strip_prefix('[') {
+        if let Some((markup, rest)) = rest.split_once(']') {
+            if !PLAIN_FORMATS.contains(&markup) {
+                notes.push(format!("its {markup} formatting was kept as plain text"));
+            }
+            before = rest.trim_start();
+        }
+    }
+
+    let mut text = unescape(before);
+    if !after.is_empty() {
+        text = format!("{text} {BLANK} {after}").trim().to_string();
+    }
+    if text.is_empty() {
+        return Err("it has no question text".to_string());
+    }
+
+    let mut body = &block[open + 1..close];
+    let mut explanation = None;
+    if let Some(index) = find_unescaped(body, "####") {
+        explanation = Some(unescape(&body[index + 4..])).filter(|text| !text.is_empty());
+        body = &body[..index];
+    }
+    let body = body.trim();
+    if body.is_empty() {
+        return Err("essay questions aren't supported".to_string());
+    }
+    if body.starts_with('#') {
+        return Err("numeric questions aren't supported".to_string());
+    }
+
+    let mut fields = match true_false(body) {
+        Some(fields) => fields,
+        None => choice_or_short_answer(body, &mut notes)?,
+    };
+    fields.insert("question".to_string(), json!(text));
+    if let Some(explanation) = explanation {
+        fields.insert("explanation".to_string(), json!(explanation));
+    }
+    Ok(GiftQuestion {
+        title,
+        question: Value::Object(fields),
+        notes,
+    })
+}
+
+/// Questions of a GIFT file, with warnings for the ones skipped and the
+/// parts dropped, labelled with the line their question starts on
+fn parse_gift(gift: &str) -> (Vec<GiftQuestion>, Vec<String>) {
+    let mut questions = Vec::new();
+    let mut warnings = Vec::new();
+    for (line, block) in question_blocks(gift) {
+        match parse_question(&block) {
+            Ok(question) => {
+                warnings.extend(
+                    question
+                        .notes
+                        .iter()
+                        .map(|note| format!("Question at line {line}: {note}")),
+                );
+                questions.push(question);
+            }
+            Err(reason) => warnings.push(format!("Question at line {line} was skipped: {reason}")),
+        }
+    }
+    (questions, warnings)
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// `#feedback` of an answer, or nothing
+fn answer_feedback(feedback: Option<&str>) -> String {
+    feedback.map(|text| format!("#{}", escape(text))).unwrap_or_default()
+}
+
+/// Answer block lines of a bank question; `Err` gives the reason it can't be
+/// exported
+fn gift_answers(question: &Value, notes: &mut Vec<String>) -> Result<Vec<String>, String> {
+    let correct_feedback = question.pointer("/feedback/correct").and_then(|v| v.as_str());
+    let incorrect_feedback = question.pointer("/feedback/incorrect").and_then(|v| v.as_str());
+    let correct_feedback = correct_feedback.filter(|text| !text.trim().is_empty());
+    let incorrect_feedback = incorrect_feedback.filter(|text| !text.trim().is_empty());
+    let correct_answer = question.get("correctAnswer").unwrap_or(&Value::Null);
+
+    match question.get("type").and_then(|v| v.as_str()).unwrap_or("multiple-choice") {
+        "multiple-choice" => {
+            let options: Vec<&str> = question
+                .get("options")
+                .and_then(|v| v.as_array())
+                .map(|options| options.iter().filter_map(|o| o.as_str()).collect())
+                .unwrap_or_default();
+            let right = match correct_answer {
+                Value::Number(index) => index.as_u64().map(|index| index as usize),
+                Value::String(answer) => options.iter().position(|option| option == answer),
+                _ => None,
+            }
+            .filter(|&index| index < options.len())
+            .ok_or("its right answer isn't one of its options")?;
+            Ok(options
+                .iter()
+                .enumerate()
+                .map(|(index, option)| {
+                    if index == right {
+                        format!("={}{}", escape(option), answer_feedback(correct_feedback))
+                    } else {
+                        format!("~{}{}", escape(option), answer_feedback(incorrect_feedback))
+                    }
+                })
+                .collect())
+        }
+        "true-false" => {
+            let answer = match correct_answer {
+                Value::Bool(answer) => *answer,
+                Value::String(answer) => answer.eq_ignore_ascii_case("true"),
+                _ => return Err("its right answer isn't true or false".to_string()),
+            };
+            let mut line = if answer { "TRUE" } else { "FALSE" }.to_string();
+            if correct_feedback.is_some() || incorrect_feedback.is_some() {
+                line.push_str(&answer_feedback(Some(incorrect_feedback.unwrap_or_default())));
+                line.push_str(&answer_feedback(correct_feedback));
+            }
+            Ok(vec![line])
+        }
+        "fill-in-the-blank" => {
+            let answer = correct_answer
+                .as_str()
+                .filter(|answer| !answer.trim().is_empty())
+                .ok_or("it has no answer")?;
+            if incorrect_feedback.is_some() {
+                notes.push("GIFT short answers have no feedback for wrong answers".to_string());
+            }
+            Ok(vec![format!("={}{}", escape(answer), answer_feedback(correct_feedback))])
+        }
+        other => Err(format!("{other} questions have no GIFT equivalent")),
+    }
+}
+
+/// The question bank entries of course content as GIFT
+fn bank_to_gift(questions: &[Value]) -> GiftExport {
+    let mut export = GiftExport::default();
+    let mut paragraphs = Vec::new();
+    for (index, question) in questions.iter().enumerate() {
+        let label = str_field(question, "id")
+            .map(|id| format!("Question '{id}'"))
+            .unwrap_or_else(|| format!("Question {}", index + 1));
+        let mut notes = Vec::new();
+        let answers = match gift_answers(question, &mut notes) {
+            Ok(answers) => answers,
+            Err(reason) => {
+                export.warnings.push(format!("{label} was skipped: {reason}"));
+                continue;
+            }
+        };
+        if question.pointer("/feedback/correctMedia").is_some()
+            || question.pointer("/feedback/incorrectMedia").is_some()
+        {
+            notes.push("feedback media can't be exported".to_string());
+        }
+
+        let text = if question.get("type").and_then(|v| v.as_str()) == Some("fill-in-the-blank") {
+            str_field(question, "blank").or_else(|| str_field(question, "question"))
+        } else {
+            str_field(question, "question").or_else(|| str_field(question, "text"))
+        }
+        .unwrap_or_default();
+
+        let mut paragraph = String::new();
+        if let Some(id) = str_field(question, "id") {
+            paragraph.push_str(&format!("::{}::", escape(id)));
+        }
+        paragraph.push_str(&escape(text));
+        paragraph.push_str(" {\n");
+        for answer in answers {
+            paragraph.push_str(&format!("\t{answer}\n"));
+        }
+        if let Some(explanation) = str_field(question, "explanation") {
+            paragraph.push_str(&format!("\t####{}\n", escape(explanation)));
+        }
+        paragraph.push('}');
+        paragraphs.push(paragraph);
+        export
+            .warnings
+            .extend(notes.into_iter().map(|note| format!("{label}: {note}")));
+    }
+    export.exported = paragraphs.len();
+    export.gift = paragraphs.join("\n\n");
+    if !export.gift.is_empty() {
+        export.gift.push('\n');
+    }
+    export
+}
+
+/// Ids bank questions may take from GIFT titles: the ones our exports write
+fn is_plain_id(title: &str) -> bool {
+    !title.is_empty()
+        && title
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Add the questions of a GIFT file to the question bank of `course_content`.
+/// A question titled with the id of a bank question replaces it.
+fn merge_gift(course_content: &mut Value, gift: &str) -> Result<GiftImportResult, String> {
+    let (questions, warnings) = parse_gift(gift);
+    if questions.is_empty() && warnings.is_empty() {
+        return Err("The GIFT file has no questions".to_string());
+    }
+    let mut result = GiftImportResult {
+        warnings,
+        ..Default::default()
+    };
+
+    let content = course_content
+        .as_object_mut()
+        .ok_or("Course content is not an object")?;
+    let bank = content
+        .entry("questionBank")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !bank.is_array() {
+        *bank = Value::Array(Vec::new());
+    }
+    let bank = bank.as_array_mut().expect("question bank is an array");
+
+    let mut seen = HashSet::new();
+    for GiftQuestion { title, mut question, .. } in questions {
+        let title = title.filter(|title| is_plain_id(title));
+        let id = match title {
+            Some(title) if seen.contains(&title) => {
+                result.warnings.push(format!(
+                    "The title '{title}' is used more than once; later questions were added as new ones"
+                ));
+                format!("question-{}", Uuid::new_v4())
+            }
+            Some(title) => title,
+            None => format!("question-{}", Uuid::new_v4()),
+        };
+        seen.insert(id.clone());
+        question["id"] = json!(id);
+
+        match bank.iter_mut().find(|existing| str_field(existing, "id") == Some(id.as_str())) {
+            Some(existing) => {
+                // Keep what GIFT doesn't carry, such as author notes and feedback media
+                let media: Map<String, Value> = existing
+                    .get("feedback")
+                    .and_then(|feedback| feedback.as_object())
+                    .map(|feedback| {
+                        feedback
+                            .iter()
+                            .filter(|(key, _)| key.ends_with("Media"))
+                            .map(|(key, value)| (key.clone(), value.clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if let (Some(existing), Some(fields)) = (existing.as_object_mut(), question.as_object()) {
+                    for key in ["options", "blank", "explanation", "feedback"] {
+                        existing.remove(key);
+                    }
+                    existing.extend(fields.clone());
+                    if !media.is_empty() {
+                        let feedback = existing
+                            .entry("feedback")
+                            .or_insert_with(|| json!({ "correct": "", "incorrect": "" }));
+                        if let Some(feedback) = feedback.as_object_mut() {
+                            feedback.extend(media);
+                        }
+                    }
+                }
+                result.updated.push(id);
+            }
+            None => {
+                bank.push(question);
+                result.added.push(id);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Export the question bank of a project as GIFT
+#[tauri::command]
+pub fn export_questions_gift(#[allow(non_snake_case)] projectId: String) -> Result<GiftExport, String> {
+    let project_path =
+        find_project_file(&projectId).ok_or_else(|| "Project file not found".to_string())?;
+    let project = load_project_file(&project_path)?;
+    let course_content = project
+        .course_content
+        .ok_or_else(|| "Project has no course content yet".to_string())?;
+    let questions = course_content
+        .get("questionBank")
+        .and_then(|bank| bank.as_array())
+        .filter(|bank| !bank.is_empty())
+        .ok_or_else(|| "Project has no question bank yet".to_string())?;
+    Ok(bank_to_gift(questions))
+}
+
+/// Add the questions of a GIFT file to a project's question bank.
+/// With `dryRun` the result is reported without saving.
+#[tauri::command]
+pub fn import_questions_gift(
+    #[allow(non_snake_case)] projectId: String,
+    gift: String,
+    #[allow(non_snake_case)] dryRun: Option<bool>,
+) -> Result<GiftImportResult, String> {
+    let project_path =
+        find_project_file(&projectId).ok_or_else(|| "Project file not found".to_string())?;
+    let mut project = load_project_file(&project_path)?;
+    let course_content = project
+        .course_content
+        .as_mut()
+        .ok_or_else(|| "Project has no course content yet".to_string())?;
+
+    let mut result = merge_gift(course_content, &gift)?;
+    if !dryRun.unwrap_or(false) {
+        save_project_file(&project, &project_path)?;
+        result.applied = true;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIFT: &str = r#"// Exported from Moodle
+$CATEGORY: $course$/Safety
+
+::ladders::Ladders need how many points of contact? {
+    =Three#Right, three at all times.
+    ~Two#Count hands and feet.
+    ~One#Count hands and feet.
+    ####Two hands and a foot, or two feet and a hand.
+}
+
+::Q2::Gloves protect against cuts.{T#They do protect.#Yes.}
+
+The colour of a fire blanket case is {=red =Red} in most workplaces.
+
+::Essay::Describe a near miss.{}
+
+Pick the best{=A ~%50%B ~C}
+"#;
+
+    #[test]
+    fn test_gift_questions_map_to_bank_questions() {
+        let (questions, warnings) = parse_gift(GIFT);
+
+        assert_eq!(questions.len(), 3);
+        let ladders = &questions[0];
+        assert_eq!(ladders.title.as_deref(), Some("ladders"));
+        assert_eq!(
+            ladders.question,
+            json!({
+                "type": "multiple-choice",
+                "question": "Ladders need how many points of contact?",
+                "options": ["Three", "Two", "One"],
+                "correctAnswer": "Three",
+                "feedback": { "correct": "Right, three at all times.", "incorrect": "Count hands and feet." },
+                "explanation": "Two hands and a foot, or two feet and a hand."
+            })
+        );
+        assert_eq!(questions[1].question["type"], "true-false");
+        assert_eq!(questions[1].question["correctAnswer"], "true");
+        assert_eq!(questions[1].question["feedback"]["incorrect"], "They do protect.");
+        assert_eq!(
+            questions[2].question["question"],
+            "The colour of a fire blanket case is _____ in most workplaces."
+        );
+        assert_eq!(questions[2].question["correctAnswer"], "red");
+
+        assert_eq!(warnings.len(), 3, "{warnings:?}");
+        assert!(warnings[0].starts_with("Question at line 13: only the first accepted answer"));
+        assert_eq!(warnings[1], "Question at line 15 was skipped: essay questions aren't supported");
+        assert!(warnings[2].contains("partial credit"));
+    }
+
+    #[test]
+    fn test_exported_bank_imports_back_onto_the_same_questions() {
+        let mut course_content = json!({
+            "topics": [],
+            "questionBank": [
+                {
+                    "id": "ladders",
+                    "type": "multiple-choice",
+                    "question": "Ladders: how many {points} of contact?",
+                    "options": ["Two", "Three"],
+                    "correctAnswer": "Three",
+                    "feedback": { "correct": "Yes", "incorrect": "No", "incorrectMedia": { "type": "image", "url": "image-1" } },
+                    "authorNotes": "From the 2023 audit"
+                },
+                { "id": "gloves", "type": "true-false", "question": "Gloves = protection", "correctAnswer": "false" },
+                { "id": "drag", "type": "drag-and-drop", "question": "Sort these" }
+            ]
+        });
+
+        let export = bank_to_gift(course_content["questionBank"].as_array().unwrap());
+        assert_eq!(export.exported, 2);
+        assert!(export.gift.contains(r"::ladders::Ladders\: how many \{points\} of contact? {"));
+        assert!(export.gift.contains("\t~Two#No\n\t=Three#Yes\n"));
+        assert_eq!(export.warnings.len(), 2, "{:?}", export.warnings);
+        assert!(export.warnings[0].contains("feedback media"));
+        assert!(export.warnings[1].starts_with("Question 'drag' was skipped"));
+
+        let edited = export.gift.replace("=Three#Yes", "=Three#Yes, three").replace("FALSE", "TRUE");
+        let result = merge_gift(&mut course_content, &edited).unwrap();
+        assert_eq!(result.updated, vec!["ladders", "gloves"]);
+        assert!(result.added.is_empty());
+
+        let bank = course_content["questionBank"].as_array().unwrap();
+        assert_eq!(bank.len(), 3);
+        assert_eq!(bank[0]["question"], "Ladders: how many {points} of contact?");
+        assert_eq!(bank[0]["feedback"]["correct"], "Yes, three");
+        assert_eq!(bank[0]["feedback"]["incorrectMedia"]["url"], "image-1");
+        assert_eq!(bank[0]["authorNotes"], "From the 2023 audit");
+        assert_eq!(bank[1]["correctAnswer"], "true");
+
+        let result = merge_gift(&mut course_content, "What is PPE? {=Protective equipment}").unwrap();
+        assert_eq!(result.added.len(), 1);
+        assert!(result.added[0].starts_with("question-"));
+        assert!(merge_gift(&mut course_content, "// nothing here\n").is_err());
+    }
+}
@@ -5,7 +5,7 @@ import { join } from 'path'
 describe('SCORM Navigation - Assessment Feedback', () => {
   it('should display custom feedback messages from data attributes', () => {
     // Read the navigation.js template
-    const jsPath = join(__dirname, '../templates/navigation.js.hbs')
+    const jsPath = join(__dirname, '../runtime/navigation.js')
     const jsContent = readFileSync(jsPath, 'utf-8')
     
     // Check if the showFeedback function exists
@@ -21,7 +21,7 @@ describe('SCORM Navigation - Assessment Feedback', () => {
   })
 
   it('should properly extract feedback from question container attributes', () => {
-    const jsPath = join(__dirname, '../templates/navigation.js.hbs')
+    const jsPath = join(__dirname, '../runtime/navigation.js')
     const jsContent = readFileSync(jsPath, 'utf-8')
     
     // Find the submitAssessment function
@@ -38,7 +38,7 @@ describe('SCORM Navigation - Assessment Feedback', () => {
   })
 
   it('should display feedback in the feedback div after submission', () => {
-    const jsPath = join(__dirname, '../templates/navigation.js.hbs')
+    const jsPath = join(__dirname, '../runtime/navigation.js')
     const jsContent = readFileSync(jsPath, 'utf-8')
     
     // Check if feedback is displayed in the correct element
@@ -5,7 +5,7 @@ import { join } from 'path'
 describe('SCORM Navigation - Autoplay Button State', () => {
   it('should update play/pause button when audio autoplays successfully', () => {
     // Read the actual navigation.js template
-    const jsPath = join(__dirname, '../templates/navigation.js.hbs')
+    const jsPath = join(__dirname, '../runtime/navigation.js')
     const jsContent = readFileSync(jsPath, 'utf-8')
     
     // Check if the autoplay code updates the play/pause button
@@ -25,7 +25,7 @@ describe('SCORM Navigation - Autoplay Button State', () => {
   })
 
   it('should have proper promise handling for audio autoplay', () => {
-    const jsPath = join(__dirname, '../templates/navigation.js.hbs')
+    const jsPath = join(__dirname, '../runtime/navigation.js')
     const jsContent = readFileSync(jsPath, 'utf-8')
     
     // Check for proper promise chain
@@ -34,7 +34,7 @@ describe('SCORM Navigation - Autoplay Button State', () => {
   })
 
   it('should maintain play button state when autoplay is blocked', () => {
-    const jsPath = join(__dirname, '../templates/navigation.js.hbs')
+    const jsPath = join(__dirname, '../runtime/navigation.js')
     const jsContent = readFileSync(jsPath, 'utf-8')
     
     // Check that catch block doesn't incorrectly update button state
@@ -8,7 +8,7 @@ describe('SCORM 1.2 Compliance in Navigation Template', () => {
 
   beforeEach(() => {
     // Read the navigation template
-    const templatePath = join(__dirname, '../templates/navigation.js.hbs')
+    const templatePath = join(__dirname, '../runtime/navigation.js')
     navigationTemplate = readFileSync(templatePath, 'utf-8')
   })
 
@@ -8,7 +8,7 @@ import * as fs from 'fs'
 import * as path from 'path'
 
 describe('SCORM Navigation Template Issues', () => {
-  const templatePath = path.join(__dirname, '..', 'templates', 'navigation.js.hbs')
+  const templatePath = path.join(__dirname, '..', 'runtime', 'navigation.js')
   let templateContent: string
 
   beforeAll(() => {
@@ -0,0 +1,224 @@
+//! Optional usage analytics for LMSs without reporting of their own.
+//!
+//! `index.html` includes the `analytics` partial, which loads
+//! `scripts/analytics.js` with the tracker settings as data attributes (no
+//! inline script, so it also works under the strict CSP). The script loads the
+//! Matomo or Google Analytics tracker and reports a page view for every course
+//! page `navigation.js` shows.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use ts_rs::TS;
+
+/// Origins gtag.js is loaded from and reports to
+const GOOGLE_ANALYTICS_SOURCES: [&str; 3] = [
+    "https://www.googletagmanager.com",
+    "https://*.google-analytics.com",
+    "https://*.analytics.google.com",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum AnalyticsProvider {
+    Matomo,
+    /// Google Analytics 4 via gtag.js
+    Ga,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AnalyticsSettings {
+    pub provider: AnalyticsProvider,
+    /// Base URL of the Matomo instance, e.g. `https://stats.example.com/`.
+    /// Not used for Google Analytics.
+    #[ts(optional)]
+    pub endpoint: Option<String>,
+    /// Matomo site id or GA measurement id (`G-...`)
+    pub site_id: String,
+    /// Ask the tracker to drop the last octets of the learner's IP (default
+    /// true). Matomo anonymizes on the server, so this only affects GA.
+    #[ts(optional)]
+    pub anonymize_ip: Option<bool>,
+    /// Track without cookies or other browser storage (default true)
+    #[ts(optional)]
+    pub disable_cookies: Option<bool>,
+    /// Send nothing when the browser asks not to be tracked (default true)
+    #[ts(optional)]
+    pub respect_do_not_track: Option<bool>,
+}
+
+impl AnalyticsSettings {
+    /// Check the settings describe a tracker the package can load
+    pub fn validate(&self) -> Result<(), String> {
+        let site_id = self.site_id.trim();
+        match self.provider {
+            AnalyticsProvider::Matomo => {
+                self.matomo_endpoint()?;
+                if site_id.is_empty() || !site_id.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(format!("Invalid Matomo site id: '{site_id}'"));
+                }
+            }
+            AnalyticsProvider::Ga => {
+                let valid = site_id.starts_with("G-")
+                    && site_id.len() > 2
+                    && site_id[2..].chars().all(|c| c.is_ascii_alphanumeric());
+                if !valid {
+                    return Err(format!(
+                        "Invalid Google Analytics measurement id: '{site_id}'"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The Matomo base URL, which must be https and end with a slash
+    fn matomo_endpoint(&self) -> Result<url::Url, String> {
+        let endpoint = self
+            .endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+            .ok_or("Matomo analytics needs the URL of the Matomo instance")?;
+        let mut url = url::Url::parse(endpoint)
+            .map_err(|e| format!("Invalid analytics endpoint '{endpoint}': {e}"))?;
+        if url.scheme() != "https" {
+            return Err(format!("Analytics endpoint must use https: '{endpoint}'"));
+        }
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
+        }
+        url.set_query(None);
+        url.set_fragment(None);
+        Ok(url)
+    }
+
+    /// Origins the tracker needs in `script-src` and `connect-src`
+    pub fn csp_sources(&self) -> Vec<String> {
+        match self.provider {
+            AnalyticsProvider::Matomo => self
+                .matomo_endpoint()
+                .map(|url| vec![url.origin().ascii_serialization()])
+                .unwrap_or_default(),
+            AnalyticsProvider::Ga => GOOGLE_ANALYTICS_SOURCES
+                .iter()
+                .map(|source| source.to_string())
+                .collect(),
+        }
+    }
+
+    /// Data for the `analytics` partial
+    pub fn template_data(&self) -> Value {
+        let endpoint = match self.provider {
+            AnalyticsProvider::Matomo => self.matomo_endpoint().map(String::from).ok(),
+            AnalyticsProvider::Ga => None,
+        };
+        json!({
+            "provider": self.provider,
+            "endpoint": endpoint,
+            "site_id": self.site_id.trim(),
+            "anonymize_ip": self.anonymize_ip.unwrap_or(true),
+            "disable_cookies": self.disable_cookies.unwrap_or(true),
+            "respect_do_not_track": self.respect_do_not_track.unwrap_or(true),
+        })
+    }
+}
+
+/// `policy` with `sources` allowed in `script-src` and `connect-src`
+pub fn csp_with_sources(policy: &str, sources: &[String]) -> String {
+    extend_directives(policy, &["script-src", "connect-src"], sources)
+}
+
+/// `policy` with `sources` allowed in `connect-src` only, for endpoints the
+/// package posts to without loading scripts from them
+pub fn csp_with_connect_sources(policy: &str, sources: &[String]) -> String {
+    extend_directives(policy, &["connect-src"], sources)
+}
+
+fn extend_directives(policy: &str, names: &[&str], sources: &[String]) -> String {
+    if sources.is_empty() {
+        return policy.to_string();
+    }
+    policy
+        .split("; ")
+        .map(|directive| {
+            let name = directive.split(' ').next().unwrap_or_default();
+            if names.contains(&name) {
+                format!("{directive} {}", sources.join(" "))
+            } else {
+                directive.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matomo(endpoint: &str, site_id: &str) -> AnalyticsSettings {
+        AnalyticsSettings {
+            provider: AnalyticsProvider::Matomo,
+            endpoint: Some(endpoint.to_string()),
+            site_id: site_id.to_string(),
+            anonymize_ip: None,
+            disable_cookies: None,
+            respect_do_not_track: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        assert!(matomo("https://stats.example.com/matomo", "3")
+            .validate()
+            .is_ok());
+        assert!(matomo("http://stats.example.com/", "3").validate().is_err());
+        assert!(matomo("https://stats.example.com/", "abc")
+            .validate()
+            .is_err());
+        assert!(matomo("", "3").validate().is_err());
+
+        let ga = |site_id: &str| AnalyticsSettings {
+            provider: AnalyticsProvider::Ga,
+            endpoint: None,
+            ..matomo("", site_id)
+        };
+        assert!(ga("G-ABC123").validate().is_ok());
+        assert!(ga("UA-1234-1").validate().is_err());
+        assert!(ga("G-\"><script>").validate().is_err());
+    }
+
+    #[test]
+    fn test_template_data_normalizes_endpoint_and_defaults_flags() {
+        let data = matomo("https://stats.example.com/matomo?x=1", " 3 ").template_data();
+        assert_eq!(data["provider"], "matomo");
+        assert_eq!(data["endpoint"], "https://stats.example.com/matomo/");
+        assert_eq!(data["site_id"], "3");
+        assert_eq!(data["anonymize_ip"], true);
+        assert_eq!(data["disable_cookies"], true);
+        assert_eq!(data["respect_do_not_track"], true);
+    }
+
+    #[test]
+    fn test_csp_allows_tracker_origin() {
+        let sources = matomo("https://stats.example.com:8443/matomo/", "3").csp_sources();
+        assert_eq!(sources, vec!["https://stats.example.com:8443"]);
+
+        let policy = csp_with_sources(
+            "default-src 'self'; script-src 'self'; connect-src 'self'",
+            &sources,
+        );
+        assert_eq!(
+            policy,
+            "default-src 'self'; script-src 'self' https://stats.example.com:8443; connect-src 'self' https://stats.example.com:8443"
+        );
+
+        assert_eq!(
+            csp_with_connect_sources("script-src 'self'; connect-src 'self'", &sources),
+            "script-src 'self'; connect-src 'self' https://stats.example.com:8443"
+        );
+    }
+}
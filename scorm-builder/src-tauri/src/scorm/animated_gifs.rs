@@ -0,0 +1,169 @@
+//! Animated GIFs packaged as video.
+//!
+//! Authors paste screen recordings in as GIFs, which run to tens of megabytes
+//! and can't be paused. They're spotted when stored, and a build with
+//! `convert_animated_gifs` set re-encodes them as MP4 or WebM (see
+//! `media_transform`). A page image whose GIF was packaged as video
+//! (`media/image-3.mp4` in place of `media/image-3.gif`) then renders as a
+//! `<video>` with controls instead of an `<img>`.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Animated GIFs at least this big get a warning when stored
+pub const LARGE_ANIMATED_GIF_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Video formats an animated GIF can be packaged as
+pub const GIF_VIDEO_FORMATS: &[&str] = &["mp4", "webm"];
+
+/// Length of a colour table announced by a GIF's packed flags byte
+fn color_table_len(flags: u8) -> usize {
+    if flags & 0x80 != 0 {
+        3 << ((flags & 0x07) + 1)
+    } else {
+        0
+    }
+}
+
+/// Position after the data sub-blocks starting at `pos`
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *data.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            return Some(pos);
+        }
+        pos += len;
+    }
+}
+
+/// Number of frames in a GIF, or 0 when `data` isn't one. A truncated file
+/// counts the frames found before it ends.
+pub fn gif_frame_count(data: &[u8]) -> usize {
+    if !data.starts_with(b"GIF87a") && !data.starts_with(b"GIF89a") {
+        return 0;
+    }
+    let Some(&screen_flags) = data.get(10) else {
+        return 0;
+    };
+
+    let mut pos = 13 + color_table_len(screen_flags);
+    let mut frames = 0;
+    while let Some(&block) = data.get(pos) {
+        let next = match block {
+            // Extension: label, then sub-blocks
+            0x21 => skip_sub_blocks(data, pos + 2),
+            // Image descriptor: position, size and flags, an optional local
+            // colour table, the LZW code size, then sub-blocks
+            0x2C => {
+                frames += 1;
+                data.get(pos + 9)
+                    .and_then(|&flags| skip_sub_blocks(data, pos + 10 + color_table_len(flags) + 1))
+            }
+            // Trailer, or something that isn't GIF data
+            _ => None,
+        };
+        match next {
+            Some(next) => pos = next,
+            None => break,
+        }
+    }
+    frames
+}
+
+pub fn is_animated_gif(data: &[u8]) -> bool {
+    gif_frame_count(data) > 1
+}
+
+/// Page GIFs packaged as video, found from the packaged files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GifVideos {
+    videos: HashMap<String, String>,
+}
+
+impl GifVideos {
+    /// Every `{stem}.mp4` or `{stem}.webm` packaged without a `{stem}.gif`
+    /// beside it; only page images linking to that GIF look it up
+    pub fn from_media_files(media_files: &HashMap<String, Vec<u8>>) -> Self {
+        let mut videos = HashMap::new();
+        for path in media_files.keys() {
+            let Some((stem, ext)) = path.rsplit_once('.') else {
+                continue;
+            };
+            if !GIF_VIDEO_FORMATS.contains(&ext.to_ascii_lowercase().as_str()) {
+                continue;
+            }
+            let gif = format!("{stem}.gif");
+            if !media_files.contains_key(&gif) {
+                videos.insert(gif, path.clone());
+            }
+        }
+        Self { videos }
+    }
+
+    /// `{url, type}` of the video an image linking to `path` should play
+    pub fn template_video(&self, path: &str) -> Option<Value> {
+        let video = self.videos.get(path)?;
+        let mime = if video.to_ascii_lowercase().ends_with(".webm") {
+            "video/webm"
+        } else {
+            "video/mp4"
+        };
+        Some(json!({ "url": video, "type": mime }))
+    }
+}
+
+/// A 1x1 GIF with `frames` frames, each behind a graphic control extension
+#[cfg(test)]
+pub(crate) fn test_gif(frames: usize) -> Vec<u8> {
+    let mut data = b"GIF89a".to_vec();
+    data.extend([1, 0, 1, 0, 0x80, 0, 0]);
+    data.extend([0, 0, 0, 255, 255, 255]);
+    for _ in 0..frames {
+        data.extend([0x21, 0xF9, 4, 0, 10, 0, 0, 0]);
+        data.extend([0x2C, 0, 0, 0, 0, 1, 0, 1, 0, 0]);
+        data.extend([2, 2, 0x44, 0x01, 0]);
+    }
+    data.push(0x3B);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_are_counted() {
+        assert_eq!(gif_frame_count(&test_gif(1)), 1);
+        assert_eq!(gif_frame_count(&test_gif(12)), 12);
+        assert!(!is_animated_gif(&test_gif(1)));
+        assert!(is_animated_gif(&test_gif(2)));
+
+        let truncated = test_gif(3);
+        assert_eq!(gif_frame_count(&truncated[..truncated.len() - 8]), 3);
+        assert_eq!(gif_frame_count(b"\x89PNG\r\n\x1a\n"), 0);
+        assert_eq!(gif_frame_count(b"GIF89a"), 0);
+    }
+
+    #[test]
+    fn test_gif_videos_only_stand_in_for_missing_gifs() {
+        let media = HashMap::from([
+            ("media/image-3.mp4".to_string(), vec![1]),
+            ("media/image-4.webm".to_string(), vec![1]),
+            ("media/image-5.gif".to_string(), vec![1]),
+            ("media/image-5.mp4".to_string(), vec![1]),
+        ]);
+
+        let videos = GifVideos::from_media_files(&media);
+
+        assert_eq!(
+            videos.template_video("media/image-3.gif"),
+            Some(json!({ "url": "media/image-3.mp4", "type": "video/mp4" }))
+        );
+        assert_eq!(
+            videos.template_video("media/image-4.gif"),
+            Some(json!({ "url": "media/image-4.webm", "type": "video/webm" }))
+        );
+        assert_eq!(videos.template_video("media/image-5.gif"), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! `scripts/scorm-api-discovery.js`: finds the LMS API for `scorm-api.js`.
+//!
+//! Implements the standard ADL search: walk up `window.parent` from the course
+//! frame, then from `window.opener` (courses launched in a popup), looking for
+//! SCORM 1.2's `API` and SCORM 2004's `API_1484_11` in every window. Some LMSs
+//! only attach the API after the course frame starts loading, so a failed
+//! search is retried a few times before the course falls back to standalone mode.
+
+/// Where the script goes in the package, loaded right before `scorm-api.js`
+pub const API_DISCOVERY_PATH: &str = "scripts/scorm-api-discovery.js";
+
+/// Parent frames searched from each starting window when the request doesn't say
+pub const DEFAULT_API_SEARCH_DEPTH: u32 = 10;
+
+/// Upper bound for `api_search_depth`; the ADL sample search gives up at 500
+pub const MAX_API_SEARCH_DEPTH: u32 = 500;
+
+/// Searches after the first one before giving up
+const RETRY_ATTEMPTS: u32 = 5;
+
+const RETRY_INTERVAL_MS: u32 = 500;
+
+pub const API_DISCOVERY_TEMPLATE: &str = r#"/*!
+ * SCORM API discovery - generated by SCORM Builder
+ */
+(function() {
+    'use strict';
+
+    var MAX_DEPTH = {{max_depth}};
+    var RETRY_ATTEMPTS = {{retry_attempts}};
+    var RETRY_INTERVAL_MS = {{retry_interval_ms}};
+
+    // The API object in one window, checking both SCORM versions
+    function apiIn(win) {
+        try {
+            if (win.API) {
+                return { api: win.API, version: '1.2' };
+            }
+            if (win.API_1484_11) {
+                return { api: win.API_1484_11, version: '2004' };
+            }
+        } catch (error) {
+            // Cross-origin frames throw on property access
+        }
+        return null;
+    }
+
+    // Walk up the parent chain of win, at most MAX_DEPTH frames
+    function searchParents(win) {
+        var depth = 0;
+        while (win) {
+            var found = apiIn(win);
+            if (found) {
+                found.depth = depth;
+                return found;
+            }
+            var parent = null;
+            try {
+                parent = win.parent;
+            } catch (error) {
+                return null;
+            }
+            if (!parent || parent === win || depth >= MAX_DEPTH) {
+                return null;
+            }
+            win = parent;
+            depth++;
+        }
+        return null;
+    }
+
+    function openerOf(win) {
+        try {
+            return win.opener || null;
+        } catch (error) {
+            return null;
+        }
+    }
+
+    function find(win) {
+        win = win || window;
+        var found = searchParents(win);
+        if (found) {
+            return found;
+        }
+        var opener = openerOf(win);
+        if (!opener) {
+            try {
+                opener = win.top ? openerOf(win.top) : null;
+            } catch (error) {
+                opener = null;
+            }
+        }
+        return opener ? searchParents(opener) : null;
+    }
+
+    // Call back with the API once found, or with null after the last retry
+    function findWithRetry(callback, win) {
+        var attempt = 0;
+        (function search() {
+            var found = find(win);
+            if (found || attempt >= RETRY_ATTEMPTS) {
+                callback(found);
+                return;
+            }
+            attempt++;
+            setTimeout(search, RETRY_INTERVAL_MS);
+        })();
+    }
+
+    window.SCORMApiDiscovery = {
+        maxDepth: MAX_DEPTH,
+        find: find,
+        findWithRetry: findWithRetry
+    };
+})();
+"#;
+
+/// The discovery script for a package searching `max_depth` parent frames
+pub fn render_api_discovery_js(max_depth: Option<u32>) -> String {
+    let max_depth = max_depth
+        .unwrap_or(DEFAULT_API_SEARCH_DEPTH)
+        .clamp(1, MAX_API_SEARCH_DEPTH);
+    API_DISCOVERY_TEMPLATE
+        .replace("{{max_depth}}", &max_depth.to_string())
+        .replace("{{retry_attempts}}", &RETRY_ATTEMPTS.to_string())
+        .replace("{{retry_interval_ms}}", &RETRY_INTERVAL_MS.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_fills_every_placeholder() {
+        let js = render_api_discovery_js(None);
+        assert!(!js.contains("{{"));
+        assert!(js.contains(&format!("var MAX_DEPTH = {DEFAULT_API_SEARCH_DEPTH};")));
+        assert!(js.contains("var RETRY_ATTEMPTS = 5;"));
+        assert!(js.contains("window.SCORMApiDiscovery"));
+
+        assert!(render_api_discovery_js(Some(3)).contains("var MAX_DEPTH = 3;"));
+        assert!(render_api_discovery_js(Some(0)).contains("var MAX_DEPTH = 1;"));
+        assert!(render_api_discovery_js(Some(10_000)).contains("var MAX_DEPTH = 500;"));
+    }
+
+    #[test]
+    fn test_searches_both_apis_parents_and_opener() {
+        let js = render_api_discovery_js(None);
+        assert!(js.contains("win.API_1484_11"));
+        assert!(js.contains("win.API"));
+        assert!(js.contains("win.parent"));
+        assert!(js.contains("win.opener"));
+        assert!(js.contains("setTimeout(search, RETRY_INTERVAL_MS)"));
+    }
+}
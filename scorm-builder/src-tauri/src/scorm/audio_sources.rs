@@ -0,0 +1,145 @@
+//! Alternative encodings of page narration.
+//!
+//! Narration is recorded as MP3, which some browsers still found in LMS
+//! deployments can't play. When an Ogg or WebM encoding is packaged beside it
+//! (`media/audio-0.ogg` next to `media/audio-0.mp3`), the audio player lists
+//! every encoding as a `<source>` so the browser picks the first it supports,
+//! and the manifest lists each file.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Extensions of fallback encodings, in the order browsers are offered them
+pub const FALLBACK_EXTENSIONS: &[&str] = &["ogg", "oga", "opus", "webm"];
+
+fn extension(path: &str) -> String {
+    path.rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// MIME type for the `type` attribute of an audio `<source>`
+pub fn audio_mime_type(path: &str) -> Option<&'static str> {
+    match extension(path).as_str() {
+        "mp3" => Some("audio/mpeg"),
+        "m4a" | "aac" => Some("audio/mp4"),
+        "wav" => Some("audio/wav"),
+        "ogg" | "oga" => Some("audio/ogg"),
+        "opus" => Some("audio/ogg; codecs=opus"),
+        "webm" => Some("audio/webm"),
+        _ => None,
+    }
+}
+
+/// Packaged narration files with the other encodings packaged beside each
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioSources {
+    fallbacks: HashMap<String, Vec<String>>,
+}
+
+impl AudioSources {
+    /// Group the audio in `media_files` by path without extension. Files with a
+    /// fallback extension are only ever alternatives to another file.
+    pub fn from_media_files(media_files: &HashMap<String, Vec<u8>>) -> Self {
+        let mut fallbacks = HashMap::new();
+        for path in media_files.keys() {
+            let ext = extension(path);
+            if audio_mime_type(path).is_none() || FALLBACK_EXTENSIONS.contains(&ext.as_str()) {
+                continue;
+            }
+            let stem = &path[..path.len() - ext.len()];
+            let alternatives: Vec<String> = FALLBACK_EXTENSIONS
+                .iter()
+                .map(|fallback| format!("{stem}{fallback}"))
+                .filter(|alternative| media_files.contains_key(alternative))
+                .collect();
+            fallbacks.insert(path.clone(), alternatives);
+        }
+        Self { fallbacks }
+    }
+
+    /// `path` followed by its other encodings, or nothing when `path` isn't packaged
+    pub fn files(&self, path: &str) -> Vec<String> {
+        self.fallbacks
+            .get(path)
+            .map(|alternatives| {
+                std::iter::once(path.to_string())
+                    .chain(alternatives.iter().cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `<source>` entries for the audio player; `None` when `path` has a single
+    /// encoding and the player can link to it directly
+    pub fn template_sources(&self, path: &str) -> Option<Vec<Value>> {
+        let files = self.files(path);
+        if files.len() < 2 {
+            return None;
+        }
+        Some(
+            files
+                .iter()
+                .map(|file| json!({ "src": file, "type": audio_mime_type(file) }))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media(paths: &[&str]) -> HashMap<String, Vec<u8>> {
+        paths
+            .iter()
+            .map(|path| (path.to_string(), vec![1]))
+            .collect()
+    }
+
+    #[test]
+    fn test_groups_fallback_encodings_with_their_narration() {
+        let sources = AudioSources::from_media_files(&media(&[
+            "media/audio-0.mp3",
+            "media/audio-0.webm",
+            "media/audio-0.ogg",
+            "media/audio-1.mp3",
+            "media/image-0.ogg.png",
+            "media/caption-0.vtt",
+        ]));
+
+        assert_eq!(
+            sources.files("media/audio-0.mp3"),
+            vec![
+                "media/audio-0.mp3",
+                "media/audio-0.ogg",
+                "media/audio-0.webm"
+            ]
+        );
+        assert_eq!(
+            sources.files("media/audio-1.mp3"),
+            vec!["media/audio-1.mp3"]
+        );
+        assert!(sources.files("media/audio-0.ogg").is_empty());
+        assert!(sources.files("media/audio-2.mp3").is_empty());
+        assert!(sources.files("media/caption-0.vtt").is_empty());
+    }
+
+    #[test]
+    fn test_template_sources_only_for_several_encodings() {
+        let sources = AudioSources::from_media_files(&media(&[
+            "media/audio-0.mp3",
+            "media/audio-0.ogg",
+            "media/audio-1.mp3",
+        ]));
+
+        assert_eq!(
+            sources.template_sources("media/audio-0.mp3"),
+            Some(vec![
+                json!({ "src": "media/audio-0.mp3", "type": "audio/mpeg" }),
+                json!({ "src": "media/audio-0.ogg", "type": "audio/ogg" }),
+            ])
+        );
+        assert_eq!(sources.template_sources("media/audio-1.mp3"), None);
+    }
+}
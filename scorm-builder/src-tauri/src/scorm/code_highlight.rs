@@ -0,0 +1,66 @@
+//! Optional syntax highlighting for code samples.
+//!
+//! Pages whose content has `<pre><code>` blocks get the vendored highlight.js
+//! build and `scripts/code-highlight.js`, which highlights the blocks in the
+//! browser (using a `language-*` class when the author set one). The token
+//! colours are part of `main.css`, themed to match the course. Courses without
+//! code ship neither.
+
+use super::generator_enhanced::GenerateScormRequest;
+
+/// Whether `html` has a `<pre>` element whose content starts with `<code>`
+pub fn contains_code_block(html: &str) -> bool {
+    let html = html.to_ascii_lowercase();
+    html.match_indices("<pre").any(|(start, _)| {
+        let tag = &html[start + "<pre".len()..];
+        if !tag.starts_with(|c: char| c == '>' || c.is_ascii_whitespace()) {
+            return false;
+        }
+        tag.find('>')
+            .is_some_and(|end| tag[end + 1..].trim_start().starts_with("<code"))
+    })
+}
+
+/// Whether any page of the course contains a code block, which decides if the
+/// package ships highlight.js
+pub fn course_uses_code(request: &GenerateScormRequest) -> bool {
+    let welcome = request.welcome_page.iter().map(|w| w.content.as_str());
+    let topics = request.topics.iter().map(|t| t.content.as_str());
+    welcome.chain(topics).any(contains_code_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::Topic;
+
+    #[test]
+    fn test_contains_code_block() {
+        assert!(contains_code_block("<pre><code>let x = 1;</code></pre>"));
+        assert!(contains_code_block(
+            "<PRE class=\"sample\">\n  <code class=\"language-rust\">fn main() {}</code></PRE>"
+        ));
+        assert!(!contains_code_block("<p>Run <code>cargo build</code></p>"));
+        assert!(!contains_code_block("<pre>plain preformatted text</pre>"));
+        assert!(!contains_code_block("<preview><code>x</code></preview>"));
+    }
+
+    #[test]
+    fn test_course_uses_code_checks_pages() {
+        let mut request = GenerateScormRequest {
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Loops".to_string(),
+                content: "<p>Loops repeat work.</p>".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(!course_uses_code(&request));
+
+        request.topics[0]
+            .content
+            .push_str("<pre><code>for i in 0..3 {}</code></pre>");
+        assert!(course_uses_code(&request));
+    }
+}
@@ -0,0 +1,252 @@
+//! Authoring checks run before a package is built.
+//!
+//! Page images need a text alternative and video players an accessible name.
+//! Without `alt_text` the templates fall back to the media title, which is
+//! often just a file name, so every image lacking one is reported. Issues are
+//! warnings unless the request sets `require_alt_text`.
+//!
+//! Media credited to someone else should also say under which license it is
+//! used, so attributed media without a license is reported too.
+
+use serde::Serialize;
+
+use super::generator_enhanced::{GenerateScormRequest, MediaItem};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    /// Image without alt text
+    MissingAltText,
+    /// Video with neither alt text nor a title to name its player
+    UnlabelledPlayer,
+    /// Media credited to an author or provider without the license it is used under
+    MissingLicense,
+}
+
+impl LintRule {
+    /// Whether the rule is about naming media for screen readers
+    pub fn is_accessibility(self) -> bool {
+        matches!(self, Self::MissingAltText | Self::UnlabelledPlayer)
+    }
+}
+
+/// A media item that fails a lint rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    /// `welcome`, `objectives` or the topic ID, prefixed with the language of a variant
+    pub page_id: String,
+    pub media_id: String,
+    pub rule: LintRule,
+}
+
+fn is_blank(text: Option<&str>) -> bool {
+    text.unwrap_or_default().trim().is_empty()
+}
+
+fn name_rule(item: &MediaItem) -> Option<LintRule> {
+    if !is_blank(item.alt_text.as_deref()) {
+        return None;
+    }
+    match item.media_type.as_str() {
+        "image" => Some(LintRule::MissingAltText),
+        "video" | "youtube" if is_blank(Some(&item.title)) => Some(LintRule::UnlabelledPlayer),
+        _ => None,
+    }
+}
+
+fn license_rule(item: &MediaItem) -> Option<LintRule> {
+    item.attribution
+        .as_ref()
+        .filter(|attribution| !attribution.has_license())
+        .map(|_| LintRule::MissingLicense)
+}
+
+fn lint_media_item(item: &MediaItem) -> impl Iterator<Item = LintRule> {
+    name_rule(item).into_iter().chain(license_rule(item))
+}
+
+fn lint_pages(request: &GenerateScormRequest, prefix: &str, issues: &mut Vec<LintIssue>) {
+    let welcome = request
+        .welcome_page
+        .as_ref()
+        .map(|page| ("welcome", page.media.as_ref()));
+    let objectives = request
+        .learning_objectives_page
+        .as_ref()
+        .map(|page| ("objectives", page.media.as_ref()));
+    let topics = request
+        .topics
+        .iter()
+        .map(|topic| (topic.id.as_str(), topic.media.as_ref()));
+
+    for (page_id, media) in welcome.into_iter().chain(objectives).chain(topics) {
+        for item in media.into_iter().flatten() {
+            for rule in lint_media_item(item) {
+                issues.push(LintIssue {
+                    page_id: format!("{prefix}{page_id}"),
+                    media_id: item.id.clone(),
+                    rule,
+                });
+            }
+        }
+    }
+}
+
+/// Lint every page of `request` and of its language variants
+pub fn lint_course(request: &GenerateScormRequest) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    lint_pages(request, "", &mut issues);
+    for variant in request.language_variants.iter().flatten() {
+        lint_pages(
+            &variant.course,
+            &format!("{}/", variant.language),
+            &mut issues,
+        );
+    }
+    issues
+}
+
+/// Refuse to build when `require_alt_text` is set and any image or player is unnamed
+pub fn enforce_alt_text(
+    request: &GenerateScormRequest,
+    issues: &[LintIssue],
+) -> Result<(), String> {
+    let unnamed: Vec<_> = issues
+        .iter()
+        .filter(|issue| issue.rule.is_accessibility())
+        .collect();
+    if !request.require_alt_text.unwrap_or(false) || unnamed.is_empty() {
+        return Ok(());
+    }
+    let media = unnamed
+        .iter()
+        .map(|issue| format!("{} on {}", issue.media_id, issue.page_id))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(format!(
+        "Failed to generate package: alt text is required but missing for {media}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media_storage::MediaAttribution;
+    use crate::scorm::generator_enhanced::Topic;
+
+    fn media(id: &str, media_type: &str, title: &str, alt_text: Option<&str>) -> MediaItem {
+        MediaItem {
+            id: id.to_string(),
+            media_type: media_type.to_string(),
+            url: format!("media/{id}.bin"),
+            title: title.to_string(),
+            embed_url: None,
+            is_youtube: None,
+            clip_start: None,
+            clip_end: None,
+            alt_text: alt_text.map(str::to_string),
+            attribution: None,
+        }
+    }
+
+    fn request() -> GenerateScormRequest {
+        GenerateScormRequest {
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                media: Some(vec![
+                    media("image-1", "image", "chart.png", None),
+                    media("image-2", "image", "", Some("Sales by quarter")),
+                    media("image-3", "image", "", Some("   ")),
+                    media("video-1", "video", "Intro", None),
+                    media("video-2", "video", "", None),
+                ]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reports_images_without_alt_text_and_unnamed_players() {
+        let found: Vec<_> = lint_course(&request())
+            .into_iter()
+            .map(|issue| (issue.page_id, issue.media_id, issue.rule))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "topic-1".to_string(),
+                    "image-1".to_string(),
+                    LintRule::MissingAltText
+                ),
+                (
+                    "topic-1".to_string(),
+                    "image-3".to_string(),
+                    LintRule::MissingAltText
+                ),
+                (
+                    "topic-1".to_string(),
+                    "video-2".to_string(),
+                    LintRule::UnlabelledPlayer
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alt_text_is_only_enforced_when_required() {
+        let mut request = request();
+        let issues = lint_course(&request);
+        assert!(enforce_alt_text(&request, &issues).is_ok());
+
+        request.require_alt_text = Some(true);
+        let error = enforce_alt_text(&request, &issues).unwrap_err();
+        assert!(error.contains("image-1 on topic-1"));
+        assert!(enforce_alt_text(&request, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_reports_attributed_media_without_license() {
+        let credited = |id: &str, license: Option<&str>| MediaItem {
+            attribution: Some(MediaAttribution {
+                provider: "google".to_string(),
+                license: license.map(str::to_string),
+                ..Default::default()
+            }),
+            ..media(id, "image", "", Some("Harbour at dawn"))
+        };
+        let mut request = GenerateScormRequest {
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                media: Some(vec![
+                    credited("image-1", None),
+                    credited("image-2", Some("CC BY 4.0")),
+                    credited("image-3", Some(" ")),
+                    media("image-4", "image", "", Some("Own photo")),
+                ]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let issues = lint_course(&request);
+        let found: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.media_id.as_str(), issue.rule))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("image-1", LintRule::MissingLicense),
+                ("image-3", LintRule::MissingLicense)
+            ]
+        );
+
+        // A missing license is a warning even when alt text is required
+        request.require_alt_text = Some(true);
+        assert!(enforce_alt_text(&request, &issues).is_ok());
+    }
+}
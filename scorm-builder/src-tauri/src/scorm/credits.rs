@@ -0,0 +1,170 @@
+//! The credits page listing who made the course's media.
+//!
+//! Media picked from stock providers carries its author and license in an
+//! [`MediaAttribution`]; when any packaged media does, a `credits` page is
+//! added after the assessment listing each item once, with links only where
+//! they are plain http(s) URLs.
+
+use serde::Serialize;
+
+use super::generator_enhanced::{GenerateScormRequest, MediaItem};
+use crate::media_storage::MediaAttribution;
+
+/// One credited media item
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Credit {
+    pub media_id: String,
+    pub title: String,
+    /// Title of the first page the media appears on
+    pub page_title: String,
+    pub author: Option<String>,
+    pub author_url: Option<String>,
+    pub source_url: Option<String>,
+    pub license: Option<String>,
+    pub license_url: Option<String>,
+}
+
+fn web_link(url: Option<&String>) -> Option<String> {
+    url.map(|url| url.trim())
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+        .map(str::to_string)
+}
+
+fn credit(item: &MediaItem, attribution: &MediaAttribution, page_title: &str) -> Credit {
+    let text = |value: Option<&String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    Credit {
+        media_id: item.id.clone(),
+        title: item.title.clone(),
+        page_title: page_title.to_string(),
+        author: text(attribution.author.as_ref()),
+        author_url: web_link(attribution.author_url.as_ref()),
+        source_url: web_link(attribution.source_url.as_ref()),
+        license: text(attribution.license.as_ref()),
+        license_url: web_link(attribution.license_url.as_ref()),
+    }
+}
+
+/// Credits for every attributed media item, in page order
+pub fn course_credits(request: &GenerateScormRequest) -> Vec<Credit> {
+    let welcome = request
+        .welcome_page
+        .as_ref()
+        .map(|page| (page.title.as_str(), page.media.as_ref()));
+    let objectives = request
+        .learning_objectives_page
+        .as_ref()
+        .map(|page| ("Learning Objectives", page.media.as_ref()));
+    let topics = request
+        .topics
+        .iter()
+        .map(|topic| (topic.title.as_str(), topic.media.as_ref()));
+
+    let mut credits: Vec<Credit> = Vec::new();
+    for (page_title, media) in welcome.into_iter().chain(objectives).chain(topics) {
+        for item in media.into_iter().flatten() {
+            let Some(attribution) = &item.attribution else {
+                continue;
+            };
+            if credits.iter().all(|credit| credit.media_id != item.id) {
+                credits.push(credit(item, attribution, page_title));
+            }
+        }
+    }
+    credits
+}
+
+/// Whether the package gets a credits page
+pub fn has_credits(request: &GenerateScormRequest) -> bool {
+    !course_credits(request).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::Topic;
+
+    fn media(id: &str, attribution: Option<MediaAttribution>) -> MediaItem {
+        MediaItem {
+            id: id.to_string(),
+            media_type: "image".to_string(),
+            url: format!("media/{id}.jpg"),
+            title: format!("Photo {id}"),
+            embed_url: None,
+            is_youtube: None,
+            clip_start: None,
+            clip_end: None,
+            alt_text: None,
+            attribution,
+        }
+    }
+
+    fn unsplash() -> MediaAttribution {
+        MediaAttribution {
+            provider: "unsplash".to_string(),
+            author: Some("Ada Lovelace".to_string()),
+            author_url: Some("https://unsplash.com/@ada".to_string()),
+            source_url: Some("javascript:alert(1)".to_string()),
+            license: Some("Unsplash License".to_string()),
+            license_url: Some("https://unsplash.com/license".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_lists_attributed_media_once_in_page_order() {
+        let request = GenerateScormRequest {
+            topics: vec![
+                Topic {
+                    id: "topic-1".to_string(),
+                    title: "Harbours".to_string(),
+                    media: Some(vec![
+                        media("image-1", Some(unsplash())),
+                        media("image-2", None),
+                    ]),
+                    ..Default::default()
+                },
+                Topic {
+                    id: "topic-2".to_string(),
+                    title: "Ships".to_string(),
+                    media: Some(vec![
+                        media("image-3", Some(unsplash())),
+                        media("image-1", Some(unsplash())),
+                    ]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let credits = course_credits(&request);
+
+        let listed: Vec<_> = credits
+            .iter()
+            .map(|credit| (credit.media_id.as_str(), credit.page_title.as_str()))
+            .collect();
+        assert_eq!(listed, [("image-1", "Harbours"), ("image-3", "Ships")]);
+        assert_eq!(credits[0].author.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(credits[0].source_url, None);
+        assert_eq!(
+            credits[0].license_url.as_deref(),
+            Some("https://unsplash.com/license")
+        );
+    }
+
+    #[test]
+    fn test_no_credits_page_without_attributions() {
+        let request = GenerateScormRequest {
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Harbours".to_string(),
+                media: Some(vec![media("image-1", None)]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(!has_credits(&request));
+    }
+}
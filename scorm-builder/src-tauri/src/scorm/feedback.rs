@@ -0,0 +1,135 @@
+//! Optional "Report a problem with this page" widget.
+//!
+//! `index.html` includes the `feedback` partial, the button and form, which
+//! loads `scripts/feedback.js` with the endpoint as data attributes. A report
+//! (course, version, page and the learner's comment, never who the learner
+//! is) is posted as JSON to the webhook; the webhook must accept cross-origin
+//! requests from the LMS. When there is no webhook, or posting fails, the
+//! learner's email app opens with the report instead.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use ts_rs::TS;
+
+/// Longest comment a learner can send, in characters
+pub const MAX_COMMENT_LENGTH: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FeedbackSettings {
+    /// https URL reports are posted to
+    #[ts(optional)]
+    pub webhook_url: Option<String>,
+    /// Address reports are mailed to when there is no webhook or it fails
+    #[ts(optional)]
+    pub email: Option<String>,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+impl FeedbackSettings {
+    /// Check reports have somewhere to go
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(email) = non_empty(&self.email) {
+            let valid = email.split_once('@').is_some_and(|(user, domain)| {
+                !user.is_empty() && domain.contains('.') && !domain.contains('@')
+            }) && !email.contains(|c: char| c.is_whitespace() || "<>\"?&".contains(c));
+            if !valid {
+                return Err(format!("Invalid feedback email address: '{email}'"));
+            }
+        }
+        match self.webhook()? {
+            Some(_) => Ok(()),
+            None if non_empty(&self.email).is_some() => Ok(()),
+            None => Err("Problem reports need a webhook URL or an email address".to_string()),
+        }
+    }
+
+    fn webhook(&self) -> Result<Option<url::Url>, String> {
+        let Some(webhook) = non_empty(&self.webhook_url) else {
+            return Ok(None);
+        };
+        let url = url::Url::parse(webhook)
+            .map_err(|e| format!("Invalid feedback webhook '{webhook}': {e}"))?;
+        if url.scheme() != "https" {
+            return Err(format!("Feedback webhook must use https: '{webhook}'"));
+        }
+        Ok(Some(url))
+    }
+
+    /// Origin the widget posts to, for `connect-src`
+    pub fn csp_sources(&self) -> Vec<String> {
+        self.webhook()
+            .ok()
+            .flatten()
+            .map(|url| vec![url.origin().ascii_serialization()])
+            .unwrap_or_default()
+    }
+
+    /// Data for the `feedback` partial
+    pub fn template_data(&self, course_version: &str) -> Value {
+        json!({
+            "webhook_url": self.webhook().ok().flatten().map(String::from),
+            "email": non_empty(&self.email),
+            "course_version": course_version,
+            "max_comment_length": MAX_COMMENT_LENGTH,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(webhook_url: Option<&str>, email: Option<&str>) -> FeedbackSettings {
+        FeedbackSettings {
+            webhook_url: webhook_url.map(str::to_string),
+            email: email.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        assert!(settings(Some("https://hooks.example.com/course"), None)
+            .validate()
+            .is_ok());
+        assert!(settings(None, Some("lms-support@example.com"))
+            .validate()
+            .is_ok());
+        assert!(settings(None, None).validate().is_err());
+        assert!(settings(Some(" "), Some("")).validate().is_err());
+        assert!(settings(Some("http://hooks.example.com/"), None)
+            .validate()
+            .is_err());
+        assert!(settings(None, Some("support@example.com?cc=x@example.com"))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_template_data_and_csp_sources() {
+        let feedback = settings(
+            Some("https://hooks.example.com:8443/course?key=abc"),
+            Some(" support@example.com "),
+        );
+        let data = feedback.template_data("2.1");
+        assert_eq!(
+            data["webhook_url"],
+            "https://hooks.example.com:8443/course?key=abc"
+        );
+        assert_eq!(data["email"], "support@example.com");
+        assert_eq!(data["course_version"], "2.1");
+        assert_eq!(
+            feedback.csp_sources(),
+            vec!["https://hooks.example.com:8443"]
+        );
+        assert!(settings(None, Some("support@example.com"))
+            .csp_sources()
+            .is_empty());
+    }
+}
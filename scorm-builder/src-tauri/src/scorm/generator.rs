@@ -1,4 +1,5 @@
 use super::package::Resource;
+use crate::course_content::CourseContent;
 use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
 use std::io::{Seek, Write};
@@ -24,7 +25,7 @@ pub struct ScormGenerationResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenerateScormRequest {
     pub project_id: String,
-    pub course_content: serde_json::Value,
+    pub course_content: CourseContent,
     pub course_metadata: CourseMetadata,
     #[serde(default)]
     pub media_files: Vec<MediaFile>,
@@ -51,6 +52,8 @@ pub struct MediaFile {
 pub async fn generate_scorm_package(
     request: GenerateScormRequest,
 ) -> Result<ScormGenerationResult, String> {
+    request.course_content.validate()?;
+
     // Create a temporary directory for the SCORM package
     let temp_dir = TempDir::new().map_err(|e| format!("Failed to create temp directory: {e}"))?;
 
@@ -121,8 +124,7 @@ pub async fn generate_scorm_package(
     let mut streamable_resources: Vec<StreamableResource> = Vec::new();
 
     // Check which media files exist on disk and can be streamed
-    let media_ids: std::collections::HashSet<String> =
-        extract_all_media_ids(&request.course_content);
+    let media_ids = request.course_content.media_ids();
 
     println!(
         "[SCORM] Found {} media IDs in course content",
@@ -321,49 +323,6 @@ fn get_file_extension(mime_type: &str) -> String {
 
 // Removed collect_static_resources - no longer needed since we only use JavaScript-generated files
 
-fn extract_all_media_ids(course_content: &serde_json::Value) -> std::collections::HashSet<String> {
-    let mut media_ids = std::collections::HashSet::new();
-
-    // Helper function to extract media IDs from a page
-    fn extract_from_page(
-        page: &serde_json::Value,
-        media_ids: &mut std::collections::HashSet<String>,
-    ) {
-        if let Some(audio_id) = page.get("audioId").and_then(|v| v.as_str()) {
-            media_ids.insert(audio_id.to_string());
-        }
-        if let Some(caption_id) = page.get("captionId").and_then(|v| v.as_str()) {
-            media_ids.insert(caption_id.to_string());
-        }
-        if let Some(media_array) = page.get("media").and_then(|v| v.as_array()) {
-            for media in media_array {
-                if let Some(id) = media.get("id").and_then(|v| v.as_str()) {
-                    media_ids.insert(id.to_string());
-                }
-            }
-        }
-    }
-
-    // Extract from welcome page
-    if let Some(welcome) = course_content.get("welcomePage") {
-        extract_from_page(welcome, &mut media_ids);
-    }
-
-    // Extract from objectives page
-    if let Some(objectives) = course_content.get("learningObjectivesPage") {
-        extract_from_page(objectives, &mut media_ids);
-    }
-
-    // Extract from topics
-    if let Some(topics) = course_content.get("topics").and_then(|v| v.as_array()) {
-        for topic in topics {
-            extract_from_page(topic, &mut media_ids);
-        }
-    }
-
-    media_ids
-}
-
 fn get_scorm_output_path(course_title: &str) -> Result<PathBuf, String> {
     let downloads_dir =
         dirs::download_dir().ok_or_else(|| "Could not find downloads directory".to_string())?;
@@ -376,50 +335,13 @@ fn get_scorm_output_path(course_title: &str) -> Result<PathBuf, String> {
 
 async fn collect_media_resources(
     project_id: &str,
-    course_content: &serde_json::Value,
+    course_content: &CourseContent,
     media_extension_map: &std::collections::HashMap<String, String>,
 ) -> Result<Vec<Resource>, String> {
     let mut resources = Vec::new();
 
     // Collect media IDs from course content
-    let mut media_ids = std::collections::HashSet::new();
-
-    // Helper function to extract media IDs from a page
-    fn extract_media_from_page(
-        page: &serde_json::Value,
-        media_ids: &mut std::collections::HashSet<String>,
-    ) {
-        if let Some(audio_id) = page.get("audioId").and_then(|v| v.as_str()) {
-            media_ids.insert(audio_id.to_string());
-        }
-        if let Some(caption_id) = page.get("captionId").and_then(|v| v.as_str()) {
-            media_ids.insert(caption_id.to_string());
-        }
-        if let Some(media_array) = page.get("media").and_then(|v| v.as_array()) {
-            for media in media_array {
-                if let Some(id) = media.get("id").and_then(|v| v.as_str()) {
-                    media_ids.insert(id.to_string());
-                }
-            }
-        }
-    }
-
-    // Extract from welcome page
-    if let Some(welcome) = course_content.get("welcomePage") {
-        extract_media_from_page(welcome, &mut media_ids);
-    }
-
-    // Extract from objectives page
-    if let Some(objectives) = course_content.get("learningObjectivesPage") {
-        extract_media_from_page(objectives, &mut media_ids);
-    }
-
-    // Extract from topics
-    if let Some(topics) = course_content.get("topics").and_then(|v| v.as_array()) {
-        for topic in topics {
-            extract_media_from_page(topic, &mut media_ids);
-        }
-    }
+    let media_ids = course_content.media_ids();
 
     // Load actual media files from disk using media_storage
     use crate::media_storage;
@@ -511,12 +433,13 @@ mod tests {
     async fn test_generate_scorm_package_creates_file() {
         let request = GenerateScormRequest {
             project_id: "test-project-123".to_string(),
-            course_content: json!({
+            course_content: serde_json::from_value(json!({
                 "welcomePage": {
                     "narration": "Welcome to the course"
                 },
                 "topics": []
-            }),
+            }))
+            .unwrap(),
             course_metadata: CourseMetadata {
                 title: "Test Course".to_string(),
                 description: "A test course".to_string(),
@@ -544,7 +467,7 @@ mod tests {
     async fn test_generate_scorm_includes_media_files() {
         let request = GenerateScormRequest {
             project_id: "test-project-123".to_string(),
-            course_content: json!({
+            course_content: serde_json::from_value(json!({
                 "welcomePage": {
                     "narration": "Welcome",
                     "mediaReferences": [{
@@ -554,7 +477,8 @@ mod tests {
                     }],
                     "audioId": "audio-0"
                 }
-            }),
+            }))
+            .unwrap(),
             course_metadata: CourseMetadata {
                 title: "Test Course".to_string(),
                 description: "".to_string(),
@@ -582,6 +506,18 @@ pub fn stream_file_to_zip<W: Write + Seek>(
 ) -> Result<(), String> {
     use std::io::copy;
 
+    // Media of encrypted projects is decrypted in memory; the rest streams from disk
+    if crate::project_encryption::is_sealed_file(source_path) {
+        let data = crate::project_encryption::read_media_file(source_path)?;
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file(zip_path, options)
+            .map_err(|e| format!("Failed to start file in ZIP: {e}"))?;
+        return zip
+            .write_all(&data)
+            .map_err(|e| format!("Failed to write file to ZIP: {e}"));
+    }
+
     let file = std::fs::File::open(source_path)
         .map_err(|e| format!("Failed to open file {}: {}", source_path.display(), e))?;
 
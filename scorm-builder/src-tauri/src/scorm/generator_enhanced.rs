@@ -1,54 +1,153 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::io::Write;
-use zip::write::FileOptions;
-use zip::ZipWriter;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use ts_rs::TS;
 
-use super::html_generator_enhanced::HtmlGenerator;
-use super::navigation_generator::NavigationGenerator;
+use super::analytics::AnalyticsSettings;
+use super::feedback::FeedbackSettings;
+use super::api_discovery::{render_api_discovery_js, API_DISCOVERY_PATH};
+use super::animated_gifs::GifVideos;
+use super::audio_sources::AudioSources;
+use super::content_lint::{enforce_alt_text, lint_course, LintIssue};
+use super::credits::has_credits;
+use super::html_generator_enhanced::{HtmlGenerator, PageOptions};
+use super::keywords::{course_keywords, KeywordSettings};
+use super::lms_profile::LmsProfile;
+use super::media_transform::{
+    apply_media_transforms, MediaTranscoder, MediaTransformReport, MediaTransformSpec,
+    RenamingSink, TransformOutcome,
+};
+use super::missing_media::{find_missing_media, is_external, substitute_placeholders, MissingMedia};
+use super::mixed_content::{
+    secure_insecure_urls, InsecureUrl, InsecureUrlAction, InsecureUrlPolicy, UrlFetcher,
+};
+use super::navigation_generator::{course_data_js, COURSE_DATA_PATH};
+use super::navigation_menu::{apply_navigation_menu, NavigationMenu};
 use super::output_validator::OutputValidator;
+use super::page_effort::{course_effort, MediaDurations};
+use super::page_preview::assemble_page_preview;
+use super::package_version::{course_version, manifest_lom, PackageBuild, PackageVersion, VERSION_FILE};
+#[cfg(test)]
+use super::plugins::GeneratorPlugin;
+use super::plugins::Plugins;
+use super::question_bank::{resolve_question_bank, BankQuestion};
+use super::render_diagnostics::{GenerationError, PageRenderError};
+use super::source_snapshot::SNAPSHOT_FILE;
+use super::transcripts::{has_transcript_page, CaptionTexts};
+use super::package_sink::{
+    CollectSink, CompressionStats, LanguageVariantSink, PackageSink, ScormPreview, ZipSink,
+};
+use super::runtime_assets::{runtime_assets, write_runtime_assets};
 use super::style_generator::StyleGenerator;
+use crate::media_storage::MediaAttribution;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, TS)]
+#[ts(export)]
 pub struct Topic {
     pub id: String,
     pub title: String,
     pub content: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub knowledge_check: Option<KnowledgeCheck>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub audio_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub caption_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub image_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub media: Option<Vec<MediaItem>>,
+    /// Narration script, used for the topic's transcript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub narration: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct KnowledgeCheck {
     pub enabled: bool,
     pub questions: Vec<Question>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
 pub struct Question {
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
+    #[ts(as = "Option<String>", optional)]
     pub question_type: String,
+    #[serde(default)]
+    #[ts(as = "Option<String>", optional)]
     pub text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub options: Option<Vec<String>>,
+    #[serde(default)]
+    #[ts(as = "Option<String>", optional)]
     pub correct_answer: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub explanation: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub correct_feedback: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub incorrect_feedback: Option<String>,
+    /// Image or audio shown with the correct feedback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub correct_feedback_media: Option<FeedbackMedia>,
+    /// Image or audio shown with the incorrect feedback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub incorrect_feedback_media: Option<FeedbackMedia>,
+    /// Id of the `question_bank` question this stands for; the other fields
+    /// are replaced by the bank's before the course renders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub bank_ref: Option<String>,
+}
+
+impl Question {
+    /// Media attached to the correct and incorrect feedback
+    pub fn feedback_media(&self) -> impl Iterator<Item = &FeedbackMedia> {
+        self.correct_feedback_media
+            .iter()
+            .chain(self.incorrect_feedback_media.iter())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum FeedbackMediaType {
+    Image,
+    Audio,
+}
+
+/// Media attached to question feedback
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export)]
+pub struct FeedbackMedia {
+    #[serde(rename = "type")]
+    pub media_type: FeedbackMediaType,
+    /// Media id or path, resolved like `image_url`
+    pub url: String,
+    /// Text alternative for an image, or accessible name for the player
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub alt_text: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[ts(export, rename = "ScormMediaItem")]
 pub struct MediaItem {
     pub id: String,
     #[serde(rename = "type")]
@@ -56,42 +155,178 @@ pub struct MediaItem {
     pub url: String,
     pub title: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub embed_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub is_youtube: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub clip_start: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub clip_end: Option<u32>,
+    /// Text alternative for the image, or accessible name for the player
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub alt_text: Option<String>,
+    /// Author and license, listed on the credits page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub attribution: Option<MediaAttribution>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct GenerateScormRequest {
     pub course_title: String,
+    #[ts(optional)]
     pub course_description: Option<String>,
+    #[ts(optional)]
     pub welcome_page: Option<WelcomePage>,
+    #[ts(optional)]
     pub learning_objectives_page: Option<ObjectivesPage>,
     pub topics: Vec<Topic>,
+    #[ts(optional)]
     pub assessment: Option<Assessment>,
     pub pass_mark: u32,
     pub navigation_mode: String,
     pub allow_retake: bool,
+    #[ts(optional)]
     pub enable_csp: Option<bool>,
+    #[ts(optional)]
+    pub strict_csp: Option<bool>, // only applies when enable_csp is true
+    #[ts(optional)]
     pub require_audio_completion: Option<bool>,
     // New comprehensive course settings
+    #[ts(optional)]
     pub auto_advance: Option<bool>,
+    #[ts(optional)]
     pub allow_previous_review: Option<bool>,
+    #[ts(optional)]
     pub retake_delay: Option<u32>, // hours
+    #[ts(optional)]
     pub completion_criteria: Option<String>,
+    #[ts(optional)]
     pub show_progress: Option<bool>,
+    #[ts(optional)]
     pub show_outline: Option<bool>,
+    #[ts(optional)]
     pub confirm_exit: Option<bool>,
+    #[ts(optional)]
     pub font_size: Option<String>, // "small", "medium", "large"
+    #[ts(optional)]
     pub time_limit: Option<u32>, // minutes, 0 = unlimited
+    #[ts(optional)]
     pub session_timeout: Option<u32>, // minutes for auto-save
+    #[ts(optional)]
     pub minimum_time_spent: Option<u32>, // minutes
+    #[ts(optional)]
     pub keyboard_navigation: Option<bool>,
+    #[ts(optional)]
     pub printable: Option<bool>,
+    #[ts(optional)]
+    pub high_contrast: Option<bool>, // force the high-contrast theme regardless of OS preference
+    #[ts(optional)]
+    pub language: Option<String>, // language code of the content, "en" when unset
+    /// Translated copies of the course, packaged under `{language}/` with an
+    /// organization of their own in the manifest
+    #[ts(optional)]
+    pub language_variants: Option<Vec<LanguageVariant>>,
+    /// Package the diagnostics overlay (scripts/diagnostics.js) for LMS triage
+    #[ts(optional)]
+    pub debug_build: Option<bool>,
+    /// Countdowns on the assessment page, separate from `time_limit`
+    #[ts(optional)]
+    pub assessment_timer: Option<AssessmentTimer>,
+    /// Per-page notes panel for learners, saved in suspend_data
+    #[ts(optional)]
+    pub learner_notes: Option<bool>,
+    /// Page visits and interaction counts kept in suspend_data, for troubleshooting
+    /// completion disputes with `decode_suspend_data`
+    #[ts(optional)]
+    pub runtime_analytics: Option<bool>,
+    /// Search box over the course text, backed by an index in the course data
+    #[ts(optional)]
+    pub course_search: Option<bool>,
+    /// Parent frames searched for the LMS API, `DEFAULT_API_SEARCH_DEPTH` when unset
+    #[ts(optional)]
+    pub api_search_depth: Option<u32>,
+    /// Matomo or Google Analytics page views, for LMSs without usage reporting
+    #[ts(optional)]
+    pub analytics: Option<AnalyticsSettings>,
+    /// "Report a problem with this page" widget; off when unset
+    #[ts(optional)]
+    pub feedback: Option<FeedbackSettings>,
+    /// Resume prompt and intro video gate on the welcome page
+    #[ts(optional)]
+    pub welcome_start: Option<WelcomeStartOptions>,
+    /// Overview page after the objectives listing every page with completion ticks
+    #[ts(optional)]
+    pub course_map: Option<bool>,
+    /// Transcript of the narration under each narrated topic, and a transcript
+    /// page after the assessment
+    #[ts(optional)]
+    pub transcripts: Option<bool>,
+    /// Page images open enlarged in a lightbox when clicked (default true)
+    #[ts(optional)]
+    pub image_lightbox: Option<bool>,
+    /// Narration seeking, speed, autoplay and resume behaviour
+    #[ts(optional)]
+    pub audio_policy: Option<AudioPolicy>,
+    /// Mobile-first layout with the outline in a collapsible drawer on small screens (default true)
+    #[ts(optional)]
+    pub responsive_layout: Option<bool>,
+    /// LMS the package is tuned for, `generic` when unset
+    #[ts(optional)]
+    pub lms_profile: Option<LmsProfile>,
+    /// Commit debounce and heartbeat
+    #[ts(optional)]
+    pub commit_settings: Option<CommitSettings>,
+    /// Package stand-ins for referenced media that is missing, instead of broken links
+    #[ts(optional)]
+    pub media_placeholders: Option<bool>,
+    /// Refuse to build while any image lacks alt text or any video player a name
+    #[ts(optional)]
+    pub require_alt_text: Option<bool>,
+    /// Whether http images and embeds are only reported, upgraded to https or downloaded
+    #[ts(optional)]
+    pub insecure_urls: Option<InsecureUrlPolicy>,
+    /// Generated interface features switched on or off individually
+    #[ts(optional)]
+    pub features: Option<CourseFeatures>,
+    /// Labels and order of the welcome, objectives and assessment pages in the menu
+    #[ts(optional)]
+    pub navigation_menu: Option<NavigationMenu>,
+    /// Version shown in the manifest and `version.json`, `1.0` when unset
+    #[ts(optional)]
+    pub course_version: Option<String>,
+    /// Keywords in the manifest metadata for LMS catalog search, derived from
+    /// topic titles and objectives unless the author replaces them
+    #[ts(optional)]
+    pub keywords: Option<KeywordSettings>,
+    /// Questions knowledge checks and the assessment refer to by `bank_ref`
+    #[ts(optional)]
+    pub question_bank: Option<Vec<BankQuestion>>,
+    /// Project build the package is stamped with; set by the app, never by the frontend
+    #[serde(skip)]
+    pub package_build: Option<PackageBuild>,
+    /// Put an encrypted copy of the project in the package, to reopen it if the project file
+    /// is lost; it is encrypted with the passphrase saved for the project
+    #[ts(optional)]
+    pub source_snapshot: Option<bool>,
+    /// The encrypted snapshot file; set by the app from `source_snapshot`
+    #[serde(skip)]
+    pub source_snapshot_file: Option<Vec<u8>>,
+}
+
+/// A language variant of the course. Its pages share the package's media folder;
+/// any variants nested inside `course` are ignored.
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LanguageVariant {
+    pub language: String,
+    pub course: GenerateScormRequest,
 }
 
 impl Default for GenerateScormRequest {
@@ -107,6 +342,7 @@ impl Default for GenerateScormRequest {
             navigation_mode: "linear".to_string(),
             allow_retake: true,
             enable_csp: Some(false), // Default to false for better LMS compatibility
+            strict_csp: Some(false),
             require_audio_completion: Some(false), // Default to false for backward compatibility
             // New comprehensive course settings with sensible defaults
             auto_advance: Some(false),
@@ -122,192 +358,578 @@ impl Default for GenerateScormRequest {
             minimum_time_spent: Some(0), // No minimum
             keyboard_navigation: Some(true),
             printable: Some(false),
+            high_contrast: Some(false),
+            language: None,
+            language_variants: None,
+            debug_build: None,
+            assessment_timer: None,
+            learner_notes: None,
+            runtime_analytics: None,
+            course_search: None,
+            api_search_depth: None,
+            analytics: None,
+            feedback: None,
+            welcome_start: None,
+            course_map: None,
+            transcripts: None,
+            image_lightbox: Some(true),
+            audio_policy: None,
+            responsive_layout: Some(true),
+            lms_profile: None,
+            commit_settings: None,
+            media_placeholders: None,
+            require_alt_text: Some(false),
+            insecure_urls: None,
+            features: None,
+            navigation_menu: None,
+            course_version: None,
+            keywords: None,
+            question_bank: None,
+            package_build: None,
+            source_snapshot: None,
+            source_snapshot_file: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct WelcomePage {
     pub title: String,
     pub content: String,
     pub start_button_text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub audio_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub caption_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub image_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub media: Option<Vec<MediaItem>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct ObjectivesPage {
     pub objectives: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub audio_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub caption_file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub image_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub media: Option<Vec<MediaItem>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct Assessment {
     pub questions: Vec<Question>,
 }
 
+/// How learners start the course from the welcome page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WelcomeStartOptions {
+    /// Ask returning learners whether to resume at their bookmark or start
+    /// over, instead of taking them straight back (default false)
+    #[ts(optional)]
+    pub resume_prompt: Option<bool>,
+    /// Id of a welcome page video (from its `media`) learners have to watch
+    /// before they can start the course
+    #[ts(optional)]
+    pub intro_video_id: Option<String>,
+    /// Share of the intro video that counts as watched, in percent (default 90)
+    #[ts(optional)]
+    pub intro_video_min_percent: Option<u8>,
+}
+
+impl WelcomeStartOptions {
+    pub fn resume_prompt(&self) -> bool {
+        self.resume_prompt.unwrap_or(false)
+    }
+
+    pub fn intro_video_min_percent(&self) -> u8 {
+        self.intro_video_min_percent.unwrap_or(90)
+    }
+
+    /// Check the intro video gate can be satisfied: the video has to be a
+    /// packaged welcome page video, since embeds can't report what was watched
+    pub fn validate(&self, welcome: Option<&WelcomePage>) -> Result<(), String> {
+        let Some(video_id) = &self.intro_video_id else {
+            return Ok(());
+        };
+        if !(1..=100).contains(&self.intro_video_min_percent()) {
+            return Err(format!(
+                "Intro video watch percentage must be between 1 and 100, got {}",
+                self.intro_video_min_percent()
+            ));
+        }
+        let video = welcome
+            .and_then(|welcome| welcome.media.as_ref())
+            .into_iter()
+            .flatten()
+            .find(|item| &item.id == video_id)
+            .ok_or_else(|| format!("Intro video '{video_id}' is not on the welcome page"))?;
+        let embedded = video.is_youtube.unwrap_or(false)
+            || video.embed_url.is_some()
+            || video.url.contains("youtube.com")
+            || video.url.contains("youtu.be");
+        if video.media_type != "video" || embedded {
+            return Err(format!(
+                "Intro video '{video_id}' must be an uploaded video, not an embed or other media"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How the narration player behaves. Every field is optional; unset fields
+/// keep the player's historical behaviour.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AudioPolicy {
+    /// Let learners seek and skip while `require_audio_completion` is on
+    /// (default false). Seeking is always allowed when completion isn't required.
+    #[ts(optional)]
+    pub allow_seeking: Option<bool>,
+    /// Show the playback speed selector (default true)
+    #[ts(optional)]
+    pub speed_control: Option<bool>,
+    /// Start the narration when a page opens (default false). Learners can
+    /// still turn it off, and a browser that blocks it gets a prompt instead.
+    #[ts(optional)]
+    pub autoplay: Option<bool>,
+    /// Pick the narration up where the learner left it on each page, kept in
+    /// suspend_data (default false)
+    #[ts(optional)]
+    pub remember_position: Option<bool>,
+    /// Cover the page with a "Click to enable audio" button when the browser
+    /// blocks autoplay (default true). Off leaves the pulsing play button and
+    /// a short notice.
+    #[ts(optional)]
+    pub unlock_overlay: Option<bool>,
+}
+
+impl AudioPolicy {
+    /// Whether seeking and skipping are disabled for a course with the given
+    /// audio completion requirement
+    pub fn seek_locked(&self, require_audio_completion: bool) -> bool {
+        require_audio_completion && !self.allow_seeking.unwrap_or(false)
+    }
+
+    pub fn speed_control(&self) -> bool {
+        self.speed_control.unwrap_or(true)
+    }
+
+    pub fn autoplay(&self) -> bool {
+        self.autoplay.unwrap_or(false)
+    }
+
+    pub fn remember_position(&self) -> bool {
+        self.remember_position.unwrap_or(false)
+    }
+
+    pub fn unlock_overlay(&self) -> bool {
+        self.unlock_overlay.unwrap_or(true)
+    }
+}
+
+/// Parts of the generated interface authors can switch off, or on, without
+/// editing the exported HTML. Unset fields follow the older course settings
+/// named on each, so existing projects build unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct CourseFeatures {
+    /// Sidebar outline of the course pages (default `show_outline`)
+    #[ts(optional)]
+    pub navigation_menu: Option<bool>,
+    /// Progress ring at the top of the navigation menu (default `show_progress`)
+    #[ts(optional)]
+    pub progress_bar: Option<bool>,
+    /// Captions button on narration players that have captions (default true)
+    #[ts(optional)]
+    pub captions_toggle: Option<bool>,
+    /// "Page 3 of 12" between the previous and next buttons (default false)
+    #[ts(optional)]
+    pub page_numbers: Option<bool>,
+    /// Print button in the header (default `printable`); turning it on allows printing
+    #[ts(optional)]
+    pub print_button: Option<bool>,
+}
+
+/// `CourseFeatures` with every default applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FeatureFlags {
+    pub navigation_menu: bool,
+    pub progress_bar: bool,
+    pub captions_toggle: bool,
+    pub page_numbers: bool,
+    pub print_button: bool,
+}
+
+impl FeatureFlags {
+    pub fn from_request(request: &GenerateScormRequest) -> Self {
+        let features = request.features.clone().unwrap_or_default();
+        let navigation_menu = features
+            .navigation_menu
+            .unwrap_or(request.show_outline.unwrap_or(true));
+        Self {
+            navigation_menu,
+            progress_bar: navigation_menu
+                && features
+                    .progress_bar
+                    .unwrap_or(request.show_progress.unwrap_or(true)),
+            captions_toggle: features.captions_toggle.unwrap_or(true),
+            page_numbers: features.page_numbers.unwrap_or(false),
+            print_button: features
+                .print_button
+                .unwrap_or(request.printable.unwrap_or(false)),
+        }
+    }
+}
+
+impl CourseFeatures {
+    /// Reject combinations the templates can't honour; the progress bar sits
+    /// in the navigation menu
+    pub fn validate(&self, request: &GenerateScormRequest) -> Result<(), String> {
+        let navigation_menu = self
+            .navigation_menu
+            .unwrap_or(request.show_outline.unwrap_or(true));
+        if self.progress_bar == Some(true) && !navigation_menu {
+            return Err(
+                "The progress bar is shown in the navigation menu, which is turned off"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// When the runtime commits progress to the LMS. Saves in quick succession
+/// share one commit, and a heartbeat commits on a timer so a closed or crashed
+/// tab loses little.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CommitSettings {
+    /// Milliseconds a save waits for further changes before committing
+    /// (default 1000, at most 10000)
+    #[ts(optional)]
+    pub debounce_ms: Option<u32>,
+    /// Seconds between heartbeat commits (default 120); 0 turns the heartbeat off
+    #[ts(optional)]
+    pub heartbeat_seconds: Option<u32>,
+}
+
+impl CommitSettings {
+    pub fn debounce_ms(&self) -> u32 {
+        self.debounce_ms.unwrap_or(1000).min(10_000)
+    }
+
+    pub fn heartbeat_seconds(&self) -> u32 {
+        self.heartbeat_seconds.unwrap_or(120)
+    }
+}
+
+/// Assessment countdowns. Either timer can be used alone; with a per-question
+/// timer the questions are shown one at a time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AssessmentTimer {
+    #[ts(optional)]
+    pub overall_minutes: Option<u32>,
+    #[ts(optional)]
+    pub per_question_seconds: Option<u32>,
+    /// How long before a countdown runs out the learner is warned (default 60s,
+    /// capped at half of the countdown)
+    #[ts(optional)]
+    pub warning_seconds: Option<u32>,
+    /// Submit when time runs out, counting unanswered questions as wrong
+    /// (default true)
+    #[ts(optional)]
+    pub auto_submit: Option<bool>,
+}
+
+impl AssessmentTimer {
+    pub fn overall_seconds(&self) -> u32 {
+        self.overall_minutes.unwrap_or(0).saturating_mul(60)
+    }
+
+    pub fn question_seconds(&self) -> u32 {
+        self.per_question_seconds.unwrap_or(0)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.overall_seconds() > 0 || self.question_seconds() > 0
+    }
+
+    /// Warning lead time for a countdown of `seconds`
+    pub fn warning_for(&self, seconds: u32) -> u32 {
+        self.warning_seconds.unwrap_or(60).min(seconds / 2)
+    }
+}
+
+/// Reports pages as a package build renders them
+struct PageProgress {
+    /// Called with the pages written so far and the pages of the package
+    report: Box<dyn Fn(usize, usize) + Send + Sync>,
+    written: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl PageProgress {
+    fn page_written(&self) {
+        let written = self.written.fetch_add(1, Ordering::Relaxed) + 1;
+        (self.report)(written, self.total.load(Ordering::Relaxed).max(written));
+    }
+}
+
+/// Pages `write_course_files` renders for one language of the course
+fn page_count(request: &GenerateScormRequest) -> usize {
+    usize::from(request.welcome_page.is_some())
+        + usize::from(request.learning_objectives_page.is_some())
+        + usize::from(request.course_map.unwrap_or(false))
+        + request.topics.len()
+        + usize::from(request.assessment.is_some())
+        + usize::from(has_transcript_page(request))
+        + usize::from(has_credits(request))
+}
+
 pub struct EnhancedScormGenerator {
-    navigation_generator: NavigationGenerator<'static>,
     style_generator: StyleGenerator<'static>,
     html_generator: HtmlGenerator<'static>,
     output_validator: OutputValidator,
+    compression_level: Option<i32>,
+    plugins: Plugins,
+    url_fetcher: Option<Box<dyn UrlFetcher>>,
+    page_progress: Option<PageProgress>,
+}
+
+/// What a package build did beyond producing the ZIP, reported to the frontend
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GenerationSummary {
+    pub compression: CompressionStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_transforms: Option<MediaTransformReport>,
+    /// Referenced media the package was built without
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_media: Vec<MissingMedia>,
+    /// Images without alt text, unnamed video players and credited media without a license
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub content_lint: Vec<LintIssue>,
+    /// http URLs pages load, with what was done about each
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub insecure_urls: Vec<InsecureUrl>,
+    /// Time spent checking the course and writing every file into the package
+    pub html_ms: u64,
+    /// Time spent finishing and validating the ZIP
+    pub zip_ms: u64,
+}
+
+impl GenerationSummary {
+    /// Media files packaged unchanged because their transform failed
+    pub fn failed_media_transforms(&self) -> usize {
+        self.media_transforms.as_ref().map_or(0, |report| {
+            report
+                .files
+                .iter()
+                .filter(|entry| matches!(entry.outcome, TransformOutcome::Failed { .. }))
+                .count()
+        })
+    }
+
+    /// http URLs left in the package, which https portals will block
+    pub fn unresolved_insecure_urls(&self) -> usize {
+        self.insecure_urls
+            .iter()
+            .filter(|entry| entry.action == InsecureUrlAction::Reported)
+            .count()
+    }
 }
 
 impl EnhancedScormGenerator {
     pub fn new() -> Result<Self, String> {
         Ok(Self {
-            navigation_generator: NavigationGenerator::new()?,
             style_generator: StyleGenerator::new()?,
             html_generator: HtmlGenerator::new()?,
             output_validator: OutputValidator::new(),
+            compression_level: None,
+            plugins: Plugins::registered(),
+            url_fetcher: None,
+            page_progress: None,
         })
     }
 
+    /// Render pages with `html_generator`, e.g. one loaded from template overrides
+    pub fn with_html_generator(mut self, html_generator: HtmlGenerator<'static>) -> Self {
+        self.html_generator = html_generator;
+        self
+    }
+
+    /// Deflate level (0-9) for text assets; already-compressed media is always stored
+    pub fn with_compression_level(mut self, level: Option<i32>) -> Self {
+        self.compression_level = level;
+        self
+    }
+
+    /// Network access for the `insecure_urls` policy; without it http URLs are only reported
+    pub fn with_url_fetcher(mut self, fetcher: Box<dyn UrlFetcher>) -> Self {
+        self.url_fetcher = Some(fetcher);
+        self
+    }
+
+    /// Call `report` with the pages written so far and the pages of the
+    /// package as each page of a package build is rendered
+    pub fn with_page_progress(mut self, report: impl Fn(usize, usize) + Send + Sync + 'static) -> Self {
+        self.page_progress = Some(PageProgress {
+            report: Box::new(report),
+            written: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+        });
+        self
+    }
+
+    /// Run `plugin` in addition to the registered ones
+    #[cfg(test)]
+    pub fn with_plugin(mut self, plugin: std::sync::Arc<dyn GeneratorPlugin>) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
     pub fn generate_scorm_package(
         &self,
         request: GenerateScormRequest,
         media_files: HashMap<String, Vec<u8>>,
         extension_map: Option<HashMap<String, String>>,
     ) -> Result<Vec<u8>, String> {
-        let mut zip_buffer = Vec::new();
-        {
-            let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_buffer));
-
-            // Helper function to choose compression method based on file extension
-            let compression_options = |path: &str| -> FileOptions {
-                let pre_compressed_extensions = [
-                    ".mp3", ".mp4", ".webm", ".avi", ".mov",
-                    ".jpg", ".jpeg", ".png", ".gif", ".webp", ".svg",
-                    ".pdf", ".zip", ".rar", ".7z"
-                ];
-
-                let use_stored = pre_compressed_extensions
-                    .iter()
-                    .any(|ext| path.to_lowercase().ends_with(ext));
-
-                FileOptions::default().compression_method(
-                    if use_stored {
-                        zip::CompressionMethod::Stored
-                    } else {
-                        zip::CompressionMethod::Deflated
-                    }
-                )
-            };
-
-            // Generate scorm-api.js first (loads before navigation.js)
-            let scorm_api_js = self.html_generator.generate_scorm_api_js(&request)?;
-            zip.start_file("scripts/scorm-api.js", compression_options("scripts/scorm-api.js"))
-                .map_err(|e| format!("Failed to create scorm-api.js: {e}"))?;
-            zip.write_all(scorm_api_js.as_bytes())
-                .map_err(|e| format!("Failed to write scorm-api.js: {e}"))?;
-
-            // Generate navigation.js
-            let navigation_js = self.navigation_generator.generate_navigation_js(&request)?;
-            self.navigation_generator
-                .validate_navigation_js(&navigation_js)
-                .map_err(|errors| errors.join("\n"))?;
-
-            zip.start_file("scripts/navigation.js", compression_options("scripts/navigation.js"))
-                .map_err(|e| format!("Failed to create navigation.js: {e}"))?;
-            zip.write_all(navigation_js.as_bytes())
-                .map_err(|e| format!("Failed to write navigation.js: {e}"))?;
-
-            // Generate main.css
-            let main_css = self.style_generator.generate_main_css(&request)?;
-            self.style_generator
-                .validate_css(&main_css)
-                .map_err(|errors| errors.join("\n"))?;
-
-            zip.start_file("styles/main.css", compression_options("styles/main.css"))
-                .map_err(|e| format!("Failed to create main.css: {e}"))?;
-            zip.write_all(main_css.as_bytes())
-                .map_err(|e| format!("Failed to write main.css: {e}"))?;
-
-            // Generate index.html
-            let index_html = self.html_generator.generate_index_html(&request)?;
-            zip.start_file("index.html", compression_options("index.html"))
-                .map_err(|e| format!("Failed to create index.html: {e}"))?;
-            zip.write_all(index_html.as_bytes())
-                .map_err(|e| format!("Failed to write index.html: {e}"))?;
-
-            // Generate page HTML files
-            if let Some(welcome) = &request.welcome_page {
-                let welcome_html = self.html_generator.generate_welcome_page(welcome, request.require_audio_completion.unwrap_or(false), extension_map.as_ref())?;
-                zip.start_file("pages/welcome.html", compression_options("pages/welcome.html"))
-                    .map_err(|e| format!("Failed to create welcome.html: {e}"))?;
-                zip.write_all(welcome_html.as_bytes())
-                    .map_err(|e| format!("Failed to write welcome.html: {e}"))?;
-            }
+        self.generate_scorm_package_with_summary(request, media_files, extension_map, None)
+            .map(|(zip_buffer, _)| zip_buffer)
+            .map_err(String::from)
+    }
 
-            if let Some(objectives) = &request.learning_objectives_page {
-                let objectives_html = self.html_generator.generate_objectives_page(objectives, request.require_audio_completion.unwrap_or(false), extension_map.as_ref())?;
-                zip.start_file("pages/objectives.html", compression_options("pages/objectives.html"))
-                    .map_err(|e| format!("Failed to create objectives.html: {e}"))?;
-                zip.write_all(objectives_html.as_bytes())
-                    .map_err(|e| format!("Failed to write objectives.html: {e}"))?;
-            }
+    /// Generate a package with the project's media transformation rules applied
+    /// to the packaged copies of the media, returning the size-savings report
+    pub fn generate_scorm_package_with_transforms(
+        &self,
+        request: GenerateScormRequest,
+        media_files: HashMap<String, Vec<u8>>,
+        extension_map: Option<HashMap<String, String>>,
+        spec: &MediaTransformSpec,
+        transcoder: &dyn MediaTranscoder,
+    ) -> Result<(Vec<u8>, MediaTransformReport), String> {
+        let (zip_buffer, summary) = self.generate_scorm_package_with_summary(
+            request,
+            media_files,
+            extension_map,
+            Some((spec, transcoder)),
+        )?;
+        Ok((zip_buffer, summary.media_transforms.unwrap_or_default()))
+    }
 
-            // Generate topic pages
-            for topic in &request.topics {
-                let topic_html = self.html_generator.generate_topic_page(topic, request.require_audio_completion.unwrap_or(false), extension_map.as_ref())?;
-                zip.start_file(format!("pages/{}.html", topic.id), compression_options(&format!("pages/{}.html", topic.id)))
-                    .map_err(|e| format!("Failed to create topic page: {e}"))?;
-                zip.write_all(topic_html.as_bytes())
-                    .map_err(|e| format!("Failed to write topic page: {e}"))?;
-            }
+    /// Generate a package, optionally applying media transformation rules, and
+    /// report how it was compressed
+    pub fn generate_scorm_package_with_summary(
+        &self,
+        request: GenerateScormRequest,
+        media_files: HashMap<String, Vec<u8>>,
+        extension_map: Option<HashMap<String, String>>,
+        media_transforms: Option<(&MediaTransformSpec, &dyn MediaTranscoder)>,
+    ) -> Result<(Vec<u8>, GenerationSummary), GenerationError> {
+        let started = Instant::now();
+        let mut request = self.prepare_request(request)?;
+        let mut media_files = media_files;
 
-            // Generate assessment page
-            if let Some(assessment) = &request.assessment {
-                let assessment_html = self.html_generator.generate_assessment_page(assessment)?;
-                zip.start_file("pages/assessment.html", compression_options("pages/assessment.html"))
-                    .map_err(|e| format!("Failed to create assessment.html: {e}"))?;
-                zip.write_all(assessment_html.as_bytes())
-                    .map_err(|e| format!("Failed to write assessment.html: {e}"))?;
-            }
+        let content_lint = lint_course(&request);
+        enforce_alt_text(&request, &content_lint)?;
+
+        // Deal with http URLs first, so downloaded copies count as packaged media
+        let insecure_urls =
+            secure_insecure_urls(&mut request, &mut media_files, self.url_fetcher.as_deref());
+        for entry in &insecure_urls {
+            eprintln!(
+                "[SCORM Generator] ⚠️  Page {} loads {} over http: {:?}",
+                entry.page_id, entry.url, entry.action
+            );
+        }
+
+        // Report pages linking to media that never arrived, before anything is packaged
+        let mut missing_media = find_missing_media(&request, &media_files, extension_map.as_ref());
+        if request.media_placeholders.unwrap_or(false) {
+            substitute_placeholders(&mut media_files, &mut missing_media);
+        }
+        for entry in &missing_media {
+            eprintln!(
+                "[SCORM Generator] ⚠️  Page {} references missing media {} ({}){}",
+                entry.page_id,
+                entry.media_id,
+                entry.path,
+                if entry.substituted { ", packaged a placeholder" } else { "" }
+            );
+        }
+
+        let mut zip_sink = ZipSink::with_compression_level(self.compression_level)?;
 
-            // Add manifest
-            let manifest = self.generate_simple_manifest(&request)?;
-            zip.start_file("imsmanifest.xml", compression_options("imsmanifest.xml"))
-                .map_err(|e| format!("Failed to create manifest: {e}"))?;
-            zip.write_all(manifest.as_bytes())
-                .map_err(|e| format!("Failed to write manifest: {e}"))?;
-
-            // Add media files
-            eprintln!("[SCORM Generator] 📦 Adding {} media files to ZIP package", media_files.len());
-            for (idx, (path, data)) in media_files.iter().enumerate() {
-                eprintln!("[SCORM Generator] 📁 Adding media file {}/{}: {} ({} bytes)", 
-                    idx + 1, media_files.len(), path, data.len());
-                
-                zip.start_file(path.as_str(), compression_options(&path))
-                    .map_err(|e| format!("Failed to create media file {path}: {e}"))?;
-                zip.write_all(&data)
-                    .map_err(|e| format!("Failed to write media file {path}: {e}"))?;
-                    
-                eprintln!("[SCORM Generator] ✅ Successfully added media file: {}", path);
+        let media_transforms = match media_transforms {
+            Some((spec, transcoder)) => {
+                let transformed = apply_media_transforms(&media_files, spec, transcoder);
+                let mut sink = RenamingSink::new(&mut zip_sink, &transformed.renamed);
+                self.write_package(
+                    &request,
+                    &transformed.files,
+                    extension_map.as_ref(),
+                    &mut sink,
+                )?;
+                Some(transformed.report)
             }
-            
-            if media_files.is_empty() {
-                eprintln!("[SCORM Generator] ⚠️  No media files to add - ZIP will contain no media directory");
-            } else {
-                eprintln!("[SCORM Generator] 🎉 All {} media files successfully added to ZIP", media_files.len());
+            None => {
+                self.write_package(
+                    &request,
+                    &media_files,
+                    extension_map.as_ref(),
+                    &mut zip_sink,
+                )?;
+                None
             }
+        };
 
-            zip.finish()
-                .map_err(|e| format!("Failed to finish ZIP: {e}"))?;
-        }
+        let html_ms = started.elapsed().as_millis() as u64;
+        let zip_started = Instant::now();
+        let (zip_buffer, compression) = self.finish_and_validate(zip_sink)?;
+        Ok((
+            zip_buffer,
+            GenerationSummary {
+                compression,
+                media_transforms,
+                missing_media,
+                content_lint,
+                insecure_urls,
+                html_ms,
+                zip_ms: zip_started.elapsed().as_millis() as u64,
+            },
+        ))
+    }
+
+    /// Apply the plugins' course transforms, resolve question bank references,
+    /// then apply the navigation menu settings
+    fn prepare_request(&self, request: GenerateScormRequest) -> Result<GenerateScormRequest, String> {
+        let mut request = self.plugins.transform_request(request)?;
+        resolve_question_bank(&mut request)?;
+        apply_navigation_menu(&mut request)?;
+        Ok(request)
+    }
+
+    fn finish_and_validate(&self, sink: ZipSink) -> Result<(Vec<u8>, CompressionStats), String> {
+        let (zip_buffer, compression) = sink.finish_with_stats()?;
 
         // Validate the generated package
         let validation_report = self.output_validator.validate_scorm_package(&zip_buffer)?;
@@ -318,42 +940,333 @@ impl EnhancedScormGenerator {
             ));
         }
 
-        Ok(zip_buffer)
+        Ok((zip_buffer, compression))
+    }
+
+    /// Run the same pipeline as `generate_scorm_package` but keep the files in
+    /// memory instead of zipping them, for the package explorer and page previews
+    pub fn generate_scorm_preview(
+        &self,
+        request: GenerateScormRequest,
+        media_files: HashMap<String, Vec<u8>>,
+        extension_map: Option<HashMap<String, String>>,
+    ) -> Result<ScormPreview, String> {
+        let request = self.prepare_request(request)?;
+        let mut sink = CollectSink::new();
+        self.write_package(&request, &media_files, extension_map.as_ref(), &mut sink)?;
+        Ok(sink.into_preview())
+    }
+
+    /// Render a single page (`welcome`, `objectives`, `assessment` or a topic ID)
+    /// through the package templates, for the editor's preview pane. Only the
+    /// pages, scripts and styles are rendered; media is neither read nor packaged.
+    pub fn render_page_preview(
+        &self,
+        request: GenerateScormRequest,
+        page_id: &str,
+        extension_map: Option<HashMap<String, String>>,
+        media_base_url: &str,
+    ) -> Result<String, String> {
+        let request = self.prepare_request(request)?;
+        let mut sink = CollectSink::new();
+        let render_errors = self.write_course_files(
+            &request,
+            extension_map.as_ref(),
+            &AudioSources::default(),
+            &GifVideos::default(),
+            &MediaDurations::default(),
+            &CaptionTexts::default(),
+            &mut sink,
+        )?;
+        if let Some(error) = render_errors.iter().find(|error| error.page_id == page_id) {
+            return Err(error.to_string());
+        }
+
+        let file = |path: &str| {
+            sink.file(path)
+                .map(|data| String::from_utf8_lossy(data).into_owned())
+                .ok_or_else(|| format!("The course has no {path}"))
+        };
+        let page_html = file(&format!("pages/{page_id}.html"))?;
+        assemble_page_preview(&file("index.html")?, &file("styles/main.css")?, &page_html, media_base_url)
+    }
+
+    /// Render every file of the package into `sink`. Plugins' course transforms
+    /// have to be applied to `request` beforehand. Pages whose templates fail
+    /// are skipped and reported together once everything else is written.
+    pub fn write_package(
+        &self,
+        request: &GenerateScormRequest,
+        media_files: &HashMap<String, Vec<u8>>,
+        extension_map: Option<&HashMap<String, String>>,
+        sink: &mut dyn PackageSink,
+    ) -> Result<(), GenerationError> {
+        let variants = language_variants(request)?;
+        let audio_sources = AudioSources::from_media_files(media_files);
+        let gif_videos = GifVideos::from_media_files(media_files);
+        let durations = MediaDurations::from_media_files(media_files);
+        let captions = CaptionTexts::from_media_files(media_files);
+
+        if let Some(progress) = &self.page_progress {
+            let total = page_count(request)
+                + variants.iter().map(|variant| page_count(&variant.course)).sum::<usize>();
+            progress.written.store(0, Ordering::Relaxed);
+            progress.total.store(total, Ordering::Relaxed);
+        }
+
+        let mut render_errors = self.write_course_files(
+            request,
+            extension_map,
+            &audio_sources,
+            &gif_videos,
+            &durations,
+            &captions,
+            sink,
+        )?;
+
+        for variant in &variants {
+            let mut variant_sink = LanguageVariantSink::new(sink, &variant.language);
+            let variant_errors = self.write_course_files(
+                &variant.course,
+                extension_map,
+                &audio_sources,
+                &gif_videos,
+                &durations,
+                &captions,
+                &mut variant_sink,
+            )?;
+            render_errors.extend(variant_errors.into_iter().map(|mut error| {
+                error.page_id = format!("{}/{}", variant.language, error.page_id);
+                error
+            }));
+        }
+
+        for (path, contents) in self.plugins.extra_files(request)? {
+            sink.add_file(&path, &contents)?;
+        }
+
+        // Add manifest
+        let manifest = self.generate_simple_manifest(request, &variants, &audio_sources, extension_map)?;
+        sink.add_file("imsmanifest.xml", manifest.as_bytes())?;
+
+        // Stamp builds of a project so the ZIP can be traced back to them
+        if let Some(build) = &request.package_build {
+            let version = PackageVersion::new(course_version(request.course_version.as_deref())?, build);
+            let json = serde_json::to_string_pretty(&version)
+                .map_err(|e| format!("Failed to serialize {VERSION_FILE}: {e}"))?;
+            sink.add_file(VERSION_FILE, json.as_bytes())?;
+        }
+        if let Some(snapshot) = &request.source_snapshot_file {
+            sink.add_file(SNAPSHOT_FILE, snapshot)?;
+        }
+
+        // Add media files
+        eprintln!("[SCORM Generator] 📦 Adding {} media files to package", media_files.len());
+        for (idx, (path, data)) in media_files.iter().enumerate() {
+            eprintln!("[SCORM Generator] 📁 Adding media file {}/{}: {} ({} bytes)", 
+                idx + 1, media_files.len(), path, data.len());
+
+            sink.add_file(path, data)?;
+
+            eprintln!("[SCORM Generator] ✅ Successfully added media file: {}", path);
+        }
+
+        if media_files.is_empty() {
+            eprintln!("[SCORM Generator] ⚠️  No media files to add - package will contain no media directory");
+        } else {
+            eprintln!("[SCORM Generator] 🎉 All {} media files successfully added to package", media_files.len());
+        }
+
+        if !render_errors.is_empty() {
+            for error in &render_errors {
+                eprintln!("[SCORM Generator] ❌ {error}");
+            }
+            return Err(GenerationError::Render(render_errors));
+        }
+
+        Ok(())
     }
 
-    fn generate_simple_manifest(&self, request: &GenerateScormRequest) -> Result<String, String> {
-        let mut resources = String::new();
+    /// Scripts, styles, index and pages of one language of the course, returning
+    /// the pages that failed to render
+    fn write_course_files(
+        &self,
+        request: &GenerateScormRequest,
+        extension_map: Option<&HashMap<String, String>>,
+        audio_sources: &AudioSources,
+        gif_videos: &GifVideos,
+        durations: &MediaDurations,
+        captions: &CaptionTexts,
+        sink: &mut dyn PackageSink,
+    ) -> Result<Vec<PageRenderError>, String> {
+        let mut render_errors = Vec::new();
+        let page_options = PageOptions::from_request(request)
+            .with_audio_sources(audio_sources)
+            .with_gif_videos(gif_videos)
+            .with_captions(captions);
+
+        // Static runtime scripts (scorm-api.js, navigation.js, course-ui.js),
+        // minified at build time
+        write_runtime_assets(sink, request)?;
+
+        // LMS API search used by scorm-api.js, rendered with the course's search depth
+        let discovery_js = render_api_discovery_js(request.api_search_depth);
+        sink.add_file(API_DISCOVERY_PATH, discovery_js.as_bytes())?;
+
+        // Course data for navigation.js, with progress weighted by each page's effort
+        let pages = course_effort(request, durations);
+        let course_data = course_data_js(request, &pages)?;
+        sink.add_file(COURSE_DATA_PATH, course_data.as_bytes())?;
+
+        // Generate main.css
+        let main_css = self.style_generator.generate_main_css(request)?;
+        self.style_generator
+            .validate_css(&main_css)
+            .map_err(|errors| errors.join("\n"))?;
+        sink.add_file("styles/main.css", main_css.as_bytes())?;
 
-        // Add main index
-        resources.push_str(r#"        <resource identifier="main" type="webcontent" adlcp:scormType="sco" href="index.html">
-            <file href="index.html"/>
-            <file href="styles/main.css"/>
-            <file href="scripts/scorm-api.js"/>
-            <file href="scripts/navigation.js"/>
-"#);
+        // Generate index.html
+        let index_html = self.html_generator.generate_index_html(request)?;
+        let index_html = self.plugins.filter_html("index.html", index_html)?;
+        sink.add_file("index.html", index_html.as_bytes())?;
 
-        // Add page files
-        if request.welcome_page.is_some() {
-            resources.push_str("            <file href=\"pages/welcome.html\"/>\n");
+        // Generate page HTML files
+        if let Some(welcome) = &request.welcome_page {
+            let start = request.welcome_start.as_ref();
+            if let Some(start) = start {
+                start.validate(Some(welcome))?;
+            }
+            let welcome_html = self.html_generator.generate_welcome_page(welcome, start, &page_options, extension_map);
+            self.write_page("pages/welcome.html", welcome_html, sink, &mut render_errors)?;
+        }
+
+        if let Some(objectives) = &request.learning_objectives_page {
+            let objectives_html = self.html_generator.generate_objectives_page(objectives, &page_options, extension_map);
+            self.write_page("pages/objectives.html", objectives_html, sink, &mut render_errors)?;
         }
-        if request.learning_objectives_page.is_some() {
-            resources.push_str("            <file href=\"pages/objectives.html\"/>\n");
+
+        if request.course_map.unwrap_or(false) {
+            let course_map_html = self.html_generator.generate_course_map_page(request);
+            self.write_page("pages/course-map.html", course_map_html, sink, &mut render_errors)?;
         }
+
+        // Generate topic pages
         for topic in &request.topics {
-            resources.push_str(&format!(
-                "            <file href=\"pages/{}.html\"/>\n",
-                topic.id
-            ));
+            let topic_html = self.html_generator.generate_topic_page(topic, &page_options, extension_map);
+            self.write_page(&format!("pages/{}.html", topic.id), topic_html, sink, &mut render_errors)?;
+        }
+
+        // Generate assessment page
+        if let Some(assessment) = &request.assessment {
+            let timer = request.assessment_timer.as_ref().filter(|timer| timer.is_active());
+            let label = request
+                .navigation_menu
+                .as_ref()
+                .and_then(|menu| menu.assessment_label.as_deref());
+            let assessment_html = self.html_generator.generate_assessment_page(assessment, timer, label, extension_map);
+            self.write_page("pages/assessment.html", assessment_html, sink, &mut render_errors)?;
+        }
+
+        if has_transcript_page(request) {
+            let transcript_html = self.html_generator.generate_transcript_page(request, captions);
+            self.write_page("pages/transcript.html", transcript_html, sink, &mut render_errors)?;
+        }
+
+        if has_credits(request) {
+            let credits_html = self.html_generator.generate_credits_page(request);
+            self.write_page("pages/credits.html", credits_html, sink, &mut render_errors)?;
         }
-        if request.assessment.is_some() {
-            resources.push_str("            <file href=\"pages/assessment.html\"/>\n");
+
+        Ok(render_errors)
+    }
+
+    /// Add a rendered page to `sink` after the plugins' HTML filters, or note
+    /// why it couldn't be rendered
+    fn write_page(
+        &self,
+        path: &str,
+        rendered: Result<String, Box<PageRenderError>>,
+        sink: &mut dyn PackageSink,
+        render_errors: &mut Vec<PageRenderError>,
+    ) -> Result<(), String> {
+        match rendered {
+            Ok(html) => {
+                let html = self.plugins.filter_html(path, html)?;
+                sink.add_file(path, html.as_bytes())?;
+            }
+            Err(error) => render_errors.push(*error),
+        }
+        if let Some(progress) = &self.page_progress {
+            progress.page_written();
         }
+        Ok(())
+    }
+
+    fn generate_simple_manifest(
+        &self,
+        request: &GenerateScormRequest,
+        variants: &[&LanguageVariant],
+        audio_sources: &AudioSources,
+        extension_map: Option<&HashMap<String, String>>,
+    ) -> Result<String, String> {
+        let course_version = course_version(request.course_version.as_deref())?;
+        // Builds of a project keep one identifier so LMSs can update the course in place
+        let version = request
+            .package_build
+            .as_ref()
+            .map(|build| PackageVersion::new(course_version, build));
+        let identifier = match &request.package_build {
+            Some(build) => build.manifest_identifier(),
+            None => format!("course-{}", uuid::Uuid::new_v4()),
+        };
+        let language = request.language.as_deref().unwrap_or("en");
+        let lom = manifest_lom(version.as_ref(), &course_keywords(request), language);
+
+        // Some LMSs decide passed/failed from the manifest rather than the runtime
+        let compatibility = request.lms_profile.unwrap_or_default().compatibility();
+        let mastery_score = |course: &GenerateScormRequest| {
+            compatibility.manifest_mastery_score.then_some(course.pass_mark)
+        };
+        let item_title = |course: &GenerateScormRequest| {
+            course
+                .navigation_menu
+                .as_ref()
+                .and_then(|menu| menu.manifest_item_title.clone())
+                .unwrap_or_else(|| course.course_title.clone())
+        };
+
+        let mut organizations = manifest_organization(
+            "default_org",
+            "item_1",
+            "main",
+            &request.course_title,
+            &item_title(request),
+            mastery_score(request),
+        );
+        let mut resources = manifest_resource("main", "", request, audio_sources, extension_map);
 
-        resources.push_str("        </resource>");
+        for variant in variants {
+            let language = &variant.language;
+            organizations.push_str(&manifest_organization(
+                &format!("org_{language}"),
+                &format!("item_{language}"),
+                &format!("main_{language}"),
+                &variant.course.course_title,
+                &item_title(&variant.course),
+                mastery_score(&variant.course),
+            ));
+            resources.push_str(&manifest_resource(
+                &format!("main_{language}"),
+                &format!("{language}/"),
+                &variant.course,
+                audio_sources,
+                extension_map,
+            ));
+        }
 
         Ok(format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
-<manifest identifier="course-{}" version="1.0"
+<manifest identifier="{}" version="{}"
           xmlns="http://www.imsproject.org/xsd/imscp_rootv1p1p2"
           xmlns:adlcp="http://www.adlnet.org/xsd/adlcp_rootv1p2"
           xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
@@ -362,27 +1275,166 @@ impl EnhancedScormGenerator {
     <metadata>
         <schema>ADL SCORM</schema>
         <schemaversion>1.2</schemaversion>
-    </metadata>
+{}    </metadata>
     <organizations default="default_org">
-        <organization identifier="default_org">
-            <title>{}</title>
-            <item identifier="item_1" identifierref="main">
-                <title>{}</title>
-            </item>
-        </organization>
-    </organizations>
+{}    </organizations>
     <resources>
-{}
-    </resources>
+{}    </resources>
 </manifest>"#,
-            uuid::Uuid::new_v4(),
-            request.course_title,
-            request.course_title,
+            identifier,
+            course_version,
+            lom,
+            organizations,
             resources
         ))
     }
 }
 
+/// Language variants of the request, after checking their codes are usable as
+/// folder names and identifiers and don't repeat
+fn language_variants(request: &GenerateScormRequest) -> Result<Vec<&LanguageVariant>, String> {
+    let primary = request.language.as_deref().unwrap_or("en");
+    let mut seen = vec![primary];
+    let mut variants = Vec::new();
+
+    for variant in request.language_variants.iter().flatten() {
+        let language = variant.language.as_str();
+        let valid = !language.is_empty()
+            && language
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(format!("Invalid language code for variant: '{language}'"));
+        }
+        if seen.contains(&language) {
+            return Err(format!("Language '{language}' is included more than once"));
+        }
+        seen.push(language);
+        variants.push(variant);
+    }
+
+    Ok(variants)
+}
+
+/// One `<organization>` with a single item pointing at `resource_id`
+fn manifest_organization(
+    identifier: &str,
+    item_id: &str,
+    resource_id: &str,
+    title: &str,
+    item_title: &str,
+    mastery_score: Option<u32>,
+) -> String {
+    let title = escape_xml(title);
+    let item_title = escape_xml(item_title);
+    let mastery_score = mastery_score
+        .map(|score| format!("\n                <adlcp:masteryscore>{score}</adlcp:masteryscore>"))
+        .unwrap_or_default();
+    format!(
+        r#"        <organization identifier="{identifier}">
+            <title>{title}</title>
+            <item identifier="{item_id}" identifierref="{resource_id}">
+                <title>{item_title}</title>{mastery_score}
+            </item>
+        </organization>
+"#
+    )
+}
+
+/// The SCO resource of one language, listing its files under `prefix`
+fn manifest_resource(
+    identifier: &str,
+    prefix: &str,
+    request: &GenerateScormRequest,
+    audio_sources: &AudioSources,
+    extension_map: Option<&HashMap<String, String>>,
+) -> String {
+    let mut files = vec![
+        "index.html".to_string(),
+        "styles/main.css".to_string(),
+        API_DISCOVERY_PATH.to_string(),
+    ];
+    files.extend(
+        runtime_assets(request).map(|asset| asset.path.to_string()),
+    );
+    files.push(COURSE_DATA_PATH.to_string());
+
+    // Add page files
+    if request.welcome_page.is_some() {
+        files.push("pages/welcome.html".to_string());
+    }
+    if request.learning_objectives_page.is_some() {
+        files.push("pages/objectives.html".to_string());
+    }
+    if request.course_map.unwrap_or(false) {
+        files.push("pages/course-map.html".to_string());
+    }
+    for topic in &request.topics {
+        files.push(format!("pages/{}.html", topic.id));
+    }
+    if request.assessment.is_some() {
+        files.push("pages/assessment.html".to_string());
+    }
+    if has_transcript_page(request) {
+        files.push("pages/transcript.html".to_string());
+    }
+    if has_credits(request) {
+        files.push("pages/credits.html".to_string());
+    }
+
+    let mut resource = format!(
+        "        <resource identifier=\"{identifier}\" type=\"webcontent\" adlcp:scormType=\"sco\" href=\"{prefix}index.html\">\n"
+    );
+    for file in files {
+        resource.push_str(&format!("            <file href=\"{prefix}{file}\"/>\n"));
+    }
+    // Narration in every packaged encoding; variants share the root media folder
+    let narration = request
+        .welcome_page
+        .as_ref()
+        .and_then(|page| page.audio_file.as_ref())
+        .into_iter()
+        .chain(
+            request
+                .learning_objectives_page
+                .as_ref()
+                .and_then(|page| page.audio_file.as_ref()),
+        )
+        .chain(request.topics.iter().filter_map(|topic| topic.audio_file.as_ref()));
+    let mut listed = HashSet::new();
+    for audio_file in narration {
+        for file in audio_sources.files(&HtmlGenerator::ensure_media_path(audio_file)) {
+            if listed.insert(file.clone()) {
+                resource.push_str(&format!("            <file href=\"{file}\"/>\n"));
+            }
+        }
+    }
+    // Feedback images and audio, also from the root media folder
+    let questions = request
+        .topics
+        .iter()
+        .filter_map(|topic| topic.knowledge_check.as_ref())
+        .flat_map(|kc| &kc.questions)
+        .chain(request.assessment.iter().flat_map(|assessment| &assessment.questions));
+    for media in questions.flat_map(Question::feedback_media) {
+        let Some(file) = HtmlGenerator::get_correct_media_url(&media.url, extension_map) else {
+            continue;
+        };
+        if !is_external(&file) && listed.insert(file.clone()) {
+            resource.push_str(&format!("            <file href=\"{}\"/>\n", escape_xml(&file)));
+        }
+    }
+    resource.push_str("        </resource>\n");
+    resource
+}
+
+pub(super) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -407,6 +1459,9 @@ mod tests {
                         explanation: Some("Paris is the capital of France.".to_string()),
                         correct_feedback: None,
                         incorrect_feedback: None,
+                        correct_feedback_media: None,
+                        incorrect_feedback_media: None,
+                        bank_ref: None,
                     }],
                 }),
                 ..Default::default()
@@ -419,20 +1474,309 @@ mod tests {
     }
 
     #[test]
-    fn test_media_item_with_youtube_fields() {
-        // Test that MediaItem can deserialize with YouTube fields
-        let json_data = serde_json::json!({
-            "id": "youtube-1",
-            "type": "video",
-            "url": "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
-            "title": "Test Video",
-            "embed_url": "https://www.youtube.com/embed/dQw4w9WgXcQ",
-            "is_youtube": true
-        });
+    fn test_generation_summary_reports_compression() {
+        let generator = EnhancedScormGenerator::new()
+            .unwrap()
+            .with_compression_level(Some(9));
+        let request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                content: "<p>Content</p>".to_string(),
+                audio_file: Some("media/audio-0.mp3".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let media = HashMap::from([(
+            "media/audio-0.mp3".to_string(),
+            vec![0xFF, 0xFB, 0x90, 0x00],
+        )]);
 
-        let media_item: MediaItem = serde_json::from_value(json_data).unwrap();
+        let (_, summary) = generator
+            .generate_scorm_package_with_summary(request, media, None, None)
+            .unwrap();
 
-        assert_eq!(media_item.id, "youtube-1");
+        assert_eq!(summary.compression.level, Some(9));
+        assert_eq!(summary.compression.stored_files, 1);
+        assert_eq!(summary.compression.stored_bytes, 4);
+        assert!(summary.compression.deflated_files > 0);
+        assert!(summary.media_transforms.is_none());
+
+        let invalid = EnhancedScormGenerator::new()
+            .unwrap()
+            .with_compression_level(Some(10));
+        assert!(invalid
+            .generate_scorm_package(GenerateScormRequest::default(), HashMap::new(), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_page_progress_is_reported_per_page() {
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let generator = EnhancedScormGenerator::new()
+            .unwrap()
+            .with_page_progress(move |written, total| sink.lock().unwrap().push((written, total)));
+        let topic = |id: &str| Topic {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: "<p>Content</p>".to_string(),
+            ..Default::default()
+        };
+        let request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            topics: vec![topic("topic-1"), topic("topic-2")],
+            ..Default::default()
+        };
+
+        generator
+            .generate_scorm_package(request, HashMap::new(), None)
+            .unwrap();
+
+        assert_eq!(*reports.lock().unwrap(), [(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_missing_media_is_reported_and_optionally_substituted() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let request = |placeholders| GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                content: "<p>Content</p>".to_string(),
+                audio_file: Some("media/audio-0.mp3".to_string()),
+                caption_file: Some("media/caption-0.vtt".to_string()),
+                ..Default::default()
+            }],
+            media_placeholders: Some(placeholders),
+            ..Default::default()
+        };
+        let media = HashMap::from([("media/audio-0.mp3".to_string(), vec![0xFF, 0xFB])]);
+
+        let (package, summary) = generator
+            .generate_scorm_package_with_summary(request(false), media.clone(), None, None)
+            .unwrap();
+        assert_eq!(summary.missing_media.len(), 1);
+        assert_eq!(summary.missing_media[0].page_id, "topic-1");
+        assert_eq!(summary.missing_media[0].media_id, "caption-0");
+        assert!(!summary.missing_media[0].substituted);
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+        assert!(archive.by_name("media/caption-0.vtt").is_err());
+
+        let (package, summary) = generator
+            .generate_scorm_package_with_summary(request(true), media, None, None)
+            .unwrap();
+        assert!(summary.missing_media[0].substituted);
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+        assert!(archive.by_name("media/caption-0.vtt").is_ok());
+    }
+
+    #[test]
+    fn test_preview_lists_package_files_without_zipping() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+
+        let request = GenerateScormRequest {
+            course_title: "Preview Course".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                content: "<p>Preview content</p>".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut media = HashMap::new();
+        media.insert("media/image-0.png".to_string(), vec![0u8; 42]);
+
+        let preview = generator
+            .generate_scorm_preview(request, media, None)
+            .unwrap();
+
+        let paths: Vec<_> = preview.files.iter().map(|f| f.path.as_str()).collect();
+        for expected in [
+            "index.html",
+            "imsmanifest.xml",
+            "scripts/navigation.js",
+            "scripts/course-data.js",
+            "styles/main.css",
+            "pages/topic-1.html",
+            "media/image-0.png",
+        ] {
+            assert!(paths.contains(&expected), "missing {expected}");
+        }
+
+        let media_file = preview
+            .files
+            .iter()
+            .find(|f| f.path == "media/image-0.png")
+            .unwrap();
+        assert_eq!(media_file.size, 42);
+
+        let topic_page = preview
+            .pages
+            .iter()
+            .find(|p| p.page_id == "topic-1")
+            .unwrap();
+        assert!(topic_page.html.contains("Preview content"));
+        assert!(!preview.pages.iter().any(|p| p.path.ends_with(".js")));
+    }
+
+    #[test]
+    fn test_page_preview_renders_one_page_in_the_shell() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+
+        let request = || GenerateScormRequest {
+            course_title: "Preview Course".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                content: "<p>Preview content</p>".to_string(),
+                image_url: Some("media/image-2.jpg".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let html = generator
+            .render_page_preview(request(), "topic-1", None, "asset://localhost/7/media/")
+            .unwrap();
+        assert!(html.contains("Preview content"));
+        assert!(html.contains("asset://localhost/7/media/image-2.bin"));
+        assert!(html.contains("<style>"));
+
+        assert!(generator
+            .render_page_preview(request(), "topic-9", None, "asset://localhost/7/media/")
+            .is_err());
+    }
+
+    #[test]
+    fn test_narration_fallbacks_become_sources_and_manifest_files() {
+        use std::io::Read;
+
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let request = GenerateScormRequest {
+            course_title: "Audio Course".to_string(),
+            topics: vec![
+                Topic {
+                    id: "topic-1".to_string(),
+                    title: "Topic 1".to_string(),
+                    content: "<p>Content</p>".to_string(),
+                    audio_file: Some("audio-1.mp3".to_string()),
+                    ..Default::default()
+                },
+                Topic {
+                    id: "topic-2".to_string(),
+                    title: "Topic 2".to_string(),
+                    content: "<p>Content</p>".to_string(),
+                    audio_file: Some("audio-2.mp3".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let media = HashMap::from([
+            ("media/audio-1.mp3".to_string(), vec![1u8; 10]),
+            ("media/audio-1.ogg".to_string(), vec![2u8; 10]),
+            ("media/audio-2.mp3".to_string(), vec![3u8; 10]),
+        ]);
+
+        let package = generator
+            .generate_scorm_package(request, media, None)
+            .unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+        let mut read = |path: &str| {
+            let mut contents = String::new();
+            archive
+                .by_name(path)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        };
+
+        let topic_1 = read("pages/topic-1.html");
+        assert!(topic_1.contains(r#"<source src="media/audio-1.mp3" type="audio/mpeg">"#));
+        assert!(topic_1.contains(r#"<source src="media/audio-1.ogg" type="audio/ogg">"#));
+        // The player itself has no src, or browsers would ignore the sources
+        assert_eq!(topic_1.matches(r#"src="media/audio-1.mp3""#).count(), 1);
+        let topic_2 = read("pages/topic-2.html");
+        assert!(topic_2.contains(r#"src="media/audio-2.mp3""#));
+        assert!(!topic_2.contains("<source"));
+
+        let manifest = read("imsmanifest.xml");
+        for file in ["media/audio-1.mp3", "media/audio-1.ogg", "media/audio-2.mp3"] {
+            assert!(manifest.contains(&format!(r#"<file href="{file}"/>"#)), "missing {file}");
+        }
+    }
+
+    #[test]
+    fn test_plugins_filter_pages_and_add_files() {
+        use crate::scorm::plugins::GeneratorPlugin;
+
+        struct Tracking;
+
+        impl GeneratorPlugin for Tracking {
+            fn name(&self) -> &str {
+                "tracking"
+            }
+
+            fn filter_html(&self, path: &str, html: String) -> Result<String, String> {
+                Ok(format!("{html}<!-- tracked {path} -->"))
+            }
+
+            fn extra_files(
+                &self,
+                _request: &GenerateScormRequest,
+            ) -> Result<Vec<(String, Vec<u8>)>, String> {
+                Ok(vec![("scripts/tracking.js".to_string(), b"track();".to_vec())])
+            }
+        }
+
+        let generator = EnhancedScormGenerator::new()
+            .unwrap()
+            .with_plugin(std::sync::Arc::new(Tracking));
+        let request = GenerateScormRequest {
+            course_title: "Tracked Course".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                content: "<p>Content</p>".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let preview = generator
+            .generate_scorm_preview(request, HashMap::new(), None)
+            .unwrap();
+
+        assert!(preview.files.iter().any(|f| f.path == "scripts/tracking.js"));
+        let topic_page = preview
+            .pages
+            .iter()
+            .find(|p| p.page_id == "topic-1")
+            .unwrap();
+        assert!(topic_page.html.ends_with("<!-- tracked pages/topic-1.html -->"));
+    }
+
+    #[test]
+    fn test_media_item_with_youtube_fields() {
+        // Test that MediaItem can deserialize with YouTube fields
+        let json_data = serde_json::json!({
+            "id": "youtube-1",
+            "type": "video",
+            "url": "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "title": "Test Video",
+            "embed_url": "https://www.youtube.com/embed/dQw4w9WgXcQ",
+            "is_youtube": true
+        });
+
+        let media_item: MediaItem = serde_json::from_value(json_data).unwrap();
+
+        assert_eq!(media_item.id, "youtube-1");
         assert_eq!(media_item.media_type, "video");
         assert_eq!(
             media_item.url,
@@ -465,7 +1809,10 @@ mod tests {
                 is_youtube: Some(true),
                 clip_start: Some(30),
                 clip_end: Some(120),
+                alt_text: None,
+                attribution: None,
             }]),
+            narration: None,
         };
 
         assert!(topic.media.is_some());
@@ -474,4 +1821,566 @@ mod tests {
         assert_eq!(media_items[0].is_youtube, Some(true));
         assert!(media_items[0].embed_url.is_some());
     }
+
+    #[test]
+    fn test_media_transforms_rewrite_package_references() {
+        use std::io::Read;
+
+        struct ShrinkingTranscoder;
+
+        impl MediaTranscoder for ShrinkingTranscoder {
+            fn transcode(
+                &self,
+                _input: &[u8],
+                _input_ext: &str,
+                _output_ext: &str,
+                _args: &[String],
+            ) -> Result<Vec<u8>, String> {
+                Ok(vec![1u8; 10])
+            }
+        }
+
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let request = GenerateScormRequest {
+            course_title: "Transform Course".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                content: "<p>Content</p>".to_string(),
+                image_url: Some("media/image-0.png".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut media = HashMap::new();
+        media.insert("media/image-0.png".to_string(), vec![0u8; 42]);
+        let spec = MediaTransformSpec {
+            convert_images_to_webp: true,
+            ..Default::default()
+        };
+
+        let (package, report) = generator
+            .generate_scorm_package_with_transforms(
+                request,
+                media,
+                None,
+                &spec,
+                &ShrinkingTranscoder,
+            )
+            .unwrap();
+
+        assert_eq!(report.bytes_saved, 32);
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+        assert!(archive.by_name("media/image-0.png").is_err());
+        assert_eq!(archive.by_name("media/image-0.webp").unwrap().size(), 10);
+
+        let mut topic_html = String::new();
+        archive
+            .by_name("pages/topic-1.html")
+            .unwrap()
+            .read_to_string(&mut topic_html)
+            .unwrap();
+        assert!(topic_html.contains(r#""media/image-0.webp""#));
+        assert!(!topic_html.contains("image-0.png"));
+    }
+
+    #[test]
+    fn test_animated_gif_packaged_as_video_plays_with_controls() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let topic = Topic {
+            id: "topic-1".to_string(),
+            title: "Topic 1".to_string(),
+            content: "<p>Content</p>".to_string(),
+            media: Some(vec![MediaItem {
+                id: "image-3".to_string(),
+                media_type: "image".to_string(),
+                url: "media/image-3.gif".to_string(),
+                title: "Saving a report".to_string(),
+                embed_url: None,
+                is_youtube: None,
+                clip_start: None,
+                clip_end: None,
+                alt_text: None,
+                attribution: None,
+            }]),
+            ..Default::default()
+        };
+        let media = HashMap::from([("media/image-3.mp4".to_string(), vec![0u8; 10])]);
+        let gif_videos = GifVideos::from_media_files(&media);
+
+        let html = generator
+            .html_generator
+            .generate_topic_page(&topic, &PageOptions::default().with_gif_videos(&gif_videos), None)
+            .unwrap();
+        assert!(html.contains(r#"<video controls loop muted playsinline class="topic-video animated-gif" aria-label="Saving a report">"#));
+        assert!(html.contains(r#"<source src="media/image-3.mp4" type="video/mp4">"#));
+        assert!(!html.contains("<img"));
+
+        let html = generator
+            .html_generator
+            .generate_topic_page(&topic, &PageOptions::default(), None)
+            .unwrap();
+        assert!(html.contains(r#"<img src="media/image-3.gif""#));
+    }
+
+    #[test]
+    fn test_language_variants_get_their_own_organization() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let topic = |title: &str| Topic {
+            id: "topic-1".to_string(),
+            title: title.to_string(),
+            content: "<p>Content</p>".to_string(),
+            image_url: Some("media/image-0.png".to_string()),
+            ..Default::default()
+        };
+        let request = GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            topics: vec![topic("Hazards")],
+            language_variants: Some(vec![LanguageVariant {
+                language: "fr".to_string(),
+                course: GenerateScormRequest {
+                    course_title: "Sécurité".to_string(),
+                    language: Some("fr".to_string()),
+                    topics: vec![topic("Dangers")],
+                    ..Default::default()
+                },
+            }]),
+            ..Default::default()
+        };
+        let mut media = HashMap::new();
+        media.insert("media/image-0.png".to_string(), vec![0u8; 42]);
+
+        let preview = generator
+            .generate_scorm_preview(request, media, None)
+            .unwrap();
+
+        let file = |path: &str| preview.files.iter().any(|f| f.path == path);
+        assert!(file("index.html") && file("fr/index.html"));
+        assert!(file("fr/pages/topic-1.html") && file("fr/scripts/navigation.js"));
+        assert!(!file("fr/media/image-0.png"));
+
+        let fr_topic = preview
+            .pages
+            .iter()
+            .find(|p| p.page_id == "fr/topic-1")
+            .unwrap();
+        assert!(fr_topic.html.contains("Dangers"));
+        assert!(fr_topic.html.contains(r#""../media/image-0.png""#));
+        let fr_index = preview
+            .pages
+            .iter()
+            .find(|p| p.page_id == "fr/index")
+            .unwrap();
+        assert!(fr_index.html.contains(r#"<html lang="fr">"#));
+
+        let mut sink = CollectSink::new();
+        let request = GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            language_variants: Some(vec![LanguageVariant {
+                language: "en".to_string(),
+                course: GenerateScormRequest::default(),
+            }]),
+            ..Default::default()
+        };
+        let error = generator
+            .write_package(&request, &HashMap::new(), None, &mut sink)
+            .unwrap_err();
+        assert!(error.to_string().contains("more than once"));
+    }
+
+    #[test]
+    fn test_course_map_page_follows_objectives() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let request = GenerateScormRequest {
+            course_title: "Long Course".to_string(),
+            learning_objectives_page: Some(ObjectivesPage {
+                objectives: vec!["Objective".to_string()],
+                audio_file: None,
+                caption_file: None,
+                image_url: None,
+                media: None,
+            }),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Ladders & Scaffolds".to_string(),
+                content: "<p>Content</p>".to_string(),
+                knowledge_check: Some(KnowledgeCheck {
+                    enabled: true,
+                    questions: vec![Question {
+                        question_type: "true-false".to_string(),
+                        text: "Check the ladder first?".to_string(),
+                        options: None,
+                        correct_answer: "true".to_string(),
+                        explanation: None,
+                        correct_feedback: None,
+                        incorrect_feedback: None,
+                        correct_feedback_media: None,
+                        incorrect_feedback_media: None,
+                        bank_ref: None,
+                    }],
+                }),
+                ..Default::default()
+            }],
+            assessment: Some(Assessment { questions: Vec::new() }),
+            course_map: Some(true),
+            ..Default::default()
+        };
+
+        let preview = generator
+            .generate_scorm_preview(request, HashMap::new(), None)
+            .unwrap();
+        let html = |page_id: &str| {
+            preview
+                .pages
+                .iter()
+                .find(|page| page.page_id == page_id)
+                .map(|page| page.html.clone())
+                .unwrap_or_default()
+        };
+
+        let map = html("course-map");
+        assert!(map.contains("<h3>Getting Started</h3>"));
+        assert!(map.contains(r#"data-map-page="objectives""#));
+        assert!(map.contains(r#"data-page="topic-1">Ladders &amp; Scaffolds</button>"#));
+        assert!(map.contains("Knowledge check"));
+        assert!(map.contains("of 3 pages completed"));
+
+        let index = html("index");
+        let objectives = index.find(r#"data-page="objectives""#).unwrap();
+        let course_map = index.find(r#"data-page="course-map""#).unwrap();
+        let topic = index.find(r#"data-page="topic-1""#).unwrap();
+        assert!(objectives < course_map && course_map < topic);
+
+        let without_map = generator
+            .generate_scorm_preview(GenerateScormRequest::default(), HashMap::new(), None)
+            .unwrap();
+        assert!(without_map.files.iter().all(|f| f.path != "pages/course-map.html"));
+    }
+
+    #[test]
+    fn test_credits_page_follows_assessment() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let request = GenerateScormRequest {
+            course_title: "Harbours".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Ships".to_string(),
+                content: "<p>Content</p>".to_string(),
+                media: Some(vec![MediaItem {
+                    id: "image-1".to_string(),
+                    media_type: "image".to_string(),
+                    url: "media/image-1.jpg".to_string(),
+                    title: "Dry dock".to_string(),
+                    embed_url: None,
+                    is_youtube: None,
+                    clip_start: None,
+                    clip_end: None,
+                    alt_text: Some("Ship in a dry dock".to_string()),
+                    attribution: Some(MediaAttribution {
+                        provider: "pexels".to_string(),
+                        author: Some("Grace Hopper".to_string()),
+                        license: Some("Pexels License".to_string()),
+                        license_url: Some("https://www.pexels.com/license/".to_string()),
+                        ..Default::default()
+                    }),
+                }]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let preview = generator
+            .generate_scorm_preview(request, HashMap::new(), None)
+            .unwrap();
+        let html = |page_id: &str| {
+            preview
+                .pages
+                .iter()
+                .find(|page| page.page_id == page_id)
+                .map(|page| page.html.clone())
+                .unwrap_or_default()
+        };
+
+        let credits = html("credits");
+        assert!(credits.contains("Grace Hopper"));
+        assert!(credits.contains(r#"<a href="https://www.pexels.com/license/""#));
+
+        let index = html("index");
+        let assessment = index.find(r#"data-page="assessment""#).unwrap();
+        let credits = index.find(r#"data-page="credits""#).unwrap();
+        assert!(assessment < credits);
+        assert!(preview.files.iter().any(|f| f.path == "pages/credits.html"));
+    }
+
+    #[test]
+    fn test_transcripts_come_from_narration_or_captions() {
+        use std::io::Read;
+
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let request = GenerateScormRequest {
+            course_title: "Gloves".to_string(),
+            topics: vec![
+                Topic {
+                    id: "topic-1".to_string(),
+                    title: "Choosing gloves".to_string(),
+                    content: "<p>Content</p>".to_string(),
+                    narration: Some("Pick gloves for the job & the chemical.".to_string()),
+                    ..Default::default()
+                },
+                Topic {
+                    id: "topic-2".to_string(),
+                    title: "Checking gloves".to_string(),
+                    content: "<p>Content</p>".to_string(),
+                    caption_file: Some("media/caption-2.vtt".to_string()),
+                    ..Default::default()
+                },
+            ],
+            assessment: Some(Assessment { questions: Vec::new() }),
+            transcripts: Some(true),
+            ..Default::default()
+        };
+        let media = HashMap::from([(
+            "media/caption-2.vtt".to_string(),
+            b"WEBVTT\n\n00:00:00.000 --> 00:00:02.000\nLook for <i>tears</i>.\n".to_vec(),
+        )]);
+
+        let package = generator
+            .generate_scorm_package(request, media, None)
+            .unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+        let mut read = |path: &str| {
+            let mut contents = String::new();
+            archive
+                .by_name(path)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        };
+
+        let topic_1 = read("pages/topic-1.html");
+        assert!(topic_1.contains(r#"<details class="topic-transcript">"#));
+        assert!(topic_1.contains("<p>Pick gloves for the job &amp; the chemical.</p>"));
+        assert!(read("pages/topic-2.html").contains("<p>Look for tears.</p>"));
+
+        let transcript = read("pages/transcript.html");
+        let first = transcript.find("<h3>Choosing gloves</h3>").unwrap();
+        let second = transcript.find("<h3>Checking gloves</h3>").unwrap();
+        assert!(first < second);
+
+        let index = read("index.html");
+        let assessment = index.find(r#"data-page="assessment""#).unwrap();
+        let transcript_link = index.find(r#"data-page="transcript""#).unwrap();
+        assert!(assessment < transcript_link);
+        assert!(read("imsmanifest.xml").contains(r#"<file href="pages/transcript.html"/>"#));
+    }
+
+    #[test]
+    fn test_project_builds_are_stamped() {
+        use crate::scorm::package_version::read_package_version;
+
+        let built_at = chrono::DateTime::parse_from_rfc3339("2024-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let request = || GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            course_version: Some("2.1".to_string()),
+            package_build: Some(PackageBuild::new("1700000000000", "0123abcd4567ef89", built_at)),
+            ..Default::default()
+        };
+
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let zip = generator
+            .generate_scorm_package(request(), HashMap::new(), None)
+            .unwrap();
+        let version = read_package_version(&zip).unwrap().expect("version.json");
+        assert_eq!(version.course_version, "2.1");
+        assert_eq!(version.build_id, "20240301-120000-0123abcd");
+        assert_eq!(version.project_id, "1700000000000");
+
+        let manifest = generator.generate_simple_manifest(&request(), &[], &AudioSources::default(), None).unwrap();
+        assert!(manifest.contains(r#"<manifest identifier="course-1700000000000" version="2.1""#));
+        assert!(manifest.contains("<identifier>20240301-120000-0123abcd</identifier>"));
+
+        // Previews and other unstamped packages keep a fresh identifier
+        let unstamped = generator
+            .generate_scorm_package(GenerateScormRequest::default(), HashMap::new(), None)
+            .unwrap();
+        assert_eq!(read_package_version(&unstamped).unwrap(), None);
+    }
+
+    #[test]
+    fn test_lms_profile_sets_manifest_mastery_score() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let mut request = GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            pass_mark: 75,
+            ..Default::default()
+        };
+
+        let manifest = generator.generate_simple_manifest(&request, &[], &AudioSources::default(), None).unwrap();
+        assert!(!manifest.contains("adlcp:masteryscore"));
+
+        request.lms_profile = Some(LmsProfile::SuccessFactors);
+        let manifest = generator.generate_simple_manifest(&request, &[], &AudioSources::default(), None).unwrap();
+        assert!(manifest.contains(
+            "<title>Safety</title>\n                <adlcp:masteryscore>75</adlcp:masteryscore>\n            </item>"
+        ));
+    }
+
+    #[test]
+    fn test_navigation_menu_labels_reach_the_menu_and_manifest() {
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let request = GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Hazards".to_string(),
+                ..Default::default()
+            }],
+            navigation_menu: Some(NavigationMenu {
+                assessment_label: Some("Final quiz".to_string()),
+                manifest_item_title: Some("Safety & you".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let index = generator.html_generator.generate_index_html(&request).unwrap();
+        assert!(index.contains(r#"class="nav-item" data-page="assessment">Final quiz</a>"#));
+        assert!(index.contains(r#"class="nav-item active" data-page="welcome">Welcome</a>"#));
+
+        let manifest = generator.generate_simple_manifest(&request, &[], &AudioSources::default(), None).unwrap();
+        assert!(manifest.contains("<title>Safety</title>\n            <item"));
+        assert!(manifest.contains("<title>Safety &amp; you</title>"));
+    }
+
+    #[test]
+    fn test_feedback_media_is_rendered_and_listed() {
+        let request = GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                knowledge_check: Some(KnowledgeCheck {
+                    enabled: true,
+                    questions: vec![Question {
+                        question_type: "true-false".to_string(),
+                        text: "Gloves are optional.".to_string(),
+                        options: None,
+                        correct_answer: "false".to_string(),
+                        explanation: None,
+                        correct_feedback: None,
+                        incorrect_feedback: None,
+                        correct_feedback_media: Some(FeedbackMedia {
+                            media_type: FeedbackMediaType::Image,
+                            url: "image-5".to_string(),
+                            alt_text: Some("Gloved hands".to_string()),
+                        }),
+                        incorrect_feedback_media: Some(FeedbackMedia {
+                            media_type: FeedbackMediaType::Audio,
+                            url: "audio-9".to_string(),
+                            alt_text: None,
+                        }),
+                        bank_ref: None,
+                    }],
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let extension_map = HashMap::from([
+            ("image-5".to_string(), ".png".to_string()),
+            ("audio-9".to_string(), ".mp3".to_string()),
+        ]);
+
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let html = generator
+            .html_generator
+            .generate_topic_page(&request.topics[0], &PageOptions::default(), Some(&extension_map))
+            .unwrap();
+        assert!(html.contains(r#"data-feedback-media="correct" hidden"#));
+        assert!(html.contains(r#"<img src="media/image-5.png" alt="Gloved hands""#));
+        assert!(html.contains(r#"<audio src="media/audio-9.mp3" controls"#));
+
+        let manifest = generator
+            .generate_simple_manifest(&request, &[], &AudioSources::default(), Some(&extension_map))
+            .unwrap();
+        assert!(manifest.contains(r#"<file href="media/image-5.png"/>"#));
+        assert!(manifest.contains(r#"<file href="media/audio-9.mp3"/>"#));
+    }
+
+    #[test]
+    fn test_katex_only_packaged_for_math_content() {
+        use std::io::Read;
+
+        let request = |content: &str| GenerateScormRequest {
+            course_title: "Kinematics".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Velocity".to_string(),
+                content: content.to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let files = |request: GenerateScormRequest| {
+            let package = generator
+                .generate_scorm_package(request, HashMap::new(), None)
+                .unwrap();
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package)).unwrap();
+            let mut index_html = String::new();
+            archive
+                .by_name("index.html")
+                .unwrap()
+                .read_to_string(&mut index_html)
+                .unwrap();
+            let names: Vec<String> = archive.file_names().map(String::from).collect();
+            (names, index_html)
+        };
+
+        let (names, index_html) = files(request("<p>Speed in a direction.</p>"));
+        assert!(!names.contains(&"scripts/katex.min.js".to_string()));
+        assert!(!index_html.contains("scripts/math.js"));
+
+        let math = request(r"<p>Average velocity is \(v = \frac{\Delta x}{\Delta t}\).</p>");
+        let manifest = generator.generate_simple_manifest(&math, &[], &AudioSources::default(), None).unwrap();
+        assert!(manifest.contains(r#"<file href="scripts/katex.min.js"/>"#));
+        let (names, index_html) = files(math);
+        assert!(names.contains(&"scripts/katex.min.js".to_string()));
+        assert!(names.contains(&"scripts/math.js".to_string()));
+        let katex = index_html.find("scripts/katex.min.js").unwrap();
+        assert!(katex < index_html.find("scripts/math.js").unwrap());
+    }
+
+    #[test]
+    fn test_manifest_lists_language_organizations() {
+        let variant = LanguageVariant {
+            language: "fr".to_string(),
+            course: GenerateScormRequest {
+                course_title: "Sécurité & santé".to_string(),
+                ..Default::default()
+            },
+        };
+        let request = GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            ..Default::default()
+        };
+
+        let generator = EnhancedScormGenerator::new().unwrap();
+        let manifest = generator
+            .generate_simple_manifest(&request, &[&variant], &AudioSources::default(), None)
+            .unwrap();
+
+        assert!(manifest.contains(r#"<organization identifier="org_fr">"#));
+        assert!(manifest.contains(r#"<item identifier="item_fr" identifierref="main_fr">"#));
+        assert!(manifest.contains(r#"href="fr/index.html""#));
+        assert!(manifest.contains("<title>Sécurité &amp; santé</title>"));
+
+        let errors = crate::scorm::output_validator::check_manifest_resources(&manifest, |href| {
+            !href.starts_with("fr/scripts/")
+        });
+        assert_eq!(errors.len(), 4, "{errors:?}");
+    }
 }
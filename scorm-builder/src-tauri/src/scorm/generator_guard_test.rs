@@ -9,10 +9,10 @@ mod tests {
         // This test ensures that we always receive generated files from JavaScript
         let request = GenerateScormRequest {
             project_id: "test-project".to_string(),
-            course_content: json!({
+            course_content: serde_json::from_value(json!({
                 "title": "Test Course",
                 "topics": []
-            }),
+            })).unwrap(),
             course_metadata: CourseMetadata {
                 title: "Test Course".to_string(),
                 description: "Test".to_string(),
@@ -41,10 +41,10 @@ mod tests {
         // This is the correct usage - always provide generated files
         let request = GenerateScormRequest {
             project_id: "test-project".to_string(),
-            course_content: json!({
+            course_content: serde_json::from_value(json!({
                 "title": "Test Course",
                 "topics": []
-            }),
+            })).unwrap(),
             course_metadata: CourseMetadata {
                 title: "Test Course".to_string(),
                 description: "Test".to_string(),
@@ -135,8 +135,8 @@ fn get_current_memory_usage() -> usize {
     }
 }
 
-fn create_test_course_content() -> serde_json::Value {
-    serde_json::json!({
+fn create_test_course_content() -> crate::course_content::CourseContent {
+    serde_json::from_value(serde_json::json!({
         "welcomePage": {
             "title": "Welcome",
             "content": "Welcome to the course"
@@ -150,5 +150,6 @@ fn create_test_course_content() -> serde_json::Value {
             "questions": [],
             "passMark": 80
         }
-    })
+    }))
+    .unwrap()
 }
@@ -1,19 +1,11 @@
+use crate::course_content::{Assessment, ContentPage, CourseContent, MediaRef};
 use crate::scorm::generator::CourseMetadata;
-use serde_json::Value;
 use std::path::Path;
 
 #[allow(dead_code)]
-pub fn generate_welcome_page_html(welcome: &Value) -> String {
-    let title = welcome
-        .get("title")
-        .and_then(|v| v.as_str())
-        .unwrap_or("Welcome");
-    let content = welcome
-        .get("content")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let audio_id = welcome.get("audioId").and_then(|v| v.as_str());
-    let caption_id = welcome.get("captionId").and_then(|v| v.as_str());
+pub fn generate_welcome_page_html(welcome: &ContentPage) -> String {
+    let title = welcome.title.as_deref().unwrap_or("Welcome");
+    let content = welcome.content.as_deref().unwrap_or("");
 
     let mut html = format!(
         r#"
@@ -25,82 +17,15 @@ pub fn generate_welcome_page_html(welcome: &Value) -> String {
 "#
     );
 
-    // Add media if present
-    if let Some(media_array) = welcome.get("media").and_then(|v| v.as_array()) {
-        for media in media_array {
-            let media_type = media.get("type").and_then(|v| v.as_str()).unwrap_or("");
-            let media_id = media.get("id").and_then(|v| v.as_str()).unwrap_or("");
-            let media_title = media.get("title").and_then(|v| v.as_str()).unwrap_or("");
-
-            match media_type {
-                "image" | "svg" => {
-                    // Get URL from media object, fallback to media_id with extension detection
-                    let image_url = if let Some(url) = media.get("url").and_then(|v| v.as_str()) {
-                        println!("[Rust HTML Gen] Found URL field for {}: '{}' (welcome page)", media_id, url);
-                        // If URL is provided, extract the filename with extension
-                        if url.starts_with("media/") {
-                            println!("[Rust HTML Gen] Using URL directly: {}", url);
-                            url.to_string()
-                        } else if url.starts_with("/media/") {
-                            let stripped = url.strip_prefix("/").unwrap_or(url).to_string();
-                            println!("[Rust HTML Gen] Stripped leading slash: {}", stripped);
-                            stripped
-                        } else {
-                            // External URL or relative path - use as filename
-                            let filename = format!("media/{}", Path::new(url).file_name().unwrap_or_default().to_string_lossy());
-                            println!("[Rust HTML Gen] Extracted filename from URL: {}", filename);
-                            filename
-                        }
-                    } else {
-                        println!("[Rust HTML Gen] No URL field found for {}, using fallback extension detection", media_id);
-                        // Fallback: try to detect extension from media_id or use default
-                        let extension = detect_image_extension(media_id, media_type);
-                        let fallback_url = format!("media/{}{}", media_id, extension);
-                        println!("[Rust HTML Gen] Fallback URL: {}", fallback_url);
-                        fallback_url
-                    };
-
-                    let img_class = if media_type == "svg" { "content-svg" } else { "content-image" };
-                    html.push_str(&format!(
-                        r#"    <img src="{}" alt="{}" class="{}" />
-"#,
-                        image_url, media_title, img_class
-                    ));
-                }
-                "video" => {
-                    html.push_str(&format!(
-                        r#"    <video src="media/{media_id}.mp4" controls class="content-video"></video>
-"#
-                    ));
-                }
-                _ => {}
-            }
-        }
-    }
-
-    // Add audio narration if present
-    if let (Some(audio), Some(caption)) = (audio_id, caption_id) {
-        html.push_str(&format!(
-            r#"    <audio id="narration-audio" controls class="narration-audio">
-        <source src="media/{audio}.mp3" type="audio/mpeg">
-        <track kind="captions" src="media/{caption}.vtt" srclang="en" label="English" default>
-    </audio>
-"#
-        ));
-    }
-
+    html.push_str(&media_html(welcome.media(), "welcome page"));
+    html.push_str(&narration_html(welcome));
     html.push_str("</div>\n");
     html
 }
 
 #[allow(dead_code)]
-pub fn generate_objectives_page_html(objectives_page: &Value) -> String {
-    let content = objectives_page
-        .get("content")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let audio_id = objectives_page.get("audioId").and_then(|v| v.as_str());
-    let caption_id = objectives_page.get("captionId").and_then(|v| v.as_str());
+pub fn generate_objectives_page_html(objectives_page: &ContentPage) -> String {
+    let content = objectives_page.content.as_deref().unwrap_or("");
 
     let mut html = format!(
         r#"
@@ -109,83 +34,16 @@ pub fn generate_objectives_page_html(objectives_page: &Value) -> String {
 "#
     );
 
-    // Add media if present
-    if let Some(media_array) = objectives_page.get("media").and_then(|v| v.as_array()) {
-        for media in media_array {
-            let media_type = media.get("type").and_then(|v| v.as_str()).unwrap_or("");
-            let media_id = media.get("id").and_then(|v| v.as_str()).unwrap_or("");
-            let media_title = media.get("title").and_then(|v| v.as_str()).unwrap_or("");
-
-            match media_type {
-                "image" | "svg" => {
-                    // Get URL from media object, fallback to media_id with extension detection
-                    let image_url = if let Some(url) = media.get("url").and_then(|v| v.as_str()) {
-                        println!("[Rust HTML Gen] Found URL field for {}: '{}' (objectives page)", media_id, url);
-                        // If URL is provided, extract the filename with extension
-                        if url.starts_with("media/") {
-                            println!("[Rust HTML Gen] Using URL directly: {}", url);
-                            url.to_string()
-                        } else if url.starts_with("/media/") {
-                            let stripped = url.strip_prefix("/").unwrap_or(url).to_string();
-                            println!("[Rust HTML Gen] Stripped leading slash: {}", stripped);
-                            stripped
-                        } else {
-                            // External URL or relative path - use as filename
-                            let filename = format!("media/{}", Path::new(url).file_name().unwrap_or_default().to_string_lossy());
-                            println!("[Rust HTML Gen] Extracted filename from URL: {}", filename);
-                            filename
-                        }
-                    } else {
-                        println!("[Rust HTML Gen] No URL field found for {}, using fallback extension detection", media_id);
-                        // Fallback: try to detect extension from media_id or use default
-                        let extension = detect_image_extension(media_id, media_type);
-                        let fallback_url = format!("media/{}{}", media_id, extension);
-                        println!("[Rust HTML Gen] Fallback URL: {}", fallback_url);
-                        fallback_url
-                    };
-
-                    let img_class = if media_type == "svg" { "content-svg" } else { "content-image" };
-                    html.push_str(&format!(
-                        r#"    <img src="{}" alt="{}" class="{}" />
-"#,
-                        image_url, media_title, img_class
-                    ));
-                }
-                "video" => {
-                    html.push_str(&format!(
-                        r#"    <video src="media/{media_id}.mp4" controls class="content-video"></video>
-"#
-                    ));
-                }
-                _ => {}
-            }
-        }
-    }
-
-    // Add audio narration if present
-    if let (Some(audio), Some(caption)) = (audio_id, caption_id) {
-        html.push_str(&format!(
-            r#"    <audio id="narration-audio" controls class="narration-audio">
-        <source src="media/{audio}.mp3" type="audio/mpeg">
-        <track kind="captions" src="media/{caption}.vtt" srclang="en" label="English" default>
-    </audio>
-"#
-        ));
-    }
-
+    html.push_str(&media_html(objectives_page.media(), "objectives page"));
+    html.push_str(&narration_html(objectives_page));
     html.push_str("</div>\n");
     html
 }
 
 #[allow(dead_code)]
-pub fn generate_topic_page_html(topic: &Value, _index: usize) -> String {
-    let title = topic
-        .get("title")
-        .and_then(|v| v.as_str())
-        .unwrap_or("Topic");
-    let content = topic.get("content").and_then(|v| v.as_str()).unwrap_or("");
-    let audio_id = topic.get("audioId").and_then(|v| v.as_str());
-    let caption_id = topic.get("captionId").and_then(|v| v.as_str());
+pub fn generate_topic_page_html(topic: &ContentPage, _index: usize) -> String {
+    let title = topic.title.as_deref().unwrap_or("Topic");
+    let content = topic.content.as_deref().unwrap_or("");
 
     let mut html = format!(
         r#"
@@ -198,7 +56,7 @@ pub fn generate_topic_page_html(topic: &Value, _index: usize) -> String {
     );
 
     // Add sections if present
-    if let Some(sections) = topic.get("sections").and_then(|v| v.as_array()) {
+    if let Some(sections) = topic.extra.get("sections").and_then(|v| v.as_array()) {
         for section in sections {
             if let Some(section_content) = section.get("content").and_then(|v| v.as_str()) {
                 html.push_str(&format!(
@@ -211,111 +69,51 @@ pub fn generate_topic_page_html(topic: &Value, _index: usize) -> String {
         }
     }
 
-    // Add media if present
-    if let Some(media_array) = topic.get("media").and_then(|v| v.as_array()) {
-        for media in media_array {
-            let media_type = media.get("type").and_then(|v| v.as_str()).unwrap_or("");
-            let media_id = media.get("id").and_then(|v| v.as_str()).unwrap_or("");
-            let media_title = media.get("title").and_then(|v| v.as_str()).unwrap_or("");
-
-            match media_type {
-                "image" | "svg" => {
-                    // Get URL from media object, fallback to media_id with extension detection
-                    let image_url = if let Some(url) = media.get("url").and_then(|v| v.as_str()) {
-                        println!("[Rust HTML Gen] Found URL field for {}: '{}' (topic page)", media_id, url);
-                        // If URL is provided, extract the filename with extension
-                        if url.starts_with("media/") {
-                            println!("[Rust HTML Gen] Using URL directly: {}", url);
-                            url.to_string()
-                        } else if url.starts_with("/media/") {
-                            let stripped = url.strip_prefix("/").unwrap_or(url).to_string();
-                            println!("[Rust HTML Gen] Stripped leading slash: {}", stripped);
-                            stripped
-                        } else {
-                            // External URL or relative path - use as filename
-                            let filename = format!("media/{}", Path::new(url).file_name().unwrap_or_default().to_string_lossy());
-                            println!("[Rust HTML Gen] Extracted filename from URL: {}", filename);
-                            filename
-                        }
-                    } else {
-                        println!("[Rust HTML Gen] No URL field found for {}, using fallback extension detection", media_id);
-                        // Fallback: try to detect extension from media_id or use default
-                        let extension = detect_image_extension(media_id, media_type);
-                        let fallback_url = format!("media/{}{}", media_id, extension);
-                        println!("[Rust HTML Gen] Fallback URL: {}", fallback_url);
-                        fallback_url
-                    };
-
-                    let img_class = if media_type == "svg" { "content-svg" } else { "content-image" };
-                    html.push_str(&format!(
-                        r#"    <img src="{}" alt="{}" class="{}" />
-"#,
-                        image_url, media_title, img_class
-                    ));
-                }
-                "video" => {
-                    html.push_str(&format!(
-                        r#"    <video src="media/{media_id}.mp4" controls class="content-video"></video>
-"#
-                    ));
-                }
-                _ => {}
-            }
-        }
-    }
+    html.push_str(&media_html(topic.media(), "topic page"));
 
     // Add knowledge check if present
-    if let Some(kc) = topic.get("knowledgeCheck").and_then(|v| v.as_object()) {
+    if let Some(kc) = &topic.knowledge_check {
         html.push_str(
             r#"    <div class="knowledge-check">
         <h3>Knowledge Check</h3>
 "#,
         );
 
-        let question = kc.get("question").and_then(|v| v.as_str()).unwrap_or("");
-        let kc_type = kc
-            .get("type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("multiple-choice");
-        let correct_answer = kc
-            .get("correctAnswer")
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
-        let explanation = kc.get("explanation").and_then(|v| v.as_str()).unwrap_or("");
+        let question = kc.all_questions().into_iter().next().unwrap_or_default();
+        let correct_option = question.correct_option();
+        let explanation = question.explanation.as_deref().unwrap_or("");
 
         html.push_str(&format!(
             r#"        <div class="question">
-            <p>{question}</p>
-"#
+            <p>{}</p>
+"#,
+            question.question
         ));
 
-        if kc_type == "multiple-choice" {
-            if let Some(options) = kc.get("options").and_then(|v| v.as_array()) {
-                html.push_str(r#"            <div class="options">"#);
-                for (i, option) in options.iter().enumerate() {
-                    let option_text = option.as_str().unwrap_or("");
-                    let is_correct = option_text == correct_answer;
+        if question.kind() == "multiple-choice" && question.options.is_some() {
+            html.push_str(r#"            <div class="options">"#);
+            for (i, option_text) in question.options().iter().enumerate() {
+                let is_correct = correct_option == Some(i);
 
-                    html.push_str(&format!(
-                        r#"
+                html.push_str(&format!(
+                    r#"
                 <label class="option">
                     <input type="radio" name="kc-question" value="{}" {} />
                     <span>{}</span>
                 </label>"#,
-                        i,
-                        if is_correct {
-                            format!(r#"data-correct="{i}""#)
-                        } else {
-                            String::new()
-                        },
-                        option_text
-                    ));
-                }
-                html.push_str(
-                    r#"
-            </div>"#,
-                );
+                    i,
+                    if is_correct {
+                        format!(r#"data-correct="{i}""#)
+                    } else {
+                        String::new()
+                    },
+                    option_text
+                ));
             }
+            html.push_str(
+                r#"
+            </div>"#,
+            );
         }
 
         html.push_str(&format!(
@@ -329,27 +127,14 @@ pub fn generate_topic_page_html(topic: &Value, _index: usize) -> String {
         ));
     }
 
-    // Add audio narration if present
-    if let (Some(audio), Some(caption)) = (audio_id, caption_id) {
-        html.push_str(&format!(
-            r#"    <audio id="narration-audio" controls class="narration-audio">
-        <source src="media/{audio}.mp3" type="audio/mpeg">
-        <track kind="captions" src="media/{caption}.vtt" srclang="en" label="English" default>
-    </audio>
-"#
-        ));
-    }
-
+    html.push_str(&narration_html(topic));
     html.push_str("</div>\n");
     html
 }
 
 #[allow(dead_code)]
-pub fn generate_assessment_page_html(assessment: &Value) -> String {
-    let pass_mark = assessment
-        .get("passMark")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(80);
+pub fn generate_assessment_page_html(assessment: &Assessment) -> String {
+    let pass_mark = assessment.pass_mark.unwrap_or(80);
 
     let mut html = format!(
         r#"
@@ -359,120 +144,95 @@ pub fn generate_assessment_page_html(assessment: &Value) -> String {
 "#
     );
 
-    // Add questions if present
-    if let Some(questions) = assessment.get("questions").and_then(|v| v.as_array()) {
-        for (q_index, question) in questions.iter().enumerate() {
-            let q_type = question
-                .get("type")
-                .and_then(|v| v.as_str())
-                .unwrap_or("multiple-choice");
-            let q_text = question
-                .get("question")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            let default_id = format!("q{}", q_index + 1);
-            let q_id = question
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or(&default_id);
-            let explanation = question
-                .get("explanation")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-
-            html.push_str(&format!(
-                r#"        <div class="question" data-question-id="{q_id}">
+    for (q_index, question) in assessment.questions.iter().enumerate() {
+        let q_text = &question.question;
+        let default_id = format!("q{}", q_index + 1);
+        let q_id = question.id.as_deref().unwrap_or(&default_id);
+        let explanation = question.explanation.as_deref().unwrap_or("");
+
+        html.push_str(&format!(
+            r#"        <div class="question" data-question-id="{q_id}">
             <p>{q_text}</p>
 "#
-            ));
-
-            match q_type {
-                "multiple-choice" => {
-                    if let Some(options) = question.get("options").and_then(|v| v.as_array()) {
-                        let correct_answer = question
-                            .get("correctAnswer")
-                            .and_then(|v| v.as_u64())
-                            .unwrap_or(0) as usize;
-
-                        html.push_str(r#"            <div class="options">"#);
-                        for (i, option) in options.iter().enumerate() {
-                            let option_text = option.as_str().unwrap_or("");
-                            let is_correct = i == correct_answer;
-
-                            html.push_str(&format!(
-                                r#"
+        ));
+
+        match question.kind() {
+            "multiple-choice" if question.options.is_some() => {
+                let correct_answer = question.correct_option().unwrap_or(0);
+
+                html.push_str(r#"            <div class="options">"#);
+                for (i, option_text) in question.options().iter().enumerate() {
+                    let is_correct = i == correct_answer;
+
+                    html.push_str(&format!(
+                        r#"
                 <label class="option">
                     <input type="radio" name="q-{}" value="{}" {} />
                     <span>{}</span>
                 </label>"#,
-                                q_id,
-                                i,
-                                if is_correct {
-                                    format!(r#"data-correct="{i}""#)
-                                } else {
-                                    String::new()
-                                },
-                                option_text
-                            ));
-                        }
-                        html.push_str(
-                            r#"
-            </div>"#,
-                        );
-                    }
+                        q_id,
+                        i,
+                        if is_correct {
+                            format!(r#"data-correct="{i}""#)
+                        } else {
+                            String::new()
+                        },
+                        option_text
+                    ));
                 }
-                "true-false" => {
-                    let correct_answer = question
-                        .get("correctAnswer")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false);
-
-                    html.push_str(r#"            <div class="options">"#);
-                    // True option
-                    html.push_str(&format!(
-                        r#"
+                html.push_str(
+                    r#"
+            </div>"#,
+                );
+            }
+            "true-false" => {
+                let correct_answer = question.correct_flag();
+
+                html.push_str(r#"            <div class="options">"#);
+                // True option
+                html.push_str(&format!(
+                    r#"
                 <label class="option">
                     <input type="radio" name="q-{}" value="0" {} />
                     <span>True</span>
                 </label>"#,
-                        q_id,
-                        if correct_answer {
-                            ""
-                        } else {
-                            r#"data-correct="0""#
-                        }
-                    ));
-                    // False option
-                    html.push_str(&format!(
-                        r#"
+                    q_id,
+                    if correct_answer {
+                        ""
+                    } else {
+                        r#"data-correct="0""#
+                    }
+                ));
+                // False option
+                html.push_str(&format!(
+                    r#"
                 <label class="option">
                     <input type="radio" name="q-{}" value="1" {} />
                     <span>False</span>
                 </label>"#,
-                        q_id,
-                        if !correct_answer {
-                            r#"data-correct="1""#
-                        } else {
-                            ""
-                        }
-                    ));
-                    html.push_str(
-                        r#"
+                    q_id,
+                    if !correct_answer {
+                        r#"data-correct="1""#
+                    } else {
+                        ""
+                    }
+                ));
+                html.push_str(
+                    r#"
             </div>"#,
-                    );
-                }
-                _ => {}
+                );
             }
+            _ => {}
+        }
 
-            html.push_str(&format!(
-                r#"
+        html.push_str(&format!(
+            r#"
             <div class="explanation" style="display:none;">
                 <p>{explanation}</p>
             </div>
         </div>
 "#
-            ));
-        }
+        ));
     }
 
     html.push_str(
@@ -483,8 +243,81 @@ pub fn generate_assessment_page_html(assessment: &Value) -> String {
     html
 }
 
+/// Image and video tags for a page's media
+fn media_html(media: &[MediaRef], page: &str) -> String {
+    let mut html = String::new();
+    for item in media {
+        let media_type = item.kind();
+        let media_id = item.id.as_str();
+        let media_title = item
+            .alt_text
+            .as_deref()
+            .or(item.title.as_deref())
+            .unwrap_or("");
+
+        match media_type {
+            "image" | "svg" => {
+                // Get URL from media object, fallback to media_id with extension detection
+                let image_url = if let Some(url) = item.url.as_deref() {
+                    println!("[Rust HTML Gen] Found URL field for {}: '{}' ({})", media_id, url, page);
+                    // If URL is provided, extract the filename with extension
+                    if url.starts_with("media/") {
+                        println!("[Rust HTML Gen] Using URL directly: {}", url);
+                        url.to_string()
+                    } else if url.starts_with("/media/") {
+                        let stripped = url.strip_prefix("/").unwrap_or(url).to_string();
+                        println!("[Rust HTML Gen] Stripped leading slash: {}", stripped);
+                        stripped
+                    } else {
+                        // External URL or relative path - use as filename
+                        let filename = format!("media/{}", Path::new(url).file_name().unwrap_or_default().to_string_lossy());
+                        println!("[Rust HTML Gen] Extracted filename from URL: {}", filename);
+                        filename
+                    }
+                } else {
+                    println!("[Rust HTML Gen] No URL field found for {}, using fallback extension detection", media_id);
+                    // Fallback: try to detect extension from media_id or use default
+                    let extension = detect_image_extension(media_id, media_type);
+                    let fallback_url = format!("media/{}{}", media_id, extension);
+                    println!("[Rust HTML Gen] Fallback URL: {}", fallback_url);
+                    fallback_url
+                };
+
+                let img_class = if media_type == "svg" { "content-svg" } else { "content-image" };
+                html.push_str(&format!(
+                    r#"    <img src="{}" alt="{}" class="{}" />
+"#,
+                    image_url, media_title, img_class
+                ));
+            }
+            "video" => {
+                html.push_str(&format!(
+                    r#"    <video src="media/{media_id}.mp4" controls class="content-video" aria-label="{media_title}"></video>
+"#
+                ));
+            }
+            _ => {}
+        }
+    }
+    html
+}
+
+/// The narration player, when a page has both audio and captions
+fn narration_html(page: &ContentPage) -> String {
+    match (&page.audio_id, &page.caption_id) {
+        (Some(audio), Some(caption)) => format!(
+            r#"    <audio id="narration-audio" controls class="narration-audio">
+        <source src="media/{audio}.mp3" type="audio/mpeg">
+        <track kind="captions" src="media/{caption}.vtt" srclang="en" label="English" default>
+    </audio>
+"#
+        ),
+        _ => String::new(),
+    }
+}
+
 #[allow(dead_code)]
-pub fn generate_complete_scorm_html(course_content: &Value, metadata: &CourseMetadata) -> String {
+pub fn generate_complete_scorm_html(course_content: &CourseContent, metadata: &CourseMetadata) -> String {
     let mut html = format!(
         r#"<!DOCTYPE html>
 <html lang="en" style="height: 100%; margin: 0; padding: 0;">
@@ -538,26 +371,17 @@ pub fn generate_complete_scorm_html(course_content: &Value, metadata: &CourseMet
     );
 
     // Add topic navigation buttons
-    if let Some(topics) = course_content.get("topics").and_then(|v| v.as_array()) {
-        for (i, topic) in topics.iter().enumerate() {
-            let default_title = format!("Topic {}", i + 1);
-            let topic_title = topic
-                .get("title")
-                .and_then(|v| v.as_str())
-                .unwrap_or(&default_title);
-            html.push_str(&format!(
-                r#"        <button id="nav-topic-{i}" class="nav-btn" onclick="showPage('topic-{i}')">{topic_title}</button>
+    for (i, topic) in course_content.topics.iter().enumerate() {
+        let default_title = format!("Topic {}", i + 1);
+        let topic_title = topic.title.as_deref().unwrap_or(&default_title);
+        html.push_str(&format!(
+            r#"        <button id="nav-topic-{i}" class="nav-btn" onclick="showPage('topic-{i}')">{topic_title}</button>
 "#
-            ));
-        }
+        ));
     }
 
     // Add assessment button if present
-    if course_content
-        .get("assessment")
-        .and_then(|v| v.as_object())
-        .is_some()
-    {
+    if course_content.assessment.is_some() {
         html.push_str(r#"        <button id="nav-assessment" class="nav-btn" onclick="showPage('assessment')">Assessment</button>
 "#);
     }
@@ -570,15 +394,12 @@ pub fn generate_complete_scorm_html(course_content: &Value, metadata: &CourseMet
     );
 
     // Generate welcome page
-    if let Some(_welcome) = course_content
-        .get("welcomePage")
-        .and_then(|v| v.as_object())
-    {
+    if let Some(welcome) = &course_content.welcome_page {
         html.push_str(
             r#"        <div id="page-welcome" class="page-content active">
 "#,
         );
-        let welcome_html = generate_welcome_page_html(course_content.get("welcomePage").unwrap());
+        let welcome_html = generate_welcome_page_html(welcome);
         // Remove the outer div wrapper since we're adding our own
         let welcome_content = welcome_html
             .trim_start_matches("<div class=\"page-content\">\n")
@@ -591,16 +412,12 @@ pub fn generate_complete_scorm_html(course_content: &Value, metadata: &CourseMet
     }
 
     // Generate objectives page
-    if let Some(_objectives) = course_content
-        .get("learningObjectivesPage")
-        .and_then(|v| v.as_object())
-    {
+    if let Some(objectives) = &course_content.learning_objectives_page {
         html.push_str(
             r#"        <div id="page-objectives" class="page-content">
 "#,
         );
-        let objectives_html =
-            generate_objectives_page_html(course_content.get("learningObjectivesPage").unwrap());
+        let objectives_html = generate_objectives_page_html(objectives);
         // Remove the outer div wrapper
         let objectives_content = objectives_html
             .trim_start_matches("<div class=\"page-content\">\n")
@@ -613,33 +430,30 @@ pub fn generate_complete_scorm_html(course_content: &Value, metadata: &CourseMet
     }
 
     // Generate topic pages
-    if let Some(topics) = course_content.get("topics").and_then(|v| v.as_array()) {
-        for (i, topic) in topics.iter().enumerate() {
-            html.push_str(&format!(
-                r#"        <div id="page-topic-{i}" class="page-content">
+    for (i, topic) in course_content.topics.iter().enumerate() {
+        html.push_str(&format!(
+            r#"        <div id="page-topic-{i}" class="page-content">
 "#
-            ));
-            let topic_html = generate_topic_page_html(topic, i);
-            // Remove the outer div wrapper
-            let topic_content = topic_html
-                .trim_start_matches("<div class=\"page-content\">\n")
-                .trim_end_matches("</div>\n");
-            html.push_str(&format!("            {topic_content}"));
-            html.push_str(
-                r#"        </div>
+        ));
+        let topic_html = generate_topic_page_html(topic, i);
+        // Remove the outer div wrapper
+        let topic_content = topic_html
+            .trim_start_matches("<div class=\"page-content\">\n")
+            .trim_end_matches("</div>\n");
+        html.push_str(&format!("            {topic_content}"));
+        html.push_str(
+            r#"        </div>
 "#,
-            );
-        }
+        );
     }
 
     // Generate assessment page
-    if let Some(_assessment) = course_content.get("assessment").and_then(|v| v.as_object()) {
+    if let Some(assessment) = &course_content.assessment {
         html.push_str(
             r#"        <div id="page-assessment" class="page-content">
 "#,
         );
-        let assessment_html =
-            generate_assessment_page_html(course_content.get("assessment").unwrap());
+        let assessment_html = generate_assessment_page_html(assessment);
         // Remove the outer div wrapper
         let assessment_content = assessment_html
             .trim_start_matches("<div class=\"page-content\" data-pass-mark=\"80\">\n")
@@ -682,11 +496,20 @@ pub fn generate_complete_scorm_html(course_content: &Value, metadata: &CourseMet
             // Notify parent window about desired size
             if (window.parent && window.parent !== window) {
                 try {
-                    // Try multiple methods to communicate with parent
-                    window.parent.postMessage({
-                        type: 'setHeight',
-                        height: Math.max(800, window.innerHeight, document.body.scrollHeight)
-                    }, '*');
+                    // Try multiple methods to communicate with parent.
+                    // Only post to the LMS origin (from the referrer) rather than '*'
+                    let parentOrigin = null;
+                    try {
+                        parentOrigin = document.referrer ? new URL(document.referrer).origin : null;
+                    } catch (e) {
+                        parentOrigin = null;
+                    }
+                    if (parentOrigin) {
+                        window.parent.postMessage({
+                            type: 'setHeight',
+                            height: Math.max(800, window.innerHeight, document.body.scrollHeight)
+                        }, parentOrigin);
+                    }
                     
                     // Try SCORM-specific resize
                     if (window.parent.resizeIframe) {
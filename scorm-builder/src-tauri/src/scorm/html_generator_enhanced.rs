@@ -1,10 +1,125 @@
 use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext, Renderable};
-use serde_json::json;
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::path::Path;
 
+use super::analytics::{csp_with_connect_sources, csp_with_sources};
+use super::animated_gifs::GifVideos;
+use super::audio_sources::AudioSources;
 use super::generator_enhanced::{
-    Assessment, GenerateScormRequest, ObjectivesPage, Topic, WelcomePage,
+    Assessment, AssessmentTimer, FeatureFlags, FeedbackMedia, FeedbackMediaType, GenerateScormRequest,
+    ObjectivesPage, Question, Topic, WelcomePage, WelcomeStartOptions,
 };
+use super::code_highlight::course_uses_code;
+use super::credits::{course_credits, has_credits};
+use super::package_version::course_version;
+use super::math::course_uses_math;
+use super::navigation_menu::{menu_pages, DEFAULT_ASSESSMENT_LABEL, DEFAULT_OBJECTIVES_LABEL};
+use super::render_diagnostics::PageRenderError;
+use super::template_dev::{page_override_path, partial_override_path, TemplateSourceError};
+use super::transcripts::{course_transcripts, topic_transcript, CaptionTexts};
+
+/// CSP used when `enable_csp` is set without `strict_csp`. Keeps the historical
+/// allowances for LMS players that inject their own inline scripts.
+pub const COMPATIBLE_CSP: &str = "default-src 'self'; script-src 'self' 'unsafe-inline' 'unsafe-eval' *; style-src 'self' 'unsafe-inline' *; img-src 'self' data: https: blob: *; media-src 'self' blob: https: *; frame-src 'self' https: *; connect-src 'self' *; font-src 'self' data: *; object-src 'none'; base-uri 'self'; form-action 'self'";
+
+/// CSP for LMSs that enforce strict policies. The generated package has no inline
+/// scripts or event-handler attributes, so scripts can be limited to 'self'.
+pub const STRICT_CSP: &str = "default-src 'self'; script-src 'self'; style-src 'self' 'unsafe-inline'; img-src 'self' data: blob: https:; media-src 'self' blob: https:; frame-src https://www.youtube.com https://www.youtube-nocookie.com https://player.vimeo.com; connect-src 'self'; font-src 'self' data:; object-src 'none'; base-uri 'self'; form-action 'self'";
+
+/// Shared page chrome registered as Handlebars partials, by name. Pages include
+/// them with `{{> name}}`, so an override of one partial changes every page.
+pub const TEMPLATE_PARTIALS: [(&str, &str); 6] = [
+    ("analytics", include_str!("templates/partials/analytics.hbs")),
+    ("audio_player", include_str!("templates/partials/audio_player.hbs")),
+    ("feedback", include_str!("templates/partials/feedback.hbs")),
+    ("feedback_media", include_str!("templates/partials/feedback_media.hbs")),
+    ("media_block", include_str!("templates/partials/media_block.hbs")),
+    ("nav_bar", include_str!("templates/partials/nav_bar.hbs")),
+];
+
+/// Page templates by name. A template override folder holds them as `{name}.html.hbs`.
+pub const PAGE_TEMPLATES: [(&str, &str); 8] = [
+    ("index", include_str!("templates/index.html.hbs")),
+    ("topic", include_str!("templates/topic.html.hbs")),
+    ("welcome", include_str!("templates/welcome.html.hbs")),
+    ("objectives", include_str!("templates/objectives.html.hbs")),
+    ("assessment", include_str!("templates/assessment.html.hbs")),
+    ("course_map", include_str!("templates/course_map.html.hbs")),
+    ("credits", include_str!("templates/credits.html.hbs")),
+    ("transcript", include_str!("templates/transcript.html.hbs")),
+];
+
+/// Course-wide settings that change how each content page renders
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PageOptions<'a> {
+    pub require_audio_completion: bool,
+    /// Narration seeking and skipping are disabled
+    pub audio_seek_locked: bool,
+    /// The narration player has a playback speed selector
+    pub audio_speed_control: bool,
+    /// Page images open in the lightbox when clicked
+    pub image_lightbox: bool,
+    /// Narration players with captions get a button to show them
+    pub captions_toggle: bool,
+    /// Other encodings of the packaged narration, offered as extra `<source>`s
+    pub audio_sources: Option<&'a AudioSources>,
+    /// Page GIFs packaged as video, played in place of the image
+    pub gif_videos: Option<&'a GifVideos>,
+    /// Narrated topics show a transcript under their text
+    pub transcripts: bool,
+    /// Text of the packaged caption files, for transcripts of topics without narration text
+    pub captions: Option<&'a CaptionTexts>,
+    /// Heading of the objectives page, when the navigation menu renames it
+    pub objectives_label: Option<&'a str>,
+}
+
+impl<'a> PageOptions<'a> {
+    pub fn from_request(request: &'a GenerateScormRequest) -> Self {
+        let require_audio_completion = request.require_audio_completion.unwrap_or(false);
+        let audio = request.audio_policy.clone().unwrap_or_default();
+        Self {
+            require_audio_completion,
+            audio_seek_locked: audio.seek_locked(require_audio_completion),
+            audio_speed_control: audio.speed_control(),
+            image_lightbox: request.image_lightbox.unwrap_or(true),
+            captions_toggle: FeatureFlags::from_request(request).captions_toggle,
+            audio_sources: None,
+            gif_videos: None,
+            transcripts: request.transcripts.unwrap_or(false),
+            captions: None,
+            objectives_label: request
+                .navigation_menu
+                .as_ref()
+                .and_then(|menu| menu.objectives_label.as_deref()),
+        }
+    }
+
+    pub fn with_audio_sources(mut self, audio_sources: &'a AudioSources) -> Self {
+        self.audio_sources = Some(audio_sources);
+        self
+    }
+
+    pub fn with_gif_videos(mut self, gif_videos: &'a GifVideos) -> Self {
+        self.gif_videos = Some(gif_videos);
+        self
+    }
+
+    pub fn with_captions(mut self, captions: &'a CaptionTexts) -> Self {
+        self.captions = Some(captions);
+        self
+    }
+
+    fn gif_video_for(&self, url: &str) -> Option<Value> {
+        self.gif_videos.and_then(|videos| videos.template_video(url))
+    }
+
+    fn audio_sources_for(&self, audio_file: Option<&String>) -> Option<Vec<Value>> {
+        self.audio_sources
+            .zip(audio_file)
+            .and_then(|(sources, path)| sources.template_sources(path))
+    }
+}
 
 pub struct HtmlGenerator<'a> {
     handlebars: Handlebars<'a>,
@@ -13,7 +128,7 @@ pub struct HtmlGenerator<'a> {
 
 impl<'a> HtmlGenerator<'a> {
     // Helper to ensure media paths are properly formatted
-    fn ensure_media_path(path: &str) -> String {
+    pub(super) fn ensure_media_path(path: &str) -> String {
         // Don't modify external URLs
         if path.starts_with("http://") || path.starts_with("https://") || path.starts_with("//") {
             path.to_string()
@@ -24,8 +139,24 @@ impl<'a> HtmlGenerator<'a> {
         }
     }
 
+    /// Template data for the media attached to a question's feedback, by outcome
+    fn feedback_media_data(question: &Question, extension_map: Option<&HashMap<String, String>>) -> serde_json::Value {
+        let media = |media: Option<&FeedbackMedia>| {
+            media.map(|media| json!({
+                "is_image": media.media_type == FeedbackMediaType::Image,
+                "is_audio": media.media_type == FeedbackMediaType::Audio,
+                "url": Self::get_correct_media_url(&media.url, extension_map),
+                "alt_text": media.alt_text,
+            }))
+        };
+        json!({
+            "correct": media(question.correct_feedback_media.as_ref()),
+            "incorrect": media(question.incorrect_feedback_media.as_ref()),
+        })
+    }
+
     // Helper to get correct media URL using extension map
-    fn get_correct_media_url(path: &str, extension_map: Option<&HashMap<String, String>>) -> Option<String> {
+    pub(super) fn get_correct_media_url(path: &str, extension_map: Option<&HashMap<String, String>>) -> Option<String> {
         // Don't modify external URLs
         if path.starts_with("http://") || path.starts_with("https://") || path.starts_with("//") {
             return Some(path.to_string());
@@ -63,37 +194,19 @@ impl<'a> HtmlGenerator<'a> {
         handlebars.register_helper("extract_youtube_id", Box::new(extract_youtube_id_helper));
         handlebars.register_helper("add", Box::new(add_helper));
 
-        // Load templates
-        let index_template = include_str!("templates/index.html.hbs");
-        let topic_template = include_str!("templates/topic.html.hbs");
-        let welcome_template = include_str!("templates/welcome.html.hbs");
-        let objectives_template = include_str!("templates/objectives.html.hbs");
-        let assessment_template = include_str!("templates/assessment.html.hbs");
-        let scorm_api_template = include_str!("templates/scorm-api.js.hbs");
-
-        handlebars
-            .register_template_string("index", index_template)
-            .map_err(|e| format!("Failed to register index template: {e}"))?;
-
-        handlebars
-            .register_template_string("topic", topic_template)
-            .map_err(|e| format!("Failed to register topic template: {e}"))?;
-
-        handlebars
-            .register_template_string("welcome", welcome_template)
-            .map_err(|e| format!("Failed to register welcome template: {e}"))?;
-
-        handlebars
-            .register_template_string("objectives", objectives_template)
-            .map_err(|e| format!("Failed to register objectives template: {e}"))?;
-
-        handlebars
-            .register_template_string("assessment", assessment_template)
-            .map_err(|e| format!("Failed to register assessment template: {e}"))?;
+        // Register partials before the templates that include them
+        for (name, source) in TEMPLATE_PARTIALS {
+            handlebars
+                .register_partial(name, source)
+                .map_err(|e| format!("Failed to register {name} partial: {e}"))?;
+        }
 
-        handlebars
-            .register_template_string("scorm-api", scorm_api_template)
-            .map_err(|e| format!("Failed to register scorm-api template: {e}"))?;
+        // Load templates
+        for (name, source) in PAGE_TEMPLATES {
+            handlebars
+                .register_template_string(name, source)
+                .map_err(|e| format!("Failed to register {name} template: {e}"))?;
+        }
 
         Ok(Self {
             handlebars,
@@ -101,17 +214,71 @@ impl<'a> HtmlGenerator<'a> {
         })
     }
 
+    /// Templates and partials from `dir` where it has them, the built-in ones
+    /// elsewhere. Overrides that fail to load keep the built-in template and are
+    /// returned alongside the generator.
+    pub fn with_template_dir(dir: &Path) -> Result<(Self, Vec<TemplateSourceError>), String> {
+        let mut generator = Self::new()?;
+        let partials = TEMPLATE_PARTIALS.iter().map(|(name, _)| (*name, partial_override_path(dir, name)));
+        let pages = PAGE_TEMPLATES.iter().map(|(name, _)| (*name, page_override_path(dir, name)));
+
+        let mut errors = Vec::new();
+        for (name, path) in partials.chain(pages) {
+            if !path.is_file() {
+                continue;
+            }
+            // A template that fails to compile leaves the registered one in place
+            if let Err(error) = generator.handlebars.register_template_file(name, &path) {
+                errors.push(TemplateSourceError::new(name, &path, &error));
+            }
+        }
+        Ok((generator, errors))
+    }
+
     pub fn generate_index_html(&self, request: &GenerateScormRequest) -> Result<String, String> {
+        if let Some(analytics) = &request.analytics {
+            analytics.validate()?;
+        }
+        if let Some(feedback) = &request.feedback {
+            feedback.validate()?;
+        }
+        if let Some(features) = &request.features {
+            features.validate(request)?;
+        }
+        let features = FeatureFlags::from_request(request);
+        let csp_policy = if request.strict_csp.unwrap_or(false) { STRICT_CSP } else { COMPATIBLE_CSP };
+        let analytics_sources = request.analytics.as_ref().map(|a| a.csp_sources()).unwrap_or_default();
+        let feedback_sources = request.feedback.as_ref().map(|f| f.csp_sources()).unwrap_or_default();
+        let feedback = match &request.feedback {
+            Some(feedback) => Some(feedback.template_data(course_version(request.course_version.as_deref())?)),
+            None => None,
+        };
+
         let data = json!({
             "course_title": request.course_title,
+            "language": request.language.as_deref().unwrap_or("en"),
+            "debug_build": request.debug_build.unwrap_or(false),
             "has_objectives": request.learning_objectives_page.is_some(),
+            "has_course_map": request.course_map.unwrap_or(false),
+            "has_credits": has_credits(request),
             "enable_csp": request.enable_csp.unwrap_or(false), // Default to false for LMS compatibility
-            "show_progress": request.show_progress.unwrap_or(true),  // Add show_progress setting
-            "show_outline": request.show_outline.unwrap_or(true),    // Add show_outline setting
+            "csp_policy": csp_with_connect_sources(&csp_with_sources(csp_policy, &analytics_sources), &feedback_sources),
+            "show_progress": features.progress_bar,
+            "show_outline": features.navigation_menu,
+            "printable": features.print_button,
+            "page_numbers": features.page_numbers,
+            "learner_notes": request.learner_notes.unwrap_or(false),
+            "course_search": request.course_search.unwrap_or(false),
+            "responsive_layout": request.responsive_layout.unwrap_or(true),
+            "analytics": request.analytics.as_ref().map(|a| a.template_data()),
+            "feedback": feedback,
+            "has_math": course_uses_math(request),
+            "has_code": course_uses_code(request),
             "topics": request.topics.iter().map(|t| json!({
                 "id": t.id,
                 "title": t.title
-            })).collect::<Vec<_>>()
+            })).collect::<Vec<_>>(),
+            "menu_pages": menu_pages(request)
         });
 
         self.handlebars
@@ -119,13 +286,15 @@ impl<'a> HtmlGenerator<'a> {
             .map_err(|e| format!("Failed to render index template: {e}"))
     }
 
-    pub fn generate_welcome_page(&self, welcome: &WelcomePage, require_audio_completion: bool, _extension_map: Option<&HashMap<String, String>>) -> Result<String, String> {
+    pub fn generate_welcome_page(&self, welcome: &WelcomePage, start: Option<&WelcomeStartOptions>, options: &PageOptions, _extension_map: Option<&HashMap<String, String>>) -> Result<String, Box<PageRenderError>> {
         eprintln!("[HTML Generator] Generating welcome page");
         eprintln!(
             "[HTML Generator] Welcome has audio_file: {}",
             welcome.audio_file.is_some()
         );
         eprintln!("[HTML Generator] Welcome has media: {:?}", welcome.media);
+        let intro_video_id = start.and_then(|start| start.intro_video_id.as_deref());
+        let intro_video_percent = start.map(|start| start.intro_video_min_percent());
 
         // Process media items to ensure URLs are prefixed with media/
         let processed_media = welcome.media.as_ref().map(|media_items| {
@@ -157,8 +326,11 @@ impl<'a> HtmlGenerator<'a> {
                         "type": item.media_type,
                         "url": url,
                         "title": item.title,
+                        "alt_text": item.alt_text,
                         "embed_url": item.embed_url,
                         "is_youtube": is_youtube,
+                        "gif_video": options.gif_video_for(&url),
+                        "intro_video_percent": intro_video_percent.filter(|_| intro_video_id == Some(item.id.as_str())),
                         "clip_start": item.clip_start,
                         "clip_end": item.clip_end
                     })
@@ -166,25 +338,31 @@ impl<'a> HtmlGenerator<'a> {
                 .collect::<Vec<_>>()
         });
 
+        let welcome_audio = welcome.audio_file.as_ref().map(|f| Self::ensure_media_path(f));
         let data = json!({
             "title": welcome.title,
             "content": welcome.content.replace('\n', "<br>"),
             "next_page": if self.has_objectives { "objectives" } else { "topic-1" },
             "start_button_text": welcome.start_button_text,
-            "audio_file": welcome.audio_file.as_ref().map(|f| Self::ensure_media_path(f)),
+            "audio_file": welcome_audio,
+            "audio_sources": options.audio_sources_for(welcome_audio.as_ref()),
             "caption_file": welcome.caption_file.as_ref().map(|f| Self::ensure_media_path(f)),
             "image_url": welcome.image_url.as_ref().map(|f| Self::ensure_media_path(f)),
             "media": processed_media,
             "id": "welcome",  // Add ID for audio player
-            "require_audio_completion": require_audio_completion
+            "require_audio_completion": options.require_audio_completion,
+            "audio_seek_locked": options.audio_seek_locked,
+            "audio_speed_control": options.audio_speed_control,
+            "image_lightbox": options.image_lightbox,
+            "captions_toggle": options.captions_toggle,
+            "resume_prompt": start.is_some_and(WelcomeStartOptions::resume_prompt),
+            "intro_video": intro_video_id.is_some()
         });
 
-        self.handlebars
-            .render("welcome", &data)
-            .map_err(|e| format!("Failed to render welcome template: {e}"))
+        self.render_page("welcome", "welcome", &welcome.title, &data)
     }
 
-    pub fn generate_objectives_page(&self, objectives: &ObjectivesPage, require_audio_completion: bool, _extension_map: Option<&HashMap<String, String>>) -> Result<String, String> {
+    pub fn generate_objectives_page(&self, objectives: &ObjectivesPage, options: &PageOptions, _extension_map: Option<&HashMap<String, String>>) -> Result<String, Box<PageRenderError>> {
         eprintln!("[HTML Generator] Generating objectives page");
         eprintln!(
             "[HTML Generator] Objectives has audio_file: {}",
@@ -221,8 +399,10 @@ impl<'a> HtmlGenerator<'a> {
                         "type": item.media_type,
                         "url": url,
                         "title": item.title,
+                        "alt_text": item.alt_text,
                         "embed_url": item.embed_url,
                         "is_youtube": is_youtube,
+                        "gif_video": options.gif_video_for(&url),
                         "clip_start": item.clip_start,
                         "clip_end": item.clip_end
                     })
@@ -230,21 +410,27 @@ impl<'a> HtmlGenerator<'a> {
                 .collect::<Vec<_>>()
         });
 
+        let objectives_audio = objectives.audio_file.as_ref().map(|f| Self::ensure_media_path(f));
         let data = json!({
             "objectives": objectives.objectives,
-            "audio_file": objectives.audio_file.as_ref().map(|f| Self::ensure_media_path(f)),
+            "audio_file": objectives_audio,
+            "audio_sources": options.audio_sources_for(objectives_audio.as_ref()),
             "caption_file": objectives.caption_file.as_ref().map(|f| Self::ensure_media_path(f)),
             "media": processed_media,
             "id": "objectives",  // Add ID for audio player
-            "require_audio_completion": require_audio_completion
+            "heading": options.objectives_label.unwrap_or(DEFAULT_OBJECTIVES_LABEL),
+            "require_audio_completion": options.require_audio_completion,
+            "audio_seek_locked": options.audio_seek_locked,
+            "audio_speed_control": options.audio_speed_control,
+            "image_lightbox": options.image_lightbox,
+            "captions_toggle": options.captions_toggle
         });
 
-        self.handlebars
-            .render("objectives", &data)
-            .map_err(|e| format!("Failed to render objectives template: {e}"))
+        let title = options.objectives_label.unwrap_or(DEFAULT_OBJECTIVES_LABEL);
+        self.render_page("objectives", "objectives", title, &data)
     }
 
-    pub fn generate_topic_page(&self, topic: &Topic, require_audio_completion: bool, extension_map: Option<&HashMap<String, String>>) -> Result<String, String> {
+    pub fn generate_topic_page(&self, topic: &Topic, options: &PageOptions, extension_map: Option<&HashMap<String, String>>) -> Result<String, Box<PageRenderError>> {
         // Use eprintln! for debugging - it goes to stderr which might be visible
         eprintln!("[HTML Generator] Processing topic: {}", topic.id);
         eprintln!(
@@ -289,6 +475,7 @@ impl<'a> HtmlGenerator<'a> {
                             "index": index,
                             "correct_answer": q.correct_answer,
                             "explanation": q.explanation.as_deref().unwrap_or(""),
+                            "feedback_media": Self::feedback_media_data(q, extension_map),
                         });
 
                         // Add type-specific fields
@@ -355,6 +542,7 @@ impl<'a> HtmlGenerator<'a> {
             "has_knowledge_check": !kc_questions.is_empty(),
             "knowledge_check_questions": kc_questions,
             "audio_file": audio_file_path,
+            "audio_sources": options.audio_sources_for(audio_file_path.as_ref()),
             "caption_file": topic.caption_file.as_ref().map(|f| Self::ensure_media_path(f)),
             // Use extension map to get correct image URL
             "image_url": topic.image_url.as_ref()
@@ -380,14 +568,24 @@ impl<'a> HtmlGenerator<'a> {
                         "type": item.media_type,
                         "url": url,
                         "title": item.title,
+                        "alt_text": item.alt_text,
                         "embed_url": item.embed_url,
                         "is_youtube": is_youtube,
+                        "gif_video": options.gif_video_for(&url),
                         "clip_start": item.clip_start,
                         "clip_end": item.clip_end
                     })
                 }).collect::<Vec<_>>()
             }),
-            "require_audio_completion": require_audio_completion
+            "require_audio_completion": options.require_audio_completion,
+            "audio_seek_locked": options.audio_seek_locked,
+            "audio_speed_control": options.audio_speed_control,
+            "image_lightbox": options.image_lightbox,
+            "captions_toggle": options.captions_toggle,
+            "transcript": options
+                .transcripts
+                .then(|| topic_transcript(topic, options.captions))
+                .flatten()
         });
 
         eprintln!(
@@ -408,10 +606,7 @@ impl<'a> HtmlGenerator<'a> {
         );
 
         // Render template and debug the result
-        let rendered_html = self
-            .handlebars
-            .render("topic", &data)
-            .map_err(|e| format!("Failed to render topic template: {e}"))?;
+        let rendered_html = self.render_page("topic", &topic.id, &topic.title, &data)?;
 
         // Check if knowledge check was rendered
         if !kc_questions.is_empty() {
@@ -433,8 +628,29 @@ impl<'a> HtmlGenerator<'a> {
         Ok(rendered_html)
     }
 
-    pub fn generate_assessment_page(&self, assessment: &Assessment) -> Result<String, String> {
+    pub fn generate_assessment_page(
+        &self,
+        assessment: &Assessment,
+        timer: Option<&AssessmentTimer>,
+        label: Option<&str>,
+        extension_map: Option<&HashMap<String, String>>,
+    ) -> Result<String, Box<PageRenderError>> {
+        let timer = timer.map(|timer| {
+            let overall = timer.overall_seconds();
+            let question = timer.question_seconds();
+            json!({
+                "overall_seconds": overall,
+                "overall_warning_seconds": timer.warning_for(overall),
+                "overall_label": countdown_label(overall),
+                "question_seconds": question,
+                "question_warning_seconds": timer.warning_for(question),
+                "question_label": countdown_label(question),
+                "auto_submit": timer.auto_submit.unwrap_or(true),
+            })
+        });
         let data = json!({
+            "timer": timer,
+            "heading": label,
             "assessment": {
                 "questions": assessment.questions.iter().enumerate().map(|(idx, q)| json!({
                     "index": idx,
@@ -446,24 +662,86 @@ impl<'a> HtmlGenerator<'a> {
                         .or(q.explanation.as_deref())
                         .unwrap_or("Correct!"),
                     "incorrect_feedback": q.incorrect_feedback.as_deref()
-                        .unwrap_or("Not quite. Try again!")
+                        .unwrap_or("Not quite. Try again!"),
+                    "feedback_media": Self::feedback_media_data(q, extension_map),
                 })).collect::<Vec<_>>()
             }
         });
 
-        self.handlebars
-            .render("assessment", &data)
-            .map_err(|e| format!("Failed to render assessment template: {e}"))
+        self.render_page("assessment", "assessment", label.unwrap_or(DEFAULT_ASSESSMENT_LABEL), &data)
+    }
+
+    /// Overview of every page of the course, grouped into sections. Completion
+    /// ticks are filled in by navigation.js from the learner's progress.
+    pub fn generate_course_map_page(&self, request: &GenerateScormRequest) -> Result<String, Box<PageRenderError>> {
+        let menu = request.navigation_menu.clone().unwrap_or_default();
+        let page = |id: &str, title: &str, has_knowledge_check: bool| json!({
+            "id": id,
+            "title": title,
+            "has_knowledge_check": has_knowledge_check
+        });
+
+        let mut getting_started = Vec::new();
+        if let Some(welcome) = &request.welcome_page {
+            getting_started.push(page("welcome", &welcome.title, false));
+        }
+        if request.learning_objectives_page.is_some() {
+            getting_started.push(page("objectives", menu.objectives_label(), false));
+        }
+        let topics: Vec<_> = request.topics.iter().map(|topic| {
+            let has_knowledge_check = topic.knowledge_check.as_ref().is_some_and(|kc| kc.enabled && !kc.questions.is_empty());
+            page(&topic.id, &topic.title, has_knowledge_check)
+        }).collect();
+        let assessment: Vec<_> = request.assessment.iter().map(|_| page("assessment", menu.assessment_label(), false)).collect();
+
+        let page_count = getting_started.len() + topics.len() + assessment.len();
+        let sections: Vec<_> = [("Getting Started", getting_started), ("Topics", topics), ("Assessment", assessment)]
+            .into_iter()
+            .filter(|(_, pages)| !pages.is_empty())
+            .map(|(title, pages)| json!({ "title": title, "pages": pages }))
+            .collect();
+
+        let data = json!({
+            "sections": sections,
+            "page_count": page_count
+        });
+
+        self.render_page("course_map", "course-map", "Course Map", &data)
     }
 
-    pub fn generate_scorm_api_js(&self, _request: &GenerateScormRequest) -> Result<String, String> {
-        // Generate the Universal SCORM API wrapper
-        // This is a static template with no dynamic content currently
-        let data = json!({});
-        
+    /// Authors and licenses of the course's attributed media
+    pub fn generate_credits_page(&self, request: &GenerateScormRequest) -> Result<String, Box<PageRenderError>> {
+        let data = json!({
+            "credits": course_credits(request)
+        });
+
+        self.render_page("credits", "credits", "Credits", &data)
+    }
+
+    /// Transcripts of every narrated topic, in course order
+    pub fn generate_transcript_page(
+        &self,
+        request: &GenerateScormRequest,
+        captions: &CaptionTexts,
+    ) -> Result<String, Box<PageRenderError>> {
+        let data = json!({
+            "transcripts": course_transcripts(request, Some(captions))
+        });
+
+        self.render_page("transcript", "transcript", "Transcript", &data)
+    }
+
+    /// Render a page template, keeping which page failed and the data it was given
+    fn render_page(
+        &self,
+        template: &str,
+        page_id: &str,
+        page_title: &str,
+        data: &Value,
+    ) -> Result<String, Box<PageRenderError>> {
         self.handlebars
-            .render("scorm-api", &data)
-            .map_err(|e| format!("Failed to render scorm-api template: {e}"))
+            .render(template, data)
+            .map_err(|e| Box::new(PageRenderError::new(page_id, page_title, template, &e, data)))
     }
 
     #[allow(dead_code)]
@@ -473,6 +751,11 @@ impl<'a> HtmlGenerator<'a> {
     }
 }
 
+/// Initial text of a countdown display, as the runtime formats it (M:SS)
+fn countdown_label(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
 // Handlebars helper for equality comparison (block helper)
 fn eq_helper<'reg, 'rc>(
     h: &Helper<'reg, 'rc>,
@@ -18,7 +18,7 @@ fn test_generate_welcome_page_html() {
         }
     });
 
-    let html = generate_welcome_page_html(&course_content["welcomePage"]);
+    let html = generate_welcome_page_html(&serde_json::from_value(course_content["welcomePage"].clone()).unwrap());
 
     // Verify the HTML contains expected elements
     assert!(html.contains("<h1>Welcome to the Course</h1>"));
@@ -48,7 +48,7 @@ fn test_generate_objectives_page_html() {
         }
     });
 
-    let html = generate_objectives_page_html(&course_content["learningObjectivesPage"]);
+    let html = generate_objectives_page_html(&serde_json::from_value(course_content["learningObjectivesPage"].clone()).unwrap());
 
     // Verify the HTML contains expected elements
     assert!(html.contains("<h2>Learning Objectives</h2>"));
@@ -86,7 +86,7 @@ fn test_generate_topic_page_html() {
         }]
     });
 
-    let html = generate_topic_page_html(&topic, 0);
+    let html = generate_topic_page_html(&serde_json::from_value(topic).unwrap(), 0);
 
     println!("Generated HTML:\n{}", html);
 
@@ -132,7 +132,7 @@ fn test_generate_assessment_page_html() {
         "passMark": 80
     });
 
-    let html = generate_assessment_page_html(&assessment);
+    let html = generate_assessment_page_html(&serde_json::from_value(assessment).unwrap());
 
     // Verify the HTML contains expected elements
     assert!(html.contains("<h1>Assessment</h1>"));
@@ -185,7 +185,7 @@ fn test_generate_complete_scorm_html() {
         scorm_version: None,
     };
 
-    let html = generate_complete_scorm_html(&course_content, &metadata);
+    let html = generate_complete_scorm_html(&serde_json::from_value(course_content).unwrap(), &metadata);
 
     // Verify the HTML structure
     assert!(html.contains("<!DOCTYPE html>"));
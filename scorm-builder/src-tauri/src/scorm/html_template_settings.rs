@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod html_template_settings_tests {
-    use crate::scorm::html_generator_enhanced::HtmlGenerator;
+    use crate::scorm::html_generator_enhanced::{HtmlGenerator, PageOptions};
     use crate::scorm::generator_enhanced::*;
 
     #[test]
@@ -114,4 +114,553 @@ mod html_template_settings_tests {
             println!("❌ Template missing show_progress conditional - this is the bug!");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_index_html_csp_policy_modes() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+
+        let mut request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            enable_csp: Some(true),
+            ..Default::default()
+        };
+
+        let html = generator.generate_index_html(&request)
+            .expect("Failed to generate index HTML");
+        assert!(html.contains("Content-Security-Policy"));
+        assert!(html.contains("'unsafe-inline' 'unsafe-eval'"),
+            "Compatible CSP should be used unless strict_csp is set");
+
+        request.strict_csp = Some(true);
+        let html = generator.generate_index_html(&request)
+            .expect("Failed to generate index HTML");
+        assert!(html.contains("script-src 'self';"), "Strict CSP should restrict scripts to 'self'");
+        assert!(!html.contains("'unsafe-eval'"));
+
+        request.enable_csp = Some(false);
+        let html = generator.generate_index_html(&request)
+            .expect("Failed to generate index HTML");
+        assert!(!html.contains("Content-Security-Policy"),
+            "strict_csp has no effect unless enable_csp is set");
+    }
+
+    #[test]
+    fn test_generated_html_has_no_inline_scripts() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+
+        let request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            ..Default::default()
+        };
+        let index_html = generator.generate_index_html(&request)
+            .expect("Failed to generate index HTML");
+
+        let welcome = WelcomePage {
+            title: "Welcome".to_string(),
+            content: "Welcome content".to_string(),
+            image_url: Some("media/image-0.jpg".to_string()),
+            audio_file: Some("media/audio-0.mp3".to_string()),
+            caption_file: Some("media/caption-0.vtt".to_string()),
+            media: None,
+            start_button_text: "Start".to_string(),
+        };
+        let welcome_html = generator.generate_welcome_page(&welcome, None, &PageOptions::default(), None)
+            .expect("Failed to generate welcome page");
+
+        for (name, html) in [("index.html", &index_html), ("welcome.html", &welcome_html)] {
+            assert!(!html.contains("<script>"), "{name} should not contain inline <script> blocks");
+            for attr in ["onclick=", "onchange=", "onerror=", "onended=", "ontimeupdate=", "onloadedmetadata="] {
+                assert!(!html.contains(attr), "{name} should not contain inline {attr} handlers");
+            }
+        }
+
+        assert!(index_html.contains(r#"<script src="scripts/course-ui.js"></script>"#));
+        assert!(welcome_html.contains(r#"data-action="toggle-play-pause" data-page-id="welcome""#));
+        assert!(welcome_html.contains(r#"data-audio-page="welcome""#));
+
+        let course_ui = include_str!("runtime/course-ui.js");
+        assert!(course_ui.contains("'toggle-play-pause'"));
+        assert!(course_ui.contains("window.exitCourse = exitCourse"));
+    }
+
+    #[test]
+    fn test_pages_share_partials() {
+        use crate::scorm::html_generator_enhanced::TEMPLATE_PARTIALS;
+
+        let names: Vec<_> = TEMPLATE_PARTIALS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            vec!["analytics", "audio_player", "feedback", "feedback_media", "media_block", "nav_bar"]
+        );
+
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+
+        let request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "First Topic".to_string(),
+                content: "Topic content".to_string(),
+                audio_file: Some("media/audio-2.mp3".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let index_html = generator.generate_index_html(&request)
+            .expect("Failed to generate index HTML");
+        assert!(index_html.contains(r#"<div class="sidebar-nav">"#));
+        assert!(index_html.contains(r#"data-page="topic-1">First Topic</a>"#));
+
+        let topic_html = generator.generate_topic_page(&request.topics[0], &PageOptions::from_request(&request), None)
+            .expect("Failed to generate topic page");
+        assert!(topic_html.contains(r#"<audio id="topic-audio-topic-1""#));
+        assert!(topic_html.contains(r#"data-page-id="topic-1""#));
+
+        let objectives = ObjectivesPage {
+            objectives: vec!["Objective".to_string()],
+            audio_file: Some("media/audio-1.mp3".to_string()),
+            caption_file: None,
+            image_url: None,
+            media: None,
+        };
+        let locked_request = GenerateScormRequest {
+            require_audio_completion: Some(true),
+            ..Default::default()
+        };
+        let locked = PageOptions::from_request(&locked_request);
+        let objectives_html = generator.generate_objectives_page(&objectives, &locked, None)
+            .expect("Failed to generate objectives page");
+        assert!(objectives_html.contains(r#"<audio id="topic-audio-objectives""#));
+        assert!(objectives_html.contains("disabled-seeking"));
+        assert!(!objectives_html.contains("{{"), "partials should be fully rendered");
+    }
+
+    #[test]
+    fn test_index_html_diagnostics_script_only_in_debug_builds() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let script = r#"<script src="scripts/diagnostics.js"></script>"#;
+
+        let release = generator
+            .generate_index_html(&GenerateScormRequest::default())
+            .expect("Failed to generate index HTML");
+        assert!(!release.contains(script));
+
+        let debug = generator
+            .generate_index_html(&GenerateScormRequest {
+                debug_build: Some(true),
+                ..Default::default()
+            })
+            .expect("Failed to generate index HTML");
+        assert!(debug.contains(script));
+    }
+
+    #[test]
+    fn test_index_html_analytics_partial() {
+        use crate::scorm::analytics::{AnalyticsProvider, AnalyticsSettings};
+
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let untracked = generator
+            .generate_index_html(&GenerateScormRequest::default())
+            .expect("Failed to generate index HTML");
+        assert!(!untracked.contains("scripts/analytics.js"));
+
+        let mut request = GenerateScormRequest {
+            enable_csp: Some(true),
+            strict_csp: Some(true),
+            analytics: Some(AnalyticsSettings {
+                provider: AnalyticsProvider::Matomo,
+                endpoint: Some("https://stats.example.com/matomo".to_string()),
+                site_id: "7".to_string(),
+                anonymize_ip: None,
+                disable_cookies: Some(false),
+                respect_do_not_track: None,
+            }),
+            ..Default::default()
+        };
+        let tracked = generator
+            .generate_index_html(&request)
+            .expect("Failed to generate index HTML");
+        assert!(tracked.contains(r#"<script src="scripts/analytics.js" data-provider="matomo" data-endpoint="https://stats.example.com/matomo/" data-site-id="7""#));
+        assert!(tracked.contains(r#"data-disable-cookies="false""#));
+        assert!(tracked.contains("script-src 'self' https://stats.example.com;"));
+        assert!(!tracked.contains("<script>"), "strict CSP forbids inline scripts");
+
+        request.analytics.as_mut().unwrap().site_id = "seven".to_string();
+        assert!(generator.generate_index_html(&request).is_err());
+    }
+
+    #[test]
+    fn test_index_html_feedback_partial() {
+        use crate::scorm::feedback::FeedbackSettings;
+
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let without = generator
+            .generate_index_html(&GenerateScormRequest::default())
+            .expect("Failed to generate index HTML");
+        assert!(!without.contains("feedback-toggle"));
+
+        let mut request = GenerateScormRequest {
+            enable_csp: Some(true),
+            strict_csp: Some(true),
+            course_version: Some("2.1".to_string()),
+            feedback: Some(FeedbackSettings {
+                webhook_url: Some("https://hooks.example.com/course".to_string()),
+                email: None,
+            }),
+            ..Default::default()
+        };
+        let html = generator
+            .generate_index_html(&request)
+            .expect("Failed to generate index HTML");
+        assert!(html.contains("Report a problem with this page"));
+        assert!(html.contains(r#"data-webhook-url="https://hooks.example.com/course""#));
+        assert!(html.contains(r#"data-course-version="2.1""#));
+        assert!(html.contains("connect-src 'self' https://hooks.example.com;"));
+        assert!(html.contains("script-src 'self';"), "the webhook is not a script source");
+
+        request.feedback.as_mut().unwrap().webhook_url = None;
+        assert!(generator.generate_index_html(&request).is_err());
+    }
+
+    #[test]
+    fn test_assessment_page_emits_timer_settings() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let assessment = Assessment {
+            questions: vec![Question {
+                question_type: "multiple-choice".to_string(),
+                text: "Pick one".to_string(),
+                options: Some(vec!["A".to_string(), "B".to_string()]),
+                correct_answer: "A".to_string(),
+                explanation: None,
+                correct_feedback: None,
+                incorrect_feedback: None,
+                correct_feedback_media: None,
+                incorrect_feedback_media: None,
+                bank_ref: None,
+            }],
+        };
+
+        let untimed = generator
+            .generate_assessment_page(&assessment, None, None, None)
+            .expect("Failed to generate assessment page");
+        assert!(!untimed.contains("assessment-timer"));
+        assert!(!untimed.contains("next-assessment-question"));
+
+        let timer = AssessmentTimer {
+            overall_minutes: Some(10),
+            per_question_seconds: Some(30),
+            warning_seconds: None,
+            auto_submit: Some(false),
+        };
+        let timed = generator
+            .generate_assessment_page(&assessment, Some(&timer), None, None)
+            .expect("Failed to generate assessment page");
+        assert!(timed.contains(r#"data-overall-seconds="600""#));
+        assert!(timed.contains(r#"data-overall-warning-seconds="60""#));
+        assert!(timed.contains(r#"data-question-seconds="30""#));
+        // The default minute of warning is capped at half the question time
+        assert!(timed.contains(r#"data-question-warning-seconds="15""#));
+        assert!(timed.contains(r#"data-auto-submit="false""#));
+        assert!(timed.contains(r#"<span id="assessment-time-remaining">10:00</span>"#));
+        assert!(timed.contains(r#"<span id="question-time-remaining">0:30</span>"#));
+        assert!(timed.contains(r#"data-action="next-assessment-question""#));
+
+        let course_ui = include_str!("runtime/course-ui.js");
+        assert!(course_ui.contains("'next-assessment-question'"));
+    }
+
+    #[test]
+    fn test_welcome_start_options() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let media = |id: &str, media_type: &str, url: &str| MediaItem {
+            id: id.to_string(),
+            media_type: media_type.to_string(),
+            url: url.to_string(),
+            title: id.to_string(),
+            embed_url: None,
+            is_youtube: None,
+            clip_start: None,
+            clip_end: None,
+            alt_text: None,
+            attribution: None,
+        };
+        let welcome = WelcomePage {
+            title: "Welcome".to_string(),
+            content: "Welcome content".to_string(),
+            image_url: None,
+            audio_file: None,
+            caption_file: None,
+            media: Some(vec![
+                media("intro", "video", "media/video-0.mp4"),
+                media("tour", "video", "https://www.youtube.com/watch?v=abc"),
+                media("logo", "image", "media/image-0.jpg"),
+            ]),
+            start_button_text: "Start".to_string(),
+        };
+        let start = |video: &str, percent: Option<u8>| WelcomeStartOptions {
+            resume_prompt: Some(true),
+            intro_video_id: Some(video.to_string()),
+            intro_video_min_percent: percent,
+        };
+
+        assert!(start("intro", None).validate(Some(&welcome)).is_ok());
+        assert!(start("intro", Some(0)).validate(Some(&welcome)).is_err());
+        assert!(start("intro", Some(101)).validate(Some(&welcome)).is_err());
+        assert!(start("tour", None).validate(Some(&welcome)).is_err());
+        assert!(start("logo", None).validate(Some(&welcome)).is_err());
+        assert!(start("missing", None).validate(Some(&welcome)).is_err());
+        assert!(start("intro", None).validate(None).is_err());
+
+        let plain = generator
+            .generate_welcome_page(&welcome, None, &PageOptions::default(), None)
+            .expect("Failed to generate welcome page");
+        assert!(!plain.contains("data-resume-prompt"));
+        assert!(!plain.contains("data-intro-video"));
+        assert!(!plain.contains("data-intro-gate"));
+
+        let gated = generator
+            .generate_welcome_page(&welcome, Some(&start("intro", Some(75))), &PageOptions::default(), None)
+            .expect("Failed to generate welcome page");
+        assert!(gated.contains("data-resume-prompt hidden"));
+        assert!(gated.contains(r#"data-action="resume-course""#));
+        assert!(gated.contains(r#"data-action="restart-course""#));
+        assert!(gated.contains(r#"data-intro-video="75""#));
+        assert_eq!(gated.matches("data-intro-video=").count(), 1);
+        assert!(gated.contains("data-intro-gate"));
+
+        let course_ui = include_str!("runtime/course-ui.js");
+        assert!(course_ui.contains("'resume-course'"));
+        assert!(course_ui.contains("'restart-course'"));
+    }
+
+    #[test]
+    fn test_image_lightbox_toggle() {
+        use crate::scorm::style_generator::StyleGenerator;
+
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let topic = Topic {
+            id: "topic-1".to_string(),
+            title: "Dashboard".to_string(),
+            content: "<p>The dashboard</p>".to_string(),
+            image_url: Some("media/image-0.png".to_string()),
+            ..Default::default()
+        };
+
+        let zoomable = generator
+            .generate_topic_page(&topic, &PageOptions { image_lightbox: true, ..Default::default() }, None)
+            .expect("Failed to generate topic page");
+        assert!(zoomable.contains(r#"<button type="button" class="lightbox-trigger" data-action="open-lightbox" data-src="media/image-0.png" data-caption="Dashboard" aria-label="Enlarge image: Dashboard">"#));
+
+        let plain = generator
+            .generate_topic_page(&topic, &PageOptions::default(), None)
+            .expect("Failed to generate topic page");
+        assert!(plain.contains(r#"<img src="media/image-0.png" alt="Dashboard" class="topic-image" data-hide-on-error />"#));
+        assert!(!plain.contains("open-lightbox"));
+
+        let styles = StyleGenerator::new().unwrap();
+        let mut request = GenerateScormRequest::default();
+        assert!(styles.generate_main_css(&request).unwrap().contains(".lightbox-trigger"));
+        request.image_lightbox = Some(false);
+        assert!(!styles.generate_main_css(&request).unwrap().contains(".lightbox-modal"));
+
+        let course_ui = include_str!("runtime/course-ui.js");
+        assert!(course_ui.contains("'toggle-lightbox-zoom'"));
+    }
+
+    #[test]
+    fn test_audio_policy() {
+        use crate::scorm::navigation_generator::course_data;
+
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let topic = Topic {
+            id: "topic-1".to_string(),
+            title: "Narrated".to_string(),
+            content: "<p>Listen</p>".to_string(),
+            audio_file: Some("media/audio-2.mp3".to_string()),
+            ..Default::default()
+        };
+        let mut request = GenerateScormRequest {
+            require_audio_completion: Some(true),
+            topics: vec![topic.clone()],
+            ..Default::default()
+        };
+        let page = |request: &GenerateScormRequest| {
+            generator
+                .generate_topic_page(&topic, &PageOptions::from_request(request), None)
+                .expect("Failed to generate topic page")
+        };
+
+        // Completion required: seeking locked, speed selector on by default
+        let locked = page(&request);
+        assert!(locked.contains("audio-progress-container disabled-seeking"));
+        assert!(!locked.contains(r#"data-action="seek-audio""#));
+        assert!(locked.contains("audio-speed-selector"));
+
+        request.audio_policy = Some(AudioPolicy {
+            allow_seeking: Some(true),
+            speed_control: Some(false),
+            autoplay: Some(true),
+            remember_position: Some(true),
+            unlock_overlay: Some(false),
+        });
+        let open = page(&request);
+        assert!(open.contains(r#"data-action="seek-audio""#));
+        assert!(!open.contains("disabled-seeking"));
+        assert!(!open.contains("audio-speed-selector"));
+
+        let data = course_data(&request);
+        assert_eq!(data["require_audio_completion"], true);
+        assert_eq!(data["audio_seek_locked"], false);
+        assert_eq!(data["audio_autoplay"], true);
+        assert_eq!(data["remember_audio_position"], true);
+        assert_eq!(data["audio_unlock_overlay"], false);
+    }
+
+    #[test]
+    fn test_responsive_layout_toggle() {
+        use crate::scorm::style_generator::StyleGenerator;
+
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let styles = StyleGenerator::new().unwrap();
+        let mut request = GenerateScormRequest {
+            course_title: "Field Guide".to_string(),
+            ..Default::default()
+        };
+
+        let html = generator.generate_index_html(&request).unwrap();
+        assert!(html.contains(r#"<body class="responsive-layout">"#));
+        assert!(html.contains(r#"data-action="toggle-nav-drawer" aria-controls="course-sidebar""#));
+        assert!(html.contains(r#"<div class="nav-drawer-backdrop" data-action="close-nav-drawer" hidden></div>"#));
+        let css = styles.generate_main_css(&request).unwrap();
+        assert!(css.contains("height: var(--viewport-height, 100vh);"));
+        assert!(css.contains(".responsive-layout.nav-drawer-open .sidebar"));
+        assert!(!css.contains("content: '•';"));
+
+        // No outline means nothing to put in a drawer
+        request.show_outline = Some(false);
+        let html = generator.generate_index_html(&request).unwrap();
+        assert!(!html.contains("nav-drawer-toggle"));
+
+        request.show_outline = Some(true);
+        request.responsive_layout = Some(false);
+        let html = generator.generate_index_html(&request).unwrap();
+        assert!(html.contains("<body>"));
+        assert!(!html.contains("nav-drawer"));
+        let css = styles.generate_main_css(&request).unwrap();
+        assert!(!css.contains("--viewport-height"));
+        assert!(css.contains("content: '•';"));
+    }
+
+    #[test]
+    fn test_media_alt_text_names_images_and_players() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let media = |id: &str, media_type: &str, alt_text: Option<&str>| MediaItem {
+            id: id.to_string(),
+            media_type: media_type.to_string(),
+            url: format!("media/{id}.bin"),
+            title: format!("{id}.bin"),
+            embed_url: None,
+            is_youtube: None,
+            clip_start: None,
+            clip_end: None,
+            alt_text: alt_text.map(str::to_string),
+            attribution: None,
+        };
+        let topic = Topic {
+            id: "topic-1".to_string(),
+            title: "Results".to_string(),
+            content: "<p>Results</p>".to_string(),
+            audio_file: Some("media/audio-2.mp3".to_string()),
+            media: Some(vec![
+                media("image-1", "image", Some("Sales by quarter")),
+                media("image-2", "image", None),
+                media("video-1", "video", Some("Product walkthrough")),
+            ]),
+            ..Default::default()
+        };
+
+        let page = generator
+            .generate_topic_page(&topic, &PageOptions { image_lightbox: true, ..Default::default() }, None)
+            .expect("Failed to generate topic page");
+        assert!(page.contains(r#"aria-label="Enlarge image: Sales by quarter""#));
+        assert!(page.contains(r#"<img src="media/image-1.bin" alt="Sales by quarter""#));
+        assert!(page.contains(r#"<img src="media/image-2.bin" alt="image-2.bin""#));
+        assert!(page.contains(r#"class="topic-video" aria-label="Product walkthrough""#));
+        assert!(page.contains(r#"role="group" aria-label="Narration: Results""#));
+    }
+
+    #[test]
+    fn test_course_features_override_interface_settings() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let mut request = GenerateScormRequest {
+            course_title: "Field Guide".to_string(),
+            printable: Some(false),
+            ..Default::default()
+        };
+
+        let html = generator.generate_index_html(&request).unwrap();
+        assert!(html.contains(r#"id="course-sidebar""#));
+        assert!(html.contains("progress-circle"));
+        assert!(!html.contains("print-button"));
+        assert!(!html.contains("page-counter"));
+
+        request.features = Some(CourseFeatures {
+            progress_bar: Some(false),
+            page_numbers: Some(true),
+            print_button: Some(true),
+            ..Default::default()
+        });
+        let html = generator.generate_index_html(&request).unwrap();
+        assert!(!html.contains("progress-circle"));
+        assert!(html.contains(r#"<span id="page-counter" class="page-counter" aria-hidden="true"></span>"#));
+        assert!(html.contains(r#"class="print-button" data-action="print-course""#));
+
+        request.features = Some(CourseFeatures {
+            navigation_menu: Some(false),
+            ..Default::default()
+        });
+        let html = generator.generate_index_html(&request).unwrap();
+        assert!(!html.contains(r#"id="course-sidebar""#));
+        assert!(!html.contains("progress-circle"));
+
+        // The progress bar can't be shown without the menu it sits in
+        request.features = Some(CourseFeatures {
+            navigation_menu: Some(false),
+            progress_bar: Some(true),
+            ..Default::default()
+        });
+        let error = generator.generate_index_html(&request).unwrap_err();
+        assert!(error.contains("navigation menu"));
+    }
+
+    #[test]
+    fn test_captions_toggle_can_be_turned_off() {
+        let generator = HtmlGenerator::new().expect("Failed to create HTML generator");
+        let topic = Topic {
+            id: "topic-1".to_string(),
+            title: "Results".to_string(),
+            content: "<p>Results</p>".to_string(),
+            audio_file: Some("media/audio-2.mp3".to_string()),
+            caption_file: Some("media/caption-2.vtt".to_string()),
+            ..Default::default()
+        };
+        let mut request = GenerateScormRequest::default();
+
+        let page = generator
+            .generate_topic_page(&topic, &PageOptions::from_request(&request), None)
+            .unwrap();
+        assert!(page.contains(r#"data-action="toggle-captions""#));
+        assert!(page.contains(r#"id="caption-topic-1" style="display:none;""#));
+
+        request.features = Some(CourseFeatures {
+            captions_toggle: Some(false),
+            ..Default::default()
+        });
+        let page = generator
+            .generate_topic_page(&topic, &PageOptions::from_request(&request), None)
+            .unwrap();
+        assert!(!page.contains("toggle-captions"));
+        // Without the button the captions stay visible
+        assert!(page.contains(r#"<div class="caption-display" id="caption-topic-1">"#));
+    }
+}
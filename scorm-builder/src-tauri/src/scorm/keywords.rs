@@ -0,0 +1,142 @@
+//! Keywords for the manifest's LOM metadata, so LMS catalog search finds the course.
+//!
+//! Topic titles are taken whole, since they are short and name what the course
+//! covers. Objectives are sentences, so only their significant words are kept:
+//! the common English words and the verbs objectives are phrased with
+//! ("identify", "describe"...) are dropped. The author's own keywords come first
+//! and can replace the derived ones altogether.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use ts_rs::TS;
+
+use super::generator_enhanced::GenerateScormRequest;
+
+/// Most keywords written to the manifest
+pub const MAX_KEYWORDS: usize = 30;
+
+/// Shortest objective word kept as a keyword
+const MIN_WORD_LEN: usize = 4;
+
+const STOP_WORDS: &[&str] = &[
+    "about", "able", "after", "also", "and", "apply", "been", "before", "being",
+    "between", "both", "define", "demonstrate", "describe", "discuss", "during", "each",
+    "explain", "from", "have", "identify", "into", "know", "learn", "list", "more", "most",
+    "other", "outline", "over", "recognise", "recognize", "should", "such", "summarise",
+    "summarize", "than", "that", "their", "them", "then", "there", "these", "they", "this",
+    "those", "through", "under", "understand", "understanding", "upon", "used", "using", "what",
+    "when", "where", "which", "while", "will", "with", "within", "without", "would", "your",
+];
+
+/// Keywords set by the author
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct KeywordSettings {
+    /// Listed before the derived keywords
+    #[serde(default)]
+    #[ts(as = "Option<Vec<String>>", optional)]
+    pub keywords: Vec<String>,
+    /// Add keywords from topic titles and objectives (default true); when false
+    /// only `keywords` are used
+    #[ts(optional)]
+    pub derive: Option<bool>,
+}
+
+/// Keywords for `request`, the author's first, without repeats (ignoring case)
+pub fn course_keywords(request: &GenerateScormRequest) -> Vec<String> {
+    let settings = request.keywords.clone().unwrap_or_default();
+    let mut keywords = Keywords::default();
+
+    for keyword in &settings.keywords {
+        keywords.push(keyword);
+    }
+    if settings.derive.unwrap_or(true) {
+        for topic in &request.topics {
+            keywords.push(&topic.title);
+        }
+        for objective in request.learning_objectives_page.iter().flat_map(|page| &page.objectives) {
+            for word in significant_words(objective) {
+                keywords.push(word);
+            }
+        }
+    }
+    keywords.list
+}
+
+#[derive(Default)]
+struct Keywords {
+    list: Vec<String>,
+    seen: HashSet<String>,
+}
+
+impl Keywords {
+    fn push(&mut self, keyword: &str) {
+        let keyword = keyword.split_whitespace().collect::<Vec<_>>().join(" ");
+        if keyword.is_empty() || self.list.len() >= MAX_KEYWORDS {
+            return;
+        }
+        if self.seen.insert(keyword.to_lowercase()) {
+            self.list.push(keyword);
+        }
+    }
+}
+
+/// Words of an objective worth searching for
+fn significant_words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-')
+        .map(|word| word.trim_matches('-'))
+        .filter(|word| word.chars().count() >= MIN_WORD_LEN)
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+        .filter(|word| !STOP_WORDS.contains(&word.to_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::{ObjectivesPage, Topic};
+
+    fn request(keywords: Option<KeywordSettings>) -> GenerateScormRequest {
+        GenerateScormRequest {
+            topics: vec![
+                Topic { title: "Confined  Spaces".to_string(), ..Default::default() },
+                Topic { title: "Gas Detection".to_string(), ..Default::default() },
+            ],
+            learning_objectives_page: Some(ObjectivesPage {
+                objectives: vec![
+                    "Identify the hazards of confined spaces".to_string(),
+                    "Describe how to use a gas detector".to_string(),
+                ],
+                audio_file: None,
+                caption_file: None,
+                image_url: None,
+                media: None,
+            }),
+            keywords,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_keywords_come_from_topics_and_objectives() {
+        assert_eq!(
+            course_keywords(&request(None)),
+            vec!["Confined Spaces", "Gas Detection", "hazards", "confined", "spaces", "detector"]
+        );
+    }
+
+    #[test]
+    fn test_author_keywords_extend_or_replace_derived_ones() {
+        let extended = course_keywords(&request(Some(KeywordSettings {
+            keywords: vec!["H2S".to_string(), "gas detection".to_string()],
+            derive: None,
+        })));
+        assert_eq!(&extended[..3], ["H2S", "gas detection", "Confined Spaces"]);
+        assert!(!extended.contains(&"Gas Detection".to_string()));
+
+        let replaced = course_keywords(&request(Some(KeywordSettings {
+            keywords: vec!["H2S".to_string()],
+            derive: Some(false),
+        })));
+        assert_eq!(replaced, vec!["H2S"]);
+    }
+}
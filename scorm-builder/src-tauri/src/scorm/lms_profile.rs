@@ -0,0 +1,201 @@
+//! Named compatibility profiles for LMSs that need the package to behave a
+//! little differently.
+//!
+//! A profile only switches a handful of behaviours, all listed in
+//! [`LmsCompatibility`]: navigation.js reads them from `LMS_COMPATIBILITY` and
+//! the suspend_data settings, and the manifest generator asks for the mastery
+//! score. The generic profile leaves everything as a plain SCORM 1.2 player
+//! expects, so new quirks belong here rather than in the templates.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use ts_rs::TS;
+
+use super::suspend_data::SUSPEND_DATA_MAX_LENGTH;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum LmsProfile {
+    #[default]
+    Generic,
+    Moodle,
+    Cornerstone,
+    /// SAP SuccessFactors Learning
+    SuccessFactors,
+}
+
+/// Behaviours a profile turns on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LmsCompatibility {
+    /// Stretch the LMS's iframe to the visible window height. Only possible
+    /// when the player serves the package from its own origin, as Moodle does.
+    pub fit_parent_frame: bool,
+    /// Write suspend_data as pure ASCII, escaping other characters as `\uXXXX`
+    pub ascii_suspend_data: bool,
+    /// Characters of suspend_data the LMS keeps intact
+    pub suspend_data_max_length: usize,
+    /// Least seconds between commits; 0 leaves them to the commit debounce
+    pub commit_interval_seconds: u32,
+    /// Put the pass mark in the manifest as `adlcp:masteryscore`
+    pub manifest_mastery_score: bool,
+    /// Ask for the audio unlock click as the course opens, because the launch
+    /// window never carries the learner's click and autoplay always fails
+    pub unlock_audio_on_launch: bool,
+}
+
+const GENERIC: LmsCompatibility = LmsCompatibility {
+    fit_parent_frame: false,
+    ascii_suspend_data: false,
+    suspend_data_max_length: SUSPEND_DATA_MAX_LENGTH,
+    commit_interval_seconds: 0,
+    manifest_mastery_score: false,
+    unlock_audio_on_launch: false,
+};
+
+impl LmsProfile {
+    pub fn name(self) -> &'static str {
+        match self {
+            LmsProfile::Generic => "generic",
+            LmsProfile::Moodle => "moodle",
+            LmsProfile::Cornerstone => "cornerstone",
+            LmsProfile::SuccessFactors => "successfactors",
+        }
+    }
+
+    pub fn compatibility(self) -> LmsCompatibility {
+        match self {
+            LmsProfile::Generic => GENERIC,
+            // Moodle's player gives the content iframe a fixed height
+            LmsProfile::Moodle => LmsCompatibility {
+                fit_parent_frame: true,
+                ..GENERIC
+            },
+            // Cornerstone garbles non-ASCII text in suspend_data and cuts long
+            // values off short of the SCORM limit instead of rejecting them.
+            // Its launcher opens the course window from a server redirect.
+            LmsProfile::Cornerstone => LmsCompatibility {
+                ascii_suspend_data: true,
+                suspend_data_max_length: SUSPEND_DATA_MAX_LENGTH - 96,
+                manifest_mastery_score: true,
+                unlock_audio_on_launch: true,
+                ..GENERIC
+            },
+            // SuccessFactors round-trips every commit to the server and slows
+            // down or times out when courses commit on each page. Its content
+            // window opens from a redirect, like Cornerstone's.
+            LmsProfile::SuccessFactors => LmsCompatibility {
+                commit_interval_seconds: 60,
+                manifest_mastery_score: true,
+                unlock_audio_on_launch: true,
+                ..GENERIC
+            },
+        }
+    }
+
+    /// Template data for `LMS_COMPATIBILITY` in navigation.js
+    pub fn template_data(self) -> Value {
+        let compatibility = self.compatibility();
+        json!({
+            "profile": self.name(),
+            "fit_parent_frame": compatibility.fit_parent_frame,
+            "unlock_audio_on_launch": compatibility.unlock_audio_on_launch,
+            "commit_interval_ms": u64::from(compatibility.commit_interval_seconds) * 1000,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::GenerateScormRequest;
+    use crate::scorm::navigation_generator::course_data;
+    use crate::scorm::suspend_data::suspend_data_settings;
+
+    fn course_data_for(profile: Option<LmsProfile>) -> Value {
+        let request = GenerateScormRequest {
+            lms_profile: profile,
+            ..Default::default()
+        };
+        course_data(&request)
+    }
+
+    #[test]
+    fn test_profiles_deserialize_by_name() {
+        let profiles: Vec<LmsProfile> =
+            serde_json::from_str(r#"["generic", "moodle", "cornerstone", "successfactors"]"#)
+                .unwrap();
+        for profile in profiles {
+            assert_eq!(
+                serde_json::to_value(profile).unwrap(),
+                json!(profile.name())
+            );
+        }
+        assert!(serde_json::from_str::<LmsProfile>(r#""blackboard""#).is_err());
+    }
+
+    #[test]
+    fn test_generic_profile_changes_nothing() {
+        assert_eq!(LmsProfile::default().compatibility(), GENERIC);
+
+        let data = course_data_for(None);
+        assert_eq!(
+            data["lms"],
+            json!({
+                "profile": "generic",
+                "fit_parent_frame": false,
+                "unlock_audio_on_launch": false,
+                "commit_interval_ms": 0,
+            })
+        );
+        assert_eq!(data["suspend_data"]["ascii_only"], false);
+        assert_eq!(data["suspend_data"]["max_length"], 4096);
+    }
+
+    #[test]
+    fn test_moodle_profile_fits_parent_frame() {
+        let compatibility = LmsProfile::Moodle.compatibility();
+        assert!(compatibility.fit_parent_frame);
+        assert_eq!(compatibility.commit_interval_seconds, 0);
+        assert!(!compatibility.manifest_mastery_score);
+
+        let data = course_data_for(Some(LmsProfile::Moodle));
+        assert_eq!(data["lms"]["profile"], "moodle");
+        assert_eq!(data["lms"]["fit_parent_frame"], true);
+    }
+
+    #[test]
+    fn test_cornerstone_profile_keeps_suspend_data_ascii() {
+        let request = GenerateScormRequest {
+            lms_profile: Some(LmsProfile::Cornerstone),
+            learner_notes: Some(true),
+            ..Default::default()
+        };
+        let settings = suspend_data_settings(&request);
+        assert_eq!(settings["ascii_only"], true);
+        assert_eq!(settings["max_length"], 4000);
+        assert_eq!(settings["notes_max_length"], 2464);
+        assert!(
+            LmsProfile::Cornerstone
+                .compatibility()
+                .manifest_mastery_score
+        );
+
+        let data = course_data_for(Some(LmsProfile::Cornerstone));
+        assert_eq!(data["suspend_data"]["ascii_only"], true);
+        assert_eq!(data["lms"]["fit_parent_frame"], false);
+    }
+
+    #[test]
+    fn test_successfactors_profile_throttles_commits() {
+        let compatibility = LmsProfile::SuccessFactors.compatibility();
+        assert_eq!(compatibility.commit_interval_seconds, 60);
+        assert!(compatibility.manifest_mastery_score);
+        assert!(!compatibility.ascii_suspend_data);
+
+        let data = course_data_for(Some(LmsProfile::SuccessFactors));
+        assert_eq!(data["lms"]["profile"], "successfactors");
+        assert_eq!(data["lms"]["unlock_audio_on_launch"], true);
+        assert_eq!(data["lms"]["commit_interval_ms"], 60000);
+    }
+}
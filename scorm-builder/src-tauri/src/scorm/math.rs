@@ -0,0 +1,116 @@
+//! Optional math rendering for technical courses.
+//!
+//! Content may contain TeX between `$$…$$` (display) or `\(…\)` (inline)
+//! delimiters. Packages whose content does get the vendored KaTeX build and
+//! `scripts/math.js`, which renders the formulas as MathML in the browser.
+//! Courses without math ship neither, so their packages stay small.
+
+use super::generator_enhanced::{GenerateScormRequest, Question};
+
+/// Opening and closing delimiters, the same pairs `math.js` looks for
+pub const MATH_DELIMITERS: [(&str, &str); 2] = [("$$", "$$"), ("\\(", "\\)")];
+
+/// Whether `text` has a non-empty formula between a pair of delimiters
+pub fn contains_math(text: &str) -> bool {
+    MATH_DELIMITERS.iter().any(|(open, close)| {
+        let mut rest = text;
+        while let Some(start) = rest.find(open) {
+            rest = &rest[start + open.len()..];
+            let Some(end) = rest.find(close) else {
+                return false;
+            };
+            if !rest[..end].trim().is_empty() {
+                return true;
+            }
+            rest = &rest[end + close.len()..];
+        }
+        false
+    })
+}
+
+/// Whether any text the course shows contains math, which decides if the
+/// package ships KaTeX
+pub fn course_uses_math(request: &GenerateScormRequest) -> bool {
+    let mut texts: Vec<&str> = vec![request.course_title.as_str()];
+    if let Some(welcome) = &request.welcome_page {
+        texts.extend([welcome.title.as_str(), welcome.content.as_str()]);
+    }
+    if let Some(objectives) = &request.learning_objectives_page {
+        texts.extend(objectives.objectives.iter().map(String::as_str));
+    }
+    for topic in &request.topics {
+        texts.extend([topic.title.as_str(), topic.content.as_str()]);
+        for question in topic.knowledge_check.iter().flat_map(|kc| &kc.questions) {
+            question_texts(question, &mut texts);
+        }
+    }
+    for question in request.assessment.iter().flat_map(|a| &a.questions) {
+        question_texts(question, &mut texts);
+    }
+
+    texts.into_iter().any(contains_math)
+}
+
+fn question_texts<'a>(question: &'a Question, texts: &mut Vec<&'a str>) {
+    texts.push(&question.text);
+    texts.extend(question.options.iter().flatten().map(String::as_str));
+    texts.extend(
+        [
+            &question.explanation,
+            &question.correct_feedback,
+            &question.incorrect_feedback,
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::{KnowledgeCheck, Topic};
+
+    #[test]
+    fn test_contains_math() {
+        assert!(contains_math("Energy: $$E = mc^2$$"));
+        assert!(contains_math(r"The root \(\sqrt{2}\) is irrational"));
+        assert!(!contains_math("Prices from $5 to $10"));
+        assert!(!contains_math("Costs $$ and more"));
+        assert!(!contains_math("Empty $$  $$ pair"));
+        assert!(contains_math("Empty $$ $$ then $$x$$"));
+        assert!(!contains_math(r"Escaped \( but never closed"));
+    }
+
+    #[test]
+    fn test_course_uses_math_checks_questions() {
+        let mut request = GenerateScormRequest {
+            course_title: "Statics".to_string(),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Forces".to_string(),
+                content: "<p>Forces add up.</p>".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(!course_uses_math(&request));
+
+        request.topics[0].knowledge_check = Some(KnowledgeCheck {
+            enabled: true,
+            questions: vec![Question {
+                question_type: "multiple-choice".to_string(),
+                text: "What is the net force?".to_string(),
+                options: Some(vec![r"\(F = ma\)".to_string(), "Zero".to_string()]),
+                correct_answer: r"\(F = ma\)".to_string(),
+                explanation: None,
+                correct_feedback: None,
+                incorrect_feedback: None,
+                correct_feedback_media: None,
+                incorrect_feedback_media: None,
+                bank_ref: None,
+            }],
+        });
+        assert!(course_uses_math(&request));
+    }
+}
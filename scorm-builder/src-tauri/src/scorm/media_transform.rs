@@ -0,0 +1,625 @@
+use super::animated_gifs::{is_animated_gif, GIF_VIDEO_FORMATS};
+use super::package_sink::PackageSink;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::process::Command;
+use ts_rs::TS;
+
+/// Per-project media rules applied while building a package. Only the copies
+/// written into the package are transformed; the project's media folder is
+/// never modified.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MediaTransformSpec {
+    /// Downscale videos taller than this (e.g. 720)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_video_height: Option<u32>,
+    /// Re-encode audio at this bitrate (e.g. 128); the result is only kept when smaller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_audio_bitrate_kbps: Option<u32>,
+    /// Convert JPEG and PNG images to WebP
+    #[serde(default)]
+    pub convert_images_to_webp: bool,
+    /// Also package each MP3 in these encodings ("ogg", "webm") for browsers
+    /// that can't play MP3; the audio player offers every encoding
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[ts(as = "Option<Vec<String>>", optional)]
+    pub audio_fallback_formats: Vec<String>,
+    /// Package animated GIFs as video in this format ("mp4" or "webm"); pages
+    /// play it with controls in place of the image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub convert_animated_gifs: Option<String>,
+}
+
+impl MediaTransformSpec {
+    pub fn is_empty(&self) -> bool {
+        self.max_video_height.is_none()
+            && self.max_audio_bitrate_kbps.is_none()
+            && !self.convert_images_to_webp
+            && self.audio_fallback_formats.is_empty()
+            && self.convert_animated_gifs.is_none()
+    }
+}
+
+/// Converts a single media file. `FfmpegTranscoder` is the real implementation;
+/// tests substitute a fake.
+pub trait MediaTranscoder {
+    fn transcode(
+        &self,
+        input: &[u8],
+        input_ext: &str,
+        output_ext: &str,
+        args: &[String],
+    ) -> Result<Vec<u8>, String>;
+}
+
+/// Runs the `ffmpeg` binary found on PATH
+pub struct FfmpegTranscoder;
+
+impl FfmpegTranscoder {
+    pub fn is_available() -> bool {
+        Command::new("ffmpeg")
+            .arg("-version")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+}
+
+impl MediaTranscoder for FfmpegTranscoder {
+    fn transcode(
+        &self,
+        input: &[u8],
+        input_ext: &str,
+        output_ext: &str,
+        args: &[String],
+    ) -> Result<Vec<u8>, String> {
+        let dir = tempfile::tempdir()
+            .map_err(|e| format!("Failed to create transcode directory: {e}"))?;
+        let input_path = dir.path().join(format!("input.{input_ext}"));
+        let output_path = dir.path().join(format!("output.{output_ext}"));
+
+        std::fs::write(&input_path, input)
+            .map_err(|e| format!("Failed to write transcode input: {e}"))?;
+
+        let output = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-loglevel")
+            .arg("error")
+            .arg("-i")
+            .arg(&input_path)
+            .args(args)
+            .arg(&output_path)
+            .output()
+            .map_err(|e| format!("Failed to run ffmpeg: {e}"))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "ffmpeg failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        std::fs::read(&output_path).map_err(|e| format!("Failed to read transcode output: {e}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformRule {
+    VideoMaxHeight,
+    AudioMaxBitrate,
+    ImageToWebp,
+    AudioFallback,
+    AnimatedGifToVideo,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformOutcome {
+    /// The transformed file replaced the original in the package, or for a
+    /// fallback encoding was packaged beside it
+    Applied,
+    /// The transformed file was not smaller, so the original was packaged
+    NoSavings,
+    /// The transcoder failed; the original was packaged
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaTransformEntry {
+    pub path: String,
+    /// Path inside the package (differs from `path` when the format changed)
+    pub output_path: String,
+    pub rule: TransformRule,
+    pub outcome: TransformOutcome,
+    pub original_size: u64,
+    pub packaged_size: u64,
+}
+
+impl MediaTransformEntry {
+    pub fn bytes_saved(&self) -> u64 {
+        self.original_size.saturating_sub(self.packaged_size)
+    }
+}
+
+/// Size savings of every file a rule applied to. Fallback encodings are extra
+/// files rather than replacements, so they're listed but left out of the totals.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MediaTransformReport {
+    pub files: Vec<MediaTransformEntry>,
+    pub original_total: u64,
+    pub packaged_total: u64,
+    pub bytes_saved: u64,
+}
+
+/// Media files as they go into the package, plus what changed
+pub struct TransformedMedia {
+    pub files: HashMap<String, Vec<u8>>,
+    /// Original package path -> new package path, for files whose format changed
+    pub renamed: BTreeMap<String, String>,
+    pub report: MediaTransformReport,
+}
+
+struct PlannedTransform {
+    rule: TransformRule,
+    output_ext: &'static str,
+    args: Vec<String>,
+}
+
+fn plan_transform(path: &str, data: &[u8], spec: &MediaTransformSpec) -> Option<PlannedTransform> {
+    let ext = path.rsplit_once('.')?.1.to_lowercase();
+
+    match ext.as_str() {
+        "mp4" | "webm" | "mov" => spec.max_video_height.map(|height| PlannedTransform {
+            rule: TransformRule::VideoMaxHeight,
+            output_ext: if ext == "webm" { "webm" } else { "mp4" },
+            args: vec![
+                "-vf".to_string(),
+                format!("scale=-2:'min({height},ih)'"),
+                "-c:a".to_string(),
+                "copy".to_string(),
+            ],
+        }),
+        "mp3" => spec.max_audio_bitrate_kbps.map(|kbps| PlannedTransform {
+            rule: TransformRule::AudioMaxBitrate,
+            output_ext: "mp3",
+            args: vec!["-b:a".to_string(), format!("{kbps}k")],
+        }),
+        "jpg" | "jpeg" | "png" if spec.convert_images_to_webp => Some(PlannedTransform {
+            rule: TransformRule::ImageToWebp,
+            output_ext: "webp",
+            args: vec![
+                "-c:v".to_string(),
+                "libwebp".to_string(),
+                "-quality".to_string(),
+                "80".to_string(),
+            ],
+        }),
+        "gif" if is_animated_gif(data) => {
+            let format = spec.convert_animated_gifs.as_deref()?.to_lowercase();
+            let output_ext = *GIF_VIDEO_FORMATS.iter().find(|ext| **ext == format)?;
+            let args: &[&str] = if output_ext == "webm" {
+                &["-c:v", "libvpx-vp9", "-crf", "35", "-b:v", "0"]
+            } else {
+                // H.264 needs even dimensions and 4:2:0 for browsers to play it
+                &[
+                    "-movflags",
+                    "+faststart",
+                    "-pix_fmt",
+                    "yuv420p",
+                    "-vf",
+                    "scale=trunc(iw/2)*2:trunc(ih/2)*2",
+                ]
+            };
+            Some(PlannedTransform {
+                rule: TransformRule::AnimatedGifToVideo,
+                output_ext,
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn fallback_args(format: &str) -> Option<Vec<String>> {
+    let args: &[&str] = match format {
+        "ogg" => &["-vn", "-c:a", "libvorbis", "-q:a", "4"],
+        "webm" => &["-vn", "-c:a", "libopus", "-b:a", "64k"],
+        _ => return None,
+    };
+    Some(args.iter().map(|arg| arg.to_string()).collect())
+}
+
+/// Encode the MP3 at `path` in each fallback format of `spec` that isn't
+/// packaged already
+fn add_audio_fallbacks(
+    path: &str,
+    data: &[u8],
+    spec: &MediaTransformSpec,
+    transcoder: &dyn MediaTranscoder,
+    media_files: &HashMap<String, Vec<u8>>,
+    files: &mut HashMap<String, Vec<u8>>,
+    report: &mut MediaTransformReport,
+) {
+    let Some((stem, "mp3")) = path.rsplit_once('.') else {
+        return;
+    };
+    for format in &spec.audio_fallback_formats {
+        let format = format.to_lowercase();
+        let output_path = format!("{stem}.{format}");
+        if media_files.contains_key(&output_path) {
+            continue;
+        }
+
+        let encoded = fallback_args(&format)
+            .ok_or_else(|| format!("Unsupported audio fallback format: {format}"))
+            .and_then(|args| transcoder.transcode(data, "mp3", &format, &args));
+        let (outcome, packaged_size) = match encoded {
+            Ok(output) => {
+                let size = output.len() as u64;
+                files.insert(output_path.clone(), output);
+                (TransformOutcome::Applied, size)
+            }
+            Err(error) => (TransformOutcome::Failed { error }, 0),
+        };
+
+        eprintln!("[Media Transform] {path} -> {output_path}: {outcome:?} ({packaged_size} bytes)");
+
+        report.files.push(MediaTransformEntry {
+            path: path.to_string(),
+            output_path,
+            rule: TransformRule::AudioFallback,
+            outcome,
+            original_size: 0,
+            packaged_size,
+        });
+    }
+}
+
+/// Apply `spec` to copies of the package media. Files no rule applies to are
+/// passed through unchanged and left out of the report.
+pub fn apply_media_transforms(
+    media_files: &HashMap<String, Vec<u8>>,
+    spec: &MediaTransformSpec,
+    transcoder: &dyn MediaTranscoder,
+) -> TransformedMedia {
+    let mut files = HashMap::with_capacity(media_files.len());
+    let mut renamed = BTreeMap::new();
+    let mut report = MediaTransformReport::default();
+
+    // Sorted so the report lists files in a stable order
+    let mut paths: Vec<&String> = media_files.keys().collect();
+    paths.sort();
+
+    for path in paths {
+        let data = &media_files[path];
+        add_audio_fallbacks(
+            path,
+            data,
+            spec,
+            transcoder,
+            media_files,
+            &mut files,
+            &mut report,
+        );
+        let Some(plan) = plan_transform(path, data, spec) else {
+            files.insert(path.clone(), data.clone());
+            continue;
+        };
+
+        let (stem, input_ext) = path.rsplit_once('.').unwrap_or((path, ""));
+        let new_path = format!("{stem}.{}", plan.output_ext);
+
+        let (outcome, output_path, packaged) =
+            match transcoder.transcode(data, input_ext, plan.output_ext, &plan.args) {
+                Ok(output) if output.len() < data.len() => {
+                    (TransformOutcome::Applied, new_path, output)
+                }
+                Ok(_) => (TransformOutcome::NoSavings, path.clone(), data.clone()),
+                Err(error) => (
+                    TransformOutcome::Failed { error },
+                    path.clone(),
+                    data.clone(),
+                ),
+            };
+
+        eprintln!(
+            "[Media Transform] {path} -> {output_path}: {outcome:?} ({} -> {} bytes)",
+            data.len(),
+            packaged.len()
+        );
+
+        let entry = MediaTransformEntry {
+            path: path.clone(),
+            output_path: output_path.clone(),
+            rule: plan.rule,
+            outcome,
+            original_size: data.len() as u64,
+            packaged_size: packaged.len() as u64,
+        };
+        report.original_total += entry.original_size;
+        report.packaged_total += entry.packaged_size;
+        report.bytes_saved += entry.bytes_saved();
+        report.files.push(entry);
+
+        if output_path != *path {
+            renamed.insert(path.clone(), output_path.clone());
+        }
+        files.insert(output_path, packaged);
+    }
+
+    TransformedMedia {
+        files,
+        renamed,
+        report,
+    }
+}
+
+/// Packaged files that can name a media file: the pages, the manifest's
+/// `<file href>` entries, and the course data and runtime scripts
+const REFERENCING_EXTENSIONS: [&str; 3] = [".html", ".xml", ".js"];
+
+/// Sink adapter that points references to renamed media files at their new names
+pub struct RenamingSink<'a> {
+    inner: &'a mut dyn PackageSink,
+    renamed: &'a BTreeMap<String, String>,
+}
+
+impl<'a> RenamingSink<'a> {
+    pub fn new(inner: &'a mut dyn PackageSink, renamed: &'a BTreeMap<String, String>) -> Self {
+        Self { inner, renamed }
+    }
+}
+
+impl PackageSink for RenamingSink<'_> {
+    fn add_file(&mut self, path: &str, data: &[u8]) -> Result<(), String> {
+        if self.renamed.is_empty() || !REFERENCING_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
+        {
+            return self.inner.add_file(path, data);
+        }
+
+        let mut text = String::from_utf8_lossy(data).into_owned();
+        for (from, to) in self.renamed {
+            for quote in ['"', '\''] {
+                text = text.replace(
+                    &format!("{quote}{from}{quote}"),
+                    &format!("{quote}{to}{quote}"),
+                );
+            }
+        }
+        self.inner.add_file(path, text.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::animated_gifs::test_gif;
+    use crate::scorm::package_sink::CollectSink;
+
+    /// Halves every file, except the extensions it's told to fail on or grow
+    struct FakeTranscoder {
+        fail_on: &'static str,
+        grow_on: &'static str,
+    }
+
+    impl MediaTranscoder for FakeTranscoder {
+        fn transcode(
+            &self,
+            input: &[u8],
+            input_ext: &str,
+            _output_ext: &str,
+            _args: &[String],
+        ) -> Result<Vec<u8>, String> {
+            if input_ext == self.fail_on {
+                return Err("unsupported codec".to_string());
+            }
+            if input_ext == self.grow_on {
+                return Ok([input, &[0]].concat());
+            }
+            Ok(input[..input.len() / 2].to_vec())
+        }
+    }
+
+    fn sample_media() -> HashMap<String, Vec<u8>> {
+        HashMap::from([
+            ("media/image-0.jpg".to_string(), vec![1; 100]),
+            ("media/audio-0.mp3".to_string(), vec![2; 80]),
+            ("media/video-0.mov".to_string(), vec![3; 1]),
+            ("media/caption-0.vtt".to_string(), vec![4; 10]),
+        ])
+    }
+
+    #[test]
+    fn test_apply_media_transforms_reports_savings() {
+        let media = sample_media();
+        let spec = MediaTransformSpec {
+            max_video_height: Some(720),
+            max_audio_bitrate_kbps: Some(128),
+            convert_images_to_webp: true,
+            ..Default::default()
+        };
+
+        let result = apply_media_transforms(
+            &media,
+            &spec,
+            &FakeTranscoder {
+                fail_on: "",
+                grow_on: "mov",
+            },
+        );
+
+        // Source map is untouched
+        assert_eq!(media, sample_media());
+
+        assert_eq!(result.files["media/image-0.webp"].len(), 50);
+        assert_eq!(result.files["media/audio-0.mp3"].len(), 40);
+        assert_eq!(result.files["media/video-0.mov"].len(), 1);
+        assert_eq!(result.files["media/caption-0.vtt"].len(), 10);
+        assert!(!result.files.contains_key("media/image-0.jpg"));
+        assert_eq!(
+            result.renamed.get("media/image-0.jpg").map(String::as_str),
+            Some("media/image-0.webp")
+        );
+
+        let outcomes: Vec<_> = result
+            .report
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.outcome.clone()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("media/audio-0.mp3", TransformOutcome::Applied),
+                ("media/image-0.jpg", TransformOutcome::Applied),
+                ("media/video-0.mov", TransformOutcome::NoSavings),
+            ]
+        );
+        assert_eq!(result.report.original_total, 181);
+        assert_eq!(result.report.packaged_total, 91);
+        assert_eq!(result.report.bytes_saved, 90);
+    }
+
+    #[test]
+    fn test_failed_transform_keeps_original() {
+        let spec = MediaTransformSpec {
+            convert_images_to_webp: true,
+            ..Default::default()
+        };
+
+        let result = apply_media_transforms(
+            &sample_media(),
+            &spec,
+            &FakeTranscoder {
+                fail_on: "jpg",
+                grow_on: "",
+            },
+        );
+
+        assert_eq!(result.files["media/image-0.jpg"].len(), 100);
+        assert!(result.renamed.is_empty());
+        assert!(matches!(
+            result.report.files[0].outcome,
+            TransformOutcome::Failed { .. }
+        ));
+        assert_eq!(result.report.bytes_saved, 0);
+    }
+
+    #[test]
+    fn test_audio_fallbacks_are_packaged_beside_the_mp3() {
+        let mut media = sample_media();
+        media.insert("media/audio-1.mp3".to_string(), vec![5; 40]);
+        media.insert("media/audio-1.ogg".to_string(), vec![6; 30]);
+        let spec = MediaTransformSpec {
+            audio_fallback_formats: vec!["ogg".to_string(), "webm".to_string(), "flac".to_string()],
+            ..Default::default()
+        };
+
+        let result = apply_media_transforms(
+            &media,
+            &spec,
+            &FakeTranscoder {
+                fail_on: "",
+                grow_on: "",
+            },
+        );
+
+        assert_eq!(result.files["media/audio-0.mp3"].len(), 80);
+        assert_eq!(result.files["media/audio-0.ogg"].len(), 40);
+        assert_eq!(result.files["media/audio-0.webm"].len(), 40);
+        // An encoding the project already has is kept as is
+        assert_eq!(result.files["media/audio-1.ogg"].len(), 30);
+        assert_eq!(result.files["media/audio-1.webm"].len(), 20);
+        assert!(result.renamed.is_empty());
+
+        let outcomes: Vec<_> = result
+            .report
+            .files
+            .iter()
+            .map(|f| (f.output_path.as_str(), f.rule, matches!(f.outcome, TransformOutcome::Applied)))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("media/audio-0.ogg", TransformRule::AudioFallback, true),
+                ("media/audio-0.webm", TransformRule::AudioFallback, true),
+                ("media/audio-0.flac", TransformRule::AudioFallback, false),
+                ("media/audio-1.webm", TransformRule::AudioFallback, true),
+                ("media/audio-1.flac", TransformRule::AudioFallback, false),
+            ]
+        );
+        assert_eq!(result.report.original_total, 0);
+        assert_eq!(result.report.bytes_saved, 0);
+    }
+
+    #[test]
+    fn test_only_animated_gifs_are_packaged_as_video() {
+        let media = HashMap::from([
+            ("media/image-1.gif".to_string(), test_gif(8)),
+            ("media/image-2.gif".to_string(), test_gif(1)),
+        ]);
+        let spec = MediaTransformSpec {
+            convert_animated_gifs: Some("mp4".to_string()),
+            ..Default::default()
+        };
+
+        let result = apply_media_transforms(
+            &media,
+            &spec,
+            &FakeTranscoder {
+                fail_on: "",
+                grow_on: "",
+            },
+        );
+
+        assert!(result.files.contains_key("media/image-1.mp4"));
+        assert_eq!(result.files["media/image-2.gif"], test_gif(1));
+        assert_eq!(
+            result.renamed,
+            BTreeMap::from([("media/image-1.gif".to_string(), "media/image-1.mp4".to_string())])
+        );
+        assert_eq!(result.report.files.len(), 1);
+        assert_eq!(result.report.files[0].rule, TransformRule::AnimatedGifToVideo);
+    }
+
+    #[test]
+    fn test_renaming_sink_rewrites_references() {
+        let renamed = BTreeMap::from([(
+            "media/image-0.jpg".to_string(),
+            "media/image-0.webp".to_string(),
+        )]);
+        let mut collect = CollectSink::new();
+        {
+            let mut sink = RenamingSink::new(&mut collect, &renamed);
+            sink.add_file("pages/welcome.html", br#"<img src="media/image-0.jpg">"#)
+                .unwrap();
+            sink.add_file("imsmanifest.xml", br#"<file href="media/image-0.jpg"/>"#)
+                .unwrap();
+            sink.add_file(
+                "scripts/course-data.js",
+                br#"window.COURSE_DATA = {"poster":"media/image-0.jpg"};"#,
+            )
+            .unwrap();
+            sink.add_file("scripts/course-ui.js", b"img.src = 'media/image-0.jpg';")
+                .unwrap();
+            sink.add_file("media/caption-0.vtt", br#""media/image-0.jpg""#)
+                .unwrap();
+        }
+
+        let file = |path| String::from_utf8(collect.file(path).unwrap().to_vec()).unwrap();
+        assert_eq!(file("pages/welcome.html"), r#"<img src="media/image-0.webp">"#);
+        assert_eq!(file("imsmanifest.xml"), r#"<file href="media/image-0.webp"/>"#);
+        assert_eq!(
+            file("scripts/course-data.js"),
+            r#"window.COURSE_DATA = {"poster":"media/image-0.webp"};"#
+        );
+        assert_eq!(file("scripts/course-ui.js"), "img.src = 'media/image-0.webp';");
+        // Media files are packaged as they are
+        assert_eq!(file("media/caption-0.vtt"), r#""media/image-0.jpg""#);
+    }
+}
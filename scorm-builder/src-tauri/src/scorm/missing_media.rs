@@ -0,0 +1,299 @@
+//! Media the course points at but the package wouldn't contain.
+//!
+//! Pages reference narration, captions and images by path; when a file never
+//! made it into `media_files` the generated page links to nothing. The report
+//! names every such reference by page so authors can fix it before publishing,
+//! and with `media_placeholders` the package gets a stand-in file instead.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::generator_enhanced::{GenerateScormRequest, MediaItem};
+use super::html_generator_enhanced::HtmlGenerator;
+
+/// A reference to a file missing from the package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingMedia {
+    /// `welcome`, `objectives` or the topic ID, prefixed with the language of a variant
+    pub page_id: String,
+    pub media_id: String,
+    /// Path inside the package the page links to
+    pub path: String,
+    /// Whether a placeholder was packaged at `path`
+    pub substituted: bool,
+}
+
+/// 1x1 light grey PNG; browsers sniff image data, so it also stands in for JPEG, GIF or WebP
+const PLACEHOLDER_IMAGE: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x02, 0x00, 0x00, 0x00, 0x90, 0x77, 0x53,
+    0xde, 0x00, 0x00, 0x00, 0x0c, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0xb8, 0x70, 0xe1, 0x02,
+    0x00, 0x04, 0xe4, 0x02, 0x71, 0x3c, 0x94, 0x2d, 0xf7, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e,
+    0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+const PLACEHOLDER_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="320" height="180" viewBox="0 0 320 180"><rect width="320" height="180" fill="#d0d0d0"/><text x="160" y="95" font-family="sans-serif" font-size="16" text-anchor="middle" fill="#555">Image unavailable</text></svg>"##;
+
+const PLACEHOLDER_CAPTIONS: &str = "WEBVTT\n";
+
+/// One second of silence, so narration still plays and completes
+fn placeholder_audio() -> Vec<u8> {
+    const SAMPLE_RATE: u32 = 8000;
+    let mut wav = Vec::with_capacity(44 + SAMPLE_RATE as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + SAMPLE_RATE).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes()); // bytes per second
+    wav.extend_from_slice(&1u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.resize(44 + SAMPLE_RATE as usize, 0x80);
+    wav
+}
+
+/// Stand-in for the file at `path`, by extension; video has none
+fn placeholder_for(path: &str) -> Option<Vec<u8>> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" => Some(PLACEHOLDER_IMAGE.to_vec()),
+        "svg" => Some(PLACEHOLDER_SVG.as_bytes().to_vec()),
+        "vtt" => Some(PLACEHOLDER_CAPTIONS.as_bytes().to_vec()),
+        "mp3" | "wav" | "m4a" | "aac" | "ogg" => Some(placeholder_audio()),
+        _ => None,
+    }
+}
+
+pub(super) fn is_external(path: &str) -> bool {
+    path.starts_with("//")
+        || path.contains("://")
+        || path.starts_with("data:")
+        || path.starts_with("blob:")
+}
+
+/// Package path a page media item links to, the way the page templates build it
+fn media_item_path(item: &MediaItem) -> Option<String> {
+    if item.is_youtube.unwrap_or(false) || item.embed_url.is_some() || is_external(&item.url) {
+        return None;
+    }
+    Some(if item.url.starts_with("media/") {
+        item.url.clone()
+    } else {
+        format!("media/{}", item.url)
+    })
+}
+
+/// Every packaged-media path of `request`'s pages, with the page it's on
+fn referenced_media(
+    request: &GenerateScormRequest,
+    extension_map: Option<&HashMap<String, String>>,
+    prefix: &str,
+) -> Vec<(String, String)> {
+    let mut references = Vec::new();
+    let mut add = |page_id: &str, path: Option<String>| {
+        if let Some(path) = path.filter(|path| !is_external(path)) {
+            references.push((format!("{prefix}{page_id}"), path));
+        }
+    };
+    let ensure = |file: &Option<String>| file.as_deref().map(HtmlGenerator::ensure_media_path);
+
+    if let Some(welcome) = &request.welcome_page {
+        add("welcome", ensure(&welcome.audio_file));
+        add("welcome", ensure(&welcome.caption_file));
+        add("welcome", ensure(&welcome.image_url));
+        for item in welcome.media.iter().flatten() {
+            add("welcome", media_item_path(item));
+        }
+    }
+    if let Some(objectives) = &request.learning_objectives_page {
+        add("objectives", ensure(&objectives.audio_file));
+        add("objectives", ensure(&objectives.caption_file));
+        for item in objectives.media.iter().flatten() {
+            add("objectives", media_item_path(item));
+        }
+    }
+    for topic in &request.topics {
+        add(&topic.id, ensure(&topic.audio_file));
+        add(&topic.id, ensure(&topic.caption_file));
+        add(
+            &topic.id,
+            topic
+                .image_url
+                .as_deref()
+                .and_then(|url| HtmlGenerator::get_correct_media_url(url, extension_map)),
+        );
+        for item in topic.media.iter().flatten() {
+            add(&topic.id, media_item_path(item));
+        }
+        for question in topic.knowledge_check.iter().flat_map(|kc| &kc.questions) {
+            for media in question.feedback_media() {
+                add(&topic.id, HtmlGenerator::get_correct_media_url(&media.url, extension_map));
+            }
+        }
+    }
+    for question in request.assessment.iter().flat_map(|assessment| &assessment.questions) {
+        for media in question.feedback_media() {
+            add("assessment", HtmlGenerator::get_correct_media_url(&media.url, extension_map));
+        }
+    }
+    references
+}
+
+/// References to files missing from `media_files`, once per page and path.
+/// Language variants share the package's media folder.
+pub fn find_missing_media(
+    request: &GenerateScormRequest,
+    media_files: &HashMap<String, Vec<u8>>,
+    extension_map: Option<&HashMap<String, String>>,
+) -> Vec<MissingMedia> {
+    let mut references = referenced_media(request, extension_map, "");
+    for variant in request.language_variants.iter().flatten() {
+        let prefix = format!("{}/", variant.language);
+        references.extend(referenced_media(&variant.course, extension_map, &prefix));
+    }
+
+    let mut missing: Vec<MissingMedia> = Vec::new();
+    for (page_id, path) in references {
+        if media_files.contains_key(&path)
+            || missing
+                .iter()
+                .any(|m| m.page_id == page_id && m.path == path)
+        {
+            continue;
+        }
+        let media_id = Path::new(&path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default()
+            .to_string();
+        missing.push(MissingMedia {
+            page_id,
+            media_id,
+            path,
+            substituted: false,
+        });
+    }
+    missing
+}
+
+/// Add a placeholder to `media_files` for each missing file that has one
+pub fn substitute_placeholders(
+    media_files: &mut HashMap<String, Vec<u8>>,
+    missing: &mut [MissingMedia],
+) {
+    for entry in missing.iter_mut() {
+        if media_files.contains_key(&entry.path) {
+            entry.substituted = true;
+        } else if let Some(placeholder) = placeholder_for(&entry.path) {
+            media_files.insert(entry.path.clone(), placeholder);
+            entry.substituted = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::{Topic, WelcomePage};
+
+    fn request() -> GenerateScormRequest {
+        GenerateScormRequest {
+            welcome_page: Some(WelcomePage {
+                title: "Welcome".to_string(),
+                content: String::new(),
+                start_button_text: "Start".to_string(),
+                audio_file: Some("audio-0.mp3".to_string()),
+                caption_file: Some("caption-0.vtt".to_string()),
+                image_url: None,
+                media: None,
+            }),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                audio_file: Some("media/audio-2.mp3".to_string()),
+                media: Some(vec![
+                    MediaItem {
+                        id: "image-1".to_string(),
+                        media_type: "image".to_string(),
+                        url: "image-1.jpg".to_string(),
+                        title: "Chart".to_string(),
+                        embed_url: None,
+                        is_youtube: None,
+                        clip_start: None,
+                        clip_end: None,
+                        alt_text: None,
+                        attribution: None,
+                    },
+                    MediaItem {
+                        id: "video-1".to_string(),
+                        media_type: "video".to_string(),
+                        url: "https://www.youtube.com/watch?v=abc".to_string(),
+                        title: "Intro".to_string(),
+                        embed_url: None,
+                        is_youtube: Some(true),
+                        clip_start: None,
+                        clip_end: None,
+                        alt_text: None,
+                        attribution: None,
+                    },
+                ]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reports_missing_media_by_page() {
+        let media_files = HashMap::from([
+            ("media/audio-0.mp3".to_string(), vec![1]),
+            ("media/audio-2.mp3".to_string(), vec![1]),
+        ]);
+
+        let missing = find_missing_media(&request(), &media_files, None);
+        let found: Vec<_> = missing
+            .iter()
+            .map(|m| (m.page_id.as_str(), m.media_id.as_str(), m.path.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("welcome", "caption-0", "media/caption-0.vtt"),
+                ("topic-1", "image-1", "media/image-1.jpg"),
+            ]
+        );
+        assert!(missing.iter().all(|m| !m.substituted));
+    }
+
+    #[test]
+    fn test_placeholders_fill_in_missing_files() {
+        let mut media_files = HashMap::new();
+        let mut missing = find_missing_media(&request(), &media_files, None);
+        assert_eq!(missing.len(), 4);
+
+        substitute_placeholders(&mut media_files, &mut missing);
+        assert!(missing.iter().all(|m| m.substituted));
+        assert!(media_files["media/image-1.jpg"].starts_with(b"\x89PNG"));
+        assert_eq!(media_files["media/caption-0.vtt"], b"WEBVTT\n");
+        assert!(media_files["media/audio-0.mp3"].starts_with(b"RIFF"));
+
+        let mut video = vec![MissingMedia {
+            page_id: "topic-1".to_string(),
+            media_id: "video-2".to_string(),
+            path: "media/video-2.mp4".to_string(),
+            substituted: false,
+        }];
+        substitute_placeholders(&mut media_files, &mut video);
+        assert!(!video[0].substituted);
+        assert!(!media_files.contains_key("media/video-2.mp4"));
+    }
+}
@@ -0,0 +1,465 @@
+//! Plain-http resources in a course.
+//!
+//! An LMS served over https can't load http:// images or embeds; browsers
+//! block them as mixed content and the page shows a gap. Before packaging,
+//! every http URL a page loads is looked up and, following the course's
+//! `insecure_urls` policy, switched to https when that answers, downloaded
+//! into the package, or only reported. What happened to each URL is part of
+//! the generation summary.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use crate::external_media;
+
+use super::generator_enhanced::{GenerateScormRequest, MediaItem};
+
+/// Largest file downloaded into a package
+const MAX_DOWNLOAD_BYTES: usize = 50 * 1024 * 1024;
+
+/// What to do about http URLs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum InsecureUrlPolicy {
+    /// Only report them
+    #[default]
+    Report,
+    /// Use https instead where the server answers on it
+    Upgrade,
+    /// Package images and media files, upgrading embeds and anything that
+    /// fails to download
+    Download,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "action")]
+pub enum InsecureUrlAction {
+    /// Left as is; browsers on https portals will block it
+    Reported,
+    /// Rewritten to https, which answered
+    Upgraded { secure_url: String },
+    /// Packaged and linked at `path`
+    Downloaded { path: String },
+}
+
+/// An http URL a page loads, and what was done about it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InsecureUrl {
+    /// `welcome`, `objectives` or the topic ID, prefixed with the language of a variant
+    pub page_id: String,
+    /// Media item ID, or `image` / `content` for the page image and the page HTML
+    pub source: String,
+    pub url: String,
+    #[serde(flatten)]
+    pub action: InsecureUrlAction,
+    /// Why an upgrade or download the policy asked for didn't happen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Network access for upgrades and downloads. `HttpUrlFetcher` is the real
+/// implementation; tests substitute a fake.
+pub trait UrlFetcher: Send + Sync {
+    /// Whether `url` answers with a success status
+    fn is_reachable(&self, url: &str) -> bool;
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, String>;
+}
+
+/// Fetches through `external_media`, so package builds get the same address
+/// and redirect checks as media the user adds. Blocks on the async runtime;
+/// call it from `tokio::task::block_in_place`.
+pub struct HttpUrlFetcher;
+
+impl HttpUrlFetcher {
+    fn block_on<F: std::future::Future>(future: F) -> Result<F::Output, String> {
+        tokio::runtime::Handle::try_current()
+            .map(|runtime| runtime.block_on(future))
+            .map_err(|e| format!("No async runtime to fetch on: {e}"))
+    }
+}
+
+impl UrlFetcher for HttpUrlFetcher {
+    fn is_reachable(&self, url: &str) -> bool {
+        Self::block_on(external_media::is_reachable(url)).unwrap_or(false)
+    }
+
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+        let media = external_media::fetch_external_media(url, MAX_DOWNLOAD_BYTES);
+        Self::block_on(media)?
+            .map(|media| media.data)
+            .map_err(|e| e.message)
+    }
+}
+
+fn is_insecure(url: &str) -> bool {
+    url.trim_start()
+        .get(..7)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
+}
+
+/// Extension of the file `url` points at, if it has a plausible one
+fn url_extension(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let name = path.rsplit('/').next().unwrap_or_default();
+    let (_, ext) = name.rsplit_once('.')?;
+    (!ext.is_empty() && ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then(|| ext.to_ascii_lowercase())
+}
+
+/// Whether a URL in page HTML names a file worth packaging, rather than a page to embed
+fn is_media_file(url: &str) -> bool {
+    matches!(
+        url_extension(url).as_deref(),
+        Some(
+            "jpg"
+                | "jpeg"
+                | "png"
+                | "gif"
+                | "webp"
+                | "svg"
+                | "bmp"
+                | "mp3"
+                | "wav"
+                | "ogg"
+                | "m4a"
+                | "mp4"
+                | "webm"
+                | "vtt"
+        )
+    )
+}
+
+/// Package path for a downloaded copy of `url`, stable across builds
+fn download_path(url: &str) -> String {
+    let digest = Sha256::digest(url.as_bytes());
+    let hash: String = digest[..6].iter().map(|b| format!("{b:02x}")).collect();
+    let ext = url_extension(url).unwrap_or_else(|| "bin".to_string());
+    format!("media/remote-{hash}.{ext}")
+}
+
+/// Rewrite the values of `src` attributes in `html` for which `rewrite` gives a new one
+fn rewrite_src_attributes(html: &str, mut rewrite: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(pos) = rest.find("src=") {
+        let (before, after) = rest.split_at(pos + 4);
+        out.push_str(before);
+        rest = after;
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let Some(end) = after[1..].find(quote) else {
+            continue;
+        };
+        let value = &after[1..1 + end];
+        out.push(quote);
+        out.push_str(&rewrite(value).unwrap_or_else(|| value.to_string()));
+        out.push(quote);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+struct Resolver<'a> {
+    policy: InsecureUrlPolicy,
+    fetcher: Option<&'a dyn UrlFetcher>,
+    media_files: &'a mut HashMap<String, Vec<u8>>,
+    /// Decisions by URL, so a URL used on several pages is looked up once
+    decided: HashMap<String, (InsecureUrlAction, Option<String>)>,
+    report: Vec<InsecureUrl>,
+}
+
+impl Resolver<'_> {
+    /// Record what happens to `url` and return the URL the page should use
+    fn resolve(&mut self, page_id: &str, source: &str, url: &str, downloadable: bool) -> String {
+        let (action, error) = match self.decided.get(url) {
+            Some(decision) => decision.clone(),
+            None => {
+                let decision = self.decide(url, downloadable);
+                self.decided.insert(url.to_string(), decision.clone());
+                decision
+            }
+        };
+        let resolved = match &action {
+            InsecureUrlAction::Reported => url.to_string(),
+            InsecureUrlAction::Upgraded { secure_url } => secure_url.clone(),
+            InsecureUrlAction::Downloaded { path } => path.clone(),
+        };
+        self.report.push(InsecureUrl {
+            page_id: page_id.to_string(),
+            source: source.to_string(),
+            url: url.to_string(),
+            action,
+            error,
+        });
+        resolved
+    }
+
+    fn decide(&mut self, url: &str, downloadable: bool) -> (InsecureUrlAction, Option<String>) {
+        if self.policy == InsecureUrlPolicy::Report {
+            return (InsecureUrlAction::Reported, None);
+        }
+        let Some(fetcher) = self.fetcher else {
+            return (
+                InsecureUrlAction::Reported,
+                Some("Not checked: no network access".to_string()),
+            );
+        };
+
+        let mut errors = Vec::new();
+        if self.policy == InsecureUrlPolicy::Download && downloadable {
+            match fetcher.fetch(url) {
+                Ok(data) => {
+                    let path = download_path(url);
+                    self.media_files.insert(path.clone(), data);
+                    return (InsecureUrlAction::Downloaded { path }, None);
+                }
+                Err(error) => errors.push(format!("Download failed: {error}")),
+            }
+        }
+        let https = format!("https://{}", &url.trim_start()[7..]);
+        if fetcher.is_reachable(&https) {
+            return (InsecureUrlAction::Upgraded { secure_url: https }, None);
+        }
+        errors.push(format!("{https} is not reachable"));
+        (InsecureUrlAction::Reported, Some(errors.join("; ")))
+    }
+
+    fn secure_media_item(&mut self, page_id: &str, item: &mut MediaItem) {
+        let embedded = item.is_youtube.unwrap_or(false) || item.embed_url.is_some();
+        if is_insecure(&item.url) {
+            item.url = self.resolve(page_id, &item.id, &item.url, !embedded);
+        }
+        if let Some(embed_url) = item.embed_url.as_mut().filter(|url| is_insecure(url)) {
+            *embed_url = self.resolve(page_id, &item.id, embed_url, false);
+        }
+    }
+
+    fn secure_page(
+        &mut self,
+        page_id: &str,
+        content: Option<&mut String>,
+        image_url: &mut Option<String>,
+        media: &mut Option<Vec<MediaItem>>,
+    ) {
+        if let Some(image_url) = image_url.as_mut().filter(|url| is_insecure(url)) {
+            *image_url = self.resolve(page_id, "image", image_url, true);
+        }
+        for item in media.iter_mut().flatten() {
+            self.secure_media_item(page_id, item);
+        }
+        if let Some(content) = content {
+            *content = rewrite_src_attributes(content, |src| {
+                is_insecure(src).then(|| self.resolve(page_id, "content", src, is_media_file(src)))
+            });
+        }
+    }
+
+    fn secure_course(&mut self, request: &mut GenerateScormRequest, prefix: &str) {
+        if let Some(welcome) = &mut request.welcome_page {
+            self.secure_page(
+                &format!("{prefix}welcome"),
+                Some(&mut welcome.content),
+                &mut welcome.image_url,
+                &mut welcome.media,
+            );
+        }
+        if let Some(objectives) = &mut request.learning_objectives_page {
+            self.secure_page(
+                &format!("{prefix}objectives"),
+                None,
+                &mut objectives.image_url,
+                &mut objectives.media,
+            );
+        }
+        for topic in &mut request.topics {
+            self.secure_page(
+                &format!("{prefix}{}", topic.id),
+                Some(&mut topic.content),
+                &mut topic.image_url,
+                &mut topic.media,
+            );
+        }
+    }
+}
+
+/// Apply the course's `insecure_urls` policy to every http URL its pages and
+/// language variants load, adding downloads to `media_files`. Without a
+/// `fetcher` nothing is changed and every URL is reported.
+pub fn secure_insecure_urls(
+    request: &mut GenerateScormRequest,
+    media_files: &mut HashMap<String, Vec<u8>>,
+    fetcher: Option<&dyn UrlFetcher>,
+) -> Vec<InsecureUrl> {
+    let mut resolver = Resolver {
+        policy: request.insecure_urls.unwrap_or_default(),
+        fetcher,
+        media_files,
+        decided: HashMap::new(),
+        report: Vec::new(),
+    };
+    resolver.secure_course(request, "");
+    for variant in request.language_variants.iter_mut().flatten() {
+        let prefix = format!("{}/", variant.language);
+        resolver.secure_course(&mut variant.course, &prefix);
+    }
+    resolver.report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::Topic;
+
+    /// Serves every URL except those on `down.example`, and https only on `secure.example`
+    struct FakeFetcher;
+
+    impl UrlFetcher for FakeFetcher {
+        fn is_reachable(&self, url: &str) -> bool {
+            url.starts_with("https://secure.example/")
+        }
+
+        fn fetch(&self, url: &str) -> Result<Vec<u8>, String> {
+            if url.contains("down.example") {
+                Err("connection refused".to_string())
+            } else {
+                Ok(url.as_bytes().to_vec())
+            }
+        }
+    }
+
+    fn request(policy: InsecureUrlPolicy) -> GenerateScormRequest {
+        GenerateScormRequest {
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Topic 1".to_string(),
+                content: r#"<p><img src="http://down.example/chart.png"> <a href="http://docs.example/">docs</a></p>"#
+                    .to_string(),
+                image_url: Some("http://secure.example/hero.jpg".to_string()),
+                media: Some(vec![MediaItem {
+                    id: "video-1".to_string(),
+                    media_type: "video".to_string(),
+                    url: "http://secure.example/watch".to_string(),
+                    title: "Intro".to_string(),
+                    embed_url: Some("http://secure.example/embed/1".to_string()),
+                    is_youtube: None,
+                    clip_start: None,
+                    clip_end: None,
+                    alt_text: None,
+                    attribution: None,
+                }]),
+                ..Default::default()
+            }],
+            insecure_urls: Some(policy),
+            ..Default::default()
+        }
+    }
+
+    fn actions(report: &[InsecureUrl]) -> Vec<(&str, &str, &InsecureUrlAction)> {
+        report
+            .iter()
+            .map(|entry| (entry.source.as_str(), entry.url.as_str(), &entry.action))
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_http_urls_pages_load() {
+        let mut request = request(InsecureUrlPolicy::Report);
+        let mut media_files = HashMap::new();
+
+        let report = secure_insecure_urls(&mut request, &mut media_files, Some(&FakeFetcher));
+
+        assert_eq!(
+            actions(&report),
+            vec![
+                (
+                    "image",
+                    "http://secure.example/hero.jpg",
+                    &InsecureUrlAction::Reported
+                ),
+                (
+                    "video-1",
+                    "http://secure.example/watch",
+                    &InsecureUrlAction::Reported
+                ),
+                (
+                    "video-1",
+                    "http://secure.example/embed/1",
+                    &InsecureUrlAction::Reported
+                ),
+                (
+                    "content",
+                    "http://down.example/chart.png",
+                    &InsecureUrlAction::Reported
+                ),
+            ]
+        );
+        assert!(report
+            .iter()
+            .all(|entry| entry.page_id == "topic-1" && entry.error.is_none()));
+        assert!(media_files.is_empty());
+        assert_eq!(
+            request.topics[0].image_url.as_deref(),
+            Some("http://secure.example/hero.jpg")
+        );
+    }
+
+    #[test]
+    fn test_downloads_files_and_upgrades_the_rest() {
+        let mut request = request(InsecureUrlPolicy::Download);
+        let mut media_files = HashMap::new();
+
+        let report = secure_insecure_urls(&mut request, &mut media_files, Some(&FakeFetcher));
+
+        let hero = download_path("http://secure.example/hero.jpg");
+        assert!(hero.starts_with("media/remote-") && hero.ends_with(".jpg"));
+        assert_eq!(
+            actions(&report),
+            vec![
+                (
+                    "image",
+                    "http://secure.example/hero.jpg",
+                    &InsecureUrlAction::Downloaded { path: hero.clone() }
+                ),
+                (
+                    "video-1",
+                    "http://secure.example/watch",
+                    &InsecureUrlAction::Upgraded {
+                        secure_url: "https://secure.example/watch".to_string()
+                    }
+                ),
+                (
+                    "video-1",
+                    "http://secure.example/embed/1",
+                    &InsecureUrlAction::Upgraded {
+                        secure_url: "https://secure.example/embed/1".to_string()
+                    }
+                ),
+                (
+                    "content",
+                    "http://down.example/chart.png",
+                    &InsecureUrlAction::Reported
+                ),
+            ]
+        );
+        assert!(report[3]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("connection refused"));
+
+        let topic = &request.topics[0];
+        assert_eq!(topic.image_url.as_deref(), Some(hero.as_str()));
+        assert!(media_files.contains_key(&hero));
+        assert_eq!(
+            topic.media.as_ref().unwrap()[0].embed_url.as_deref(),
+            Some("https://secure.example/embed/1")
+        );
+        // Links aren't loaded by the page, so they're left alone
+        assert!(topic.content.contains(r#"href="http://docs.example/""#));
+    }
+}
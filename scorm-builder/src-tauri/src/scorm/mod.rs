@@ -1,12 +1,41 @@
+pub mod analytics;
+pub mod animated_gifs;
+pub mod api_discovery;
+pub mod audio_sources;
+pub mod code_highlight;
+pub mod content_lint;
+pub mod credits;
+pub mod feedback;
 pub mod generator;
 pub mod generator_enhanced;
 pub mod html_generator;
 pub mod html_generator_enhanced;
+pub mod keywords;
+pub mod lms_profile;
 pub mod manifest;
+pub mod math;
+pub mod media_transform;
+pub mod missing_media;
+pub mod mixed_content;
 pub mod navigation_generator;
+pub mod navigation_menu;
 pub mod output_validator;
 pub mod package;
+pub mod package_inspector;
+pub mod package_sink;
+pub mod package_version;
+pub mod page_effort;
+pub mod page_preview;
+pub mod plugins;
+pub mod question_bank;
+pub mod render_diagnostics;
+pub mod runtime_assets;
+pub mod search_index;
+pub mod source_snapshot;
 pub mod style_generator;
+pub mod suspend_data;
+pub mod template_dev;
+pub mod transcripts;
 
 // Re-export commonly used types - removed unused CourseMetadata export
 
@@ -25,6 +54,9 @@ mod core_settings_test;
 #[cfg(test)]
 mod html_template_settings;
 
+#[cfg(test)]
+mod runtime_minify;
+
 #[cfg(test)]
 mod tests {
     use super::*;
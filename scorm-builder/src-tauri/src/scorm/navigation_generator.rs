@@ -1,150 +1,163 @@
-use handlebars::Handlebars;
-use serde_json::json;
+//! Per-course data for the navigation runtime. `runtime/navigation.js` is
+//! static and shipped like the other runtime scripts; everything it needs to
+//! know about the course comes from `scripts/course-data.js`, which sets
+//! `window.COURSE_DATA` and is loaded just before it.
 
-use crate::scorm::generator_enhanced::GenerateScormRequest;
+use serde_json::{json, Value};
 
-pub struct NavigationGenerator<'a> {
-    handlebars: Handlebars<'a>,
-}
+use crate::scorm::generator_enhanced::{FeatureFlags, GenerateScormRequest, WelcomeStartOptions};
+use crate::scorm::navigation_menu::menu_pages;
+use crate::scorm::page_effort::{
+    course_effort, page_weights, MediaDurations, PageEffort, MIN_PAGE_SECONDS,
+};
+use crate::scorm::search_index::build_search_index;
+use crate::scorm::suspend_data::suspend_data_settings;
 
-impl<'a> NavigationGenerator<'a> {
-    pub fn new() -> Result<Self, String> {
-        let mut handlebars = Handlebars::new();
+/// Package path of the course data script
+pub const COURSE_DATA_PATH: &str = "scripts/course-data.js";
 
-        // Register helpers
-        handlebars.register_helper("eq", Box::new(eq_helper));
+/// Course data with progress weighted by reading time only, for when the
+/// packaged narration isn't at hand
+pub fn course_data(request: &GenerateScormRequest) -> Value {
+    let pages = course_effort(request, &MediaDurations::default());
+    course_data_with_effort(request, &pages)
+}
 
-        // Load navigation template
-        let navigation_template = include_str!("templates/navigation.js.hbs");
-        handlebars
-            .register_template_string("navigation", navigation_template)
-            .map_err(|e| format!("Failed to register navigation template: {e}"))?;
+/// Course data with progress weighted by each page's estimated effort
+pub fn course_data_with_effort(request: &GenerateScormRequest, pages: &[PageEffort]) -> Value {
+    let knowledge_check_pages: Vec<_> = request
+        .topics
+        .iter()
+        .filter(|topic| {
+            topic
+                .knowledge_check
+                .as_ref()
+                .is_some_and(|kc| kc.enabled && !kc.questions.is_empty())
+        })
+        .map(|topic| topic.id.as_str())
+        .collect();
 
-        Ok(Self { handlebars })
-    }
+    let welcome_start = request.welcome_start.as_ref();
+    let require_audio_completion = request.require_audio_completion.unwrap_or(false);
+    let audio_policy = request.audio_policy.clone().unwrap_or_default();
+    let commit_settings = request.commit_settings.clone().unwrap_or_default();
+    let course_search = request.course_search.unwrap_or(false);
+    let features = FeatureFlags::from_request(request);
+    let search_index = if course_search {
+        build_search_index(request)
+    } else {
+        Vec::new()
+    };
 
-    pub fn generate_navigation_js(&self, request: &GenerateScormRequest) -> Result<String, String> {
-        // Build topic data with knowledge check info
-        let topics_data: Vec<_> = request
-            .topics
-            .iter()
-            .map(|topic| {
-                let has_knowledge_check = topic
-                    .knowledge_check
-                    .as_ref()
-                    .map(|kc| kc.enabled && !kc.questions.is_empty())
-                    .unwrap_or(false);
-
-                json!({
-                    "id": topic.id,
-                    "title": topic.title,
-                    "has_knowledge_check": has_knowledge_check
-                })
-            })
-            .collect();
-
-        let data = json!({
-            "has_objectives": request.learning_objectives_page.is_some(),
-            "topics": topics_data,
-            "pass_mark": request.pass_mark,
-            "allow_retake": request.allow_retake,
-            "require_audio_completion": request.require_audio_completion.unwrap_or(false),
-            // New comprehensive course settings for template
-            "auto_advance": request.auto_advance.unwrap_or(false),
-            "allow_previous_review": request.allow_previous_review.unwrap_or(true),
-            "retake_delay": request.retake_delay.unwrap_or(0),
-            "completion_criteria": request.completion_criteria.as_ref().unwrap_or(&"view_and_pass".to_string()),
-            "show_progress": request.show_progress.unwrap_or(true),
-            "show_outline": request.show_outline.unwrap_or(true),
-            "confirm_exit": request.confirm_exit.unwrap_or(true),
-            "font_size": request.font_size.as_ref().unwrap_or(&"medium".to_string()),
-            "time_limit": request.time_limit.unwrap_or(0),
-            "session_timeout": request.session_timeout.unwrap_or(30),
-            "minimum_time_spent": request.minimum_time_spent.unwrap_or(0),
-            "keyboard_navigation": request.keyboard_navigation.unwrap_or(true),
-            "printable": request.printable.unwrap_or(false),
-            "navigation_mode": request.navigation_mode.as_str()
-        });
-
-        self.handlebars
-            .render("navigation", &data)
-            .map_err(|e| format!("Failed to render navigation template: {e}"))
-    }
+    json!({
+        "resume_prompt": welcome_start.is_some_and(WelcomeStartOptions::resume_prompt),
+        "intro_video_gate": request.welcome_page.is_some()
+            && welcome_start.is_some_and(|start| start.intro_video_id.is_some()),
+        "knowledge_check_pages": knowledge_check_pages,
+        "menu_pages": menu_pages(request),
+        "pass_mark": request.pass_mark,
+        "allow_retake": request.allow_retake,
+        "require_audio_completion": require_audio_completion,
+        "audio_seek_locked": audio_policy.seek_locked(require_audio_completion),
+        "audio_autoplay": audio_policy.autoplay(),
+        "remember_audio_position": audio_policy.remember_position(),
+        "audio_unlock_overlay": audio_policy.unlock_overlay(),
+        "auto_advance": request.auto_advance.unwrap_or(false),
+        "allow_previous_review": request.allow_previous_review.unwrap_or(true),
+        "retake_delay": request.retake_delay.unwrap_or(0),
+        "completion_criteria": request.completion_criteria.as_deref().unwrap_or("view_and_pass"),
+        "show_progress": features.progress_bar,
+        "show_outline": features.navigation_menu,
+        "confirm_exit": request.confirm_exit.unwrap_or(true),
+        "font_size": request.font_size.as_deref().unwrap_or("medium"),
+        "time_limit": request.time_limit.unwrap_or(0),
+        "session_timeout": request.session_timeout.unwrap_or(30),
+        "minimum_time_spent": request.minimum_time_spent.unwrap_or(0),
+        "keyboard_navigation": request.keyboard_navigation.unwrap_or(true),
+        "printable": request.printable.unwrap_or(false) || features.print_button,
+        "learner_notes": request.learner_notes.unwrap_or(false),
+        "runtime_analytics": request.runtime_analytics.unwrap_or(false),
+        "suspend_data": suspend_data_settings(request),
+        "lms": request.lms_profile.unwrap_or_default().template_data(),
+        "commit": {
+            "debounce_ms": commit_settings.debounce_ms(),
+            "heartbeat_ms": u64::from(commit_settings.heartbeat_seconds()) * 1000,
+        },
+        "search_index": search_index,
+        "navigation_mode": request.navigation_mode.as_str(),
+        "page_weights": page_weights(pages),
+        "min_page_seconds": MIN_PAGE_SECONDS
+    })
+}
 
-    pub fn validate_navigation_js(&self, js_content: &str) -> Result<(), Vec<String>> {
-        let mut errors = Vec::new();
-
-        // Check for required functions
-        let required_functions = [
-            "updateNavigationState",
-            "shouldBlockNavigation",
-            "navigateToPage",
-            "checkMultipleChoice",
-            "checkFillInBlank",
-            "initializeNavigation",
-        ];
-
-        for func in &required_functions {
-            if !js_content.contains(&format!("function {func}"))
-                && !js_content.contains(&format!("window.{func} ="))
-            {
-                errors.push(format!("Missing required function: {func}"));
-            }
-        }
+/// `scripts/course-data.js` for `request`. `</` and the JS line separators are
+/// escaped so page text in the search index and menu can't break the script.
+pub fn course_data_js(
+    request: &GenerateScormRequest,
+    pages: &[PageEffort],
+) -> Result<String, String> {
+    let json = serde_json::to_string(&course_data_with_effort(request, pages))
+        .map_err(|e| format!("Failed to serialize course data: {e}"))?;
+    let json = json
+        .replace("</", "<\\/")
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029");
+    Ok(format!("window.COURSE_DATA = {json};\n"))
+}
 
-        // Check for navigation blocking logic
-        if !js_content.contains("shouldBlockNavigation()") {
-            errors.push("Navigation blocking check not found in navigation flow".to_string());
-        }
+/// Check that the navigation runtime defines everything the pages call into
+pub fn validate_navigation_js(js_content: &str) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    // Check for required functions
+    let required_functions = [
+        "updateNavigationState",
+        "shouldBlockNavigation",
+        "navigateToPage",
+        "checkMultipleChoice",
+        "checkFillInBlank",
+        "initializeNavigation",
+    ];
 
-        // Check for updateNavigationState after content load
-        if !js_content.contains("initializePageAudio(pageId);")
-            || !js_content.contains("updateNavigationState();")
+    for func in &required_functions {
+        if !js_content.contains(&format!("function {func}"))
+            && !js_content.contains(&format!("window.{func} ="))
         {
-            errors
-                .push("Navigation state update not properly placed after content load".to_string());
+            errors.push(format!("Missing required function: {func}"));
         }
+    }
 
-        // Check for sidebar click logging
-        if !js_content.contains("[SCORM Navigation] Sidebar click:") {
-            errors.push("Sidebar navigation logging not found".to_string());
-        }
+    // Check for navigation blocking logic
+    if !js_content.contains("shouldBlockNavigation()") {
+        errors.push("Navigation blocking check not found in navigation flow".to_string());
+    }
 
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(errors)
-        }
+    // Check for updateNavigationState after content load
+    if !js_content.contains("initializePageAudio(pageId);")
+        || !js_content.contains("updateNavigationState();")
+    {
+        errors.push("Navigation state update not properly placed after content load".to_string());
     }
-}
 
-// Handlebars helper for equality comparison
-fn eq_helper(
-    h: &handlebars::Helper,
-    _: &Handlebars,
-    _: &handlebars::Context,
-    _: &mut handlebars::RenderContext,
-    out: &mut dyn handlebars::Output,
-) -> handlebars::HelperResult {
-    let param1 = h.param(0).map(|v| v.value());
-    let param2 = h.param(1).map(|v| v.value());
-
-    if param1 == param2 {
-        out.write("true")?;
+    // Check for sidebar click logging
+    if !js_content.contains("[SCORM Navigation] Sidebar click:") {
+        errors.push("Sidebar navigation logging not found".to_string());
     }
 
-    Ok(())
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scorm::generator_enhanced::{KnowledgeCheck, Question, Topic};
+    use crate::scorm::generator_enhanced::{CommitSettings, KnowledgeCheck, Question, Topic};
 
     #[test]
-    fn test_navigation_generation() {
-        let generator = NavigationGenerator::new().unwrap();
-
+    fn test_course_data() {
         let request = GenerateScormRequest {
             course_title: "Test Course".to_string(),
             topics: vec![
@@ -162,6 +175,9 @@ mod tests {
                             explanation: Some("Explanation".to_string()),
                             correct_feedback: None,
                             incorrect_feedback: None,
+                            correct_feedback_media: None,
+                            incorrect_feedback_media: None,
+                            bank_ref: None,
                         }],
                     }),
                     ..Default::default()
@@ -177,28 +193,86 @@ mod tests {
             ..Default::default()
         };
 
-        let js = generator.generate_navigation_js(&request).unwrap();
-
-        // Verify generated content
-        assert!(js.contains("PAGES_WITH_KNOWLEDGE_CHECKS"));
-        assert!(js.contains("'topic-1': true"));
-        assert!(!js.contains("'topic-2': true"));
+        let data = course_data(&request);
+        assert_eq!(data["knowledge_check_pages"], json!(["topic-1"]));
+        assert_eq!(
+            data["menu_pages"][1],
+            json!({ "id": "topic-1", "title": "Topic 1" })
+        );
+        assert_eq!(data["search_index"], json!([]));
+    }
 
-        // Validate the generated JS
-        generator.validate_navigation_js(&js).unwrap();
+    #[test]
+    fn test_navigation_runtime_is_complete() {
+        validate_navigation_js(include_str!("runtime/navigation.js")).unwrap();
+        // Nothing per course is left in the runtime itself
+        assert!(!include_str!("runtime/navigation.js").contains("{{"));
     }
 
     #[test]
     fn test_navigation_validation() {
-        let generator = NavigationGenerator::new().unwrap();
-
         // Test with invalid JS
         let invalid_js = "// Invalid navigation file";
-        let result = generator.validate_navigation_js(invalid_js);
+        let result = validate_navigation_js(invalid_js);
 
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert!(errors.len() > 0);
         assert!(errors.iter().any(|e| e.contains("updateNavigationState")));
     }
+
+    #[test]
+    fn test_commit_debounce_and_heartbeat() {
+        let data = course_data(&GenerateScormRequest::default());
+        assert_eq!(
+            data["commit"],
+            json!({ "debounce_ms": 1000, "heartbeat_ms": 120000 })
+        );
+        let navigation = include_str!("runtime/navigation.js");
+        assert!(navigation.contains("debounceMs: COURSE_DATA.commit.debounce_ms,"));
+        assert!(navigation.contains("window.addEventListener('pagehide'"));
+
+        let request = GenerateScormRequest {
+            commit_settings: Some(CommitSettings {
+                debounce_ms: Some(60_000),
+                heartbeat_seconds: Some(0),
+            }),
+            ..Default::default()
+        };
+        let data = course_data(&request);
+        assert_eq!(
+            data["commit"],
+            json!({ "debounce_ms": 10000, "heartbeat_ms": 0 })
+        );
+
+        // The API wrapper only closes sessions navigation.js can't
+        let scorm_api = include_str!("runtime/scorm-api.js");
+        assert!(scorm_api.contains("!window.SafeSCORM"));
+        assert!(!scorm_api.contains("'cmi.core.lesson_status', 'completed'"));
+    }
+
+    #[test]
+    fn test_course_data_cannot_close_a_script() {
+        let request = GenerateScormRequest {
+            course_search: Some(true),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "</script><b>\u{2028}".to_string(),
+                content: "&lt;/script&gt;".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let js = course_data_js(&request, &[]).unwrap();
+        assert!(js.starts_with("window.COURSE_DATA = {"));
+        assert!(!js.contains("</") && !js.contains('\u{2028}'));
+        let json = js
+            .trim_start_matches("window.COURSE_DATA = ")
+            .trim_end()
+            .trim_end_matches(';');
+        let parsed: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed["search_index"][0]["text"], "</script>");
+        assert_eq!(parsed["menu_pages"][1]["title"], "</script><b>\u{2028}");
+    }
 }
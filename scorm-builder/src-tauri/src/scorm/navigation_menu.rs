@@ -0,0 +1,239 @@
+//! Labels and order of the course menu.
+//!
+//! The welcome, objectives and assessment entries used to be named and placed
+//! by the templates. `NavigationMenu` renames them, leaves the objectives page
+//! out and moves the assessment in among the topics. [`menu_pages`] is the one
+//! page order the sidebar and `navigation.js` follow, so learners page through
+//! the course in the order the menu shows.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::credits::has_credits;
+use super::transcripts::has_transcript_page;
+use super::generator_enhanced::GenerateScormRequest;
+
+/// Longest label the sidebar lays out without wrapping badly
+pub const MAX_LABEL_CHARS: usize = 60;
+
+pub const DEFAULT_WELCOME_LABEL: &str = "Welcome";
+pub const DEFAULT_OBJECTIVES_LABEL: &str = "Learning Objectives";
+pub const DEFAULT_ASSESSMENT_LABEL: &str = "Assessment";
+
+/// How the fixed pages appear in the course menu. Unset fields keep the
+/// built-in labels and order, so existing projects build unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct NavigationMenu {
+    /// Menu label of the welcome page (default "Welcome")
+    #[ts(optional)]
+    pub welcome_label: Option<String>,
+    /// Menu label and heading of the objectives page (default "Learning Objectives")
+    #[ts(optional)]
+    pub objectives_label: Option<String>,
+    /// Menu label and heading of the assessment (default "Assessment")
+    #[ts(optional)]
+    pub assessment_label: Option<String>,
+    /// Leave the objectives page out of the package
+    #[serde(default)]
+    #[ts(as = "Option<bool>", optional)]
+    pub hide_objectives: bool,
+    /// Put the assessment straight after this topic rather than after the last
+    #[ts(optional)]
+    pub assessment_after_topic: Option<String>,
+    /// Title of the course's item in the LMS table of contents (default the course title)
+    #[ts(optional)]
+    pub manifest_item_title: Option<String>,
+}
+
+impl NavigationMenu {
+    pub fn welcome_label(&self) -> &str {
+        self.welcome_label.as_deref().unwrap_or(DEFAULT_WELCOME_LABEL)
+    }
+
+    pub fn objectives_label(&self) -> &str {
+        self.objectives_label.as_deref().unwrap_or(DEFAULT_OBJECTIVES_LABEL)
+    }
+
+    pub fn assessment_label(&self) -> &str {
+        self.assessment_label.as_deref().unwrap_or(DEFAULT_ASSESSMENT_LABEL)
+    }
+
+    /// Reject labels the menu can't show and an assessment placed after a
+    /// topic the course doesn't have
+    pub fn validate(&self, request: &GenerateScormRequest) -> Result<(), String> {
+        let labels = [
+            ("welcome", &self.welcome_label),
+            ("objectives", &self.objectives_label),
+            ("assessment", &self.assessment_label),
+            ("manifest item", &self.manifest_item_title),
+        ];
+        for (name, label) in labels {
+            let Some(label) = label else {
+                continue;
+            };
+            if label.trim().is_empty() {
+                return Err(format!("The {name} label is empty"));
+            }
+            if label.chars().count() > MAX_LABEL_CHARS {
+                return Err(format!(
+                    "The {name} label is longer than {MAX_LABEL_CHARS} characters"
+                ));
+            }
+            if label.chars().any(char::is_control) {
+                return Err(format!("The {name} label contains a line break or control character"));
+            }
+        }
+        if let Some(topic_id) = &self.assessment_after_topic {
+            if !request.topics.iter().any(|topic| &topic.id == topic_id) {
+                return Err(format!(
+                    "The assessment is placed after topic '{topic_id}', which isn't in the course"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validate the menu settings of the course and each language variant, and
+/// drop objectives pages the menu hides
+pub fn apply_navigation_menu(request: &mut GenerateScormRequest) -> Result<(), String> {
+    if let Some(menu) = &request.navigation_menu {
+        menu.validate(request)?;
+        if menu.hide_objectives {
+            request.learning_objectives_page = None;
+        }
+    }
+    for variant in request.language_variants.iter_mut().flatten() {
+        apply_navigation_menu(&mut variant.course)
+            .map_err(|e| format!("{} variant: {e}", variant.language))?;
+    }
+    Ok(())
+}
+
+/// One entry of the course menu
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MenuPage {
+    pub id: String,
+    pub title: String,
+}
+
+impl MenuPage {
+    fn new(id: &str, title: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+        }
+    }
+}
+
+/// Every page of the course in menu order, with its menu label
+pub fn menu_pages(request: &GenerateScormRequest) -> Vec<MenuPage> {
+    let menu = request.navigation_menu.clone().unwrap_or_default();
+    let mut assessment = Some(MenuPage::new("assessment", menu.assessment_label()));
+
+    let mut pages = vec![MenuPage::new("welcome", menu.welcome_label())];
+    if request.learning_objectives_page.is_some() {
+        pages.push(MenuPage::new("objectives", menu.objectives_label()));
+    }
+    if request.course_map.unwrap_or(false) {
+        pages.push(MenuPage::new("course-map", "Course Map"));
+    }
+    for topic in &request.topics {
+        pages.push(MenuPage::new(&topic.id, &topic.title));
+        if menu.assessment_after_topic.as_ref() == Some(&topic.id) {
+            pages.extend(assessment.take());
+        }
+    }
+    pages.extend(assessment);
+    if has_transcript_page(request) {
+        pages.push(MenuPage::new("transcript", "Transcript"));
+    }
+    if has_credits(request) {
+        pages.push(MenuPage::new("credits", "Credits"));
+    }
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::{ObjectivesPage, Topic};
+
+    fn request(menu: NavigationMenu) -> GenerateScormRequest {
+        let topic = |id: &str| Topic {
+            id: id.to_string(),
+            title: id.replace('-', " "),
+            ..Default::default()
+        };
+        GenerateScormRequest {
+            learning_objectives_page: Some(ObjectivesPage {
+                objectives: vec!["Spot the hazard".to_string()],
+                audio_file: None,
+                caption_file: None,
+                image_url: None,
+                media: None,
+            }),
+            topics: vec![topic("topic-1"), topic("topic-2")],
+            navigation_menu: Some(menu),
+            ..Default::default()
+        }
+    }
+
+    fn ids(pages: &[MenuPage]) -> Vec<&str> {
+        pages.iter().map(|page| page.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_menu_keeps_the_built_in_order() {
+        let pages = menu_pages(&request(NavigationMenu::default()));
+        assert_eq!(
+            ids(&pages),
+            vec!["welcome", "objectives", "topic-1", "topic-2", "assessment"]
+        );
+        assert_eq!(pages[1].title, "Learning Objectives");
+    }
+
+    #[test]
+    fn test_menu_renames_hides_and_moves_pages() {
+        let mut request = request(NavigationMenu {
+            welcome_label: Some("Start here".to_string()),
+            assessment_label: Some("Final quiz".to_string()),
+            hide_objectives: true,
+            assessment_after_topic: Some("topic-1".to_string()),
+            ..Default::default()
+        });
+        apply_navigation_menu(&mut request).unwrap();
+
+        let pages = menu_pages(&request);
+        assert!(request.learning_objectives_page.is_none());
+        assert_eq!(ids(&pages), vec!["welcome", "topic-1", "assessment", "topic-2"]);
+        assert_eq!(pages[0].title, "Start here");
+    }
+
+    #[test]
+    fn test_invalid_menu_is_rejected() {
+        let invalid = [
+            NavigationMenu {
+                objectives_label: Some("  ".to_string()),
+                ..Default::default()
+            },
+            NavigationMenu {
+                assessment_label: Some("Quiz\nTime".to_string()),
+                ..Default::default()
+            },
+            NavigationMenu {
+                welcome_label: Some("x".repeat(MAX_LABEL_CHARS + 1)),
+                ..Default::default()
+            },
+            NavigationMenu {
+                assessment_after_topic: Some("topic-9".to_string()),
+                ..Default::default()
+            },
+        ];
+        for menu in invalid {
+            assert!(apply_navigation_menu(&mut request(menu)).is_err());
+        }
+    }
+}
@@ -2,20 +2,25 @@ use std::collections::HashMap;
 use std::io::Read;
 use zip::ZipArchive;
 
+type ValidationRule = Box<dyn Fn(&str) -> Result<(), String>>;
+
 pub struct OutputValidator {
-    validation_rules: HashMap<String, Box<dyn Fn(&str) -> Result<(), String>>>,
+    validation_rules: HashMap<String, ValidationRule>,
+    accessibility_rules: HashMap<String, ValidationRule>,
 }
 
 impl OutputValidator {
     pub fn new() -> Self {
         let mut validator = Self {
             validation_rules: HashMap::new(),
+            accessibility_rules: HashMap::new(),
         };
 
         // Add validation rules
         validator.add_navigation_rules();
         validator.add_css_rules();
         validator.add_html_rules();
+        validator.add_accessibility_rules();
 
         validator
     }
@@ -132,6 +137,76 @@ impl OutputValidator {
                     return Err("Alert container not found".to_string());
                 }
 
+                // Strict LMS CSPs block inline scripts, so all behaviour lives in scripts/
+                if content.contains("<script>") {
+                    return Err("Inline script block found in index.html".to_string());
+                }
+
+                if !content.contains("src=\"scripts/course-ui.js\"") {
+                    return Err("Course UI script not referenced".to_string());
+                }
+
+                Ok(())
+            }),
+        );
+    }
+
+    fn add_accessibility_rules(&mut self) {
+        // Screen readers rely on these live regions for page change and feedback announcements
+        self.accessibility_rules.insert(
+            "index.html".to_string(),
+            Box::new(|content| {
+                if !content.contains("id=\"sr-announcer-polite\"") {
+                    return Err("Polite screen reader live region not found".to_string());
+                }
+
+                if !content.contains("id=\"sr-announcer-assertive\"") {
+                    return Err("Assertive screen reader live region not found".to_string());
+                }
+
+                if !content.contains("aria-live=\"polite\"")
+                    || !content.contains("aria-live=\"assertive\"")
+                {
+                    return Err("Live regions missing aria-live attributes".to_string());
+                }
+
+                Ok(())
+            }),
+        );
+
+        self.accessibility_rules.insert(
+            "scripts/navigation.js".to_string(),
+            Box::new(|content| {
+                if !content.contains("function announceToScreenReader(") {
+                    return Err("Screen reader announcer not found".to_string());
+                }
+
+                // Page changes must be announced once the new content is in the DOM
+                if let Some(then_block_start) = content.find(".then(html => {") {
+                    if !content[then_block_start..].contains("announcePageChange(pageId)") {
+                        return Err(
+                            "Page change announcement not found after content load".to_string()
+                        );
+                    }
+                } else {
+                    return Err("navigateToPage then block not found".to_string());
+                }
+
+                if !content.contains("'Correct answer'") {
+                    return Err("Knowledge check feedback announcements not found".to_string());
+                }
+
+                Ok(())
+            }),
+        );
+
+        self.accessibility_rules.insert(
+            "styles/main.css".to_string(),
+            Box::new(|content| {
+                if !content.contains(".sr-only") {
+                    return Err("Screen reader only (.sr-only) styles missing".to_string());
+                }
+
                 Ok(())
             }),
         );
@@ -167,6 +242,51 @@ impl OutputValidator {
             }
         }
 
+        // Check accessibility requirements
+        for (path, validator) in &self.accessibility_rules {
+            if let Ok(mut file) = archive.by_name(path) {
+                let mut content = String::new();
+                file.read_to_string(&mut content)
+                    .map_err(|e| format!("Failed to read {path}: {e}"))?;
+
+                match validator(&content) {
+                    Ok(()) => {
+                        report.add_success(path.clone(), "Accessibility checks passed".to_string());
+                    }
+                    Err(error) => {
+                        report.add_error(path.clone(), format!("Accessibility: {error}"));
+                    }
+                }
+            }
+            // Missing files are already reported by the structural rules above
+        }
+
+        // Every organization item must point at a resource whose files are in the package
+        let manifest = match archive.by_name("imsmanifest.xml") {
+            Ok(mut file) => {
+                let mut content = String::new();
+                file.read_to_string(&mut content)
+                    .map_err(|e| format!("Failed to read imsmanifest.xml: {e}"))?;
+                Some(content)
+            }
+            Err(_) => None,
+        };
+        if let Some(manifest) = manifest {
+            let package_files: Vec<String> = archive.file_names().map(str::to_string).collect();
+            let errors = check_manifest_resources(&manifest, |href| {
+                package_files.iter().any(|name| name == href)
+            });
+            if errors.is_empty() {
+                report.add_success(
+                    "imsmanifest.xml".to_string(),
+                    "Organization resources present".to_string(),
+                );
+            }
+            for error in errors {
+                report.add_error("imsmanifest.xml".to_string(), error);
+            }
+        }
+
         // Check for knowledge check HTML
         for i in 0..archive.len() {
             let mut file = archive.by_index(i).unwrap();
@@ -186,13 +306,13 @@ impl OutputValidator {
                         );
                     }
 
-                    // Verify submit button - checks for submitAllKnowledgeChecks which handles all question types
+                    // Verify submit button - routed to submitAllKnowledgeChecks which handles all question types
                     if content.contains("fill-blank-")
-                        && !content.contains("onclick=\"window.submitAllKnowledgeChecks")
+                        && !content.contains("data-action=\"submit-knowledge-checks\"")
                     {
                         report.add_error(
                             name,
-                            "Fill-in-blank submit button missing proper onclick handler"
+                            "Fill-in-blank submit button missing submit-knowledge-checks action"
                                 .to_string(),
                         );
                     }
@@ -204,6 +324,65 @@ impl OutputValidator {
     }
 }
 
+/// Check that each `<item identifierref>` names a `<resource>` and that the
+/// resource's `href` and `<file>` entries exist according to `has_file`
+pub fn check_manifest_resources(manifest: &str, has_file: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    // identifier -> every href of the resource (its own href first)
+    let mut resources: Vec<(String, Vec<String>)> = Vec::new();
+    let mut item_refs = Vec::new();
+
+    for tag in manifest.split('<').skip(1) {
+        let tag = tag.split('>').next().unwrap_or_default();
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        if name == "item" {
+            if let Some(resource_id) = xml_attribute(tag, "identifierref") {
+                let item_id = xml_attribute(tag, "identifier").unwrap_or_default();
+                item_refs.push((item_id, resource_id));
+            }
+        } else if name == "resource" {
+            let identifier = xml_attribute(tag, "identifier").unwrap_or_default();
+            resources.push((identifier, xml_attribute(tag, "href").into_iter().collect()));
+        } else if name == "file" {
+            if let (Some((_, hrefs)), Some(href)) =
+                (resources.last_mut(), xml_attribute(tag, "href"))
+            {
+                hrefs.push(href);
+            }
+        }
+    }
+
+    for (item_id, resource_id) in item_refs {
+        let Some((_, hrefs)) = resources.iter().find(|(id, _)| *id == resource_id) else {
+            errors.push(format!(
+                "Item '{item_id}' references missing resource '{resource_id}'"
+            ));
+            continue;
+        };
+
+        for href in hrefs {
+            if !has_file(href) {
+                errors.push(format!(
+                    "Resource '{resource_id}' file not found in package: {href}"
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let needle = format!("{name}=\"");
+    tag.match_indices(&needle)
+        .find(|(index, _)| tag[..*index].ends_with(char::is_whitespace))
+        .and_then(|(index, _)| {
+            let value = &tag[index + needle.len()..];
+            value.find('"').map(|end| value[..end].to_string())
+        })
+}
+
 pub struct ValidationReport {
     pub success: Vec<(String, String)>,
     pub errors: Vec<(String, String)>,
@@ -285,4 +464,84 @@ mod tests {
         let invalid_js = "// Missing required functions";
         assert!(rule(invalid_js).is_err());
     }
+
+    #[test]
+    fn test_accessibility_validation() {
+        let validator = OutputValidator::new();
+
+        let html_rule = validator.accessibility_rules.get("index.html").unwrap();
+        let valid_html = r#"
+        <div id="sr-announcer-polite" class="sr-only" role="status" aria-live="polite"></div>
+        <div id="sr-announcer-assertive" class="sr-only" role="alert" aria-live="assertive"></div>
+        "#;
+        assert!(html_rule(valid_html).is_ok());
+        assert!(html_rule("<div id=\"content-container\"></div>").is_err());
+
+        let js_rule = validator
+            .accessibility_rules
+            .get("scripts/navigation.js")
+            .unwrap();
+        let valid_js = r#"
+        function announceToScreenReader(message, priority) {}
+        fetch(`pages/${pageId}.html`)
+            .then(html => {
+                initializePageAudio(pageId);
+                updateNavigationState();
+                announcePageChange(pageId);
+            });
+        announceToScreenReader(isCorrect ? 'Correct answer' : 'Incorrect answer');
+        "#;
+        assert!(js_rule(valid_js).is_ok());
+
+        // Announcer defined but never used for page changes
+        let missing_page_announce = r#"
+        function announceToScreenReader(message, priority) {}
+        fetch(`pages/${pageId}.html`).then(html => { updateNavigationState(); });
+        "#;
+        assert!(js_rule(missing_page_announce).is_err());
+
+        let css_rule = validator
+            .accessibility_rules
+            .get("styles/main.css")
+            .unwrap();
+        assert!(css_rule(".sr-only { position: absolute; }").is_ok());
+        assert!(css_rule("body { height: 100vh; }").is_err());
+    }
+
+    #[test]
+    fn test_manifest_resource_check() {
+        let manifest = r#"
+        <organizations default="default_org">
+            <organization identifier="default_org">
+                <item identifier="item_1" identifierref="main"><title>Course</title></item>
+            </organization>
+            <organization identifier="org_fr">
+                <item identifier="item_fr"
+                      identifierref="main_fr"><title>Cours</title></item>
+            </organization>
+            <organization identifier="org_de">
+                <item identifier="item_de" identifierref="main_de"><title>Kurs</title></item>
+            </organization>
+        </organizations>
+        <resources>
+            <resource identifier="main" type="webcontent" href="index.html">
+                <file href="index.html"/>
+            </resource>
+            <resource identifier="main_fr" type="webcontent" href="fr/index.html">
+                <file href="fr/index.html"/>
+                <file href="fr/pages/topic-1.html"/>
+            </resource>
+        </resources>"#;
+
+        let errors = check_manifest_resources(manifest, |href| {
+            ["index.html", "fr/index.html"].contains(&href)
+        });
+        assert_eq!(
+            errors,
+            vec![
+                "Resource 'main_fr' file not found in package: fr/pages/topic-1.html".to_string(),
+                "Item 'item_de' references missing resource 'main_de'".to_string(),
+            ]
+        );
+    }
 }
@@ -0,0 +1,647 @@
+use super::package_sink::package_mime_type;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use zip::ZipArchive;
+
+/// Many LMSs reject uploads above this size
+const LMS_UPLOAD_LIMIT: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    /// The package will not work in most LMSs
+    Error,
+    /// The package works in some LMSs but not others
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageIssue {
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// Organization item that launches a SCO
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoInfo {
+    pub identifier: String,
+    pub title: String,
+    pub resource: String,
+    /// Launch file inside the package, including any xml:base
+    pub launch: Option<String>,
+    pub parameters: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageInspection {
+    pub scorm_version: Option<String>,
+    pub manifest_identifier: Option<String>,
+    pub title: Option<String>,
+    pub scos: Vec<ScoInfo>,
+    /// Resources that are not SCOs
+    pub asset_count: usize,
+    pub launch_files: Vec<String>,
+    pub file_count: usize,
+    /// Uncompressed size of all files
+    pub total_size: u64,
+    pub compressed_size: u64,
+    /// Uncompressed size per MIME type
+    pub size_by_type: BTreeMap<String, u64>,
+    pub issues: Vec<PackageIssue>,
+}
+
+impl PackageInspection {
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == IssueSeverity::Error)
+    }
+
+    fn error(&mut self, message: String) {
+        self.issues.push(PackageIssue {
+            severity: IssueSeverity::Error,
+            message,
+        });
+    }
+
+    fn warning(&mut self, message: String) {
+        self.issues.push(PackageIssue {
+            severity: IssueSeverity::Warning,
+            message,
+        });
+    }
+}
+
+#[derive(Debug, Default)]
+struct ManifestItem {
+    identifier: String,
+    identifierref: Option<String>,
+    title: String,
+    parameters: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct ManifestResource {
+    identifier: String,
+    href: Option<String>,
+    scorm_type: Option<String>,
+    files: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Manifest {
+    identifier: Option<String>,
+    schema_version: Option<String>,
+    namespaces: Vec<String>,
+    schema_locations: Vec<String>,
+    default_organization: Option<String>,
+    organizations: Vec<(String, String)>,
+    items: Vec<ManifestItem>,
+    resources: Vec<ManifestResource>,
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref().eq_ignore_ascii_case(name))
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.to_string())
+}
+
+/// Join an xml:base and an href the way an LMS resolves launch paths
+fn with_base(base: &str, href: &str) -> String {
+    if base.is_empty() || base.ends_with('/') {
+        format!("{base}{href}")
+    } else {
+        format!("{base}/{href}")
+    }
+}
+
+fn parse_manifest(xml: &str) -> Result<Manifest, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut manifest = Manifest::default();
+    // Open elements by local name, innermost last
+    let mut path: Vec<String> = Vec::new();
+    // Indices into manifest.items of the <item> elements currently open
+    let mut open_items: Vec<usize> = Vec::new();
+    let mut resources_base = String::new();
+    let mut resource_base = String::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid imsmanifest.xml: {e}"))?;
+        let (element, is_empty) = match &event {
+            Event::Start(element) => (Some(element), false),
+            Event::Empty(element) => (Some(element), true),
+            _ => (None, false),
+        };
+
+        if let Some(element) = element {
+            let name = String::from_utf8_lossy(element.local_name().as_ref()).to_lowercase();
+            match name.as_str() {
+                "manifest" if path.is_empty() => {
+                    manifest.identifier = attribute(element, b"identifier");
+                    for attr in element.attributes().flatten() {
+                        let key = attr.key.as_ref();
+                        let value = attr.unescape_value().unwrap_or_default().to_string();
+                        if key.starts_with(b"xmlns") {
+                            manifest.namespaces.push(value);
+                        } else if attr.key.local_name().as_ref() == b"schemaLocation" {
+                            manifest.schema_locations.extend(
+                                value
+                                    .split_whitespace()
+                                    .skip(1)
+                                    .step_by(2)
+                                    .map(str::to_string),
+                            );
+                        }
+                    }
+                }
+                "organizations" => {
+                    manifest.default_organization = attribute(element, b"default");
+                }
+                "organization" => {
+                    let identifier = attribute(element, b"identifier").unwrap_or_default();
+                    manifest.organizations.push((identifier, String::new()));
+                }
+                "item" => {
+                    manifest.items.push(ManifestItem {
+                        identifier: attribute(element, b"identifier").unwrap_or_default(),
+                        identifierref: attribute(element, b"identifierref"),
+                        parameters: attribute(element, b"parameters"),
+                        ..Default::default()
+                    });
+                    if !is_empty {
+                        open_items.push(manifest.items.len() - 1);
+                    }
+                }
+                "resources" => {
+                    resources_base = attribute(element, b"base").unwrap_or_default();
+                }
+                "resource" => {
+                    resource_base = with_base(
+                        &resources_base,
+                        &attribute(element, b"base").unwrap_or_default(),
+                    );
+                    manifest.resources.push(ManifestResource {
+                        identifier: attribute(element, b"identifier").unwrap_or_default(),
+                        href: attribute(element, b"href")
+                            .map(|href| with_base(&resource_base, &href)),
+                        scorm_type: attribute(element, b"scormtype"),
+                        files: Vec::new(),
+                    });
+                }
+                "file" if path.last().map(String::as_str) == Some("resource") => {
+                    if let (Some(resource), Some(href)) =
+                        (manifest.resources.last_mut(), attribute(element, b"href"))
+                    {
+                        resource.files.push(with_base(&resource_base, &href));
+                    }
+                }
+                _ => {}
+            }
+            if !is_empty {
+                path.push(name);
+            }
+            continue;
+        }
+
+        match event {
+            Event::Text(text) => {
+                let text = text.unescape().unwrap_or_default().trim().to_string();
+                let parent = path.iter().rev().nth(1).map(String::as_str);
+                match (path.last().map(String::as_str), parent) {
+                    (Some("schemaversion"), _) => manifest.schema_version = Some(text),
+                    (Some("title"), Some("item")) => {
+                        if let Some(&index) = open_items.last() {
+                            manifest.items[index].title = text;
+                        }
+                    }
+                    (Some("title"), Some("organization")) => {
+                        if let Some(organization) = manifest.organizations.last_mut() {
+                            organization.1 = text;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(_) => {
+                let closed = path.pop();
+                if closed.as_deref() == Some("item") {
+                    open_items.pop();
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Human readable SCORM version from the manifest metadata, falling back to
+/// the ADL namespaces it declares
+fn detect_scorm_version(manifest: &Manifest) -> Option<String> {
+    let by_schema_version = match manifest.schema_version.as_deref().map(str::trim) {
+        Some("1.2") => Some("SCORM 1.2"),
+        Some("1.1") => Some("SCORM 1.1"),
+        Some("CAM 1.3") | Some("2004 2nd Edition") => Some("SCORM 2004 2nd Edition"),
+        Some("2004 3rd Edition") => Some("SCORM 2004 3rd Edition"),
+        Some("2004 4th Edition") => Some("SCORM 2004 4th Edition"),
+        _ => None,
+    };
+    let by_namespace = || {
+        if manifest
+            .namespaces
+            .iter()
+            .any(|ns| ns.contains("adlcp_rootv1p2"))
+        {
+            Some("SCORM 1.2")
+        } else if manifest
+            .namespaces
+            .iter()
+            .any(|ns| ns.contains("adlcp_v1p3"))
+        {
+            Some("SCORM 2004")
+        } else {
+            None
+        }
+    };
+    by_schema_version.or_else(by_namespace).map(str::to_string)
+}
+
+/// Path inside the package an href points at, without query or fragment
+fn href_path(href: &str) -> String {
+    href.split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .replace("%20", " ")
+}
+
+fn check_entry_name(name: &str, inspection: &mut PackageInspection) {
+    if name.contains('\\') {
+        inspection.error(format!(
+            "'{name}' uses backslashes; the ZIP was probably created on Windows and most LMSs will not find the file"
+        ));
+    }
+    if name.starts_with('/') || name.split(['/', '\\']).any(|part| part == "..") {
+        inspection.error(format!("'{name}' points outside the package"));
+    }
+}
+
+fn check_href(
+    href: &str,
+    owner: &str,
+    files: &HashSet<String>,
+    lowercase_files: &HashSet<String>,
+    inspection: &mut PackageInspection,
+) {
+    let path = href_path(href);
+    if href.contains("://") || files.contains(&path) {
+        if path.contains(' ') || !path.is_ascii() {
+            inspection.warning(format!(
+                "'{path}' contains spaces or non-ASCII characters, which some LMSs cannot serve"
+            ));
+        }
+        return;
+    }
+    if lowercase_files.contains(&path.to_lowercase()) {
+        inspection.error(format!(
+            "{owner} refers to '{path}', which only exists with different capitalization; this fails on case-sensitive servers"
+        ));
+    } else {
+        inspection.error(format!(
+            "{owner} refers to '{path}', which is not in the package"
+        ));
+    }
+}
+
+/// Inspect any SCORM ZIP: manifest structure, SCOs and launch files, SCORM
+/// version, size by type and common LMS compatibility problems
+pub fn inspect_package(zip_data: &[u8]) -> Result<PackageInspection, String> {
+    let mut archive = ZipArchive::new(std::io::Cursor::new(zip_data))
+        .map_err(|e| format!("Failed to open ZIP archive: {e}"))?;
+
+    let mut inspection = PackageInspection {
+        compressed_size: zip_data.len() as u64,
+        ..Default::default()
+    };
+    let mut files = HashSet::new();
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read ZIP entry: {e}"))?;
+        let name = file.name().to_string();
+        check_entry_name(&name, &mut inspection);
+        if file.is_dir() {
+            continue;
+        }
+        let mut head = Vec::new();
+        (&mut file)
+            .take(16)
+            .read_to_end(&mut head)
+            .map_err(|e| format!("Failed to read {name}: {e}"))?;
+        *inspection
+            .size_by_type
+            .entry(package_mime_type(&name, &head).to_string())
+            .or_default() += file.size();
+        inspection.total_size += file.size();
+        inspection.file_count += 1;
+        files.insert(name);
+    }
+    let lowercase_files: HashSet<String> = files.iter().map(|f| f.to_lowercase()).collect();
+
+    if inspection.compressed_size > LMS_UPLOAD_LIMIT {
+        inspection.warning(format!(
+            "Package is {} MB; many LMSs limit uploads to 100 MB",
+            inspection.compressed_size / (1024 * 1024)
+        ));
+    }
+
+    if !files.contains("imsmanifest.xml") {
+        let misplaced = files
+            .iter()
+            .find(|name| name.to_lowercase().ends_with("imsmanifest.xml"));
+        inspection.error(match misplaced {
+            Some(name) => {
+                format!("imsmanifest.xml must be at the root of the ZIP, found '{name}' instead")
+            }
+            None => "Package has no imsmanifest.xml".to_string(),
+        });
+        return Ok(inspection);
+    }
+
+    let mut xml = String::new();
+    archive
+        .by_name("imsmanifest.xml")
+        .map_err(|e| format!("Failed to read imsmanifest.xml: {e}"))?
+        .read_to_string(&mut xml)
+        .map_err(|e| format!("Failed to read imsmanifest.xml: {e}"))?;
+    let manifest = match parse_manifest(&xml) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            inspection.error(e);
+            return Ok(inspection);
+        }
+    };
+
+    inspection.manifest_identifier = manifest.identifier.clone();
+    inspection.scorm_version = detect_scorm_version(&manifest);
+    if inspection.scorm_version.is_none() {
+        inspection.warning(
+            "No SCORM version found in the manifest metadata; LMSs may import it as a plain content package"
+                .to_string(),
+        );
+    }
+    if manifest.identifier.is_none() {
+        inspection.error("<manifest> has no identifier".to_string());
+    }
+
+    for schema in &manifest.schema_locations {
+        if !schema.contains("://") && !files.contains(schema) {
+            inspection.warning(format!(
+                "Schema '{schema}' is declared but not included; strict LMSs reject packages without it"
+            ));
+        }
+    }
+
+    match &manifest.default_organization {
+        _ if manifest.organizations.is_empty() => inspection
+            .error("Manifest has no organizations, so there is nothing to launch".to_string()),
+        Some(default) if !manifest.organizations.iter().any(|(id, _)| id == default) => {
+            inspection.error(format!("Default organization '{default}' does not exist"))
+        }
+        _ => {}
+    }
+    let default_organization = manifest
+        .default_organization
+        .as_ref()
+        .and_then(|default| manifest.organizations.iter().find(|(id, _)| id == default))
+        .or(manifest.organizations.first());
+    inspection.title = default_organization
+        .map(|(_, title)| title.clone())
+        .filter(|title| !title.is_empty());
+
+    let mut identifiers = HashSet::new();
+    let all_ids = manifest.items.iter().map(|item| &item.identifier).chain(
+        manifest
+            .resources
+            .iter()
+            .map(|resource| &resource.identifier),
+    );
+    for identifier in all_ids {
+        if !identifier.is_empty() && !identifiers.insert(identifier) {
+            inspection.error(format!("Identifier '{identifier}' is used more than once"));
+        }
+    }
+
+    for resource in &manifest.resources {
+        for file in &resource.files {
+            check_href(
+                file,
+                &format!("Resource '{}'", resource.identifier),
+                &files,
+                &lowercase_files,
+                &mut inspection,
+            );
+        }
+    }
+
+    for item in &manifest.items {
+        let Some(resource_id) = &item.identifierref else {
+            continue;
+        };
+        let Some(resource) = manifest
+            .resources
+            .iter()
+            .find(|resource| &resource.identifier == resource_id)
+        else {
+            inspection.error(format!(
+                "Item '{}' references missing resource '{resource_id}'",
+                item.identifier
+            ));
+            continue;
+        };
+
+        match resource.scorm_type.as_deref().map(str::to_lowercase).as_deref() {
+            Some("sco") => {}
+            Some(_) => continue,
+            None => inspection.warning(format!(
+                "Resource '{resource_id}' has no adlcp:scormtype; LMSs disagree on whether it is a SCO"
+            )),
+        }
+
+        match &resource.href {
+            Some(href) => {
+                check_href(
+                    href,
+                    &format!("Item '{}'", item.identifier),
+                    &files,
+                    &lowercase_files,
+                    &mut inspection,
+                );
+                let launch = href_path(href);
+                if !inspection.launch_files.contains(&launch) {
+                    inspection.launch_files.push(launch);
+                }
+            }
+            None => inspection.error(format!(
+                "SCO resource '{resource_id}' has no href to launch"
+            )),
+        }
+        inspection.scos.push(ScoInfo {
+            identifier: item.identifier.clone(),
+            title: item.title.clone(),
+            resource: resource_id.clone(),
+            launch: resource.href.clone(),
+            parameters: item.parameters.clone(),
+        });
+    }
+
+    if inspection.scos.is_empty() {
+        inspection.error("No organization item launches a SCO".to_string());
+    }
+    inspection.asset_count = manifest
+        .resources
+        .iter()
+        .filter(|resource| {
+            resource
+                .scorm_type
+                .as_deref()
+                .map(str::to_lowercase)
+                .as_deref()
+                != Some("sco")
+        })
+        .count();
+
+    Ok(inspection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    const SCORM_12_MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest identifier="course-1" version="1.0"
+    xmlns="http://www.imsproject.org/xsd/imscp_rootv1p1p2"
+    xmlns:adlcp="http://www.adlnet.org/xsd/adlcp_rootv1p2"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+    xsi:schemaLocation="http://www.imsproject.org/xsd/imscp_rootv1p1p2 imscp_rootv1p1p2.xsd">
+  <metadata><schema>ADL SCORM</schema><schemaversion>1.2</schemaversion></metadata>
+  <organizations default="org-1">
+    <organization identifier="org-1">
+      <title>Safety &amp; Health</title>
+      <item identifier="item-1" identifierref="res-1"><title>Course</title></item>
+    </organization>
+  </organizations>
+  <resources>
+    <resource identifier="res-1" type="webcontent" adlcp:scormtype="sco" href="index.html">
+      <file href="index.html"/>
+      <file href="media/audio-0.mp3"/>
+    </resource>
+  </resources>
+</manifest>"#;
+
+    #[test]
+    fn test_inspect_valid_scorm_12_package() {
+        let data = zip(&[
+            ("imsmanifest.xml", SCORM_12_MANIFEST),
+            ("imscp_rootv1p1p2.xsd", "<xs:schema/>"),
+            ("index.html", "<html></html>"),
+            ("media/audio-0.mp3", "ID3audio"),
+        ]);
+
+        let inspection = inspect_package(&data).unwrap();
+
+        assert!(inspection.issues.is_empty(), "{:?}", inspection.issues);
+        assert_eq!(inspection.scorm_version.as_deref(), Some("SCORM 1.2"));
+        assert_eq!(inspection.title.as_deref(), Some("Safety & Health"));
+        assert_eq!(inspection.scos.len(), 1);
+        assert_eq!(inspection.scos[0].title, "Course");
+        assert_eq!(inspection.launch_files, vec!["index.html"]);
+        assert_eq!(inspection.file_count, 4);
+        assert_eq!(inspection.size_by_type["audio/mpeg"], 8);
+        assert_eq!(inspection.size_by_type["text/html"], 13);
+    }
+
+    #[test]
+    fn test_inspect_flags_vendor_package_problems() {
+        let manifest = r#"<manifest identifier="vendor" xmlns:imscp="http://www.imsglobal.org/xsd/imscp_v1p1"
+    xmlns:adlcp="http://www.adlnet.org/xsd/adlcp_v1p3">
+  <imscp:organizations default="missing">
+    <imscp:organization identifier="org">
+      <imscp:item identifier="i1" identifierref="r1"><imscp:title>One</imscp:title></imscp:item>
+      <imscp:item identifier="i2" identifierref="r2"><imscp:title>Two</imscp:title></imscp:item>
+    </imscp:organization>
+  </imscp:organizations>
+  <imscp:resources xml:base="content/">
+    <imscp:resource identifier="r1" adlcp:scormType="sco" href="Launch.html?lesson=1"/>
+  </imscp:resources>
+</manifest>"#;
+        let data = zip(&[
+            ("imsmanifest.xml", manifest),
+            ("content/launch.html", "<html></html>"),
+            ("content\\extra.js", ""),
+        ]);
+
+        let inspection = inspect_package(&data).unwrap();
+        let messages: Vec<_> = inspection
+            .issues
+            .iter()
+            .map(|i| i.message.as_str())
+            .collect();
+
+        assert!(inspection.has_errors());
+        assert_eq!(inspection.scorm_version.as_deref(), Some("SCORM 2004"));
+        assert_eq!(
+            inspection.scos[0].launch.as_deref(),
+            Some("content/Launch.html?lesson=1")
+        );
+        assert!(
+            messages.iter().any(|m| m.contains("backslashes")),
+            "{messages:?}"
+        );
+        assert!(messages
+            .iter()
+            .any(|m| m.contains("different capitalization")));
+        assert!(messages
+            .iter()
+            .any(|m| m.contains("Default organization 'missing'")));
+        assert!(messages.iter().any(|m| m.contains("missing resource 'r2'")));
+    }
+
+    #[test]
+    fn test_inspect_manifest_in_subfolder() {
+        let data = zip(&[("course/imsmanifest.xml", SCORM_12_MANIFEST)]);
+
+        let inspection = inspect_package(&data).unwrap();
+
+        assert_eq!(inspection.issues.len(), 1);
+        assert!(inspection.issues[0]
+            .message
+            .contains("course/imsmanifest.xml"));
+        assert!(inspection.scorm_version.is_none());
+    }
+}
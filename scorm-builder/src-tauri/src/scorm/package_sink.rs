@@ -0,0 +1,407 @@
+use serde::Serialize;
+use std::io::{Cursor, Write};
+use std::time::{Duration, Instant};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// Destination for the files produced by `EnhancedScormGenerator`.
+///
+/// The generator writes every file through a sink, so the same pipeline can
+/// produce a ZIP package or an in-memory file tree for previews.
+pub trait PackageSink {
+    fn add_file(&mut self, path: &str, data: &[u8]) -> Result<(), String>;
+}
+
+/// MIME type of a package file, from its extension or, for media stored
+/// without a meaningful one (e.g. `.bin`), from its first bytes
+pub fn package_mime_type(path: &str, data: &[u8]) -> &'static str {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" => "text/javascript",
+        "json" => "application/json",
+        "xml" | "xsd" => "application/xml",
+        "vtt" => "text/vtt",
+        "txt" => "text/plain",
+        "svg" => "image/svg+xml",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "mp3" => "audio/mpeg",
+        "m4a" | "aac" => "audio/aac",
+        "ogg" | "oga" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "avi" => "video/x-msvideo",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "rar" => "application/vnd.rar",
+        "7z" => "application/x-7z-compressed",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => sniff_mime_type(data),
+    }
+}
+
+fn sniff_mime_type(data: &[u8]) -> &'static str {
+    match data {
+        [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+        [0x89, b'P', b'N', b'G', ..] => "image/png",
+        [b'G', b'I', b'F', b'8', ..] => "image/gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => "audio/wav",
+        [b'I', b'D', b'3', ..] | [0xFF, 0xFB | 0xF3 | 0xF2, ..] => "audio/mpeg",
+        [b'O', b'g', b'g', b'S', ..] => "audio/ogg",
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => "video/mp4",
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => "video/webm",
+        [b'%', b'P', b'D', b'F', ..] => "application/pdf",
+        [b'P', b'K', 0x03, 0x04, ..] => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Formats that are already compressed, where deflating again only costs CPU
+pub fn is_precompressed(mime: &str) -> bool {
+    match mime {
+        "image/svg+xml" | "audio/wav" => false,
+        "application/pdf"
+        | "application/zip"
+        | "application/vnd.rar"
+        | "application/x-7z-compressed"
+        | "font/woff"
+        | "font/woff2" => true,
+        _ => ["image/", "audio/", "video/"]
+            .iter()
+            .any(|prefix| mime.starts_with(prefix)),
+    }
+}
+
+/// How the files of a package were compressed, for the generation summary
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompressionStats {
+    /// Deflate level used for text assets; `None` is the zip default
+    pub level: Option<i32>,
+    pub stored_files: usize,
+    pub stored_bytes: u64,
+    pub deflated_files: usize,
+    pub deflated_bytes: u64,
+    pub deflate_time_ms: u64,
+    /// Time deflating the stored files would have taken at this build's deflate rate
+    pub estimated_time_saved_ms: u64,
+}
+
+/// Writes files into a ZIP archive held in memory. Already-compressed media is
+/// stored; everything else is deflated at the configured level.
+pub struct ZipSink {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+    level: Option<i32>,
+    stats: CompressionStats,
+    deflate_time: Duration,
+    /// Whether the entry being written is deflated. Its last block is only
+    /// flushed when the next entry starts, so that time is charged to it too.
+    deflating: bool,
+}
+
+impl ZipSink {
+    pub fn new() -> Self {
+        Self {
+            zip: ZipWriter::new(Cursor::new(Vec::new())),
+            level: None,
+            stats: CompressionStats::default(),
+            deflate_time: Duration::ZERO,
+            deflating: false,
+        }
+    }
+
+    /// Deflate text assets at `level` (0-9) instead of the default
+    pub fn with_compression_level(level: Option<i32>) -> Result<Self, String> {
+        if let Some(level) = level.filter(|level| !(0..=9).contains(level)) {
+            return Err(format!(
+                "Compression level must be between 0 and 9, got {level}"
+            ));
+        }
+        let mut sink = Self::new();
+        sink.level = level;
+        sink.stats.level = level;
+        Ok(sink)
+    }
+
+    /// Finish the archive and return its bytes
+    pub fn finish(self) -> Result<Vec<u8>, String> {
+        self.finish_with_stats().map(|(bytes, _)| bytes)
+    }
+
+    /// Finish the archive and return its bytes with the compression stats
+    pub fn finish_with_stats(mut self) -> Result<(Vec<u8>, CompressionStats), String> {
+        let started = Instant::now();
+        let cursor = self
+            .zip
+            .finish()
+            .map_err(|e| format!("Failed to finish ZIP: {e}"))?;
+        if self.deflating {
+            self.deflate_time += started.elapsed();
+        }
+
+        let mut stats = self.stats;
+        stats.deflate_time_ms = self.deflate_time.as_millis() as u64;
+        if stats.deflated_bytes > 0 {
+            let seconds_per_byte = self.deflate_time.as_secs_f64() / stats.deflated_bytes as f64;
+            stats.estimated_time_saved_ms =
+                (stats.stored_bytes as f64 * seconds_per_byte * 1000.0).round() as u64;
+        }
+        Ok((cursor.into_inner(), stats))
+    }
+
+    fn options(&self, deflate: bool) -> FileOptions {
+        if deflate {
+            FileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated)
+                .compression_level(self.level)
+        } else {
+            FileOptions::default().compression_method(zip::CompressionMethod::Stored)
+        }
+    }
+}
+
+impl Default for ZipSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PackageSink for ZipSink {
+    fn add_file(&mut self, path: &str, data: &[u8]) -> Result<(), String> {
+        let deflate = !is_precompressed(package_mime_type(path, data));
+
+        let started = Instant::now();
+        self.zip
+            .start_file(path, self.options(deflate))
+            .map_err(|e| format!("Failed to create {path}: {e}"))?;
+        if self.deflating {
+            self.deflate_time += started.elapsed();
+        }
+
+        let started = Instant::now();
+        self.zip
+            .write_all(data)
+            .map_err(|e| format!("Failed to write {path}: {e}"))?;
+
+        self.deflating = deflate;
+        if deflate {
+            self.deflate_time += started.elapsed();
+            self.stats.deflated_files += 1;
+            self.stats.deflated_bytes += data.len() as u64;
+        } else {
+            self.stats.stored_files += 1;
+            self.stats.stored_bytes += data.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps generated files in memory, in the order they were written
+#[derive(Default)]
+pub struct CollectSink {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl CollectSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Contents of a file written so far
+    pub fn file(&self, path: &str) -> Option<&[u8]> {
+        self.files
+            .iter()
+            .find(|(file_path, _)| file_path == path)
+            .map(|(_, data)| data.as_slice())
+    }
+
+    /// Build the preview returned to the frontend: every file with its size,
+    /// plus the rendered HTML of each page.
+    pub fn into_preview(self) -> ScormPreview {
+        let mut files = Vec::with_capacity(self.files.len());
+        let mut pages = Vec::new();
+        let mut total_size = 0u64;
+
+        for (path, data) in self.files {
+            let size = data.len() as u64;
+            total_size += size;
+
+            if path.ends_with(".html") {
+                pages.push(PreviewPage {
+                    // Language variants keep their folder: "fr/welcome"
+                    page_id: path
+                        .replacen("pages/", "", 1)
+                        .trim_end_matches(".html")
+                        .to_string(),
+                    path: path.clone(),
+                    html: String::from_utf8_lossy(&data).into_owned(),
+                });
+            }
+
+            files.push(PreviewFile { path, size });
+        }
+
+        ScormPreview {
+            files,
+            pages,
+            total_size,
+        }
+    }
+}
+
+impl PackageSink for CollectSink {
+    fn add_file(&mut self, path: &str, data: &[u8]) -> Result<(), String> {
+        self.files.push((path.to_string(), data.to_vec()));
+        Ok(())
+    }
+}
+
+/// Writes one language variant of the course under `{language}/`. Variant pages
+/// are loaded into `{language}/index.html`, so their media references are pointed
+/// at the package's shared `media/` folder.
+pub struct LanguageVariantSink<'a> {
+    inner: &'a mut dyn PackageSink,
+    language: &'a str,
+}
+
+impl<'a> LanguageVariantSink<'a> {
+    pub fn new(inner: &'a mut dyn PackageSink, language: &'a str) -> Self {
+        Self { inner, language }
+    }
+}
+
+impl PackageSink for LanguageVariantSink<'_> {
+    fn add_file(&mut self, path: &str, data: &[u8]) -> Result<(), String> {
+        let path = format!("{}/{path}", self.language);
+        if !path.ends_with(".html") {
+            return self.inner.add_file(&path, data);
+        }
+
+        let html = String::from_utf8_lossy(data).replace("\"media/", "\"../media/");
+        self.inner.add_file(&path, html.as_bytes())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewFile {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewPage {
+    /// "index" for the shell, otherwise the page ID (welcome, objectives, topic IDs, assessment)
+    pub page_id: String,
+    pub path: String,
+    pub html: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScormPreview {
+    pub files: Vec<PreviewFile>,
+    pub pages: Vec<PreviewPage>,
+    pub total_size: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_zip_sink_round_trip() {
+        let mut sink = ZipSink::new();
+        sink.add_file("index.html", b"<html></html>").unwrap();
+        sink.add_file("media/image-0.png", &[1, 2, 3]).unwrap();
+        let bytes = sink.finish().unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut index = String::new();
+        archive
+            .by_name("index.html")
+            .unwrap()
+            .read_to_string(&mut index)
+            .unwrap();
+        assert_eq!(index, "<html></html>");
+        assert_eq!(
+            archive.by_name("media/image-0.png").unwrap().compression(),
+            zip::CompressionMethod::Stored
+        );
+    }
+
+    #[test]
+    fn test_zip_sink_stores_precompressed_media_by_mime_type() {
+        let mut sink = ZipSink::with_compression_level(Some(9)).unwrap();
+        let css = "body { margin: 0; }\n".repeat(200);
+        sink.add_file("styles/main.css", css.as_bytes()).unwrap();
+        sink.add_file("media/image-1.svg", b"<svg></svg>").unwrap();
+        // No useful extension: sniffed from the JPEG signature
+        sink.add_file("media/image-0.bin", &[0xFF, 0xD8, 0xFF, 0xE0, 0, 0])
+            .unwrap();
+        sink.add_file("media/audio-0.mp3", b"ID3\x03audio").unwrap();
+        let (bytes, stats) = sink.finish_with_stats().unwrap();
+
+        assert_eq!(stats.level, Some(9));
+        assert_eq!((stats.deflated_files, stats.stored_files), (2, 2));
+        assert_eq!(stats.stored_bytes, 6 + 9);
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        use zip::CompressionMethod::{Deflated, Stored};
+        for (name, method) in [
+            ("styles/main.css", Deflated),
+            ("media/image-1.svg", Deflated),
+            ("media/image-0.bin", Stored),
+            ("media/audio-0.mp3", Stored),
+        ] {
+            assert_eq!(
+                archive.by_name(name).unwrap().compression(),
+                method,
+                "{name}"
+            );
+        }
+
+        assert!(ZipSink::with_compression_level(Some(12)).is_err());
+    }
+
+    #[test]
+    fn test_collect_sink_preview() {
+        let mut sink = CollectSink::new();
+        sink.add_file("index.html", b"<html></html>").unwrap();
+        sink.add_file("pages/topic-1.html", b"<p>Topic</p>").unwrap();
+        sink.add_file("styles/main.css", b"body{}").unwrap();
+
+        let preview = sink.into_preview();
+        assert_eq!(preview.files.len(), 3);
+        assert_eq!(preview.total_size, 31);
+        let page_ids: Vec<_> = preview.pages.iter().map(|p| p.page_id.as_str()).collect();
+        assert_eq!(page_ids, vec!["index", "topic-1"]);
+        assert_eq!(preview.pages[1].html, "<p>Topic</p>");
+    }
+
+    #[test]
+    fn test_language_variant_sink_prefixes_paths() {
+        let mut collect = CollectSink::new();
+        {
+            let mut sink = LanguageVariantSink::new(&mut collect, "fr");
+            sink.add_file("pages/welcome.html", br#"<img src="media/image-0.png">"#)
+                .unwrap();
+            sink.add_file("styles/main.css", b"body{}").unwrap();
+        }
+
+        let preview = collect.into_preview();
+        let paths: Vec<_> = preview.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["fr/pages/welcome.html", "fr/styles/main.css"]);
+        assert_eq!(preview.pages[0].html, r#"<img src="../media/image-0.png">"#);
+    }
+}
@@ -0,0 +1,206 @@
+//! Version stamp of a generated package, so a ZIP found on an LMS can be
+//! traced back to the project build it came from.
+//!
+//! Packages built by the app carry `version.json` at their root and the same
+//! course version and build id in the manifest. Their manifest identifier is
+//! derived from the project id, so LMSs that match uploads by identifier
+//! replace the previous version in place instead of adding a second course.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Read};
+
+use super::generator_enhanced::escape_xml;
+use super::runtime_assets::RUNTIME_VERSION;
+
+/// Where the stamp goes in the package
+pub const VERSION_FILE: &str = "version.json";
+
+/// Course version of packages whose request doesn't set one
+pub const DEFAULT_COURSE_VERSION: &str = "1.0";
+
+/// Longest accepted course version
+const MAX_COURSE_VERSION_LEN: usize = 32;
+
+/// The project build a package is generated for, filled in by the app rather
+/// than sent by the frontend
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageBuild {
+    pub build_id: String,
+    pub project_id: String,
+    /// Hash of the course and media the build ran with (see `build_history`)
+    pub content_hash: String,
+    pub built_at: DateTime<Utc>,
+}
+
+impl PackageBuild {
+    /// A build with an id made of its time and content, e.g. `20240101-093000-0123abcd`
+    pub fn new(project_id: &str, content_hash: &str, built_at: DateTime<Utc>) -> Self {
+        let short_hash: String = content_hash.chars().take(8).collect();
+        Self {
+            build_id: format!("{}-{short_hash}", built_at.format("%Y%m%d-%H%M%S")),
+            project_id: project_id.to_string(),
+            content_hash: content_hash.to_string(),
+            built_at,
+        }
+    }
+
+    /// Manifest identifier shared by every build of the project
+    pub fn manifest_identifier(&self) -> String {
+        format!("course-{}", self.project_id)
+    }
+}
+
+/// Contents of `version.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVersion {
+    pub course_version: String,
+    pub build_id: String,
+    pub project_id: String,
+    pub content_hash: String,
+    pub built_at: DateTime<Utc>,
+    /// Version of the app that generated the package
+    pub generator_version: String,
+}
+
+impl PackageVersion {
+    pub fn new(course_version: &str, build: &PackageBuild) -> Self {
+        Self {
+            course_version: course_version.to_string(),
+            build_id: build.build_id.clone(),
+            project_id: build.project_id.clone(),
+            content_hash: build.content_hash.clone(),
+            built_at: build.built_at,
+            generator_version: RUNTIME_VERSION.to_string(),
+        }
+    }
+}
+
+/// The course version of a request, checked to be usable in the manifest
+pub fn course_version(requested: Option<&str>) -> Result<&str, String> {
+    let version = requested.map(str::trim).unwrap_or(DEFAULT_COURSE_VERSION);
+    let valid = !version.is_empty()
+        && version.len() <= MAX_COURSE_VERSION_LEN
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !valid {
+        return Err(format!(
+            "Invalid course version '{version}': use up to {MAX_COURSE_VERSION_LEN} letters, digits, '.', '-' or '_'"
+        ));
+    }
+    Ok(version)
+}
+
+/// LOM metadata for the manifest: the build id and course version of stamped
+/// builds, and the course keywords. Empty when there is neither.
+pub fn manifest_lom(version: Option<&PackageVersion>, keywords: &[String], language: &str) -> String {
+    if version.is_none() && keywords.is_empty() {
+        return String::new();
+    }
+
+    let mut general = String::new();
+    if let Some(version) = version {
+        general.push_str(&format!(
+            "                <identifier>{}</identifier>\n",
+            version.build_id
+        ));
+    }
+    for keyword in keywords {
+        general.push_str(&format!(
+            "                <keyword>\n                    <langstring xml:lang=\"{}\">{}</langstring>\n                </keyword>\n",
+            escape_xml(language),
+            escape_xml(keyword)
+        ));
+    }
+
+    let lifecycle = version
+        .map(|version| {
+            format!(
+                r#"            <lifecycle>
+                <version>
+                    <langstring xml:lang="x-none">{}</langstring>
+                </version>
+            </lifecycle>
+"#,
+                version.course_version
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"        <lom xmlns="http://www.imsglobal.org/xsd/imsmd_rootv1p2p1">
+            <general>
+{general}            </general>
+{lifecycle}        </lom>
+"#
+    )
+}
+
+/// The stamp of a package ZIP, or None for packages built before stamping
+pub fn read_package_version(zip_data: &[u8]) -> Result<Option<PackageVersion>, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip_data))
+        .map_err(|e| format!("Failed to read package: {e}"))?;
+    let mut file = match archive.by_name(VERSION_FILE) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {VERSION_FILE}: {e}")),
+    };
+    let mut json = String::new();
+    file.read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read {VERSION_FILE}: {e}"))?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {VERSION_FILE}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build() -> PackageBuild {
+        let built_at = DateTime::parse_from_rfc3339("2024-01-01T09:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        PackageBuild::new("1700000000000", "0123abcd4567ef89", built_at)
+    }
+
+    #[test]
+    fn test_build_id_and_identifier() {
+        let build = build();
+        assert_eq!(build.build_id, "20240101-093000-0123abcd");
+        assert_eq!(build.manifest_identifier(), "course-1700000000000");
+
+        let version = PackageVersion::new("2.1", &build);
+        let json = serde_json::to_value(&version).unwrap();
+        assert_eq!(json["courseVersion"], "2.1");
+        assert_eq!(json["buildId"], "20240101-093000-0123abcd");
+        assert_eq!(json["generatorVersion"], RUNTIME_VERSION);
+    }
+
+    #[test]
+    fn test_course_version_validation() {
+        assert_eq!(course_version(None).unwrap(), DEFAULT_COURSE_VERSION);
+        assert_eq!(course_version(Some(" 2.0-rc1 ")).unwrap(), "2.0-rc1");
+        assert!(course_version(Some("")).is_err());
+        assert!(course_version(Some("1.0\"><x")).is_err());
+        assert!(course_version(Some(&"9".repeat(33))).is_err());
+    }
+
+    #[test]
+    fn test_lom_carries_keywords_with_or_without_a_build() {
+        assert_eq!(manifest_lom(None, &[], "en"), "");
+
+        let keywords = vec!["Gas & Air".to_string()];
+        let unstamped = manifest_lom(None, &keywords, "de");
+        assert!(unstamped.contains(r#"<langstring xml:lang="de">Gas &amp; Air</langstring>"#));
+        assert!(!unstamped.contains("<lifecycle>"));
+
+        let version = PackageVersion::new("2.1", &build());
+        let stamped = manifest_lom(Some(&version), &keywords, "en");
+        assert!(stamped.contains("<identifier>20240101-093000-0123abcd</identifier>"));
+        assert!(stamped.contains("<keyword>"));
+        assert!(stamped.contains(r#"<langstring xml:lang="x-none">2.1</langstring>"#));
+    }
+}
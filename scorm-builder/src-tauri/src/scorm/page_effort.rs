@@ -0,0 +1,341 @@
+//! How long each page of a course takes.
+//!
+//! Progress used to advance one step per page, so a page with a single
+//! sentence counted as much as one with ten minutes of narration. Each page's
+//! effort is estimated from its word count at an average reading speed, the
+//! length of its narration and video clips and the questions it asks; the
+//! progress indicator then shows the share of the course's effort up to the
+//! current page. Project statistics report the same estimates.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use super::generator_enhanced::{GenerateScormRequest, MediaItem, Question};
+use super::html_generator_enhanced::HtmlGenerator;
+
+/// Average silent reading speed
+pub const WORDS_PER_MINUTE: usize = 200;
+/// Time counted for answering one knowledge check or assessment question
+pub const SECONDS_PER_QUESTION: u32 = 30;
+/// Least effort a page counts for, so short pages still move progress along
+pub const MIN_PAGE_SECONDS: u32 = 15;
+
+/// Bitrates (kbps) by header index of MPEG-1 and MPEG-2/2.5 Layer III frames
+const MPEG1_LAYER3_KBPS: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MPEG2_LAYER3_KBPS: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// Words of text in `html`, ignoring tags
+pub fn count_words(html: &str) -> usize {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().count()
+}
+
+/// Length of an MP3 from its size and the bitrate of its first frame. Exact
+/// for constant bitrates, an estimate for variable ones.
+pub fn mp3_duration_seconds(data: &[u8]) -> Option<u32> {
+    let mut start = 0;
+    if data.len() >= 10 && data.starts_with(b"ID3") {
+        // ID3v2 tag size is stored as four 7-bit bytes
+        let size = data[6..10]
+            .iter()
+            .fold(0usize, |size, byte| (size << 7) | usize::from(byte & 0x7F));
+        start = 10 + size;
+    }
+    let offset = data.get(start..)?.windows(4).position(|header| {
+        header[0] == 0xFF
+            && header[1] & 0xE0 == 0xE0
+            // Layer III, and a bitrate that is neither free nor invalid
+            && (header[1] >> 1) & 0b11 == 0b01
+            && !matches!(header[2] >> 4, 0 | 15)
+    })?;
+    let frame = start + offset;
+    let header = &data[frame..frame + 4];
+    let index = usize::from(header[2] >> 4);
+    let kbps = match (header[1] >> 3) & 0b11 {
+        0b11 => MPEG1_LAYER3_KBPS[index],
+        0b10 | 0b00 => MPEG2_LAYER3_KBPS[index],
+        _ => return None,
+    };
+    let bits = (data.len() - frame) as u64 * 8;
+    Some((bits / (u64::from(kbps) * 1000)) as u32)
+}
+
+/// Seconds of a clip set on a media item, if it has both ends
+fn clip_seconds(start: Option<u64>, end: Option<u64>) -> u32 {
+    match (start, end) {
+        (Some(start), Some(end)) if end > start => (end - start) as u32,
+        _ => 0,
+    }
+}
+
+/// Estimated effort of one page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PageEffort {
+    pub page_id: String,
+    pub title: String,
+    pub words: usize,
+    pub questions: usize,
+    /// Narration and video clips
+    pub media_seconds: u32,
+    pub estimated_seconds: u32,
+}
+
+impl PageEffort {
+    pub fn new(
+        page_id: &str,
+        title: &str,
+        words: usize,
+        questions: usize,
+        media_seconds: u32,
+    ) -> Self {
+        let reading = (words * 60).div_ceil(WORDS_PER_MINUTE) as u32;
+        let answering = questions as u32 * SECONDS_PER_QUESTION;
+        // Narration is read along, so only the longer of the two counts
+        let estimated_seconds = (reading.max(media_seconds) + answering).max(MIN_PAGE_SECONDS);
+        Self {
+            page_id: page_id.to_string(),
+            title: title.to_string(),
+            words,
+            questions,
+            media_seconds,
+            estimated_seconds,
+        }
+    }
+}
+
+/// Length in seconds of the narration packaged with a course, by package path
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaDurations {
+    seconds: HashMap<String, u32>,
+}
+
+impl MediaDurations {
+    pub fn from_media_files(media_files: &HashMap<String, Vec<u8>>) -> Self {
+        let seconds = media_files
+            .iter()
+            .filter(|(path, _)| path.to_ascii_lowercase().ends_with(".mp3"))
+            .filter_map(|(path, data)| Some((path.clone(), mp3_duration_seconds(data)?)))
+            .collect();
+        Self { seconds }
+    }
+
+    fn narration(&self, audio_file: Option<&String>) -> u32 {
+        audio_file
+            .map(|file| HtmlGenerator::ensure_media_path(file))
+            .and_then(|path| self.seconds.get(&path).copied())
+            .unwrap_or(0)
+    }
+}
+
+fn media_seconds(
+    durations: &MediaDurations,
+    audio_file: Option<&String>,
+    media: Option<&Vec<MediaItem>>,
+) -> u32 {
+    let clips: u32 = media
+        .into_iter()
+        .flatten()
+        .map(|item| clip_seconds(item.clip_start.map(u64::from), item.clip_end.map(u64::from)))
+        .sum();
+    durations.narration(audio_file) + clips
+}
+
+fn question_words(questions: &[Question]) -> usize {
+    questions
+        .iter()
+        .map(|question| {
+            count_words(&question.text)
+                + question
+                    .options
+                    .iter()
+                    .flatten()
+                    .map(|option| count_words(option))
+                    .sum::<usize>()
+        })
+        .sum()
+}
+
+/// Effort of every page of the course, in course order
+pub fn course_effort(
+    request: &GenerateScormRequest,
+    durations: &MediaDurations,
+) -> Vec<PageEffort> {
+    let mut pages = Vec::new();
+    if let Some(welcome) = &request.welcome_page {
+        pages.push(PageEffort::new(
+            "welcome",
+            &welcome.title,
+            count_words(&welcome.content),
+            0,
+            media_seconds(
+                durations,
+                welcome.audio_file.as_ref(),
+                welcome.media.as_ref(),
+            ),
+        ));
+    }
+    if let Some(objectives) = &request.learning_objectives_page {
+        pages.push(PageEffort::new(
+            "objectives",
+            "Learning Objectives",
+            objectives
+                .objectives
+                .iter()
+                .map(|objective| count_words(objective))
+                .sum(),
+            0,
+            media_seconds(
+                durations,
+                objectives.audio_file.as_ref(),
+                objectives.media.as_ref(),
+            ),
+        ));
+    }
+    for topic in &request.topics {
+        let questions = topic
+            .knowledge_check
+            .as_ref()
+            .filter(|check| check.enabled)
+            .map(|check| check.questions.as_slice())
+            .unwrap_or_default();
+        pages.push(PageEffort::new(
+            &topic.id,
+            &topic.title,
+            count_words(&topic.content) + question_words(questions),
+            questions.len(),
+            media_seconds(durations, topic.audio_file.as_ref(), topic.media.as_ref()),
+        ));
+    }
+    if let Some(assessment) = &request.assessment {
+        pages.push(PageEffort::new(
+            "assessment",
+            "Assessment",
+            question_words(&assessment.questions),
+            assessment.questions.len(),
+            0,
+        ));
+    }
+    pages
+}
+
+/// Clip length of a media item in a project's `course_content` (`clipStart` / `clipEnd`)
+pub fn content_clip_seconds(extra: &Map<String, Value>) -> u32 {
+    let field = |name: &str| extra.get(name).and_then(Value::as_u64);
+    clip_seconds(field("clipStart"), field("clipEnd"))
+}
+
+/// `{ page id: estimated seconds }` for navigation.js
+pub fn page_weights(pages: &[PageEffort]) -> Value {
+    let weights: Map<String, Value> = pages
+        .iter()
+        .map(|page| (page.page_id.clone(), Value::from(page.estimated_seconds)))
+        .collect();
+    Value::Object(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::{KnowledgeCheck, Topic};
+
+    /// Constant 128 kbps MPEG-1 Layer III stream of `seconds` behind an ID3 tag
+    fn mp3(seconds: usize) -> Vec<u8> {
+        let mut data = b"ID3\x04\x00\x00\x00\x00\x00\x05hello".to_vec();
+        data.extend([0xFF, 0xFB, 0x90, 0x00]);
+        data.resize(15 + seconds * 16_000, 0);
+        data
+    }
+
+    #[test]
+    fn test_counts_words_and_mp3_length() {
+        assert_eq!(
+            count_words(
+                "<p>Wear <strong>gloves</strong> at all times.</p><ul><li>Always</li></ul>"
+            ),
+            6
+        );
+        assert_eq!(count_words("<br/>"), 0);
+        assert_eq!(mp3_duration_seconds(&mp3(90)), Some(90));
+        assert_eq!(mp3_duration_seconds(b"not audio"), None);
+    }
+
+    #[test]
+    fn test_course_effort_weighs_narration_clips_and_questions() {
+        let request = GenerateScormRequest {
+            topics: vec![
+                Topic {
+                    id: "short".to_string(),
+                    title: "Short".to_string(),
+                    content: "<p>Read this.</p>".to_string(),
+                    ..Default::default()
+                },
+                Topic {
+                    id: "narrated".to_string(),
+                    title: "Narrated".to_string(),
+                    content: "word ".repeat(400),
+                    audio_file: Some("audio-3.mp3".to_string()),
+                    media: Some(vec![MediaItem {
+                        id: "video-1".to_string(),
+                        media_type: "video".to_string(),
+                        url: "https://www.youtube.com/watch?v=abc".to_string(),
+                        title: "Demo".to_string(),
+                        embed_url: None,
+                        is_youtube: Some(true),
+                        clip_start: Some(30),
+                        clip_end: Some(90),
+                        alt_text: None,
+                        attribution: None,
+                    }]),
+                    knowledge_check: Some(KnowledgeCheck {
+                        enabled: true,
+                        questions: vec![Question {
+                            question_type: "true-false".to_string(),
+                            text: "Gloves are optional".to_string(),
+                            options: None,
+                            correct_answer: "false".to_string(),
+                            explanation: None,
+                            correct_feedback: None,
+                            incorrect_feedback: None,
+                            correct_feedback_media: None,
+                            incorrect_feedback_media: None,
+                            bank_ref: None,
+                        }],
+                    }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let durations = MediaDurations::from_media_files(&HashMap::from([(
+            "media/audio-3.mp3".to_string(),
+            mp3(180),
+        )]));
+
+        let pages = course_effort(&request, &durations);
+
+        assert_eq!(pages[0].estimated_seconds, MIN_PAGE_SECONDS);
+        assert_eq!(pages[1].words, 403);
+        assert_eq!(pages[1].media_seconds, 240);
+        // 403 words read in 121s, narration and clip 240s, one question 30s
+        assert_eq!(pages[1].estimated_seconds, 270);
+        assert_eq!(
+            page_weights(&pages),
+            serde_json::json!({ "short": 15, "narrated": 270 })
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Standalone HTML for previewing one page of the course in the editor.
+//!
+//! The page is rendered by the same templates as the package and placed in the
+//! rendered `index.html` shell where navigation.js would load it, with
+//! `styles/main.css` inlined. Media references point at the project's stored
+//! files instead of the package's `media/` folder, and the runtime scripts are
+//! left out: they would try to reach an LMS and fetch the page again.
+
+/// Asset-protocol URL of a project's media folder, as `MediaUrlService` builds it
+pub fn default_media_base_url(project_id: &str) -> String {
+    format!("asset://localhost/{project_id}/media/")
+}
+
+const STYLESHEET_LINK: &str = r#"<link rel="stylesheet" href="styles/main.css">"#;
+const CONTENT_CONTAINER: &str = r#"<div id="content-container" class="content-container">"#;
+
+/// Put `page_html` into the `index_html` shell, ready to show in a preview pane
+pub fn assemble_page_preview(
+    index_html: &str,
+    main_css: &str,
+    page_html: &str,
+    media_base_url: &str,
+) -> Result<String, String> {
+    let container = index_html
+        .find(CONTENT_CONTAINER)
+        .ok_or("index.html has no content container")?
+        + CONTENT_CONTAINER.len();
+
+    let mut html = String::with_capacity(index_html.len() + main_css.len() + page_html.len());
+    html.push_str(&index_html[..container]);
+    html.push_str(page_html);
+    html.push_str(&index_html[container..]);
+
+    let html = html.replacen(STYLESHEET_LINK, &format!("<style>\n{main_css}\n</style>"), 1);
+    Ok(rewrite_media_urls(&strip_scripts(&html), media_base_url))
+}
+
+/// Remove every `<script>` element
+fn strip_scripts(html: &str) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("<script") {
+        result.push_str(&rest[..start]);
+        match rest[start..].find("</script>") {
+            Some(end) => rest = &rest[start + end + "</script>".len()..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Point quoted `media/{id}.{ext}` references at `{base}{id}.bin`, the name
+/// media is stored under in the project
+fn rewrite_media_urls(html: &str, media_base_url: &str) -> String {
+    const PREFIX: &str = "\"media/";
+
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(PREFIX) {
+        let path_start = start + PREFIX.len();
+        let Some(path_len) = rest[path_start..].find('"') else {
+            break;
+        };
+        let file_name = &rest[path_start..path_start + path_len];
+        let media_id = file_name.split('.').next().unwrap_or(file_name);
+
+        result.push_str(&rest[..=start]);
+        result.push_str(media_base_url);
+        result.push_str(media_id);
+        result.push_str(".bin\"");
+        rest = &rest[path_start + path_len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = concat!(
+        r#"<html><head><link rel="stylesheet" href="styles/main.css">"#,
+        r#"<script src="scripts/scorm-api.js"></script></head>"#,
+        r#"<body><div id="content-container" class="content-container"></div>"#,
+        r#"<script src="scripts/navigation.js"></script></body></html>"#,
+    );
+
+    #[test]
+    fn test_page_goes_into_the_shell_with_inlined_styles() {
+        let html = assemble_page_preview(INDEX, "body { color: red; }", "<h2>Topic</h2>", "base/").unwrap();
+
+        assert!(html.contains(r#"class="content-container"><h2>Topic</h2></div>"#));
+        assert!(html.contains("<style>\nbody { color: red; }\n</style>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("styles/main.css"));
+    }
+
+    #[test]
+    fn test_media_references_point_at_stored_files() {
+        let page = r#"<img src="media/image-3.jpg" alt="x"><audio src="media/audio-2.mp3"></audio><a href="https://example.com/media/x.png">"#;
+        let html = assemble_page_preview(INDEX, "", page, &default_media_base_url("42")).unwrap();
+
+        assert!(html.contains(r#"src="asset://localhost/42/media/image-3.bin""#));
+        assert!(html.contains(r#"src="asset://localhost/42/media/audio-2.bin""#));
+        assert!(html.contains("https://example.com/media/x.png"));
+    }
+
+    #[test]
+    fn test_shell_without_content_container_is_an_error() {
+        assert!(assemble_page_preview("<html></html>", "", "", "base/").is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! Extension points of `EnhancedScormGenerator` for behaviour that doesn't
+//! belong in the generator itself, e.g. an organisation-specific footer.
+//!
+//! A [`GeneratorPlugin`] can transform the course before anything is rendered,
+//! filter every rendered HTML file and contribute extra files to the package.
+//! With the `generator-plugins` feature, plugins registered via
+//! [`register_plugin`] run for every package the app builds.
+
+use super::generator_enhanced::GenerateScormRequest;
+use std::sync::Arc;
+
+pub trait GeneratorPlugin: Send + Sync {
+    /// Shown in errors the plugin returns
+    fn name(&self) -> &str;
+
+    /// Change the course before it is rendered, including its language variants
+    fn transform_request(&self, _request: &mut GenerateScormRequest) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Rewrite a rendered HTML file. `path` is relative to the root of its
+    /// language, e.g. `index.html` or `pages/welcome.html`.
+    fn filter_html(&self, _path: &str, html: String) -> Result<String, String> {
+        Ok(html)
+    }
+
+    /// Files to add to the package as `(path, contents)`, relative to the
+    /// package root. They are not listed in the manifest.
+    fn extra_files(
+        &self,
+        _request: &GenerateScormRequest,
+    ) -> Result<Vec<(String, Vec<u8>)>, String> {
+        Ok(Vec::new())
+    }
+}
+
+/// The plugins one generator runs, in registration order
+#[derive(Clone, Default)]
+pub struct Plugins(Vec<Arc<dyn GeneratorPlugin>>);
+
+#[cfg(feature = "generator-plugins")]
+static REGISTERED: std::sync::RwLock<Vec<Arc<dyn GeneratorPlugin>>> =
+    std::sync::RwLock::new(Vec::new());
+
+/// Run `plugin` for every package generated from now on
+#[cfg(feature = "generator-plugins")]
+pub fn register_plugin(plugin: Arc<dyn GeneratorPlugin>) {
+    if let Ok(mut registered) = REGISTERED.write() {
+        registered.push(plugin);
+    }
+}
+
+impl Plugins {
+    /// The plugins registered with `register_plugin`; none without the
+    /// `generator-plugins` feature
+    pub fn registered() -> Self {
+        #[cfg(feature = "generator-plugins")]
+        if let Ok(registered) = REGISTERED.read() {
+            return Self(registered.clone());
+        }
+        Self::default()
+    }
+
+    #[cfg(test)]
+    pub fn push(&mut self, plugin: Arc<dyn GeneratorPlugin>) {
+        self.0.push(plugin);
+    }
+
+    pub fn transform_request(
+        &self,
+        mut request: GenerateScormRequest,
+    ) -> Result<GenerateScormRequest, String> {
+        for plugin in &self.0 {
+            plugin.transform_request(&mut request).map_err(|e| {
+                format!(
+                    "Plugin {} failed to transform the course: {e}",
+                    plugin.name()
+                )
+            })?;
+        }
+        Ok(request)
+    }
+
+    pub fn filter_html(&self, path: &str, html: String) -> Result<String, String> {
+        self.0.iter().try_fold(html, |html, plugin| {
+            plugin
+                .filter_html(path, html)
+                .map_err(|e| format!("Plugin {} failed to filter {path}: {e}", plugin.name()))
+        })
+    }
+
+    pub fn extra_files(
+        &self,
+        request: &GenerateScormRequest,
+    ) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let mut files = Vec::new();
+        for plugin in &self.0 {
+            let contributed = plugin
+                .extra_files(request)
+                .map_err(|e| format!("Plugin {} failed to add files: {e}", plugin.name()))?;
+            for (path, contents) in contributed {
+                if path.starts_with('/') || path.split(['/', '\\']).any(|part| part == "..") {
+                    return Err(format!(
+                        "Plugin {} added a file outside the package: {path}",
+                        plugin.name()
+                    ));
+                }
+                files.push((path, contents));
+            }
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Analytics;
+
+    impl GeneratorPlugin for Analytics {
+        fn name(&self) -> &str {
+            "analytics"
+        }
+
+        fn transform_request(&self, request: &mut GenerateScormRequest) -> Result<(), String> {
+            request.course_title.push_str(" (tracked)");
+            Ok(())
+        }
+
+        fn filter_html(&self, path: &str, html: String) -> Result<String, String> {
+            if path == "index.html" {
+                Ok(html.replace(
+                    "</head>",
+                    "<script src=\"scripts/analytics.js\"></script></head>",
+                ))
+            } else {
+                Ok(html)
+            }
+        }
+
+        fn extra_files(
+            &self,
+            _request: &GenerateScormRequest,
+        ) -> Result<Vec<(String, Vec<u8>)>, String> {
+            Ok(vec![(
+                "scripts/analytics.js".to_string(),
+                b"track();".to_vec(),
+            )])
+        }
+    }
+
+    struct Escaping;
+
+    impl GeneratorPlugin for Escaping {
+        fn name(&self) -> &str {
+            "escaping"
+        }
+
+        fn extra_files(
+            &self,
+            _request: &GenerateScormRequest,
+        ) -> Result<Vec<(String, Vec<u8>)>, String> {
+            Ok(vec![("../outside.txt".to_string(), Vec::new())])
+        }
+    }
+
+    #[test]
+    fn test_hooks_run_every_plugin() {
+        let mut plugins = Plugins::default();
+        plugins.push(Arc::new(Analytics));
+
+        let request = GenerateScormRequest {
+            course_title: "Safety".to_string(),
+            ..Default::default()
+        };
+        let request = plugins.transform_request(request).unwrap();
+        assert_eq!(request.course_title, "Safety (tracked)");
+
+        let html = plugins
+            .filter_html("index.html", "<head></head>".to_string())
+            .unwrap();
+        assert!(html.contains("analytics.js"));
+        let page = plugins
+            .filter_html("pages/welcome.html", "<head></head>".to_string())
+            .unwrap();
+        assert_eq!(page, "<head></head>");
+
+        let files = plugins.extra_files(&request).unwrap();
+        assert_eq!(files[0].0, "scripts/analytics.js");
+    }
+
+    #[test]
+    fn test_extra_files_stay_inside_the_package() {
+        let mut plugins = Plugins::default();
+        plugins.push(Arc::new(Escaping));
+        let error = plugins
+            .extra_files(&GenerateScormRequest::default())
+            .unwrap_err();
+        assert!(error.contains("escaping"));
+    }
+}
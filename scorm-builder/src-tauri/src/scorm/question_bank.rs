@@ -0,0 +1,178 @@
+//! Questions shared between knowledge checks and the assessment.
+//!
+//! A project keeps reusable questions in its `question_bank`, and a knowledge
+//! check or assessment question with a `bank_ref` stands for the bank question
+//! with that id. References are resolved before anything renders, so every
+//! page gets the bank's current wording instead of a copy that drifted. A
+//! reference to a question that isn't in the bank fails the build, naming
+//! every page that has one.
+
+use super::generator_enhanced::{GenerateScormRequest, Question};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+/// A question in the project's bank
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BankQuestion {
+    pub id: String,
+    #[serde(flatten)]
+    pub question: Question,
+}
+
+/// A question referring to an id the bank doesn't have
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DanglingReference {
+    /// Topic ID or `assessment`, prefixed with the language of a variant
+    page_id: String,
+    /// Position of the question on its page
+    question_index: usize,
+    bank_ref: String,
+}
+
+/// Questions of every page, with the page's ID
+fn questions_mut(request: &mut GenerateScormRequest) -> Vec<(String, &mut Vec<Question>)> {
+    let mut pages: Vec<(String, &mut Vec<Question>)> = request
+        .topics
+        .iter_mut()
+        .filter_map(|topic| {
+            let id = topic.id.clone();
+            topic.knowledge_check.as_mut().map(|check| (id, &mut check.questions))
+        })
+        .collect();
+    if let Some(assessment) = request.assessment.as_mut() {
+        pages.push(("assessment".to_string(), &mut assessment.questions));
+    }
+    pages
+}
+
+/// Replace referencing questions in `request` with their bank question,
+/// returning the references that didn't resolve
+fn resolve_pages(
+    request: &mut GenerateScormRequest,
+    bank: &HashMap<&str, &Question>,
+    prefix: &str,
+) -> Vec<DanglingReference> {
+    let mut dangling = Vec::new();
+    for (page_id, questions) in questions_mut(request) {
+        for (index, question) in questions.iter_mut().enumerate() {
+            let Some(bank_ref) = question.bank_ref.take() else {
+                continue;
+            };
+            match bank.get(bank_ref.as_str()) {
+                Some(resolved) => {
+                    *question = Question {
+                        bank_ref: None,
+                        ..(*resolved).clone()
+                    }
+                }
+                None => dangling.push(DanglingReference {
+                    page_id: format!("{prefix}{page_id}"),
+                    question_index: index,
+                    bank_ref,
+                }),
+            }
+        }
+    }
+    dangling
+}
+
+/// Resolve the bank references of `request` and its language variants. A
+/// variant looks questions up in its own bank first, then in the course's.
+pub fn resolve_question_bank(request: &mut GenerateScormRequest) -> Result<(), String> {
+    let course_bank = request.question_bank.take().unwrap_or_default();
+    let bank: HashMap<&str, &Question> = course_bank
+        .iter()
+        .map(|entry| (entry.id.as_str(), &entry.question))
+        .collect();
+    let mut dangling = resolve_pages(request, &bank, "");
+    for variant in request.language_variants.iter_mut().flatten() {
+        let variant_bank = variant.course.question_bank.take().unwrap_or_default();
+        let mut bank = bank.clone();
+        bank.extend(variant_bank.iter().map(|entry| (entry.id.as_str(), &entry.question)));
+        let prefix = format!("{}/", variant.language);
+        dangling.extend(resolve_pages(&mut variant.course, &bank, &prefix));
+    }
+    if dangling.is_empty() {
+        return Ok(());
+    }
+    dangling.sort_by(|a, b| a.page_id.cmp(&b.page_id));
+    let references = dangling
+        .iter()
+        .map(|entry| {
+            format!(
+                "question {} on {} refers to '{}'",
+                entry.question_index + 1,
+                entry.page_id,
+                entry.bank_ref
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(format!(
+        "Failed to generate package: {} question(s) refer to questions missing from the question bank: {references}",
+        dangling.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::{Assessment, KnowledgeCheck, Topic};
+
+    fn question(text: &str, bank_ref: Option<&str>) -> Question {
+        serde_json::from_value(serde_json::json!({
+            "type": "true-false",
+            "text": text,
+            "correct_answer": "true",
+            "bank_ref": bank_ref
+        }))
+        .unwrap()
+    }
+
+    fn request(bank_ref: &str) -> GenerateScormRequest {
+        GenerateScormRequest {
+            topics: vec![Topic {
+                id: "topic-0".to_string(),
+                knowledge_check: Some(KnowledgeCheck {
+                    enabled: true,
+                    questions: vec![question("Stale copy", Some("ladders")), question("Local", None)],
+                }),
+                ..Default::default()
+            }],
+            assessment: Some(Assessment {
+                questions: vec![question("", Some(bank_ref))],
+            }),
+            question_bank: Some(vec![BankQuestion {
+                id: "ladders".to_string(),
+                question: question("Ladders need three points of contact", None),
+            }]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_references_resolve_to_the_bank_question() {
+        let mut request = request("ladders");
+
+        resolve_question_bank(&mut request).unwrap();
+
+        let topic_questions = &request.topics[0].knowledge_check.as_ref().unwrap().questions;
+        assert_eq!(topic_questions[0].text, "Ladders need three points of contact");
+        assert_eq!(topic_questions[1].text, "Local");
+        let assessment = &request.assessment.as_ref().unwrap().questions[0];
+        assert_eq!(assessment.text, "Ladders need three points of contact");
+        assert_eq!(assessment.bank_ref, None);
+    }
+
+    #[test]
+    fn test_dangling_references_fail_with_their_page() {
+        let mut request = request("scaffolds");
+
+        let error = resolve_question_bank(&mut request).unwrap_err();
+
+        assert!(error.contains("question 1 on assessment refers to 'scaffolds'"), "{error}");
+        assert!(!error.contains("ladders"), "{error}");
+    }
+}
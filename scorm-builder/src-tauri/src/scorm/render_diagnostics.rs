@@ -0,0 +1,216 @@
+//! Template failures with the page they happened on.
+//!
+//! A Handlebars error on its own names a template and a line, which says
+//! little when the same topic template renders every page. Pages that fail
+//! are collected with their ID, title and the slice of template data the
+//! error points at, the remaining pages still render, and the build then
+//! fails once with every page listed.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Longest data snippet kept for a failed page
+const SNIPPET_CHARS: usize = 200;
+
+/// A page whose template failed to render
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PageRenderError {
+    /// `welcome`, `objectives`, `course-map`, `assessment` or the topic ID,
+    /// prefixed with the language of a variant
+    pub page_id: String,
+    pub page_title: String,
+    /// Template or partial the error was raised in
+    pub template: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// JSON of the data the error refers to, or of the whole page data when it
+    /// names nothing in it, shortened
+    pub data_snippet: String,
+}
+
+impl PageRenderError {
+    pub fn new(
+        page_id: &str,
+        page_title: &str,
+        template: &str,
+        error: &handlebars::RenderError,
+        data: &Value,
+    ) -> Self {
+        Self {
+            page_id: page_id.to_string(),
+            page_title: page_title.to_string(),
+            template: error
+                .template_name
+                .clone()
+                .unwrap_or_else(|| template.to_string()),
+            message: error.desc.clone(),
+            line: error.line_no,
+            column: error.column_no,
+            data_snippet: data_snippet(&error.desc, data),
+        }
+    }
+}
+
+impl fmt::Display for PageRenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to render page {} \"{}\" with template {}",
+            self.page_id, self.page_title, self.template
+        )?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, " (line {line}, column {column})")?;
+        }
+        write!(f, ": {} [data: {}]", self.message, self.data_snippet)
+    }
+}
+
+impl From<PageRenderError> for String {
+    fn from(error: PageRenderError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Why a package couldn't be built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerationError {
+    /// Pages whose templates failed; everything else rendered
+    Render(Vec<PageRenderError>),
+    Failed(String),
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Render(errors) => {
+                write!(f, "{} page(s) failed to render", errors.len())?;
+                for error in errors {
+                    write!(f, "\n{error}")?;
+                }
+                Ok(())
+            }
+            Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for GenerationError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+impl From<GenerationError> for String {
+    fn from(error: GenerationError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Deepest value in `data` along a Handlebars path such as `media.0.url` or
+/// `this/title`, if its first segment exists
+fn lookup<'a>(data: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut segments = path
+        .split(['.', '/'])
+        .filter(|segment| !segment.is_empty() && *segment != "this")
+        .peekable();
+    segments.peek()?;
+    let mut value = data;
+    let mut matched = false;
+    for segment in segments {
+        let next = match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment
+                .trim_matches(['[', ']'])
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get(index)),
+            _ => None,
+        };
+        match next {
+            Some(next) => {
+                value = next;
+                matched = true;
+            }
+            None => break,
+        }
+    }
+    matched.then_some(value)
+}
+
+/// The data an error message quotes (`Variable "topic.title" not found`), or all of it
+fn data_snippet(message: &str, data: &Value) -> String {
+    let referenced = message
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .find_map(|path| lookup(data, path));
+    let json = serde_json::to_string(referenced.unwrap_or(data)).unwrap_or_default();
+    if json.chars().count() <= SNIPPET_CHARS {
+        return json;
+    }
+    let mut snippet: String = json.chars().take(SNIPPET_CHARS).collect();
+    snippet.push('…');
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use handlebars::Handlebars;
+    use serde_json::json;
+
+    #[test]
+    fn test_page_render_error_points_at_the_data() {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(true);
+        handlebars
+            .register_template_string("topic", "<h1>{{title}}</h1>\n<p>{{media.0.caption}}</p>")
+            .unwrap();
+        let data = json!({
+            "title": "Safety",
+            "media": [{ "id": "image-1", "url": "media/image-1.png" }]
+        });
+
+        let error = handlebars.render("topic", &data).unwrap_err();
+        let error = PageRenderError::new("topic-1", "Safety", "topic", &error, &data);
+
+        assert_eq!(error.page_id, "topic-1");
+        assert_eq!(error.template, "topic");
+        assert_eq!(error.line, Some(2));
+        assert!(error.message.contains("media.0.caption"));
+        assert_eq!(
+            error.data_snippet,
+            r#"{"id":"image-1","url":"media/image-1.png"}"#
+        );
+        assert!(error
+            .to_string()
+            .starts_with("Failed to render page topic-1 \"Safety\" with template topic (line 2"));
+    }
+
+    #[test]
+    fn test_data_snippet_falls_back_to_shortened_page_data() {
+        let data = json!({ "content": "x".repeat(500) });
+
+        let snippet = data_snippet("Helper \"shout\" not defined", &data);
+
+        assert_eq!(snippet.chars().count(), SNIPPET_CHARS + 1);
+        assert!(snippet.starts_with(r#"{"content":"xxx"#) && snippet.ends_with('…'));
+
+        let errors = GenerationError::Render(vec![PageRenderError {
+            page_id: "fr/welcome".to_string(),
+            page_title: "Bienvenue".to_string(),
+            template: "audio_player".to_string(),
+            message: "Helper \"shout\" not defined".to_string(),
+            line: None,
+            column: None,
+            data_snippet: snippet,
+        }]);
+        assert!(errors.to_string().starts_with(
+            "1 page(s) failed to render\nFailed to render page fr/welcome \"Bienvenue\" with template audio_player: Helper"
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+/*!
+ * Course analytics (only packaged when analytics are configured)
+ * Settings come from the data attributes of this script's tag
+ */
+
+(function() {
+    'use strict';
+
+    const script = document.currentScript;
+    if (!script) return;
+
+    const settings = {
+        provider: script.getAttribute('data-provider'),
+        endpoint: script.getAttribute('data-endpoint') || '',
+        siteId: script.getAttribute('data-site-id'),
+        anonymizeIp: script.getAttribute('data-anonymize-ip') !== 'false',
+        disableCookies: script.getAttribute('data-disable-cookies') !== 'false',
+        respectDoNotTrack: script.getAttribute('data-respect-dnt') !== 'false'
+    };
+
+    function doNotTrack() {
+        const value = navigator.doNotTrack || window.doNotTrack || navigator.msDoNotTrack;
+        return value === '1' || value === 'yes';
+    }
+
+    if (!settings.siteId || (settings.respectDoNotTrack && doNotTrack())) return;
+
+    function loadScript(src) {
+        const tag = document.createElement('script');
+        tag.async = true;
+        tag.src = src;
+        document.head.appendChild(tag);
+    }
+
+    // Course pages all load into index.html, so report them under a
+    // virtual URL per page
+    function pageUrl(pageId) {
+        return window.location.href.split('#')[0] + '#/' + pageId;
+    }
+
+    function pageTitle(pageId) {
+        const item = document.querySelector('.nav-item[data-page="' + pageId + '"]');
+        const title = item ? item.textContent.trim() : pageId;
+        return document.title + ' - ' + title;
+    }
+
+    let trackPage;
+
+    if (settings.provider === 'matomo') {
+        const paq = window._paq = window._paq || [];
+        if (settings.disableCookies) paq.push(['disableCookies']);
+        if (settings.respectDoNotTrack) paq.push(['setDoNotTrack', true]);
+        paq.push(['setTrackerUrl', settings.endpoint + 'matomo.php']);
+        paq.push(['setSiteId', settings.siteId]);
+        loadScript(settings.endpoint + 'matomo.js');
+
+        trackPage = function(pageId) {
+            paq.push(['setCustomUrl', pageUrl(pageId)]);
+            paq.push(['setDocumentTitle', pageTitle(pageId)]);
+            paq.push(['trackPageView']);
+        };
+    } else if (settings.provider === 'ga') {
+        window.dataLayer = window.dataLayer || [];
+        const gtag = function() {
+            window.dataLayer.push(arguments);
+        };
+        const config = { send_page_view: false, anonymize_ip: settings.anonymizeIp };
+        if (settings.disableCookies) config.client_storage = 'none';
+        gtag('js', new Date());
+        gtag('config', settings.siteId, config);
+        loadScript('https://www.googletagmanager.com/gtag/js?id=' + encodeURIComponent(settings.siteId));
+
+        trackPage = function(pageId) {
+            gtag('event', 'page_view', {
+                page_location: pageUrl(pageId),
+                page_title: pageTitle(pageId)
+            });
+        };
+    } else {
+        return;
+    }
+
+    // navigation.js announces every page it shows
+    document.addEventListener('course:pagechange', function(event) {
+        if (event.detail && event.detail.pageId) {
+            trackPage(event.detail.pageId);
+        }
+    });
+})();
@@ -0,0 +1,72 @@
+/**
+ * Syntax highlighting for courses whose pages contain <pre><code> blocks.
+ * Applies the bundled highlight.js (scripts/highlight.min.js, loaded first)
+ * to every block, honouring a language-* class and auto-detecting the
+ * language otherwise. Token colours come from main.css.
+ *
+ * Pages load into the content area after navigation, so blocks are
+ * highlighted as they are added to the document.
+ */
+(function() {
+    'use strict';
+
+    // highlight.js 10.1.1 looks language classes up on a plain object and has
+    // slow patterns on some input, so blocks are only highlighted with a
+    // registered language and up to MAX_CODE_LENGTH characters
+    const MAX_CODE_LENGTH = 20000;
+
+    function dropUnknownLanguages(block) {
+        const known = hljs.listLanguages();
+        Array.from(block.classList).forEach(className => {
+            const prefix = ['language-', 'lang-'].find(p => className.indexOf(p) === 0);
+            const name = (prefix ? className.slice(prefix.length) : className).toLowerCase();
+            // Bare class names are looked up as languages too
+            if (name in Object.prototype || (prefix && known.indexOf(name) === -1)) {
+                block.classList.remove(className);
+            }
+        });
+    }
+
+    function highlightBlock(block) {
+        if (block.classList.contains('hljs') || block.classList.contains('no-highlight')) return;
+        if (block.textContent.length > MAX_CODE_LENGTH) return;
+        try {
+            dropUnknownLanguages(block);
+            // highlight.js 11 renamed highlightBlock to highlightElement
+            if (hljs.highlightElement) {
+                hljs.highlightElement(block);
+            } else {
+                hljs.highlightBlock(block);
+            }
+        } catch (e) {
+            console.warn('[Code] Could not highlight code block:', e);
+        }
+    }
+
+    function highlightCode(root) {
+        if (!root || root.nodeType !== 1 || typeof hljs === 'undefined') return;
+
+        if (root.matches('pre > code')) {
+            highlightBlock(root);
+            return;
+        }
+        root.querySelectorAll('pre > code').forEach(highlightBlock);
+    }
+
+    function start() {
+        highlightCode(document.body);
+        new MutationObserver(mutations => {
+            mutations.forEach(mutation => mutation.addedNodes.forEach(node => {
+                if (node.isConnected) highlightCode(node);
+            }));
+        }).observe(document.body, { childList: true, subtree: true });
+    }
+
+    window.highlightCourseCode = highlightCode;
+
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', start);
+    } else {
+        start();
+    }
+})();
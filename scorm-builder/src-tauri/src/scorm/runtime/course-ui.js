@@ -0,0 +1,310 @@
+// SCORM Course UI - modal dialogs, exit, fullscreen and delegated event handling
+// Kept out of index.html so the package works under a strict Content-Security-Policy
+// (no inline <script> blocks or on* attributes are required).
+
+(function() {
+    'use strict';
+
+    // Custom modal dialog functions
+    function showModal(title, message, buttons) {
+        const overlay = document.getElementById('scorm-modal-overlay');
+        const headerEl = document.getElementById('scorm-modal-header');
+        const bodyEl = document.getElementById('scorm-modal-body');
+        const buttonsEl = document.getElementById('scorm-modal-buttons');
+
+        headerEl.textContent = title;
+        bodyEl.textContent = message;
+        buttonsEl.innerHTML = '';
+
+        buttons.forEach(button => {
+            const btn = document.createElement('button');
+            btn.className = `scorm-modal-button scorm-modal-button-${button.type || 'secondary'}`;
+            btn.textContent = button.text;
+            btn.addEventListener('click', () => {
+                overlay.classList.remove('active');
+                if (button.callback) button.callback();
+            });
+            buttonsEl.appendChild(btn);
+        });
+
+        overlay.classList.add('active');
+    }
+
+    // Exit course functionality
+    function exitCourse() {
+        const buttons = [
+            {
+                text: 'Cancel',
+                type: 'secondary',
+                callback: null
+            },
+            {
+                text: 'Exit Course',
+                type: 'primary',
+                callback: () => {
+                    // Use SafeSCORM if available (loaded from navigation.js)
+                    if (window.SafeSCORM) {
+                        // Set exit status for proper session termination
+                        window.SafeSCORM.setValue('cmi.core.exit', 'suspend');
+                        console.log('[SCORM] Set exit status to suspend');
+
+                        // Save progress and session time, then commit and finish
+                        call('finishScormSession', 'exit');
+                    }
+
+                    // Close window
+                    setTimeout(() => {
+                        window.close();
+                        // Fallback if window.close() doesn't work
+                        window.location.href = 'about:blank';
+                    }, 500);
+                }
+            }
+        ];
+
+        // Offer a copy of the learner's notes before they leave
+        if (typeof window.hasLearnerNotes === 'function' && window.hasLearnerNotes()) {
+            buttons.splice(1, 0, {
+                text: 'Export Notes',
+                type: 'secondary',
+                callback: () => {
+                    call('exportLearnerNotes');
+                    exitCourse();
+                }
+            });
+        }
+
+        showModal(
+            'Exit Course',
+            'Are you sure you want to exit the course? Your progress has been saved.',
+            buttons
+        );
+    }
+
+    function isFullscreen() {
+        return !!(document.fullscreenElement || document.webkitFullscreenElement ||
+            document.mozFullScreenElement || document.msFullscreenElement);
+    }
+
+    // Fullscreen toggle functionality
+    function toggleFullscreen() {
+        const elem = document.documentElement;
+        const body = document.body;
+        const fullscreenIcon = document.querySelector('.fullscreen-icon');
+        const exitIcon = document.querySelector('.exit-fullscreen-icon');
+
+        if (!isFullscreen()) {
+            // Enter fullscreen
+            if (elem.requestFullscreen) {
+                elem.requestFullscreen();
+            } else if (elem.webkitRequestFullscreen) {
+                elem.webkitRequestFullscreen();
+            } else if (elem.mozRequestFullScreen) {
+                elem.mozRequestFullScreen();
+            } else if (elem.msRequestFullscreen) {
+                elem.msRequestFullscreen();
+            }
+            body.classList.add('fullscreen-mode');
+            if (fullscreenIcon) fullscreenIcon.style.display = 'none';
+            if (exitIcon) exitIcon.style.display = 'inline';
+        } else {
+            // Exit fullscreen
+            if (document.exitFullscreen) {
+                document.exitFullscreen();
+            } else if (document.webkitExitFullscreen) {
+                document.webkitExitFullscreen();
+            } else if (document.mozCancelFullScreen) {
+                document.mozCancelFullScreen();
+            } else if (document.msExitFullscreen) {
+                document.msExitFullscreen();
+            }
+            body.classList.remove('fullscreen-mode');
+            if (fullscreenIcon) fullscreenIcon.style.display = 'inline';
+            if (exitIcon) exitIcon.style.display = 'none';
+        }
+    }
+
+    function handleFullscreenChange() {
+        const fullscreenIcon = document.querySelector('.fullscreen-icon');
+        const exitIcon = document.querySelector('.exit-fullscreen-icon');
+
+        if (!isFullscreen()) {
+            document.body.classList.remove('fullscreen-mode');
+            if (fullscreenIcon) fullscreenIcon.style.display = 'inline';
+            if (exitIcon) exitIcon.style.display = 'none';
+        }
+    }
+
+    document.addEventListener('fullscreenchange', handleFullscreenChange);
+    document.addEventListener('webkitfullscreenchange', handleFullscreenChange);
+    document.addEventListener('mozfullscreenchange', handleFullscreenChange);
+    document.addEventListener('MSFullscreenChange', handleFullscreenChange);
+
+    // Responsive layout: the outline becomes a drawer on small screens
+    const DRAWER_MEDIA = window.matchMedia('(max-width: 768px)');
+
+    function isResponsiveLayout() {
+        return document.body.classList.contains('responsive-layout');
+    }
+
+    function isNavDrawerOpen() {
+        return document.body.classList.contains('nav-drawer-open');
+    }
+
+    function setNavDrawerOpen(open) {
+        const toggle = document.querySelector('.nav-drawer-toggle');
+        if (!toggle) return;
+
+        document.body.classList.toggle('nav-drawer-open', open);
+        toggle.setAttribute('aria-expanded', String(open));
+        const backdrop = document.querySelector('.nav-drawer-backdrop');
+        if (backdrop) backdrop.hidden = !open;
+
+        if (open) {
+            const current = document.querySelector('.sidebar .nav-item.active') ||
+                document.querySelector('.sidebar .nav-item');
+            if (current) current.focus();
+        }
+    }
+
+    function toggleNavDrawer() {
+        setNavDrawerOpen(!isNavDrawerOpen());
+    }
+
+    function closeNavDrawer(returnFocus) {
+        if (!isNavDrawerOpen()) return;
+        setNavDrawerOpen(false);
+        const toggle = document.querySelector('.nav-drawer-toggle');
+        if (returnFocus && toggle) toggle.focus();
+    }
+
+    // Size the shell to the visible viewport; 100vh includes mobile browser toolbars
+    function updateViewportHeight() {
+        document.documentElement.style.setProperty('--viewport-height', window.innerHeight + 'px');
+    }
+
+    if (isResponsiveLayout()) {
+        updateViewportHeight();
+        window.addEventListener('resize', updateViewportHeight);
+        window.addEventListener('orientationchange', updateViewportHeight);
+
+        // Leaving the small-screen layout puts the outline back in place
+        const onLayoutChange = () => { if (!DRAWER_MEDIA.matches) closeNavDrawer(false); };
+        if (DRAWER_MEDIA.addEventListener) {
+            DRAWER_MEDIA.addEventListener('change', onLayoutChange);
+        } else {
+            DRAWER_MEDIA.addListener(onLayoutChange);
+        }
+
+        document.addEventListener('keydown', function(event) {
+            if (event.key === 'Escape' && isNavDrawerOpen()) {
+                closeNavDrawer(true);
+            }
+        });
+    }
+
+    window.showModal = showModal;
+    window.exitCourse = exitCourse;
+    window.toggleFullscreen = toggleFullscreen;
+    window.toggleNavDrawer = toggleNavDrawer;
+    window.closeNavDrawer = closeNavDrawer;
+
+    // seekAudio/setVolume expect a click event on the control itself
+    function relativeClick(el, event) {
+        const rect = el.getBoundingClientRect();
+        return { currentTarget: el, offsetX: event.clientX - rect.left };
+    }
+
+    function call(fnName, ...args) {
+        if (typeof window[fnName] === 'function') {
+            return window[fnName](...args);
+        }
+        console.warn('[SCORM UI] Handler not available:', fnName);
+    }
+
+    // Click actions declared with data-action="..." in the generated pages
+    const CLICK_ACTIONS = {
+        'exit-course': () => exitCourse(),
+        'toggle-fullscreen': () => toggleFullscreen(),
+        'toggle-nav-drawer': () => toggleNavDrawer(),
+        'close-nav-drawer': () => closeNavDrawer(true),
+        'toggle-autoplay': () => call('toggleAudioAutoplay'),
+        'print-course': () => call('printCourse'),
+        'toggle-notes': () => call('toggleLearnerNotes'),
+        'export-notes': () => call('exportLearnerNotes'),
+        'open-search-result': (el) => call('openSearchResult', el.dataset.page),
+        'open-course-map-page': (el) => call('openCourseMapPage', el.dataset.page),
+        'resume-course': () => call('resumeCourse'),
+        'restart-course': () => call('restartCourse'),
+        'submit-knowledge-checks': () => call('submitAllKnowledgeChecks'),
+        'submit-assessment': () => call('submitAssessment'),
+        'next-assessment-question': () => call('nextAssessmentQuestion'),
+        'open-lightbox': (el) => call('openLightbox', el.dataset.src, el.dataset.caption || ''),
+        'close-lightbox': () => call('closeLightbox'),
+        'toggle-lightbox-zoom': () => call('toggleLightboxZoom'),
+        'dismiss-alert': (el) => el.parentElement && el.parentElement.remove(),
+        'toggle-play-pause': (el) => call('togglePlayPause', el.dataset.pageId),
+        'seek-audio': (el, event) => call('seekAudio', el.dataset.pageId, relativeClick(el, event)),
+        'skip-backward': (el) => call('skipBackward', el.dataset.pageId, Number(el.dataset.seconds || 10)),
+        'skip-forward': (el) => call('skipForward', el.dataset.pageId, Number(el.dataset.seconds || 10)),
+        'toggle-mute': (el) => call('toggleMute', el.dataset.pageId),
+        'set-volume': (el, event) => call('setVolume', el.dataset.pageId, relativeClick(el, event)),
+        'toggle-captions': (el) => call('toggleCaptions', el.dataset.pageId)
+    };
+
+    const CHANGE_ACTIONS = {
+        'set-playback-speed': (el) => call('setPlaybackSpeed', el.dataset.pageId, el.value)
+    };
+
+    document.addEventListener('click', function(event) {
+        // Picking a page from the drawer closes it
+        if (isNavDrawerOpen() && event.target.closest('.sidebar .nav-item')) {
+            closeNavDrawer(false);
+        }
+
+        const el = event.target.closest('[data-action]');
+        if (!el || el.disabled) return;
+
+        const handler = CLICK_ACTIONS[el.dataset.action];
+        if (handler) {
+            handler(el, event);
+        }
+    });
+
+    document.addEventListener('change', function(event) {
+        const el = event.target.closest('[data-change-action]');
+        if (!el) return;
+
+        const handler = CHANGE_ACTIONS[el.dataset.changeAction];
+        if (handler) {
+            handler(el, event);
+        }
+    });
+
+    // Media events don't bubble, so listen in the capture phase
+    const AUDIO_EVENTS = {
+        loadedmetadata: 'onAudioLoaded',
+        timeupdate: 'onAudioTimeUpdate',
+        ended: 'onAudioEnded'
+    };
+
+    Object.keys(AUDIO_EVENTS).forEach(eventName => {
+        document.addEventListener(eventName, function(event) {
+            const audio = event.target;
+            if (audio && audio.dataset && audio.dataset.audioPage) {
+                call(AUDIO_EVENTS[eventName], audio.dataset.audioPage);
+            }
+        }, true);
+    });
+
+    // Hide broken media instead of showing a broken image icon
+    document.addEventListener('error', function(event) {
+        const el = event.target;
+        if (el && el.dataset && el.dataset.hideOnError !== undefined && el.parentElement) {
+            console.error('[SCORM UI] Failed to load media:', el.src);
+            el.parentElement.style.display = 'none';
+        }
+    }, true);
+
+    console.log('[SCORM UI] Course UI handlers ready');
+})();
@@ -0,0 +1,155 @@
+/*!
+ * Course diagnostics overlay (debug builds only)
+ * Toggle with Ctrl+Alt+Shift+D, or open on load with ?scorm_debug=1
+ */
+
+(function() {
+    'use strict';
+
+    const MAX_CALLS = 20;
+    // Longest suspend_data each standard guarantees an LMS will store
+    const SUSPEND_DATA_LIMITS = { '1.2': 4096, '2004': 64000 };
+
+    const calls = [];
+    let panel = null;
+
+    /**
+     * Record every SetValue made through the UniversalSCORM wrapper
+     */
+    function wrapSetValue() {
+        const scorm = window.UniversalSCORM;
+        if (!scorm || scorm.diagnosticsWrapped) return;
+
+        const original = scorm.setValue;
+        scorm.setValue = function(element, value) {
+            const result = original.call(this, element, value);
+            calls.unshift({
+                time: new Date().toISOString().substring(11, 19),
+                element: element,
+                value: String(value),
+                result: result
+            });
+            if (calls.length > MAX_CALLS) calls.pop();
+            render();
+            return result;
+        };
+        scorm.diagnosticsWrapped = true;
+    }
+
+    function read(element) {
+        const scorm = window.UniversalSCORM;
+        if (!scorm || !scorm.api) return '';
+        return scorm.getValue(element) || '';
+    }
+
+    /**
+     * Current SCORM state as shown in the panel
+     */
+    function snapshot() {
+        const scorm = window.UniversalSCORM || {};
+        const is12 = scorm.version === '1.2';
+        const suspendData = read('cmi.suspend_data');
+
+        return {
+            connection: scorm.available ? 'Connected (SCORM ' + scorm.version + ')' : 'No LMS API found',
+            initialized: !!scorm.initialized,
+            lastError: scorm.getLastError ? scorm.getLastError() : '0',
+            suspendDataSize: suspendData.length,
+            suspendDataLimit: SUSPEND_DATA_LIMITS[scorm.version] || null,
+            completion: read(is12 ? 'cmi.core.lesson_status' : 'cmi.completion_status'),
+            success: is12 ? '' : read('cmi.success_status'),
+            score: read(is12 ? 'cmi.core.score.raw' : 'cmi.score.raw'),
+            calls: calls.slice()
+        };
+    }
+
+    function row(label, value) {
+        const line = document.createElement('div');
+        const name = document.createElement('strong');
+        name.textContent = label + ': ';
+        line.appendChild(name);
+        line.appendChild(document.createTextNode(value));
+        return line;
+    }
+
+    function render() {
+        if (!panel || panel.hidden) return;
+
+        const state = snapshot();
+        const suspendData = state.suspendDataLimit
+            ? state.suspendDataSize + ' / ' + state.suspendDataLimit + ' chars'
+            : state.suspendDataSize + ' chars';
+
+        panel.textContent = '';
+        const title = document.createElement('div');
+        title.textContent = 'SCORM diagnostics';
+        title.style.cssText = 'font-weight:bold;margin-bottom:6px';
+        panel.appendChild(title);
+
+        panel.appendChild(row('Connection', state.connection));
+        panel.appendChild(row('Initialized', state.initialized ? 'yes' : 'no'));
+        panel.appendChild(row('Last error', state.lastError));
+        panel.appendChild(row('suspend_data', suspendData));
+        panel.appendChild(row('Completion', state.completion || '(not set)'));
+        if (state.success) panel.appendChild(row('Success', state.success));
+        panel.appendChild(row('Score', state.score || '(not set)'));
+
+        const heading = document.createElement('div');
+        heading.textContent = 'Last SetValue calls';
+        heading.style.cssText = 'font-weight:bold;margin-top:6px';
+        panel.appendChild(heading);
+
+        if (state.calls.length === 0) {
+            panel.appendChild(document.createTextNode('(none yet)'));
+        }
+        state.calls.forEach(function(call) {
+            const value = call.value.length > 60 ? call.value.substring(0, 60) + '…' : call.value;
+            panel.appendChild(row(call.time + ' ' + call.element, value + (call.result ? '' : ' (failed)')));
+        });
+    }
+
+    function createPanel() {
+        panel = document.createElement('div');
+        panel.id = 'scorm-diagnostics';
+        panel.setAttribute('role', 'log');
+        panel.setAttribute('aria-label', 'SCORM diagnostics');
+        panel.hidden = true;
+        // Styles are set through the CSSOM so strict CSPs don't block them
+        panel.style.cssText = [
+            'position:fixed', 'bottom:12px', 'right:12px', 'z-index:100000',
+            'width:360px', 'max-height:60vh', 'overflow:auto', 'padding:10px',
+            'background:rgba(17,24,39,0.95)', 'color:#f9fafb',
+            'font:12px/1.4 monospace', 'border-radius:6px', 'word-break:break-all'
+        ].join(';');
+        document.body.appendChild(panel);
+    }
+
+    function toggle(show) {
+        if (!panel) createPanel();
+        panel.hidden = typeof show === 'boolean' ? !show : !panel.hidden;
+        render();
+    }
+
+    function init() {
+        wrapSetValue();
+
+        document.addEventListener('keydown', function(event) {
+            if (event.ctrlKey && event.altKey && event.shiftKey && (event.key === 'D' || event.key === 'd')) {
+                event.preventDefault();
+                toggle();
+            }
+        });
+
+        if (new URLSearchParams(window.location.search).get('scorm_debug') === '1') {
+            toggle(true);
+        }
+    }
+
+    window.SCORMDiagnostics = { toggle: toggle, snapshot: snapshot };
+
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', init);
+    } else {
+        init();
+    }
+})();
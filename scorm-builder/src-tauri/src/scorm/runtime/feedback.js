@@ -0,0 +1,148 @@
+/*!
+ * "Report a problem with this page" (only packaged when feedback is configured)
+ * Settings come from the data attributes of this script's tag
+ */
+
+(function() {
+    'use strict';
+
+    const script = document.currentScript;
+    if (!script) return;
+
+    const settings = {
+        webhookUrl: script.getAttribute('data-webhook-url') || '',
+        email: script.getAttribute('data-email') || '',
+        courseVersion: script.getAttribute('data-course-version') || ''
+    };
+
+    const toggle = document.getElementById('feedback-toggle');
+    const panel = document.getElementById('feedback-panel');
+    const form = document.getElementById('feedback-form');
+    const comment = document.getElementById('feedback-comment');
+    const status = document.getElementById('feedback-status');
+    const pageTitleLabel = document.getElementById('feedback-page-title');
+    if (!toggle || !panel || !form || !comment || !status) return;
+
+    if (!settings.webhookUrl && !settings.email) {
+        toggle.hidden = true;
+        return;
+    }
+
+    // navigation.js announces every page it shows
+    let currentPageId = window.currentPage || 'welcome';
+    document.addEventListener('course:pagechange', function(event) {
+        if (event.detail && event.detail.pageId) {
+            currentPageId = event.detail.pageId;
+            if (pageTitleLabel) pageTitleLabel.textContent = pageTitle(currentPageId);
+        }
+    });
+
+    function pageTitle(pageId) {
+        const item = document.querySelector('.nav-item[data-page="' + pageId + '"]');
+        return item ? item.textContent.trim() : pageId;
+    }
+
+    function openPanel() {
+        if (pageTitleLabel) pageTitleLabel.textContent = pageTitle(currentPageId);
+        status.textContent = '';
+        panel.hidden = false;
+        toggle.setAttribute('aria-expanded', 'true');
+        comment.focus();
+    }
+
+    function closePanel() {
+        panel.hidden = true;
+        toggle.setAttribute('aria-expanded', 'false');
+        toggle.focus();
+    }
+
+    // Who the learner is stays out of the report
+    function buildReport() {
+        return {
+            course_title: document.title,
+            course_version: settings.courseVersion,
+            page_id: currentPageId,
+            page_title: pageTitle(currentPageId),
+            comment: comment.value.trim(),
+            reported_at: new Date().toISOString()
+        };
+    }
+
+    function postReport(report) {
+        return fetch(settings.webhookUrl, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify(report)
+        }).then(function(response) {
+            if (!response.ok) throw new Error('HTTP ' + response.status);
+        });
+    }
+
+    function mailReport(report) {
+        const subject = 'Problem report: ' + report.course_title + ' - ' + report.page_title;
+        const body = [
+            'Course: ' + report.course_title + ' (version ' + report.course_version + ')',
+            'Page: ' + report.page_title + ' (' + report.page_id + ')',
+            '',
+            report.comment
+        ].join('\n');
+        window.location.href = 'mailto:' + settings.email +
+            '?subject=' + encodeURIComponent(subject) +
+            '&body=' + encodeURIComponent(body);
+    }
+
+    function finish(message) {
+        status.textContent = message;
+        comment.value = '';
+        setTimeout(closePanel, 2000);
+    }
+
+    function submitReport(report) {
+        const send = form.querySelector('.feedback-send');
+        if (!settings.webhookUrl) {
+            mailReport(report);
+            finish('Your email app should open with the report.');
+            return;
+        }
+        send.disabled = true;
+        status.textContent = 'Sending...';
+        postReport(report).then(function() {
+            finish('Thank you, your report was sent.');
+        }).catch(function(error) {
+            console.warn('[Feedback] Sending the report failed:', error);
+            if (settings.email) {
+                mailReport(report);
+                finish('Sending failed, so your email app should open with the report.');
+            } else {
+                status.textContent = 'Sending failed. Please try again later.';
+            }
+        }).finally(function() {
+            send.disabled = false;
+        });
+    }
+
+    toggle.addEventListener('click', function() {
+        if (panel.hidden) {
+            openPanel();
+        } else {
+            closePanel();
+        }
+    });
+
+    document.getElementById('feedback-cancel').addEventListener('click', closePanel);
+
+    panel.addEventListener('keydown', function(event) {
+        if (event.key === 'Escape') closePanel();
+    });
+
+    form.addEventListener('submit', function(event) {
+        event.preventDefault();
+        const report = buildReport();
+        if (!report.comment) {
+            status.textContent = 'Please describe the problem.';
+            comment.focus();
+            return;
+        }
+        submitReport(report);
+    });
+})();
@@ -0,0 +1,132 @@
+/**
+ * Math rendering for courses whose content contains formulas. Finds TeX
+ * between $$...$$ (display) or \(...\) (inline) in the page text and renders
+ * it with the bundled KaTeX (scripts/katex.min.js, loaded first) as MathML,
+ * which the browser lays out without KaTeX's stylesheet or fonts.
+ *
+ * Pages load into the content area after navigation and feedback appears
+ * after answers, so newly added content is rendered as it arrives. A formula
+ * has to sit in one text node: markup inside the delimiters leaves it as is.
+ */
+(function() {
+    'use strict';
+
+    const DELIMITERS = [
+        { open: '$$', close: '$$', display: true },
+        { open: '\\(', close: '\\)', display: false }
+    ];
+
+    // KaTeX 0.16.4 doesn't count some expansions against maxExpand, so a
+    // formula defining its own macros could hang the page. Formulas using
+    // these, or longer than MAX_TEX_LENGTH, are left as text.
+    const MACRO_DEFINITIONS = ['\\def', '\\edef', '\\gdef', '\\xdef', '\\let', '\\newcommand', '\\renewcommand', '\\providecommand', '\\futurelet'];
+    const MAX_TEX_LENGTH = 2000;
+
+    // Text inside these is never treated as math
+    const SKIP_TAGS = ['SCRIPT', 'STYLE', 'TEXTAREA', 'CODE', 'PRE', 'MATH'];
+
+    // The earliest non-empty formula in text at or after from, or null
+    function findMath(text, from) {
+        let found = null;
+        DELIMITERS.forEach(delimiter => {
+            let start = text.indexOf(delimiter.open, from);
+            while (start !== -1) {
+                const end = text.indexOf(delimiter.close, start + delimiter.open.length);
+                if (end === -1) return;
+
+                const tex = text.slice(start + delimiter.open.length, end);
+                if (tex.trim()) {
+                    if (!found || start < found.start) {
+                        found = { start: start, end: end + delimiter.close.length, tex: tex, display: delimiter.display };
+                    }
+                    return;
+                }
+                start = text.indexOf(delimiter.open, end + delimiter.close.length);
+            }
+        });
+        return found;
+    }
+
+    function isSafeTex(tex) {
+        return tex.length <= MAX_TEX_LENGTH &&
+            MACRO_DEFINITIONS.every(macro => tex.indexOf(macro) === -1);
+    }
+
+    function shouldSkip(element) {
+        for (let node = element; node && node.nodeType === 1; node = node.parentNode) {
+            if (SKIP_TAGS.indexOf(node.tagName) !== -1 || node.isContentEditable) return true;
+            if (node.classList.contains('katex') || node.classList.contains('no-math')) return true;
+        }
+        return false;
+    }
+
+    function renderTextNode(node) {
+        const text = node.nodeValue;
+        const fragment = document.createDocumentFragment();
+        let last = 0;
+        let match = findMath(text, 0);
+
+        while (match) {
+            fragment.appendChild(document.createTextNode(text.slice(last, match.start)));
+
+            const container = document.createElement('span');
+            container.className = match.display ? 'course-math course-math-display' : 'course-math';
+            try {
+                if (!isSafeTex(match.tex)) throw new Error('Formula defines macros or is too long');
+                katex.render(match.tex, container, {
+                    displayMode: match.display,
+                    output: 'mathml',
+                    throwOnError: false,
+                    trust: false,
+                    maxExpand: 100,
+                    maxSize: 20
+                });
+            } catch (e) {
+                console.warn('[Math] Could not render formula:', match.tex, e);
+                container.textContent = text.slice(match.start, match.end);
+            }
+            fragment.appendChild(container);
+
+            last = match.end;
+            match = findMath(text, last);
+        }
+
+        if (last === 0) return;
+        fragment.appendChild(document.createTextNode(text.slice(last)));
+        node.parentNode.replaceChild(fragment, node);
+    }
+
+    function renderMath(root) {
+        if (!root || typeof katex === 'undefined') return;
+        if (root.nodeType === 3) root = root.parentNode;
+        if (!root || shouldSkip(root)) return;
+
+        const walker = document.createTreeWalker(root, NodeFilter.SHOW_TEXT);
+        const nodes = [];
+        while (walker.nextNode()) {
+            const node = walker.currentNode;
+            const text = node.nodeValue;
+            if ((text.indexOf('$$') !== -1 || text.indexOf('\\(') !== -1) && !shouldSkip(node.parentNode)) {
+                nodes.push(node);
+            }
+        }
+        nodes.forEach(renderTextNode);
+    }
+
+    function start() {
+        renderMath(document.body);
+        new MutationObserver(mutations => {
+            mutations.forEach(mutation => mutation.addedNodes.forEach(node => {
+                if (node.isConnected) renderMath(node);
+            }));
+        }).observe(document.body, { childList: true, subtree: true });
+    }
+
+    window.renderCourseMath = renderMath;
+
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', start);
+    } else {
+        start();
+    }
+})();
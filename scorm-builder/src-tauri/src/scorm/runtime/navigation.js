@@ -23,6 +23,27 @@
         }
     }
     
+    // Per-course data from scripts/course-data.js (navigation_generator.rs),
+    // which index.html loads before this script
+    const COURSE_DATA = window.COURSE_DATA;
+    
+    // Behaviours switched by the LMS compatibility profile (see lms_profile.rs)
+    const LMS_COMPATIBILITY = {
+        profile: COURSE_DATA.lms.profile,
+        fitParentFrame: COURSE_DATA.lms.fit_parent_frame,
+        unlockAudioOnLaunch: COURSE_DATA.lms.unlock_audio_on_launch, // autoplay is always blocked in this LMS's launch window
+        commitIntervalMs: COURSE_DATA.lms.commit_interval_ms // least time between commits
+    };
+    
+    // When saves reach the LMS (CommitSettings in generator_enhanced.rs)
+    const COMMIT_SETTINGS = {
+        debounceMs: COURSE_DATA.commit.debounce_ms,
+        heartbeatMs: COURSE_DATA.commit.heartbeat_ms // 0 turns the heartbeat off
+    };
+    let lastCommitAt = 0;
+    let pendingCommit = null;
+    let sessionFinished = false;
+    
     // Legacy SafeSCORM compatibility wrapper for existing code
     const SafeSCORM = {
         get api() { 
@@ -51,11 +72,32 @@
             return window.UniversalSCORM ? window.UniversalSCORM.setValue(element, value) : false;
         },
         
+        // Saves in quick succession share one commit, and profiles can space
+        // commits further apart. flushCommit() sends a pending commit now.
         commit: function() {
+            if (!window.UniversalSCORM) return false;
+            
+            const wait = Math.max(
+                COMMIT_SETTINGS.debounceMs,
+                LMS_COMPATIBILITY.commitIntervalMs - (Date.now() - lastCommitAt)
+            );
+            if (wait <= 0) {
+                return this.flushCommit();
+            }
+            clearTimeout(pendingCommit);
+            pendingCommit = setTimeout(() => this.flushCommit(), wait);
+            return true;
+        },
+        
+        flushCommit: function() {
+            clearTimeout(pendingCommit);
+            pendingCommit = null;
+            lastCommitAt = Date.now();
             return window.UniversalSCORM ? window.UniversalSCORM.commit() : false;
         },
         
         finish: function() {
+            if (pendingCommit) this.flushCommit();
             return window.UniversalSCORM ? window.UniversalSCORM.finish() : false;
         },
         
@@ -154,12 +196,52 @@
     
     // Don't set initial location yet - will be set after checking for saved progress
     
-    // Set up beforeunload handler to properly close SCORM session
+    // Stretch the LMS's iframe to the window when its player gives it a fixed
+    // height (Moodle profile). Cross-origin players don't allow this.
+    function fitParentFrame() {
+        try {
+            const frame = window.frameElement;
+            if (!frame) return;
+            const top = Math.max(0, frame.getBoundingClientRect().top);
+            frame.style.height = Math.max(400, window.parent.innerHeight - top) + 'px';
+        } catch (e) {
+            console.log('[SCORM] Cannot resize the LMS frame:', e.message);
+        }
+    }
+    
+    if (LMS_COMPATIBILITY.fitParentFrame) {
+        fitParentFrame();
+        try {
+            window.parent.addEventListener('resize', fitParentFrame);
+        } catch (e) {
+            // Cross-origin parent; fitParentFrame already logged why
+        }
+    }
+    
+    // Save everything and close the LMS session, once, however the course ends
+    function finishSession(reason) {
+        if (sessionFinished) return;
+        console.log('[SCORM] Finishing session:', reason);
+        
+        const sessionTime = window.getSessionTime();
+        SafeSCORM.setValue('cmi.core.session_time', sessionTime);
+        console.log('[SCORM] Session time:', sessionTime);
+        
+        saveProgress();
+        sessionFinished = true;
+        SafeSCORM.finish();
+    }
+    window.finishScormSession = finishSession;
+    
     window.addEventListener('beforeunload', function(e) {
+        if (sessionFinished) return;
         console.log('[SCORM] Page unloading - saving all progress');
         
-        // Show confirmation dialog if enabled
+        // Save before asking: the learner may leave whatever they answer
         if (COURSE_SETTINGS.confirmExit) {
+            saveProgress();
+            SafeSCORM.flushCommit();
+            
             const message = 'Are you sure you want to exit the course? Your progress will be saved.';
             e.preventDefault();
             e.returnValue = message;
@@ -168,16 +250,28 @@
             return message;
         }
         
-        // Set session time
-        const sessionTime = window.getSessionTime();
-        SafeSCORM.setValue('cmi.core.session_time', sessionTime);
-        console.log('[SCORM] Session time:', sessionTime);
-        
-        // Save all progress
-        saveProgress();
-        
-        // Finish SCORM session
-        SafeSCORM.finish();
+        finishSession('beforeunload');
+    });
+    
+    // Mobile browsers and back/forward navigation may skip beforeunload, and
+    // confirmed exits only get here
+    window.addEventListener('pagehide', function() {
+        finishSession('pagehide');
+    });
+    
+    // A page restored from the back/forward cache has no LMS session left
+    window.addEventListener('pageshow', function(e) {
+        if (e.persisted && sessionFinished) {
+            window.location.reload();
+        }
+    });
+    
+    // Background tabs can be discarded without any unload event
+    document.addEventListener('visibilitychange', function() {
+        if (document.visibilityState === 'hidden' && !sessionFinished) {
+            saveProgress();
+            SafeSCORM.flushCommit();
+        }
     });
     
     // Initialize navigation state with defaults
@@ -209,54 +303,96 @@
         twoMinute: false
     };
     
+    // Learner notes by page id (full text; suspend_data may hold a shortened copy)
+    window.learnerNotes = {};
+    window.learnerNotesTruncated = false;
+    
     // Audio completion tracking
     window.completedAudio = new Set();
-    const REQUIRE_AUDIO_COMPLETION = {{require_audio_completion}};
+    
+    // Welcome page start options: the intro video gate and the bookmark
+    // waiting for the learner's answer to the resume prompt
+    window.introVideoWatched = false;
+    window.resumeLocation = null;
+    
+    // Narration positions per page (seconds), when the course remembers them
+    window.audioPositions = {};
+    
+    // [visits, interactions] per page, with runtime analytics
+    window.pageActivity = {};
+    
+    // Times the browser refused to autoplay narration, over all sessions, and
+    // whether the learner has clicked the audio unlock overlay this session
+    window.audioBlocked = 0;
+    window.audioUnlocked = false;
+    const REQUIRE_AUDIO_COMPLETION = COURSE_DATA.require_audio_completion;
+    const AUDIO_SEEK_LOCKED = COURSE_DATA.audio_seek_locked; // seeking and skipping disabled
     
     // Course settings configuration
     const COURSE_SETTINGS = {
-        autoAdvance: {{auto_advance}},
-        allowPreviousReview: {{allow_previous_review}},
-        allowRetake: {{allow_retake}},
-        retakeDelay: {{retake_delay}}, // hours
-        completionCriteria: '{{completion_criteria}}',
-        showProgress: {{show_progress}},
-        showOutline: {{show_outline}},
-        confirmExit: {{confirm_exit}},
-        fontSize: '{{font_size}}',
-        timeLimit: {{time_limit}}, // minutes, 0 = unlimited
-        sessionTimeout: {{session_timeout}}, // minutes for auto-save
-        minimumTimeSpent: {{minimum_time_spent}}, // minutes
-        keyboardNavigation: {{keyboard_navigation}},
-        printable: {{printable}},
-        navigationMode: '{{navigation_mode}}',
-        passMark: {{pass_mark}}
+        autoAdvance: COURSE_DATA.auto_advance,
+        allowPreviousReview: COURSE_DATA.allow_previous_review,
+        allowRetake: COURSE_DATA.allow_retake,
+        retakeDelay: COURSE_DATA.retake_delay, // hours
+        completionCriteria: COURSE_DATA.completion_criteria,
+        showProgress: COURSE_DATA.show_progress,
+        showOutline: COURSE_DATA.show_outline,
+        confirmExit: COURSE_DATA.confirm_exit,
+        fontSize: COURSE_DATA.font_size,
+        timeLimit: COURSE_DATA.time_limit, // minutes, 0 = unlimited
+        sessionTimeout: COURSE_DATA.session_timeout, // minutes for auto-save
+        minimumTimeSpent: COURSE_DATA.minimum_time_spent, // minutes
+        keyboardNavigation: COURSE_DATA.keyboard_navigation,
+        printable: COURSE_DATA.printable,
+        learnerNotes: COURSE_DATA.learner_notes,
+        runtimeAnalytics: COURSE_DATA.runtime_analytics, // visits and interactions per page kept in suspend_data
+        resumePrompt: COURSE_DATA.resume_prompt, // ask returning learners to resume or start over
+        introVideoGate: COURSE_DATA.intro_video_gate, // welcome video must be watched before starting
+        audioAutoplay: COURSE_DATA.audio_autoplay, // narration starts on page enter unless the learner turned it off
+        audioUnlockOverlay: COURSE_DATA.audio_unlock_overlay, // "Click to enable audio" when the browser blocks autoplay
+        rememberAudioPosition: COURSE_DATA.remember_audio_position, // narration resumes where the learner left it
+        navigationMode: COURSE_DATA.navigation_mode,
+        passMark: COURSE_DATA.pass_mark
     };
     
+    // Layout and size budget of cmi.suspend_data (see suspend_data.rs)
+    const SUSPEND_DATA = {
+        version: COURSE_DATA.suspend_data.version,
+        maxLength: COURSE_DATA.suspend_data.max_length, // characters the LMS must keep
+        asciiOnly: COURSE_DATA.suspend_data.ascii_only, // escape other characters as \uXXXX
+        notesMaxLength: COURSE_DATA.suspend_data.notes_max_length, // 0 when learner notes are off
+        activityMaxLength: COURSE_DATA.suspend_data.activity_max_length // 0 when runtime analytics are off
+    };
+    
+    // Search index built by the generator: page id, title and visible text per
+    // page, empty when course search is off
+    const COURSE_SEARCH_INDEX = COURSE_DATA.search_index;
+    
     // Apply font size setting to document
     if (COURSE_SETTINGS.fontSize !== 'medium') {
         document.documentElement.classList.add(`font-${COURSE_SETTINGS.fontSize}`);
     }
     
-    // Course structure from Rust
-    const COURSE_PAGES = [
-        'welcome',
-        {{#if has_objectives}}'objectives',{{/if}}
-        {{#each topics}}'{{this.id}}',{{/each}}
-        'assessment'
-    ];
+    // Course structure from Rust, in menu order (navigation_menu.rs)
+    const COURSE_PAGES = COURSE_DATA.menu_pages.map(page => page.id);
+    const MENU_TITLES = Object.fromEntries(COURSE_DATA.menu_pages.map(page => [page.id, page.title]));
     
     // Initialize course structure
     window.courseStructure = COURSE_PAGES;
     
+    // Pages progress is measured over; the credits page is reference, not course content
+    const PROGRESS_PAGES = COURSE_PAGES.filter(pageId => pageId !== 'credits');
+    
+    // Estimated seconds per page (page_effort.rs); progress follows effort, not page count
+    const PAGE_WEIGHTS = COURSE_DATA.page_weights;
+    function pageWeight(pageId) {
+        return PAGE_WEIGHTS[pageId] || COURSE_DATA.min_page_seconds;
+    }
+    
     // Knowledge check configuration
-    const PAGES_WITH_KNOWLEDGE_CHECKS = {
-        {{#each topics}}
-        {{#if this.has_knowledge_check}}
-        '{{this.id}}': true,
-        {{/if}}
-        {{/each}}
-    };
+    const PAGES_WITH_KNOWLEDGE_CHECKS = Object.fromEntries(
+        COURSE_DATA.knowledge_check_pages.map(pageId => [pageId, true])
+    );
     
     // Navigation state management
     function updateNavigationState() {
@@ -304,6 +440,13 @@
             }
         }
         
+        // The course starts once the welcome page's intro video has been watched
+        if (direction !== 'backward' && window.currentPage === 'welcome' && introVideoPending()) {
+            console.log('[SCORM Navigation] Blocking - intro video not watched');
+            showNavigationMessage('Please watch the introduction video before starting the course.');
+            return true;
+        }
+        
         // Check audio completion requirement for forward navigation
         if (direction === 'forward' && REQUIRE_AUDIO_COMPLETION) {
             const currentPageAudio = document.querySelector(`#topic-audio-${window.currentPage}`);
@@ -402,6 +545,9 @@
         // Check all pages between current and target
         for (let i = fromIndex; i < toIndex; i++) {
             const pageId = COURSE_PAGES[i];
+            if (pageId === 'welcome' && introVideoPending()) {
+                return true;
+            }
             if (PAGES_WITH_KNOWLEDGE_CHECKS[pageId]) {
                 // Check if this page has unanswered multiple choice questions
                 const mcQuestionKey = `${pageId}_q0`;
@@ -431,6 +577,7 @@
         
         // Start timing for new page
         window.sessionData.pageStartTime = Date.now();
+        recordPageActivity(pageId, 1, 0);
         
         const contentContainer = document.getElementById('content-container');
         if (!contentContainer) {
@@ -458,6 +605,8 @@
                 initializePageVideo(pageId);
                 initializeKnowledgeChecks();
                 restoreAnsweredQuestions(pageId);
+                initializeIntroVideoGate();
+                showResumePrompt(pageId);
                 
                 // Update navigation state after content loads
                 // This is CRITICAL - must happen after content is in DOM
@@ -469,6 +618,23 @@
                 // Update sidebar active state
                 updateSidebarActiveState();
                 updateSidebarCompletionState();
+                updateCourseMap();
+                
+                // Announce the new page to screen readers
+                announcePageChange(pageId);
+                
+                // Let optional scripts (analytics.js) follow the learner's progress
+                document.dispatchEvent(new CustomEvent('course:pagechange', { detail: { pageId: pageId } }));
+                
+                // Keep the notes panel on the current page's note
+                showLearnerNotesForPage(pageId);
+                
+                // Assessment timers only tick while the assessment is shown
+                if (pageId === 'assessment') {
+                    initializeAssessmentTimer();
+                } else {
+                    suspendAssessmentTimer();
+                }
                 
                 // Save progress after navigation
                 saveProgress();
@@ -516,12 +682,16 @@
     // Handle sidebar navigation click
     function handleSidebarClick(event) {
         event.preventDefault();
-        
-        const targetPage = event.currentTarget.dataset.page;
+        console.log('[SCORM Navigation] Sidebar click:', event.currentTarget.dataset.page);
+        jumpToPage(event.currentTarget.dataset.page);
+    }
+    
+    // Go straight to a page from the outline or search, within the navigation rules
+    function jumpToPage(targetPage) {
         const currentIndex = COURSE_PAGES.indexOf(window.currentPage);
         const targetIndex = COURSE_PAGES.indexOf(targetPage);
         
-        console.log('[SCORM Navigation] Sidebar click:', {
+        console.log('[SCORM Navigation] Jump to page:', {
             currentPage: window.currentPage,
             targetPage,
             currentIndex,
@@ -529,11 +699,11 @@
             isForward: targetIndex > currentIndex
         });
         
-        // Check navigation permissions for sidebar jumps
+        // Check navigation permissions for jumps
         const direction = targetIndex > currentIndex ? 'jump' : targetIndex < currentIndex ? 'backward' : 'current';
         
         if (shouldBlockNavigation(direction, targetIndex)) {
-            console.log('[SCORM Navigation] Sidebar navigation BLOCKED');
+            console.log('[SCORM Navigation] Jump navigation BLOCKED');
             showNavigationBlockedAlert();
             return;
         }
@@ -549,9 +719,64 @@
         navigateToPage(targetPage);
     }
     
+    // Screen reader announcements via the live regions in index.html
+    function announceToScreenReader(message, priority = 'polite') {
+        const region = document.getElementById(
+            priority === 'assertive' ? 'sr-announcer-assertive' : 'sr-announcer-polite'
+        );
+        if (!region || !message) {
+            return;
+        }
+        
+        // Clear first so repeating the same message is still announced
+        region.textContent = '';
+        setTimeout(() => {
+            region.textContent = message;
+        }, 100);
+    }
+    
+    // Expose for page-level scripts
+    window.announceToScreenReader = announceToScreenReader;
+    
+    // Unhide the image or audio attached to the feedback for this outcome
+    // ('correct' or 'incorrect'), hiding and pausing the other; null hides both
+    function showFeedbackMedia(feedbackElement, outcome) {
+        const container = feedbackElement && feedbackElement.parentElement;
+        if (!container) {
+            return;
+        }
+        container.querySelectorAll(':scope > .feedback-media').forEach(media => {
+            media.hidden = media.dataset.feedbackMedia !== outcome;
+            const audio = media.querySelector('audio');
+            if (audio && media.hidden) {
+                audio.pause();
+            }
+        });
+    }
+    
+    // Announce "Page 3 of 10: Title" after navigation
+    function announcePageChange(pageId) {
+        const pageIndex = COURSE_PAGES.indexOf(pageId);
+        if (pageIndex === -1) {
+            return;
+        }
+        
+        // Footer page numbers, when the course shows them
+        const pageCounter = document.getElementById('page-counter');
+        if (pageCounter) {
+            pageCounter.textContent = `Page ${pageIndex + 1} of ${COURSE_PAGES.length}`;
+        }
+        
+        // Prefer the sidebar label so topic titles are read as authored
+        const navItem = document.querySelector(`.nav-item[data-page="${pageId}"]`);
+        const title = navItem && navItem.textContent.trim() ? navItem.textContent.trim() : getPageTitle(pageId);
+        announceToScreenReader(`Page ${pageIndex + 1} of ${COURSE_PAGES.length}: ${title}`);
+    }
+    
     // Show navigation blocked alert
     function showNavigationBlockedAlert() {
         const message = 'Please complete the knowledge check on this page before proceeding.';
+        announceToScreenReader(message, 'assertive');
         
         // Create custom alert
         const alertContainer = document.getElementById('scorm-alert-container');
@@ -637,6 +862,8 @@
                 feedbackElement.className = 'feedback incorrect';
             }
             feedbackElement.style.display = 'block';
+            showFeedbackMedia(feedbackElement, isCorrect ? 'correct' : 'incorrect');
+            announceToScreenReader(`${isCorrect ? 'Correct answer' : 'Incorrect answer'}. ${feedbackElement.textContent}`);
         }
         
         // Disable submit button
@@ -698,6 +925,8 @@
             feedbackElement.textContent = isCorrect ? correctFeedback : incorrectFeedback;
             feedbackElement.className = isCorrect ? 'feedback correct' : 'feedback incorrect';
             feedbackElement.style.display = 'block';
+            showFeedbackMedia(feedbackElement, isCorrect ? 'correct' : 'incorrect');
+            announceToScreenReader(`${isCorrect ? 'Correct answer' : 'Incorrect answer'}. ${feedbackElement.textContent}`);
         }
         
         // Update navigation state
@@ -749,6 +978,8 @@
             feedbackElement.textContent = isCorrect ? correctFeedback : incorrectFeedback;
             feedbackElement.className = isCorrect ? 'feedback correct' : 'feedback incorrect';
             feedbackElement.style.display = 'block';
+            showFeedbackMedia(feedbackElement, isCorrect ? 'correct' : 'incorrect');
+            announceToScreenReader(`${isCorrect ? 'Correct answer' : 'Incorrect answer'}. ${feedbackElement.textContent}`);
         }
         
         // Disable submit button
@@ -789,6 +1020,7 @@
         // Find all question wrappers
         const questions = knowledgeCheckContainer.querySelectorAll('.kc-question-wrapper');
         let allAnswered = true;
+        let correctCount = 0;
         
         questions.forEach((wrapper, index) => {
             // Check if it's a multiple choice or true/false question
@@ -805,6 +1037,7 @@
                 const selectedValue = selectedInput.value;
                 const correctAnswer = wrapper.dataset.correctAnswer || '';
                 const isCorrect = selectedValue === correctAnswer;
+                if (isCorrect) correctCount++;
                 
                 console.log('[SCORM Knowledge Check] MC/TF question:', {
                     index,
@@ -847,6 +1080,7 @@
                     feedbackElement.textContent = isCorrect ? correctFeedback : incorrectFeedback;
                     feedbackElement.className = isCorrect ? 'feedback correct' : 'feedback incorrect';
                     feedbackElement.style.display = 'block';
+                    showFeedbackMedia(feedbackElement, isCorrect ? 'correct' : 'incorrect');
                 }
                 
                 // Disable radio buttons after submission
@@ -864,6 +1098,7 @@
                 
                 const correctAnswer = (wrapper.dataset.correctAnswer || '').toLowerCase();
                 const isCorrect = userAnswer.toLowerCase() === correctAnswer;
+                if (isCorrect) correctCount++;
                 
                 console.log('[SCORM Knowledge Check] Fill-in-blank:', {
                     index,
@@ -894,6 +1129,7 @@
                     feedbackElement.textContent = isCorrect ? correctFeedback : incorrectFeedback;
                     feedbackElement.className = isCorrect ? 'feedback correct' : 'feedback incorrect';
                     feedbackElement.style.display = 'block';
+                    showFeedbackMedia(feedbackElement, isCorrect ? 'correct' : 'incorrect');
                 }
                 
                 // Disable input after submission
@@ -909,6 +1145,7 @@
                 alert.className = 'scorm-alert scorm-alert-warning';
                 alert.textContent = 'Please answer all questions before submitting.';
                 alertContainer.appendChild(alert);
+                announceToScreenReader(alert.textContent, 'assertive');
                 
                 // Auto-remove after 5 seconds
                 setTimeout(() => {
@@ -925,6 +1162,13 @@
             submitButton.textContent = 'All Answers Submitted';
         }
         
+        // Summarise results for screen reader users
+        if (questions.length === 1) {
+            announceToScreenReader(correctCount === 1 ? 'Correct answer' : 'Incorrect answer');
+        } else {
+            announceToScreenReader(`${correctCount} of ${questions.length} answers correct`);
+        }
+        
         // Update navigation state
         updateNavigationState();
         
@@ -957,15 +1201,17 @@
                 loadCaptions(pageId, captionFile);
             }
             
+            // Pick up where the learner left the narration on this page
+            restoreAudioPosition(pageId, audio);
+            
             // Check autoplay preference
-            const autoplayEnabled = localStorage.getItem('audioAutoplay') === 'true';
-            if (autoplayEnabled) {
+            if (isAudioAutoplayEnabled()) {
                 console.log('[SCORM Audio] Autoplay enabled, attempting to play audio');
+                const playPauseBtn = audio.parentElement.querySelector('.audio-play-pause');
                 // Attempt to play audio
                 audio.play().then(() => {
                     console.log('[SCORM Audio] Autoplay started successfully');
                     // Update play/pause button to show pause icon
-                    const playPauseBtn = document.querySelector(`#topic-audio-${pageId}`).parentElement.querySelector('.audio-play-pause');
                     if (playPauseBtn) {
                         const playIcon = playPauseBtn.querySelector('.play-icon');
                         const pauseIcon = playPauseBtn.querySelector('.pause-icon');
@@ -973,19 +1219,31 @@
                             playIcon.style.display = 'none';
                             pauseIcon.style.display = 'inline';
                         }
-                        // Update button state
-                        playPauseBtn.setAttribute('data-playing', 'true');
-                        playPauseBtn.innerHTML = '⏸';
                     }
                 }).catch(error => {
-                    console.log('[SCORM Audio] Autoplay blocked:', error);
-                    // Show notification that user interaction is required
-                    showAutoplayBlockedNotification();
+                    handleBlockedAutoplay(audio, playPauseBtn, error);
                 });
             }
         }
     }
     
+    function restoreAudioPosition(pageId, audio) {
+        const position = window.audioPositions[pageId];
+        if (!COURSE_SETTINGS.rememberAudioPosition || !(position > 0)) return;
+        
+        const seek = () => {
+            if (isFinite(audio.duration) && position < audio.duration) {
+                audio.currentTime = position;
+                console.log('[SCORM Audio] Resumed narration at', position, 's on page:', pageId);
+            }
+        };
+        if (audio.readyState >= 1) {
+            seek();
+        } else {
+            audio.addEventListener('loadedmetadata', seek, { once: true });
+        }
+    }
+    
     // Initialize page video
     function initializePageVideo(pageId) {
         console.log('[SCORM Navigation] Initializing video for page:', pageId);
@@ -1070,8 +1328,8 @@
     };
     
     window.seekAudio = function(pageId, event) {
-        // Block seeking if audio completion is required
-        if (REQUIRE_AUDIO_COMPLETION) {
+        // Block seeking if the course locks it until the audio completes
+        if (AUDIO_SEEK_LOCKED) {
             console.log('[SCORM Audio] Seeking disabled - audio completion required');
             return;
         }
@@ -1088,8 +1346,8 @@
     };
     
     window.skipBackward = function(pageId, seconds) {
-        // Block skip backward if audio completion is required
-        if (REQUIRE_AUDIO_COMPLETION) {
+        // Block skip backward if the course locks seeking
+        if (AUDIO_SEEK_LOCKED) {
             console.log('[SCORM Audio] Skip backward disabled - audio completion required');
             return;
         }
@@ -1101,8 +1359,8 @@
     };
     
     window.skipForward = function(pageId, seconds) {
-        // Block skip forward if audio completion is required
-        if (REQUIRE_AUDIO_COMPLETION) {
+        // Block skip forward if the course locks seeking
+        if (AUDIO_SEEK_LOCKED) {
             console.log('[SCORM Audio] Skip forward disabled - audio completion required');
             return;
         }
@@ -1191,6 +1449,11 @@
         
         // Update captions
         updateCaptions(pageId, audio.currentTime);
+        
+        // Remember the position; it is saved with the rest of the progress
+        if (COURSE_SETTINGS.rememberAudioPosition && audio.currentTime > 0) {
+            window.audioPositions[pageId] = Math.floor(audio.currentTime);
+        }
     };
     
     window.onAudioEnded = function(pageId) {
@@ -1200,6 +1463,12 @@
         playIcon.style.display = 'inline';
         pauseIcon.style.display = 'none';
         
+        // Finished narration starts from the beginning next time
+        if (COURSE_SETTINGS.rememberAudioPosition && window.audioPositions[pageId] !== undefined) {
+            delete window.audioPositions[pageId];
+            if (!REQUIRE_AUDIO_COMPLETION) saveProgress();
+        }
+        
         // Track audio completion for navigation blocking
         if (REQUIRE_AUDIO_COMPLETION) {
             window.completedAudio.add(pageId);
@@ -1327,9 +1596,20 @@
     
     // Update progress
     function updateProgress() {
-        const totalPages = COURSE_PAGES.length;
-        const currentIndex = COURSE_PAGES.indexOf(window.currentPage) + 1;
-        const percentage = Math.round((currentIndex / totalPages) * 100);
+        const totalPages = PROGRESS_PAGES.length;
+        // On the credits page every page before it counts as done
+        const currentIndex = window.currentPage === 'credits'
+            ? PROGRESS_PAGES.filter(pageId => COURSE_PAGES.indexOf(pageId) < COURSE_PAGES.indexOf('credits')).length
+            : PROGRESS_PAGES.indexOf(window.currentPage) + 1;
+        let totalWeight = 0;
+        let doneWeight = 0;
+        PROGRESS_PAGES.forEach((pageId, index) => {
+            totalWeight += pageWeight(pageId);
+            if (index < currentIndex) {
+                doneWeight += pageWeight(pageId);
+            }
+        });
+        const percentage = totalWeight > 0 ? Math.round((doneWeight / totalWeight) * 100) : 0;
         
         console.log('[SCORM Navigation] Progress update:', {
             currentIndex,
@@ -1381,6 +1661,115 @@
         });
     }
     
+    // Completion ticks and locks on the course map page, from the same state as the sidebar
+    function updateCourseMap() {
+        const map = document.querySelector('[data-course-map]');
+        if (!map) return;
+        
+        const currentIndex = COURSE_PAGES.indexOf(window.currentPage);
+        let completed = 0;
+        map.querySelectorAll('[data-map-page]').forEach(item => {
+            const pageId = item.dataset.mapPage;
+            const pageIndex = COURSE_PAGES.indexOf(pageId);
+            const isCompleted = window.completedPages.has(pageId);
+            const isLocked = pageIndex > currentIndex && shouldBlockForwardNavigation(currentIndex, pageIndex);
+            if (isCompleted) completed++;
+            
+            item.classList.toggle('completed', isCompleted);
+            item.classList.toggle('locked', isLocked);
+            const status = item.querySelector('[data-map-status]');
+            if (status) {
+                status.textContent = isCompleted ? 'Completed' : isLocked ? 'Locked' : 'Not completed';
+            }
+        });
+        
+        const count = map.querySelector('[data-course-map-count]');
+        if (count) count.textContent = completed;
+    }
+    
+    window.openCourseMapPage = function(pageId) {
+        jumpToPage(pageId);
+    };
+    
+    function introVideoPending() {
+        return COURSE_SETTINGS.introVideoGate && !window.introVideoWatched;
+    }
+    
+    // Track how much of the welcome page's intro video has actually been
+    // played (seeking ahead doesn't count) and lift the gate once it's enough
+    function initializeIntroVideoGate() {
+        const video = document.querySelector('[data-intro-video]');
+        const message = document.querySelector('[data-intro-gate]');
+        if (!video || !introVideoPending()) {
+            if (message) message.hidden = true;
+            return;
+        }
+        
+        const minPercent = Number(video.dataset.introVideo) || 90;
+        const check = () => {
+            if (!introVideoPending() || !(video.duration > 0) || !isFinite(video.duration)) return;
+            
+            let played = 0;
+            for (let i = 0; i < video.played.length; i++) {
+                played += video.played.end(i) - video.played.start(i);
+            }
+            if (video.ended || (played / video.duration) * 100 >= minPercent) {
+                window.introVideoWatched = true;
+                if (message) message.hidden = true;
+                announceToScreenReader('Introduction complete. You can now start the course.');
+                saveProgress();
+                updateNavigationState();
+                updateSidebarNavigationState();
+            }
+        };
+        video.addEventListener('timeupdate', check);
+        video.addEventListener('ended', check);
+    }
+    
+    // Offer returning learners their bookmark instead of jumping straight to it
+    function showResumePrompt(pageId) {
+        const prompt = document.querySelector('[data-resume-prompt]');
+        if (!prompt) return;
+        
+        prompt.hidden = pageId !== 'welcome' || !window.resumeLocation;
+        const pageName = prompt.querySelector('[data-resume-page]');
+        if (pageName && window.resumeLocation) {
+            pageName.textContent = getPageTitle(window.resumeLocation);
+        }
+    }
+    
+    window.resumeCourse = function() {
+        const target = window.resumeLocation;
+        window.resumeLocation = null;
+        showResumePrompt(window.currentPage);
+        if (target) {
+            navigateToPage(target);
+        }
+    };
+    
+    // Clear page progress and answers. Assessment attempts, notes and the
+    // intro video stay, so starting over doesn't reset the retake limit.
+    window.restartCourse = function() {
+        window.resumeLocation = null;
+        window.completedPages = new Set([window.currentPage]);
+        window.answeredQuestions = {};
+        window.completedAudio = new Set();
+        
+        document.querySelectorAll('.nav-item.completed').forEach(item => {
+            item.classList.remove('completed');
+            const check = item.querySelector('.completion-check');
+            if (check) check.remove();
+        });
+        
+        showResumePrompt(window.currentPage);
+        updateNavigationState();
+        updateSidebarNavigationState();
+        updateProgress();
+        updateSidebarCompletionState();
+        saveProgress();
+        announceToScreenReader('Your progress has been reset. Starting the course from the beginning.');
+    };
+    
     // Restore answered questions on page load
     function restoreAnsweredQuestions(pageId) {
         console.log('[SCORM Progress] Restoring answers for page:', pageId);
@@ -1481,6 +1870,28 @@
                     });
                 }
                 
+                // Restore the intro video gate
+                if (data.introVideoWatched === true) {
+                    window.introVideoWatched = true;
+                }
+                
+                // Restore narration positions
+                if (COURSE_SETTINGS.rememberAudioPosition && data.audioPositions && typeof data.audioPositions === 'object') {
+                    window.audioPositions = data.audioPositions;
+                }
+                
+                // Restore runtime analytics
+                if (COURSE_SETTINGS.runtimeAnalytics && data.activity && typeof data.activity === 'object') {
+                    window.pageActivity = data.activity;
+                }
+                window.audioBlocked = Number(data.audioBlocked) || 0;
+                
+                // Restore learner notes
+                if (data.notes && typeof data.notes === 'object') {
+                    window.learnerNotes = data.notes;
+                    console.log('[SCORM Progress] Restored notes for', Object.keys(data.notes).length, 'pages');
+                }
+                
                 // Restore session data for time tracking
                 if (data.sessionData) {
                     window.sessionData.totalTimeSpent = data.sessionData.totalTimeSpent || 0;
@@ -1518,9 +1929,15 @@
         
         window.hasRestoredProgress = progressLoaded;
         
-        // Show resume notification if progress was loaded
+        // Show resume notification if progress was loaded, or hold the bookmark
+        // on the welcome page until the learner chooses to resume or start over
         if (progressLoaded && savedLocation && savedLocation !== 'welcome') {
-            showResumeNotification(savedLocation);
+            if (COURSE_SETTINGS.resumePrompt && COURSE_PAGES.includes(savedLocation)) {
+                window.resumeLocation = savedLocation;
+                window.currentPage = 'welcome';
+            } else {
+                showResumeNotification(savedLocation);
+            }
         }
         
         return progressLoaded;
@@ -1552,7 +1969,7 @@
     
     // Helper function: Check if all pages have been viewed
     function checkAllPagesViewed() {
-        const requiredPages = COURSE_PAGES.filter(page => page !== 'assessment'); // Don't require assessment viewing for this check
+        const requiredPages = COURSE_PAGES.filter(page => page !== 'assessment' && page !== 'credits' && page !== 'transcript'); // Don't require assessment, credits or transcript viewing for this check
         const viewedAll = requiredPages.every(page => window.completedPages.has(page));
         console.log('[SCORM Completion] All pages viewed:', viewedAll, 'Required:', requiredPages.length, 'Completed:', window.completedPages.size);
         return viewedAll;
@@ -1578,8 +1995,23 @@
         return timeMet;
     }
     
+    // JSON as written to cmi.suspend_data. Profiles for LMSs that garble
+    // non-ASCII text get it escaped, which JSON.parse reads back unchanged.
+    function encodeSuspendData(value) {
+        const json = JSON.stringify(value);
+        if (!SUSPEND_DATA.asciiOnly) return json;
+        
+        let encoded = '';
+        for (let i = 0; i < json.length; i++) {
+            const code = json.charCodeAt(i);
+            encoded += code > 0x7e ? '\\u' + code.toString(16).padStart(4, '0') : json[i];
+        }
+        return encoded;
+    }
+    
     // Save all progress to SCORM
     function saveProgress() {
+        if (sessionFinished) return;
         console.log('[SCORM Progress] Saving progress to SCORM');
         
         // Save current page location
@@ -1602,13 +2034,28 @@
             attemptHistory: window.assessmentData.scores,
             lastAnswers: window.assessmentData.lastAnswers,
             sessionData: window.sessionData,
+            introVideoWatched: window.introVideoWatched,
+            audioBlocked: window.audioBlocked,
             timestamp: new Date().toISOString(),
-            version: '1.0'
+            version: SUSPEND_DATA.version
         };
+        if (COURSE_SETTINGS.rememberAudioPosition) {
+            progressData.audioPositions = window.audioPositions;
+        }
+        if (COURSE_SETTINGS.runtimeAnalytics) {
+            progressData.activity = fitPageActivity(SUSPEND_DATA.activityMaxLength);
+        }
+        
+        // Notes get whatever room the progress data leaves, up to their own cap
+        if (COURSE_SETTINGS.learnerNotes) {
+            const room = SUSPEND_DATA.maxLength - encodeSuspendData(progressData).length - ',"notes":'.length;
+            progressData.notes = fitLearnerNotes(Math.min(SUSPEND_DATA.notesMaxLength, room));
+            updateLearnerNotesStatus();
+        }
         
         // Save suspend data
         try {
-            const suspendDataStr = JSON.stringify(progressData);
+            const suspendDataStr = encodeSuspendData(progressData);
             SafeSCORM.setValue('cmi.suspend_data', suspendDataStr);
             console.log('[SCORM Progress] Saved progress data:', {
                 completedPages: progressData.completedPages.length,
@@ -1657,6 +2104,13 @@
         showAutoSaveIndicator();
     }
     
+    // Heartbeat: save and commit on a timer, independent of the visible auto-save
+    function initializeCommitHeartbeat() {
+        if (COMMIT_SETTINGS.heartbeatMs > 0 && !window.commitHeartbeatTimer) {
+            window.commitHeartbeatTimer = setInterval(saveProgress, COMMIT_SETTINGS.heartbeatMs);
+        }
+    }
+    
     function showAutoSaveIndicator() {
         // Create or update auto-save indicator
         let indicator = document.getElementById('auto-save-indicator');
@@ -1965,6 +2419,11 @@
             return;
         }
         
+        // The open lightbox handles its own keys
+        if (isLightboxOpen()) {
+            return;
+        }
+        
         const key = event.key.toLowerCase();
         const ctrlKey = event.ctrlKey || event.metaKey;
         const altKey = event.altKey;
@@ -2305,33 +2764,22 @@
         }
     }
     
+    // Author-supplied labels going into the print HTML
+    function escapeHtml(text) {
+        return String(text)
+            .split('&').join('&amp;')
+            .split('<').join('&lt;')
+            .split('>').join('&gt;')
+            .split('"').join('&quot;')
+            .split("'").join('&#39;');
+    }
+
+    // In menu order, so the assessment sits where assessment_after_topic put it
     function generateTableOfContents() {
-        const pages = [];
-        
-        // Add welcome page
-        pages.push({ title: 'Welcome', type: 'page' });
-        
-        // Add learning objectives if available
-        if (COURSE_SETTINGS.hasObjectives) {
-            pages.push({ title: 'Learning Objectives', type: 'page' });
-        }
-        
-        // Add topic pages
-        COURSE_PAGES.forEach(pageId => {
-            if (pageId.startsWith('topic-')) {
-                const pageTitle = getPageTitle(pageId);
-                pages.push({ title: pageTitle, type: 'topic' });
-            }
-        });
-        
-        // Add assessment
-        pages.push({ title: 'Assessment', type: 'assessment' });
-        
-        // Generate TOC HTML
-        const tocItems = pages.map(page => {
-            const icon = page.type === 'topic' ? '📖' : 
-                        page.type === 'assessment' ? '✅' : '📋';
-            return `<li>${icon} ${page.title}</li>`;
+        const tocItems = COURSE_PAGES.map(pageId => {
+            const icon = pageId.startsWith('topic-') ? '📖' :
+                        pageId === 'assessment' ? '✅' : '📋';
+            return `<li>${icon} ${escapeHtml(getPageTitle(pageId))}</li>`;
         }).join('');
         
         return `
@@ -2355,7 +2803,7 @@
                 
                 const pageHtml = `
                     <div class="page-content">
-                        <h2>${pageTitle}</h2>
+                        <h2>${escapeHtml(pageTitle)}</h2>
                         ${pageContent}
                     </div>
                     <div class="page-separator"></div>
@@ -2366,7 +2814,7 @@
                 console.warn('[SCORM] Could not load content for page:', pageId, error);
                 compiledPages.push(`
                     <div class="page-content">
-                        <h2>${getPageTitle(pageId)}</h2>
+                        <h2>${escapeHtml(getPageTitle(pageId))}</h2>
                         <p><em>Content could not be loaded for printing.</em></p>
                     </div>
                     <div class="page-separator"></div>
@@ -2403,8 +2851,8 @@
     // Show notification when resuming progress
     function showResumeNotification(savedLocation) {
         const pageTitle = getPageTitle(savedLocation);
-        const completedCount = window.completedPages.size;
-        const totalPages = COURSE_PAGES.length;
+        const completedCount = PROGRESS_PAGES.filter(pageId => window.completedPages.has(pageId)).length;
+        const totalPages = PROGRESS_PAGES.length;
         const percentage = Math.round((completedCount / totalPages) * 100);
         
         const message = `Welcome back! Resuming from: ${pageTitle} (${percentage}% complete)`;
@@ -2423,7 +2871,7 @@
                             ${message}
                         </div>
                     </div>
-                    <button onclick="this.parentElement.remove()" class="alert-close">×</button>
+                    <button data-action="dismiss-alert" class="alert-close">×</button>
                 `;
                 alertContainer.appendChild(alert);
                 
@@ -2437,17 +2885,342 @@
         }, 1000); // Delay to ensure page is loaded
     }
     
+    // Runtime analytics - visits and interactions per page, kept in suspend_data
+    // for support staff to read back with the builder's suspend_data decoder
+    function recordPageActivity(pageId, visits, interactions) {
+        if (!COURSE_SETTINGS.runtimeAnalytics || !pageId) return;
+        
+        const counts = window.pageActivity[pageId] || [0, 0];
+        window.pageActivity[pageId] = [counts[0] + visits, counts[1] + interactions];
+    }
+    
+    function initializeRuntimeAnalytics() {
+        if (!COURSE_SETTINGS.runtimeAnalytics) return;
+        
+        // Clicks on controls and media starting to play count as interactions
+        const content = document.getElementById('content-container');
+        if (!content) return;
+        content.addEventListener('click', (event) => {
+            if (event.target.closest('button, input, select, textarea, a, summary, [role="button"]')) {
+                recordPageActivity(window.currentPage, 0, 1);
+            }
+        });
+        content.addEventListener('play', () => recordPageActivity(window.currentPage, 0, 1), true);
+    }
+    
+    // Activity that fits in `budget` characters of JSON, keeping the most visited pages
+    function fitPageActivity(budget) {
+        const entries = Object.entries(window.pageActivity)
+            .sort((a, b) => b[1][0] - a[1][0]);
+        let fitted = Object.fromEntries(entries);
+        while (entries.length > 0 && encodeSuspendData(fitted).length > budget) {
+            entries.pop();
+            fitted = Object.fromEntries(entries);
+        }
+        return fitted;
+    }
+    
     // Get human-readable page title
+    // Learner Notes - one note per page, saved in suspend_data with progress
+    function initializeLearnerNotes() {
+        if (!COURSE_SETTINGS.learnerNotes) return;
+        
+        const textarea = document.getElementById('learner-notes-text');
+        if (!textarea) return;
+        
+        let saveTimeout = null;
+        textarea.addEventListener('input', () => {
+            if (!window.currentPage) return;
+            
+            if (textarea.value.trim()) {
+                window.learnerNotes[window.currentPage] = textarea.value;
+            } else {
+                delete window.learnerNotes[window.currentPage];
+            }
+            updateLearnerNotesStatus();
+            
+            // Save once the learner pauses typing
+            clearTimeout(saveTimeout);
+            saveTimeout = setTimeout(saveProgress, 2000);
+        });
+    }
+    
+    function getLearnerNotesPageTitle(pageId) {
+        const navItem = document.querySelector(`.nav-item[data-page="${pageId}"]`);
+        return navItem && navItem.textContent.trim() ? navItem.textContent.trim() : getPageTitle(pageId);
+    }
+    
+    function showLearnerNotesForPage(pageId) {
+        const textarea = document.getElementById('learner-notes-text');
+        if (!textarea) return;
+        
+        textarea.value = window.learnerNotes[pageId] || '';
+        const titleEl = document.getElementById('learner-notes-page-title');
+        if (titleEl) {
+            titleEl.textContent = getLearnerNotesPageTitle(pageId);
+        }
+        updateLearnerNotesStatus();
+    }
+    
+    function updateLearnerNotesStatus() {
+        const used = encodeSuspendData(window.learnerNotes).length;
+        const remaining = Math.max(0, SUSPEND_DATA.notesMaxLength - used);
+        
+        const remainingEl = document.getElementById('learner-notes-remaining');
+        if (remainingEl) {
+            remainingEl.textContent = `About ${remaining} characters of note space left`;
+        }
+        const warningEl = document.getElementById('learner-notes-warning');
+        if (warningEl) {
+            warningEl.hidden = !window.learnerNotesTruncated && used <= SUSPEND_DATA.notesMaxLength;
+        }
+    }
+    
+    // Notes that fit in `budget` characters of JSON. Notes that don't fit are
+    // shortened for the LMS; the full text stays available for export.
+    function fitLearnerNotes(budget) {
+        const fitted = {};
+        let used = 2; // {}
+        let truncated = false;
+        
+        Object.entries(window.learnerNotes).forEach(([pageId, text]) => {
+            // Key, colon, quotes and separating comma
+            const room = budget - used - encodeSuspendData(pageId).length - 4;
+            if (room <= 0) {
+                truncated = true;
+                return;
+            }
+            
+            let kept = text;
+            if (encodeSuspendData(kept).length - 2 > room) {
+                truncated = true;
+                kept = kept.slice(0, room);
+                while (kept && encodeSuspendData(kept).length - 2 > room) {
+                    kept = kept.slice(0, -1);
+                }
+            }
+            if (kept) {
+                fitted[pageId] = kept;
+                used = encodeSuspendData(fitted).length;
+            }
+        });
+        
+        window.learnerNotesTruncated = truncated;
+        return fitted;
+    }
+    
+    window.hasLearnerNotes = function() {
+        return COURSE_SETTINGS.learnerNotes && Object.keys(window.learnerNotes).length > 0;
+    };
+    
+    window.toggleLearnerNotes = function() {
+        const panel = document.getElementById('learner-notes-panel');
+        if (!panel) return;
+        
+        panel.hidden = !panel.hidden;
+        document.querySelectorAll('[data-action="toggle-notes"][aria-controls]').forEach(button => {
+            button.setAttribute('aria-expanded', String(!panel.hidden));
+        });
+        if (!panel.hidden) {
+            showLearnerNotesForPage(window.currentPage);
+            const textarea = document.getElementById('learner-notes-text');
+            if (textarea) textarea.focus();
+        }
+    };
+    
+    // Download all notes as a plain text file, in course page order
+    window.exportLearnerNotes = function() {
+        const pageIds = COURSE_PAGES.filter(pageId => window.learnerNotes[pageId])
+            .concat(Object.keys(window.learnerNotes).filter(pageId => !COURSE_PAGES.includes(pageId)));
+        const title = document.title || 'Course';
+        const sections = pageIds.map(pageId =>
+            `${getLearnerNotesPageTitle(pageId)}\n${'-'.repeat(40)}\n${window.learnerNotes[pageId].trim()}`
+        );
+        const text = `${title} - My Notes\n${new Date().toLocaleString()}\n\n${sections.join('\n\n') || 'No notes yet.'}\n`;
+        
+        const blob = new Blob([text], { type: 'text/plain;charset=utf-8' });
+        const url = URL.createObjectURL(blob);
+        const link = document.createElement('a');
+        link.href = url;
+        link.download = `${title.replace(new RegExp('[^a-z0-9]+', 'gi'), '-').replace(new RegExp('^-+|-+$', 'g'), '').toLowerCase() || 'course'}-notes.txt`;
+        document.body.appendChild(link);
+        link.click();
+        link.remove();
+        setTimeout(() => URL.revokeObjectURL(url), 1000);
+        
+        announceToScreenReader('Notes exported');
+    };
+    
+    // Course Search - matches queries against the embedded index
+    function normalizeSearchText(text) {
+        return text.normalize('NFD').replace(new RegExp('[\\u0300-\\u036f]', 'g'), '').toLowerCase();
+    }
+    
+    // Pages containing every term, title matches first, then course order
+    function searchCourse(query) {
+        const terms = normalizeSearchText(query).split(new RegExp('\\s+')).filter(Boolean);
+        if (terms.length === 0) return [];
+        
+        return COURSE_SEARCH_INDEX
+            .filter(entry => COURSE_PAGES.includes(entry.page_id))
+            .map(entry => {
+                const title = normalizeSearchText(entry.title);
+                const text = normalizeSearchText(entry.text);
+                let score = 0;
+                for (const term of terms) {
+                    const inTitle = title.includes(term);
+                    const inText = text.includes(term);
+                    if (!inTitle && !inText) return null;
+                    score += (inTitle ? 10 : 0) + (inText ? 1 : 0);
+                }
+                return { entry, score };
+            })
+            .filter(Boolean)
+            .sort((a, b) => b.score - a.score ||
+                COURSE_PAGES.indexOf(a.entry.page_id) - COURSE_PAGES.indexOf(b.entry.page_id))
+            .map(result => result.entry);
+    }
+    
+    // A short excerpt of the page text around the first matching term
+    function searchSnippet(text, terms) {
+        const lower = text.toLowerCase();
+        const found = terms.map(term => lower.indexOf(term)).filter(index => index >= 0).sort((a, b) => a - b);
+        const at = found.length > 0 ? found[0] : 0;
+        const start = Math.max(0, at - 40);
+        const end = Math.min(text.length, at + 120);
+        return (start > 0 ? '…' : '') + text.slice(start, end) + (end < text.length ? '…' : '');
+    }
+    
+    // Append `text` to `parent` with matching terms wrapped in <mark>
+    function appendHighlighted(parent, text, terms) {
+        const lower = text.toLowerCase();
+        let pos = 0;
+        
+        while (pos < text.length) {
+            let next = -1;
+            let length = 0;
+            terms.forEach(term => {
+                const at = lower.indexOf(term, pos);
+                if (at !== -1 && (next === -1 || at < next)) {
+                    next = at;
+                    length = term.length;
+                }
+            });
+            
+            if (next === -1) {
+                parent.appendChild(document.createTextNode(text.slice(pos)));
+                break;
+            }
+            if (next > pos) {
+                parent.appendChild(document.createTextNode(text.slice(pos, next)));
+            }
+            const mark = document.createElement('mark');
+            mark.textContent = text.slice(next, next + length);
+            parent.appendChild(mark);
+            pos = next + length;
+        }
+    }
+    
+    function renderSearchResults(query) {
+        const container = document.getElementById('course-search-results');
+        if (!container) return;
+        
+        container.innerHTML = '';
+        if (!query.trim()) {
+            container.hidden = true;
+            return;
+        }
+        
+        const results = searchCourse(query).slice(0, 10);
+        const terms = query.toLowerCase().split(new RegExp('\\s+')).filter(Boolean);
+        
+        if (results.length === 0) {
+            const empty = document.createElement('p');
+            empty.className = 'course-search-empty';
+            empty.textContent = 'No pages match your search.';
+            container.appendChild(empty);
+        }
+        
+        results.forEach(entry => {
+            const button = document.createElement('button');
+            button.type = 'button';
+            button.className = 'course-search-result';
+            button.dataset.action = 'open-search-result';
+            button.dataset.page = entry.page_id;
+            
+            const title = document.createElement('span');
+            title.className = 'course-search-result-title';
+            appendHighlighted(title, entry.title, terms);
+            button.appendChild(title);
+            
+            if (entry.text) {
+                const snippet = document.createElement('span');
+                snippet.className = 'course-search-result-snippet';
+                appendHighlighted(snippet, searchSnippet(entry.text, terms), terms);
+                button.appendChild(snippet);
+            }
+            container.appendChild(button);
+        });
+        
+        container.hidden = false;
+        announceToScreenReader(results.length === 1 ? '1 page found' : `${results.length} pages found`);
+    }
+    
+    function closeSearchResults() {
+        const container = document.getElementById('course-search-results');
+        if (container) {
+            container.hidden = true;
+        }
+    }
+    
+    function initializeCourseSearch() {
+        const input = document.getElementById('course-search-input');
+        if (!input) return;
+        
+        let searchTimeout = null;
+        input.addEventListener('input', () => {
+            clearTimeout(searchTimeout);
+            searchTimeout = setTimeout(() => renderSearchResults(input.value), 150);
+        });
+        input.addEventListener('keydown', (event) => {
+            if (event.key === 'Escape') {
+                input.value = '';
+                closeSearchResults();
+            } else if (event.key === 'Enter') {
+                const first = document.querySelector('.course-search-result');
+                if (first) first.click();
+            }
+        });
+        
+        // Close the results when clicking anywhere else
+        document.addEventListener('click', (event) => {
+            if (!event.target.closest('.course-search')) {
+                closeSearchResults();
+            }
+        });
+    }
+    
+    window.openSearchResult = function(pageId) {
+        closeSearchResults();
+        jumpToPage(pageId);
+    };
+    
     function getPageTitle(pageId) {
+        if (MENU_TITLES[pageId]) {
+            return MENU_TITLES[pageId];
+        }
         const titles = {
             'welcome': 'Welcome',
             'objectives': 'Learning Objectives',
-            'assessment': 'Assessment'
+            'course-map': 'Course Map',
+            'assessment': 'Assessment',
+            'transcript': 'Transcript',
+            'credits': 'Credits'
         };
         
         // For topics, capitalize and format
         if (pageId.startsWith('topic-')) {
-            return pageId.replace('topic-', 'Topic ').replace(/-/g, ' ');
+            return pageId.replace('topic-', 'Topic ').split('-').join(' ');
         }
         
         return titles[pageId] || pageId;
@@ -2488,6 +3261,312 @@
         navigateToPage(window.currentPage);
     }
     
+    // Assessment timers. The assessment template emits their configuration as
+    // data attributes; the clock keeps running while the learner is on other pages.
+    window.assessmentTimer = null;
+    
+    // When each assessment question was shown and last answered, for interaction latency
+    window.assessmentQuestionTimes = [];
+    
+    function getAssessmentQuestions() {
+        return Array.from(document.querySelectorAll('.question-container'));
+    }
+    
+    function initializeAssessmentTimer() {
+        const questions = getAssessmentQuestions();
+        if (window.assessmentQuestionTimes.length !== questions.length) {
+            const now = Date.now();
+            window.assessmentQuestionTimes = questions.map(() => ({ shownAt: now, answeredAt: null }));
+        }
+        questions.forEach((container, index) => {
+            container.addEventListener('change', () => {
+                window.assessmentQuestionTimes[index].answeredAt = Date.now();
+            });
+        });
+        
+        const config = document.querySelector('.assessment-timer');
+        if (!config) return;
+        
+        const timer = window.assessmentTimer;
+        if (!timer || (timer.finished && timer.canRetake)) {
+            startAssessmentTimer(config);
+        }
+        resumeAssessmentTimer();
+    }
+    
+    function startAssessmentTimer(config) {
+        const now = Date.now();
+        window.assessmentTimer = {
+            overallSeconds: parseInt(config.dataset.overallSeconds, 10) || 0,
+            overallWarningSeconds: parseInt(config.dataset.overallWarningSeconds, 10) || 0,
+            questionSeconds: parseInt(config.dataset.questionSeconds, 10) || 0,
+            questionWarningSeconds: parseInt(config.dataset.questionWarningSeconds, 10) || 0,
+            autoSubmit: config.dataset.autoSubmit !== 'false',
+            startedAt: now,
+            questionIndex: 0,
+            questionStartedAt: now,
+            lockedQuestions: [],
+            warned: {},
+            expired: false,
+            finished: false,
+            canRetake: false,
+            interval: null
+        };
+        window.assessmentQuestionTimes.forEach(times => {
+            times.shownAt = now;
+            times.answeredAt = null;
+        });
+        console.log('[SCORM Assessment] Timer started:', window.assessmentTimer);
+    }
+    
+    // Re-applies the timer state to a freshly loaded assessment page
+    function resumeAssessmentTimer() {
+        const timer = window.assessmentTimer;
+        if (!timer || timer.finished) return;
+        
+        const questions = getAssessmentQuestions();
+        questions.forEach(container => {
+            container.classList.remove('timed-out');
+            delete container.dataset.timedOut;
+            container.querySelectorAll('input').forEach(input => {
+                input.disabled = false;
+            });
+        });
+        timer.lockedQuestions.forEach(index => lockAssessmentQuestion(questions[index]));
+        
+        if (timer.expired) {
+            showAllAssessmentQuestions();
+            return;
+        }
+        if (timer.questionSeconds > 0) {
+            showAssessmentQuestion(questions, timer.questionIndex);
+        }
+        
+        clearInterval(timer.interval);
+        timer.interval = setInterval(tickAssessmentTimer, 1000);
+        tickAssessmentTimer();
+    }
+    
+    function suspendAssessmentTimer() {
+        if (window.assessmentTimer) {
+            clearInterval(window.assessmentTimer.interval);
+            window.assessmentTimer.interval = null;
+        }
+    }
+    
+    // Stops the countdown once an attempt has been scored
+    function finishAssessmentTimer(canRetake) {
+        const timer = window.assessmentTimer;
+        if (!timer) return;
+        
+        suspendAssessmentTimer();
+        timer.finished = true;
+        timer.canRetake = canRetake;
+        showAllAssessmentQuestions();
+    }
+    
+    function tickAssessmentTimer() {
+        const timer = window.assessmentTimer;
+        if (!timer || timer.finished || timer.expired) return;
+        const now = Date.now();
+        
+        if (timer.overallSeconds > 0) {
+            const remaining = timer.overallSeconds - Math.floor((now - timer.startedAt) / 1000);
+            updateAssessmentCountdown('assessment-time-remaining', remaining, timer.overallWarningSeconds, 'overall');
+            if (remaining <= 0) {
+                expireAssessmentTimer();
+                return;
+            }
+        }
+        
+        if (timer.questionSeconds > 0) {
+            const remaining = timer.questionSeconds - Math.floor((now - timer.questionStartedAt) / 1000);
+            updateAssessmentCountdown('question-time-remaining', remaining, timer.questionWarningSeconds, `question-${timer.questionIndex}`);
+            if (remaining <= 0) {
+                advanceAssessmentQuestion(true);
+            }
+        }
+    }
+    
+    function updateAssessmentCountdown(elementId, remaining, warningSeconds, warningKey) {
+        const timer = window.assessmentTimer;
+        const seconds = Math.max(0, remaining);
+        const element = document.getElementById(elementId);
+        const display = element ? element.closest('.time-limit-display') : null;
+        
+        if (element) {
+            element.textContent = `${Math.floor(seconds / 60)}:${(seconds % 60).toString().padStart(2, '0')}`;
+        }
+        
+        const inWarning = warningSeconds > 0 && seconds <= warningSeconds;
+        if (display) {
+            display.classList.toggle('warning', inWarning);
+        }
+        if (inWarning && seconds > 0 && !timer.warned[warningKey]) {
+            timer.warned[warningKey] = true;
+            const message = warningKey === 'overall'
+                ? `${seconds} seconds left to finish the assessment.`
+                : `${seconds} seconds left for this question.`;
+            showAssessmentTimerAlert(message);
+            announceToScreenReader(message, 'assertive');
+        }
+    }
+    
+    function showAssessmentTimerAlert(message) {
+        const alertContainer = document.getElementById('scorm-alert-container');
+        if (!alertContainer) return;
+        
+        const alert = document.createElement('div');
+        alert.className = 'scorm-alert scorm-alert-warning';
+        alert.textContent = message;
+        alertContainer.appendChild(alert);
+        
+        // Auto-remove after 5 seconds
+        setTimeout(() => {
+            if (alert.parentElement) {
+                alert.remove();
+            }
+        }, 5000);
+    }
+    
+    // Per-question timing shows one question at a time
+    function showAssessmentQuestion(questions, index) {
+        questions.forEach((container, i) => {
+            container.style.display = i === index ? '' : 'none';
+        });
+        
+        const isLast = index >= questions.length - 1;
+        const nextButton = document.querySelector('.next-assessment-question');
+        if (nextButton) {
+            nextButton.style.display = isLast ? 'none' : '';
+        }
+        const submitButton = document.querySelector('.submit-assessment');
+        if (submitButton) {
+            submitButton.style.display = isLast ? '' : 'none';
+        }
+    }
+    
+    function showAllAssessmentQuestions() {
+        getAssessmentQuestions().forEach(container => {
+            container.style.display = '';
+        });
+        const nextButton = document.querySelector('.next-assessment-question');
+        if (nextButton) {
+            nextButton.style.display = 'none';
+        }
+    }
+    
+    // A question whose time ran out keeps its answer (or lack of one)
+    function lockAssessmentQuestion(container) {
+        if (!container) return;
+        container.classList.add('timed-out');
+        container.dataset.timedOut = 'true';
+        container.querySelectorAll('input').forEach(input => {
+            input.disabled = true;
+        });
+    }
+    
+    function advanceAssessmentQuestion(timedOut) {
+        const timer = window.assessmentTimer;
+        if (!timer || timer.finished || timer.expired) return;
+        
+        const questions = getAssessmentQuestions();
+        const index = timer.questionIndex;
+        if (timedOut) {
+            lockAssessmentQuestion(questions[index]);
+            timer.lockedQuestions.push(index);
+        }
+        
+        if (index >= questions.length - 1) {
+            if (timedOut) {
+                expireAssessmentTimer();
+            }
+            return;
+        }
+        
+        timer.questionIndex = index + 1;
+        timer.questionStartedAt = Date.now();
+        const times = window.assessmentQuestionTimes[timer.questionIndex];
+        if (times) {
+            times.shownAt = timer.questionStartedAt;
+        }
+        showAssessmentQuestion(questions, timer.questionIndex);
+    }
+    
+    window.nextAssessmentQuestion = function() {
+        advanceAssessmentQuestion(false);
+    };
+    
+    function expireAssessmentTimer() {
+        const timer = window.assessmentTimer;
+        if (!timer || timer.finished || timer.expired) return;
+        
+        console.log('[SCORM Assessment] Assessment time expired');
+        suspendAssessmentTimer();
+        timer.expired = true;
+        getAssessmentQuestions().forEach((container, index) => {
+            lockAssessmentQuestion(container);
+            if (!timer.lockedQuestions.includes(index)) {
+                timer.lockedQuestions.push(index);
+            }
+        });
+        showAllAssessmentQuestions();
+        
+        if (timer.autoSubmit) {
+            showAssessmentTimerAlert('Time is up. Your answers have been submitted.');
+            window.submitAssessment();
+        } else {
+            const submitButton = document.querySelector('.submit-assessment');
+            if (submitButton) {
+                submitButton.style.display = '';
+            }
+            showAssessmentTimerAlert('Time is up. Submit the assessment to record your answers.');
+        }
+    }
+    
+    // SCORM 1.2 timespan (HHHH:MM:SS.SS) or SCORM 2004 duration (PTnS)
+    function formatInteractionLatency(ms, is2004) {
+        const centiseconds = Math.max(0, Math.round(ms / 10));
+        if (is2004) {
+            return `PT${(centiseconds / 100).toFixed(2)}S`;
+        }
+        const hours = Math.floor(centiseconds / 360000);
+        const minutes = Math.floor((centiseconds % 360000) / 6000);
+        const seconds = (centiseconds % 6000) / 100;
+        return `${hours.toString().padStart(4, '0')}:${minutes.toString().padStart(2, '0')}:${seconds.toFixed(2).padStart(5, '0')}`;
+    }
+    
+    // Report each answer as a cmi.interactions entry, with the time the
+    // learner took on the question as its latency
+    function recordAssessmentInteractions(questions, answers) {
+        const is2004 = window.UniversalSCORM && window.UniversalSCORM.version === '2004';
+        const first = parseInt(SafeSCORM.getValue('cmi.interactions._count'), 10) || 0;
+        const attempt = window.assessmentData.attempts;
+        const now = Date.now();
+        
+        Array.from(questions).forEach((container, index) => {
+            const answer = answers[index];
+            const times = window.assessmentQuestionTimes[index] || { shownAt: now, answeredAt: null };
+            const options = Array.from(container.querySelectorAll('input[type="radio"]'));
+            const selectedIndex = answer ? options.findIndex(input => input.value === answer.selected) : -1;
+            const prefix = `cmi.interactions.${first + index}`;
+            
+            SafeSCORM.setValue(`${prefix}.id`, `assessment-attempt${attempt}-q${index + 1}`);
+            SafeSCORM.setValue(`${prefix}.type`, 'choice');
+            if (selectedIndex >= 0) {
+                const response = String.fromCharCode(97 + selectedIndex);
+                SafeSCORM.setValue(is2004 ? `${prefix}.learner_response` : `${prefix}.student_response`, response);
+            }
+            SafeSCORM.setValue(`${prefix}.result`, answer && answer.isCorrect ? 'correct' : (is2004 ? 'incorrect' : 'wrong'));
+            SafeSCORM.setValue(`${prefix}.latency`, formatInteractionLatency((times.answeredAt || now) - times.shownAt, is2004));
+            
+            // The next attempt is timed from now
+            times.shownAt = now;
+            times.answeredAt = null;
+        });
+        SafeSCORM.commit();
+    }
+    
     // Submit assessment
     window.submitAssessment = async function() {
         console.log('[SCORM Navigation] Submitting assessment');
@@ -2530,7 +3609,8 @@
         const questions = document.querySelectorAll('.question-container');
         let score = 0;
         let answered = 0;
-        const passingScore = {{pass_mark}};
+        let timedOut = 0;
+        const passingScore = COURSE_SETTINGS.passMark;
         const currentAnswers = {};
         
         // Increment attempt counter and record timestamp
@@ -2600,6 +3680,7 @@
                             feedbackElement.textContent = actualIncorrectFeedback;
                             feedbackElement.className = 'feedback incorrect';
                         }
+                        showFeedbackMedia(feedbackElement, isCorrect ? 'correct' : 'incorrect');
                     } else {
                         // Just indicate right/wrong for first attempt
                         if (isCorrect) {
@@ -2612,13 +3693,24 @@
                     }
                     feedbackElement.style.display = 'block';
                 }
+            } else if (container.dataset.timedOut === 'true') {
+                // Time ran out before this question was answered - counts as wrong
+                timedOut++;
+                currentAnswers[index] = { selected: '', correct: container.dataset.correctAnswer, isCorrect: false };
+                
+                const feedbackElement = document.getElementById(`assessment-feedback-${index}`);
+                if (feedbackElement) {
+                    feedbackElement.textContent = '✗ Not answered in time';
+                    feedbackElement.className = 'feedback incorrect';
+                    feedbackElement.style.display = 'block';
+                }
             }
         });
         
         // Store answers for potential review
         window.assessmentData.lastAnswers = currentAnswers;
         
-        if (answered < questions.length) {
+        if (answered + timedOut < questions.length) {
             // Decrement attempt counter since this isn't a valid submission
             window.assessmentData.attempts--;
             
@@ -2646,6 +3738,13 @@
         const percentage = Math.round((score / questions.length) * 100);
         console.log('[SCORM Navigation] Assessment score:', percentage);
         
+        recordAssessmentInteractions(questions, currentAnswers);
+        finishAssessmentTimer(percentage < passingScore && COURSE_SETTINGS.allowRetake);
+        announceToScreenReader(
+            `Assessment submitted. You scored ${percentage}%. ${percentage >= passingScore ? 'Passed.' : 'Not yet passed.'}`,
+            'assertive'
+        );
+        
         // Report score to SCORM using SafeSCORM wrapper
         console.log('[SCORM] Starting score submission');
         console.log('[SCORM] Score to report:', percentage);
@@ -2795,6 +3894,12 @@
         }
         
         // Completion tracking already handled above
+        
+        // Time the resubmission as a fresh attempt
+        if (window.assessmentTimer && window.assessmentTimer.canRetake && submitButton &&
+            submitButton.style.display !== 'none' && !submitButton.disabled) {
+            initializeAssessmentTimer();
+        }
     };
     
     // Retry assessment function
@@ -2814,6 +3919,7 @@
             feedbacks.forEach(feedback => {
                 feedback.style.display = 'none';
                 feedback.textContent = '';
+                showFeedbackMedia(feedback, null);
             });
         } else {
             // Keep incorrect answers visible, only clear correct ones
@@ -2828,6 +3934,7 @@
                         const feedback = document.getElementById(`assessment-feedback-${index}`);
                         if (feedback) {
                             feedback.style.display = 'none';
+                            showFeedbackMedia(feedback, null);
                         }
                     }
                 }
@@ -2851,7 +3958,16 @@
         window.scrollTo(0, 0);
     };
     
-    // Lightbox functionality
+    // Lightbox: enlarged view of a page image, captioned with its alt text.
+    // Clicking the enlarged image toggles between fitting the screen and its
+    // actual size; Escape closes it and focus returns to the image's trigger.
+    let lightboxOpener = null;
+    
+    function isLightboxOpen() {
+        const lightbox = document.getElementById('lightbox-modal');
+        return !!lightbox && lightbox.classList.contains('active');
+    }
+    
     window.openLightbox = function(src, alt) {
         console.log('[SCORM Lightbox] Opening lightbox:', { src, alt });
         
@@ -2861,14 +3977,20 @@
             lightbox = document.createElement('div');
             lightbox.id = 'lightbox-modal';
             lightbox.className = 'lightbox-modal';
+            lightbox.setAttribute('role', 'dialog');
+            lightbox.setAttribute('aria-modal', 'true');
+            lightbox.setAttribute('aria-label', 'Enlarged image');
             lightbox.innerHTML = `
-                <div class="lightbox-overlay" onclick="window.closeLightbox()"></div>
+                <div class="lightbox-overlay" data-action="close-lightbox"></div>
                 <div class="lightbox-content">
-                    <button class="lightbox-close" onclick="window.closeLightbox()">×</button>
-                    <img id="lightbox-image" src="" alt="" />
-                    <div class="lightbox-caption"></div>
+                    <button type="button" class="lightbox-close" data-action="close-lightbox" aria-label="Close enlarged image">×</button>
+                    <button type="button" class="lightbox-zoom" data-action="toggle-lightbox-zoom" aria-pressed="false" aria-label="Show image at actual size">
+                        <img id="lightbox-image" src="" alt="" />
+                    </button>
+                    <div class="lightbox-caption" id="lightbox-caption"></div>
                 </div>
             `;
+            lightbox.setAttribute('aria-describedby', 'lightbox-caption');
             document.body.appendChild(lightbox);
         }
         
@@ -2879,14 +4001,19 @@
             lightboxImage.src = src;
             lightboxImage.alt = alt;
             caption.textContent = alt;
+            caption.hidden = !alt;
         }
+        setLightboxZoom(lightbox, false);
         
         // Show lightbox
+        lightboxOpener = document.activeElement;
         lightbox.classList.add('active');
         document.body.style.overflow = 'hidden';
+        const closeButton = lightbox.querySelector('.lightbox-close');
+        if (closeButton) closeButton.focus();
         
-        // Add escape key handler
-        document.addEventListener('keydown', handleLightboxEscape);
+        // Escape closes, Tab stays inside the lightbox
+        document.addEventListener('keydown', handleLightboxKeydown);
     };
     
     window.closeLightbox = function() {
@@ -2897,21 +4024,48 @@
             lightbox.classList.remove('active');
             document.body.style.overflow = '';
             
-            // Remove escape key handler
-            document.removeEventListener('keydown', handleLightboxEscape);
+            document.removeEventListener('keydown', handleLightboxKeydown);
+            if (lightboxOpener && typeof lightboxOpener.focus === 'function' && lightboxOpener.isConnected) {
+                lightboxOpener.focus();
+            }
+            lightboxOpener = null;
         }
     };
     
-    function handleLightboxEscape(e) {
+    window.toggleLightboxZoom = function() {
+        const lightbox = document.getElementById('lightbox-modal');
+        if (lightbox) {
+            setLightboxZoom(lightbox, !lightbox.classList.contains('zoomed'));
+        }
+    };
+    
+    function setLightboxZoom(lightbox, zoomed) {
+        lightbox.classList.toggle('zoomed', zoomed);
+        const zoomButton = lightbox.querySelector('.lightbox-zoom');
+        if (zoomButton) {
+            zoomButton.setAttribute('aria-pressed', zoomed.toString());
+            zoomButton.setAttribute('aria-label', zoomed ? 'Fit image to screen' : 'Show image at actual size');
+        }
+    }
+    
+    function handleLightboxKeydown(e) {
         if (e.key === 'Escape') {
+            e.preventDefault();
             window.closeLightbox();
+        } else if (e.key === 'Tab') {
+            trapFocusInModal(e, document.getElementById('lightbox-modal'));
         }
     }
     
     // Audio autoplay functions
+    // The learner's own choice wins over the course default
+    function isAudioAutoplayEnabled() {
+        const stored = localStorage.getItem('audioAutoplay');
+        return stored === null ? COURSE_SETTINGS.audioAutoplay : stored === 'true';
+    }
+    
     window.toggleAudioAutoplay = function() {
-        const current = localStorage.getItem('audioAutoplay') === 'true';
-        const newValue = !current;
+        const newValue = !isAudioAutoplayEnabled();
         localStorage.setItem('audioAutoplay', newValue.toString());
         updateAutoplayToggle();
         
@@ -2920,7 +4074,7 @@
     
     function updateAutoplayToggle() {
         const toggle = document.querySelector('.audio-autoplay-toggle');
-        const enabled = localStorage.getItem('audioAutoplay') === 'true';
+        const enabled = isAudioAutoplayEnabled();
         if (toggle) {
             toggle.classList.toggle('enabled', enabled);
             toggle.setAttribute('aria-pressed', enabled.toString());
@@ -2928,6 +4082,61 @@
         }
     }
     
+    // Browsers block autoplay until the learner has interacted with the page,
+    // rejecting play() with a NotAllowedError: ask for a click instead
+    function handleBlockedAutoplay(audio, playPauseBtn, error) {
+        if (error && error.name !== 'NotAllowedError') {
+            console.warn('[SCORM Audio] Narration failed to start:', error);
+            return;
+        }
+        console.log('[SCORM Audio] Autoplay blocked:', error);
+        window.audioBlocked++;
+        if (playPauseBtn) {
+            playPauseBtn.classList.add('autoplay-blocked');
+            audio.addEventListener('play', () => playPauseBtn.classList.remove('autoplay-blocked'), { once: true });
+        }
+        if (COURSE_SETTINGS.audioUnlockOverlay && !window.audioUnlocked) {
+            showAudioUnlockOverlay();
+        } else {
+            showAutoplayBlockedNotification();
+        }
+    }
+    
+    // The click on the overlay is the interaction the browser waits for, so the
+    // current page's narration can start from it and later pages autoplay
+    function showAudioUnlockOverlay() {
+        if (document.getElementById('audio-unlock-overlay')) return;
+        
+        const overlay = document.createElement('div');
+        overlay.id = 'audio-unlock-overlay';
+        overlay.className = 'audio-unlock-overlay';
+        overlay.innerHTML = `
+            <button type="button" class="audio-unlock-button">
+                <span aria-hidden="true">🔊</span> Click to enable audio
+            </button>
+        `;
+        const button = overlay.querySelector('.audio-unlock-button');
+        button.addEventListener('click', () => {
+            window.audioUnlocked = true;
+            overlay.remove();
+            const pageId = window.currentPage;
+            const audio = window.audioPlayers && window.audioPlayers[pageId];
+            if (audio && audio.paused) {
+                window.togglePlayPause(pageId);
+            }
+        });
+        document.body.appendChild(overlay);
+        button.focus();
+    }
+    
+    // LMSs whose launch window never sees the learner's click block the first
+    // narration every time, so ask before it fails
+    function initializeAudioUnlock() {
+        if (LMS_COMPATIBILITY.unlockAudioOnLaunch && COURSE_SETTINGS.audioUnlockOverlay && isAudioAutoplayEnabled()) {
+            showAudioUnlockOverlay();
+        }
+    }
+    
     function showAutoplayBlockedNotification() {
         const alertContainer = document.getElementById('scorm-alert-container');
         if (alertContainer) {
@@ -2935,7 +4144,7 @@
             alert.className = 'scorm-alert scorm-alert-info';
             alert.innerHTML = `
                 <span>Audio autoplay was blocked by your browser. Click play to start audio.</span>
-                <button onclick="this.parentElement.remove()" class="alert-close">×</button>
+                <button data-action="dismiss-alert" class="alert-close">×</button>
             `;
             alertContainer.appendChild(alert);
             
@@ -2954,8 +4163,10 @@
             initializeNavigation();
             // Initialize autoplay toggle
             updateAutoplayToggle();
+            initializeAudioUnlock();
             // Initialize auto-save
             initializeAutoSave();
+            initializeCommitHeartbeat();
             // Initialize time limit
             initializeTimeLimit();
             // Initialize minimum time tracking
@@ -2964,13 +4175,20 @@
             initializeKeyboardNavigation();
             // Initialize print functionality
             initializePrintFeature();
+            // Initialize learner notes
+            initializeLearnerNotes();
+            initializeRuntimeAnalytics();
+            // Initialize course search
+            initializeCourseSearch();
         });
     } else {
         initializeNavigation();
         // Initialize autoplay toggle
         updateAutoplayToggle();
+        initializeAudioUnlock();
         // Initialize auto-save
         initializeAutoSave();
+        initializeCommitHeartbeat();
         // Initialize time limit
         initializeTimeLimit();
         // Initialize minimum time tracking
@@ -2979,6 +4197,11 @@
         initializeKeyboardNavigation();
         // Initialize print functionality
         initializePrintFeature();
+        // Initialize learner notes
+        initializeLearnerNotes();
+        initializeRuntimeAnalytics();
+        // Initialize course search
+        initializeCourseSearch();
     }
     
     // Clean up timers on page unload
@@ -6,12 +6,96 @@
 (function() {
     'use strict';
     
+    // Stand-in for the LMS API when the package runs without one (e.g. opened
+    // from disk for review). Values are kept in localStorage, per package path.
+    function createOfflineAPI() {
+        const storageKey = 'scorm-offline:' + window.location.pathname;
+        let storage = null;
+        let data = {};
+        try {
+            storage = window.localStorage;
+            data = JSON.parse(storage.getItem(storageKey) || '{}') || {};
+        } catch (error) {
+            // Storage is blocked for some file:// pages; keep values in memory
+            storage = null;
+        }
+        
+        function save() {
+            if (storage) {
+                try {
+                    storage.setItem(storageKey, JSON.stringify(data));
+                } catch (error) {
+                    console.warn('[UniversalSCORM] Could not save offline progress:', error.message);
+                }
+            }
+            return 'true';
+        }
+        
+        return {
+            offline: true,
+            LMSInitialize: function() { return 'true'; },
+            LMSFinish: save,
+            LMSCommit: save,
+            LMSGetValue: function(element) {
+                return Object.prototype.hasOwnProperty.call(data, element) ? String(data[element]) : '';
+            },
+            LMSSetValue: function(element, value) {
+                data[element] = String(value);
+                return 'true';
+            },
+            LMSGetLastError: function() { return '0'; },
+            LMSGetErrorString: function() { return ''; },
+            LMSGetDiagnostic: function() { return ''; }
+        };
+    }
+    
+    function showOfflineBanner() {
+        if (!document.body) {
+            document.addEventListener('DOMContentLoaded', showOfflineBanner);
+            return;
+        }
+        if (document.getElementById('lms-offline-banner')) return;
+        
+        const banner = document.createElement('div');
+        banner.id = 'lms-offline-banner';
+        banner.className = 'lms-offline-banner';
+        banner.setAttribute('role', 'status');
+        
+        const text = document.createElement('span');
+        text.textContent = 'Not connected to an LMS. Progress is saved in this browser only.';
+        
+        const close = document.createElement('button');
+        close.type = 'button';
+        close.className = 'lms-offline-banner-close';
+        close.setAttribute('aria-label', 'Dismiss');
+        close.textContent = '\u00d7';
+        close.addEventListener('click', function() {
+            banner.remove();
+        });
+        
+        banner.appendChild(text);
+        banner.appendChild(close);
+        document.body.insertBefore(banner, document.body.firstChild);
+    }
+    
+    // Whether an LMS could still attach the API to a parent or opener window
+    function hasHostWindow() {
+        try {
+            return window.parent !== window || !!window.opener;
+        } catch (error) {
+            return true;
+        }
+    }
+    
     // Global SCORM API wrapper - available to all other scripts
     window.UniversalSCORM = {
         api: null,
         initialized: false,
         available: false,
         version: null, // "1.2" or "2004"
+        searching: false, // a delayed API search is running
+        offline: false, // no LMS; tracking locally via createOfflineAPI
+        finished: false, // LMSFinish/Terminate was called; the LMS takes no more data
         
         /**
          * Initialize SCORM API connection
@@ -26,8 +110,6 @@
                 this.available = !!this.api;
                 
                 if (this.available) {
-                    // Determine SCORM version
-                    this.version = this.api.LMSInitialize ? "1.2" : "2004";
                     console.log('[UniversalSCORM] Found SCORM API version:', this.version);
                     
                     // Try to initialize
@@ -42,8 +124,21 @@
                         // Still mark as available - some LMS platforms work without explicit init
                         this.available = true;
                     }
-                } else {
-                    console.warn('[UniversalSCORM] No SCORM API found - running in standalone mode');
+                } else if (window.SCORMApiDiscovery && !this.searching && hasHostWindow()) {
+                    // Some LMS frames attach the API only after the course starts loading
+                    console.log('[UniversalSCORM] No SCORM API yet, retrying discovery...');
+                    const self = this;
+                    this.searching = true;
+                    window.SCORMApiDiscovery.findWithRetry(function(found) {
+                        self.searching = false;
+                        if (found) {
+                            self.init();
+                        } else {
+                            self.goOffline();
+                        }
+                    });
+                } else if (!this.searching) {
+                    this.goOffline();
                 }
             } catch (error) {
                 console.error('[UniversalSCORM] Error during initialization:', error);
@@ -54,56 +149,32 @@
             return this.available;
         },
         
+        /**
+         * Switch to local tracking when no LMS API can be found, so navigation,
+         * progress and scoring keep working in standalone review
+         */
+        goOffline: function() {
+            console.warn('[UniversalSCORM] No SCORM API found - running in standalone mode');
+            this.api = createOfflineAPI();
+            this.version = "1.2";
+            this.offline = true;
+            this.available = true;
+            this.initialize();
+            showOfflineBanner();
+        },
+        
         /**
          * Find SCORM API in window hierarchy
-         * Searches through multiple window levels and both API naming conventions
+         * The search itself lives in scorm-api-discovery.js, generated with the
+         * package's search depth
          */
         findAPI: function(win) {
-            let currentWindow = win;
-            let attempts = 0;
-            const maxAttempts = 10; // Search more levels for complex LMS setups
-            
-            while (attempts < maxAttempts) {
-                console.log(`[UniversalSCORM] Searching window level ${attempts}...`);
-                
-                // Check for SCORM 1.2 API
-                if (currentWindow.API) {
-                    console.log('[UniversalSCORM] Found SCORM 1.2 API at level', attempts);
-                    return currentWindow.API;
-                }
-                
-                // Check for SCORM 2004 API
-                if (currentWindow.API_1484_11) {
-                    console.log('[UniversalSCORM] Found SCORM 2004 API at level', attempts);
-                    return currentWindow.API_1484_11;
-                }
-                
-                // Check opener window (for popups)
-                if (attempts === 0 && currentWindow.opener) {
-                    const openerAPI = this.findAPI(currentWindow.opener);
-                    if (openerAPI) {
-                        console.log('[UniversalSCORM] Found API in opener window');
-                        return openerAPI;
-                    }
-                }
-                
-                // Move to parent window
-                if (currentWindow.parent && currentWindow.parent !== currentWindow) {
-                    currentWindow = currentWindow.parent;
-                } else {
-                    break; // Reached the top
-                }
-                
-                attempts++;
+            const found = window.SCORMApiDiscovery ? window.SCORMApiDiscovery.find(win) : null;
+            if (found) {
+                console.log('[UniversalSCORM] Found SCORM ' + found.version + ' API at level', found.depth);
+                this.version = found.version;
+                return found.api;
             }
-            
-            // Last resort - check top window directly
-            if (win.top && win.top !== win) {
-                console.log('[UniversalSCORM] Checking top window as last resort...');
-                if (win.top.API) return win.top.API;
-                if (win.top.API_1484_11) return win.top.API_1484_11;
-            }
-            
             return null;
         },
         
@@ -153,7 +224,7 @@
          * Set a value in the SCORM API
          */
         setValue: function(element, value) {
-            if (!this.api) return false;
+            if (!this.api || this.finished) return false;
             
             try {
                 // Convert value to string for consistency
@@ -182,7 +253,7 @@
          * Commit/save data to the SCORM API
          */
         commit: function() {
-            if (!this.api) return false;
+            if (!this.api || this.finished) return false;
             
             try {
                 let result;
@@ -204,6 +275,9 @@
          */
         finish: function() {
             if (!this.api) return false;
+            // The course and this script can both try to close the session
+            if (this.finished) return true;
+            this.finished = true;
             
             try {
                 let result;
@@ -258,11 +332,11 @@
         }, 100);
     }
     
-    // Handle page unload
-    window.addEventListener('beforeunload', function() {
-        if (window.UniversalSCORM.available) {
+    // Close the session if the page goes away without navigation.js, which
+    // otherwise saves progress first and finishes the session itself
+    window.addEventListener('pagehide', function() {
+        if (window.UniversalSCORM.available && !window.SafeSCORM) {
             console.log('[UniversalSCORM] Page unloading, finishing session...');
-            window.UniversalSCORM.setValue('cmi.core.lesson_status', 'completed');
             window.UniversalSCORM.commit();
             window.UniversalSCORM.finish();
         }
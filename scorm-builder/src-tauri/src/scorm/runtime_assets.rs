@@ -0,0 +1,262 @@
+use super::code_highlight::course_uses_code;
+use super::generator_enhanced::GenerateScormRequest;
+use super::math::course_uses_math;
+use super::package_sink::PackageSink;
+
+/// Version stamped into the banner of every runtime script (the app version)
+pub const RUNTIME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A static course runtime script, minified by `build.rs` from `src/scorm/runtime`
+pub struct RuntimeAsset {
+    /// Path inside the package
+    pub path: &'static str,
+    pub contents: &'static [u8],
+}
+
+/// Runtime scripts copied verbatim into every package. `scorm-api.js` must load
+/// before `navigation.js`, and the course data it reads (`navigation_generator`)
+/// must load just before it.
+pub const RUNTIME_ASSETS: [RuntimeAsset; 3] = [
+    RuntimeAsset {
+        path: "scripts/scorm-api.js",
+        contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/scorm-api.js")),
+    },
+    RuntimeAsset {
+        path: "scripts/navigation.js",
+        contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/navigation.js")),
+    },
+    RuntimeAsset {
+        path: "scripts/course-ui.js",
+        contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/course-ui.js")),
+    },
+];
+
+/// Diagnostics overlay, only packaged in debug builds
+pub const DIAGNOSTICS_ASSET: RuntimeAsset = RuntimeAsset {
+    path: "scripts/diagnostics.js",
+    contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/diagnostics.js")),
+};
+
+/// Analytics tracker loader, only packaged when the course configures analytics
+pub const ANALYTICS_ASSET: RuntimeAsset = RuntimeAsset {
+    path: "scripts/analytics.js",
+    contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/analytics.js")),
+};
+
+/// Problem report widget, only packaged when the course configures feedback
+pub const FEEDBACK_ASSET: RuntimeAsset = RuntimeAsset {
+    path: "scripts/feedback.js",
+    contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/feedback.js")),
+};
+
+/// Math rendering, only packaged when the course content contains math (see
+/// `math`). KaTeX is vendored already minified and must load before `math.js`.
+pub const MATH_ASSETS: [RuntimeAsset; 2] = [
+    RuntimeAsset {
+        path: "scripts/katex.min.js",
+        contents: include_bytes!("vendor/katex/katex.min.js"),
+    },
+    RuntimeAsset {
+        path: "scripts/math.js",
+        contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/math.js")),
+    },
+];
+
+/// Code highlighting, only packaged when a page contains a code block (see
+/// `code_highlight`). highlight.js is vendored already minified and must load
+/// before `code-highlight.js`.
+pub const CODE_HIGHLIGHT_ASSETS: [RuntimeAsset; 2] = [
+    RuntimeAsset {
+        path: "scripts/highlight.min.js",
+        contents: include_bytes!("vendor/highlightjs/highlight.min.js"),
+    },
+    RuntimeAsset {
+        path: "scripts/code-highlight.js",
+        contents: include_bytes!(concat!(env!("OUT_DIR"), "/runtime/code-highlight.js")),
+    },
+];
+
+/// Runtime scripts a package of `request` ships with
+pub fn runtime_assets(
+    request: &GenerateScormRequest,
+) -> impl Iterator<Item = &'static RuntimeAsset> {
+    let debug_build = request.debug_build.unwrap_or(false);
+    let math = course_uses_math(request);
+    let code = course_uses_code(request);
+    RUNTIME_ASSETS
+        .iter()
+        .chain(debug_build.then_some(&DIAGNOSTICS_ASSET))
+        .chain(request.analytics.is_some().then_some(&ANALYTICS_ASSET))
+        .chain(request.feedback.is_some().then_some(&FEEDBACK_ASSET))
+        .chain(MATH_ASSETS.iter().filter(move |_| math))
+        .chain(CODE_HIGHLIGHT_ASSETS.iter().filter(move |_| code))
+}
+
+/// Write every runtime script for `request` into `sink`
+pub fn write_runtime_assets(
+    sink: &mut dyn PackageSink,
+    request: &GenerateScormRequest,
+) -> Result<(), String> {
+    for asset in runtime_assets(request) {
+        sink.add_file(asset.path, asset.contents)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::runtime_minify::{minify_js, runtime_banner};
+
+    #[test]
+    fn test_runtime_assets_match_sources() {
+        let sources = [
+            ("scorm-api.js", include_str!("runtime/scorm-api.js")),
+            ("navigation.js", include_str!("runtime/navigation.js")),
+            ("course-ui.js", include_str!("runtime/course-ui.js")),
+        ];
+
+        for (asset, (name, source)) in RUNTIME_ASSETS.iter().zip(sources) {
+            assert_eq!(asset.path, format!("scripts/{name}"));
+            let expected = format!(
+                "{}{}",
+                runtime_banner(name, RUNTIME_VERSION),
+                minify_js(source)
+            );
+            assert_eq!(std::str::from_utf8(asset.contents).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_runtime_assets_keep_entry_points() {
+        let scorm_api = std::str::from_utf8(RUNTIME_ASSETS[0].contents).unwrap();
+        assert!(scorm_api.contains("window.UniversalSCORM"));
+        assert!(scorm_api.contains("goOffline: function()"));
+        assert!(scorm_api.contains("'lms-offline-banner'"));
+
+        let navigation = std::str::from_utf8(RUNTIME_ASSETS[1].contents).unwrap();
+        assert!(navigation.contains("const COURSE_DATA = window.COURSE_DATA;"));
+
+        let course_ui = std::str::from_utf8(RUNTIME_ASSETS[2].contents).unwrap();
+        assert!(course_ui.contains("'toggle-play-pause'"));
+        assert!(course_ui.contains("window.exitCourse = exitCourse"));
+        assert!(
+            !course_ui.contains("\n    "),
+            "indentation should be stripped"
+        );
+    }
+
+    fn paths(request: &GenerateScormRequest) -> Vec<&'static str> {
+        runtime_assets(request).map(|a| a.path).collect()
+    }
+
+    fn course_with_content(content: &str) -> GenerateScormRequest {
+        GenerateScormRequest {
+            topics: vec![crate::scorm::generator_enhanced::Topic {
+                id: "topic-1".to_string(),
+                title: "Topic".to_string(),
+                content: content.to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_only_in_debug_builds() {
+        let debug = |debug_build| GenerateScormRequest {
+            debug_build: Some(debug_build),
+            ..Default::default()
+        };
+
+        assert!(!paths(&debug(false)).contains(&"scripts/diagnostics.js"));
+        assert_eq!(
+            paths(&debug(true)),
+            vec![
+                "scripts/scorm-api.js",
+                "scripts/course-ui.js",
+                "scripts/diagnostics.js"
+            ]
+        );
+
+        let diagnostics = std::str::from_utf8(DIAGNOSTICS_ASSET.contents).unwrap();
+        assert!(diagnostics.contains("window.SCORMDiagnostics"));
+        assert!(diagnostics.contains("'cmi.suspend_data'"));
+    }
+
+    #[test]
+    fn test_analytics_only_when_configured() {
+        use crate::scorm::analytics::{AnalyticsProvider, AnalyticsSettings};
+
+        let tracked = GenerateScormRequest {
+            analytics: Some(AnalyticsSettings {
+                provider: AnalyticsProvider::Ga,
+                endpoint: None,
+                site_id: "G-ABC123".to_string(),
+                anonymize_ip: None,
+                disable_cookies: None,
+                respect_do_not_track: None,
+            }),
+            ..Default::default()
+        };
+
+        assert!(!paths(&GenerateScormRequest::default()).contains(&"scripts/analytics.js"));
+        assert_eq!(paths(&tracked).last(), Some(&"scripts/analytics.js"));
+
+        let analytics = std::str::from_utf8(ANALYTICS_ASSET.contents).unwrap();
+        assert!(analytics.contains("'course:pagechange'"));
+        assert!(analytics.contains("'setTrackerUrl'"));
+        assert!(analytics.contains("gtag('config'"));
+    }
+
+    #[test]
+    fn test_feedback_only_when_configured() {
+        use crate::scorm::feedback::FeedbackSettings;
+
+        let with_feedback = GenerateScormRequest {
+            feedback: Some(FeedbackSettings {
+                webhook_url: None,
+                email: Some("support@example.com".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        assert!(!paths(&GenerateScormRequest::default()).contains(&"scripts/feedback.js"));
+        assert_eq!(paths(&with_feedback).last(), Some(&"scripts/feedback.js"));
+
+        let feedback = std::str::from_utf8(FEEDBACK_ASSET.contents).unwrap();
+        assert!(feedback.contains("'course:pagechange'"));
+        assert!(feedback.contains("'mailto:'"));
+    }
+
+    #[test]
+    fn test_math_only_when_content_has_math() {
+        assert!(!paths(&course_with_content("<p>No formulas</p>")).contains(&"scripts/math.js"));
+        assert_eq!(
+            paths(&course_with_content("<p>$$E = mc^2$$</p>"))[2..],
+            ["scripts/katex.min.js", "scripts/math.js"]
+        );
+
+        let katex = std::str::from_utf8(MATH_ASSETS[0].contents).unwrap();
+        assert!(katex.contains("katex"));
+        let math = std::str::from_utf8(MATH_ASSETS[1].contents).unwrap();
+        assert!(math.contains("output: 'mathml'"));
+        assert!(math.contains("window.renderCourseMath"));
+    }
+
+    #[test]
+    fn test_code_highlighting_only_when_content_has_code() {
+        assert!(!paths(&course_with_content("<p>Run <code>ls</code></p>"))
+            .contains(&"scripts/code-highlight.js"));
+        assert_eq!(
+            paths(&course_with_content("<pre><code>ls -la</code></pre>"))[2..],
+            ["scripts/highlight.min.js", "scripts/code-highlight.js"]
+        );
+
+        let highlight = std::str::from_utf8(CODE_HIGHLIGHT_ASSETS[0].contents).unwrap();
+        assert!(highlight.contains("License: BSD-3-Clause"));
+        let code = std::str::from_utf8(CODE_HIGHLIGHT_ASSETS[1].contents).unwrap();
+        assert!(code.contains("hljs.highlightBlock(block)"));
+        assert!(code.contains("window.highlightCourseCode"));
+    }
+}
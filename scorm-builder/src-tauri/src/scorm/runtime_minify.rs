@@ -0,0 +1,113 @@
+//! Minifier for the course runtime scripts in `src/scorm/runtime`.
+//!
+//! Shared by `build.rs` (which emits the minified assets into `OUT_DIR`) and the
+//! crate's tests. It strips comments, indentation and blank lines while keeping
+//! line breaks, so automatic semicolon insertion behaves exactly as in the source.
+//! The runtime sources must not use regex literals, which this scanner would
+//! read as division or comments.
+
+/// Banner prepended to every emitted runtime file
+pub fn runtime_banner(name: &str, version: &str) -> String {
+    format!("/*! SCORM Builder runtime {version} | {name} */\n")
+}
+
+/// Remove comments and whitespace-only lines, leaving string and template
+/// literals untouched
+pub fn minify_js(source: &str) -> String {
+    let stripped = strip_comments(source);
+
+    let mut output = String::with_capacity(stripped.len());
+    for line in stripped.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                // Line comment: drop up to (not including) the newline
+                while chars.peek().is_some_and(|next| *next != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    // Keep line breaks so statements stay on separate lines
+                    if next == '\n' {
+                        output.push('\n');
+                    }
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ('\'' | '"' | '`', _) => {
+                quote = Some(c);
+                output.push(c);
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_strips_comments_and_indentation() {
+        let source = r#"/*!
+ * Header
+ */
+(function() {
+    'use strict';
+    // line comment
+    var url = "http://example.com"; // trailing
+    var glob = '/* not a comment */';
+    /** doc */
+    console.log(`count ${n} // kept`);
+})();
+"#;
+
+        assert_eq!(
+            minify_js(source),
+            "(function() {\n'use strict';\nvar url = \"http://example.com\";\nvar glob = '/* not a comment */';\nconsole.log(`count ${n} // kept`);\n})();\n"
+        );
+    }
+
+    #[test]
+    fn test_minify_handles_escaped_quotes() {
+        assert_eq!(
+            minify_js("var s = 'it\\'s // fine'; // gone\n"),
+            "var s = 'it\\'s // fine';\n"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Client-side search index for the generated course. It ships in the course
+//! data navigation.js reads, which matches queries against it without any server.
+
+use serde::Serialize;
+
+use super::generator_enhanced::GenerateScormRequest;
+
+/// Longest stretch of page text kept per entry, so the index stays small
+pub const MAX_INDEXED_TEXT: usize = 4000;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchEntry {
+    pub page_id: String,
+    pub title: String,
+    pub text: String,
+}
+
+/// One entry per page, in course order. Knowledge check questions are
+/// searchable but their options are not, and assessment questions are left
+/// out entirely so the index doesn't give answers away.
+pub fn build_search_index(request: &GenerateScormRequest) -> Vec<SearchEntry> {
+    let mut entries = Vec::new();
+
+    if let Some(welcome) = &request.welcome_page {
+        entries.push(entry("welcome", &welcome.title, &welcome.content));
+    }
+    if let Some(objectives) = &request.learning_objectives_page {
+        entries.push(entry(
+            "objectives",
+            "Learning Objectives",
+            &objectives.objectives.join("\n"),
+        ));
+    }
+    for topic in &request.topics {
+        let mut content = topic.content.clone();
+        if let Some(kc) = topic.knowledge_check.as_ref().filter(|kc| kc.enabled) {
+            for question in &kc.questions {
+                content.push('\n');
+                content.push_str(&question.text);
+            }
+        }
+        entries.push(entry(&topic.id, &topic.title, &content));
+    }
+    if request.assessment.is_some() {
+        entries.push(entry("assessment", "Assessment", ""));
+    }
+
+    entries
+}
+
+fn entry(page_id: &str, title: &str, html: &str) -> SearchEntry {
+    let mut text = strip_html(html);
+    if let Some((cut, _)) = text.char_indices().nth(MAX_INDEXED_TEXT) {
+        text.truncate(cut);
+    }
+    SearchEntry {
+        page_id: page_id.to_string(),
+        title: strip_html(title),
+        text,
+    }
+}
+
+/// Visible text of an HTML fragment: tags and script/style bodies removed,
+/// common entities decoded and whitespace collapsed
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let Some(end) = after.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = after[1..end].trim_start().to_ascii_lowercase();
+        rest = &after[end + 1..];
+
+        // Skip the contents of elements that never render as text
+        for hidden in ["script", "style"] {
+            if tag.starts_with(hidden) {
+                let close = format!("</{hidden}");
+                rest = match rest.to_ascii_lowercase().find(&close) {
+                    Some(pos) => rest[pos..].find('>').map_or("", |gt| &rest[pos + gt + 1..]),
+                    None => "",
+                };
+            }
+        }
+        // Tags separate words: "<p>a</p><p>b</p>" is "a b"
+        text.push(' ');
+    }
+    text.push_str(rest);
+
+    let text = decode_entities(&text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::generator_enhanced::{
+        Assessment, KnowledgeCheck, Question, Topic, WelcomePage,
+    };
+
+    fn question(text: &str) -> Question {
+        Question {
+            question_type: "multiple-choice".to_string(),
+            text: text.to_string(),
+            options: Some(vec!["Secret option".to_string()]),
+            correct_answer: "Secret option".to_string(),
+            explanation: None,
+            correct_feedback: None,
+            incorrect_feedback: None,
+            correct_feedback_media: None,
+            incorrect_feedback_media: None,
+            bank_ref: None,
+        }
+    }
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(
+            strip_html("<p>Fire&nbsp;<b>safety</b></p><p>first</p><script>var x = '<p>';</script>"),
+            "Fire safety first"
+        );
+        assert_eq!(strip_html("Tom &amp; Jerry &lt;3"), "Tom & Jerry <3");
+        assert_eq!(strip_html("<STYLE>p { color: red }</STYLE>Plain"), "Plain");
+    }
+
+    #[test]
+    fn test_index_covers_pages_without_answers() {
+        let request = GenerateScormRequest {
+            welcome_page: Some(WelcomePage {
+                title: "Welcome".to_string(),
+                content: "<p>Hello</p>".to_string(),
+                start_button_text: "Start".to_string(),
+                audio_file: None,
+                caption_file: None,
+                image_url: None,
+                media: None,
+            }),
+            topics: vec![Topic {
+                id: "topic-1".to_string(),
+                title: "Extinguishers".to_string(),
+                content: "<h2>Types</h2><p>Water and foam</p>".to_string(),
+                knowledge_check: Some(KnowledgeCheck {
+                    enabled: true,
+                    questions: vec![question("Which one for oil fires?")],
+                }),
+                ..Default::default()
+            }],
+            assessment: Some(Assessment {
+                questions: vec![question("Assessment question")],
+            }),
+            ..Default::default()
+        };
+
+        let index = build_search_index(&request);
+        let ids: Vec<_> = index.iter().map(|e| e.page_id.as_str()).collect();
+        assert_eq!(ids, vec!["welcome", "topic-1", "assessment"]);
+        assert_eq!(index[1].text, "Types Water and foam Which one for oil fires?");
+        assert_eq!(index[2].text, "");
+
+        let json = serde_json::to_string(&index).unwrap();
+        assert!(!json.contains("Secret option"));
+        assert!(!json.contains("Assessment question"));
+    }
+}
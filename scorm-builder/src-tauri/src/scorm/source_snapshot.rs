@@ -0,0 +1,241 @@
+//! Encrypted copy of the project inside a generated package.
+//!
+//! With the option on, the package carries the `.scormproj` it was built from
+//! at [`SNAPSHOT_FILE`], so a course found on an LMS can be reopened even when
+//! the project file is gone. Media binaries stay out (the package has the
+//! media already) and so do author notes, which never reach a published course.
+//! The project is encrypted with AES-256-GCM under a key derived from the
+//! author's passphrase; the project id and name stay readable so the package
+//! can be traced without it, and are authenticated with the ciphertext so they
+//! can't be swapped. The passphrase is kept per project in the encrypted store
+//! next to the API keys, never in the project file.
+
+use crate::api_keys::{get_encrypted_file_path, load_encrypted, save_encrypted};
+use crate::project_encryption::derive_passphrase_key;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+
+/// Where the snapshot goes in the package, in a folder of its own
+pub const SNAPSHOT_FILE: &str = "scorm-builder/project-snapshot.json";
+
+const SNAPSHOT_FORMAT: &str = "scorm-builder-project-snapshot";
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+/// Snapshot passphrases by project id, in the encrypted store
+const PASSPHRASES_FILE: &str = "snapshot_passphrases.enc";
+
+/// Fields the snapshot leaves out of the project
+const STRIPPED_FIELDS: [&str; 2] = ["authorNotes", "base64_data"];
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(format!(
+            "The project snapshot passphrase needs at least {MIN_PASSPHRASE_LENGTH} characters"
+        ));
+    }
+    Ok(())
+}
+
+fn load_passphrases() -> Result<BTreeMap<String, String>, String> {
+    let path = get_encrypted_file_path(PASSPHRASES_FILE)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    load_encrypted(&path)
+}
+
+/// The snapshot passphrase saved for `project_id`
+pub fn stored_passphrase(project_id: &str) -> Result<Option<String>, String> {
+    Ok(load_passphrases()?.remove(project_id))
+}
+
+/// Keep the passphrase packaged snapshots of `project_id` are encrypted with
+#[tauri::command]
+pub fn save_snapshot_passphrase(project_id: String, passphrase: String) -> Result<(), String> {
+    validate_passphrase(&passphrase)?;
+    let mut passphrases = load_passphrases()?;
+    passphrases.insert(project_id, passphrase);
+    save_encrypted(&get_encrypted_file_path(PASSPHRASES_FILE)?, &passphrases)
+}
+
+#[tauri::command]
+pub fn delete_snapshot_passphrase(project_id: String) -> Result<(), String> {
+    let mut passphrases = load_passphrases()?;
+    if passphrases.remove(&project_id).is_some() {
+        save_encrypted(&get_encrypted_file_path(PASSPHRASES_FILE)?, &passphrases)?;
+    }
+    Ok(())
+}
+
+/// Whether `project_id` has a snapshot passphrase, without returning it to the webview
+#[tauri::command]
+pub fn has_snapshot_passphrase(project_id: String) -> Result<bool, String> {
+    Ok(load_passphrases()?.contains_key(&project_id))
+}
+
+/// Contents of the snapshot file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceSnapshot {
+    pub format: String,
+    pub format_version: u32,
+    pub project_id: String,
+    pub project_name: String,
+    pub created_at: DateTime<Utc>,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// The readable fields the ciphertext is bound to
+fn associated_data(project_id: &str, project_name: &str) -> Vec<u8> {
+    serde_json::json!([SNAPSHOT_FORMAT, project_id, project_name])
+        .to_string()
+        .into_bytes()
+}
+
+fn strip_fields(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for field in STRIPPED_FIELDS {
+                object.remove(field);
+            }
+            object.values_mut().for_each(strip_fields);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_fields),
+        _ => {}
+    }
+}
+
+impl SourceSnapshot {
+    /// Encrypt the project file `project` (with its pages inlined) for the package
+    pub fn seal(
+        project: &[u8],
+        passphrase: &str,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, String> {
+        validate_passphrase(passphrase)?;
+        let mut project: Value = serde_json::from_slice(project)
+            .map_err(|e| format!("Failed to read project for the snapshot: {e}"))?;
+        strip_fields(&mut project);
+        let project_id = project["project"]["id"].as_str().unwrap_or_default().to_string();
+        let project_name = project["project"]["name"].as_str().unwrap_or_default().to_string();
+        let json = serde_json::to_vec(&project)
+            .map_err(|e| format!("Failed to serialize project snapshot: {e}"))?;
+
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce_bytes = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        let key_bytes = derive_passphrase_key(passphrase, &salt)?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+        let aad = associated_data(&project_id, &project_name);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: &json, aad: &aad })
+            .map_err(|e| format!("Failed to encrypt project snapshot: {e}"))?;
+
+        Ok(Self {
+            format: SNAPSHOT_FORMAT.to_string(),
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            project_id,
+            project_name,
+            created_at,
+            salt: general_purpose::STANDARD.encode(salt),
+            nonce: general_purpose::STANDARD.encode(nonce_bytes),
+            ciphertext: general_purpose::STANDARD.encode(ciphertext),
+        })
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec_pretty(self).map_err(|e| format!("Failed to serialize project snapshot: {e}"))
+    }
+
+    /// The snapshot in a package ZIP, if it has one
+    pub fn from_package(zip_data: &[u8]) -> Result<Option<Self>, String> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(zip_data))
+            .map_err(|e| format!("Not a valid ZIP file: {e}"))?;
+        let mut file = match archive.by_name(SNAPSHOT_FILE) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {SNAPSHOT_FILE}: {e}")),
+        };
+        let mut json = String::new();
+        file.read_to_string(&mut json)
+            .map_err(|e| format!("Failed to read {SNAPSHOT_FILE}: {e}"))?;
+        let snapshot: Self = serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse {SNAPSHOT_FILE}: {e}"))?;
+        if snapshot.format != SNAPSHOT_FORMAT || snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+            return Err("The package's project snapshot was made by a newer version of the app".to_string());
+        }
+        Ok(Some(snapshot))
+    }
+
+    /// The project file, decrypted with `passphrase`
+    pub fn open(&self, passphrase: &str) -> Result<Value, String> {
+        let decode = |value: &str, what: &str| {
+            general_purpose::STANDARD
+                .decode(value)
+                .map_err(|e| format!("Failed to decode snapshot {what}: {e}"))
+        };
+        let salt = decode(&self.salt, "salt")?;
+        let nonce_bytes = decode(&self.nonce, "nonce")?;
+        let ciphertext = decode(&self.ciphertext, "data")?;
+
+        let key_bytes = derive_passphrase_key(passphrase, &salt)?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+        let aad = associated_data(&self.project_id, &self.project_name);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| "Failed to decrypt the project snapshot: wrong passphrase or damaged package".to_string())?;
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse project snapshot: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_snapshot_round_trip_without_notes_or_media_data() {
+        let project = json!({
+            "project": { "id": "1700000000000", "name": "Ladder safety" },
+            "course_content": { "topics": [{ "id": "topic-0", "authorNotes": "Check with legal" }] },
+            "media": { "images": [{ "id": "image-0", "filename": "a.png", "base64_data": "aGVsbG8=" }] }
+        });
+        let snapshot =
+            SourceSnapshot::seal(&serde_json::to_vec(&project).unwrap(), "correct horse", Utc::now()).unwrap();
+        assert_eq!(snapshot.project_name, "Ladder safety");
+
+        // The readable id and name can't be changed without breaking the snapshot
+        let mut renamed = snapshot.clone();
+        renamed.project_name = "Other course".to_string();
+        assert!(renamed.open("correct horse").is_err());
+
+        let mut zip_data = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut zip_data));
+            zip.start_file(SNAPSHOT_FILE, zip::write::FileOptions::default()).unwrap();
+            std::io::Write::write_all(&mut zip, &snapshot.to_json().unwrap()).unwrap();
+            zip.finish().unwrap();
+        }
+        let snapshot = SourceSnapshot::from_package(&zip_data).unwrap().unwrap();
+
+        assert!(snapshot.open("wrong passphrase").is_err());
+        let opened = snapshot.open("correct horse").unwrap();
+        assert_eq!(opened["course_content"]["topics"][0], json!({ "id": "topic-0" }));
+        assert_eq!(opened["media"]["images"][0]["filename"], "a.png");
+        assert!(opened["media"]["images"][0].get("base64_data").is_none());
+
+        assert!(SourceSnapshot::seal(b"{}", "short", Utc::now()).is_err());
+    }
+}
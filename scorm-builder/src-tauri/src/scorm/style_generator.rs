@@ -1,8 +1,28 @@
 use handlebars::Handlebars;
-use serde_json::json;
+use serde_json::{json, Value};
 
+use crate::scorm::code_highlight::course_uses_code;
 use crate::scorm::generator_enhanced::GenerateScormRequest;
 
+/// Course theme colours
+const PRIMARY_COLOR: &str = "#8fbb40";
+const SECONDARY_COLOR: &str = "#241f20";
+
+/// Token colours for highlighted code blocks: the dark secondary colour as the
+/// background with keywords in the primary colour, all readable on it
+fn code_theme() -> Value {
+    json!({
+        "background": SECONDARY_COLOR,
+        "text": "#f1f1ef",
+        "keyword": PRIMARY_COLOR,
+        "string": "#e5c07b",
+        "number": "#f0a36b",
+        "comment": "#a39e9f",
+        "title": "#7cc6f2",
+        "meta": "#c8a2e8",
+    })
+}
+
 pub struct StyleGenerator<'a> {
     handlebars: Handlebars<'a>,
 }
@@ -26,15 +46,21 @@ impl<'a> StyleGenerator<'a> {
         let show_progress = request.show_progress.unwrap_or(true);
         let show_outline = request.show_outline.unwrap_or(true);
         let printable = request.printable.unwrap_or(false);
+        let force_high_contrast = request.high_contrast.unwrap_or(false);
         
         let data = json!({
-            "primary_color": "#8fbb40",
-            "secondary_color": "#241f20",
+            "primary_color": PRIMARY_COLOR,
+            "secondary_color": SECONDARY_COLOR,
             "sidebar_width": if show_outline { "200px" } else { "0px" },
             "font_size": font_size,
             "show_progress": show_progress,
             "show_outline": show_outline,
             "printable": printable,
+            "force_high_contrast": force_high_contrast,
+            "code_highlighting": course_uses_code(request),
+            "image_lightbox": request.image_lightbox.unwrap_or(true),
+            "responsive_layout": request.responsive_layout.unwrap_or(true),
+            "code_theme": code_theme(),
             "base_font_size": match font_size {
                 "small" => "14px",
                 "large" => "18px",
@@ -125,6 +151,72 @@ mod tests {
         generator.validate_css(&css).unwrap();
     }
 
+    #[test]
+    fn test_css_includes_motion_and_contrast_preferences() {
+        let generator = StyleGenerator::new().unwrap();
+
+        let request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            ..Default::default()
+        };
+
+        let css = generator.generate_main_css(&request).unwrap();
+
+        assert!(css.contains("@media (prefers-reduced-motion: reduce)"));
+        assert!(css.contains("@media (prefers-contrast: more)"));
+        assert!(!css.contains("/* Forced by course settings */"));
+        assert!(
+            !css.contains("{{"),
+            "Inline partial should be fully rendered"
+        );
+    }
+
+    #[test]
+    fn test_css_forced_high_contrast() {
+        let generator = StyleGenerator::new().unwrap();
+
+        let request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            high_contrast: Some(true),
+            ..Default::default()
+        };
+
+        let css = generator.generate_main_css(&request).unwrap();
+
+        // High-contrast rules apply unconditionally instead of behind the media query
+        assert!(css.contains("/* Forced by course settings */"));
+        assert!(!css.contains("@media (prefers-contrast: more)"));
+        assert!(css.contains("background: #ffff00 !important"));
+
+        // Reduced motion still follows the learner's preference
+        assert!(css.contains("@media (prefers-reduced-motion: reduce)"));
+        generator.validate_css(&css).unwrap();
+    }
+
+    #[test]
+    fn test_css_code_theme_only_with_code_blocks() {
+        let generator = StyleGenerator::new().unwrap();
+
+        let mut request = GenerateScormRequest {
+            course_title: "Test Course".to_string(),
+            topics: vec![crate::scorm::generator_enhanced::Topic {
+                id: "topic-1".to_string(),
+                title: "Shell".to_string(),
+                content: "<p>No samples yet</p>".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let css = generator.generate_main_css(&request).unwrap();
+        assert!(!css.contains(".hljs-keyword"));
+
+        request.topics[0].content = "<pre><code class=\"language-bash\">ls</code></pre>".to_string();
+        let css = generator.generate_main_css(&request).unwrap();
+        assert!(css.contains("background: #241f20;"));
+        assert!(css.contains(".hljs-literal {\n    color: #8fbb40;"));
+        generator.validate_css(&css).unwrap();
+    }
+
     #[test]
     fn test_css_validation_catches_issues() {
         let generator = StyleGenerator::new().unwrap();
@@ -0,0 +1,299 @@
+//! Layout of the JSON the course runtime keeps in `cmi.suspend_data`.
+//!
+//! navigation.js writes one object with these top-level fields:
+//! `completedPages`, `answeredQuestions`, `completedAudio`, `assessmentAttempts`,
+//! `attemptHistory`, `lastAnswers`, `sessionData`, `introVideoWatched`, `audioBlocked`,
+//! `timestamp`, `version`, when learner notes are enabled `notes` (page id -> text),
+//! when narration positions are remembered `audioPositions` (page id -> seconds) and,
+//! with runtime analytics, `activity` (page id -> `[visits, interactions]`). The generator
+//! supplies the version and size budget so saves stay within what the LMS keeps; the
+//! LMS profile can lower the budget and ask for the JSON to be written as ASCII.
+//!
+//! [`decode_suspend_data`] reads the JSON back for support staff, who paste it
+//! from the LMS when a learner disputes their completion.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::generator_enhanced::GenerateScormRequest;
+use super::lms_profile::LmsCompatibility;
+
+/// Bumped whenever the runtime adds or changes a field ("1.1" added `notes`,
+/// "1.2" `introVideoWatched`, "1.3" `audioPositions`, "1.4" `activity`, "1.5" `audioBlocked`)
+pub const SUSPEND_DATA_VERSION: &str = "1.5";
+
+/// SCORM 1.2 limits cmi.suspend_data to 4096 characters
+pub const SUSPEND_DATA_MAX_LENGTH: usize = 4096;
+
+/// Characters always left to progress tracking, so notes can't crowd out
+/// completion data on long courses
+pub const PROGRESS_RESERVE: usize = 1536;
+
+/// Most characters the runtime analytics may take up; the pages with the fewest
+/// visits are dropped first when a long course needs more
+pub const ACTIVITY_MAX_LENGTH: usize = 512;
+
+/// Most characters learner notes may take up in suspend_data
+pub fn notes_max_length(compatibility: &LmsCompatibility) -> usize {
+    compatibility.suspend_data_max_length - PROGRESS_RESERVE
+}
+
+/// Template data describing the suspend_data budget for navigation.js
+pub fn suspend_data_settings(request: &GenerateScormRequest) -> Value {
+    let learner_notes = request.learner_notes.unwrap_or(false);
+    let runtime_analytics = request.runtime_analytics.unwrap_or(false);
+    let compatibility = request.lms_profile.unwrap_or_default().compatibility();
+    json!({
+        "version": SUSPEND_DATA_VERSION,
+        "max_length": compatibility.suspend_data_max_length,
+        "ascii_only": compatibility.ascii_suspend_data,
+        "notes_max_length": if learner_notes { notes_max_length(&compatibility) } else { 0 },
+        "activity_max_length": if runtime_analytics { ACTIVITY_MAX_LENGTH } else { 0 },
+    })
+}
+
+/// What the learner did on one page
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageActivity {
+    pub page_id: String,
+    pub completed: bool,
+    /// Time on the page over all sessions
+    pub seconds: u64,
+    /// Only recorded by packages built with runtime analytics
+    pub visits: Option<u64>,
+    pub interactions: Option<u64>,
+}
+
+/// A learner's suspend_data in readable form
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuspendDataReport {
+    pub version: Option<String>,
+    /// When the course last saved, as the learner's browser reported it
+    pub saved_at: Option<String>,
+    pub completed_pages: Vec<String>,
+    pub answered_questions: usize,
+    pub completed_audio: usize,
+    pub assessment_attempts: u64,
+    /// Percentage of each assessment attempt
+    pub assessment_scores: Vec<f64>,
+    pub intro_video_watched: bool,
+    /// Times the browser refused to autoplay narration
+    pub autoplay_blocked: u64,
+    pub total_seconds: u64,
+    /// Pages with learner notes
+    pub notes: usize,
+    /// Whether the package recorded visits and interactions
+    pub activity_recorded: bool,
+    /// Completed pages first, then the others in the order they were saved
+    pub pages: Vec<PageActivity>,
+    /// The report as plain text, for pasting into a support ticket
+    pub summary: String,
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn object_len(value: &Value) -> usize {
+    value.as_object().map_or(0, |object| object.len())
+}
+
+fn page<'a>(pages: &'a mut Vec<PageActivity>, page_id: &str) -> &'a mut PageActivity {
+    let index = match pages.iter().position(|page| page.page_id == page_id) {
+        Some(index) => index,
+        None => {
+            pages.push(PageActivity {
+                page_id: page_id.to_string(),
+                ..Default::default()
+            });
+            pages.len() - 1
+        }
+    };
+    &mut pages[index]
+}
+
+fn format_duration(seconds: u64) -> String {
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
+
+fn summary(report: &SuspendDataReport) -> String {
+    let mut lines = vec![
+        format!(
+            "Saved: {} (suspend_data version {})",
+            report.saved_at.as_deref().unwrap_or("unknown"),
+            report.version.as_deref().unwrap_or("unknown")
+        ),
+        format!("Time in course: {}", format_duration(report.total_seconds)),
+        format!("Pages completed: {}", report.completed_pages.len()),
+        format!("Questions answered: {}", report.answered_questions),
+        format!("Narrations completed: {}", report.completed_audio),
+    ];
+    if report.autoplay_blocked > 0 {
+        lines.push(format!(
+            "Narration autoplay blocked by the browser: {} time(s)",
+            report.autoplay_blocked
+        ));
+    }
+    if report.assessment_attempts > 0 {
+        let scores: Vec<String> = report
+            .assessment_scores
+            .iter()
+            .map(|score| format!("{score}%"))
+            .collect();
+        lines.push(format!(
+            "Assessment attempts: {} ({})",
+            report.assessment_attempts,
+            scores.join(", ")
+        ));
+    } else {
+        lines.push("Assessment attempts: none".to_string());
+    }
+    if !report.activity_recorded {
+        lines.push("Visits and interactions were not recorded by this package".to_string());
+    }
+    lines.push(String::new());
+    for page in &report.pages {
+        let mut line = format!(
+            "{} {}: {}",
+            if page.completed { "[x]" } else { "[ ]" },
+            page.page_id,
+            format_duration(page.seconds)
+        );
+        if let (Some(visits), Some(interactions)) = (page.visits, page.interactions) {
+            line.push_str(&format!(", {visits} visit(s), {interactions} interaction(s)"));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Read suspend_data as a course wrote it, from any version
+pub fn decode_suspend_data(data: &str) -> Result<SuspendDataReport, String> {
+    let data = data.trim();
+    if data.is_empty() {
+        return Err("The suspend_data is empty".to_string());
+    }
+    let value: Value = serde_json::from_str(data)
+        .map_err(|e| format!("This is not suspend_data written by the course: {e}"))?;
+    if !value.is_object() {
+        return Err("This is not suspend_data written by the course".to_string());
+    }
+
+    let completed_pages = string_list(&value["completedPages"]);
+    let mut pages: Vec<PageActivity> = Vec::new();
+    for page_id in &completed_pages {
+        page(&mut pages, page_id).completed = true;
+    }
+    if let Some(timers) = value["sessionData"]["pageTimers"].as_object() {
+        for (page_id, ms) in timers {
+            page(&mut pages, page_id).seconds = ms.as_f64().unwrap_or(0.0) as u64 / 1000;
+        }
+    }
+    let activity = value["activity"].as_object();
+    for (page_id, counts) in activity.into_iter().flatten() {
+        let page = page(&mut pages, page_id);
+        page.visits = Some(counts[0].as_u64().unwrap_or(0));
+        page.interactions = Some(counts[1].as_u64().unwrap_or(0));
+    }
+
+    let mut report = SuspendDataReport {
+        version: value["version"].as_str().map(str::to_string),
+        saved_at: value["timestamp"].as_str().map(str::to_string),
+        answered_questions: object_len(&value["answeredQuestions"]),
+        completed_audio: value["completedAudio"].as_array().map_or(0, Vec::len),
+        assessment_attempts: value["assessmentAttempts"].as_u64().unwrap_or(0),
+        assessment_scores: value["attemptHistory"]
+            .as_array()
+            .map(|scores| scores.iter().filter_map(Value::as_f64).collect())
+            .unwrap_or_default(),
+        intro_video_watched: value["introVideoWatched"].as_bool().unwrap_or(false),
+        autoplay_blocked: value["audioBlocked"].as_u64().unwrap_or(0),
+        total_seconds: value["sessionData"]["totalTimeSpent"].as_f64().unwrap_or(0.0) as u64 / 1000,
+        notes: object_len(&value["notes"]),
+        activity_recorded: activity.is_some(),
+        completed_pages,
+        pages,
+        summary: String::new(),
+    };
+    report.summary = summary(&report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorm::navigation_generator::course_data;
+
+    #[test]
+    fn test_notes_budget_only_when_enabled() {
+        let request = GenerateScormRequest::default();
+        assert_eq!(suspend_data_settings(&request)["notes_max_length"], 0);
+
+        let request = GenerateScormRequest {
+            learner_notes: Some(true),
+            ..Default::default()
+        };
+        let settings = suspend_data_settings(&request);
+        assert_eq!(settings["notes_max_length"], 2560);
+        assert_eq!(settings["max_length"], 4096);
+    }
+
+    #[test]
+    fn test_navigation_writes_schema_version_and_limits() {
+        let request = GenerateScormRequest {
+            learner_notes: Some(true),
+            ..Default::default()
+        };
+        let data = course_data(&request);
+
+        assert_eq!(data["suspend_data"]["version"], "1.5");
+        assert_eq!(data["suspend_data"]["max_length"], 4096);
+        assert_eq!(data["suspend_data"]["notes_max_length"], 2560);
+        assert_eq!(data["learner_notes"], true);
+    }
+
+    #[test]
+    fn test_decode_reports_pages_time_and_activity() {
+        let data = r#"{
+            "completedPages": ["welcome", "topic-0"],
+            "answeredQuestions": {"topic-0_q0": true},
+            "completedAudio": ["welcome"],
+            "assessmentAttempts": 2,
+            "attemptHistory": [60, 85],
+            "sessionData": {"totalTimeSpent": 754000, "pageTimers": {"welcome": 30000, "topic-1": 12500}},
+            "activity": {"welcome": [1, 0], "topic-1": [3, 7]},
+            "audioBlocked": 2,
+            "timestamp": "2024-05-01T09:30:00.000Z",
+            "version": "1.5"
+        }"#;
+
+        let report = decode_suspend_data(data).unwrap();
+
+        assert_eq!(report.total_seconds, 754);
+        assert_eq!(report.assessment_scores, vec![60.0, 85.0]);
+        assert!(report.activity_recorded);
+        let ids: Vec<_> = report.pages.iter().map(|page| page.page_id.as_str()).collect();
+        assert_eq!(ids, vec!["welcome", "topic-0", "topic-1"]);
+        assert_eq!(report.pages[2].interactions, Some(7));
+        assert!(!report.pages[2].completed);
+        assert!(report.summary.contains("Time in course: 12m 34s"));
+        assert!(report.summary.contains("Narration autoplay blocked by the browser: 2 time(s)"));
+        assert!(report.summary.contains("[ ] topic-1: 12s, 3 visit(s), 7 interaction(s)"));
+
+        assert!(decode_suspend_data("completedPages=welcome").is_err());
+    }
+}
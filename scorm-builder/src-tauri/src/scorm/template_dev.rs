@@ -0,0 +1,233 @@
+//! Template development mode.
+//!
+//! People customizing the course templates keep their copies in the app config
+//! folder: `templates/{name}.html.hbs` for pages and `templates/partials/{name}.hbs`
+//! for partials. While dev mode is on, page previews render with those files
+//! instead of the templates compiled into the app, and a background poll
+//! notices when one changes, compiles them all again and emits
+//! `template-dev-changed` with any errors, so the preview can be re-rendered.
+
+use super::html_generator_enhanced::{HtmlGenerator, PAGE_TEMPLATES, TEMPLATE_PARTIALS};
+use crate::settings::get_app_config_dir;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::Emitter;
+
+/// How often the override folder is checked for changes while dev mode is on
+pub const POLL_INTERVAL_MS: u64 = 500;
+
+pub const TEMPLATES_CHANGED_EVENT: &str = "template-dev-changed";
+
+/// Modification time and size of every override path, present or not
+type Fingerprint = Vec<(PathBuf, Option<SystemTime>, Option<u64>)>;
+
+/// `Some` with the last fingerprint seen while dev mode is on
+static WATCHED: Lazy<Mutex<Option<Fingerprint>>> = Lazy::new(|| Mutex::new(None));
+
+/// An override template that doesn't compile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateSourceError {
+    pub template: String,
+    pub path: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+impl TemplateSourceError {
+    pub fn new(template: &str, path: &Path, error: &handlebars::TemplateError) -> Self {
+        let (line, column) = error.pos().unzip();
+        Self {
+            template: template.to_string(),
+            path: path.to_string_lossy().to_string(),
+            message: error.reason().to_string(),
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for TemplateSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path)?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+            if let Some(column) = self.column {
+                write!(f, ":{column}")?;
+            }
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// The override folder and what is in it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateCheck {
+    pub dir: String,
+    /// Templates and partials the folder overrides, including broken ones
+    pub overrides: Vec<String>,
+    pub errors: Vec<TemplateSourceError>,
+}
+
+pub fn template_override_dir() -> Result<PathBuf, String> {
+    Ok(get_app_config_dir()?.join("templates"))
+}
+
+pub fn partial_override_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join("partials").join(format!("{name}.hbs"))
+}
+
+pub fn page_override_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.html.hbs"))
+}
+
+/// Every template and partial that can be overridden, with its path in `dir`
+fn override_paths(dir: &Path) -> Vec<(&'static str, PathBuf)> {
+    let partials = TEMPLATE_PARTIALS.iter().map(|(name, _)| (*name, partial_override_path(dir, name)));
+    let pages = PAGE_TEMPLATES.iter().map(|(name, _)| (*name, page_override_path(dir, name)));
+    partials.chain(pages).collect()
+}
+
+fn fingerprint(dir: &Path) -> Fingerprint {
+    override_paths(dir)
+        .into_iter()
+        .map(|(_, path)| {
+            let metadata = fs::metadata(&path).ok();
+            let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+            let len = metadata.map(|m| m.len());
+            (path, modified, len)
+        })
+        .collect()
+}
+
+/// Compile every override in `dir`, reporting the ones that fail with their line
+pub fn check_templates(dir: &Path) -> Result<TemplateCheck, String> {
+    let (_, errors) = HtmlGenerator::with_template_dir(dir)?;
+    Ok(TemplateCheck {
+        dir: dir.to_string_lossy().to_string(),
+        overrides: override_paths(dir)
+            .into_iter()
+            .filter(|(_, path)| path.is_file())
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        errors,
+    })
+}
+
+pub fn is_enabled() -> bool {
+    WATCHED.lock().map(|watched| watched.is_some()).unwrap_or(false)
+}
+
+/// Record `current` as the last fingerprint seen, returning whether it changed.
+/// Nothing changes while dev mode is off.
+fn note_fingerprint(watched: &mut Option<Fingerprint>, current: Fingerprint) -> bool {
+    match watched {
+        Some(last) if *last != current => {
+            *last = current;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Check the override folder once, emitting `template-dev-changed` if anything
+/// in it changed since the last check
+pub fn poll(app: &tauri::AppHandle) {
+    if !is_enabled() {
+        return;
+    }
+    let Ok(dir) = template_override_dir() else {
+        return;
+    };
+    let current = fingerprint(&dir);
+    let changed = match WATCHED.lock() {
+        Ok(mut watched) => note_fingerprint(&mut watched, current),
+        Err(_) => false,
+    };
+    if !changed {
+        return;
+    }
+
+    match check_templates(&dir) {
+        Ok(check) => {
+            for error in &check.errors {
+                eprintln!("[Template Dev] {error}");
+            }
+            let _ = app.emit(TEMPLATES_CHANGED_EVENT, check);
+        }
+        Err(e) => eprintln!("[Template Dev] Failed to check templates: {e}"),
+    }
+}
+
+/// Turn template dev mode on or off, returning the state of the override folder.
+/// Turning it on creates the folder so there is somewhere to put templates.
+#[tauri::command]
+pub fn set_template_dev_mode(enabled: bool) -> Result<TemplateCheck, String> {
+    let dir = template_override_dir()?;
+    if enabled {
+        fs::create_dir_all(dir.join("partials"))
+            .map_err(|e| format!("Failed to create template folder: {e}"))?;
+    }
+
+    let mut watched = WATCHED.lock().map_err(|e| format!("Failed to lock dev mode: {e}"))?;
+    *watched = enabled.then(|| fingerprint(&dir));
+    drop(watched);
+
+    check_templates(&dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_broken_override_is_reported_with_its_line() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("partials")).unwrap();
+        fs::write(partial_override_path(dir.path(), "nav_bar"), "<nav>{{title}}</nav>").unwrap();
+        fs::write(page_override_path(dir.path(), "topic"), "<h1>{{title}}</h1>\n{{#if content}}\n").unwrap();
+
+        let check = check_templates(dir.path()).unwrap();
+
+        assert_eq!(check.overrides, vec!["nav_bar", "topic"]);
+        assert_eq!(check.errors.len(), 1);
+        assert_eq!(check.errors[0].template, "topic");
+        assert!(check.errors[0].line.is_some());
+    }
+
+    #[test]
+    fn test_overrides_replace_built_in_templates() {
+        let dir = TempDir::new().unwrap();
+        fs::write(page_override_path(dir.path(), "credits"), "<p>Custom credits</p>").unwrap();
+
+        let (generator, errors) = HtmlGenerator::with_template_dir(dir.path()).unwrap();
+        let request = Default::default();
+        let html = generator.generate_credits_page(&request).unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(html, "<p>Custom credits</p>");
+    }
+
+    #[test]
+    fn test_changes_are_only_noticed_in_dev_mode() {
+        let dir = TempDir::new().unwrap();
+        let before = fingerprint(dir.path());
+        fs::write(page_override_path(dir.path(), "topic"), "<h1>{{title}}</h1>").unwrap();
+        let after = fingerprint(dir.path());
+
+        let mut off = None;
+        assert!(!note_fingerprint(&mut off, after.clone()));
+
+        let mut on = Some(before);
+        assert!(note_fingerprint(&mut on, after.clone()));
+        assert!(!note_fingerprint(&mut on, after));
+    }
+}
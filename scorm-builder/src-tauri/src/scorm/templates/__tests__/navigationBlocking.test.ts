@@ -4,8 +4,8 @@ import path from 'path'
 
 describe('SCORM templates - navigation blocking', () => {
   it('should have answeredQuestions tracking for knowledge check pages', () => {
-    // Read the navigation.js.hbs template
-    const navPath = path.join(__dirname, '..', 'navigation.js.hbs')
+    // Read the navigation.js runtime
+    const navPath = path.join(__dirname, '..', '..', 'runtime', 'navigation.js')
     const navContent = fs.readFileSync(navPath, 'utf-8')
     
     // Check that answeredQuestions is properly initialized
@@ -13,19 +13,16 @@ describe('SCORM templates - navigation blocking', () => {
   })
   
   it('should have PAGES_WITH_KNOWLEDGE_CHECKS populated with correct page IDs', () => {
-    const navPath = path.join(__dirname, '..', 'navigation.js.hbs')
+    const navPath = path.join(__dirname, '..', '..', 'runtime', 'navigation.js')
     const navContent = fs.readFileSync(navPath, 'utf-8')
     
-    // Check that PAGES_WITH_KNOWLEDGE_CHECKS is populated via Handlebars
-    expect(navContent).toMatch(/const\s+PAGES_WITH_KNOWLEDGE_CHECKS\s*=\s*{/i)
-    
-    // Should have Handlebars template for populating the object
-    expect(navContent).toMatch(/{{#each\s+topics}}/i)
-    expect(navContent).toMatch(/{{#if\s+this\.has_knowledge_check}}/i)
+    // Check that PAGES_WITH_KNOWLEDGE_CHECKS is populated from the course data
+    expect(navContent).toMatch(/const\s+PAGES_WITH_KNOWLEDGE_CHECKS\s*=\s*Object\.fromEntries\(/i)
+    expect(navContent).toContain('COURSE_DATA.knowledge_check_pages')
   })
   
   it('should have shouldBlockNavigation function that properly checks knowledge check status', () => {
-    const navPath = path.join(__dirname, '..', 'navigation.js.hbs')
+    const navPath = path.join(__dirname, '..', '..', 'runtime', 'navigation.js')
     const navContent = fs.readFileSync(navPath, 'utf-8')
     
     // Check for shouldBlockNavigation function
@@ -39,7 +36,7 @@ describe('SCORM templates - navigation blocking', () => {
   })
   
   it('should update answeredQuestions when questions are answered', () => {
-    const navPath = path.join(__dirname, '..', 'navigation.js.hbs')
+    const navPath = path.join(__dirname, '..', '..', 'runtime', 'navigation.js')
     const navContent = fs.readFileSync(navPath, 'utf-8')
     
     // Check for checkMultipleChoice function
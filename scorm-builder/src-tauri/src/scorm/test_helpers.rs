@@ -505,11 +505,11 @@ button:hover {{
 pub fn create_test_scorm_request() -> GenerateScormRequest {
     GenerateScormRequest {
         project_id: "test-project".to_string(),
-        course_content: json!({
+        course_content: serde_json::from_value(json!({
             "title": "Test Course",
             "description": "A test course for validation",
             "topics": ["Introduction", "Content", "Assessment"]
-        }),
+        })).unwrap(),
         course_metadata: CourseMetadata {
             title: "Test Course".to_string(),
             description: "Test Description".to_string(),
@@ -527,7 +527,7 @@ pub fn create_test_scorm_request() -> GenerateScormRequest {
 pub fn create_test_scorm_request_with_settings(settings: TestCourseSettings) -> GenerateScormRequest {
     GenerateScormRequest {
         project_id: "test-project-settings".to_string(),
-        course_content: json!({
+        course_content: serde_json::from_value(json!({
             "title": "Test Course with Settings",
             "description": "A test course with custom settings",
             "topics": ["Introduction", "Content", "Assessment"],
@@ -542,7 +542,7 @@ pub fn create_test_scorm_request_with_settings(settings: TestCourseSettings) ->
                 "timeLimit": settings.time_limit,
                 "keyboardNavigation": settings.keyboard_navigation
             }
-        }),
+        })).unwrap(),
         course_metadata: CourseMetadata {
             title: "Test Course with Settings".to_string(),
             description: "Test course with custom CourseSettings".to_string(),
@@ -0,0 +1,200 @@
+//! Text transcripts of narrated topics, for learners who can't use audio.
+//!
+//! With `transcripts` on, every topic with narration gets a collapsible
+//! transcript under its text, and a `transcript` page after the assessment
+//! collects them all in course order. The author's narration text is used
+//! where the topic has any; otherwise the text of its caption file, with cue
+//! numbers, timings and markup stripped.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::generator_enhanced::{GenerateScormRequest, Topic};
+use super::html_generator_enhanced::HtmlGenerator;
+
+/// Caption formats whose cue text can be read: WebVTT and SubRip
+const CAPTION_EXTENSIONS: [&str; 2] = [".vtt", ".srt"];
+
+/// Where a transcript's text came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptSource {
+    Narration,
+    Captions,
+}
+
+/// The transcript of one topic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TopicTranscript {
+    pub page_id: String,
+    pub title: String,
+    pub source: TranscriptSource,
+    pub paragraphs: Vec<String>,
+}
+
+/// Cue text of the packaged caption files, by package path
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaptionTexts {
+    texts: HashMap<String, Vec<String>>,
+}
+
+impl CaptionTexts {
+    pub fn from_media_files(media_files: &HashMap<String, Vec<u8>>) -> Self {
+        let texts = media_files
+            .iter()
+            .filter(|(path, _)| {
+                let path = path.to_ascii_lowercase();
+                CAPTION_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
+            })
+            .map(|(path, data)| (path.clone(), caption_paragraphs(&String::from_utf8_lossy(data))))
+            .filter(|(_, paragraphs)| !paragraphs.is_empty())
+            .collect();
+        Self { texts }
+    }
+
+    fn get(&self, caption_file: &str) -> Option<&Vec<String>> {
+        self.texts.get(&HtmlGenerator::ensure_media_path(caption_file))
+    }
+}
+
+/// `text` without markup tags, with the entities captions use decoded
+fn strip_markup(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// The spoken text of a WebVTT or SubRip file as one paragraph, leaving out
+/// headers, notes, styles, cue numbers and timings. Cues repeated by rolling
+/// captions are kept once.
+pub fn caption_paragraphs(captions: &str) -> Vec<String> {
+    let captions = captions.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut cues: Vec<String> = Vec::new();
+    for block in captions.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| line.trim().is_empty());
+        // Only cues have a timing line; the header, NOTE, STYLE and REGION blocks don't
+        if !lines.any(|line| line.contains("-->")) {
+            continue;
+        }
+        let text = lines
+            .map(|line| strip_markup(line.trim()))
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() && cues.last() != Some(&text) {
+            cues.push(text);
+        }
+    }
+    if cues.is_empty() {
+        Vec::new()
+    } else {
+        vec![cues.join(" ")]
+    }
+}
+
+/// Narration text split into paragraphs at blank lines
+fn narration_paragraphs(narration: &str) -> Vec<String> {
+    narration
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect()
+}
+
+/// Transcript of `topic` from its narration, else its captions when they were packaged
+pub fn topic_transcript(topic: &Topic, captions: Option<&CaptionTexts>) -> Option<TopicTranscript> {
+    let transcript = |source, paragraphs| TopicTranscript {
+        page_id: topic.id.clone(),
+        title: topic.title.clone(),
+        source,
+        paragraphs,
+    };
+    let narration = narration_paragraphs(topic.narration.as_deref().unwrap_or_default());
+    if !narration.is_empty() {
+        return Some(transcript(TranscriptSource::Narration, narration));
+    }
+    let caption_file = topic.caption_file.as_deref()?;
+    let paragraphs = captions?.get(caption_file)?;
+    Some(transcript(TranscriptSource::Captions, paragraphs.clone()))
+}
+
+/// Transcripts of every topic that has one, in course order
+pub fn course_transcripts(request: &GenerateScormRequest, captions: Option<&CaptionTexts>) -> Vec<TopicTranscript> {
+    request
+        .topics
+        .iter()
+        .filter_map(|topic| topic_transcript(topic, captions))
+        .collect()
+}
+
+/// Whether the package gets a transcript page: transcripts are on and some
+/// topic has narration text or captions
+pub fn has_transcript_page(request: &GenerateScormRequest) -> bool {
+    request.transcripts.unwrap_or(false)
+        && request.topics.iter().any(|topic| {
+            topic.caption_file.is_some()
+                || topic.narration.as_deref().is_some_and(|narration| !narration.trim().is_empty())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VTT: &str = "WEBVTT\n\nNOTE recorded 2024-03-01\n\n1\n00:00:00.000 --> 00:00:02.500\n<v Narrator>Welcome to <b>safety</b> &amp; health.\n\n00:00:02.500 --> 00:00:04.000 align:start\nWelcome to <b>safety</b> &amp; health.\n\n00:00:04.000 --> 00:00:06.000\nGloves protect\nyour hands.\n";
+
+    #[test]
+    fn test_caption_cues_become_plain_text() {
+        assert_eq!(
+            caption_paragraphs(VTT),
+            vec!["Welcome to safety & health. Gloves protect your hands."]
+        );
+        let srt = "1\r\n00:00:00,000 --> 00:00:02,000\r\nFirst line\r\n\r\n2\r\n00:00:02,000 --> 00:00:03,000\r\nSecond line\r\n";
+        assert_eq!(caption_paragraphs(srt), vec!["First line Second line"]);
+        assert!(caption_paragraphs("WEBVTT\n\nNOTE nothing spoken\n").is_empty());
+    }
+
+    #[test]
+    fn test_narration_is_preferred_over_captions() {
+        let media_files = HashMap::from([("media/caption-0.vtt".to_string(), VTT.as_bytes().to_vec())]);
+        let captions = CaptionTexts::from_media_files(&media_files);
+        let mut topic = Topic {
+            id: "topic-0".to_string(),
+            title: "Gloves".to_string(),
+            caption_file: Some("caption-0.vtt".to_string()),
+            ..Default::default()
+        };
+
+        let from_captions = topic_transcript(&topic, Some(&captions)).unwrap();
+        assert_eq!(from_captions.source, TranscriptSource::Captions);
+        assert!(topic_transcript(&topic, None).is_none());
+
+        topic.narration = Some("Wear gloves.\n\nCheck them\nfor tears.".to_string());
+        let from_narration = topic_transcript(&topic, Some(&captions)).unwrap();
+        assert_eq!(from_narration.source, TranscriptSource::Narration);
+        assert_eq!(from_narration.paragraphs, vec!["Wear gloves.", "Check them for tears."]);
+
+        let mut request = GenerateScormRequest {
+            topics: vec![topic, Topic::default()],
+            ..Default::default()
+        };
+        assert!(!has_transcript_page(&request));
+        request.transcripts = Some(true);
+        assert!(has_transcript_page(&request));
+        assert_eq!(course_transcripts(&request, Some(&captions)).len(), 1);
+    }
+}
@@ -1,4 +1,11 @@
+use crate::api_quota::ApiLimit;
+use crate::display_time::TimeDisplay;
+use crate::media_policy::MediaPolicy;
+use crate::metadata_policy::MetadataPolicy;
+use crate::nightly_archive::ArchiveSchedule;
+use crate::storage_budget::StorageBudget;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use once_cell::sync::OnceCell;
@@ -24,6 +31,27 @@ fn log_directory_once(level: &str, message: &str, path: &Path) {
 pub struct AppSettings {
     pub projects_directory: Option<String>,
     pub recent_projects_count: Option<usize>,
+    /// Language for messages returned by commands (e.g. "fr"); English when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Size, type and scanner rules for media; the default policy when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_policy: Option<MediaPolicy>,
+    /// Metadata fields each media type may carry; the default policy when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_policy: Option<MetadataPolicy>,
+    /// Nightly archive of the whole projects directory; off when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_schedule: Option<ArchiveSchedule>,
+    /// Daily limits of external API usage by provider; unlimited when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_limits: Option<HashMap<String, ApiLimit>>,
+    /// Format of the local times reporting commands add; 24-hour date and time when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_display: Option<TimeDisplay>,
+    /// Media size budget of projects without their own; the default budget when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_budget: Option<StorageBudget>,
 }
 
 impl Default for AppSettings {
@@ -31,12 +59,19 @@ impl Default for AppSettings {
         Self {
             projects_directory: None,
             recent_projects_count: Some(10),
+            locale: None,
+            media_policy: None,
+            metadata_policy: None,
+            archive_schedule: None,
+            api_limits: None,
+            time_display: None,
+            storage_budget: None,
         }
     }
 }
 
-/// Get the settings file path
-fn get_settings_path() -> Result<PathBuf, String> {
+/// Get the application config directory (settings, themes, template overrides)
+pub fn get_app_config_dir() -> Result<PathBuf, String> {
     let config_dir =
         dirs::config_dir().ok_or_else(|| "Unable to find config directory".to_string())?;
 
@@ -48,7 +83,12 @@ fn get_settings_path() -> Result<PathBuf, String> {
             .map_err(|e| format!("Failed to create config directory: {e}"))?;
     }
 
-    Ok(app_config_dir.join("settings.json"))
+    Ok(app_config_dir)
+}
+
+/// Get the settings file path
+fn get_settings_path() -> Result<PathBuf, String> {
+    Ok(get_app_config_dir()?.join("settings.json"))
 }
 
 /// Load application settings
@@ -77,6 +117,7 @@ pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
         .map_err(|e| format!("Failed to serialize settings: {e}"))?;
 
     fs::write(&settings_path, json).map_err(|e| format!("Failed to write settings file: {e}"))?;
+    crate::messages::set_locale(settings.locale.clone());
 
     Ok(())
 }
@@ -96,8 +137,7 @@ pub fn get_projects_directory() -> Result<PathBuf, String> {
     }
 
     // Fall back to default
-    let home_dir = dirs::home_dir().ok_or_else(|| "Unable to find home directory".to_string())?;
-    let default_dir = home_dir.join("Documents").join("SCORM Projects");
+    let default_dir = default_projects_directory()?;
 
     log_directory_once("INFO", &format!("Using default projects directory: {}", default_dir.display()), &default_dir);
 
@@ -112,6 +152,13 @@ pub fn get_projects_directory() -> Result<PathBuf, String> {
     Ok(default_dir)
 }
 
+/// Where projects go when the settings don't name a folder
+pub fn default_projects_directory() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| home.join("Documents").join("SCORM Projects"))
+        .ok_or_else(|| "Unable to find home directory".to_string())
+}
+
 /// Set the projects directory
 pub fn set_projects_directory(path: &Path) -> Result<(), String> {
     let mut settings = load_settings()?;
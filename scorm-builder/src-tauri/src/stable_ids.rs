@@ -0,0 +1,507 @@
+use crate::app_services::AppServices;
+use crate::course_structure::MediaChanges;
+use crate::media_storage::get_media_directory;
+use crate::media_usage::find_project_file;
+use crate::project_encryption::{open_media_data, read_media_file};
+use crate::project_storage::{load_project_file, save_project_file};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use uuid::Uuid;
+
+pub const WELCOME_PAGE_ID: &str = "welcome";
+pub const OBJECTIVES_PAGE_ID: &str = "objectives";
+
+/// Media kinds whose legacy IDs encode the page position (audio-N, caption-N)
+const POSITIONAL_MEDIA: [&str; 2] = ["audio", "caption"];
+
+/// Page of the course, resolved from a page ID
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageRef {
+    Welcome,
+    Objectives,
+    Topic(usize),
+}
+
+pub fn new_topic_id() -> String {
+    format!("topic-{}", Uuid::new_v4())
+}
+
+pub fn new_media_id(kind: &str) -> String {
+    format!("{}-{}", kind, Uuid::new_v4())
+}
+
+/// IDs media may be stored under: letters, digits, `-` and `_`, so `{id}.bin`
+/// always names a file inside the media folder
+pub fn is_plain_media_id(media_id: &str) -> bool {
+    !media_id.is_empty()
+        && media_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// `audio-<uuid>` style IDs, whose page is only known from their page_id
+pub fn is_stable_media_id(media_id: &str) -> bool {
+    media_id
+        .split_once('-')
+        .is_some_and(|(_, rest)| Uuid::parse_str(rest).is_ok())
+}
+
+fn trailing_number(id: &str, prefix: &str) -> Option<usize> {
+    id.strip_prefix(prefix)?.strip_prefix('-')?.parse().ok()
+}
+
+/// `topic-3` style page IDs, which older projects used for the topic at index 3
+pub fn legacy_topic_index(page_id: &str) -> Option<usize> {
+    trailing_number(page_id, "topic")
+}
+
+/// Split a legacy `audio-5` / `caption-5` ID into its kind and number
+pub fn legacy_media_index(media_id: &str) -> Option<(&'static str, usize)> {
+    POSITIONAL_MEDIA
+        .iter()
+        .find_map(|kind| trailing_number(media_id, kind).map(|n| (*kind, n)))
+}
+
+/// Compatibility shim: the page a legacy audio/caption ID was numbered after
+/// (welcome=0, objectives=1, topics from 2)
+pub fn legacy_page_of_media(media_id: &str) -> Option<PageRef> {
+    match legacy_media_index(media_id)? {
+        (_, 0) => Some(PageRef::Welcome),
+        (_, 1) => Some(PageRef::Objectives),
+        (_, n) => Some(PageRef::Topic(n - 2)),
+    }
+}
+
+fn topics(course_content: &Value) -> &[Value] {
+    course_content
+        .get("topics")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Resolve a page_id as stored in media metadata. Stable IDs are matched
+/// against topic IDs; legacy `topic-N` IDs fall back to the topic at index N.
+pub fn resolve_page(course_content: &Value, page_id: &str) -> Option<PageRef> {
+    match page_id {
+        WELCOME_PAGE_ID => return Some(PageRef::Welcome),
+        OBJECTIVES_PAGE_ID => return Some(PageRef::Objectives),
+        _ => {}
+    }
+    let topics = topics(course_content);
+    if let Some(index) = legacy_topic_index(page_id) {
+        return (index < topics.len()).then_some(PageRef::Topic(index));
+    }
+    topics
+        .iter()
+        .position(|t| t.get("id").and_then(Value::as_str) == Some(page_id))
+        .map(PageRef::Topic)
+}
+
+/// The page_id to store for a page
+pub fn page_id_of(course_content: &Value, page: PageRef) -> Option<String> {
+    match page {
+        PageRef::Welcome => Some(WELCOME_PAGE_ID.to_string()),
+        PageRef::Objectives => Some(OBJECTIVES_PAGE_ID.to_string()),
+        PageRef::Topic(index) => topics(course_content)
+            .get(index)?
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+pub fn page_mut(course_content: &mut Value, page: PageRef) -> Option<&mut Value> {
+    match page {
+        PageRef::Welcome => course_content.get_mut("welcomePage"),
+        PageRef::Objectives => course_content.get_mut("learningObjectivesPage"),
+        PageRef::Topic(index) => course_content.get_mut("topics")?.get_mut(index),
+    }
+}
+
+fn pages(course_content: &Value) -> Vec<(PageRef, &Value)> {
+    let mut pages = Vec::new();
+    if let Some(page) = course_content.get("welcomePage") {
+        pages.push((PageRef::Welcome, page));
+    }
+    if let Some(page) = course_content.get("learningObjectivesPage") {
+        pages.push((PageRef::Objectives, page));
+    }
+    pages.extend(
+        topics(course_content)
+            .iter()
+            .enumerate()
+            .map(|(index, topic)| (PageRef::Topic(index), topic)),
+    );
+    pages
+}
+
+/// Media IDs referenced directly on a page: audioId/captionId and its media list
+pub(crate) fn page_media_ids(page: &Value) -> Vec<String> {
+    let mut ids: Vec<String> = ["audioId", "captionId"]
+        .iter()
+        .filter_map(|key| page.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    if let Some(media) = page.get("media").and_then(Value::as_array) {
+        for item in media {
+            for key in ["id", "storageId"] {
+                if let Some(id) = item.get(key).and_then(Value::as_str) {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+    }
+    ids
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StableIdMigration {
+    /// Topic IDs that were replaced, as (old ID, new ID)
+    pub topics: Vec<(String, String)>,
+    /// Media files whose page_id now names its page explicitly
+    pub reassigned_media: Vec<String>,
+    /// Media that could not be matched to a page and was left alone
+    pub warnings: Vec<String>,
+    /// False for a dry run
+    pub applied: bool,
+}
+
+/// Give every topic a stable ID and point every media page_id at its page by
+/// ID. Pages are found from course content references first, then from the
+/// page_id in the metadata and only then from the legacy numbering.
+/// Positional audio/caption IDs are kept: the editor still looks narration up
+/// by page position, and topic edits renumber them.
+///
+/// On error every file is put back. On success the caller must `commit` the
+/// returned changes once the project is saved, or `rollback` them.
+pub fn migrate_to_stable_ids(
+    course_content: &mut Value,
+    media_dir: &Path,
+    next_topic_id: &mut dyn FnMut() -> String,
+) -> Result<(StableIdMigration, MediaChanges), String> {
+    let mut result = StableIdMigration::default();
+    let original = course_content.clone();
+
+    // Topics: replace missing, positional and duplicate IDs
+    let mut seen = HashSet::new();
+    if let Some(topics) = course_content
+        .get_mut("topics")
+        .and_then(Value::as_array_mut)
+    {
+        for topic in topics.iter_mut() {
+            let id = topic
+                .get("id")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if id.is_empty() || legacy_topic_index(&id).is_some() || !seen.insert(id.clone()) {
+                let stable = next_topic_id();
+                result.topics.push((id, stable.clone()));
+                topic["id"] = Value::String(stable);
+            }
+        }
+    }
+
+    // Which page each media ID is referenced from
+    let mut referenced: BTreeMap<String, PageRef> = BTreeMap::new();
+    for (page, value) in pages(course_content) {
+        for id in page_media_ids(value) {
+            referenced.entry(id).or_insert(page);
+        }
+    }
+
+    let mut changes = MediaChanges::default();
+    let outcome = migrate_media(
+        &original,
+        course_content,
+        media_dir,
+        &referenced,
+        &mut changes,
+        &mut result,
+    );
+    if let Err(e) = outcome {
+        changes.rollback();
+        *course_content = original;
+        return Err(e);
+    }
+    Ok((result, changes))
+}
+
+fn migrate_media(
+    original: &Value,
+    course_content: &Value,
+    media_dir: &Path,
+    referenced: &BTreeMap<String, PageRef>,
+    changes: &mut MediaChanges,
+    result: &mut StableIdMigration,
+) -> Result<(), String> {
+    if !media_dir.exists() {
+        return Ok(());
+    }
+    let entries =
+        fs::read_dir(media_dir).map_err(|e| format!("Failed to read media directory: {}", e))?;
+    let mut metadata_files: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    metadata_files.sort();
+
+    for json_path in metadata_files {
+        let media_id = json_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let Ok(original_bytes) = fs::read(&json_path) else {
+            continue;
+        };
+        let Ok(mut metadata) = open_media_data(&json_path, original_bytes.clone())
+            .and_then(|json| serde_json::from_slice::<Value>(&json).map_err(|e| e.to_string()))
+        else {
+            result
+                .warnings
+                .push(format!("{}: metadata could not be read", media_id));
+            continue;
+        };
+        let page_id = metadata["page_id"].as_str().unwrap_or_default().to_string();
+
+        // Metadata page_ids were written against the old topic order and IDs
+        let page = referenced
+            .get(&media_id)
+            .copied()
+            .or_else(|| resolve_page(original, &page_id))
+            .or_else(|| legacy_page_of_media(&media_id));
+        let Some(stable_page_id) = page.and_then(|page| page_id_of(course_content, page)) else {
+            result.warnings.push(format!(
+                "{}: no page found for page_id '{}'",
+                media_id, page_id
+            ));
+            continue;
+        };
+
+        if page_id != stable_page_id {
+            metadata["page_id"] = Value::String(stable_page_id);
+            let content = serde_json::to_vec_pretty(&metadata)
+                .map_err(|e| format!("Failed to serialize media metadata: {}", e))?;
+            changes.rewrite(&json_path, original_bytes, &content)?;
+            result.reassigned_media.push(media_id);
+        }
+    }
+    Ok(())
+}
+
+/// A media entry added to a topic by `attach_media_by_page_id`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachedMedia {
+    pub page_id: String,
+    pub media_id: String,
+    pub media_type: String,
+}
+
+/// Import fix-up: add audio and captions in `media_dir` to the media list of
+/// the topic their metadata page_id names, if the topic does not reference
+/// them yet. Returns the media entries added.
+pub fn attach_media_by_page_id(course_content: &mut Value, media_dir: &Path) -> Vec<AttachedMedia> {
+    let Ok(entries) = fs::read_dir(media_dir) else {
+        return Vec::new();
+    };
+    let mut metadata_files: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    metadata_files.sort();
+
+    let mut added = Vec::new();
+    for json_path in metadata_files {
+        let Some(metadata) = read_media_file(&json_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+        else {
+            continue;
+        };
+        let media_type = metadata["type"].as_str().unwrap_or_default();
+        if !POSITIONAL_MEDIA.contains(&media_type) {
+            continue;
+        }
+        let page_id = metadata["page_id"].as_str().unwrap_or_default();
+        let Some(PageRef::Topic(index)) = resolve_page(course_content, page_id) else {
+            continue;
+        };
+        let media_id = json_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let Some(topic) = page_mut(course_content, PageRef::Topic(index)) else {
+            continue;
+        };
+        if page_media_ids(topic).contains(&media_id) {
+            continue;
+        }
+        let Some(media) = topic.get_mut("media").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        media.push(serde_json::json!({
+            "id": media_id,
+            "type": media_type,
+            "storageId": media_id,
+            "title": "",
+            "url": ""
+        }));
+        added.push(AttachedMedia {
+            page_id: page_id.to_string(),
+            media_id,
+            media_type: media_type.to_string(),
+        });
+    }
+    added
+}
+
+/// Migrate a project from positional topic IDs and media page_ids to stable
+/// ones. With `dryRun` the changes are reported and everything is put back.
+#[tauri::command]
+pub fn migrate_stable_ids(
+    #[allow(non_snake_case)] projectId: String,
+    #[allow(non_snake_case)] dryRun: Option<bool>,
+    services: State<'_, AppServices>,
+) -> Result<StableIdMigration, String> {
+    let project_path =
+        find_project_file(&projectId).ok_or_else(|| "Project file not found".to_string())?;
+    if !dryRun.unwrap_or(false) {
+        crate::project_access::ensure_writable(&project_path)?;
+    }
+    let mut project = load_project_file(&project_path)?;
+    let course_content = project
+        .course_content
+        .as_mut()
+        .ok_or_else(|| "Project has no course content yet".to_string())?;
+    let media_dir = get_media_directory(&projectId)?;
+
+    let (mut result, changes) = migrate_to_stable_ids(course_content, &media_dir, &mut || {
+        services.new_stable_id("topic")
+    })?;
+    if dryRun.unwrap_or(false) {
+        changes.rollback();
+        return Ok(result);
+    }
+    if let Err(e) = save_project_file(&project, &project_path) {
+        changes.rollback();
+        return Err(e);
+    }
+    changes.commit();
+    result.applied = true;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_services::testing::deterministic_services;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn write_media(dir: &Path, id: &str, media_type: &str, page_id: &str) {
+        fs::write(dir.join(format!("{}.bin", id)), id).unwrap();
+        fs::write(
+            dir.join(format!("{}.json", id)),
+            json!({ "page_id": page_id, "type": media_type, "original_name": id }).to_string(),
+        )
+        .unwrap();
+    }
+
+    fn page_id(dir: &Path, id: &str) -> String {
+        let json = fs::read_to_string(dir.join(format!("{}.json", id))).unwrap();
+        serde_json::from_str::<Value>(&json).unwrap()["page_id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_resolve_page_prefers_stable_ids() {
+        let content = json!({ "topics": [{ "id": "topic-a1" }, { "id": "safety" }] });
+        assert_eq!(resolve_page(&content, "welcome"), Some(PageRef::Welcome));
+        assert_eq!(resolve_page(&content, "safety"), Some(PageRef::Topic(1)));
+        // Legacy positional page IDs still resolve by index
+        assert_eq!(resolve_page(&content, "topic-1"), Some(PageRef::Topic(1)));
+        assert_eq!(resolve_page(&content, "topic-5"), None);
+        assert_eq!(legacy_page_of_media("caption-1"), Some(PageRef::Objectives));
+        assert_eq!(legacy_page_of_media("audio-4"), Some(PageRef::Topic(2)));
+        assert!(is_stable_media_id(&new_media_id("audio")));
+        assert!(!is_stable_media_id("audio-12"));
+    }
+
+    #[test]
+    fn test_migrate_to_stable_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        write_media(dir, "audio-0", "audio", "welcome");
+        write_media(dir, "audio-2", "audio", "topic-0");
+        write_media(dir, "audio-3", "audio", "topic-1");
+        // Not referenced by the content: found through its page_id
+        write_media(dir, "caption-3", "caption", "topic-1");
+        write_media(dir, "image-0", "image", "topic-1");
+        write_media(dir, "image-7", "image", "removed-page");
+        let mut content = json!({
+            "welcomePage": { "id": "welcome", "audioId": "audio-0" },
+            "topics": [
+                { "id": "topic-1", "audioId": "audio-2" },
+                { "id": "controls", "audioId": "audio-3", "media": [{ "id": "image-0" }] }
+            ]
+        });
+
+        let (_, services) = deterministic_services();
+        let (result, changes) =
+            migrate_to_stable_ids(&mut content, dir, &mut || services.new_stable_id("topic"))
+                .unwrap();
+        changes.commit();
+
+        let stable = "topic-00000000-0000-0000-0000-018bcfe56800";
+        assert_eq!(
+            result.topics,
+            vec![("topic-1".to_string(), stable.to_string())]
+        );
+        assert_eq!(content["topics"][0]["id"], stable);
+        assert_eq!(content["topics"][1]["id"], "controls");
+        // Positional audio and caption IDs are kept
+        assert_eq!(content["welcomePage"]["audioId"], "audio-0");
+        assert_eq!(content["topics"][0]["audioId"], "audio-2");
+        assert_eq!(content["topics"][1]["audioId"], "audio-3");
+        assert_eq!(
+            fs::read_to_string(dir.join("audio-2.bin")).unwrap(),
+            "audio-2"
+        );
+        assert_eq!(page_id(dir, "audio-2"), stable);
+        assert_eq!(page_id(dir, "caption-3"), "controls");
+        assert_eq!(page_id(dir, "image-0"), "controls");
+        assert_eq!(page_id(dir, "audio-0"), "welcome");
+        assert_eq!(result.warnings.len(), 1, "{:?}", result.warnings);
+        assert_eq!(page_id(dir, "image-7"), "removed-page");
+    }
+
+    #[test]
+    fn test_attach_media_by_page_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        write_media(dir, "audio-x", "audio", "topic-b");
+        write_media(dir, "caption-y", "caption", "topic-b");
+        write_media(dir, "image-0", "image", "topic-b");
+        let mut content = json!({
+            "topics": [
+                { "id": "topic-a", "media": [] },
+                { "id": "topic-b", "captionId": "caption-y", "media": [] }
+            ]
+        });
+
+        assert_eq!(attach_media_by_page_id(&mut content, dir).len(), 1);
+        assert_eq!(content["topics"][0]["media"], json!([]));
+        assert_eq!(content["topics"][1]["media"][0]["id"], "audio-x");
+        assert!(attach_media_by_page_id(&mut content, dir).is_empty());
+    }
+}
@@ -0,0 +1,505 @@
+//! Image search against the stock providers the user has keys for.
+//!
+//! Searches run here rather than in the webview so the keys stay in the
+//! encrypted key file, and so every provider's results come back in one
+//! shape, with the author and license needed to credit an image once it is
+//! stored with the project.
+
+use crate::api_keys::{load_api_keys, ApiKeys};
+use crate::api_quota::{check_quota, record_usage, today};
+use crate::app_services::AppServices;
+use crate::external_media::fetch_external_media;
+use crate::media_storage::MediaAttribution;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+/// Results asked for per page, where the provider lets us choose
+const PER_PAGE: u32 = 20;
+/// Largest thumbnail kept in the cache
+const MAX_THUMBNAIL_BYTES: usize = 2 * 1024 * 1024;
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One search result, the same for every provider
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StockImage {
+    pub id: String,
+    pub provider: String,
+    pub title: String,
+    pub thumbnail_url: String,
+    pub full_url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub attribution: MediaAttribution,
+    /// Local copy of the thumbnail, when caching was asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_thumbnail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StockProvider {
+    Google,
+    Unsplash,
+    Pexels,
+}
+
+impl StockProvider {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "google" => Ok(Self::Google),
+            "unsplash" => Ok(Self::Unsplash),
+            "pexels" => Ok(Self::Pexels),
+            _ => Err(format!("Unknown image provider: {name}")),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Google => "google",
+            Self::Unsplash => "unsplash",
+            Self::Pexels => "pexels",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GoogleResponse {
+    #[serde(default)]
+    items: Vec<GoogleItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleItem {
+    link: String,
+    #[serde(default)]
+    title: String,
+    cache_id: Option<String>,
+    image: Option<GoogleImage>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct GoogleImage {
+    context_link: Option<String>,
+    thumbnail_link: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct UnsplashResponse {
+    #[serde(default)]
+    results: Vec<UnsplashPhoto>,
+}
+
+#[derive(Deserialize)]
+struct UnsplashPhoto {
+    id: String,
+    description: Option<String>,
+    alt_description: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    urls: UnsplashUrls,
+    links: UnsplashLinks,
+    user: UnsplashUser,
+}
+
+#[derive(Deserialize)]
+struct UnsplashUrls {
+    small: String,
+    regular: String,
+}
+
+#[derive(Deserialize)]
+struct UnsplashLinks {
+    html: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UnsplashUser {
+    name: Option<String>,
+    links: Option<UnsplashLinks>,
+}
+
+#[derive(Deserialize)]
+struct PexelsResponse {
+    #[serde(default)]
+    photos: Vec<PexelsPhoto>,
+}
+
+#[derive(Deserialize)]
+struct PexelsPhoto {
+    id: u64,
+    width: Option<u32>,
+    height: Option<u32>,
+    url: Option<String>,
+    photographer: Option<String>,
+    photographer_url: Option<String>,
+    alt: Option<String>,
+    src: PexelsSources,
+}
+
+#[derive(Deserialize)]
+struct PexelsSources {
+    medium: String,
+    large2x: String,
+}
+
+fn parse_json<T: for<'de> Deserialize<'de>>(provider: &str, body: &str) -> Result<T, String> {
+    serde_json::from_str(body).map_err(|e| format!("Failed to parse {provider} results: {e}"))
+}
+
+/// Google returns images from anywhere on the web, so there is no license to
+/// record; only the page the image was found on
+fn parse_google(body: &str) -> Result<Vec<StockImage>, String> {
+    let response: GoogleResponse = parse_json("google", body)?;
+    Ok(response
+        .items
+        .into_iter()
+        .filter(|item| !item.link.to_ascii_lowercase().ends_with(".svg"))
+        .map(|item| {
+            let image = item.image.unwrap_or_default();
+            StockImage {
+                id: item.cache_id.unwrap_or_else(|| item.link.clone()),
+                provider: "google".to_string(),
+                title: item.title,
+                thumbnail_url: image.thumbnail_link.unwrap_or_else(|| item.link.clone()),
+                full_url: item.link,
+                width: image.width,
+                height: image.height,
+                attribution: MediaAttribution {
+                    provider: "google".to_string(),
+                    source_url: image.context_link,
+                    ..Default::default()
+                },
+                cached_thumbnail: None,
+            }
+        })
+        .collect())
+}
+
+fn parse_unsplash(body: &str) -> Result<Vec<StockImage>, String> {
+    let response: UnsplashResponse = parse_json("unsplash", body)?;
+    Ok(response
+        .results
+        .into_iter()
+        .map(|photo| StockImage {
+            id: photo.id,
+            provider: "unsplash".to_string(),
+            title: photo
+                .description
+                .or(photo.alt_description)
+                .unwrap_or_default(),
+            thumbnail_url: photo.urls.small,
+            full_url: photo.urls.regular,
+            width: photo.width,
+            height: photo.height,
+            attribution: MediaAttribution {
+                provider: "unsplash".to_string(),
+                author: photo.user.name,
+                author_url: photo.user.links.and_then(|links| links.html),
+                source_url: photo.links.html,
+                license: Some("Unsplash License".to_string()),
+                license_url: Some("https://unsplash.com/license".to_string()),
+            },
+            cached_thumbnail: None,
+        })
+        .collect())
+}
+
+fn parse_pexels(body: &str) -> Result<Vec<StockImage>, String> {
+    let response: PexelsResponse = parse_json("pexels", body)?;
+    Ok(response
+        .photos
+        .into_iter()
+        .map(|photo| StockImage {
+            id: photo.id.to_string(),
+            provider: "pexels".to_string(),
+            title: photo.alt.unwrap_or_default(),
+            thumbnail_url: photo.src.medium,
+            full_url: photo.src.large2x,
+            width: photo.width,
+            height: photo.height,
+            attribution: MediaAttribution {
+                provider: "pexels".to_string(),
+                author: photo.photographer,
+                author_url: photo.photographer_url,
+                source_url: photo.url,
+                license: Some("Pexels License".to_string()),
+                license_url: Some("https://www.pexels.com/license/".to_string()),
+            },
+            cached_thumbnail: None,
+        })
+        .collect())
+}
+
+/// Search URL and authorization header (if the key isn't in the URL)
+fn search_request(
+    provider: StockProvider,
+    keys: &ApiKeys,
+    query: &str,
+    page: u32,
+) -> Result<(Url, Option<String>), String> {
+    let missing_key = || {
+        format!(
+            "No API key for {}; add one in the settings",
+            provider.name()
+        )
+    };
+    let page = page.max(1);
+    let page_param = page.to_string();
+    let per_page = PER_PAGE.to_string();
+    let (base, params, authorization): (&str, Vec<(&str, &str)>, Option<String>) = match provider {
+        StockProvider::Google => {
+            if keys.google_image_api_key.is_empty() || keys.google_cse_id.is_empty() {
+                return Err(missing_key());
+            }
+            (
+                "https://www.googleapis.com/customsearch/v1",
+                vec![
+                    ("key", keys.google_image_api_key.as_str()),
+                    ("cx", keys.google_cse_id.as_str()),
+                    ("searchType", "image"),
+                ],
+                None,
+            )
+        }
+        StockProvider::Unsplash => {
+            if keys.unsplash_access_key.is_empty() {
+                return Err(missing_key());
+            }
+            (
+                "https://api.unsplash.com/search/photos",
+                vec![
+                    ("query", query),
+                    ("page", &page_param),
+                    ("per_page", &per_page),
+                ],
+                Some(format!("Client-ID {}", keys.unsplash_access_key)),
+            )
+        }
+        StockProvider::Pexels => {
+            if keys.pexels_api_key.is_empty() {
+                return Err(missing_key());
+            }
+            (
+                "https://api.pexels.com/v1/search",
+                vec![
+                    ("query", query),
+                    ("page", &page_param),
+                    ("per_page", &per_page),
+                ],
+                Some(keys.pexels_api_key.clone()),
+            )
+        }
+    };
+    let mut url = Url::parse_with_params(base, params)
+        .map_err(|e| format!("Failed to build search URL: {e}"))?;
+    if provider == StockProvider::Google {
+        // Google pages by result index, 10 at a time; SVGs can't go in a package
+        let start = ((page - 1) * 10 + 1).to_string();
+        url.query_pairs_mut()
+            .append_pair("q", &format!("{query} -filetype:svg"))
+            .append_pair("start", &start);
+    }
+    Ok((url, authorization))
+}
+
+async fn fetch_results(url: Url, authorization: Option<String>) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(SEARCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let mut request = client.get(url);
+    if let Some(authorization) = authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to search images: {e}"))?;
+    let status = response.status();
+    match status.as_u16() {
+        401 | 403 => return Err("Invalid API key or insufficient permissions".to_string()),
+        429 => return Err("Rate limit exceeded. Please try again later".to_string()),
+        _ if !status.is_success() => return Err(format!("Image search failed: {status}")),
+        _ => {}
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read search results: {e}"))
+}
+
+fn thumbnail_cache_dir() -> Result<PathBuf, String> {
+    Ok(crate::settings::get_app_config_dir()?.join("thumbnail_cache"))
+}
+
+/// Cached copy of `url` in `dir`, downloading it if there isn't one yet
+async fn cache_thumbnail(dir: &Path, url: &str) -> Result<PathBuf, String> {
+    let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+    if let Some(existing) = fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.file_stem().is_some_and(|stem| *stem == *key))
+    {
+        return Ok(existing);
+    }
+
+    let media = fetch_external_media(url, MAX_THUMBNAIL_BYTES).await?;
+    let extension = match media.mime_type.as_str() {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "jpg",
+    };
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create thumbnail cache: {e}"))?;
+    let path = dir.join(format!("{key}.{extension}"));
+    fs::write(&path, &media.data).map_err(|e| format!("Failed to cache thumbnail: {e}"))?;
+    Ok(path)
+}
+
+/// Search `provider` (`google`, `unsplash` or `pexels`) for images, with
+/// `page` starting at 1. With `cache_thumbnails`, thumbnails are also saved
+/// in the app's cache so they can be shown offline; one that fails to
+/// download is just left uncached.
+#[tauri::command]
+pub async fn search_stock_images(
+    services: tauri::State<'_, AppServices>,
+    query: String,
+    provider: String,
+    page: Option<u32>,
+    cache_thumbnails: Option<bool>,
+) -> Result<Vec<StockImage>, String> {
+    let provider = StockProvider::parse(&provider)?;
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let keys = load_api_keys()?;
+    let (url, authorization) = search_request(provider, &keys, query.trim(), page.unwrap_or(1))?;
+
+    let day = today(&services);
+    check_quota(provider.name(), day)?;
+    let body = fetch_results(url, authorization).await;
+    let _ = record_usage(provider.name(), day, 1, 0);
+    let body = body?;
+    let mut images = match provider {
+        StockProvider::Google => parse_google(&body)?,
+        StockProvider::Unsplash => parse_unsplash(&body)?,
+        StockProvider::Pexels => parse_pexels(&body)?,
+    };
+
+    if cache_thumbnails.unwrap_or(false) {
+        let dir = thumbnail_cache_dir()?;
+        for image in &mut images {
+            image.cached_thumbnail = cache_thumbnail(&dir, &image.thumbnail_url)
+                .await
+                .ok()
+                .map(|path| path.to_string_lossy().to_string());
+        }
+    }
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> ApiKeys {
+        ApiKeys {
+            google_image_api_key: "g-key".to_string(),
+            google_cse_id: "cse".to_string(),
+            youtube_api_key: String::new(),
+            unsplash_access_key: "u-key".to_string(),
+            pexels_api_key: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_results_are_normalized_with_attribution() {
+        let unsplash = parse_unsplash(
+            r#"{"total": 1, "results": [{
+                "id": "abc", "description": null, "alt_description": "A red kite",
+                "width": 4000, "height": 3000,
+                "urls": {"raw": "r", "full": "f", "regular": "https://images.unsplash.com/abc?w=1080", "small": "https://images.unsplash.com/abc?w=400", "thumb": "t"},
+                "links": {"html": "https://unsplash.com/photos/abc"},
+                "user": {"name": "Ada Lovelace", "links": {"html": "https://unsplash.com/@ada"}}
+            }]}"#,
+        )
+        .unwrap();
+        assert_eq!(unsplash[0].title, "A red kite");
+        assert_eq!(
+            unsplash[0].full_url,
+            "https://images.unsplash.com/abc?w=1080"
+        );
+        assert_eq!(
+            unsplash[0].attribution.author.as_deref(),
+            Some("Ada Lovelace")
+        );
+        assert_eq!(
+            unsplash[0].attribution.license.as_deref(),
+            Some("Unsplash License")
+        );
+
+        let pexels = parse_pexels(
+            r#"{"page": 1, "photos": [{
+                "id": 42, "width": 1200, "height": 800, "url": "https://www.pexels.com/photo/42/",
+                "photographer": "Grace Hopper", "photographer_url": "https://www.pexels.com/@grace",
+                "alt": "Harbour at dusk",
+                "src": {"original": "o", "large2x": "https://images.pexels.com/42-large2x.jpg", "medium": "https://images.pexels.com/42-medium.jpg"}
+            }]}"#,
+        )
+        .unwrap();
+        assert_eq!(pexels[0].id, "42");
+        assert_eq!(
+            pexels[0].thumbnail_url,
+            "https://images.pexels.com/42-medium.jpg"
+        );
+        assert_eq!(
+            pexels[0].attribution.source_url.as_deref(),
+            Some("https://www.pexels.com/photo/42/")
+        );
+
+        let google = parse_google(
+            r#"{"items": [
+                {"link": "https://example.com/diagram.svg", "title": "Diagram"},
+                {"link": "https://example.com/photo.jpg", "title": "Photo",
+                 "image": {"contextLink": "https://example.com/article", "thumbnailLink": "https://t.example/1", "width": 640, "height": 480}}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(google.len(), 1);
+        assert_eq!(google[0].thumbnail_url, "https://t.example/1");
+        assert_eq!(google[0].attribution.license, None);
+        assert!(parse_google("{}").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_requests_carry_key_and_page() {
+        let (url, authorization) =
+            search_request(StockProvider::Google, &keys(), "solar panels", 3).unwrap();
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert!(params.contains(&("q".to_string(), "solar panels -filetype:svg".to_string())));
+        assert!(params.contains(&("start".to_string(), "21".to_string())));
+        assert_eq!(authorization, None);
+
+        let (url, authorization) =
+            search_request(StockProvider::Unsplash, &keys(), "solar panels", 0).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://api.unsplash.com/search/photos?query=solar+panels&page=1&per_page=20"
+        );
+        assert_eq!(authorization.as_deref(), Some("Client-ID u-key"));
+
+        let error = search_request(StockProvider::Pexels, &keys(), "solar", 1).unwrap_err();
+        assert!(error.contains("No API key for pexels"));
+        assert!(StockProvider::parse("bing").is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! Size budgets for a project's media.
+//!
+//! Recordings and videos add up quickly, and a project of several gigabytes is
+//! slow to back up, export and upload to an LMS. Each project has a budget:
+//! past `warn_bytes` its statistics carry a warning, and `store_media` refuses
+//! a file that would take the project past `block_bytes` or one media type
+//! past its own limit. Both name the media types taking the most space, so
+//! authors know where to trim. The budget comes from the app settings unless
+//! the project has its own in [`BUDGET_FILE`].
+
+use crate::disk_space::format_size;
+use crate::messages::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+
+const MB: u64 = 1024 * 1024;
+
+/// Kept in a project folder next to `media/` when the project has its own budget
+pub const BUDGET_FILE: &str = "storage-budget.json";
+
+/// Media types named in warnings and errors, largest first
+const NAMED_TYPES: usize = 3;
+
+/// Media size budget of a project, or the workspace default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct StorageBudget {
+    /// Media size past which the project statistics warn; never when null
+    #[ts(type = "number | null")]
+    pub warn_bytes: Option<u64>,
+    /// Media size `store_media` won't take the project past; no limit when null
+    #[ts(type = "number | null")]
+    pub block_bytes: Option<u64>,
+    /// Largest total per media type (`image`, `audio`, ...), in bytes.
+    /// Types without an entry are only limited by `block_bytes`.
+    #[ts(type = "Record<string, number>")]
+    pub max_type_bytes: HashMap<String, u64>,
+}
+
+impl Default for StorageBudget {
+    fn default() -> Self {
+        Self {
+            warn_bytes: Some(500 * MB),
+            block_bytes: Some(2048 * MB),
+            max_type_bytes: HashMap::new(),
+        }
+    }
+}
+
+/// Number and total size of a project's media files of one type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct MediaTypeUsage {
+    pub media_type: String,
+    pub files: usize,
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum BudgetStatus {
+    WithinBudget,
+    /// Past `warn_bytes`
+    Warning,
+    /// At or past `block_bytes`; no more media can be added
+    Full,
+}
+
+/// A project's media size against its budget
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct StorageReport {
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+    pub budget: StorageBudget,
+    pub status: BudgetStatus,
+    /// Largest first
+    pub by_type: Vec<MediaTypeUsage>,
+    /// What to do about a project past its warning level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    #[ts(type = "{ message: string, messageKey: string, params: Record<string, string> }")]
+    pub warning: Option<Message>,
+}
+
+/// `image` for `image-3`, the way media IDs and their `.bin` files are named
+fn media_type_of(media_id: &str) -> &str {
+    media_id.split_once('-').map_or(media_id, |(media_type, _)| media_type)
+}
+
+/// Size of the media in `media_dir` by type, largest first
+pub fn media_usage(media_dir: &Path) -> Vec<MediaTypeUsage> {
+    let mut totals: HashMap<String, (usize, u64)> = HashMap::new();
+    for entry in fs::read_dir(media_dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("bin") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let size = entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+        let total = totals.entry(media_type_of(stem).to_string()).or_default();
+        total.0 += 1;
+        total.1 += size;
+    }
+    let mut usage: Vec<_> = totals
+        .into_iter()
+        .map(|(media_type, (files, bytes))| MediaTypeUsage {
+            media_type,
+            files,
+            bytes,
+        })
+        .collect();
+    usage.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.media_type.cmp(&b.media_type)));
+    usage
+}
+
+/// "video (1.2 GB in 4 files), audio (300.0 MB in 12 files)"
+fn largest_types(usage: &[MediaTypeUsage]) -> String {
+    usage
+        .iter()
+        .filter(|usage| usage.bytes > 0)
+        .take(NAMED_TYPES)
+        .map(|usage| {
+            format!(
+                "{} ({} in {} file{})",
+                usage.media_type,
+                format_size(usage.bytes),
+                usage.files,
+                if usage.files == 1 { "" } else { "s" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl StorageBudget {
+    /// Whether a `size`-byte file `media_id` fits in a project using `usage`.
+    /// `replaced` is the size of the file it replaces, if any.
+    pub fn check_store(
+        &self,
+        usage: &[MediaTypeUsage],
+        media_id: &str,
+        size: u64,
+        replaced: u64,
+    ) -> Result<(), Message> {
+        let media_type = media_type_of(media_id);
+        let total: u64 = usage.iter().map(|usage| usage.bytes).sum();
+        let new_total = (total + size).saturating_sub(replaced);
+        if let Some(&limit) = self.max_type_bytes.get(media_type) {
+            let type_bytes = usage
+                .iter()
+                .find(|usage| usage.media_type == media_type)
+                .map_or(0, |usage| usage.bytes);
+            let new_type_bytes = (type_bytes + size).saturating_sub(replaced);
+            if new_type_bytes > limit {
+                return Err(Message::new("error.storage_budget.media_type")
+                    .with("file", media_id)
+                    .with("type", media_type)
+                    .with("size", format_size(new_type_bytes))
+                    .with("limit", format_size(limit))
+                    .with("largest", largest_types(usage)));
+            }
+        }
+        match self.block_bytes {
+            Some(limit) if new_total > limit => Err(Message::new("error.storage_budget.project")
+                .with("file", media_id)
+                .with("size", format_size(new_total))
+                .with("limit", format_size(limit))
+                .with("largest", largest_types(usage))),
+            _ => Ok(()),
+        }
+    }
+
+    /// `usage` measured against this budget
+    pub fn report(&self, usage: Vec<MediaTypeUsage>) -> StorageReport {
+        let total_bytes: u64 = usage.iter().map(|usage| usage.bytes).sum();
+        let status = if self.block_bytes.is_some_and(|limit| total_bytes >= limit) {
+            BudgetStatus::Full
+        } else if self.warn_bytes.is_some_and(|limit| total_bytes > limit) {
+            BudgetStatus::Warning
+        } else {
+            BudgetStatus::WithinBudget
+        };
+        let warning = (status != BudgetStatus::WithinBudget).then(|| {
+            let limit = match status {
+                BudgetStatus::Full => self.block_bytes,
+                _ => self.warn_bytes,
+            };
+            Message::new("warning.storage_budget")
+                .with("size", format_size(total_bytes))
+                .with("limit", format_size(limit.unwrap_or_default()))
+                .with("largest", largest_types(&usage))
+        });
+        StorageReport {
+            total_bytes,
+            budget: self.clone(),
+            status,
+            by_type: usage,
+            warning,
+        }
+    }
+}
+
+fn load_project_budget(project_dir: &Path) -> Result<Option<StorageBudget>, String> {
+    let path = project_dir.join(BUDGET_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read storage budget: {e}"))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse storage budget: {e}"))
+}
+
+/// The budget of the project in `project_dir`: its own, else the one in the
+/// app settings, else the default
+pub fn budget_for(project_dir: &Path) -> StorageBudget {
+    load_project_budget(project_dir)
+        .ok()
+        .flatten()
+        .or_else(|| {
+            crate::settings::load_settings()
+                .ok()
+                .and_then(|settings| settings.storage_budget)
+        })
+        .unwrap_or_default()
+}
+
+/// Refuse a `size`-byte `media_id` the project's budget has no room for
+pub fn check_media_budget(project_id: &str, media_id: &str, size: u64) -> Result<(), Message> {
+    let media_dir = crate::media_storage::get_media_directory(project_id)?;
+    let Some(project_dir) = media_dir.parent() else {
+        return Ok(());
+    };
+    let replaced = fs::metadata(media_dir.join(format!("{media_id}.bin")))
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    budget_for(project_dir).check_store(&media_usage(&media_dir), media_id, size, replaced)
+}
+
+/// Media size of the project whose media is in `media_dir`, against its budget
+pub fn storage_report(media_dir: &Path) -> StorageReport {
+    let budget = media_dir.parent().map(budget_for).unwrap_or_default();
+    budget.report(media_usage(media_dir))
+}
+
+/// The budget of a project, or the workspace default without `projectId`
+#[tauri::command]
+pub fn get_storage_budget(
+    #[allow(non_snake_case)] projectId: Option<String>,
+) -> Result<StorageBudget, String> {
+    match projectId {
+        Some(project_id) => {
+            let projects_dir = crate::settings::get_projects_directory()?;
+            Ok(budget_for(&projects_dir.join(project_id)))
+        }
+        None => Ok(crate::settings::load_settings()?
+            .storage_budget
+            .unwrap_or_default()),
+    }
+}
+
+/// Set the budget of a project, or the workspace default without `projectId`.
+/// A null budget removes the project's own, or resets the default.
+#[tauri::command]
+pub fn set_storage_budget(
+    #[allow(non_snake_case)] projectId: Option<String>,
+    budget: Option<StorageBudget>,
+) -> Result<(), String> {
+    let Some(project_id) = projectId else {
+        let mut settings = crate::settings::load_settings()?;
+        settings.storage_budget = budget;
+        return crate::settings::save_settings(&settings);
+    };
+    if !crate::backup_recovery::is_project_id(&project_id) {
+        return Err(format!("Invalid project ID: {project_id}"));
+    }
+    let project_dir = crate::settings::get_projects_directory()?.join(project_id);
+    let path = project_dir.join(BUDGET_FILE);
+    let Some(budget) = budget else {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove storage budget: {e}"))?;
+        }
+        return Ok(());
+    };
+    fs::create_dir_all(&project_dir)
+        .map_err(|e| format!("Failed to create project directory: {e}"))?;
+    let json = serde_json::to_string_pretty(&budget)
+        .map_err(|e| format!("Failed to serialize storage budget: {e}"))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write storage budget: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write storage budget: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn usage(media_type: &str, files: usize, mb: u64) -> MediaTypeUsage {
+        MediaTypeUsage {
+            media_type: media_type.to_string(),
+            files,
+            bytes: mb * MB,
+        }
+    }
+
+    #[test]
+    fn test_media_is_totalled_by_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let media_dir = temp_dir.path();
+        fs::write(media_dir.join("audio-0.bin"), [0u8; 30]).unwrap();
+        fs::write(media_dir.join("audio-0.json"), "{}").unwrap();
+        fs::write(media_dir.join("audio-1.bin"), [0u8; 20]).unwrap();
+        fs::write(media_dir.join("image-0.bin"), [0u8; 10]).unwrap();
+
+        let usage: Vec<_> = media_usage(media_dir)
+            .into_iter()
+            .map(|usage| (usage.media_type, usage.files, usage.bytes))
+            .collect();
+        assert_eq!(usage, [("audio".to_string(), 2, 50), ("image".to_string(), 1, 10)]);
+    }
+
+    #[test]
+    fn test_budget_blocks_and_warns_naming_the_largest_types() {
+        let budget = StorageBudget {
+            max_type_bytes: HashMap::from([("audio".to_string(), 400 * MB)]),
+            ..StorageBudget::default()
+        };
+        let usage = vec![usage("video", 4, 1500), usage("audio", 12, 300), usage("image", 1, 20)];
+
+        assert!(budget.check_store(&usage, "image-1", 50 * MB, 0).is_ok());
+        let error = budget.check_store(&usage, "video-4", 300 * MB, 0).unwrap_err();
+        assert_eq!(error.key, "error.storage_budget.project");
+        let largest = &error.params.iter().find(|(name, _)| *name == "largest").unwrap().1;
+        assert_eq!(
+            largest,
+            "video (1.5 GB in 4 files), audio (300.0 MB in 12 files), image (20.0 MB in 1 file)"
+        );
+        // Replacing a file only counts the difference
+        assert!(budget.check_store(&usage, "video-0", 300 * MB, 280 * MB).is_ok());
+
+        let error = budget.check_store(&usage, "audio-12", 150 * MB, 0).unwrap_err();
+        assert_eq!(error.key, "error.storage_budget.media_type");
+
+        let report = budget.report(usage);
+        assert_eq!(report.status, BudgetStatus::Warning);
+        assert_eq!(report.total_bytes, 1820 * MB);
+        assert!(report.warning.is_some());
+        assert_eq!(
+            StorageBudget::default().report(Vec::new()).status,
+            BudgetStatus::WithinBudget
+        );
+    }
+}
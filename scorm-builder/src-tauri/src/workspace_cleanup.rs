@@ -0,0 +1,254 @@
+use crate::app_services::AppServices;
+use crate::backup_recovery::{
+    is_project_id, modified_time, split_project_file_name, RecoveryItemKind,
+};
+use crate::disk_space::path_size;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+/// Screenshots older than this are considered stale unless the caller says otherwise
+const DEFAULT_SCREENSHOT_MAX_AGE_DAYS: u32 = 7;
+
+/// Kind of leftover found in the projects directory
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceArtifactKind {
+    /// Project folder (media etc.) whose `.scormproj` no longer exists
+    OrphanedProjectFolder,
+    /// `.scormproj.tmp` left behind by a save that never completed
+    PartialWrite,
+    /// Workflow screenshot older than the cutoff
+    StaleScreenshot,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceArtifact {
+    pub path: String,
+    pub kind: WorkspaceArtifactKind,
+    /// Bytes on disk, including everything inside a folder
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceCleanupReport {
+    pub artifacts: Vec<WorkspaceArtifact>,
+    pub total_size: u64,
+    /// Whether the artifacts were deleted or only listed
+    pub removed: bool,
+    /// Artifacts that could not be deleted, with the reason
+    pub errors: Vec<(String, String)>,
+}
+
+fn artifact(path: &Path, kind: WorkspaceArtifactKind) -> WorkspaceArtifact {
+    WorkspaceArtifact {
+        path: path.to_string_lossy().to_string(),
+        kind,
+        size: path_size(path),
+        modified: modified_time(path).map(|time| DateTime::<Utc>::from(time).to_rfc3339()),
+    }
+}
+
+/// List everything in `projects_dir` that no project refers to any more.
+/// Screenshots count as stale once they were last modified before `stale_before`.
+pub fn scan_workspace(
+    projects_dir: &Path,
+    stale_before: DateTime<Utc>,
+) -> Result<Vec<WorkspaceArtifact>, String> {
+    let entries = fs::read_dir(projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+    let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+
+    let project_ids: HashSet<String> = paths
+        .iter()
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("scormproj"))
+        .filter_map(|path| split_project_file_name(path).1)
+        .collect();
+
+    let mut artifacts = Vec::new();
+    for path in &paths {
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if is_project_id(file_name) && !project_ids.contains(file_name) {
+                artifacts.push(artifact(path, WorkspaceArtifactKind::OrphanedProjectFolder));
+            }
+        } else if let Some((RecoveryItemKind::PartialWrite, _)) = RecoveryItemKind::classify(path) {
+            artifacts.push(artifact(path, WorkspaceArtifactKind::PartialWrite));
+        }
+    }
+
+    let screenshots_dir = projects_dir.join("workflow-screenshots");
+    if let Ok(entries) = fs::read_dir(&screenshots_dir) {
+        let mut screenshots: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        screenshots.sort();
+        for path in screenshots {
+            let is_stale = modified_time(&path)
+                .map(|time| DateTime::<Utc>::from(time) < stale_before)
+                .unwrap_or(false);
+            if path.is_file() && is_stale {
+                artifacts.push(artifact(&path, WorkspaceArtifactKind::StaleScreenshot));
+            }
+        }
+    }
+
+    Ok(artifacts)
+}
+
+/// Scan `projects_dir` and, when `confirm` is set, delete what was found
+pub fn cleanup_workspace_in(
+    projects_dir: &Path,
+    stale_before: DateTime<Utc>,
+    confirm: bool,
+) -> Result<WorkspaceCleanupReport, String> {
+    let artifacts = scan_workspace(projects_dir, stale_before)?;
+    let mut report = WorkspaceCleanupReport {
+        total_size: artifacts.iter().map(|a| a.size).sum(),
+        removed: confirm,
+        ..Default::default()
+    };
+
+    if confirm {
+        for artifact in &artifacts {
+            let path = Path::new(&artifact.path);
+            let result = if artifact.kind == WorkspaceArtifactKind::OrphanedProjectFolder {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            };
+            match result {
+                Ok(()) => println!("[cleanup] Removed {:?}", path),
+                Err(e) => report
+                    .errors
+                    .push((artifact.path.clone(), format!("Failed to remove: {}", e))),
+            }
+        }
+    }
+
+    report.artifacts = artifacts;
+    Ok(report)
+}
+
+/// List orphaned project folders, temp files and stale workflow screenshots.
+/// Nothing is deleted unless `confirm` is true.
+#[tauri::command]
+pub fn cleanup_workspace(
+    confirm: Option<bool>,
+    #[allow(non_snake_case)] maxScreenshotAgeDays: Option<u32>,
+    services: State<'_, AppServices>,
+) -> Result<WorkspaceCleanupReport, String> {
+    let max_age = maxScreenshotAgeDays.unwrap_or(DEFAULT_SCREENSHOT_MAX_AGE_DAYS);
+    let stale_before = services.now() - Duration::days(i64::from(max_age));
+    cleanup_workspace_in(
+        &crate::settings::get_projects_directory()?,
+        stale_before,
+        confirm.unwrap_or(false),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn workspace() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("Safety_1234567890.scormproj"), "{}").unwrap();
+        fs::create_dir_all(dir.join("1234567890").join("media")).unwrap();
+        fs::create_dir_all(dir.join("42").join("media")).unwrap();
+        fs::write(
+            dir.join("42").join("media").join("image-0.bin"),
+            vec![0u8; 100],
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("9f1c2e4a-58b1-4c1e-9c7e-2d3f4a5b6c7d")).unwrap();
+        fs::write(dir.join("Other_7.scormproj.tmp"), "{\"v\": ").unwrap();
+        fs::create_dir_all(dir.join("workflow-screenshots")).unwrap();
+        fs::write(
+            dir.join("workflow-screenshots").join("step-1.png"),
+            vec![0u8; 10],
+        )
+        .unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_scan_workspace_lists_artifacts() {
+        let temp_dir = workspace();
+        let dir = temp_dir.path();
+
+        // Nothing is stale yet with a cutoff in the past
+        let artifacts = scan_workspace(dir, Utc::now() - Duration::days(1)).unwrap();
+        let found: Vec<_> = artifacts
+            .iter()
+            .map(|a| {
+                (
+                    Path::new(&a.path)
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string(),
+                    a.kind,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "42".to_string(),
+                    WorkspaceArtifactKind::OrphanedProjectFolder
+                ),
+                (
+                    "9f1c2e4a-58b1-4c1e-9c7e-2d3f4a5b6c7d".to_string(),
+                    WorkspaceArtifactKind::OrphanedProjectFolder
+                ),
+                (
+                    "Other_7.scormproj.tmp".to_string(),
+                    WorkspaceArtifactKind::PartialWrite
+                ),
+            ]
+        );
+        assert_eq!(artifacts[0].size, 100);
+
+        let artifacts = scan_workspace(dir, Utc::now() + Duration::days(1)).unwrap();
+        assert_eq!(
+            artifacts.last().unwrap().kind,
+            WorkspaceArtifactKind::StaleScreenshot
+        );
+    }
+
+    #[test]
+    fn test_cleanup_workspace_only_removes_when_confirmed() {
+        let temp_dir = workspace();
+        let dir = temp_dir.path();
+        let cutoff = Utc::now() + Duration::days(1);
+
+        let dry_run = cleanup_workspace_in(dir, cutoff, false).unwrap();
+        assert!(!dry_run.removed);
+        assert_eq!(dry_run.artifacts.len(), 4);
+        assert_eq!(dry_run.total_size, 100 + 6 + 10);
+        assert!(dir.join("42").exists());
+
+        let report = cleanup_workspace_in(dir, cutoff, true).unwrap();
+        assert!(report.removed);
+        assert!(report.errors.is_empty());
+        assert!(!dir.join("42").exists());
+        assert!(!dir.join("Other_7.scormproj.tmp").exists());
+        assert!(!dir.join("workflow-screenshots").join("step-1.png").exists());
+        // The live project and its folder are untouched
+        assert!(dir.join("Safety_1234567890.scormproj").exists());
+        assert!(dir.join("1234567890").join("media").exists());
+        assert!(scan_workspace(dir, cutoff).unwrap().is_empty());
+    }
+}
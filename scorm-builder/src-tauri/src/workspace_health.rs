@@ -0,0 +1,280 @@
+//! Startup health check of the workspace.
+//!
+//! Problems with the workspace itself (a projects folder on an unplugged
+//! drive, a hand-edited settings file, a full disk) otherwise surface as
+//! unrelated errors the first time something is saved. The frontend runs
+//! `workspace_health_check` at startup and shows every check that isn't OK
+//! as a notification.
+
+use crate::backup_recovery::{build_recovery_report, split_project_file_name};
+use crate::disk_space::{available_space, format_size};
+use crate::project_index::load_project_index;
+use crate::settings::{load_settings, AppSettings};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Free space kept in reserve on top of one backup of every project
+const MIN_FREE_SPACE: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthCheckKind {
+    ProjectsDirectory,
+    Settings,
+    ProjectIndex,
+    DiskSpace,
+    Recovery,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub kind: HealthCheckKind,
+    pub status: HealthStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceHealthReport {
+    /// Worst status of all checks
+    pub status: HealthStatus,
+    pub checks: Vec<HealthCheck>,
+    pub projects_directory: String,
+    /// Bytes free on the projects disk, when the system reports it
+    pub free_space: Option<u64>,
+}
+
+fn check(kind: HealthCheckKind, status: HealthStatus, message: impl Into<String>) -> HealthCheck {
+    HealthCheck {
+        kind,
+        status,
+        message: message.into(),
+    }
+}
+
+/// The projects directory exists and a file can be written to it
+fn check_projects_directory(projects_dir: &Path, configured: Option<&str>) -> HealthCheck {
+    let kind = HealthCheckKind::ProjectsDirectory;
+    if let Some(configured) = configured.filter(|dir| Path::new(dir) != projects_dir) {
+        return check(
+            kind,
+            HealthStatus::Warning,
+            format!(
+                "Projects folder '{configured}' is not reachable; using '{}' instead",
+                projects_dir.display()
+            ),
+        );
+    }
+    if !projects_dir.is_dir() {
+        return check(
+            kind,
+            HealthStatus::Error,
+            format!("Projects folder '{}' does not exist", projects_dir.display()),
+        );
+    }
+
+    let probe = projects_dir.join(format!(".health-check-{}.tmp", std::process::id()));
+    match fs::write(&probe, b"ok") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            check(kind, HealthStatus::Ok, "Projects folder is writable")
+        }
+        Err(e) => check(
+            kind,
+            HealthStatus::Error,
+            format!("Projects folder '{}' is not writable: {e}", projects_dir.display()),
+        ),
+    }
+}
+
+fn check_settings(settings: &Result<AppSettings, String>) -> HealthCheck {
+    match settings {
+        Ok(_) => check(HealthCheckKind::Settings, HealthStatus::Ok, "Settings loaded"),
+        Err(e) => check(
+            HealthCheckKind::Settings,
+            HealthStatus::Error,
+            format!("{e}; default settings are in use until it is fixed"),
+        ),
+    }
+}
+
+fn project_files(projects_dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(projects_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("scormproj"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The index parses and only lists projects that exist
+fn check_project_index(projects_dir: &Path, project_files: &[PathBuf]) -> HealthCheck {
+    let kind = HealthCheckKind::ProjectIndex;
+    let index = match load_project_index(projects_dir) {
+        Ok(index) => index,
+        Err(e) => return check(kind, HealthStatus::Error, e),
+    };
+    let project_ids: HashSet<String> = project_files
+        .iter()
+        .filter_map(|path| split_project_file_name(path).1)
+        .collect();
+    let stale = index
+        .projects
+        .keys()
+        .filter(|id| !project_ids.contains(*id))
+        .count();
+    if stale == 0 {
+        check(kind, HealthStatus::Ok, "Projects index matches the projects folder")
+    } else {
+        check(
+            kind,
+            HealthStatus::Warning,
+            format!("Projects index lists {stale} project(s) that no longer exist"),
+        )
+    }
+}
+
+/// There is room for a backup of every project, plus a reserve
+fn check_disk_space(free_space: Option<u64>, project_files: &[PathBuf]) -> HealthCheck {
+    let kind = HealthCheckKind::DiskSpace;
+    let Some(free_space) = free_space else {
+        return check(kind, HealthStatus::Warning, "Free disk space could not be determined");
+    };
+    let backups: u64 = project_files
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    let needed = backups + MIN_FREE_SPACE;
+    if free_space >= needed {
+        check(kind, HealthStatus::Ok, format!("{} free", format_size(free_space)))
+    } else {
+        check(
+            kind,
+            HealthStatus::Warning,
+            format!(
+                "Only {} free; backups need about {}",
+                format_size(free_space),
+                format_size(needed)
+            ),
+        )
+    }
+}
+
+/// Backups, drafts and interrupted saves waiting to be recovered
+fn check_recovery(projects_dir: &Path) -> HealthCheck {
+    let kind = HealthCheckKind::Recovery;
+    match build_recovery_report(projects_dir) {
+        Ok(report) if report.items.is_empty() => {
+            check(kind, HealthStatus::Ok, "No unsaved work to recover")
+        }
+        Ok(report) => check(
+            kind,
+            HealthStatus::Warning,
+            format!("{} item(s) of unsaved work can be recovered", report.items.len()),
+        ),
+        Err(e) => check(kind, HealthStatus::Error, e),
+    }
+}
+
+/// Run every check against `projects_dir`. `settings` is the result of loading
+/// the settings file and `free_space` the space left on the projects disk.
+pub fn workspace_health(
+    projects_dir: &Path,
+    settings: &Result<AppSettings, String>,
+    free_space: Option<u64>,
+) -> WorkspaceHealthReport {
+    let configured = settings
+        .as_ref()
+        .ok()
+        .and_then(|settings| settings.projects_directory.as_deref());
+    let project_files = project_files(projects_dir);
+    let checks = vec![
+        check_projects_directory(projects_dir, configured),
+        check_settings(settings),
+        check_project_index(projects_dir, &project_files),
+        check_disk_space(free_space, &project_files),
+        check_recovery(projects_dir),
+    ];
+    WorkspaceHealthReport {
+        status: checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(HealthStatus::Ok),
+        checks,
+        projects_directory: projects_dir.to_string_lossy().to_string(),
+        free_space,
+    }
+}
+
+/// Check the projects folder, settings, projects index, disk space and pending
+/// recovery in one go, for the startup notifications
+#[tauri::command]
+pub fn workspace_health_check() -> Result<WorkspaceHealthReport, String> {
+    let settings = load_settings();
+    let projects_dir = match &settings {
+        Ok(_) => crate::settings::get_projects_directory()?,
+        // The default folder, as get_projects_directory can't read the settings either
+        Err(_) => crate::settings::default_projects_directory()?,
+    };
+    let free_space = available_space(&projects_dir);
+    Ok(workspace_health(&projects_dir, &settings, free_space))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn status(report: &WorkspaceHealthReport, kind: HealthCheckKind) -> HealthStatus {
+        report.checks.iter().find(|check| check.kind == kind).unwrap().status
+    }
+
+    #[test]
+    fn test_healthy_workspace_has_no_warnings() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Course_1700000000000.scormproj"), "{}").unwrap();
+
+        let report = workspace_health(dir.path(), &Ok(AppSettings::default()), Some(u64::MAX));
+
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert_eq!(report.checks.len(), 5);
+        // The write probe is cleaned up
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_problems_are_reported_per_check() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("projects-index.json"),
+            r#"{"projects": {"1700000000000": {"tags": ["safety"]}}}"#,
+        )
+        .unwrap();
+        let settings = Err("Failed to parse settings file: expected value".to_string());
+
+        let report = workspace_health(dir.path(), &settings, Some(1024));
+
+        assert_eq!(report.status, HealthStatus::Error);
+        assert_eq!(status(&report, HealthCheckKind::ProjectsDirectory), HealthStatus::Ok);
+        assert_eq!(status(&report, HealthCheckKind::Settings), HealthStatus::Error);
+        assert_eq!(status(&report, HealthCheckKind::ProjectIndex), HealthStatus::Warning);
+        assert_eq!(status(&report, HealthCheckKind::DiskSpace), HealthStatus::Warning);
+        assert_eq!(status(&report, HealthCheckKind::Recovery), HealthStatus::Ok);
+    }
+}
@@ -0,0 +1,314 @@
+//! Disk usage across every project in the workspace.
+//!
+//! The projects directory is read once: the top level for the project files,
+//! then each project folder recursively. Media files are `{type}-{id}.bin`
+//! under `media/`, so their type comes from the file name without reading
+//! the metadata next to them.
+
+use crate::app_services::AppServices;
+use crate::backup_recovery::{is_project_id, modified_time, split_project_file_name};
+use chrono::{DateTime, Months, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Files listed in `largest_files`
+pub const LARGEST_FILES: usize = 20;
+/// Months without changes after which a project is stale, unless the caller says otherwise
+const DEFAULT_STALE_MONTHS: u32 = 6;
+
+/// Number and total size of the media files of one type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MediaTypeTotal {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// A file in a project folder, or a project file itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LargeFile {
+    pub path: String,
+    pub size: u64,
+    pub project_id: String,
+    /// Absent when the folder's project file no longer exists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
+}
+
+/// A project nobody has changed since before the staleness cutoff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StaleProject {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// Newest change to the project file or anything in its folder
+    pub last_modified: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WorkspaceStatistics {
+    pub total_projects: usize,
+    /// Project files and folders together
+    pub total_size: u64,
+    /// By media type: `image`, `audio`, `video`, `caption`, ...
+    pub media_by_type: BTreeMap<String, MediaTypeTotal>,
+    /// Largest first
+    pub largest_files: Vec<LargeFile>,
+    /// Least recently changed first
+    pub stale_projects: Vec<StaleProject>,
+}
+
+struct ProjectEntry {
+    name: String,
+    path: PathBuf,
+    size: u64,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+/// Keeps the `LARGEST_FILES` biggest files seen so far
+#[derive(Default)]
+struct LargestFiles(BinaryHeap<Reverse<(u64, String, String)>>);
+
+impl LargestFiles {
+    fn offer(&mut self, size: u64, path: &Path, project_id: &str) {
+        self.0.push(Reverse((
+            size,
+            path.to_string_lossy().to_string(),
+            project_id.to_string(),
+        )));
+        if self.0.len() > LARGEST_FILES {
+            self.0.pop();
+        }
+    }
+}
+
+fn modified_utc(path: &Path) -> Option<DateTime<Utc>> {
+    modified_time(path).map(DateTime::<Utc>::from)
+}
+
+/// `image` from `media/image-<id>.bin`
+fn media_type(path: &Path) -> Option<&str> {
+    if path.extension().and_then(|e| e.to_str()) != Some("bin") {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    stem.split_once('-').map(|(kind, _)| kind)
+}
+
+/// Walk one project folder, returning its size and newest modification
+fn scan_project_folder(
+    dir: &Path,
+    project_id: &str,
+    statistics: &mut WorkspaceStatistics,
+    largest: &mut LargestFiles,
+) -> (u64, Option<DateTime<Utc>>) {
+    let mut size = 0;
+    let mut newest = None;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let file_size = meta.len();
+            size += file_size;
+            newest = newest.max(meta.modified().ok().map(DateTime::<Utc>::from));
+            largest.offer(file_size, &path, project_id);
+            let in_media = path.parent().and_then(Path::file_name) == Some(OsStr::new("media"));
+            if let Some(kind) = media_type(&path).filter(|_| in_media) {
+                let total = statistics
+                    .media_by_type
+                    .entry(kind.to_string())
+                    .or_default();
+                total.files += 1;
+                total.bytes += file_size;
+            }
+        }
+    }
+    (size, newest)
+}
+
+/// Statistics of `projects_dir`, counting projects not changed since `stale_before` as stale
+pub fn workspace_statistics(
+    projects_dir: &Path,
+    stale_before: DateTime<Utc>,
+) -> Result<WorkspaceStatistics, String> {
+    let entries = fs::read_dir(projects_dir)
+        .map_err(|e| format!("Failed to read projects directory: {e}"))?;
+    let mut projects: HashMap<String, ProjectEntry> = HashMap::new();
+    let mut folders = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            let is_project_folder = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(is_project_id);
+            if is_project_folder {
+                folders.push(path);
+            }
+        } else if path.extension().and_then(|e| e.to_str()) == Some("scormproj") {
+            if let (name, Some(id)) = split_project_file_name(&path) {
+                let size = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+                projects.insert(
+                    id,
+                    ProjectEntry {
+                        name,
+                        size,
+                        last_modified: modified_utc(&path),
+                        path,
+                    },
+                );
+            }
+        }
+    }
+
+    let mut statistics = WorkspaceStatistics {
+        total_projects: projects.len(),
+        total_size: projects.values().map(|project| project.size).sum(),
+        ..Default::default()
+    };
+    let mut largest = LargestFiles::default();
+    for (id, project) in &projects {
+        largest.offer(project.size, &project.path, id);
+    }
+    for folder in &folders {
+        let id = folder
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let (size, newest) = scan_project_folder(folder, id, &mut statistics, &mut largest);
+        statistics.total_size += size;
+        if let Some(project) = projects.get_mut(id) {
+            project.size += size;
+            project.last_modified = project.last_modified.max(newest);
+        }
+    }
+
+    statistics.largest_files = largest
+        .0
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((size, path, project_id))| LargeFile {
+            project_name: projects
+                .get(&project_id)
+                .map(|project| project.name.clone()),
+            path,
+            size,
+            project_id,
+        })
+        .collect();
+
+    let mut stale: Vec<_> = projects
+        .into_iter()
+        .filter_map(|(id, project)| {
+            let last_modified = project.last_modified?;
+            (last_modified < stale_before).then_some((last_modified, id, project))
+        })
+        .collect();
+    stale.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+    statistics.stale_projects = stale
+        .into_iter()
+        .map(|(last_modified, id, project)| StaleProject {
+            id,
+            name: project.name,
+            path: project.path.to_string_lossy().to_string(),
+            last_modified: last_modified.to_rfc3339(),
+            size: project.size,
+        })
+        .collect();
+    Ok(statistics)
+}
+
+/// Project count, media size by type, the largest files and projects
+/// untouched for `staleMonths` months (6 when unset)
+#[tauri::command]
+pub fn get_workspace_statistics(
+    #[allow(non_snake_case)] staleMonths: Option<u32>,
+    services: State<'_, AppServices>,
+) -> Result<WorkspaceStatistics, String> {
+    let months = staleMonths.unwrap_or(DEFAULT_STALE_MONTHS);
+    let stale_before = services
+        .now()
+        .checked_sub_months(Months::new(months))
+        .ok_or_else(|| format!("Invalid number of months: {months}"))?;
+    workspace_statistics(&crate::settings::get_projects_directory()?, stale_before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sizes_media_types_and_largest_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("Safety_1234567890.scormproj"), "{}").unwrap();
+        let media = dir.join("1234567890").join("media");
+        fs::create_dir_all(&media).unwrap();
+        fs::write(media.join("image-0.bin"), vec![0u8; 300]).unwrap();
+        fs::write(media.join("image-0.json"), "{}").unwrap();
+        fs::write(media.join("audio-0.bin"), vec![0u8; 500]).unwrap();
+        // A folder without a project file still takes up space
+        fs::create_dir_all(dir.join("42").join("media")).unwrap();
+        fs::write(
+            dir.join("42").join("media").join("video-1.bin"),
+            vec![0u8; 900],
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), vec![0u8; 10_000]).unwrap();
+
+        let future = Utc::now() + Duration::days(1);
+        let statistics = workspace_statistics(dir, future).unwrap();
+
+        assert_eq!(statistics.total_projects, 1);
+        assert_eq!(statistics.total_size, 2 + 300 + 2 + 500 + 900);
+        assert_eq!(
+            statistics.media_by_type["image"],
+            MediaTypeTotal {
+                files: 1,
+                bytes: 300
+            }
+        );
+        assert_eq!(statistics.media_by_type["video"].bytes, 900);
+
+        let largest: Vec<_> = statistics
+            .largest_files
+            .iter()
+            .map(|file| {
+                (
+                    file.size,
+                    file.project_id.as_str(),
+                    file.project_name.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(largest[0], (900, "42", None));
+        assert_eq!(largest[1], (500, "1234567890", Some("Safety")));
+        assert_eq!(largest.len(), 5);
+
+        assert_eq!(statistics.stale_projects.len(), 1);
+        assert_eq!(statistics.stale_projects[0].name, "Safety");
+        assert_eq!(statistics.stale_projects[0].size, 2 + 300 + 2 + 500);
+
+        let past = Utc::now() - Duration::days(1);
+        assert!(workspace_statistics(dir, past)
+            .unwrap()
+            .stale_projects
+            .is_empty());
+    }
+}
@@ -17,6 +17,7 @@ import App from './App'
 import { openProjectWithCoordination } from './utils/coordinatedProjectLoading'
 import { startRustLogListener } from './services/rustLogListener'
 import { isTauriEnvironment } from './config/environment'
+import { FileStorage } from './services/FileStorage'
 import { safeLog } from './utils/productionLogger'
 
 interface DashboardContentProps {
@@ -32,6 +33,8 @@ function DashboardContent({ onSecretClick }: DashboardContentProps) {
   const [pendingProjectId, setPendingProjectId] = useState<string | null>(null)
   const [showRecoveryDialog, setShowRecoveryDialog] = useState(false)
   const [recoveryInfo, setRecoveryInfo] = useState<{ backupPath: string; projectName: string } | null>(null)
+  // Project another app instance has open, waiting for the user to choose how to open it
+  const [openElsewhere, setOpenElsewhere] = useState<{ projectId: string; message: string } | null>(null)
   // Loading dialog state
   const [isLoadingProject, setIsLoadingProject] = useState(false)
   const [loadingProgress, setLoadingProgress] = useState<{
@@ -177,6 +180,8 @@ function DashboardContent({ onSecretClick }: DashboardContentProps) {
         // Show dashboard is already false, which will show the App component
         // The App component will show the unsaved changes dialog
         setShowDashboard(false)
+      } else if (FileStorage.isOpenElsewhereError(err)) {
+        setOpenElsewhere({ projectId, message: err instanceof Error ? err.message : String(err) })
       } else {
         showError(err instanceof Error ? err.message : 'Failed to open project', {
           label: 'Retry',
@@ -187,6 +192,24 @@ function DashboardContent({ onSecretClick }: DashboardContentProps) {
     }
   }
 
+  const handleOpenElsewhere = async (mode: 'readonly' | 'takeover') => {
+    if (!openElsewhere) return
+    const { projectId } = openElsewhere
+    setOpenElsewhere(null)
+
+    try {
+      if (mode === 'readonly') {
+        await storage.fileStorage.openProjectReadonly(projectId)
+      } else {
+        await storage.fileStorage.takeOverProject(projectId)
+      }
+      setShowDashboard(false)
+      showInfo(mode === 'readonly' ? 'Project opened read-only' : 'Project opened successfully')
+    } catch (error: unknown) {
+      showError(`Failed to open project: ${error instanceof Error ? error.message : String(error)}`)
+    }
+  }
+
   // Monitor loading progress to close dialog only when truly complete
   useEffect(() => {
     if (isLoadingProject && loadingProgress.percent === 100 && loadingProgress.phase === 'finalizing') {
@@ -492,6 +515,74 @@ function DashboardContent({ onSecretClick }: DashboardContentProps) {
         </div>
       )}
       
+      {openElsewhere && (
+        <div style={{
+          position: 'fixed',
+          inset: 0,
+          backgroundColor: 'rgba(0, 0, 0, 0.75)',
+          display: 'flex',
+          alignItems: 'center',
+          justifyContent: 'center',
+          zIndex: 1000
+        }}>
+          <div style={{
+            backgroundColor: '#1f2937',
+            padding: '2rem',
+            borderRadius: '0.5rem',
+            maxWidth: '28rem',
+            boxShadow: '0 25px 50px -12px rgba(0, 0, 0, 0.25)'
+          }}>
+            <h2 style={{ fontSize: '1.5rem', fontWeight: 600, marginBottom: '1rem', color: '#f3f4f6' }}>
+              Project Open Elsewhere
+            </h2>
+            <p style={{ color: '#d1d5db', marginBottom: '1.5rem' }}>
+              {openElsewhere.message} Taking it over stops the other instance from saving its changes.
+            </p>
+            <div style={{ display: 'flex', gap: '1rem', justifyContent: 'flex-end' }}>
+              <button
+                onClick={() => setOpenElsewhere(null)}
+                style={{
+                  padding: '0.5rem 1rem',
+                  backgroundColor: '#374151',
+                  color: '#f3f4f6',
+                  border: 'none',
+                  borderRadius: '0.375rem',
+                  cursor: 'pointer'
+                }}
+              >
+                Cancel
+              </button>
+              <button
+                onClick={() => handleOpenElsewhere('readonly')}
+                style={{
+                  padding: '0.5rem 1rem',
+                  backgroundColor: '#374151',
+                  color: '#f3f4f6',
+                  border: 'none',
+                  borderRadius: '0.375rem',
+                  cursor: 'pointer'
+                }}
+              >
+                Open Read-Only
+              </button>
+              <button
+                onClick={() => handleOpenElsewhere('takeover')}
+                style={{
+                  padding: '0.5rem 1rem',
+                  backgroundColor: '#3b82f6',
+                  color: 'white',
+                  border: 'none',
+                  borderRadius: '0.375rem',
+                  cursor: 'pointer'
+                }}
+              >
+                Take Over
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+
       <ProjectLoadingDialog 
         isOpen={isLoadingProject}
         progress={loadingProgress}
@@ -271,7 +271,7 @@ function AppContent({ onBackToDashboard, pendingProjectId, onPendingProjectHandl
   })
   
   // Use notification context instead of local toast state
-  const { success, error: showError, info } = useNotifications()
+  const { success, error: showError, warning, info } = useNotifications()
   const [pendingNavigationAction, setPendingNavigationAction] = useState<(() => void) | null>(null)
   const [isLoadingProject, setIsLoadingProject] = useState(false)
   const [isSaving, setIsSaving] = useState(false)
@@ -395,6 +395,25 @@ function AppContent({ onBackToDashboard, pendingProjectId, onPendingProjectHandl
     loadApiKeys()
   }, [])
 
+  // Problems with the workspace itself (projects folder, settings, disk space) as notifications
+  useEffect(() => {
+    const checkWorkspace = async () => {
+      try {
+        const report = await storage.fileStorage.workspaceHealthCheck()
+        for (const check of report?.checks ?? []) {
+          if (check.status === 'error') {
+            showError(check.message)
+          } else if (check.status === 'warning') {
+            warning(check.message)
+          }
+        }
+      } catch (error) {
+        debugLogger.error('App.init', 'Workspace health check failed', error)
+      }
+    }
+    checkWorkspace()
+  }, [])
+
   // Load project data from PersistentStorage on mount
   useEffect(() => {
     // Reset tracker when project ID changes
@@ -23,7 +23,7 @@ describe('SCORM Audio Duration Handling', () => {
     createElement: vi.fn(() => createMockAudio(Infinity))
   })
 
-  // Mock the formatTime function from navigation.js.hbs (FIXED VERSION)
+  // Mock the formatTime function from navigation.js (FIXED VERSION)
   const formatTime = (seconds: number): string => {
     // Handle invalid durations: NaN, Infinity, null, undefined
     if (isNaN(seconds) || !isFinite(seconds) || seconds == null) {
@@ -35,7 +35,7 @@ describe('SCORM Audio Duration Handling', () => {
     return `${mins}:${secs.toString().padStart(2, '0')}`
   }
 
-  // Mock the duration display update function (FIXED VERSION from navigation.js.hbs)
+  // Mock the duration display update function (FIXED VERSION from navigation.js)
   const updateAudioDuration = (audio: any, durationElement: any, progressBar: any) => {
     // Use the fixed formatTime function for duration display
     durationElement.textContent = formatTime(audio.duration) // Line 1055 equivalent (now with guards)
@@ -3,7 +3,7 @@ import { CourseSeedData } from '../types/course'
 import { PageLayout } from './PageLayout'
 import { useFormChanges } from '../hooks/useFormChanges'
 import { AutoSaveBadge } from './AutoSaveBadge'
-import { Check, Copy, Settings } from 'lucide-react'
+import { Check, Copy, Settings, Sparkles } from 'lucide-react'
 import { Card, Button } from './DesignSystem'
 import './DesignSystem/designSystem.css'
 import { useNotifications } from '../contexts/NotificationContext'
@@ -13,6 +13,7 @@ import { PromptTuningModal } from './PromptTuningModal'
 import { PromptTuningSettings, DEFAULT_PROMPT_TUNING_SETTINGS } from '../types/promptTuning'
 import { usePersistentStorage } from '../hooks/usePersistentStorage'
 import { useUnsavedChanges } from '../contexts/UnsavedChangesContext'
+import { generateContentWithAi, getAiProvider, type AiProviderStatus } from '../services/aiAssist'
 import styles from './AIPromptGenerator.module.css'
 
 interface AIPromptGeneratorProps {
@@ -43,6 +44,9 @@ export const AIPromptGenerator: React.FC<AIPromptGeneratorProps> = ({
   const [mounted, setMounted] = useState(false)
   const [isPromptTuningModalOpen, setIsPromptTuningModalOpen] = useState(false)
   const [promptTuningSettings, setPromptTuningSettings] = useState<PromptTuningSettings>(DEFAULT_PROMPT_TUNING_SETTINGS)
+  // AI provider configured in Settings, if any; its key stays in the backend
+  const [aiProvider, setAiProvider] = useState<AiProviderStatus | null>(null)
+  const [generatedLength, setGeneratedLength] = useState<number | null>(null)
 
   // Storage integration
   const storage = usePersistentStorage()
@@ -89,6 +93,10 @@ export const AIPromptGenerator: React.FC<AIPromptGeneratorProps> = ({
       loadPromptTuningSettings()
     }
 
+    getAiProvider()
+      .then(status => setAiProvider(status?.has_api_key ? status : null))
+      .catch(error => console.warn('[AIPromptGenerator] Failed to load AI provider:', error))
+
     return () => {
       setMounted(false)
       resetDirty('promptTuning')
@@ -122,6 +130,28 @@ export const AIPromptGenerator: React.FC<AIPromptGeneratorProps> = ({
     }
   }
   
+  // Send the prompt to the configured provider and hand the response to the JSON import step
+  const handleGenerate = async () => {
+    setGeneratedLength(0)
+    try {
+      const result = await generateContentWithAi({ prompt: generatePrompt() }, (delta) => {
+        setGeneratedLength(length => (length ?? 0) + delta.length)
+      })
+      await storage.saveContent('json-import-data', {
+        rawJson: result.text,
+        validationResult: null,
+        isLocked: false,
+        isTreeVisible: false
+      })
+      success(`Course content generated with ${result.model}`)
+      onNext()
+    } catch (error) {
+      notifyError(`AI generation failed: ${error instanceof Error ? error.message : String(error)}`)
+    } finally {
+      setGeneratedLength(null)
+    }
+  }
+
   // Wrapped navigation handlers
   const handleBack = () => {
     attemptNavigation(() => onBack())
@@ -219,6 +249,9 @@ export const AIPromptGenerator: React.FC<AIPromptGeneratorProps> = ({
             <li>Copy the JSON response from the AI</li>
             <li>Click Next to proceed to the JSON import step</li>
           </ol>
+          {aiProvider && (
+            <p>Or click Generate with AI to have {aiProvider.model} write the course and go straight to the JSON import step.</p>
+          )}
         </Card>
       </div>
 
@@ -284,6 +317,22 @@ export const AIPromptGenerator: React.FC<AIPromptGeneratorProps> = ({
                 <Settings size={16} className={styles.tuningButtonIcon} />
                 {!isDefault ? 'Custom Settings' : 'Prompt Tuning'}
               </Button>
+              {aiProvider && (
+                <Button
+                  onClick={handleGenerate}
+                  aria-label={`Generate course content with ${aiProvider.model}`}
+                  data-testid="generate-with-ai-button"
+                  variant="primary"
+                  size="medium"
+                  disabled={generatedLength !== null}
+                  title="Send the prompt to the AI provider configured in Settings"
+                >
+                  <Sparkles size={16} className={styles.tuningButtonIcon} />
+                  {generatedLength !== null
+                    ? `Generating... (${generatedLength} characters)`
+                    : 'Generate with AI'}
+                </Button>
+              )}
             </div>
           </div>
           
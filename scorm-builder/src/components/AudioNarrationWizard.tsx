@@ -2325,7 +2325,8 @@ export function AudioNarrationWizard({
         try {
           // Clear from cache first to ensure fresh replacement
           clearAudioFromCache(existingAudio.mediaId)
-          await deleteMedia(existingAudio.mediaId)
+          // Replaced by the new recording, so the saved content's reference doesn't keep it
+          await deleteMedia(existingAudio.mediaId, { force: true })
           // Invalidate getAllMedia cache since media was deleted
           invalidateAllMediaCache()
           logger.log(`[AudioNarrationWizard] Deleted old audio before replacement: ${existingAudio.mediaId}`)
@@ -2645,7 +2646,8 @@ export function AudioNarrationWizard({
       // Delete from media storage if it has a mediaId
       if (fileToRemove.mediaId && deleteMedia) {
         try {
-          await deleteMedia(fileToRemove.mediaId)
+          // The author removed it from the block, so the saved content's reference doesn't keep it
+          await deleteMedia(fileToRemove.mediaId, { force: true })
           // Invalidate getAllMedia cache since media was deleted
           invalidateAllMediaCache()
           logger.log(`[AudioNarrationWizard] Deleted audio media: ${fileToRemove.mediaId}`)
@@ -2710,7 +2712,8 @@ export function AudioNarrationWizard({
       // Delete from media storage if it has a mediaId
       if (captionToRemove.mediaId && deleteMedia) {
         try {
-          await deleteMedia(captionToRemove.mediaId)
+          // The author removed it from the block, so the saved content's reference doesn't keep it
+          await deleteMedia(captionToRemove.mediaId, { force: true })
           // Invalidate getAllMedia cache since media was deleted
           invalidateAllMediaCache()
           logger.log(`[AudioNarrationWizard] Deleted caption media: ${captionToRemove.mediaId}`)
@@ -4421,13 +4424,14 @@ export function AudioNarrationWizard({
         <ConfirmDialog
           isOpen={showClearAllConfirm}
           onConfirm={async () => {
-            // Delete all media from storage first
+            // Delete all media from storage first; the author confirmed clearing it
+            // from the narration, so the saved content's references don't keep it
             if (deleteMedia) {
               // Delete all audio files from storage
               for (const file of audioFiles) {
                 if (file.mediaId) {
                   try {
-                    await deleteMedia(file.mediaId)
+                    await deleteMedia(file.mediaId, { force: true })
                   } catch (error) {
                     logger.error(`Failed to delete audio ${file.mediaId}:`, error)
                   }
@@ -4438,7 +4442,7 @@ export function AudioNarrationWizard({
               for (const file of captionFiles) {
                 if (file.mediaId) {
                   try {
-                    await deleteMedia(file.mediaId)
+                    await deleteMedia(file.mediaId, { force: true })
                   } catch (error) {
                     logger.error(`Failed to delete caption ${file.mediaId}:`, error)
                   }
@@ -29,10 +29,72 @@ interface CourseSettingsWizardProps {
 export type NavigationMode = 'linear' | 'free'
 export type CompletionCriteria = 'view_all_pages' | 'pass_assessment' | 'time_spent' | 'view_and_pass'
 export type FontSize = 'small' | 'medium' | 'large'
+export type LmsProfile = 'generic' | 'moodle' | 'cornerstone' | 'successfactors'
+export type InsecureUrlPolicy = 'report' | 'upgrade' | 'download'
+
+// Countdowns on the assessment page, separate from the course time limit
+export interface AssessmentTimerSettings {
+  overallMinutes: number // 0 = no overall timer
+  perQuestionSeconds: number // 0 = no per-question timer
+  warningSeconds?: number // warn this long before a countdown ends (default 60)
+  autoSubmit: boolean // submit when time runs out, unanswered questions count as wrong
+}
+
+// Page-view tracking in the generated package, for LMSs without usage reporting
+export interface AnalyticsSettings {
+  provider: 'matomo' | 'ga'
+  endpoint?: string // Matomo instance URL (https), unused for Google Analytics
+  siteId: string // Matomo site id or GA measurement id (G-...)
+  anonymizeIp?: boolean // default true
+  disableCookies?: boolean // default true
+  respectDoNotTrack?: boolean // default true
+}
+
+// "Report a problem with this page" widget in the generated package
+export interface FeedbackSettings {
+  webhookUrl?: string // https endpoint reports are posted to as JSON
+  email?: string // mailto fallback when there is no webhook or posting fails
+}
+
+// Narration player behaviour in the generated package
+export interface AudioPolicySettings {
+  allowSeeking?: boolean // Seeking while audio completion is required (default false)
+  speedControl?: boolean // Playback speed selector (default true)
+  autoplay?: boolean // Start narration on page enter (default false)
+  rememberPosition?: boolean // Resume narration where the learner left it (default false)
+  unlockOverlay?: boolean // "Click to enable audio" overlay when the browser blocks autoplay (default true)
+}
+
+// How the welcome page starts the course
+export interface WelcomeStartSettings {
+  resumePrompt?: boolean // Ask returning learners to resume or start over
+  introVideoId?: string // Welcome page video that must be watched before starting
+  introVideoMinPercent?: number // Share of the video that counts as watched (default 90)
+}
+
+// Generated interface features; unset ones follow showOutline, showProgress and printable
+export interface CourseFeatureSettings {
+  navigationMenu?: boolean // Sidebar outline of the course pages
+  progressBar?: boolean // Progress ring at the top of the navigation menu
+  captionsToggle?: boolean // Captions button on narration players (default true)
+  pageNumbers?: boolean // "Page 3 of 12" in the footer (default false)
+  printButton?: boolean // Print button in the header
+}
+
+// Labels and order of the fixed pages in the course menu; unset fields keep the built-in ones
+export interface NavigationMenuSettings {
+  welcomeLabel?: string // Menu label of the welcome page (default "Welcome")
+  objectivesLabel?: string // Menu label and heading of the objectives page
+  assessmentLabel?: string // Menu label and heading of the assessment
+  hideObjectives?: boolean // Leave the objectives page out of the package
+  assessmentAfterTopic?: string // Topic ID the assessment follows, instead of the last topic
+  manifestItemTitle?: string // Title of the course's item in the LMS (default the course title)
+}
 
 export interface CourseSettings {
   // Learning Control
   requireAudioCompletion: boolean
+  audioPolicy?: AudioPolicySettings
   navigationMode: NavigationMode
   autoAdvance: boolean
   allowPreviousReview: boolean
@@ -48,15 +110,44 @@ export interface CourseSettings {
   showOutline: boolean
   confirmExit: boolean
   fontSize: FontSize
+  features?: CourseFeatureSettings
+  navigationMenu?: NavigationMenuSettings
 
   // Timing & Sessions
   timeLimit: number // minutes, 0 = unlimited
   sessionTimeout: number // minutes for auto-save
   minimumTimeSpent: number // minutes required for completion
+  assessmentTimer?: AssessmentTimerSettings
 
   // Accessibility
   keyboardNavigation: boolean
   printable: boolean
+  highContrast?: boolean // Force high-contrast theme in the generated course
+  learnerNotes?: boolean // Per-page notes panel for learners, exportable as text
+  runtimeAnalytics?: boolean // Page visits and interactions kept in suspend_data for support
+  courseSearch?: boolean // Search box over the course text in the generated package
+  courseMap?: boolean // Overview page after the objectives with per-page completion ticks
+  transcripts?: boolean // Narration as text under each topic and on a transcript page after the assessment
+  imageLightbox?: boolean // Page images open enlarged when clicked (default true)
+  responsiveLayout?: boolean // Mobile-first layout with the outline in a drawer on small screens (default true)
+  mediaPlaceholders?: boolean // Package stand-in files for media that is missing at generation time
+  requireAltText?: boolean // Refuse to generate while page images lack alt text
+  insecureUrls?: InsecureUrlPolicy // What to do about http:// images and embeds (default 'report')
+  welcomeStart?: WelcomeStartSettings // Resume prompt and intro video gate on the welcome page
+
+  // LMS compatibility
+  lmsProfile?: LmsProfile // Quirks of the target LMS (default 'generic')
+  commitHeartbeat?: number // Seconds between heartbeat commits to the LMS, 0 = off (default 120)
+  apiSearchDepth?: number // Parent frames searched for the LMS API (default 10)
+  analytics?: AnalyticsSettings // Matomo or Google Analytics page views
+  feedback?: FeedbackSettings // Learner problem reports, off unless a webhook or email is set
+  courseVersion?: string // Shown in the manifest and version.json (default '1.0')
+  keywords?: string[] // Manifest keywords for LMS catalog search, listed before the derived ones
+  deriveKeywords?: boolean // Add keywords from topic titles and objectives (default true)
+  sourceSnapshot?: boolean // Packages an encrypted copy of the project; its passphrase is saved with saveSnapshotPassphrase
+
+  // Localization
+  language?: string // Language code of the course content, e.g. 'en'
 }
 
 // Default course settings - these represent the historical defaults that have been used
@@ -87,7 +178,15 @@ export const DEFAULT_COURSE_SETTINGS: CourseSettings = {
 
   // Accessibility - inclusive defaults
   keyboardNavigation: true, // Good for accessibility
-  printable: false // Usually not needed, can affect formatting
+  printable: false, // Usually not needed, can affect formatting
+  highContrast: false, // Learners' OS contrast preference is still honoured
+  learnerNotes: false,
+  runtimeAnalytics: false,
+  courseSearch: false,
+  courseMap: false,
+  transcripts: false,
+  imageLightbox: true,
+  responsiveLayout: true
 }
 
 export const CourseSettingsWizard: React.FC<CourseSettingsWizardProps> = ({
@@ -490,6 +589,53 @@ export const CourseSettingsWizard: React.FC<CourseSettingsWizardProps> = ({
                       </label>
                     </div>
 
+                    <div style={{ display: 'flex', flexDirection: 'column', gap: '0.75rem', marginBottom: '1rem' }}>
+                      {settings.requireAudioCompletion && (
+                        <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                          <input
+                            type="checkbox"
+                            checked={settings.audioPolicy?.allowSeeking ?? false}
+                            onChange={(e) => updateSettings(prev => ({ ...prev, audioPolicy: { ...prev.audioPolicy, allowSeeking: e.target.checked } }))}
+                          />
+                          <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Let learners seek in audio they must complete</span>
+                        </label>
+                      )}
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.audioPolicy?.speedControl ?? true}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, audioPolicy: { ...prev.audioPolicy, speedControl: e.target.checked } }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Show the playback speed control</span>
+                      </label>
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.audioPolicy?.autoplay ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, audioPolicy: { ...prev.audioPolicy, autoplay: e.target.checked } }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Play narration when a page opens</span>
+                      </label>
+                      {settings.audioPolicy?.autoplay && (
+                        <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                          <input
+                            type="checkbox"
+                            checked={settings.audioPolicy?.unlockOverlay ?? true}
+                            onChange={(e) => updateSettings(prev => ({ ...prev, audioPolicy: { ...prev.audioPolicy, unlockOverlay: e.target.checked } }))}
+                          />
+                          <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Ask learners to click to enable audio when the browser blocks it</span>
+                        </label>
+                      )}
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.audioPolicy?.rememberPosition ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, audioPolicy: { ...prev.audioPolicy, rememberPosition: e.target.checked } }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Resume narration where learners left it</span>
+                      </label>
+                    </div>
+
                     <div style={{ marginBottom: '1rem' }}>
                       <div style={{ fontWeight: '500', color: 'var(--text-primary)', marginBottom: '0.5rem' }}>Navigation Mode</div>
                       <div style={{ display: 'flex', gap: '1rem' }}>
@@ -621,6 +767,59 @@ export const CourseSettingsWizard: React.FC<CourseSettingsWizardProps> = ({
                       </select>
                     </div>
 
+                    <div style={{ marginBottom: '1rem' }}>
+                      <label style={{ display: 'block', fontSize: '0.875rem', fontWeight: '500', color: 'var(--text-primary)', marginBottom: '0.5rem' }}>
+                        Target LMS
+                      </label>
+                      <select
+                        value={settings.lmsProfile ?? 'generic'}
+                        onChange={(e) => updateSettings(prev => ({
+                          ...prev,
+                          lmsProfile: e.target.value as LmsProfile
+                        }))}
+                        style={{ 
+                          width: '100%',
+                          padding: '0.5rem',
+                          border: '1px solid var(--border-default)',
+                          borderRadius: '0.375rem',
+                          fontSize: '0.875rem',
+                          backgroundColor: 'var(--bg-secondary)',
+                          color: 'var(--text-primary)'
+                        }}
+                      >
+                        <option value="generic">Any SCORM 1.2 LMS</option>
+                        <option value="moodle">Moodle</option>
+                        <option value="cornerstone">Cornerstone</option>
+                        <option value="successfactors">SAP SuccessFactors</option>
+                      </select>
+                    </div>
+
+                    <div style={{ marginBottom: '1rem' }}>
+                      <label style={{ display: 'block', fontSize: '0.875rem', fontWeight: '500', color: 'var(--text-primary)', marginBottom: '0.5rem' }}>
+                        Insecure (http) Resources
+                      </label>
+                      <select
+                        value={settings.insecureUrls ?? 'report'}
+                        onChange={(e) => updateSettings(prev => ({
+                          ...prev,
+                          insecureUrls: e.target.value as InsecureUrlPolicy
+                        }))}
+                        style={{ 
+                          width: '100%',
+                          padding: '0.5rem',
+                          border: '1px solid var(--border-default)',
+                          borderRadius: '0.375rem',
+                          fontSize: '0.875rem',
+                          backgroundColor: 'var(--bg-secondary)',
+                          color: 'var(--text-primary)'
+                        }}
+                      >
+                        <option value="report">Report them</option>
+                        <option value="upgrade">Switch to https where available</option>
+                        <option value="download">Download files into the package</option>
+                      </select>
+                    </div>
+
                     <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
                       <input
                         type="checkbox"
@@ -657,6 +856,33 @@ export const CourseSettingsWizard: React.FC<CourseSettingsWizardProps> = ({
                         />
                         <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Show course outline</span>
                       </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.features?.pageNumbers ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, features: { ...prev.features, pageNumbers: e.target.checked } }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Show page numbers</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.features?.captionsToggle ?? true}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, features: { ...prev.features, captionsToggle: e.target.checked } }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Show captions button on narration</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.features?.printButton ?? settings.printable}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, features: { ...prev.features, printButton: e.target.checked } }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Show print button</span>
+                      </label>
                       
                       <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
                         <input
@@ -776,6 +1002,93 @@ export const CourseSettingsWizard: React.FC<CourseSettingsWizardProps> = ({
                       />
                     </div>
 
+                    <div style={{ marginBottom: '1rem' }}>
+                      <label style={{ display: 'block', fontSize: '0.875rem', fontWeight: '500', color: 'var(--text-primary)', marginBottom: '0.5rem' }}>
+                        LMS Heartbeat (sec, 0 = off)
+                      </label>
+                      <input
+                        type="number"
+                        min="0"
+                        max="3600"
+                        value={settings.commitHeartbeat ?? 120}
+                        onChange={(e) => updateSettings(prev => ({ 
+                          ...prev, 
+                          commitHeartbeat: Math.min(3600, Math.max(0, parseInt(e.target.value, 10) || 0))
+                        }))}
+                        style={{ 
+                          width: '100%',
+                          padding: '0.5rem',
+                          border: '1px solid var(--border-default)',
+                          borderRadius: '0.375rem',
+                          fontSize: '0.875rem',
+                          backgroundColor: 'var(--bg-secondary)',
+                          color: 'var(--text-primary)'
+                        }}
+                      />
+                    </div>
+
+                    <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: '1rem', marginBottom: '1.5rem' }}>
+                      <div>
+                        <label style={{ display: 'block', fontSize: '0.875rem', fontWeight: '500', color: 'var(--text-primary)', marginBottom: '0.5rem' }}>
+                          Assessment Timer (min)
+                        </label>
+                        <input
+                          type="number"
+                          min="0"
+                          value={settings.assessmentTimer?.overallMinutes ?? 0}
+                          onChange={(e) => updateSettings(prev => ({
+                            ...prev,
+                            assessmentTimer: {
+                              perQuestionSeconds: 0,
+                              autoSubmit: true,
+                              ...prev.assessmentTimer,
+                              overallMinutes: Math.max(0, parseInt(e.target.value, 10) || 0)
+                            }
+                          }))}
+                          placeholder="0 = no timer"
+                          style={{ 
+                            width: '100%',
+                            padding: '0.5rem',
+                            border: '1px solid var(--border-default)',
+                            borderRadius: '0.375rem',
+                            fontSize: '0.875rem',
+                            backgroundColor: 'var(--bg-secondary)',
+                            color: 'var(--text-primary)'
+                          }}
+                        />
+                      </div>
+
+                      <div>
+                        <label style={{ display: 'block', fontSize: '0.875rem', fontWeight: '500', color: 'var(--text-primary)', marginBottom: '0.5rem' }}>
+                          Per Question (sec)
+                        </label>
+                        <input
+                          type="number"
+                          min="0"
+                          value={settings.assessmentTimer?.perQuestionSeconds ?? 0}
+                          onChange={(e) => updateSettings(prev => ({
+                            ...prev,
+                            assessmentTimer: {
+                              overallMinutes: 0,
+                              autoSubmit: true,
+                              ...prev.assessmentTimer,
+                              perQuestionSeconds: Math.max(0, parseInt(e.target.value, 10) || 0)
+                            }
+                          }))}
+                          placeholder="0 = no timer"
+                          style={{ 
+                            width: '100%',
+                            padding: '0.5rem',
+                            border: '1px solid var(--border-default)',
+                            borderRadius: '0.375rem',
+                            fontSize: '0.875rem',
+                            backgroundColor: 'var(--bg-secondary)',
+                            color: 'var(--text-primary)'
+                          }}
+                        />
+                      </div>
+                    </div>
+
                     <div style={{ display: 'flex', flexDirection: 'column', gap: '1rem' }}>
                       <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
                         <input
@@ -794,6 +1107,113 @@ export const CourseSettingsWizard: React.FC<CourseSettingsWizardProps> = ({
                         />
                         <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Allow content printing</span>
                       </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.highContrast ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, highContrast: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Force high-contrast theme</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.learnerNotes ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, learnerNotes: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Let learners take notes on each page</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.runtimeAnalytics ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, runtimeAnalytics: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Record page visits and interactions for support</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.courseSearch ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, courseSearch: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Let learners search the course</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.courseMap ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, courseMap: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Add a course map page after the objectives</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.transcripts ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, transcripts: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Add transcripts of the narration</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.imageLightbox ?? true}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, imageLightbox: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Let learners enlarge page images</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.responsiveLayout ?? true}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, responsiveLayout: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Adapt the layout to phones and tablets</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.mediaPlaceholders ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, mediaPlaceholders: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Use placeholders for missing media</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.requireAltText ?? false}
+                          onChange={(e) => updateSettings(prev => ({ ...prev, requireAltText: e.target.checked }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Require alt text for images and videos</span>
+                      </label>
+
+                      <label style={{ display: 'flex', alignItems: 'center', gap: '0.75rem', cursor: 'pointer' }}>
+                        <input
+                          type="checkbox"
+                          checked={settings.assessmentTimer?.autoSubmit ?? true}
+                          onChange={(e) => updateSettings(prev => ({
+                            ...prev,
+                            assessmentTimer: {
+                              overallMinutes: 0,
+                              perQuestionSeconds: 0,
+                              ...prev.assessmentTimer,
+                              autoSubmit: e.target.checked
+                            }
+                          }))}
+                        />
+                        <span style={{ fontSize: '0.875rem', color: 'var(--text-primary)' }}>Auto-submit the assessment when its timer runs out</span>
+                      </label>
                     </div>
                   </div>
                 </div>
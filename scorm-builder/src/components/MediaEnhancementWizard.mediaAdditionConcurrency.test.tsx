@@ -143,6 +143,8 @@ describe('MediaEnhancementWizard - Concurrent Media Addition Issue', () => {
     })
 
     // STEP 2: Start media addition for first image (this will be SLOW)
+    // Web images need a license before they can be added
+    fireEvent.change(screen.getByTestId('media-license-input'), { target: { value: 'CC0' } })
     const setMediaButton1 = screen.getByRole('button', { name: /set media/i })
     expect(setMediaButton1).not.toBeDisabled()
     fireEvent.click(setMediaButton1)
@@ -206,6 +208,8 @@ describe('MediaEnhancementWizard - Concurrent Media Addition Issue', () => {
       expect(screen.getByRole('dialog')).toBeInTheDocument()
     })
 
+    // Web images need a license before they can be added
+    fireEvent.change(screen.getByTestId('media-license-input'), { target: { value: 'CC0' } })
     const setMediaButton1 = screen.getByRole('button', { name: /set media/i })
     fireEvent.click(setMediaButton1)
 
@@ -224,6 +228,8 @@ describe('MediaEnhancementWizard - Concurrent Media Addition Issue', () => {
 
     // FIXED BEHAVIOR: Second image should have independent state
     // The button should show "Set Media" and be clickable
+    // Web images need a license before they can be added
+    fireEvent.change(screen.getByTestId('media-license-input'), { target: { value: 'CC0' } })
     const setMediaButton2 = screen.getByRole('button', { name: /set media/i })
     
     expect(setMediaButton2.textContent).toBe('Set Media') // Should work after fix
@@ -267,6 +273,8 @@ describe('MediaEnhancementWizard - Concurrent Media Addition Issue', () => {
       expect(screen.getByRole('dialog')).toBeInTheDocument()
     })
 
+    // Web images need a license before they can be added
+    fireEvent.change(screen.getByTestId('media-license-input'), { target: { value: 'CC0' } })
     const setMediaButton = screen.getByRole('button', { name: /set media/i })
     fireEvent.click(setMediaButton)
 
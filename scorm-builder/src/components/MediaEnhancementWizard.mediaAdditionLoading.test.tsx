@@ -126,6 +126,8 @@ describe('MediaEnhancementWizard - Media Addition Loading Issue', () => {
     })
 
     // Click "Set Media" to trigger media addition - this should start the slow process
+    // Web images need a license before they can be added
+    fireEvent.change(screen.getByTestId('media-license-input'), { target: { value: 'CC0' } })
     const setMediaButton = screen.getByRole('button', { name: /set media/i })
     fireEvent.click(setMediaButton)
 
@@ -180,6 +182,8 @@ describe('MediaEnhancementWizard - Media Addition Loading Issue', () => {
     })
 
     // Start media addition (this will be slow due to our mock)
+    // Web images need a license before they can be added
+    fireEvent.change(screen.getByTestId('media-license-input'), { target: { value: 'CC0' } })
     const setMediaButton = screen.getByRole('button', { name: /set media/i })
     fireEvent.click(setMediaButton)
 
@@ -235,6 +239,8 @@ describe('MediaEnhancementWizard - Media Addition Loading Issue', () => {
     })
 
     // Start media addition
+    // Web images need a license before they can be added
+    fireEvent.change(screen.getByTestId('media-license-input'), { target: { value: 'CC0' } })
     const setMediaButton = screen.getByRole('button', { name: /set media/i })
     fireEvent.click(setMediaButton)
 
@@ -3,7 +3,7 @@ import { CourseContentUnion, CourseContent, Media, Page, Topic } from '../types/
 import type { MediaItem } from '../services/MediaService'
 import { CourseSeedData } from '../types/course'
 import { searchGoogleImages, searchYouTubeVideos, SearchError } from '../services/searchService'
-import { isKnownCorsRestrictedDomain, downloadExternalImage, forceDownloadExternalImage } from '../services/externalImageDownloader'
+import { isKnownCorsRestrictedDomain, downloadExternalImage, forceDownloadExternalImage, isLicenseRequiredError } from '../services/externalImageDownloader'
 import { PageLayout } from './PageLayout'
 import { ConfirmDialog } from './ConfirmDialog'
 import { AutoSaveBadge } from './AutoSaveBadge'
@@ -35,6 +35,8 @@ import { useNotifications } from '../contexts/NotificationContext'
 import DOMPurify from 'dompurify'
 import { logger } from '../utils/logger'
 import { buildYouTubeEmbed, parseYouTubeClipTiming, extractClipTimingFromUrl } from '../services/mediaUrl'
+import { parseMediaPolicyError } from '../services/mediaPolicy'
+import type { MediaAttribution } from '../types/ipc'
 import styles from './MediaEnhancementWizard.module.css'
 
 interface SearchResult {
@@ -53,6 +55,7 @@ interface SearchResult {
   isYouTube?: boolean
   clipStart?: number // seconds
   clipEnd?: number   // seconds
+  attribution?: MediaAttribution // Required before an image is downloaded from the web
 }
 
 interface MediaEnhancementWizardRefactoredProps {
@@ -248,6 +251,8 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
   const [addingMediaIds, setAddingMediaIds] = useState<Set<string>>(new Set())
   const [lightboxMedia, setLightboxMedia] = useState<SearchResult | null>(null)
   const [isLightboxOpen, setIsLightboxOpen] = useState(false)
+  // License the previewed web image is used under; the backend refuses to download it without one
+  const [licenseText, setLicenseText] = useState('')
   const [clipStart, setClipStart] = useState<number | undefined>(undefined)
   const [clipEnd, setClipEnd] = useState<number | undefined>(undefined)
   const [currentPageIndex, setCurrentPageIndex] = useState(0)
@@ -904,6 +909,7 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
       setClipEnd(undefined)
     }
     
+    setLicenseText(result.attribution?.license ?? '')
     setLightboxMedia(result)
     setIsLightboxOpen(true)
   }, [displayedResults])
@@ -1023,6 +1029,13 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
     console.log('[MediaEnhancement] ✅ Clip timing commit completed')
   }, [lightboxMedia, existingPageMedia, startText, endText, getCurrentPage, getPageId, updateYouTubeVideoMetadata, setPendingClip, buildYouTubeEmbed, parseTimeToSeconds, setExistingPageMedia, setIsLightboxOpen, setStartText, setEndText, setLightboxMedia, setClipStart, setClipEnd])
 
+  // Images fetched from the web, as opposed to uploads and YouTube videos
+  const needsLicense = (result: SearchResult) =>
+    !result.isYouTube &&
+    !result.embedUrl &&
+    !mediaItemsRef.current.get(result.id)?.blob &&
+    !uploadedMedia.some(m => m.id === result.id)
+
   // Handle lightbox actions
   const handleLightboxConfirm = async () => {
     if (!lightboxMedia) return
@@ -1056,14 +1069,15 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
             timestamp: new Date().toISOString()
           })
           
-          // Delete from storage using the same method as handleReplaceConfirm
+          // Delete from storage using the same method as handleReplaceConfirm.
+          // The page is dropping the media, so the saved content's reference doesn't keep it.
           if (media.storageId) {
-            await deleteMedia(media.storageId)
+            await deleteMedia(media.storageId, { force: true })
             // Clear enriched metadata cache
             enrichedMetadataCacheRef.current.delete(media.storageId)
           } else {
             // Fallback to media.id if no storageId
-            await deleteMedia(media.id)
+            await deleteMedia(media.id, { force: true })
             // Clear enriched metadata cache
             enrichedMetadataCacheRef.current.delete(media.id)
           }
@@ -1078,6 +1092,16 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
     
     // Add new media with clip timing
     const mediaToAdd = { ...lightboxMedia }
+
+    // Web images are credited on the package's credits page under the license given here
+    if (needsLicense(lightboxMedia)) {
+      mediaToAdd.attribution = {
+        provider: 'google',
+        author: lightboxMedia.photographer,
+        ...lightboxMedia.attribution,
+        license: licenseText.trim()
+      }
+    }
     
     // For YouTube videos, update clip timing and embed URL
     if (lightboxMedia.isYouTube) {
@@ -1736,10 +1760,10 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
           // Download and store external image
           if (forceDownloadMode) {
             console.log('[MediaEnhancement] Using force download mode for external image')
-            blob = await forceDownloadExternalImage(result.url)
+            blob = await forceDownloadExternalImage(result.url, result.attribution)
           } else {
             console.log('[MediaEnhancement] Downloading external image (normal mode)')
-            blob = await downloadExternalImage(result.url)
+            blob = await downloadExternalImage(result.url, result.attribution)
           }
         }
         
@@ -1751,7 +1775,8 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
         storedItem = await storeMedia(blob, pageId, 'image', {
           title: result.title || 'Image',
           type: 'image',
-          url: result.url
+          url: result.url,
+          attribution: result.attribution
         })
         console.log('[MediaEnhancement] Successfully stored image:', storedItem)
         success(`Image "${result.title || 'Image'}" added successfully`)
@@ -1807,7 +1832,8 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
           title: (typeof storedItem.metadata?.title === 'string' ? storedItem.metadata.title : undefined) || storedItem.fileName || result.title || 'Image',
           url: blobUrl || `media-error://${storedItem.id}`,
           storageId: storedItem.id,
-          mimeType: storedItem.metadata?.mimeType || 'image/jpeg'
+          mimeType: storedItem.metadata?.mimeType || 'image/jpeg',
+          attribution: result.attribution
         }
       }
 
@@ -1854,8 +1880,13 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
       // Provide helpful error messages based on error type
       let errorMsg = 'Failed to add media to page'
       const errorMessage = error instanceof Error ? error.message.toLowerCase() : ''
+      const policyError = parseMediaPolicyError(error)
       
-      if (errorMessage.includes('cors') || errorMessage.includes('blocked') || errorMessage.includes('policy')) {
+      if (policyError) {
+        errorMsg = policyError.violations.map(violation => violation.message).join(' ')
+      } else if (isLicenseRequiredError(error)) {
+        errorMsg = 'Enter the license this image may be used under before adding it. Open the image to preview it and fill in its license.'
+      } else if (errorMessage.includes('cors') || errorMessage.includes('blocked') || errorMessage.includes('policy')) {
         if (forceDownloadMode) {
           errorMsg = 'Unable to download this image even with force mode. The source may have strict security policies.'
         } else {
@@ -1912,14 +1943,15 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
             timestamp: new Date().toISOString()
           })
           
-          // Delete from storage if it has a storage ID
+          // Delete from storage if it has a storage ID. The page is dropping the
+          // media, so the saved content's reference doesn't keep it.
           if (media.storageId) {
-            await deleteMedia(media.storageId)
+            await deleteMedia(media.storageId, { force: true })
             // Clear enriched metadata cache
             enrichedMetadataCacheRef.current.delete(media.storageId)
           } else {
             // Fallback to media.id if no storageId
-            await deleteMedia(media.id)
+            await deleteMedia(media.id, { force: true })
             // Clear enriched metadata cache
             enrichedMetadataCacheRef.current.delete(media.id)
           }
@@ -2953,8 +2985,8 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
         confirmDialogData: removeConfirm
       })
       
-      // Delete from storage
-      await deleteMedia(mediaToRemove.storageId)
+      // Delete from storage; the author confirmed removing it from the page
+      await deleteMedia(mediaToRemove.storageId, { force: true })
       
       // Clear enriched metadata cache
       enrichedMetadataCacheRef.current.delete(mediaToRemove.storageId)
@@ -4445,6 +4477,24 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
               {lightboxMedia.dimensions && <p className={styles.lightboxDimensions}>{lightboxMedia.dimensions}</p>}
               {lightboxMedia.duration && <p className={styles.lightboxDuration}>Duration: {lightboxMedia.duration}</p>}
               {lightboxMedia.channel && <p className={styles.lightboxChannel}>Channel: {lightboxMedia.channel}</p>}
+
+              {/* License of web images, shown on the package's credits page */}
+              {needsLicense(lightboxMedia) && (
+                <div className={styles.clipTimingSection}>
+                  <h4>License</h4>
+                  <Input
+                    id="mediaLicense"
+                    type="text"
+                    placeholder="e.g. CC BY 4.0"
+                    value={licenseText}
+                    onChange={(e) => setLicenseText(e.target.value)}
+                    data-testid="media-license-input"
+                  />
+                  <p className={styles.clipTimingHelp}>
+                    Check the image's source page for the license it may be used under. Images without one can't be added.
+                  </p>
+                </div>
+              )}
               
               {/* Clip timing inputs for YouTube videos */}
               {lightboxMedia.isYouTube && (
@@ -4584,7 +4634,9 @@ const MediaEnhancementWizard: React.FC<MediaEnhancementWizardRefactoredProps> =
                 variant="primary"
                 size="large"
                 onClick={handleLightboxConfirm}
-                disabled={lightboxMedia ? addingMediaIds.has(lightboxMedia.id) : false}
+                disabled={lightboxMedia
+                  ? addingMediaIds.has(lightboxMedia.id) || (needsLicense(lightboxMedia) && !licenseText.trim())
+                  : false}
                 data-testid="set-media-button"
               >
                 {lightboxMedia && addingMediaIds.has(lightboxMedia.id)
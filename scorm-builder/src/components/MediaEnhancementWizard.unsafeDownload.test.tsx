@@ -7,7 +7,7 @@ vi.mock('@tauri-apps/api/core', () => ({
 }))
 
 describe('Unsafe Download Integration - Unit Tests', () => {
-  it('should call unsafe_download_image Tauri command in forceDownloadExternalImage', async () => {
+  it('should call download_external_media Tauri command in forceDownloadExternalImage', async () => {
     const { invoke } = await import('@tauri-apps/api/core')
     const mockInvoke = vi.mocked(invoke)
     
@@ -18,9 +18,10 @@ describe('Unsafe Download Integration - Unit Tests', () => {
     })
     
     const testUrl = 'https://example.com/image.png'
-    const result = await externalImageDownloader.forceDownloadExternalImage(testUrl)
+    const attribution = { provider: 'google', license: 'CC BY 4.0' }
+    const result = await externalImageDownloader.forceDownloadExternalImage(testUrl, attribution)
     
-    expect(mockInvoke).toHaveBeenCalledWith('unsafe_download_image', { url: testUrl })
+    expect(mockInvoke).toHaveBeenCalledWith('download_external_media', { url: testUrl, attribution })
     expect(result).toBeInstanceOf(Blob)
     expect(result.type).toBe('image/png')
     expect(result.size).toBeGreaterThan(0)
@@ -49,7 +50,7 @@ describe('Unsafe Download Integration - Unit Tests', () => {
       expect(error).toBeInstanceOf(Error)
     }
     
-    expect(mockInvoke).toHaveBeenCalledWith('unsafe_download_image', { url: testUrl })
+    expect(mockInvoke).toHaveBeenCalledWith('download_external_media', { url: testUrl })
   })
 
   it('should have updated function signature and error messages', async () => {
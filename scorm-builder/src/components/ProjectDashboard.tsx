@@ -23,6 +23,9 @@ import { ExportProgressDialog, type ExportProgressState } from './ExportProgress
 // Import removed: now using Rust-only import flow
 import { ProjectImportConflictDialog } from './ProjectImportConflictDialog'
 import { debugLogger } from '@/utils/ultraSimpleLogger'
+import { parseMediaPolicyError } from '../services/mediaPolicy'
+import { migrateMediaPageIds } from '../services/mediaPageIds'
+import type { ProjectIntegrityReport } from '../services/FileStorage'
 import styles from './ProjectDashboard.module.css'
 import JSZip from 'jszip'
 
@@ -138,6 +141,7 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
   const [isDragging, setIsDragging] = useState(false)
   const [defaultFolder, setDefaultFolder] = useState<string | null>(null)
   const [importingProject, setImportingProject] = useState(false)
+  const [archivingProjects, setArchivingProjects] = useState(false)
   const [exportingProjectId, setExportingProjectId] = useState<string | null>(null)
   const [exportProgress, setExportProgress] = useState<ExportProgressState>({
     phase: 'preparing',
@@ -155,6 +159,8 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
   const [renamingProjectId, setRenamingProjectId] = useState<string | null>(null)
   const [renameValue, setRenameValue] = useState('')
   const [renameError, setRenameError] = useState<string | null>(null)
+  const [integrityReport, setIntegrityReport] = useState<ProjectIntegrityReport | null>(null)
+  const [fixingPageIds, setFixingPageIds] = useState(false)
 
   // Import conflict dialog state (disabled for now - using Rust-only import)
   const [showConflictDialog, setShowConflictDialog] = useState(false)
@@ -714,7 +720,10 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
 
       // Enhanced error messages
       let errorMessage = 'Unknown error'
-      if (error instanceof Error) {
+      const policyError = parseMediaPolicyError(error)
+      if (policyError) {
+        errorMessage = `${policyError.message}: ${policyError.violations.map(violation => violation.message).join('; ')}`
+      } else if (error instanceof Error) {
         if (error.message.includes('timeout')) {
           errorMessage = 'Import timed out. The file may be too large or corrupted.'
         } else if (error.message.includes('ZIP')) {
@@ -747,6 +756,39 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
     }
   }
 
+  async function handleCheckIntegrity(projectId: string) {
+    try {
+      const report = await storage.fileStorage.checkProjectIntegrity(projectId)
+      if (!report?.mediaPageIds) {
+        notifyError('Integrity check returned no report')
+        return
+      }
+      setIntegrityReport(report)
+    } catch (error) {
+      debugLogger.error('ProjectDashboard.handleCheckIntegrity', 'Integrity check failed', { projectId, error })
+      notifyError(`Integrity check failed: ${error instanceof Error ? error.message : String(error)}`)
+    }
+  }
+
+  // The report's page_id changes are the dry run; this applies them after backing the files up
+  async function handleFixPageIds() {
+    if (!integrityReport) return
+    setFixingPageIds(true)
+    try {
+      const result = await migrateMediaPageIds(integrityReport.projectId)
+      if (result.errors?.length) {
+        notifyError(`${result.message} ${result.errors.length} files could not be fixed.`)
+      } else {
+        success(result.backup_dir ? `${result.message} Backup: ${result.backup_dir}` : result.message)
+      }
+      setIntegrityReport(null)
+    } catch (error) {
+      notifyError(`Failed to fix media page IDs: ${error instanceof Error ? error.message : String(error)}`)
+    } finally {
+      setFixingPageIds(false)
+    }
+  }
+
   async function handleExportProject(projectId: string, projectPath?: string) {
     try {
       setExportingProjectId(projectId)
@@ -1127,6 +1169,41 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
     }
   }
 
+  async function handleArchiveAllProjects() {
+    const outputDir = await open({
+      directory: true,
+      multiple: false,
+      title: 'Select Archive Folder'
+    })
+    if (!outputDir || typeof outputDir !== 'string') return
+
+    setArchivingProjects(true)
+    let unlisten: UnlistenFn | undefined
+    try {
+      unlisten = await listen<{ message: string }>('archive-progress', (event) => {
+        debugLogger.debug('ProjectDashboard.archiveProgress', event.payload.message)
+      })
+      const result = await invoke<{
+        indexPath: string
+        exported: number
+        failed: number
+      }>('export_all_projects', { outputDir, includeMedia: true })
+
+      debugLogger.info('ProjectDashboard.handleArchiveAllProjects', 'Archived projects', result)
+      if (result.failed > 0) {
+        notifyError(`Archived ${result.exported} projects; ${result.failed} failed (see ${result.indexPath})`)
+      } else {
+        success(`Archived ${result.exported} projects to ${outputDir}`)
+      }
+    } catch (error) {
+      debugLogger.error('ProjectDashboard.handleArchiveAllProjects', 'Failed to archive projects', { error })
+      notifyError(`Failed to archive projects: ${error instanceof Error ? error.message : String(error)}`)
+    } finally {
+      unlisten?.()
+      setArchivingProjects(false)
+    }
+  }
+
   function handleClearDefaultFolder() {
     setDefaultFolder(null)
     localStorage.removeItem('defaultProjectsFolder')
@@ -1231,6 +1308,15 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
               {importingProject ? 'Importing...' : 'Import Project'}
             </Button>
           </Tooltip>
+          <Tooltip content="Export every project to its own ZIP with an index file" position="bottom">
+            <Button
+              variant="secondary"
+              onClick={handleArchiveAllProjects}
+              disabled={archivingProjects}
+            >
+              {archivingProjects ? 'Archiving...' : 'Archive All Projects'}
+            </Button>
+          </Tooltip>
           <Tooltip content="Start a new SCORM course project" position="bottom">
             <Button 
               variant="primary"
@@ -1321,6 +1407,7 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
               onExport={(project: ProjectRow) => handleExportProject(project.id, project.path || project.filePath)}
               onDelete={(project: ProjectRow) => setDeleteConfirm({id: project.id, path: project.path || project.filePath})}
               onRename={(project: ProjectRow) => handleStartRename(project.id, project.name)}
+              onCheckIntegrity={(project: ProjectRow) => handleCheckIntegrity(project.id)}
             />
           </div>
         </div>
@@ -1456,6 +1543,54 @@ export function ProjectDashboard({ onProjectSelected, onSecretClick }: ProjectDa
         </div>
       </Modal>
 
+      <Modal
+        isOpen={integrityReport !== null}
+        onClose={() => setIntegrityReport(null)}
+        title="Project Integrity"
+      >
+        {integrityReport && (
+          <div data-testid="integrity-report">
+            <p>
+              {integrityReport.mediaAlignment.length === 0
+                ? 'All media is attached to the right page.'
+                : `${integrityReport.mediaAlignment.length} media items are on the wrong page; exporting and re-importing the project fixes them.`}
+            </p>
+            <p>{integrityReport.mediaPageIds.message}</p>
+            {integrityReport.mediaPageIds.changes.length > 0 && (
+              <ul data-testid="page-id-changes">
+                {integrityReport.mediaPageIds.changes.map(change => (
+                  <li key={change.file}>
+                    {change.file}: {change.current_page_id || '(none)'} → {change.expected_page_id}
+                  </li>
+                ))}
+              </ul>
+            )}
+            {integrityReport.mediaPageIds.errors.length > 0 && (
+              <ul>
+                {integrityReport.mediaPageIds.errors.map(fileError => (
+                  <li key={fileError.file}>{fileError.file}: {fileError.error}</li>
+                ))}
+              </ul>
+            )}
+            <ButtonGroup gap="medium" justify="end">
+              <Button variant="secondary" onClick={() => setIntegrityReport(null)}>
+                Close
+              </Button>
+              {integrityReport.mediaPageIds.changes.length > 0 && (
+                <Button
+                  variant="primary"
+                  onClick={handleFixPageIds}
+                  disabled={fixingPageIds}
+                  data-testid="fix-page-ids-button"
+                >
+                  {fixingPageIds ? 'Fixing...' : 'Back Up and Fix Page IDs'}
+                </Button>
+              )}
+            </ButtonGroup>
+          </div>
+        )}
+      </Modal>
+
       <ExportProgressDialog
         isOpen={showExportProgress}
         state={exportProgress}
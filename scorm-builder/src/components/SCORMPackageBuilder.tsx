@@ -1842,6 +1842,9 @@ const SCORMPackageBuilderComponent: React.FC<SCORMPackageBuilderProps> = ({
           ? pkg.data 
           : new Uint8Array(pkg.data)
         
+        // Fail with a clear message, not halfway through the write, when the chosen disk is full
+        await invoke('ensure_free_space', { path: filePath, bytes: data.length })
+
         console.log('[SCORMPackageBuilder] Writing', data.length, 'bytes to file...')
         await writeFile(filePath, data)
         
@@ -11,6 +11,12 @@ import { Badge } from './DesignSystem/Badge'
 import { Icon } from './DesignSystem'
 import { Check, AlertTriangle, Eye, EyeOff, BookOpen, Download, Shield } from 'lucide-react'
 import { useNotifications } from '../contexts/NotificationContext'
+import { getMediaPolicy, setMediaPolicy, type MediaPolicy } from '../services/mediaPolicy'
+import { getAiProvider, saveAiProvider, deleteAiProvider, type AiProvider, type AiProviderConfig } from '../services/aiAssist'
+
+const BYTES_PER_MB = 1024 * 1024
+
+const EMPTY_AI_PROVIDER: AiProviderConfig = { provider: 'openai', api_key: '', model: '' }
 
 interface SettingsProps {
   onSave?: (apiKeys: ApiKeys) => void
@@ -25,6 +31,9 @@ export const Settings: React.FC<SettingsProps> = ({ onSave }) => {
   const [disabledLogCategories, setDisabledLogCategories] = useState<string[]>([])
   const [newCategory, setNewCategory] = useState('')
   const [forceDownloadMode, setForceDownloadMode] = useState<boolean>(false)
+  const [mediaPolicy, setMediaPolicyState] = useState<MediaPolicy | null>(null)
+  const [aiProvider, setAiProviderState] = useState<AiProviderConfig>(EMPTY_AI_PROVIDER)
+  const [hasAiKey, setHasAiKey] = useState(false)
   const { success, error: notifyError } = useNotifications()
   
   // Load API keys from encrypted file on mount
@@ -53,6 +62,23 @@ export const Settings: React.FC<SettingsProps> = ({ onSave }) => {
     if (savedForceDownload !== null) {
       setForceDownloadMode(JSON.parse(savedForceDownload))
     }
+
+    // Load the media policy the backend applies to stored and imported media
+    getMediaPolicy()
+      .then(policy => {
+        if (mounted && Array.isArray(policy?.allowed_mime_types)) setMediaPolicyState(policy)
+      })
+      .catch(error => console.error('Failed to load media policy:', error))
+
+    // Load the AI provider; the backend never returns its key
+    getAiProvider()
+      .then(status => {
+        if (mounted && status?.has_api_key) {
+          setAiProviderState({ ...EMPTY_AI_PROVIDER, provider: status.provider, model: status.model, endpoint: status.endpoint })
+          setHasAiKey(true)
+        }
+      })
+      .catch(error => console.error('Failed to load AI provider:', error))
     
     return () => {
       mounted = false
@@ -123,6 +149,45 @@ export const Settings: React.FC<SettingsProps> = ({ onSave }) => {
     }
   }
 
+  const handleSaveMediaPolicy = async () => {
+    if (!mediaPolicy) return
+    try {
+      await setMediaPolicy(mediaPolicy)
+      success('Media policy saved')
+    } catch (error) {
+      console.error('Error saving media policy:', error)
+      notifyError(`Failed to save media policy: ${error}`)
+    }
+  }
+
+  const handleSaveAiProvider = async () => {
+    if (!aiProvider.model.trim() || (!hasAiKey && !aiProvider.api_key.trim())) {
+      notifyError('Enter a model and an API key')
+      return
+    }
+    try {
+      await saveAiProvider(aiProvider)
+      setHasAiKey(true)
+      setAiProviderState(prev => ({ ...prev, api_key: '' }))
+      success('AI provider saved securely!')
+    } catch (error) {
+      console.error('Error saving AI provider:', error)
+      notifyError(`Failed to save AI provider: ${error}`)
+    }
+  }
+
+  const handleRemoveAiProvider = async () => {
+    try {
+      await deleteAiProvider()
+      setHasAiKey(false)
+      setAiProviderState(EMPTY_AI_PROVIDER)
+      success('AI provider removed')
+    } catch (error) {
+      console.error('Error removing AI provider:', error)
+      notifyError(`Failed to remove AI provider: ${error}`)
+    }
+  }
+
   const inputType = showPasswords ? 'text' : 'password'
   
   // Logger category handlers
@@ -257,6 +322,86 @@ export const Settings: React.FC<SettingsProps> = ({ onSave }) => {
           </form>
         </Tab>
         
+        <Tab tabKey="ai-provider" label="AI Provider">
+          <Section>
+            <Card variant="dark" style={{ padding: 'var(--space-lg)' }}>
+              <h2 style={{ fontSize: '1.25rem', fontWeight: 500, color: 'var(--gray-200)', marginBottom: 'var(--space-sm)' }}>
+                AI Content Generation
+              </h2>
+              <p style={{ color: 'var(--gray-400)', marginBottom: 'var(--space-lg)' }}>
+                Requests are sent by the app itself; the key is stored encrypted and is never shown again after saving.
+              </p>
+
+              <Grid cols={1} gap="medium">
+                <div>
+                  <label htmlFor="ai-provider-select" style={{ display: 'block', fontSize: '0.875rem', fontWeight: 500, color: 'var(--text-primary)', marginBottom: '0.5rem' }}>
+                    Provider
+                  </label>
+                  <select
+                    id="ai-provider-select"
+                    value={aiProvider.provider}
+                    onChange={(e) => setAiProviderState(prev => ({ ...prev, provider: e.target.value as AiProvider }))}
+                    style={{
+                      width: '100%',
+                      padding: '0.5rem',
+                      border: '1px solid var(--border-default)',
+                      borderRadius: '0.375rem',
+                      fontSize: '0.875rem',
+                      backgroundColor: 'var(--bg-secondary)',
+                      color: 'var(--text-primary)'
+                    }}
+                  >
+                    <option value="openai">OpenAI</option>
+                    <option value="azure">Azure OpenAI</option>
+                    <option value="anthropic">Anthropic</option>
+                  </select>
+                </div>
+
+                <Input
+                  label={aiProvider.provider === 'azure' ? 'Deployment name' : 'Model'}
+                  value={aiProvider.model}
+                  onChange={(e) => setAiProviderState(prev => ({ ...prev, model: e.target.value }))}
+                />
+
+                {aiProvider.provider === 'azure' && (
+                  <>
+                    <Input
+                      label="Endpoint"
+                      value={aiProvider.endpoint ?? ''}
+                      onChange={(e) => setAiProviderState(prev => ({ ...prev, endpoint: e.target.value || undefined }))}
+                      placeholder="https://your-resource.openai.azure.com"
+                    />
+                    <Input
+                      label="API version"
+                      value={aiProvider.api_version ?? ''}
+                      onChange={(e) => setAiProviderState(prev => ({ ...prev, api_version: e.target.value || undefined }))}
+                    />
+                  </>
+                )}
+
+                <Input
+                  label="API Key"
+                  type="password"
+                  value={aiProvider.api_key}
+                  onChange={(e) => setAiProviderState(prev => ({ ...prev, api_key: e.target.value }))}
+                  placeholder={hasAiKey ? 'Saved - enter a new key to replace it' : 'Enter your API key'}
+                />
+              </Grid>
+
+              <div style={{ display: 'flex', gap: 'var(--space-sm)', marginTop: 'var(--space-md)' }}>
+                <Button variant="primary" size="medium" onClick={handleSaveAiProvider}>
+                  Save AI Provider
+                </Button>
+                {hasAiKey && (
+                  <Button variant="secondary" size="medium" onClick={handleRemoveAiProvider}>
+                    Remove
+                  </Button>
+                )}
+              </div>
+            </Card>
+          </Section>
+        </Tab>
+
         <Tab tabKey="logger" label="Logger Settings">
           <Section>
             <h2 style={{ fontSize: '1.25rem', fontWeight: 500, color: 'var(--gray-200)', marginBottom: 'var(--space-sm)' }}>
@@ -422,6 +567,69 @@ export const Settings: React.FC<SettingsProps> = ({ onSave }) => {
               </div>
             </Card>
 
+            {/* Media Policy */}
+            {mediaPolicy && (
+              <Card variant="dark" style={{ padding: 'var(--space-lg)', marginBottom: 'var(--space-lg)' }}>
+                <div style={{ display: 'flex', alignItems: 'center', gap: 'var(--space-sm)', marginBottom: 'var(--space-sm)' }}>
+                  <Icon icon={Shield} size="sm" color="var(--gray-300)" />
+                  <h3 style={{ fontSize: '0.875rem', fontWeight: 500, color: 'var(--gray-200)' }}>
+                    Media Policy
+                  </h3>
+                </div>
+                <p style={{ fontSize: '0.75rem', color: 'var(--gray-400)', marginBottom: 'var(--space-md)' }}>
+                  Media that breaks these rules is rejected when it is added or when a project is imported.
+                </p>
+
+                <Grid cols={1} gap="medium">
+                  <Input
+                    label="Allowed MIME types (comma separated, empty allows any)"
+                    value={mediaPolicy.allowed_mime_types.join(', ')}
+                    onChange={(e) => setMediaPolicyState({
+                      ...mediaPolicy,
+                      allowed_mime_types: e.target.value.split(',').map(type => type.trim()).filter(Boolean)
+                    })}
+                    placeholder="image/*, audio/mpeg, video/mp4"
+                  />
+
+                  {Object.entries(mediaPolicy.max_size_bytes).map(([mediaType, bytes]) => (
+                    <Input
+                      key={mediaType}
+                      label={`Largest ${mediaType} file (MB)`}
+                      type="number"
+                      min={1}
+                      value={Math.round(bytes / BYTES_PER_MB)}
+                      onChange={(e) => setMediaPolicyState({
+                        ...mediaPolicy,
+                        max_size_bytes: {
+                          ...mediaPolicy.max_size_bytes,
+                          [mediaType]: Math.max(1, Number(e.target.value)) * BYTES_PER_MB
+                        }
+                      })}
+                    />
+                  ))}
+
+                  <Input
+                    label="Scanner command (the file path is appended)"
+                    value={(mediaPolicy.scanner_command ?? []).join(' ')}
+                    onChange={(e) => {
+                      const command = e.target.value.split(' ').filter(Boolean)
+                      setMediaPolicyState({
+                        ...mediaPolicy,
+                        scanner_command: command.length > 0 ? command : undefined
+                      })
+                    }}
+                    placeholder="clamscan --no-summary"
+                  />
+                </Grid>
+
+                <div style={{ marginTop: 'var(--space-md)' }}>
+                  <Button variant="primary" size="medium" onClick={handleSaveMediaPolicy}>
+                    Save Media Policy
+                  </Button>
+                </div>
+              </Card>
+            )}
+
             {/* Information about when to use */}
             <Alert variant="info">
               <h3 style={{ fontWeight: 500, marginBottom: 'var(--space-sm)' }}>
@@ -19,9 +19,10 @@ type Props = {
   onExport: (p: ProjectRow) => void
   onDelete: (p: ProjectRow) => void
   onRename: (p: ProjectRow) => void
+  onCheckIntegrity?: (p: ProjectRow) => void
 }
 
-export default function ProjectsList({ projects, onOpen, onExport, onDelete, onRename }: Props) {
+export default function ProjectsList({ projects, onOpen, onExport, onDelete, onRename, onCheckIntegrity }: Props) {
   const [query, setQuery] = useState('')
   const [sortKey, setSortKey] = useState<'name' | 'lastAccessed'>('name')
   const [sortDir, setSortDir] = useState<'asc' | 'desc'>('asc')
@@ -144,6 +145,9 @@ export default function ProjectsList({ projects, onOpen, onExport, onDelete, onR
                 <Button size="small" onClick={() => onOpen(p)}>Open</Button>
                 <Button size="small" variant="secondary" onClick={() => onExport(p)}>Export</Button>
                 <Button size="small" variant="secondary" onClick={() => onRename(p)}>Rename</Button>
+                {onCheckIntegrity && (
+                  <Button size="small" variant="secondary" onClick={() => onCheckIntegrity(p)}>Check</Button>
+                )}
                 <Button size="small" variant="danger" onClick={() => onDelete(p)}>Delete</Button>
               </div>
             </div>
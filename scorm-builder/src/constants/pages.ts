@@ -70,6 +70,8 @@ export const MEDIA_INDEX = {
 
 /**
  * Helper function to get topic audio ID
+ * @deprecated Positional IDs break when topics are reordered. Use the page's
+ * audioId; this is only a fallback for projects not yet migrated to stable IDs.
  */
 export const getTopicAudioId = (topicIndex: number): string => {
   return `audio-${MEDIA_INDEX.TOPICS_AUDIO_START + topicIndex}`
@@ -77,6 +79,7 @@ export const getTopicAudioId = (topicIndex: number): string => {
 
 /**
  * Helper function to get topic caption ID
+ * @deprecated See getTopicAudioId.
  */
 export const getTopicCaptionId = (topicIndex: number): string => {
   return `caption-${MEDIA_INDEX.TOPICS_CAPTION_START + topicIndex}`
@@ -46,7 +46,7 @@ export interface UnifiedMediaContextType {
   storeMedia: (file: File | Blob, pageId: string, type: MediaType, metadata?: Partial<MediaMetadata>, progressCallback?: ProgressCallback) => Promise<MediaItem>
   updateMedia: (existingId: string, file: File | Blob, metadata?: Partial<MediaMetadata>, progressCallback?: ProgressCallback) => Promise<MediaItem>
   getMedia: (mediaId: string) => Promise<{ data?: Uint8Array; metadata: MediaMetadata; url?: string } | null>
-  deleteMedia: (mediaId: string) => Promise<boolean>
+  deleteMedia: (mediaId: string, options?: { force?: boolean }) => Promise<boolean>
   deleteAllMedia: (projectId: string) => Promise<void>
   
   // YouTube specific
@@ -1045,13 +1045,13 @@ export function UnifiedMediaProvider({ children, projectId, loadingTimeout = 300
     }
   }, [loadingProfile])
   
-  const deleteMedia = useCallback(async (mediaId: string): Promise<boolean> => {
+  const deleteMedia = useCallback(async (mediaId: string, options?: { force?: boolean }): Promise<boolean> => {
     try {
       const mediaService = mediaServiceRef.current
       if (!mediaService) {
         throw new Error('Media service not initialized')
       }
-      const success = await mediaService.deleteMedia(mediaService.projectId, mediaId)
+      const success = await mediaService.deleteMedia(mediaService.projectId, mediaId, options)
       
       if (success) {
         // Update cache
@@ -56,7 +56,7 @@ export interface MediaActions {
   // Core operations
   storeMedia: (file: File | Blob, pageId: string, type: MediaType, metadata?: Partial<MediaMetadata>, progressCallback?: ProgressCallback) => Promise<MediaItem>
   updateMedia: (existingId: string, file: File | Blob, metadata?: Partial<MediaMetadata>, progressCallback?: ProgressCallback) => Promise<MediaItem>
-  deleteMedia: (mediaId: string) => Promise<boolean>
+  deleteMedia: (mediaId: string, options?: { force?: boolean }) => Promise<boolean>
   deleteAllMedia: (projectId: string) => Promise<void>
   
   // YouTube operations
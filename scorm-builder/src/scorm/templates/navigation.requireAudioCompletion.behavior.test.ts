@@ -3,7 +3,7 @@ import { describe, it, expect } from 'vitest'
 describe('SCORM Navigation - Require Audio Completion Feature', () => {
   it('should block navigation when audio completion is required but not finished', () => {
     // This test simulates the navigation blocking logic that will be implemented
-    // in the navigation.js.hbs template
+    // in the navigation.js runtime
     
     // Mock the variables that would be available in the SCORM template
     const mockTemplateData = {
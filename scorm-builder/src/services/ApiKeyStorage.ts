@@ -4,19 +4,29 @@ export interface ApiKeys {
   googleImageApiKey: string
   googleCseId: string  
   youtubeApiKey: string
+  /** Write-only: the backend never returns the stock image keys */
+  unsplashAccessKey?: string
+  pexelsApiKey?: string
+  hasUnsplashAccessKey?: boolean
+  hasPexelsApiKey?: boolean
 }
 
 // Convert between camelCase (frontend) and snake_case (backend)
 const toSnakeCase = (apiKeys: ApiKeys): any => ({
   google_image_api_key: apiKeys.googleImageApiKey,
   google_cse_id: apiKeys.googleCseId,
-  youtube_api_key: apiKeys.youtubeApiKey
+  youtube_api_key: apiKeys.youtubeApiKey,
+  // Left out when unset so the stored key is kept
+  unsplash_access_key: apiKeys.unsplashAccessKey || undefined,
+  pexels_api_key: apiKeys.pexelsApiKey || undefined
 })
 
 const toCamelCase = (apiKeys: any): ApiKeys => ({
   googleImageApiKey: apiKeys.google_image_api_key,
   googleCseId: apiKeys.google_cse_id,
-  youtubeApiKey: apiKeys.youtube_api_key
+  youtubeApiKey: apiKeys.youtube_api_key,
+  hasUnsplashAccessKey: apiKeys.has_unsplash_access_key,
+  hasPexelsApiKey: apiKeys.has_pexels_api_key
 })
 
 export class ApiKeyStorage {
@@ -4,6 +4,36 @@ import { debugLogger } from '@/utils/ultraSimpleLogger';
 import { extractProjectId } from '@/utils/projectIdExtraction';
 import type { ProjectData, MediaFile, SaveResult, LoadResult, DeleteResult } from '@/types/project';
 import type { MediaReference } from '@/types/projectStructure';
+import type { ProjectFilter, ProjectStatus } from './projectTags';
+import type { LocalTimes } from './timeDisplay';
+import type { PageIdValidationReport } from './mediaPageIds';
+import type {
+  AnimatedGifInfo,
+  BackupFileReport,
+  CleanupResult as BackupCleanupResult,
+  ClipTimingIssue,
+  DeleteMediaResult,
+  EncryptionStatus,
+  GiftExport,
+  GiftImportResult,
+  ImportProgress as ImportProgressPayload,
+  ImportResult,
+  MediaCorrection,
+  ProgressEvent,
+  ProjectMetadata
+} from '@/types/ipc';
+
+export type {
+  AnimatedGifInfo,
+  BackupCleanupResult,
+  BackupFileReport,
+  ClipTimingIssue,
+  EncryptionStatus,
+  GiftExport,
+  GiftImportResult,
+  ImportResult,
+  MediaCorrection
+};
 
 interface Project {
   id: string;
@@ -11,21 +41,19 @@ interface Project {
   path: string;
   createdAt: string;
   updatedAt: string;
+  tags?: string[];
+  status?: ProjectStatus;
 }
 
 // Tauri backend response types
-interface TauriProjectMetadata {
-  id: string;
-  name: string;
-  path: string;
-  created: string;
-  last_modified: string;
-}
+/** `create_project` always returns the path the project was saved to */
+type TauriProjectMetadata = ProjectMetadata & { path: string };
 
 // Extended ProjectData interface for Tauri backend usage
 interface TauriProjectData extends ProjectData {
   id: string;
   name: string;
+  is_writable?: boolean;
 }
 
 interface TauriProjectFile {
@@ -55,6 +83,17 @@ interface TauriProjectFile {
   };
   scorm_config?: {
     packageTitle?: string;
+    media_transforms?: {
+      max_video_height?: number;
+      max_audio_bitrate_kbps?: number;
+      convert_images_to_webp?: boolean;
+      /** Extra narration encodings packaged beside each MP3, e.g. ['ogg', 'webm'] */
+      audio_fallback_formats?: string[];
+      /** Package animated GIFs as video in this format; pages play it with controls */
+      convert_animated_gifs?: 'mp4' | 'webm';
+    };
+    /** Deflate level (0-9) for text assets; already-compressed media is stored */
+    zip_compression_level?: number;
     [key: string]: unknown;
   };
   json_import_data?: unknown;
@@ -69,11 +108,103 @@ interface TauriRecoveryInfo {
   backupPath?: string;
   lastModified?: string;
   backupTimestamp?: string;
+  localTimes?: LocalTimes;
+}
+
+export interface RecoveryItem {
+  id: string;
+  kind: 'newerBackup' | 'autosaveDraft' | 'partialWrite';
+  projectPath: string;
+  projectName: string;
+  modified?: string;
+  projectModified?: string;
+  size: number;
+  readable: boolean;
+  /** `modified` and `projectModified` in local time */
+  localTimes?: LocalTimes;
+}
+
+export interface RecoveryReport {
+  items: RecoveryItem[];
+  scannedProjects: number;
+}
+
+export interface OutlineMergeResult {
+  renamed: number;
+  addedTopics: string[];
+  reordered: boolean;
+  objectivesUpdated: boolean;
+  warnings: string[];
+  applied: boolean;
+}
+
+export interface TopicEditResult {
+  topicIds: string[];
+  insertedTopic: string | null;
+  renamedMedia: [string, string][];
+  reassignedMedia: string[];
+  removedMedia: string[];
+}
+
+export interface StableIdMigration {
+  topics: [string, string][];
+  reassignedMedia: string[];
+  warnings: string[];
+  applied: boolean;
+}
+
+export interface WorkspaceArtifact {
+  path: string;
+  kind: 'orphanedProjectFolder' | 'partialWrite' | 'staleScreenshot';
+  size: number;
+  modified: string | null;
+}
+
+export interface WorkspaceCleanupReport {
+  artifacts: WorkspaceArtifact[];
+  totalSize: number;
+  removed: boolean;
+  errors: [string, string][];
+}
+
+export type HealthStatus = 'ok' | 'warning' | 'error';
+
+export interface HealthCheck {
+  kind: 'projectsDirectory' | 'settings' | 'projectIndex' | 'diskSpace' | 'recovery';
+  status: HealthStatus;
+  message: string;
+}
+
+export interface WorkspaceHealthReport {
+  /** Worst status of all checks */
+  status: HealthStatus;
+  checks: HealthCheck[];
+  projectsDirectory: string;
+  freeSpace: number | null;
+}
+
+/** Payload of the `import-progress` events of importProjectFromZipWithProgress */
+export type ImportProgress = ProgressEvent<ImportProgressPayload & {
+  /** Pass to cancelImport to stop the import */
+  operationId: string;
+  /** Overall percentage */
+  progress: number;
+  freeSpace: number | null;
+}>;
+
+export interface ProjectIntegrityReport {
+  projectId: string;
+  /** Corrections an import would make to the project's media */
+  mediaAlignment: MediaCorrection[];
+  /** Media metadata naming the wrong page; migrateMediaPageIds fixes them */
+  mediaPageIds: PageIdValidationReport;
 }
 
 interface ExtendedLoadResult extends LoadResult {
   hasRecovery?: boolean;
   backupTimestamp?: string;
+  /** False when the project is on read-only storage or was opened read-only */
+  isWritable?: boolean;
 }
 
 interface MediaInfo {
@@ -97,6 +228,7 @@ export class FileStorage {
 
   private _currentProjectId: string | null = null;
   private _currentProjectPath: string | null = null;
+  private _isReadOnly = false;
   public isInitialized = false;
 
   // Debouncing for save operations
@@ -124,6 +256,11 @@ export class FileStorage {
     return this._currentProjectId;
   }
 
+  /** True when the open project can't be saved; the backend rejects every change */
+  get isReadOnly(): boolean {
+    return this._isReadOnly;
+  }
+
   async initialize(): Promise<void> {
     debugLogger.info('FileStorage.initialize', 'Initializing storage system');
     this.isInitialized = true;
@@ -213,17 +350,20 @@ export class FileStorage {
 
       this._currentProjectId = finalProjectId;
       this._currentProjectPath = projectId;
+      this._isReadOnly = projectFile.project.is_writable === false;
       
       debugLogger.info('FileStorage.openProject', `Project opened successfully: ${projectFile.project.name}`, {
         originalId: projectFile.project.id,
         extractedId: finalProjectId,
-        projectPath: projectId
+        projectPath: projectId,
+        readOnly: this._isReadOnly
       });
       
       // Return data with recovery info if available
       const result: ExtendedLoadResult = { 
         success: true,
-        data: projectFile.project
+        data: projectFile.project,
+        isWritable: !this._isReadOnly
       };
       if (recoveryInfo && recoveryInfo.hasRecovery) {
         result.hasRecovery = true;
@@ -399,6 +539,13 @@ export class FileStorage {
         projectFile.scorm_config.version = (typeof scormContent.version === 'string' ? scormContent.version : projectFile.scorm_config.version) || 'SCORM_2004';
         projectFile.scorm_config.completion_criteria = (typeof scormContent.completion_criteria === 'string' ? scormContent.completion_criteria : projectFile.scorm_config.completion_criteria) || 'all';
         projectFile.scorm_config.passing_score = (typeof scormContent.passing_score === 'number' ? scormContent.passing_score : projectFile.scorm_config.passing_score) || 80;
+        // Per-package media rules (applied to the packaged copies only)
+        if (scormContent.media_transforms && typeof scormContent.media_transforms === 'object') {
+          projectFile.scorm_config.media_transforms = scormContent.media_transforms;
+        }
+        if (typeof scormContent.zip_compression_level === 'number') {
+          projectFile.scorm_config.zip_compression_level = scormContent.zip_compression_level;
+        }
       } else if (contentId === 'courseSeedData') {
         // Save complete course seed data and sync topics to course_data
         projectFile.course_seed_data = content;
@@ -576,12 +723,12 @@ export class FileStorage {
     }
   }
 
-  async listProjects(): Promise<Project[]> {
+  async listProjects(filter?: ProjectFilter): Promise<Project[]> {
     try {
       debugLogger.info('FileStorage.listProjects', 'Fetching project list');
       console.log('[FileStorage] 📡 Calling backend list_projects command...');
 
-      const projects = await invoke<any[]>('list_projects');
+      const projects = await invoke<any[]>('list_projects', { filter });
 
       console.log('[FileStorage] 📊 Backend response:', {
         responseType: typeof projects,
@@ -600,7 +747,9 @@ export class FileStorage {
         last_modified: p.last_modified || p.updatedAt || new Date().toISOString(),
         // Also include these for backward compatibility
         createdAt: p.created || p.createdAt || new Date().toISOString(),
-        updatedAt: p.last_modified || p.updatedAt || new Date().toISOString()
+        updatedAt: p.last_modified || p.updatedAt || new Date().toISOString(),
+        tags: p.tags || [],
+        status: p.status || 'draft'
       }));
       
       debugLogger.info('FileStorage.listProjects', `Returning ${mappedProjects.length} projects`, {
@@ -804,7 +953,9 @@ export class FileStorage {
         thumbnail: metadata?.thumbnail || undefined,
         // Preserve YouTube clip timing (support both formats for robust persistence)
         clip_start: metadata?.clip_start ?? metadata?.clipStart ?? undefined,
-        clip_end: metadata?.clip_end ?? metadata?.clipEnd ?? undefined
+        clip_end: metadata?.clip_end ?? metadata?.clipEnd ?? undefined,
+        alt_text: metadata?.alt_text || metadata?.altText || undefined,
+        attribution: metadata?.attribution || undefined
       }
       
       console.log('🔍 [CLIP DEBUG] FileStorage.storeMedia - About to call Rust backend with:', {
@@ -1222,6 +1373,79 @@ export class FileStorage {
     }
   }
 
+  /**
+   * Open a project for viewing only. Saves, media changes and other edits
+   * fail with a clear error until the project is opened normally again.
+   */
+  async openProjectReadonly(filePath: string): Promise<ExtendedLoadResult> {
+    try {
+      const projectFile = await invoke<TauriProjectFile>('open_project_readonly', { filePath });
+      this._currentProjectId = extractProjectId(filePath) || projectFile.project.id;
+      this._currentProjectPath = filePath;
+      this._isReadOnly = true;
+
+      debugLogger.info('FileStorage.openProjectReadonly', `Project opened read-only: ${projectFile.project.name}`, {
+        projectPath: filePath
+      });
+
+      return { success: true, data: projectFile.project, isWritable: false };
+    } catch (error) {
+      debugLogger.error('FileStorage.openProjectReadonly', `Failed to open project: ${filePath}`, error);
+      throw error;
+    }
+  }
+
+  /**
+   * Open a project that another app instance has open, taking it over.
+   * The other instance can no longer save changes to it.
+   */
+  async takeOverProject(filePath: string): Promise<ExtendedLoadResult> {
+    try {
+      const projectFile = await invoke<TauriProjectFile>('take_over_project', { filePath });
+      this._currentProjectId = extractProjectId(filePath) || projectFile.project.id;
+      this._currentProjectPath = filePath;
+      this._isReadOnly = false;
+
+      debugLogger.info('FileStorage.takeOverProject', `Project taken over: ${projectFile.project.name}`, {
+        projectPath: filePath
+      });
+
+      return { success: true, data: projectFile.project, isWritable: true };
+    } catch (error) {
+      debugLogger.error('FileStorage.takeOverProject', `Failed to take over project: ${filePath}`, error);
+      throw error;
+    }
+  }
+
+  /**
+   * Close the current project, releasing its lock so other instances can edit it
+   */
+  async closeProject(): Promise<void> {
+    // Cancel all pending saves
+    this.cancelAllPendingSaves();
+
+    if (this._currentProjectPath) {
+      try {
+        await invoke('close_project', { filePath: this._currentProjectPath });
+      } catch (error) {
+        debugLogger.warn('FileStorage.closeProject', 'Failed to release project lock', error);
+      }
+    }
+
+    this._currentProjectId = null;
+    this._currentProjectPath = null;
+    this._isReadOnly = false;
+
+    debugLogger.info('FileStorage.closeProject', 'Project closed and saves cancelled');
+  }
+
+  /**
+   * Whether an open failed because another app instance has the project open
+   */
+  static isOpenElsewhereError(error: unknown): boolean {
+    return String(error).includes('open in another instance');
+  }
+
   async openProjectFromPath(filePath: string, options?: any): Promise<void> {
     try {
       const projectFile = await invoke<any>('load_project', { filePath: filePath });
@@ -1267,10 +1491,11 @@ export class FileStorage {
         // Use the full project path if available, otherwise use projectId (which might be a path)
         const backupId = projectId.includes('.scormproj') ? projectId : 
                         (this._currentProjectPath || projectId);
-        await invoke('create_backup', { projectId: backupId });
-        debugLogger.debug('FileStorage.saveProject', 'Backup created before save', { 
+        const status = await invoke<'written' | 'upToDate' | 'skipped'>('create_backup', { projectId: backupId });
+        debugLogger.debug('FileStorage.saveProject', 'Backup checked before save', { 
           projectId: backupId,
-          originalId: projectId 
+          originalId: projectId,
+          status
         });
       } catch (backupError) {
         // Log but don't fail the save if backup fails
@@ -1295,8 +1520,9 @@ export class FileStorage {
         try {
           // Prefer the full project path over just the ID
           const backupId = this._currentProjectPath || this._currentProjectId;
-          await invoke('create_backup', { projectId: backupId });
-          debugLogger.debug('FileStorage.saveProject', 'Backup created before save', { 
+          const status = await invoke<'written' | 'upToDate' | 'skipped'>('create_backup', { projectId: backupId });
+          debugLogger.debug('FileStorage.saveProject', 'Backup checked before save', { 
+            status,
             projectId: backupId,
             currentId: this._currentProjectId,
             currentPath: this._currentProjectPath
@@ -1360,6 +1586,74 @@ export class FileStorage {
     }
   }
 
+  /**
+   * Store a project's course content inline ('single') or as one file per
+   * page next to the project file ('split'), which keeps saves of large
+   * courses small. Loading and saving work the same either way.
+   */
+  async setContentLayout(projectIdOrPath: string, layout: 'single' | 'split'): Promise<void> {
+    try {
+      const projectPath = await this.resolveProjectPath(projectIdOrPath);
+      await invoke('set_content_layout', { filePath: projectPath, layout });
+      debugLogger.info('FileStorage.setContentLayout', 'Content layout changed', {
+        projectPath,
+        layout
+      });
+    } catch (error) {
+      debugLogger.error('FileStorage.setContentLayout', 'Failed to change content layout', error);
+      throw error;
+    }
+  }
+
+  /** Whether a project is encrypted, and unlocked */
+  async getProjectEncryption(projectIdOrPath: string): Promise<EncryptionStatus> {
+    const projectPath = await this.resolveProjectPath(projectIdOrPath);
+    return invoke<EncryptionStatus>('get_project_encryption', { projectId: projectPath });
+  }
+
+  /**
+   * Encrypt a project's content and media with `passphrase`. The passphrase
+   * can't be recovered, so the backend refuses unless `acknowledgeNoRecovery`
+   * says the author was shown that warning. The project stays unlocked.
+   */
+  async enableProjectEncryption(
+    projectIdOrPath: string,
+    passphrase: string,
+    acknowledgeNoRecovery: boolean
+  ): Promise<EncryptionStatus> {
+    const projectPath = await this.resolveProjectPath(projectIdOrPath);
+    const status = await invoke<EncryptionStatus>('enable_project_encryption', {
+      projectId: projectPath,
+      passphrase,
+      acknowledgeNoRecovery
+    });
+    debugLogger.info('FileStorage.enableProjectEncryption', 'Project encrypted', { projectPath });
+    return status;
+  }
+
+  /** Unlock an encrypted project for this session so it can be opened and exported */
+  async unlockProject(projectIdOrPath: string, passphrase: string): Promise<EncryptionStatus> {
+    const projectPath = await this.resolveProjectPath(projectIdOrPath);
+    return invoke<EncryptionStatus>('unlock_project', { projectId: projectPath, passphrase });
+  }
+
+  /** Forget an encrypted project's key until it is unlocked again */
+  async lockProject(projectIdOrPath: string): Promise<EncryptionStatus> {
+    const projectPath = await this.resolveProjectPath(projectIdOrPath);
+    return invoke<EncryptionStatus>('lock_project', { projectId: projectPath });
+  }
+
+  /** Decrypt a project's content and media for good */
+  async disableProjectEncryption(projectIdOrPath: string, passphrase: string): Promise<EncryptionStatus> {
+    const projectPath = await this.resolveProjectPath(projectIdOrPath);
+    const status = await invoke<EncryptionStatus>('disable_project_encryption', {
+      projectId: projectPath,
+      passphrase
+    });
+    debugLogger.info('FileStorage.disableProjectEncryption', 'Project decrypted', { projectPath });
+    return status;
+  }
+
   async deleteProject(projectIdOrPath: string): Promise<void> {
     try {
       // Resolve to path if it's an ID
@@ -1404,7 +1698,7 @@ export class FileStorage {
     }
   }
 
-  async deleteMedia(mediaId: string): Promise<boolean> {
+  async deleteMedia(mediaId: string, options: { force?: boolean } = {}): Promise<boolean> {
     if (!this._currentProjectId) {
       debugLogger.error('FileStorage.deleteMedia', 'No project open');
       throw new Error('No project open');
@@ -1413,10 +1707,21 @@ export class FileStorage {
     try {
       // Call Tauri backend to delete media file and metadata
       // Use path if available (backend will extract ID), otherwise use ID
-      await invoke('delete_media', {
+      // The backend refuses to delete media the saved course content still uses unless forced
+      const result = await invoke<DeleteMediaResult | undefined>('delete_media', {
         projectId: this._currentProjectPath || this._currentProjectId,
-        mediaId
+        mediaId,
+        ...(options.force ? { force: true } : {})
       });
+
+      if (result && result.deleted === false) {
+        debugLogger.warn('FileStorage.deleteMedia', `Media still in use, not deleted: ${mediaId}`, {
+          projectId: this._currentProjectId,
+          mediaId,
+          referencedBy: result.referenced_by
+        });
+        return false;
+      }
       
       debugLogger.info('FileStorage.deleteMedia', `Media deleted: ${mediaId}`, { 
         projectId: this._currentProjectId,
@@ -1434,6 +1739,77 @@ export class FileStorage {
     }
   }
 
+  /**
+   * Set or clear a media item's alt text; returns the stored metadata
+   */
+  async updateMediaAltText(mediaId: string, altText: string | undefined): Promise<any> {
+    if (!this._currentProjectId) throw new Error('No project open');
+
+    try {
+      return await invoke('update_media_alt_text', {
+        projectId: this._currentProjectPath || this._currentProjectId,
+        mediaId,
+        altText: altText || null
+      });
+    } catch (error) {
+      debugLogger.error('FileStorage.updateMediaAltText', `Failed to update alt text: ${mediaId}`, error);
+      throw error;
+    }
+  }
+
+  /**
+   * Change titles, alt text, clip timing and pages of many media items at once,
+   * without re-uploading their data. Unset fields are kept; empty text clears.
+   * If any update is invalid nothing changes and the error lists each problem,
+   * including YouTube clips that start or end past the video.
+   */
+  async updateMediaMetadataBatch(
+    updates: Array<{
+      media_id: string
+      title?: string
+      alt_text?: string
+      clip_timing?: { start: number | null; end: number | null }
+      page_id?: string
+    }>
+  ): Promise<any[]> {
+    if (!this._currentProjectId) throw new Error('No project open');
+
+    try {
+      return await invoke<any[]>('update_media_metadata_batch', {
+        projectId: this._currentProjectPath || this._currentProjectId,
+        updates
+      });
+    } catch (error) {
+      debugLogger.error('FileStorage.updateMediaMetadataBatch', `Failed to update ${updates.length} media items`, error);
+      throw error;
+    }
+  }
+
+  /**
+   * YouTube clips of the open project that don't fit their video, with its
+   * length. Lengths come from the YouTube Data API key saved under API keys;
+   * without one only clips that end before they start are found.
+   */
+  async checkClipTiming(): Promise<ClipTimingIssue[]> {
+    if (!this._currentProjectId) throw new Error('No project open');
+
+    return invoke<ClipTimingIssue[]>('check_clip_timing', {
+      projectId: this._currentProjectPath || this._currentProjectId
+    });
+  }
+
+  /**
+   * The open project's animated GIF images, biggest first. Set
+   * `scorm_config.media_transforms.convert_animated_gifs` to package them as video.
+   */
+  async findAnimatedGifs(): Promise<AnimatedGifInfo[]> {
+    if (!this._currentProjectId) throw new Error('No project open');
+
+    return invoke<AnimatedGifInfo[]>('find_animated_gifs', {
+      projectId: this._currentProjectPath || this._currentProjectId
+    });
+  }
+
   /**
    * Check if media exists by ID
    * @param mediaId The media ID to check
@@ -1470,16 +1846,6 @@ export class FileStorage {
     this.retryCount.clear();
   }
 
-  async closeProject(): Promise<void> {
-    // Cancel all pending saves
-    this.cancelAllPendingSaves();
-    
-    this._currentProjectId = null;
-    this._currentProjectPath = null;
-    
-    debugLogger.info('FileStorage.closeProject', 'Project closed and saves cancelled');
-  }
-
   async recoverFromBackup(projectIdOrPath: string): Promise<any> {
     try {
       // Reject if user tries to pass a backup file path directly
@@ -1510,16 +1876,165 @@ export class FileStorage {
     }
   }
   
-  async cleanupOldBackups(projectId: string, keepCount: number = 5): Promise<any> {
+  /**
+   * Everything that could be recovered across all projects: newer backups,
+   * autosave drafts and temp files left by interrupted saves
+   */
+  async getRecoveryReport(): Promise<RecoveryReport> {
+    const report = await invoke<RecoveryReport>('get_recovery_report');
+    debugLogger.info('FileStorage.getRecoveryReport', 'Recovery report', {
+      items: report.items.length,
+      scannedProjects: report.scannedProjects
+    });
+    return report;
+  }
+
+  async recoverAll(): Promise<{ recovered: string[]; skipped: [string, string][] }> {
+    return invoke('recover_all');
+  }
+
+  /** Restore one report item over its project file; returns the project path */
+  async acceptRecoveryItem(itemId: string): Promise<string> {
+    return invoke<string>('accept_recovery_item', { itemId });
+  }
+
+  async discardRecoveryItem(itemId: string): Promise<void> {
+    await invoke('discard_recovery_item', { itemId });
+  }
+
+  /**
+   * Orphaned project folders, leftover .scormproj.tmp files and stale workflow
+   * screenshots. Only lists them unless `confirm` is true.
+   */
+  async cleanupWorkspace(confirm: boolean = false, maxScreenshotAgeDays?: number): Promise<WorkspaceCleanupReport> {
+    const report = await invoke<WorkspaceCleanupReport>('cleanup_workspace', { confirm, maxScreenshotAgeDays });
+    debugLogger.info('FileStorage.cleanupWorkspace', confirm ? 'Workspace cleaned' : 'Workspace scanned', {
+      artifacts: report.artifacts.length,
+      totalSize: report.totalSize,
+      errors: report.errors.length
+    });
+    return report;
+  }
+
+  /**
+   * Startup check of the projects folder, settings, projects index, free disk
+   * space and pending recovery; every check that isn't ok deserves a notification
+   */
+  async workspaceHealthCheck(): Promise<WorkspaceHealthReport> {
+    const report = await invoke<WorkspaceHealthReport>('workspace_health_check');
+    debugLogger.info('FileStorage.workspaceHealthCheck', 'Workspace health', {
+      status: report.status,
+      problems: report.checks?.filter(check => check.status !== 'ok').map(check => check.kind)
+    });
+    return report;
+  }
+
+  /** Media on the wrong page of a saved project, found without changing it */
+  async checkProjectIntegrity(projectId: string): Promise<ProjectIntegrityReport> {
+    return invoke<ProjectIntegrityReport>('check_project_integrity', { projectId });
+  }
+
+  /** Course structure (titles, objectives, question stems) as an editable YAML outline */
+  async exportCourseOutline(projectId: string): Promise<string> {
+    return invoke<string>('export_course_outline', { projectId });
+  }
+
+  /** Author notes of every page and question as a Markdown document, for review outside the app */
+  async exportAuthorNotes(projectId: string): Promise<string> {
+    return invoke<string>('export_author_notes', { projectId });
+  }
+
+  /**
+   * Merge an edited outline back into the course content. With `dryRun` the
+   * changes and warnings are reported without saving.
+   */
+  async importCourseOutline(projectId: string, outline: string, dryRun: boolean = false): Promise<OutlineMergeResult> {
+    const result = await invoke<OutlineMergeResult>('import_course_outline', { projectId, outline, dryRun });
+    if (result.warnings.length > 0) {
+      debugLogger.warn('FileStorage.importCourseOutline', 'Outline merged with warnings', {
+        warnings: result.warnings
+      });
+    }
+    return result;
+  }
+
+  /** The question bank in Moodle's GIFT format; questions GIFT can't hold are listed in `warnings` */
+  async exportQuestionsGift(projectId: string): Promise<GiftExport> {
+    return invoke<GiftExport>('export_questions_gift', { projectId });
+  }
+
+  /**
+   * Add the questions of a GIFT file to the question bank. Questions titled
+   * with a bank id replace that question; unsupported ones are skipped with a
+   * warning. With `dryRun` nothing is saved.
+   */
+  async importQuestionsGift(projectId: string, gift: string, dryRun: boolean = false): Promise<GiftImportResult> {
+    const result = await invoke<GiftImportResult>('import_questions_gift', { projectId, gift, dryRun });
+    if (result.warnings.length > 0) {
+      debugLogger.warn('FileStorage.importQuestionsGift', 'GIFT questions imported with warnings', {
+        warnings: result.warnings
+      });
+    }
+    return result;
+  }
+
+  /**
+   * Structural topic edits. These run in the backend so audio-N/caption-N
+   * numbering and media page IDs stay in step with the topic order.
+   */
+  async moveTopic(projectId: string, fromIndex: number, toIndex: number): Promise<TopicEditResult> {
+    return invoke<TopicEditResult>('move_topic', { projectId, fromIndex, toIndex });
+  }
+
+  async deleteTopic(projectId: string, index: number): Promise<TopicEditResult> {
+    return invoke<TopicEditResult>('delete_topic', { projectId, index });
+  }
+
+  async insertTopic(projectId: string, index: number, title: string): Promise<TopicEditResult> {
+    return invoke<TopicEditResult>('insert_topic', { projectId, index, title });
+  }
+
+  /**
+   * Replace positional topic IDs (topic-N) with stable ones and point every
+   * media page_id at its page by ID. Audio and caption IDs (audio-N) keep their
+   * page numbering. With `dryRun` the changes are reported without touching
+   * the project.
+   */
+  async migrateStableIds(projectId: string, dryRun: boolean = false): Promise<StableIdMigration> {
+    const result = await invoke<StableIdMigration>('migrate_stable_ids', { projectId, dryRun });
+    if (result.warnings.length > 0) {
+      debugLogger.warn('FileStorage.migrateStableIds', 'Some media could not be matched to a page', {
+        warnings: result.warnings
+      });
+    }
+    return result;
+  }
+
+  /**
+   * Delete backups beyond the newest `keepCount` or older than `maxAgeDays`; the
+   * main `.backup` file is always kept. With `dryRun` nothing is deleted and the
+   * result lists what would be.
+   */
+  async cleanupOldBackups(
+    projectId: string,
+    keepCount: number = 5,
+    options: { maxAgeDays?: number; dryRun?: boolean } = {}
+  ): Promise<BackupCleanupResult> {
     try {
-      debugLogger.info('FileStorage.cleanupOldBackups', `Cleaning up old backups for: ${projectId}`, { keepCount });
+      debugLogger.info('FileStorage.cleanupOldBackups', `Cleaning up old backups for: ${projectId}`, { keepCount, ...options });
       
-      const result = await invoke<any>('cleanup_old_backups', {
+      const result = await invoke<BackupCleanupResult>('cleanup_old_backups', {
         projectId,
-        keepCount
+        keepCount,
+        maxAgeDays: options.maxAgeDays,
+        dryRun: options.dryRun
       });
       
-      debugLogger.info('FileStorage.cleanupOldBackups', 'Cleanup complete', result);
+      debugLogger.info('FileStorage.cleanupOldBackups', result.dryRun ? 'Cleanup dry run' : 'Cleanup complete', {
+        deleted: result.deleted.map(file => ({ path: file.path, size: file.size, ageDays: file.ageDays, reason: file.reason })),
+        keptCount: result.keptCount,
+        freedBytes: result.freedBytes
+      });
       
       return result;
     } catch (error) {
@@ -1573,7 +2088,11 @@ export class FileStorage {
     }
   }
 
-  async exportProject(): Promise<Blob> {
+  /**
+   * Export the open project as a ZIP. With `nameMediaByOriginal` media files in
+   * the ZIP keep the names they were added with; importing restores their ids.
+   */
+  async exportProject(nameMediaByOriginal: boolean = false): Promise<Blob> {
     if (!this._currentProjectPath) throw new Error('No project open');
     try {
       // Export project as ZIP with media
@@ -1581,7 +2100,8 @@ export class FileStorage {
         const zipResult = await invoke<any>('create_project_zip', {
           projectPath: this._currentProjectPath,
           projectId: this._currentProjectId,
-          includeMedia: true
+          includeMedia: true,
+          nameMediaByOriginal
         });
         
         const blob = new Blob([zipResult.zipData], { type: 'application/zip' });
@@ -1644,32 +2164,75 @@ export class FileStorage {
     }
   }
 
-  async importProjectFromZip(zipBlob: Blob): Promise<void> {
+  /**
+   * Import a project ZIP. Media on the wrong page is fixed unless `autoFix`
+   * is false; either way the corrections are in the result.
+   */
+  async importProjectFromZip(zipBlob: Blob, autoFix: boolean = true): Promise<ImportResult> {
     try {
       // Convert blob to ArrayBuffer for Tauri
       const arrayBuffer = await zipBlob.arrayBuffer();
       const zipData = Array.from(new Uint8Array(arrayBuffer));
       
       // Extract and save project from ZIP (simplified - Rust does all the work)
-      const result = await invoke<any>('extract_project_zip', {
-        zipData: zipData
+      const result = await invoke<ImportResult>('extract_project_zip', {
+        zipData: zipData,
+        autoFix
       });
       
       debugLogger.info('FileStorage.importProjectFromZip', 'Project imported successfully', {
         projectPath: result.projectPath,
         projectId: result.projectId,
-        projectName: result.projectName
+        projectName: result.projectName,
+        mediaCorrections: result.mediaCorrections,
+        mediaFixed: result.mediaFixed
       });
       
       // The Rust function already extracted and saved everything
-      // We just need to return success so the UI can refresh
+      return result;
     } catch (error) {
       debugLogger.error('FileStorage.importProjectFromZip', 'Failed to import project from zip', error);
       // Pass through the actual error message for better debugging
-      throw error instanceof Error ? error : new Error('Failed to import project');
+      throw error instanceof Error ? error : new Error(String(error));
+    }
+  }
+
+  /**
+   * importProjectFromZip, calling `onProgress` as entries are extracted and
+   * media is copied. The import can be stopped with cancelImport and the
+   * operationId of any progress event; a cancelled import adds nothing.
+   */
+  async importProjectFromZipWithProgress(
+    zipBlob: Blob,
+    onProgress: (progress: ImportProgress) => void,
+    autoFix: boolean = true
+  ): Promise<ImportResult> {
+    const { listen } = await import('@tauri-apps/api/event');
+    const unlisten = await listen<ImportProgress>('import-progress', event => onProgress(event.payload));
+    try {
+      const zipData = Array.from(new Uint8Array(await zipBlob.arrayBuffer()));
+      const result = await invoke<ImportResult>('extract_project_zip_with_progress', {
+        zipData,
+        autoFix
+      });
+      debugLogger.info('FileStorage.importProjectFromZipWithProgress', 'Project imported successfully', {
+        projectPath: result.projectPath,
+        projectId: result.projectId
+      });
+      return result;
+    } catch (error) {
+      debugLogger.error('FileStorage.importProjectFromZipWithProgress', 'Failed to import project from zip', error);
+      throw error instanceof Error ? error : new Error(String(error));
+    } finally {
+      unlisten();
     }
   }
 
+  /** Stop a running import; false when it already finished */
+  async cancelImport(operationId: string): Promise<boolean> {
+    return invoke<boolean>('cancel_project_operation', { operationId });
+  }
+
   setProjectsDirectory(directory: string): void {
     debugLogger.info('FileStorage.setProjectsDirectory', `Setting projects directory: ${directory}`);
     
@@ -8,6 +8,7 @@
 import { FileStorage } from './FileStorage'
 import { generateMediaId, type MediaType, type MediaId } from '../utils/idGenerator'
 import { logger } from '../utils/logger'
+import type { MediaAttribution } from './stockImages'
 
 // 🔧 FIX 5: ULTIMATE CIRCUIT BREAKER - Global flag declaration
 declare global {
@@ -15,6 +16,7 @@ declare global {
 }
 import { debugLogger } from '@/utils/ultraSimpleLogger'
 import { blobUrlManager } from '../utils/blobUrlManager'
+import { parseMediaPolicyError } from './mediaPolicy'
 
 // 🎯 CONTEXT-AWARE CACHE: Operation types for intelligent TTL selection
 export type ListAllOperation = 'ui' | 'gen' | 'bg'
@@ -44,6 +46,8 @@ export interface MediaMetadata {
   isYouTube?: boolean
   clipStart?: number  // YouTube clip start time in seconds
   clipEnd?: number    // YouTube clip end time in seconds
+  altText?: string    // Image alt text, or the accessible name of a video player
+  attribution?: MediaAttribution  // Author and license, listed on the package's credits page
   [key: string]: unknown
 }
 
@@ -123,7 +127,7 @@ export interface MediaServiceExtended {
   // Standard MediaService methods
   storeMedia(file: File | Blob, pageId: string, type: MediaType, metadata?: Partial<MediaMetadata>, progressCallback?: ProgressCallback): Promise<MediaItem>
   updateMedia(existingId: string, file: File | Blob, metadata?: Partial<MediaMetadata>, progressCallback?: ProgressCallback): Promise<MediaItem>
-  deleteMedia(projectId: string, mediaId: string): Promise<boolean>
+  deleteMedia(projectId: string, mediaId: string, options?: { force?: boolean }): Promise<boolean>
   listAllMedia(options?: { excludeTypes?: string[]; op?: ListAllOperation }): Promise<MediaItem[]>
   getMedia(mediaId: string): Promise<{ data?: Uint8Array; metadata: MediaMetadata; url?: string } | null>
 }
@@ -688,6 +692,10 @@ export class MediaService implements MediaServiceExtended {
         error
       })
       logger.error('[MediaService] Failed to store media to file system:', error)
+      // Keep policy rejections intact so callers can list the violations
+      if (parseMediaPolicyError(error)) {
+        throw error
+      }
       throw new Error(`Failed to store media to file system: ${error}`)
     }
   }
@@ -828,7 +836,8 @@ export class MediaService implements MediaServiceExtended {
         embed_url: updatedMetadata.embedUrl,
         duration: updatedMetadata.duration,
         clip_start: normalizedClipStart,
-        clip_end: normalizedClipEnd
+        clip_end: normalizedClipEnd,
+        alt_text: updatedMetadata.altText
       }
       
       mediaServiceDebugLog('🔍 [CLIP DEBUG] About to call storeYouTubeVideo with metadata:', {
@@ -2288,6 +2297,8 @@ export class MediaService implements MediaServiceExtended {
       isYouTube: mediaInfo.metadata?.source === 'youtube' || mediaInfo.metadata?.isYouTube === true,
       youtubeUrl: youtubeUrl,
       title: mediaInfo.metadata?.title,
+      altText: mediaInfo.metadata?.altText || mediaInfo.metadata?.alt_text,
+      attribution: mediaInfo.metadata?.attribution,
       uploadedAt: mediaInfo.metadata?.uploadedAt || new Date().toISOString(),
       // 🔧 FIX: Convert snake_case clip timing fields to camelCase with proper null/string handling
       clipStart: this.normalizeClipTiming(mediaInfo.metadata?.clipStart || mediaInfo.metadata?.clip_start),
@@ -2790,14 +2801,15 @@ export class MediaService implements MediaServiceExtended {
   }
   
   /**
-   * Delete media from file system
+   * Delete media from file system. Media the saved course content still uses
+   * is kept unless `force` is set.
    */
-  async deleteMedia(projectId: string, mediaId: string): Promise<boolean> {
+  async deleteMedia(projectId: string, mediaId: string, options: { force?: boolean } = {}): Promise<boolean> {
     debugLogger.info('MediaService.deleteMedia', 'Deleting media', { projectId, mediaId })
     
     try {
       // Call FileStorage to delete from backend
-      const deleted = await this.fileStorage.deleteMedia(mediaId)
+      const deleted = await this.fileStorage.deleteMedia(mediaId, options)
       
       if (deleted) {
         // Remove from cache only if deletion succeeded
@@ -2828,6 +2840,23 @@ export class MediaService implements MediaServiceExtended {
     }
   }
 
+  /**
+   * Persist alt text for a media item so it reaches the generated package
+   */
+  async updateMediaAltText(mediaId: string, altText: string | undefined): Promise<MediaItem | undefined> {
+    const stored = await this.fileStorage.updateMediaAltText(mediaId, altText)
+    const existing = this.mediaCache.get(mediaId)
+    if (!existing) return undefined
+
+    const updated: MediaItem = {
+      ...existing,
+      metadata: { ...existing.metadata, altText: stored?.alt_text ?? undefined }
+    }
+    this.mediaCache.set(mediaId, updated)
+    this.clearListAllMediaCaches()
+    return updated
+  }
+
   /**
    * Delete all media for a specific topic
    */
@@ -181,7 +181,8 @@ describe('FileStorage Export/Import', () => {
       
       // Assert
       expect(mockInvoke).toHaveBeenCalledWith('extract_project_zip', {
-        zipData: expect.any(Object) // ArrayBuffer
+        zipData: expect.any(Object), // ArrayBuffer
+        autoFix: true
       });
       
       expect(mockInvoke).toHaveBeenCalledWith('save_project_with_media', 
@@ -91,6 +91,34 @@ describe('MediaStorage - .scormproj path handling', () => {
       })
     })
 
+    it('should not report success when the backend keeps in-use media', async () => {
+      mockInvoke.mockImplementation((cmd) => {
+        if (cmd === 'delete_media') {
+          return Promise.resolve({ deleted: false, referenced_by: ['topic-1'] })
+        }
+        return Promise.resolve(undefined)
+      })
+
+      fileStorage['_currentProjectPath'] = scormProjPath
+      const result = await fileStorage.deleteMedia('image-0')
+
+      expect(result).toBe(false)
+    })
+
+    it('should pass the force flag through to the backend', async () => {
+      mockInvoke.mockResolvedValue({ deleted: true, referenced_by: ['topic-1'] })
+
+      fileStorage['_currentProjectPath'] = scormProjPath
+      const result = await fileStorage.deleteMedia('image-0', { force: true })
+
+      expect(result).toBe(true)
+      expect(mockInvoke).toHaveBeenCalledWith('delete_media', {
+        projectId: scormProjPath,
+        mediaId: 'image-0',
+        force: true
+      })
+    })
+
     it('should get media when projectId is a .scormproj path', async () => {
       const mediaId = 'media-123'
       const mediaData = new ArrayBuffer(100)
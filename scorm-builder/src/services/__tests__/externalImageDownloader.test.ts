@@ -1,5 +1,11 @@
 import { describe, it, expect, vi, beforeEach } from 'vitest'
-import { downloadExternalImage, isExternalUrl, downloadIfExternal, isKnownCorsRestrictedDomain } from '../externalImageDownloader'
+import {
+  downloadExternalImage,
+  forceDownloadExternalImage,
+  isExternalUrl,
+  downloadIfExternal,
+  isKnownCorsRestrictedDomain
+} from '../externalImageDownloader'
 import { invoke } from '@tauri-apps/api/core'
 
 // Mock Tauri invoke
@@ -33,44 +39,73 @@ describe('ExternalImageDownloader', () => {
       }
       vi.mocked(invoke).mockResolvedValue(mockResponse)
 
-      const blob = await downloadExternalImage('https://example.com/image.jpg')
+      const attribution = { provider: 'google', license: 'CC0' }
+      const blob = await downloadExternalImage('https://example.com/image.jpg', attribution)
 
-      expect(invoke).toHaveBeenCalledWith('download_image', {
-        url: 'https://example.com/image.jpg'
+      expect(invoke).toHaveBeenCalledWith('download_external_media', {
+        url: 'https://example.com/image.jpg',
+        attribution
       })
       expect(blob).toBeInstanceOf(Blob)
       expect(blob.type).toBe('image/jpeg')
       expect(blob.size).toBeGreaterThan(0)
     })
 
-    it('should fallback to direct fetch when Tauri command fails', async () => {
-      vi.mocked(invoke).mockRejectedValue(new Error('Command not found'))
-      
-      const mockBlob = new Blob(['test'], { type: 'image/jpeg' })
-      vi.mocked(fetch).mockResolvedValueOnce({
-        ok: true,
-        blob: async () => mockBlob
-      } as Response)
-
-      const blob = await downloadExternalImage('https://example.com/image.jpg')
+    it('should not fall back to browser fetches when the backend fails', async () => {
+      vi.mocked(invoke).mockRejectedValue(
+        JSON.stringify({
+          message: 'Failed to fetch https://example.com/image.jpg: connection reset',
+          errorType: 'externalMedia',
+          kind: 'network',
+          url: 'https://example.com/image.jpg'
+        })
+      )
+
+      await expect(downloadExternalImage('https://example.com/image.jpg')).rejects.toThrow(/network connection/i)
+      expect(fetch).not.toHaveBeenCalled()
+    })
 
-      expect(fetch).toHaveBeenCalledWith('https://example.com/image.jpg')
-      expect(blob).toBe(mockBlob)
+    it('should report why the backend refused a URL', async () => {
+      vi.mocked(invoke).mockRejectedValue(
+        JSON.stringify({
+          message: '127.0.0.1 is a local or private address',
+          errorType: 'externalMedia',
+          kind: 'addressNotAllowed',
+          url: 'http://127.0.0.1/image.jpg'
+        })
+      )
+
+      await expect(downloadExternalImage('http://127.0.0.1/image.jpg')).rejects.toThrow(
+        '127.0.0.1 is a local or private address'
+      )
+      expect(fetch).not.toHaveBeenCalled()
     })
 
-    it('should use CORS proxy as last resort', async () => {
-      vi.mocked(invoke).mockRejectedValue(new Error('Command not found'))
-      vi.mocked(fetch)
-        .mockRejectedValueOnce(new Error('CORS error'))
-        .mockResolvedValueOnce({
-          ok: true,
-          blob: async () => new Blob(['proxy'], { type: 'image/jpeg' })
-        } as Response)
+    it('should not fall back when the backend refuses an unlicensed image', async () => {
+      vi.mocked(invoke).mockRejectedValue(
+        'A license is required for media imported from the web; none was given for https://example.com/image.jpg'
+      )
 
-      const blob = await downloadExternalImage('https://example.com/image.jpg')
+      await expect(downloadExternalImage('https://example.com/image.jpg')).rejects.toThrow('A license is required')
+      expect(fetch).not.toHaveBeenCalled()
+    })
+  })
 
-      expect(fetch).toHaveBeenLastCalledWith('https://corsproxy.io/?https%3A%2F%2Fexample.com%2Fimage.jpg')
-      expect(blob).toBeInstanceOf(Blob)
+  describe('forceDownloadExternalImage', () => {
+    it('should not fall back to browser fetches when the daily download limit is reached', async () => {
+      vi.mocked(invoke).mockRejectedValue(
+        JSON.stringify({
+          message: 'Daily limit of 200 requests for media_download reached; it resets tomorrow',
+          errorType: 'externalMedia',
+          kind: 'quotaExceeded',
+          url: 'https://example.com/image.jpg'
+        })
+      )
+
+      await expect(
+        forceDownloadExternalImage('https://example.com/image.jpg', { provider: 'google', license: 'CC0' })
+      ).rejects.toThrow('Daily limit of 200 requests')
+      expect(fetch).not.toHaveBeenCalled()
     })
   })
 
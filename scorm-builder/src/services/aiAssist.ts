@@ -0,0 +1,73 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+export type AiProvider = 'openai' | 'azure' | 'anthropic'
+
+/** Provider settings as saved; the key is stored encrypted by the backend */
+export interface AiProviderConfig {
+  provider: AiProvider
+  api_key: string
+  /** Model name; for Azure, the deployment name */
+  model: string
+  /** Azure resource endpoint */
+  endpoint?: string
+  api_version?: string
+}
+
+/** The saved provider, without its key */
+export interface AiProviderStatus {
+  provider: AiProvider
+  model: string
+  endpoint?: string
+  has_api_key: boolean
+}
+
+export interface AiContentRequest {
+  prompt: string
+  system?: string
+  maxTokens?: number
+  temperature?: number
+}
+
+export interface AiContentResult {
+  text: string
+  provider: AiProvider
+  model: string
+}
+
+export async function saveAiProvider(config: AiProviderConfig): Promise<void> {
+  await invoke('save_ai_provider', { config })
+}
+
+export async function getAiProvider(): Promise<AiProviderStatus | null> {
+  return invoke<AiProviderStatus | null>('get_ai_provider')
+}
+
+export async function deleteAiProvider(): Promise<void> {
+  await invoke('delete_ai_provider')
+}
+
+/**
+ * Generate text with the configured provider. The request is made by the
+ * backend, so the key never reaches the webview; text is passed to `onDelta`
+ * as it streams in.
+ */
+export async function generateContentWithAi(
+  request: AiContentRequest,
+  onDelta?: (delta: string) => void
+): Promise<AiContentResult> {
+  const requestId = crypto.randomUUID()
+  let unlisten: UnlistenFn | undefined
+  try {
+    if (onDelta) {
+      unlisten = await listen<{ requestId: string; delta: string }>('ai-content-delta', (event) => {
+        if (event.payload.requestId === requestId) onDelta(event.payload.delta)
+      })
+    }
+    return await invoke<AiContentResult>('generate_content_with_ai', {
+      request: { ...request, requestId }
+    })
+  } finally {
+    unlisten?.()
+  }
+}
@@ -0,0 +1,32 @@
+import { invoke } from '@tauri-apps/api/core'
+
+/** Daily limits for one provider; unset limits don't apply */
+export interface ApiLimit {
+  max_requests_per_day?: number
+  max_tokens_per_day?: number
+}
+
+/**
+ * Usage of one provider on one day. Providers are `openai`, `azure`,
+ * `anthropic` and `media_download` (images and other files fetched from URLs).
+ */
+export interface ProviderUsage {
+  date: string
+  provider: string
+  requests: number
+  tokens: number
+  limit: ApiLimit
+}
+
+/** Usage for the last `days` days, newest first */
+export async function getApiUsage(days = 7): Promise<ProviderUsage[]> {
+  return invoke<ProviderUsage[]>('get_api_usage', { days })
+}
+
+export async function getApiLimits(): Promise<Record<string, ApiLimit>> {
+  return invoke<Record<string, ApiLimit>>('get_api_limits')
+}
+
+export async function setApiLimits(limits: Record<string, ApiLimit>): Promise<void> {
+  await invoke('set_api_limits', { limits })
+}
@@ -1,6 +1,11 @@
 import { describe, test, expect, beforeEach, vi } from 'vitest'
+import { invoke } from '@tauri-apps/api/core'
 import { downloadExternalImage, isKnownCorsRestrictedDomain } from './externalImageDownloader'
 
+function externalMediaError(kind: string, message: string, url: string): string {
+  return JSON.stringify({ message, errorType: 'externalMedia', kind, url })
+}
+
 describe('CORS Error Handling for External Images', () => {
   beforeEach(() => {
     vi.clearAllMocks()
@@ -12,7 +17,7 @@ describe('CORS Error Handling for External Images', () => {
     // Mock CORS error from the workflow recording
     const corsError = new Error('Access to fetch at \'https://www.aga.org/...\' blocked by CORS policy')
     
-    vi.mocked(fetch).mockRejectedValueOnce(corsError)
+    vi.mocked(invoke).mockRejectedValueOnce(corsError)
 
     try {
       await downloadExternalImage('https://www.aga.org/image.jpg')
@@ -51,32 +56,21 @@ describe('CORS Error Handling for External Images', () => {
     })
   })
 
-  test('should retry with CORS proxies when direct fetch fails', async () => {
-    // Mock initial CORS failure
-    const corsError = new Error('CORS policy block')
-    
-    vi.mocked(fetch)
-      .mockRejectedValueOnce(corsError) // Direct fetch fails
-      .mockResolvedValueOnce({ // CORS proxy succeeds
-        ok: true,
-        blob: async () => new Blob(['proxy success'], { type: 'image/jpeg' })
-      } as Response)
+  test('should not retry in the browser or through CORS proxies when the backend fails', async () => {
+    vi.mocked(invoke).mockRejectedValueOnce(
+      externalMediaError('httpStatus', 'HTTP error: 403 Forbidden', 'https://example.com/image.jpg')
+    )
 
-    const result = await downloadExternalImage('https://example.com/image.jpg')
-    
-    expect(result).toBeInstanceOf(Blob)
-    expect(fetch).toHaveBeenCalledTimes(2) // Original + proxy attempt
-    
-    // Should have tried CORS proxy
-    const calls = vi.mocked(fetch).mock.calls
-    expect(calls[1][0]).toContain('corsproxy.io') // First proxy service
+    await expect(downloadExternalImage('https://example.com/image.jpg')).rejects.toThrow(/unable to download/i)
+
+    expect(fetch).not.toHaveBeenCalled()
   })
 
-  test('should show user-friendly error when all CORS methods fail', async () => {
-    // Mock all methods failing
-    const corsError = new Error('CORS blocked')
-    
-    vi.mocked(fetch).mockRejectedValue(corsError)
+  test('should show user-friendly error when the backend download fails', async () => {
+    // Mock the backend download failing
+    vi.mocked(invoke).mockRejectedValueOnce(
+      externalMediaError('httpStatus', 'HTTP error: 403 Forbidden', 'https://restricted.example.com/image.jpg')
+    )
 
     try {
       await downloadExternalImage('https://restricted.example.com/image.jpg')
@@ -92,7 +86,9 @@ describe('CORS Error Handling for External Images', () => {
 
   test('should handle network errors gracefully', async () => {
     // Mock network failure
-    vi.mocked(fetch).mockRejectedValue(new Error('Network error'))
+    vi.mocked(invoke).mockRejectedValueOnce(
+      externalMediaError('network', 'Failed to fetch https://example.com/image.jpg: connection reset', 'https://example.com/image.jpg')
+    )
 
     try {
       await downloadExternalImage('https://example.com/image.jpg')
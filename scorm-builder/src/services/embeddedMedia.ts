@@ -0,0 +1,62 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import type { LocalizedMessage } from './rustScormGenerator'
+
+/** A media item whose embedded data moved, or would move on a dry run */
+export interface EmbeddedMediaChange {
+  mediaId: string
+  mediaType: 'image' | 'audio' | 'caption'
+  /** Decoded size in bytes */
+  size: number
+  relativePath: string
+}
+
+/** An item left embedded, and why */
+export interface EmbeddedMediaError {
+  mediaId: string
+  error: string
+}
+
+export interface EmbeddedMediaReport {
+  dryRun: boolean
+  changes: EmbeddedMediaChange[]
+  errors: EmbeddedMediaError[]
+  /** Embedded items the migration doesn't move, such as videos */
+  skipped: EmbeddedMediaError[]
+  projectSizeBefore: number
+  /** Expected size on a dry run */
+  projectSizeAfter: number
+}
+
+export interface EmbeddedMediaProgress extends LocalizedMessage {
+  progress: number
+  current?: number
+  total?: number
+  /** On the final event */
+  dryRun?: boolean
+  failed?: number
+  skipped?: number
+}
+
+/**
+ * Move base64 media embedded in a legacy project file into the media folder,
+ * shrinking the project file. Call with `dryRun` first to show what would move
+ * and the expected size; items that fail stay embedded.
+ */
+export async function migrateEmbeddedMedia(
+  projectId: string,
+  dryRun = false,
+  onProgress?: (progress: EmbeddedMediaProgress) => void
+): Promise<EmbeddedMediaReport> {
+  let unlisten: UnlistenFn | undefined
+  try {
+    if (onProgress) {
+      unlisten = await listen<EmbeddedMediaProgress>('embedded-media-migration-progress', (event) => {
+        onProgress(event.payload)
+      })
+    }
+    return await invoke<EmbeddedMediaReport>('migrate_embedded_media', { projectId, dryRun })
+  } finally {
+    unlisten?.()
+  }
+}
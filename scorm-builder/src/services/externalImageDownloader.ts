@@ -1,10 +1,11 @@
 import { invoke } from '@tauri-apps/api/core'
+import type { MediaAttribution } from '@/types/ipc'
 
 /*
  * EXPECTED BROWSER WARNINGS:
  * - "Tracking Prevention: Connection was blocked" - Normal browser security feature
  * - "CORS policy: No 'Access-Control-Allow-Origin' header" - Expected for external domains
- * - These warnings do not affect functionality as we have fallback handling
+ * - These come from the browser methods of force download mode; normal downloads go through the backend
  * - External images like www.aga.org may trigger these warnings but are handled gracefully
  */
 
@@ -13,6 +14,66 @@ interface DownloadImageResponse {
   content_type: string
 }
 
+export type ExternalMediaErrorKind =
+  | 'invalidUrl'
+  | 'schemeNotAllowed'
+  | 'addressNotAllowed'
+  | 'dnsFailed'
+  | 'tooManyRedirects'
+  | 'timeout'
+  | 'network'
+  | 'httpStatus'
+  | 'tooLarge'
+  | 'typeNotAllowed'
+  | 'quotaExceeded'
+
+export interface ExternalMediaError {
+  message: string
+  errorType: 'externalMedia'
+  kind: ExternalMediaErrorKind
+  url: string
+  status?: number
+}
+
+/**
+ * Kinds of error for URLs the backend refuses to fetch, as opposed to failing to.
+ * None of them is retried in the browser: the rule applies however the file is fetched.
+ */
+const REFUSED_KINDS: ExternalMediaErrorKind[] = [
+  'invalidUrl',
+  'schemeNotAllowed',
+  'addressNotAllowed',
+  'tooLarge',
+  'typeNotAllowed',
+  'quotaExceeded'
+]
+
+/** Kinds of error caused by the user's connection rather than the URL */
+const NETWORK_KINDS: ExternalMediaErrorKind[] = ['network', 'timeout', 'dnsFailed']
+
+/**
+ * Why download_external_media failed, or null when the error has another cause
+ */
+export function parseExternalMediaError(error: unknown): ExternalMediaError | null {
+  const text = error instanceof Error ? error.message : String(error)
+  if (!text.includes('"errorType":"externalMedia"')) return null
+  try {
+    return JSON.parse(text) as ExternalMediaError
+  } catch {
+    return null
+  }
+}
+
+/**
+ * Whether the backend refused media because no license was given for it.
+ * The browser fallbacks are not tried then: the license is required however
+ * the file is fetched.
+ */
+export function isLicenseRequiredError(error: unknown): boolean {
+  const text = error instanceof Error ? error.message : String(error)
+  return text.includes('A license is required')
+}
+
 /**
  * Sleep helper for retry delays
  */
@@ -21,132 +82,54 @@ function sleep(ms: number): Promise<void> {
 }
 
 /**
- * Downloads an external image through Tauri to avoid CORS issues
+ * Turns a download_external_media response into a blob
+ */
+function responseToBlob(response: DownloadImageResponse): Blob {
+  const byteCharacters = atob(response.base64_data)
+  const byteArray = new Uint8Array(byteCharacters.length)
+  for (let i = 0; i < byteCharacters.length; i++) {
+    byteArray[i] = byteCharacters.charCodeAt(i)
+  }
+  return new Blob([byteArray], { type: response.content_type })
+}
+
+/**
+ * User-facing error for a failed backend download
+ */
+function downloadError(error: unknown): Error {
+  if (isLicenseRequiredError(error)) {
+    return error instanceof Error ? error : new Error(String(error))
+  }
+  const mediaError = parseExternalMediaError(error)
+  if (mediaError && REFUSED_KINDS.includes(mediaError.kind)) {
+    return new Error(mediaError.message)
+  }
+  if (mediaError && NETWORK_KINDS.includes(mediaError.kind)) {
+    return new Error('Network connection issue prevented downloading the image. Please check your internet connection and try again.')
+  }
+  return new Error('Unable to download this external image. Please save the image to your computer and upload it locally instead.')
+}
+
+/**
+ * Downloads an external image through the backend, which checks the address,
+ * size and content type. Nothing is fetched from the browser: a URL the
+ * backend refuses or fails to fetch is not retried directly or via proxies.
  * @param url The URL of the image to download
+ * @param attribution Author and license of the image; a license is required
  * @returns A blob containing the downloaded image
  */
-export async function downloadExternalImage(url: string): Promise<Blob> {
+export async function downloadExternalImage(url: string, attribution?: MediaAttribution): Promise<Blob> {
+  console.log('[ExternalImageDownloader] Downloading image:', url)
+  let response: DownloadImageResponse
   try {
-    console.log('[ExternalImageDownloader] Downloading image:', url)
-    
-    // First, try to use Tauri command if available
-    try {
-      const response = await invoke<DownloadImageResponse>('download_image', { url })
-      
-      // Convert base64 to blob
-      const byteCharacters = atob(response.base64_data)
-      const byteNumbers = new Array(byteCharacters.length)
-      for (let i = 0; i < byteCharacters.length; i++) {
-        byteNumbers[i] = byteCharacters.charCodeAt(i)
-      }
-      const byteArray = new Uint8Array(byteNumbers)
-      const blob = new Blob([byteArray], { type: response.content_type })
-      
-      console.log('[ExternalImageDownloader] Successfully downloaded image via Tauri, size:', blob.size)
-      return blob
-    } catch (tauriError) {
-      console.warn('[ExternalImageDownloader] Tauri command not available, trying alternative methods')
-      
-      // Fallback: Try to fetch directly (might work for some CORS-enabled sites)
-      try {
-        const response = await fetch(url)
-        if (!response.ok) {
-          throw new Error(`HTTP error! status: ${response.status}`)
-        }
-        const blob = await response.blob()
-        console.log('[ExternalImageDownloader] Successfully downloaded image via direct fetch, size:', blob.size)
-        return blob
-      } catch (fetchError) {
-        // If direct fetch fails, try using CORS proxies with retry logic
-        console.warn('[ExternalImageDownloader] Direct fetch failed, trying CORS proxies')
-        
-        // List of proxy services to try (in order of preference)
-        const proxyServices = [
-          (url: string) => `https://corsproxy.io/?${encodeURIComponent(url)}`,
-          (url: string) => `https://api.allorigins.win/raw?url=${encodeURIComponent(url)}`,
-          (url: string) => `https://proxy.cors.sh/${url}`
-        ]
-        
-        let lastError: Error | null = null
-        
-        for (let i = 0; i < proxyServices.length; i++) {
-          const proxyUrl = proxyServices[i](url)
-          console.log(`[ExternalImageDownloader] Trying proxy service ${i + 1}/${proxyServices.length}`)
-          
-          // Retry logic with exponential backoff for rate limiting (429 errors)
-          let retries = 3
-          let delay = 1000 // Start with 1 second delay
-          
-          while (retries > 0) {
-            try {
-              const proxyResponse = await fetch(proxyUrl, {
-                signal: AbortSignal.timeout(15000) // 15 second timeout
-              })
-              
-              if (proxyResponse.status === 429) {
-                // Rate limited - wait and retry with exponential backoff
-                console.warn(`[ExternalImageDownloader] Rate limited (429), retrying in ${delay}ms...`)
-                await sleep(delay)
-                delay *= 2 // Exponential backoff
-                retries--
-                continue
-              }
-              
-              if (!proxyResponse.ok) {
-                throw new Error(`Proxy fetch failed! status: ${proxyResponse.status}`)
-              }
-              
-              const blob = await proxyResponse.blob()
-              
-              // Validate that we actually got an image
-              if (blob.size === 0 || !blob.type.startsWith('image/')) {
-                throw new Error(`Invalid image data received: size=${blob.size}, type=${blob.type}`)
-              }
-              
-              console.log(`[ExternalImageDownloader] Successfully downloaded image via proxy ${i + 1}, size:`, blob.size)
-              return blob
-            } catch (error) {
-              lastError = error as Error
-              retries--
-              
-              if (retries > 0 && (error as any).name !== 'AbortError') {
-                console.warn(`[ExternalImageDownloader] Proxy attempt failed, ${retries} retries left:`, error)
-                await sleep(delay)
-                delay *= 2
-              } else {
-                // Move to next proxy service
-                break
-              }
-            }
-          }
-        }
-        
-        // All proxy attempts failed - provide user-friendly error message
-        if (lastError?.message?.includes('CORS') || lastError?.message?.includes('blocked')) {
-          throw new Error('Unable to download this external image due to security restrictions. Please save the image to your computer and upload it locally instead.')
-        } else if (lastError?.message?.includes('Network') || lastError?.message?.includes('network')) {
-          throw new Error('Network connection issue prevented downloading the image. Please check your internet connection and try again.')
-        } else {
-          throw new Error('Unable to download this external image. Please save the image to your computer and upload it locally instead.')
-        }
-      }
-    }
+    response = await invoke<DownloadImageResponse>('download_external_media', { url, attribution })
   } catch (error) {
     console.error('[ExternalImageDownloader] Failed to download image:', error)
-    
-    // Provide user-friendly error messages instead of technical details
-    const errorMessage = (error as Error).message
-    if (errorMessage.includes('CORS') || errorMessage.includes('blocked')) {
-      throw new Error('Unable to download this external image due to security restrictions. Please save the image to your computer and upload it locally instead.')
-    } else if (errorMessage.includes('Network') || errorMessage.includes('network')) {
-      throw new Error('Network connection issue prevented downloading the image. Please check your internet connection and try again.')
-    } else if (errorMessage.includes('Unable to download')) {
-      // Already user-friendly, re-throw as-is
-      throw error
-    } else {
-      throw new Error('Unable to download this external image. Please save the image to your computer and upload it locally instead.')
-    }
+    throw downloadError(error)
   }
+  const blob = responseToBlob(response)
+  console.log('[ExternalImageDownloader] Successfully downloaded image, size:', blob.size)
+  return blob
 }
 
 /**
@@ -186,31 +169,32 @@ export function isKnownCorsRestrictedDomain(url: string): boolean {
 }
 
 /**
- * Force download mode with aggressive insecure methods for VPN/corporate networks
+ * Force download mode for VPN/corporate networks: the backend fetch first, then browser workarounds
  * @param url The URL of the image to download
+ * @param attribution Author and license of the image; a license is required
  * @returns A blob containing the downloaded image
  */
-export async function forceDownloadExternalImage(url: string): Promise<Blob> {
+export async function forceDownloadExternalImage(url: string, attribution?: MediaAttribution): Promise<Blob> {
   console.log('[ExternalImageDownloader] Force download mode - using aggressive insecure methods for:', url)
   
-  // Method 1: Try the new unsafe Tauri command first (bypasses all security)
+  // Method 1: Fetch through the backend, which checks the address, size and content type
   try {
-    console.log('[ExternalImageDownloader] Trying unsafe Tauri command...')
-    const response = await invoke<DownloadImageResponse>('unsafe_download_image', { url })
-    
-    // Convert base64 to blob
-    const byteCharacters = atob(response.base64_data)
-    const byteNumbers = new Array(byteCharacters.length)
-    for (let i = 0; i < byteCharacters.length; i++) {
-      byteNumbers[i] = byteCharacters.charCodeAt(i)
-    }
-    const byteArray = new Uint8Array(byteNumbers)
-    const blob = new Blob([byteArray], { type: response.content_type })
+    console.log('[ExternalImageDownloader] Trying download_external_media...')
+    const response = await invoke<DownloadImageResponse>('download_external_media', { url, attribution })
+    const blob = responseToBlob(response)
     
-    console.log('[ExternalImageDownloader] Unsafe Tauri download successful, size:', blob.size)
+    console.log('[ExternalImageDownloader] Backend download successful, size:', blob.size)
     return blob
   } catch (tauriError) {
-    console.warn('[ExternalImageDownloader] Unsafe Tauri command failed, trying browser methods:', tauriError)
+    // Refused URLs (local addresses, non-media content, the daily limit reached) are not retried in the browser
+    const refused = parseExternalMediaError(tauriError)
+    if (refused && REFUSED_KINDS.includes(refused.kind)) {
+      throw new Error(refused.message)
+    }
+    if (isLicenseRequiredError(tauriError)) {
+      throw tauriError instanceof Error ? tauriError : new Error(String(tauriError))
+    }
+    console.warn('[ExternalImageDownloader] Backend download failed, trying browser methods:', tauriError)
   }
   
   // Method 2: Ultra-aggressive browser fetch with disabled security
@@ -513,10 +497,10 @@ async function downloadWithDataUrlConversion(url: string): Promise<Blob> {
 /**
  * Downloads an image if it's external, otherwise returns null
  */
-export async function downloadIfExternal(url: string): Promise<Blob | null> {
+export async function downloadIfExternal(url: string, attribution?: MediaAttribution): Promise<Blob | null> {
   if (!isExternalUrl(url)) {
     return null
   }
   
-  return downloadExternalImage(url)
+  return downloadExternalImage(url, attribution)
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { LinkCheck, LinkCheckReport, LinkStatus } from '@/types/ipc'
+
+// What a link's server said: `redirected` links work but should be updated to
+// `final_url`; `blocked` servers refused the check (401, 403, 429), or the host
+// is local or private and wasn't contacted, so a browser may still get through.
+export type { LinkCheck, LinkCheckReport, LinkStatus }
+
+/** Check every http(s) link in a project's pages, media and questions */
+export async function checkExternalLinks(projectId: string): Promise<LinkCheckReport> {
+  return invoke<LinkCheckReport>('check_external_links', { projectId })
+}
@@ -0,0 +1,58 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import type { LocalizedMessage } from './rustScormGenerator'
+import type { MediaAttribution } from './stockImages'
+
+/** A remote file to store on a page */
+export interface MediaDownloadItem {
+  url: string
+  pageId: string
+  type: 'image' | 'audio' | 'video' | 'caption'
+  title?: string
+  /** Credit and license from the search result it came from */
+  attribution?: MediaAttribution
+}
+
+export interface MediaDownloadOutcome {
+  url: string
+  pageId: string
+  /** ID the file was stored under; absent when it failed */
+  mediaId?: string
+  mimeType?: string
+  size: number
+  attempts: number
+  error?: string
+}
+
+export interface MediaDownloadProgress extends LocalizedMessage {
+  progress: number
+  current?: number
+  total?: number
+  /** The item that just finished */
+  outcome?: MediaDownloadOutcome
+  /** Items that failed, on the final event */
+  failed?: number
+}
+
+/**
+ * Download remote files straight into a project's media, a few at a time with
+ * retries, instead of one download_external_media call and one store per URL.
+ * Each item succeeds or fails on its own.
+ */
+export async function downloadMediaBatch(
+  projectId: string,
+  items: MediaDownloadItem[],
+  onProgress?: (progress: MediaDownloadProgress) => void
+): Promise<MediaDownloadOutcome[]> {
+  let unlisten: UnlistenFn | undefined
+  try {
+    if (onProgress) {
+      unlisten = await listen<MediaDownloadProgress>('media-download-progress', (event) => {
+        onProgress(event.payload)
+      })
+    }
+    return await invoke<MediaDownloadOutcome[]>('download_media_batch', { projectId, items })
+  } finally {
+    unlisten?.()
+  }
+}
@@ -0,0 +1,51 @@
+import { invoke } from '@tauri-apps/api/core'
+
+/** A metadata file whose page_id doesn't match its media ID */
+export interface PageIdChange {
+  file: string
+  media_id: string
+  /** Empty when the file has no page_id at all */
+  current_page_id: string
+  expected_page_id: string
+}
+
+export interface PageIdFileError {
+  file: string
+  error: string
+}
+
+export interface PageIdValidationReport {
+  valid_files: number
+  invalid_files: number
+  changes: PageIdChange[]
+  errors: PageIdFileError[]
+  issues: string[]
+  message: string
+}
+
+export interface PageIdMigrationReport {
+  dry_run: boolean
+  /** Files rewritten, or that would be rewritten on a dry run */
+  changes: PageIdChange[]
+  errors: PageIdFileError[]
+  /** Where the changed files were copied before being rewritten */
+  backup_dir?: string
+  fixes_made: number
+  message: string
+}
+
+export async function validateMediaPageIds(projectId: string): Promise<PageIdValidationReport> {
+  return invoke<PageIdValidationReport>('validate_media_page_ids', { projectId })
+}
+
+/**
+ * Fix media page_id assignments. Call with `dryRun` first to show the per-file changes,
+ * then again without it once the user confirms; the files being changed are backed up
+ * before anything is written.
+ */
+export async function migrateMediaPageIds(
+  projectId: string,
+  dryRun = false
+): Promise<PageIdMigrationReport> {
+  return invoke<PageIdMigrationReport>('migrate_media_page_ids', { projectId, dryRun })
+}
@@ -0,0 +1,50 @@
+import { invoke } from '@tauri-apps/api/core'
+
+/**
+ * Size, type and scanner rules the backend applies to media before storing it,
+ * both when media is added and when a project is imported.
+ */
+export interface MediaPolicy {
+  /** Largest accepted file per media type (image, audio, ...), in bytes */
+  max_size_bytes: Record<string, number>
+  /** Accepted MIME types; `image/*` accepts every image type, empty accepts anything */
+  allowed_mime_types: string[]
+  /** Scanner command and arguments; the file path is appended */
+  scanner_command?: string[]
+}
+
+export type MediaPolicyViolationKind = 'tooLarge' | 'typeNotAllowed' | 'scannerRejected' | 'scannerFailed'
+
+export interface MediaPolicyViolation {
+  file: string
+  kind: MediaPolicyViolationKind
+  message: string
+}
+
+export interface MediaPolicyError {
+  message: string
+  errorType: 'mediaPolicy'
+  violations: MediaPolicyViolation[]
+}
+
+export async function getMediaPolicy(): Promise<MediaPolicy> {
+  return invoke<MediaPolicy>('get_media_policy')
+}
+
+export async function setMediaPolicy(policy: MediaPolicy): Promise<void> {
+  await invoke('set_media_policy', { policy })
+}
+
+/**
+ * The policy violations behind a failed store_media or project import,
+ * or null when the error has another cause
+ */
+export function parseMediaPolicyError(error: unknown): MediaPolicyError | null {
+  const text = error instanceof Error ? error.message : String(error)
+  if (!text.includes('"errorType":"mediaPolicy"')) return null
+  try {
+    return JSON.parse(text) as MediaPolicyError
+  } catch {
+    return null
+  }
+}
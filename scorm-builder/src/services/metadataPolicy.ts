@@ -0,0 +1,26 @@
+import { invoke } from '@tauri-apps/api/core'
+import type {
+  MetadataAuditEntry,
+  MetadataField,
+  MetadataPolicy,
+  ViolationAction as MetadataViolationAction
+} from '@/types/ipc'
+
+// Which metadata fields each media type may carry, applied when media is
+// stored, edited in bulk and imported. By default images, audio and captions
+// lose any YouTube fields. `youtube_source` stands for `source: "youtube"`, and
+// the `*` entry of `forbidden_fields` covers types without their own.
+export type { MetadataAuditEntry, MetadataField, MetadataPolicy, MetadataViolationAction }
+
+export async function getMetadataPolicy(): Promise<MetadataPolicy> {
+  return invoke<MetadataPolicy>('get_metadata_policy')
+}
+
+export async function setMetadataPolicy(policy: MetadataPolicy): Promise<void> {
+  await invoke('set_metadata_policy', { policy })
+}
+
+/** What the policy changed in a project, newest first */
+export async function getMetadataAudit(projectId: string, limit?: number): Promise<MetadataAuditEntry[]> {
+  return invoke<MetadataAuditEntry[]>('get_metadata_audit', { projectId, limit })
+}
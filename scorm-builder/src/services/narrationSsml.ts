@@ -0,0 +1,31 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { LexiconEntry, PageSsml } from '@/types/ipc'
+
+// How a term should be spoken: an `alias` read in its place, or an IPA
+// `phoneme`. PageSsml holds a page's `<speak>` document with the lexicon terms
+// wrapped in `<sub>` or `<phoneme>`.
+export type { LexiconEntry, PageSsml }
+
+export async function listLexicon(projectId: string): Promise<LexiconEntry[]> {
+  return invoke<LexiconEntry[]>('list_lexicon', { projectId })
+}
+
+/** Add a term, replacing any entry for it, and return the updated lexicon */
+export async function addLexiconEntry(
+  projectId: string,
+  entry: LexiconEntry
+): Promise<LexiconEntry[]> {
+  return invoke<LexiconEntry[]>('add_lexicon_entry', { projectId, entry })
+}
+
+export async function removeLexiconEntry(projectId: string, term: string): Promise<boolean> {
+  return invoke<boolean>('remove_lexicon_entry', { projectId, term })
+}
+
+/** SSML of each page's narration for the TTS pipeline, or of one page */
+export async function generateNarrationSsml(
+  projectId: string,
+  pageId?: string
+): Promise<PageSsml[]> {
+  return invoke<PageSsml[]>('generate_narration_ssml', { projectId, pageId })
+}
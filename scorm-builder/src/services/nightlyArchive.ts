@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core'
+
+/**
+ * Nightly archive of the whole projects directory, kept in the app settings.
+ * Complements the per-project backups made on save.
+ */
+export interface ArchiveSchedule {
+  enabled: boolean
+  /** Folder the archives are written to; nothing is archived while unset */
+  destination?: string
+  /** Local hour (0-23) from which the night's archive is made */
+  hour: number
+  /** Archives kept; older ones are deleted after each run */
+  keep: number
+}
+
+export interface BackupStatus {
+  schedule: ArchiveSchedule
+  lastRun: string | null
+  lastArchive: string | null
+  lastSize: number | null
+  lastError: string | null
+  nextRun: string | null
+  /** Archives currently in the destination, newest first */
+  archives: string[]
+}
+
+export async function getBackupStatus(): Promise<BackupStatus> {
+  return invoke<BackupStatus>('get_backup_status')
+}
+
+export async function setArchiveSchedule(schedule: ArchiveSchedule): Promise<void> {
+  await invoke('set_archive_schedule', { schedule })
+}
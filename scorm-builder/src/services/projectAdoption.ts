@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/core'
+
+/** A project file copied in by hand, now laid out as `Name_{id}.scormproj` + `{id}/media/` */
+export interface AdoptedProject {
+  sourcePath: string
+  projectPath: string
+  projectId: string
+  /** ID the file had; differs from `projectId` when another project already used it */
+  previousId: string
+  /** Media files copied into the project's media folder */
+  mediaFiles: number
+  /** Saved paths and URLs pointed at the new ID and workspace */
+  rewrittenReferences: number
+}
+
+export interface AdoptionReport {
+  adopted: AdoptedProject[]
+  /** Project files already laid out the way the workspace expects */
+  skipped: string[]
+  failed: { path: string; error: string }[]
+}
+
+/**
+ * Bring a `.scormproj` file, or every one in a folder, copied between machines
+ * by hand into the workspace. Source files are left in place.
+ */
+export async function adoptExternalProject(path: string): Promise<AdoptionReport> {
+  return invoke<AdoptionReport>('adopt_external_project', { path })
+}
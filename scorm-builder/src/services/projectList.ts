@@ -0,0 +1,51 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import type { ProjectFilter, ProjectStatus } from './projectTags'
+import type { LocalTimes } from './timeDisplay'
+
+/** A project's header as listed, with its tags and status from the index */
+export interface ListedProject {
+  id: string
+  name: string
+  created: string
+  last_modified: string
+  path?: string
+  tags?: string[]
+  status?: ProjectStatus
+  /** `created` and `last_modified` in local time */
+  local_times?: LocalTimes
+}
+
+/** One project file read during a refresh */
+export interface ProjectListUpdate {
+  path: string
+  /** null when the file couldn't be read; see `error` */
+  project: ListedProject | null
+  error: string | null
+  /** Whether the header came from the cache of unchanged files */
+  cached: boolean
+  done: number
+  total: number
+}
+
+/**
+ * List the projects, calling `onUpdate` for each one as it is read so a long
+ * list on a slow drive can fill in as it arrives. Unchanged files come from
+ * the cache first. Resolves with the full list, newest first.
+ */
+export async function refreshProjectList(
+  filter?: ProjectFilter,
+  onUpdate?: (update: ProjectListUpdate) => void
+): Promise<ListedProject[]> {
+  let unlisten: UnlistenFn | undefined
+  try {
+    if (onUpdate) {
+      unlisten = await listen<ProjectListUpdate>('project-list-update', (event) => {
+        onUpdate(event.payload)
+      })
+    }
+    return await invoke<ListedProject[]>('refresh_project_list', { filter })
+  } finally {
+    unlisten?.()
+  }
+}
@@ -0,0 +1,12 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { RunningOperation } from '@/types/ipc'
+
+// A SCORM build, ZIP export or import holding a project. While one runs,
+// another request for the project fails with an `error.operation.*_running`
+// message whose `id` param is its `operationId`.
+export type { RunningOperation }
+
+/** The build or export running on a project, or null when it is free */
+export async function getProjectOperation(projectId: string): Promise<RunningOperation | null> {
+  return invoke<RunningOperation | null>('get_project_operation', { projectId })
+}
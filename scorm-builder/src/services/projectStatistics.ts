@@ -0,0 +1,31 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { StorageReport } from './storageBudget'
+
+/**
+ * Estimated effort of a page: reading time at 200 words a minute or the
+ * narration and video clips, whichever is longer, plus 30s per question.
+ */
+export interface PageStatistics {
+  page_id: string
+  title: string
+  words: number
+  questions: number
+  media_seconds: number
+  estimated_seconds: number
+  /** Percentage of the course's estimated time; the generated progress indicator advances by it */
+  weight: number
+}
+
+export interface ProjectStatistics {
+  pages: PageStatistics[]
+  total_words: number
+  total_questions: number
+  media_seconds: number
+  estimated_seconds: number
+  /** Media size against the project's storage budget */
+  storage?: StorageReport
+}
+
+export async function getProjectStatistics(projectId: string): Promise<ProjectStatistics> {
+  return invoke<ProjectStatistics>('get_project_statistics', { projectId })
+}
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { ProjectStatus } from '@/types/ipc'
+
+export type { ProjectStatus }
+
+/** Tags and status of one project, kept in the projects index */
+export interface ProjectTags {
+  tags: string[]
+  status: ProjectStatus
+}
+
+/** Narrows `list_projects`; unset fields match every project. Tags match ignoring case. */
+export interface ProjectFilter {
+  tag?: string
+  status?: ProjectStatus
+}
+
+export async function getProjectTags(projectId: string): Promise<ProjectTags> {
+  return invoke<ProjectTags>('get_project_tags', { projectId })
+}
+
+/** Replace a project's tags; blanks and repeats are dropped */
+export async function setProjectTags(projectId: string, tags: string[]): Promise<ProjectTags> {
+  return invoke<ProjectTags>('set_project_tags', { projectId, tags })
+}
+
+export async function setProjectStatus(projectId: string, status: ProjectStatus): Promise<ProjectTags> {
+  return invoke<ProjectTags>('set_project_status', { projectId, status })
+}
+
+/** Every tag used on any project, sorted */
+export async function listProjectTags(): Promise<string[]> {
+  return invoke<string[]>('list_project_tags')
+}
@@ -0,0 +1,94 @@
+import { invoke } from '@tauri-apps/api/core'
+
+export type PublishDestination =
+  | { kind: 'local'; path: string }
+  /** A UNC path such as \\server\courses */
+  | { kind: 'share'; path: string }
+  | {
+      kind: 'sftp'
+      host: string
+      port?: number
+      username: string
+      remote_dir: string
+      /** SHA256:... as printed by ssh-keygen -lf; publishing without it reports the server's */
+      host_fingerprint?: string
+    }
+
+/** Either a password or a private key file */
+export interface SftpCredential {
+  password?: string
+  private_key_path?: string
+  passphrase?: string
+}
+
+export interface PublishReceipt {
+  destination: string
+  path: string
+  size: number
+  /** Hex SHA-256 of the package, as read back from the destination */
+  sha256: string
+  attempts: number
+  /** The publish webhook call, when one is configured */
+  webhook?: WebhookDelivery
+}
+
+export interface WebhookDelivery {
+  url: string
+  delivered: boolean
+  attempts: number
+  /** HTTP status of the last attempt, when the server answered */
+  status: number | null
+  error: string | null
+}
+
+export interface WebhookAuditEntry extends WebhookDelivery {
+  timestamp: string
+  project_id: string
+  sha256: string
+  destination: string
+}
+
+/** Copy a built package to the LMS content server, verified by checksum */
+export async function publishPackage(
+  projectId: string,
+  packagePath: string,
+  destination: PublishDestination
+): Promise<PublishReceipt> {
+  return invoke<PublishReceipt>('publish_package', { projectId, packagePath, destination })
+}
+
+/** Saved encrypted; secrets are never read back */
+export async function saveSftpCredentials(
+  host: string,
+  username: string,
+  credential: SftpCredential,
+  port?: number
+): Promise<void> {
+  return invoke('save_sftp_credentials', { host, port, username, credential })
+}
+
+export async function deleteSftpCredentials(host: string, username: string, port?: number): Promise<void> {
+  return invoke('delete_sftp_credentials', { host, port, username })
+}
+
+/**
+ * Call `url` after every successful publish. The request body is signed with
+ * HMAC-SHA256 of `secret`, sent in the X-Scorm-Builder-Signature header.
+ */
+export async function savePublishWebhook(url: string, secret: string): Promise<void> {
+  return invoke('save_publish_webhook', { url, secret })
+}
+
+/** The configured webhook URL; the secret is never read back */
+export async function getPublishWebhook(): Promise<{ url: string } | null> {
+  return invoke<{ url: string } | null>('get_publish_webhook')
+}
+
+export async function deletePublishWebhook(): Promise<void> {
+  return invoke('delete_publish_webhook')
+}
+
+/** Past webhook deliveries, newest first */
+export async function getPublishWebhookLog(limit?: number): Promise<WebhookAuditEntry[]> {
+  return invoke<WebhookAuditEntry[]>('get_publish_webhook_log', { limit })
+}
@@ -100,7 +100,8 @@ describe('convertToRustFormat course settings impact', () => {
       sessionTimeout: 10,
       minimumTimeSpent: 15,
       keyboardNavigation: false,
-      printable: true
+      printable: true,
+      highContrast: true
     }
 
     const { courseData } = await convertToRustFormat(baseCourseContent as any, 'project-123', customSettings)
@@ -122,7 +123,8 @@ describe('convertToRustFormat course settings impact', () => {
       session_timeout: 10,
       minimum_time_spent: 15,
       keyboard_navigation: false,
-      printable: true
+      printable: true,
+      high_contrast: true
     })
   })
 
@@ -146,7 +148,8 @@ describe('convertToRustFormat course settings impact', () => {
       session_timeout: 30,
       minimum_time_spent: 0,
       keyboard_navigation: true,
-      printable: false
+      printable: false,
+      high_contrast: false
     })
   })
 })
@@ -154,4 +154,25 @@ describe('Media Collection', () => {
 
     console.log('[TEST] Collected media IDs:', mediaIds.filter(id => id.includes('1')))
   })
-})
\ No newline at end of file
+
+  it('should collect feedback media of question bank questions', () => {
+    const courseContent = {
+      topics: [{ knowledgeCheck: { questions: [{ bankRef: 'q-ladder' }] } }],
+      assessment: { questions: [{ bankRef: 'q-ladder' }] },
+      questionBank: [
+        {
+          id: 'q-ladder',
+          feedback: {
+            correctMedia: { mediaId: 'image-bank-correct', type: 'image' },
+            incorrectMedia: { mediaId: 'audio-bank-incorrect', type: 'audio' }
+          }
+        }
+      ]
+    }
+
+    const mediaIds = collectAllMediaIds(courseContent)
+
+    expect(mediaIds).toContain('image-bank-correct')
+    expect(mediaIds).toContain('audio-bank-incorrect')
+  })
+})
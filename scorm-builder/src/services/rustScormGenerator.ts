@@ -1,13 +1,16 @@
 import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
 import type { CourseContent, EnhancedCourseContent } from '../types/scorm'
-import type { CourseSettings } from '../components/CourseSettingsWizard'
+import type { FeedbackMedia } from '../types/aiPrompt'
+import type { AnalyticsSettings, AssessmentTimerSettings, AudioPolicySettings, CourseFeatureSettings, CourseSettings, FeedbackSettings, NavigationMenuSettings, WelcomeStartSettings } from '../components/CourseSettingsWizard'
 import { downloadIfExternal, isExternalUrl } from './externalImageDownloader'
 import { debugLogger } from '../utils/ultraSimpleLogger'
 import { z } from 'zod'
 import { PAGE_LEARNING_OBJECTIVES, CONTENT_LEARNING_OBJECTIVES } from '../constants/media'
 import { devLog, devTime, devBatchLog, devMemoryLog, criticalLog, criticalWarn } from '../utils/logging'
 import { MEDIA_FETCH_TIMEOUT, BATCH_GENERATION_TIMEOUT } from '../constants/timeouts'
+import type { LocalTimes } from './timeDisplay'
+import type { NavigationMenu } from '@/types/ipc'
 
 // ============================================================================
 // REGRESSION DETECTION GUARDS
@@ -237,9 +240,18 @@ const CourseSettingsSchema = z.object({
   timeLimit: z.number().min(0).max(86400).optional(), // Max 24 hours
   sessionTimeout: z.number().min(5).max(7200).optional(), // 5 min to 2 hours
   minimumTimeSpent: z.number().min(0).max(7200).optional(),
+  assessmentTimer: z.object({
+    overallMinutes: z.number().min(0).max(1440),
+    perQuestionSeconds: z.number().min(0).max(3600),
+    warningSeconds: z.number().min(0).max(3600).optional(),
+    autoSubmit: z.boolean()
+  }).optional(),
   showProgress: z.boolean().optional(),
   showOutline: z.boolean().optional(),
   enableCsp: z.boolean().optional(),
+  learnerNotes: z.boolean().optional(),
+  runtimeAnalytics: z.boolean().optional(),
+  courseSearch: z.boolean().optional(),
   allowSkipping: z.boolean().optional(),
   randomizeQuestions: z.boolean().optional(),
   maxAttempts: z.number().min(1).max(10).optional()
@@ -297,6 +309,11 @@ function validateCourseContent(courseContent: any, projectId: string): void {
       throw new Error(`Course content validation failed: ${errors}`)
     }
 
+    const danglingBankRefs = findDanglingBankRefs(courseContent)
+    if (danglingBankRefs.length > 0) {
+      throw new Error(`Course content validation failed: questions refer to questions missing from the question bank (${danglingBankRefs.join(', ')})`)
+    }
+
     debugLogger.info('SCORM_VALIDATION', 'Course content validation passed', {
       projectId,
       contentType: isEnhanced ? 'enhanced' : 'standard',
@@ -711,6 +728,182 @@ function constrainNumber(value: any, min: number, max: number, fallback: number)
   return Math.min(Math.max(num, min), max)
 }
 
+/**
+ * Convert the assessment timer settings, leaving them out when neither countdown is set
+ */
+function toAssessmentTimer(timer: AssessmentTimerSettings | undefined) {
+  if (!timer || (!(timer.overallMinutes > 0) && !(timer.perQuestionSeconds > 0))) return undefined
+  return {
+    overall_minutes: Math.round(constrainNumber(timer.overallMinutes, 0, 1440, 0)),
+    per_question_seconds: Math.round(constrainNumber(timer.perQuestionSeconds, 0, 3600, 0)),
+    warning_seconds: timer.warningSeconds === undefined ? undefined : Math.round(constrainNumber(timer.warningSeconds, 0, 3600, 60)),
+    auto_submit: timer.autoSubmit
+  }
+}
+
+/**
+ * Convert the problem report settings, leaving them out until a webhook or email is entered
+ */
+function toFeedback(feedback: FeedbackSettings | undefined) {
+  const webhookUrl = feedback?.webhookUrl?.trim()
+  const email = feedback?.email?.trim()
+  if (!webhookUrl && !email) return undefined
+  return {
+    webhook_url: webhookUrl || undefined,
+    email: email || undefined
+  }
+}
+
+/**
+ * Convert the manifest keyword settings, leaving them out while the defaults apply
+ */
+function toKeywords(settings: CourseSettings | undefined) {
+  const keywords = (settings?.keywords ?? []).map(keyword => keyword.trim()).filter(Boolean)
+  if (keywords.length === 0 && settings?.deriveKeywords !== false) return undefined
+  return {
+    keywords,
+    derive: settings?.deriveKeywords
+  }
+}
+
+/**
+ * Convert the project's question bank. Questions elsewhere refer to these by
+ * id with `bankRef`, and the generator packages the bank's version.
+ */
+function toQuestionBank(questionBank: any): any[] | undefined {
+  if (!Array.isArray(questionBank) || questionBank.length === 0) return undefined
+  return questionBank.map((q: any) => {
+    const options = Array.isArray(q.options) ? q.options : undefined
+    const correctAnswer = typeof q.correctAnswer === 'number' && options
+      ? options[q.correctAnswer] ?? String(q.correctAnswer)
+      : String(q.correctAnswer ?? '')
+    return {
+      id: q.id,
+      type: q.type || 'multiple-choice',
+      text: q.type === 'fill-in-the-blank' ? q.blank || q.question : q.question || q.text,
+      options,
+      correct_answer: correctAnswer,
+      explanation: q.explanation || '',
+      correct_feedback: q.feedback?.correct || 'Correct!',
+      incorrect_feedback: q.feedback?.incorrect || 'Not quite. Try again!',
+      correct_feedback_media: toFeedbackMedia(q.feedback?.correctMedia),
+      incorrect_feedback_media: toFeedbackMedia(q.feedback?.incorrectMedia),
+    }
+  })
+}
+
+/**
+ * Questions whose bankRef names no question in the bank, as `page: ref`
+ */
+function findDanglingBankRefs(courseContent: any): string[] {
+  const bankIds = new Set(
+    (Array.isArray(courseContent?.questionBank) ? courseContent.questionBank : []).map((q: any) => q?.id)
+  )
+  const dangling: string[] = []
+  const check = (page: string, questions: any) => {
+    if (!Array.isArray(questions)) return
+    questions.forEach((q: any) => {
+      if (q?.bankRef && !bankIds.has(q.bankRef)) dangling.push(`${page}: ${q.bankRef}`)
+    })
+  }
+  for (const topic of Array.isArray(courseContent?.topics) ? courseContent.topics : []) {
+    check(topic?.id ?? 'topic', topic?.knowledgeCheck?.questions)
+  }
+  check('assessment', courseContent?.assessment?.questions)
+  return dangling
+}
+
+/**
+ * Whether to package the encrypted project snapshot; the backend encrypts it
+ * with the passphrase saved for the project
+ */
+function toSourceSnapshot(settings: CourseSettings | undefined) {
+  return settings?.sourceSnapshot ? true : undefined
+}
+
+/**
+ * Convert media attached to question feedback. The media id is resolved to its
+ * packaged file by the extension map, like image_url.
+ */
+function toFeedbackMedia(media: FeedbackMedia | undefined) {
+  if (!media?.mediaId) return undefined
+  return {
+    type: media.type,
+    url: media.mediaId,
+    alt_text: media.altText
+  }
+}
+
+/**
+ * Convert the analytics settings, leaving them out until a site id is entered
+ */
+function toAnalytics(analytics: AnalyticsSettings | undefined) {
+  if (!analytics || !analytics.siteId?.trim()) return undefined
+  return {
+    provider: analytics.provider,
+    endpoint: analytics.provider === 'matomo' ? analytics.endpoint?.trim() : undefined,
+    site_id: analytics.siteId.trim(),
+    anonymize_ip: analytics.anonymizeIp,
+    disable_cookies: analytics.disableCookies,
+    respect_do_not_track: analytics.respectDoNotTrack
+  }
+}
+
+/**
+ * Convert the narration player policy; unset fields keep the player's defaults
+ */
+function toAudioPolicy(policy: AudioPolicySettings | undefined) {
+  if (!policy) return undefined
+  return {
+    allow_seeking: policy.allowSeeking,
+    speed_control: policy.speedControl,
+    autoplay: policy.autoplay,
+    remember_position: policy.rememberPosition,
+    unlock_overlay: policy.unlockOverlay
+  }
+}
+
+/**
+ * Convert the interface feature flags; unset flags follow the older show/printable settings
+ */
+function toFeatures(features: CourseFeatureSettings | undefined) {
+  if (!features) return undefined
+  return {
+    navigation_menu: features.navigationMenu,
+    progress_bar: features.progressBar,
+    captions_toggle: features.captionsToggle,
+    page_numbers: features.pageNumbers,
+    print_button: features.printButton
+  }
+}
+
+/**
+ * Convert the course menu settings; labels and placement are validated by the generator
+ */
+function toNavigationMenu(menu: NavigationMenuSettings | undefined): NavigationMenu | undefined {
+  if (!menu) return undefined
+  return {
+    welcome_label: menu.welcomeLabel,
+    objectives_label: menu.objectivesLabel,
+    assessment_label: menu.assessmentLabel,
+    hide_objectives: menu.hideObjectives ?? false,
+    assessment_after_topic: menu.assessmentAfterTopic,
+    manifest_item_title: menu.manifestItemTitle
+  }
+}
+
+/**
+ * Convert the welcome page start options, leaving them out when neither is used
+ */
+function toWelcomeStart(start: WelcomeStartSettings | undefined) {
+  if (!start || (!start.resumePrompt && !start.introVideoId)) return undefined
+  return {
+    resume_prompt: start.resumePrompt,
+    intro_video_id: start.introVideoId || undefined,
+    intro_video_min_percent: start.introVideoMinPercent === undefined ? undefined : Math.round(constrainNumber(start.introVideoMinPercent, 1, 100, 90))
+  }
+}
+
 /**
  * Check for maximum size limits to prevent memory issues
  */
@@ -1252,10 +1445,26 @@ export function collectAllMediaIds(courseContent: any): string[] {
             addMediaId(m?.url, `topic[${topicIndex}].knowledgeCheck.questions[${qIndex}].media[${mIndex}].url`)
           })
         }
+        addMediaId(q.feedback?.correctMedia?.mediaId, `topic[${topicIndex}].knowledgeCheck.questions[${qIndex}].feedback.correctMedia`)
+        addMediaId(q.feedback?.incorrectMedia?.mediaId, `topic[${topicIndex}].knowledgeCheck.questions[${qIndex}].feedback.incorrectMedia`)
       })
     }
   })
 
+  // Scan assessment feedback media
+  const assessmentQuestions = courseContent.assessment?.questions || []
+  assessmentQuestions.forEach((q: any, qIndex: number) => {
+    addMediaId(q?.feedback?.correctMedia?.mediaId, `assessment.questions[${qIndex}].feedback.correctMedia`)
+    addMediaId(q?.feedback?.incorrectMedia?.mediaId, `assessment.questions[${qIndex}].feedback.incorrectMedia`)
+  })
+
+  // Scan question bank feedback media; questions elsewhere refer to the bank by bankRef
+  const bankQuestions = Array.isArray(courseContent.questionBank) ? courseContent.questionBank : []
+  bankQuestions.forEach((q: any, qIndex: number) => {
+    addMediaId(q?.feedback?.correctMedia?.mediaId, `questionBank[${qIndex}].feedback.correctMedia`)
+    addMediaId(q?.feedback?.incorrectMedia?.mediaId, `questionBank[${qIndex}].feedback.incorrectMedia`)
+  })
+
   const result = Array.from(mediaIds)
   console.log(`[SCORM Media Pre-Collection] Collected ${result.length} unique media IDs:`, result)
   return result
@@ -2163,7 +2372,7 @@ async function resolveMedia(
           continue
         }
         
-        const blob = await downloadIfExternal(media.url)
+        const blob = await downloadIfExternal(media.url, media.attribution)
         
         if (blob) {
           const arrayBuffer = await blob.arrayBuffer()
@@ -2568,8 +2777,36 @@ export async function convertToRustFormat(courseContent: CourseContent | Enhance
     time_limit: constrainNumber(courseSettings?.timeLimit, 0, 86400, 0), // Max 24 hours
     session_timeout: constrainNumber(courseSettings?.sessionTimeout, 5, 1440, 30), // 5 min to 24 hours
     minimum_time_spent: constrainNumber(courseSettings?.minimumTimeSpent, 0, 7200, 0), // Max 2 hours
+    assessment_timer: toAssessmentTimer(courseSettings?.assessmentTimer),
     keyboard_navigation: courseSettings?.keyboardNavigation ?? true,
     printable: courseSettings?.printable || false,
+    high_contrast: courseSettings?.highContrast || false,
+    learner_notes: courseSettings?.learnerNotes || false,
+    runtime_analytics: courseSettings?.runtimeAnalytics || false,
+    course_search: courseSettings?.courseSearch || false,
+    course_map: courseSettings?.courseMap || false,
+    transcripts: courseSettings?.transcripts || false,
+    image_lightbox: courseSettings?.imageLightbox ?? true,
+    responsive_layout: courseSettings?.responsiveLayout ?? true,
+    media_placeholders: courseSettings?.mediaPlaceholders || false,
+    require_alt_text: courseSettings?.requireAltText || false,
+    insecure_urls: courseSettings?.insecureUrls,
+    lms_profile: courseSettings?.lmsProfile,
+    commit_settings: courseSettings?.commitHeartbeat !== undefined
+      ? { heartbeat_seconds: constrainNumber(courseSettings.commitHeartbeat, 0, 3600, 120) }
+      : undefined,
+    audio_policy: toAudioPolicy(courseSettings?.audioPolicy),
+    welcome_start: toWelcomeStart(courseSettings?.welcomeStart),
+    features: toFeatures(courseSettings?.features),
+    navigation_menu: toNavigationMenu(courseSettings?.navigationMenu),
+    api_search_depth: courseSettings?.apiSearchDepth ? constrainNumber(courseSettings.apiSearchDepth, 1, 500, 10) : undefined,
+    analytics: toAnalytics(courseSettings?.analytics),
+    feedback: toFeedback(courseSettings?.feedback),
+    course_version: courseSettings?.courseVersion?.trim() || undefined,
+    keywords: toKeywords(courseSettings),
+    source_snapshot: toSourceSnapshot(courseSettings),
+    question_bank: toQuestionBank(cc.questionBank),
+    language: courseSettings?.language || 'en',
     
     welcome_page: cc.welcome || cc.welcomePage || cc.welcomeMedia ? {
       title: sanitizeHtml(safeString(cc.welcome?.title || cc.welcomePage?.title, 'Welcome')),
@@ -2605,6 +2842,8 @@ export async function convertToRustFormat(courseContent: CourseContent | Enhance
             type: m.type,
             url: m.url || '',
             title: m.title || '',
+            alt_text: m.alt_text || m.altText,
+            attribution: m.attribution,
             clipStart: m.clip_start || m.clipStart,
             clipEnd: m.clip_end || m.clipEnd
           })),
@@ -2744,11 +2983,15 @@ export async function convertToRustFormat(courseContent: CourseContent | Enhance
             explanation: q.explanation || q.feedback?.incorrect || q.feedback?.correct || '',
             correct_feedback: q.feedback?.correct || 'Correct!',
             incorrect_feedback: q.feedback?.incorrect || 'Not quite. Try again!',
+            correct_feedback_media: toFeedbackMedia(q.feedback?.correctMedia),
+            incorrect_feedback_media: toFeedbackMedia(q.feedback?.incorrectMedia),
+            bank_ref: q.bankRef,
           }
         }) || []
       } : undefined,
         audio_file: await resolveAudioCaptionFile(sanitizedTopic.audioFile || sanitizedTopic.audioId, projectId, mediaFiles),
         caption_file: await resolveAudioCaptionFile(sanitizedTopic.captionFile || sanitizedTopic.captionId, projectId, mediaFiles),
+        narration: sanitizedTopic.narration || undefined,
         // Extract image from media array or use imageUrl field
         image_url: await resolveImageUrl(
           sanitizedTopic.imageUrl ||
@@ -2772,6 +3015,8 @@ export async function convertToRustFormat(courseContent: CourseContent | Enhance
             type: m.type,
             url: m.url || '',
             title: m.title || '',
+            alt_text: m.alt_text || m.altText,
+            attribution: m.attribution,
             clipStart: m.clip_start || m.clipStart,
             clipEnd: m.clip_end || m.clipEnd
           })),
@@ -2842,7 +3087,10 @@ export async function convertToRustFormat(courseContent: CourseContent | Enhance
           correct_answer: qAny.correctAnswer,
           explanation,
           correct_feedback,
-          incorrect_feedback
+          incorrect_feedback,
+          correct_feedback_media: toFeedbackMedia(qAny.feedback?.correctMedia),
+          incorrect_feedback_media: toFeedbackMedia(qAny.feedback?.incorrectMedia),
+          bank_ref: qAny.bankRef
         }
       })
     } : undefined,
@@ -3433,6 +3681,7 @@ async function injectOrphanedMediaIntoCourseContent(projectId: string, courseDat
           embed_url: normalizedEmbedUrl, // Ensure consistency
           title: mediaItem.metadata?.title || `YouTube Video ${mediaItem.id}`,
           alt: mediaItem.metadata?.alt || mediaItem.metadata?.title || `YouTube Video ${mediaItem.id}`,
+          alt_text: mediaItem.metadata?.altText,
           // Include YouTube-specific properties (keep original youtubeUrl for reference)
           youtubeUrl: youtubeUrl,
           clipStart: clipStart,
@@ -3449,6 +3698,8 @@ async function injectOrphanedMediaIntoCourseContent(projectId: string, courseDat
           url: `media/${mediaItem.id}.${extension}`,
           title: mediaItem.metadata?.title || `Media ${mediaItem.id}`,
           alt: mediaItem.metadata?.alt || mediaItem.metadata?.title || `Media ${mediaItem.id}`,
+          alt_text: mediaItem.metadata?.altText,
+          attribution: mediaItem.metadata?.attribution,
           // For regular videos, mark as non-YouTube
           ...(mediaItem.id.startsWith('video-') && {
             is_youtube: false
@@ -3808,8 +4059,36 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
     time_limit: constrainNumber(courseSettings?.timeLimit, 0, 86400, 0), // Max 24 hours
     session_timeout: constrainNumber(courseSettings?.sessionTimeout, 5, 1440, 30), // 5 min to 24 hours
     minimum_time_spent: constrainNumber(courseSettings?.minimumTimeSpent, 0, 7200, 0), // Max 2 hours
+    assessment_timer: toAssessmentTimer(courseSettings?.assessmentTimer),
     keyboard_navigation: courseSettings?.keyboardNavigation ?? true,
     printable: courseSettings?.printable || false,
+    high_contrast: courseSettings?.highContrast || false,
+    learner_notes: courseSettings?.learnerNotes || false,
+    runtime_analytics: courseSettings?.runtimeAnalytics || false,
+    course_search: courseSettings?.courseSearch || false,
+    course_map: courseSettings?.courseMap || false,
+    transcripts: courseSettings?.transcripts || false,
+    image_lightbox: courseSettings?.imageLightbox ?? true,
+    responsive_layout: courseSettings?.responsiveLayout ?? true,
+    media_placeholders: courseSettings?.mediaPlaceholders || false,
+    require_alt_text: courseSettings?.requireAltText || false,
+    insecure_urls: courseSettings?.insecureUrls,
+    lms_profile: courseSettings?.lmsProfile,
+    commit_settings: courseSettings?.commitHeartbeat !== undefined
+      ? { heartbeat_seconds: constrainNumber(courseSettings.commitHeartbeat, 0, 3600, 120) }
+      : undefined,
+    audio_policy: toAudioPolicy(courseSettings?.audioPolicy),
+    welcome_start: toWelcomeStart(courseSettings?.welcomeStart),
+    features: toFeatures(courseSettings?.features),
+    navigation_menu: toNavigationMenu(courseSettings?.navigationMenu),
+    api_search_depth: courseSettings?.apiSearchDepth ? constrainNumber(courseSettings.apiSearchDepth, 1, 500, 10) : undefined,
+    analytics: toAnalytics(courseSettings?.analytics),
+    feedback: toFeedback(courseSettings?.feedback),
+    course_version: courseSettings?.courseVersion?.trim() || undefined,
+    keywords: toKeywords(courseSettings),
+    source_snapshot: toSourceSnapshot(courseSettings),
+    question_bank: toQuestionBank((courseContent as any).questionBank),
+    language: courseSettings?.language || 'en',
     
     welcome_page: courseContent.welcome ? {
       title: courseContent.welcome.title || 'Welcome',
@@ -3923,6 +4202,7 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
           mediaFiles,
           topic.captionBlob
         ),
+        narration: topic.narration || undefined,
         image_url: await resolveImageUrl(topic.imageUrl, projectId, mediaFiles, mediaCounter),
         // Filter out audio/caption from media array since they're handled separately
         media: await resolveMedia(safeArrayFilter(topic.media).filter((m: any) => {
@@ -3937,6 +4217,8 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
           type: m.type,
           url: m.url || '',
           title: m.title || '',
+          alt_text: (m as any).alt_text || m.altText,
+          attribution: (m as any).attribution,
           clipStart: (m as any).clip_start || m.clipStart,
           clipEnd: (m as any).clip_end || m.clipEnd
         })), projectId, mediaFiles, mediaCounter)
@@ -3979,6 +4261,8 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
               explanation: kc.explanation || (kc.feedback && kc.feedback.incorrect) || (kc.feedback && kc.feedback.correct) || '',
             correct_feedback: kc.correctFeedback || (kc.feedback && kc.feedback.correct) || 'Correct!',
             incorrect_feedback: kc.incorrectFeedback || (kc.feedback && kc.feedback.incorrect) || 'Not quite. Try again!',
+            correct_feedback_media: toFeedbackMedia(kc.feedback?.correctMedia),
+            incorrect_feedback_media: toFeedbackMedia(kc.feedback?.incorrectMedia),
             }]
           }
         } else {
@@ -4024,6 +4308,8 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
               explanation: kc.explanation || (kc.feedback && kc.feedback.incorrect) || (kc.feedback && kc.feedback.correct) || '',
             correct_feedback: kc.correctFeedback || (kc.feedback && kc.feedback.correct) || 'Correct!',
             incorrect_feedback: kc.incorrectFeedback || (kc.feedback && kc.feedback.incorrect) || 'Not quite. Try again!',
+            correct_feedback_media: toFeedbackMedia(kc.feedback?.correctMedia),
+            incorrect_feedback_media: toFeedbackMedia(kc.feedback?.incorrectMedia),
             }]
           }
         }
@@ -4103,6 +4389,9 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
               explanation: q.explanation || ((q as any).feedback && (q as any).feedback.incorrect) || ((q as any).feedback && (q as any).feedback.correct) || '',
               correct_feedback: (q as any).correctFeedback || ((q as any).feedback && (q as any).feedback.correct) || 'Correct!',
               incorrect_feedback: (q as any).incorrectFeedback || ((q as any).feedback && (q as any).feedback.incorrect) || 'Not quite. Try again!',
+              correct_feedback_media: toFeedbackMedia((q as any).feedback?.correctMedia),
+              incorrect_feedback_media: toFeedbackMedia((q as any).feedback?.incorrectMedia),
+              bank_ref: (q as any).bankRef,
             }
             
             if (q.type === 'fill-in-the-blank') {
@@ -4161,6 +4450,9 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
           explanation: '', // Enhanced format doesn't have explanations for assessment
           correct_feedback: (q as any).correct_feedback || (q as any).correctFeedback || 'Correct!',
           incorrect_feedback: (q as any).incorrect_feedback || (q as any).incorrectFeedback || 'Not quite. Try again!',
+          correct_feedback_media: toFeedbackMedia((q as any).feedback?.correctMedia),
+          incorrect_feedback_media: toFeedbackMedia((q as any).feedback?.incorrectMedia),
+          bank_ref: (q as any).bankRef,
         }
       })
     } : undefined,
@@ -4190,17 +4482,74 @@ async function convertEnhancedToRustFormat(courseContent: EnhancedCourseContent,
   return { courseData: result, mediaFiles }
 }
 
+/**
+ * Messages from the Rust side carry a catalog key and parameters next to the
+ * text, which is already rendered in the app locale
+ */
+export interface LocalizedMessage {
+  message: string
+  messageKey?: string
+  params?: Record<string, string>
+}
+
+/** A page linking to media the package was generated without */
+export interface MissingMediaWarning {
+  page_id: string
+  media_id: string
+  path: string
+  substituted: boolean // a placeholder was packaged in its place
+}
+
+/** A page image without alt text, a video player without a name, or credited media without a license */
+export interface ContentLintIssue {
+  page_id: string
+  media_id: string
+  rule: 'missing_alt_text' | 'unlabelled_player' | 'missing_license'
+}
+
+/** An http URL a page loads, and whether it was upgraded, downloaded or left as is */
+export interface InsecureUrlReport {
+  page_id: string
+  source: string // media item ID, 'image' or 'content'
+  url: string
+  action: 'reported' | 'upgraded' | 'downloaded'
+  secure_url?: string // https URL the page uses instead
+  path?: string // package path of a downloaded copy
+  error?: string // why the policy's upgrade or download didn't happen
+}
+
+/** A page whose template failed to render, with the data the error points at */
+export interface PageRenderError {
+  page_id: string // prefixed with the language for language variants, e.g. 'fr/topic-1'
+  page_title: string
+  template: string // template or partial the error was raised in
+  message: string
+  line?: number
+  column?: number
+  data_snippet: string
+}
+
+/** Readable text for a rejected invoke, which may be a string or a LocalizedMessage */
+export function commandErrorMessage(error: unknown): string {
+  if (error instanceof Error) return error.message
+  if (error && typeof error === 'object' && typeof (error as LocalizedMessage).message === 'string') {
+    return (error as LocalizedMessage).message
+  }
+  return String(error)
+}
+
 /**
  * Generate SCORM package using Rust backend with templates
  */
 export async function generateRustSCORM(
   courseContent: CourseContent | EnhancedCourseContent,
   projectId: string,
-  onProgress?: (message: string, progress: number) => void,
+  onProgress?: (message: string, progress: number, etaMs?: number) => void,
   preloadedMedia?: Map<string, Blob> | Map<string, { data: Uint8Array; mimeType: string }>,
   courseSettings?: CourseSettings,
   strictValidation?: boolean,
-  authoritativeExtensionMap?: Map<string, string> // Optional map for enhanced path to prevent extension fallbacks
+  authoritativeExtensionMap?: Map<string, string>, // Optional map for enhanced path to prevent extension fallbacks
+  debugBuild?: boolean // Include the diagnostics overlay (Ctrl+Alt+Shift+D or ?scorm_debug=1) for LMS triage
 ): Promise<Uint8Array> {
   // Provide default if no authoritative extension map is provided
   const effectiveExtensionMap = authoritativeExtensionMap || new Map<string, string>()
@@ -4245,13 +4594,52 @@ export async function generateRustSCORM(
   
   // Set up progress event listener
   let unlisten: (() => void) | undefined
+  let unlistenRenderErrors: (() => void) | undefined
   if (onProgress && typeof onProgress === 'function') {
-    listen<{ message: string; progress: number }>('scorm-generation-progress', (event) => {
+    listen<LocalizedMessage & {
+      progress: number
+      /** Build phase the progress is in: media, html or zip */
+      phase?: 'media' | 'html' | 'zip'
+      /** Estimated milliseconds left, from the project's earlier builds */
+      etaMs?: number | null
+      /** Bytes free on the disk packages are saved to, when the system reports it */
+      freeSpace?: number | null
+      missingMedia?: MissingMediaWarning[]
+      contentLint?: ContentLintIssue[]
+      insecureUrls?: InsecureUrlReport[]
+    }>('scorm-generation-progress', (event) => {
       console.log('[Rust SCORM] Progress event:', event.payload)
-      onProgress(event.payload.message, event.payload.progress)
+      if (event.payload.missingMedia?.length) {
+        debugLogger.warn('SCORM_GENERATION', 'Course references missing media', {
+          projectId,
+          missingMedia: event.payload.missingMedia
+        })
+      }
+      if (event.payload.contentLint?.length) {
+        debugLogger.warn('SCORM_GENERATION', 'Course media is missing alt text', {
+          projectId,
+          contentLint: event.payload.contentLint
+        })
+      }
+      if (event.payload.insecureUrls?.length) {
+        debugLogger.warn('SCORM_GENERATION', 'Course loads resources over http', {
+          projectId,
+          insecureUrls: event.payload.insecureUrls
+        })
+      }
+      onProgress(event.payload.message, event.payload.progress, event.payload.etaMs ?? undefined)
     }).then(unlistenFn => {
       unlisten = unlistenFn
     })
+    // Pages that failed to render are reported before the command rejects
+    listen<PageRenderError[]>('scorm-render-errors', (event) => {
+      debugLogger.error('SCORM_GENERATION', 'Course pages failed to render', {
+        projectId,
+        renderErrors: event.payload
+      })
+    }).then(unlistenFn => {
+      unlistenRenderErrors = unlistenFn
+    })
   }
   
   // Declare mediaFiles outside try block so it's accessible in catch block
@@ -4698,6 +5086,7 @@ export async function generateRustSCORM(
         projectId: projectId,
         mediaFiles: mediaFiles, // Always pass array, even if empty - prevents fallback to disk loading
         extensionMap: extensionMapObject, // Pass complete authoritative extension map
+        debugBuild: debugBuild || undefined,
       }),
       timeoutPromise
     ])
@@ -4748,7 +5137,7 @@ export async function generateRustSCORM(
 
     // Log critical SCORM error to ultraSimpleLogger for persistence
     debugLogger.error('SCORM_GENERATION', 'SCORM generation process failed', {
-      message: error instanceof Error ? error.message : String(error),
+      message: commandErrorMessage(error),
       stack: error instanceof Error ? error.stack : undefined,
       mediaFilesCount: mediaFiles.length,
       timestamp: new Date().toISOString()
@@ -4756,7 +5145,7 @@ export async function generateRustSCORM(
     
     console.error('[Rust SCORM] Generation failed:', error)
     console.error('[Rust SCORM] Error details:', {
-      message: error instanceof Error ? error.message : String(error),
+      message: commandErrorMessage(error),
       stack: error instanceof Error ? error.stack : undefined,
       type: error?.constructor?.name || typeof error
     })
@@ -4776,14 +5165,14 @@ export async function generateRustSCORM(
       // Log critical SCORM generation failure to ultraSimpleLogger
       debugLogger.error('SCORM_GENERATION', 'SCORM package generation failed', {
         mediaFileCount: mediaFiles.length,
-        error: error instanceof Error ? error.message : String(error),
+        error: commandErrorMessage(error),
         errorStack: error instanceof Error ? error.stack : undefined,
         timestamp: new Date().toISOString()
       })
       
       throw new Error(
         `Failed to generate SCORM package with ${mediaFiles.length} media files. ` +
-        `Error: ${error instanceof Error ? error.message : String(error)}. ` +
+        `Error: ${commandErrorMessage(error)}. ` +
         `If this persists, try optimizing your media files.`
       )
     }
@@ -4792,6 +5181,9 @@ export async function generateRustSCORM(
     if (unlisten) {
       unlisten()
     }
+    if (unlistenRenderErrors) {
+      unlistenRenderErrors()
+    }
     
     // Clear media cache after generation
     clearMediaCache()
@@ -4811,7 +5203,8 @@ export interface ScormBuildOptions {
   maxMissing?: number
   /** Allow files with unmapped extensions to fall back to .bin */
   allowUnmappedExt?: boolean
-  onProgress?: (message: string, progress: number) => void
+  /** `etaMs` is the estimated time left, once the project has earlier builds to go by */
+  onProgress?: (message: string, progress: number, etaMs?: number) => void
   courseSettings?: CourseSettings
 }
 
@@ -4919,7 +5312,220 @@ export async function isRustScormAvailable(): Promise<boolean> {
     return true
   } catch (error) {
     // Check if error is because of invalid data (command exists) or missing command
-    const errorMessage = String(error)
+    const errorMessage = commandErrorMessage(error)
     return !errorMessage.includes('not found') && !errorMessage.includes('unknown')
   }
-}
\ No newline at end of file
+}
+
+export interface PackageIssue {
+  severity: 'error' | 'warning'
+  message: string
+}
+
+export interface ScoInfo {
+  identifier: string
+  title: string
+  resource: string
+  launch: string | null
+  parameters: string | null
+}
+
+export interface PackageInspection {
+  scormVersion: string | null
+  manifestIdentifier: string | null
+  title: string | null
+  scos: ScoInfo[]
+  assetCount: number
+  launchFiles: string[]
+  fileCount: number
+  totalSize: number
+  compressedSize: number
+  sizeByType: Record<string, number>
+  issues: PackageIssue[]
+}
+
+/**
+ * Inspect any SCORM ZIP on disk (ours or a vendor's) for QA: manifest
+ * structure, SCOs, SCORM version, size by type and LMS compatibility problems
+ */
+export async function inspectScormPackage(path: string): Promise<PackageInspection> {
+  return invoke<PackageInspection>('inspect_scorm_package', { path })
+}
+
+export interface PageActivity {
+  pageId: string
+  completed: boolean
+  /** Time on the page over all sessions */
+  seconds: number
+  /** Only recorded by packages built with runtime analytics */
+  visits: number | null
+  interactions: number | null
+}
+
+export interface SuspendDataReport {
+  version: string | null
+  savedAt: string | null
+  completedPages: string[]
+  answeredQuestions: number
+  completedAudio: number
+  assessmentAttempts: number
+  /** Percentage of each assessment attempt */
+  assessmentScores: number[]
+  introVideoWatched: boolean
+  /** Times the browser refused to autoplay narration */
+  autoplayBlocked: number
+  totalSeconds: number
+  notes: number
+  activityRecorded: boolean
+  pages: PageActivity[]
+  /** The report as plain text, for support tickets */
+  summary: string
+}
+
+/**
+ * Readable progress report from a learner's cmi.suspend_data as copied from
+ * the LMS, for troubleshooting completion disputes
+ */
+export async function decodeSuspendData(data: string): Promise<SuspendDataReport> {
+  return invoke<SuspendDataReport>('decode_suspend_data', { data })
+}
+
+/**
+ * Restore the encrypted project snapshot packaged in a SCORM ZIP into the
+ * projects folder, returning the restored project's path. Fails while the
+ * original project still exists.
+ */
+export async function extractSourceSnapshot(packagePath: string, passphrase: string): Promise<string> {
+  return invoke<string>('extract_source_snapshot', { packagePath, passphrase })
+}
+
+/**
+ * Save the passphrase the project's snapshots are encrypted with. It goes to
+ * the encrypted store next to the API keys, never into the project file.
+ */
+export async function saveSnapshotPassphrase(projectId: string, passphrase: string): Promise<void> {
+  await invoke('save_snapshot_passphrase', { projectId, passphrase })
+}
+
+export async function deleteSnapshotPassphrase(projectId: string): Promise<void> {
+  await invoke('delete_snapshot_passphrase', { projectId })
+}
+
+export async function hasSnapshotPassphrase(projectId: string): Promise<boolean> {
+  return (await invoke<boolean>('has_snapshot_passphrase', { projectId })) === true
+}
+export interface BuildRecord {
+  timestamp: string
+  /** Equal hashes mean the builds used the same settings */
+  settingsHash: string
+  settings: Record<string, unknown>
+  /** Hash of the course content and media the build ran with */
+  contentHash?: string
+  /** Build id stamped into the package's version.json */
+  buildId?: string
+  durationMs: number
+  /** ZIP size in bytes, 0 when the build failed */
+  outputSize: number
+  success: boolean
+  warnings: number
+  error?: string
+  /** `timestamp` in local time */
+  localTimes?: LocalTimes
+}
+
+/**
+ * SCORM builds of a project, newest first, for the dashboard
+ */
+export async function getBuildHistory(projectId: string, limit?: number): Promise<BuildRecord[]> {
+  return invoke<BuildRecord[]>('get_build_history', { projectId, limit })
+}
+
+/**
+ * The last successful build of this course when neither its content, media
+ * nor settings changed since, or null when it needs building again
+ */
+export async function checkBuildUpToDate(
+  courseData: unknown,
+  projectId: string,
+  debugBuild?: boolean
+): Promise<BuildRecord | null> {
+  return invoke<BuildRecord | null>('check_build_up_to_date', {
+    courseData,
+    projectId,
+    debugBuild,
+  })
+}
+
+/** Contents of version.json in packages built by the app */
+export interface PackageVersion {
+  courseVersion: string
+  buildId: string
+  projectId: string
+  contentHash: string
+  builtAt: string
+  generatorVersion: string
+}
+
+export interface PackageVersionInfo {
+  path: string
+  /** null for packages built before version stamping */
+  version: PackageVersion | null
+  /** The project's record of the build, while it is still in the history */
+  build: BuildRecord | null
+  /** Whether the package is the project's most recent successful build */
+  latest: boolean
+}
+
+export interface PackageComparison {
+  packages: PackageVersionInfo[]
+  sameBuild: boolean
+  /** Same course content and media, even if built at different times */
+  sameContent: boolean
+}
+
+/**
+ * Trace package ZIPs (e.g. downloaded from an LMS) back to their project
+ * builds, for matching uploads with project revisions in support tickets
+ */
+export async function comparePackageVersions(paths: string[]): Promise<PackageComparison> {
+  return invoke<PackageComparison>('compare_package_versions', { paths })
+}
+
+/**
+ * One page (welcome, objectives, assessment or a topic id) rendered by the package
+ * templates inside the course shell, for the editor's preview pane. Media is served
+ * from the project through the asset protocol, so nothing is built or zipped.
+ */
+export async function renderPagePreview(
+  courseContent: CourseContent | EnhancedCourseContent,
+  projectId: string,
+  pageId: string,
+  courseSettings?: CourseSettings
+): Promise<string> {
+  const { courseData } = await convertToRustFormat(courseContent, projectId, courseSettings)
+  return invoke<string>('render_page_preview', { courseData, projectId, pageId })
+}
+
+export interface PageThumbnail {
+  pageId: string
+  /** Hash of the page HTML and media the thumbnail was rendered from */
+  contentHash: string
+  /** Whether it came from the project's thumbnail cache */
+  cached: boolean
+  /** `data:image/png;base64,...`, 320x200 */
+  dataUrl: string
+}
+
+/**
+ * A PNG thumbnail of one page for the course outline, screenshotted by a
+ * headless Chrome, Chromium or Edge and cached until the page or its media change
+ */
+export async function renderPageThumbnail(
+  courseContent: CourseContent | EnhancedCourseContent,
+  projectId: string,
+  pageId: string,
+  courseSettings?: CourseSettings
+): Promise<PageThumbnail> {
+  const { courseData } = await convertToRustFormat(courseContent, projectId, courseSettings)
+  return invoke<PageThumbnail>('render_page_thumbnail', { courseData, projectId, pageId })
+}
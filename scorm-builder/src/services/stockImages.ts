@@ -0,0 +1,35 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { MediaAttribution } from '@/types/ipc'
+
+export type StockImageProvider = 'google' | 'unsplash' | 'pexels'
+
+// Who made an image and under which license; stored with its media metadata
+export type { MediaAttribution }
+
+/** A search result, the same shape for every provider */
+export interface StockImage {
+  id: string
+  provider: StockImageProvider
+  title: string
+  thumbnail_url: string
+  full_url: string
+  width?: number | null
+  height?: number | null
+  attribution: MediaAttribution
+  /** Path of the locally cached thumbnail, when caching was asked for */
+  cached_thumbnail?: string
+}
+
+/**
+ * Search a stock image provider. The request is made by the backend with the
+ * keys saved in settings; Google results carry no license, so check the
+ * source page before using them.
+ */
+export async function searchStockImages(
+  query: string,
+  provider: StockImageProvider,
+  page = 1,
+  cacheThumbnails = false
+): Promise<StockImage[]> {
+  return invoke<StockImage[]>('search_stock_images', { query, provider, page, cacheThumbnails })
+}
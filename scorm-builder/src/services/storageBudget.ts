@@ -0,0 +1,21 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { BudgetStatus, MediaTypeUsage, StorageBudget, StorageReport } from '@/types/ipc'
+
+// Media size budget of a project. Past `warn_bytes` the project statistics
+// carry a warning; store_media refuses files that would take the project past
+// `block_bytes` or a media type past its `max_type_bytes` entry, with an
+// `error.storage_budget.*` message naming the largest media types.
+export type { BudgetStatus, MediaTypeUsage, StorageBudget, StorageReport }
+
+/** The budget of a project, or the workspace default without a project */
+export async function getStorageBudget(projectId?: string): Promise<StorageBudget> {
+  return invoke<StorageBudget>('get_storage_budget', { projectId })
+}
+
+/**
+ * Set the budget of a project, or the workspace default without a project.
+ * null removes the project's own budget, or resets the default.
+ */
+export async function setStorageBudget(budget: StorageBudget | null, projectId?: string): Promise<void> {
+  await invoke('set_storage_budget', { projectId, budget })
+}
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+/** An override template that doesn't compile */
+export interface TemplateSourceError {
+  template: string
+  path: string
+  message: string
+  line?: number
+  column?: number
+}
+
+export interface TemplateCheck {
+  /** templates/ under the app config folder */
+  dir: string
+  /** Templates and partials the folder overrides, including broken ones */
+  overrides: string[]
+  errors: TemplateSourceError[]
+}
+
+/**
+ * Turn template dev mode on or off. While it is on, page previews render with the
+ * templates in the override folder and edits to them are reported by onTemplatesChanged.
+ */
+export async function setTemplateDevMode(enabled: boolean): Promise<TemplateCheck> {
+  return invoke<TemplateCheck>('set_template_dev_mode', { enabled })
+}
+
+/** Called after an override template is saved, to re-render the preview and show errors */
+export async function onTemplatesChanged(
+  callback: (check: TemplateCheck) => void
+): Promise<UnlistenFn> {
+  return listen<TemplateCheck>('template-dev-changed', (event) => callback(event.payload))
+}
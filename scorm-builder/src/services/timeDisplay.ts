@@ -0,0 +1,26 @@
+import { invoke } from '@tauri-apps/api/core'
+
+/**
+ * Local renderings of a response's UTC timestamps, keyed by the field they
+ * render (e.g. `last_modified`, `backupTimestamp`), plus the IANA name of the
+ * zone they are in. Formatted with the display format from the settings.
+ */
+export type LocalTimes = { timezone: string } & Record<string, string>
+
+export type TimeFormatPreset = 'datetime_24h' | 'datetime_12h' | 'long' | 'iso'
+
+/** How reporting commands format local times */
+export interface TimeDisplay {
+  preset: TimeFormatPreset
+  /** strftime pattern used instead of the preset, e.g. "%d/%m/%Y %H:%M" */
+  pattern?: string
+}
+
+export async function getTimeDisplay(): Promise<TimeDisplay> {
+  return invoke<TimeDisplay>('get_time_display')
+}
+
+/** Fails when `pattern` isn't a valid strftime format */
+export async function setTimeDisplay(display: TimeDisplay): Promise<void> {
+  return invoke('set_time_display', { display })
+}
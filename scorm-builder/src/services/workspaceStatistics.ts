@@ -0,0 +1,40 @@
+import { invoke } from '@tauri-apps/api/core'
+
+export interface MediaTypeTotal {
+  files: number
+  bytes: number
+}
+
+/** A file in a project folder, or a project file itself */
+export interface LargeFile {
+  path: string
+  size: number
+  project_id: string
+  /** Absent when the folder's project file no longer exists */
+  project_name?: string
+}
+
+export interface StaleProject {
+  id: string
+  name: string
+  path: string
+  /** Newest change to the project file or anything in its folder */
+  last_modified: string
+  size: number
+}
+
+export interface WorkspaceStatistics {
+  total_projects: number
+  total_size: number
+  /** Keyed by media type: image, audio, video, caption... */
+  media_by_type: Record<string, MediaTypeTotal>
+  /** The 20 largest files, largest first */
+  largest_files: LargeFile[]
+  /** Least recently changed first */
+  stale_projects: StaleProject[]
+}
+
+/** Disk usage of every project; projects untouched for `staleMonths` months are listed as stale */
+export async function getWorkspaceStatistics(staleMonths = 6): Promise<WorkspaceStatistics> {
+  return invoke<WorkspaceStatistics>('get_workspace_statistics', { staleMonths })
+}
@@ -1,3 +1,5 @@
+import type { MediaAttribution } from './ipc'
+
 // New format
 export interface CourseContent {
   welcomePage: Page
@@ -5,6 +7,7 @@ export interface CourseContent {
   topics: Topic[]
   assessment: Assessment
   objectives?: string[] // Array of learning objectives
+  questionBank?: KnowledgeCheckQuestion[] // Questions shared by knowledge checks and the assessment via bankRef
 }
 
 // Legacy format (for backward compatibility during transition)
@@ -34,6 +37,7 @@ export interface Page {
   audioBlob?: Blob
   captionId?: string
   captionBlob?: Blob
+  authorNotes?: string // For the course team; never included in the package
 }
 
 export interface Topic extends Page {
@@ -52,10 +56,23 @@ export interface KnowledgeCheckQuestion {
   correctAnswer: string
   blank?: string // for fill-in-the-blank only
   explanation?: string // optional explanation
-  feedback?: {
-    correct: string
-    incorrect: string
-  }
+  feedback?: QuestionFeedback
+  authorNotes?: string // For the course team; never included in the package
+  bankRef?: string // Id of a questionBank question; its current version is packaged instead of these fields
+}
+
+export interface QuestionFeedback {
+  correct: string
+  incorrect: string
+  correctMedia?: FeedbackMedia // shown with the correct feedback
+  incorrectMedia?: FeedbackMedia // shown with the incorrect feedback
+}
+
+// Image or short audio clip attached to question feedback
+export interface FeedbackMedia {
+  mediaId: string
+  type: 'image' | 'audio'
+  altText?: string // Text alternative for images, accessible name for the player
 }
 
 export interface Media {
@@ -81,6 +98,8 @@ export interface Media {
   fileName?: string // Original file name
   clipStart?: number // Start time in seconds for video clips
   clipEnd?: number // End time in seconds for video clips
+  altText?: string // Text alternative for images, accessible name for video players
+  attribution?: MediaAttribution // Author and license, listed on the package's credits page
 }
 
 export interface Assessment {
@@ -96,10 +115,9 @@ export interface AssessmentQuestion {
   options?: string[] // for multiple choice
   correctAnswer: string
   blank?: string // for fill-in-the-blank only (legacy compatibility)
-  feedback: {
-    correct: string
-    incorrect: string
-  }
+  feedback: QuestionFeedback
+  authorNotes?: string // For the course team; never included in the package
+  bankRef?: string // Id of a questionBank question; its current version is packaged instead of these fields
 }
 
 // Legacy types for backward compatibility
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AnalyticsProvider } from "./AnalyticsProvider";
+
+export type AnalyticsSettings = { provider: AnalyticsProvider, 
+/**
+ * Base URL of the Matomo instance, e.g. `https://stats.example.com/`.
+ * Not used for Google Analytics.
+ */
+endpoint?: string, 
+/**
+ * Matomo site id or GA measurement id (`G-...`)
+ */
+site_id: string, 
+/**
+ * Ask the tracker to drop the last octets of the learner's IP (default
+ * true). Matomo anonymizes on the server, so this only affects GA.
+ */
+anonymize_ip?: boolean, 
+/**
+ * Track without cookies or other browser storage (default true)
+ */
+disable_cookies?: boolean, 
+/**
+ * Send nothing when the browser asks not to be tracked (default true)
+ */
+respect_do_not_track?: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An image stored as an animated GIF
+ */
+export type AnimatedGifInfo = { media_id: string, frames: number, size: number, 
+/**
+ * Big enough to be worth packaging as video
+ */
+large: boolean, 
+/**
+ * ffmpeg is installed, so builds can package it as MP4 or WebM
+ */
+can_convert: boolean, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Assessment countdowns. Either timer can be used alone; with a per-question
+ * timer the questions are shown one at a time.
+ */
+export type AssessmentTimer = { overall_minutes?: number, per_question_seconds?: number, 
+/**
+ * How long before a countdown runs out the learner is warned (default 60s,
+ * capped at half of the countdown)
+ */
+warning_seconds?: number, 
+/**
+ * Submit when time runs out, counting unanswered questions as wrong
+ * (default true)
+ */
+auto_submit?: boolean, };
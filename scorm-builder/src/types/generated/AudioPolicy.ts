@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the narration player behaves. Every field is optional; unset fields
+ * keep the player's historical behaviour.
+ */
+export type AudioPolicy = { 
+/**
+ * Let learners seek and skip while `require_audio_completion` is on
+ * (default false). Seeking is always allowed when completion isn't required.
+ */
+allow_seeking?: boolean, 
+/**
+ * Show the playback speed selector (default true)
+ */
+speed_control?: boolean, 
+/**
+ * Start the narration when a page opens (default false). Learners can
+ * still turn it off, and a browser that blocks it gets a prompt instead.
+ */
+autoplay?: boolean, 
+/**
+ * Pick the narration up where the learner left it on each page, kept in
+ * suspend_data (default false)
+ */
+remember_position?: boolean, 
+/**
+ * Cover the page with a "Click to enable audio" button when the browser
+ * blocks autoplay (default true). Off leaves the pulsing play button and
+ * a short notice.
+ */
+unlock_overlay?: boolean, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackupDeletionReason } from "./BackupDeletionReason";
+
+/**
+ * One backup file looked at by `cleanup_old_backups`
+ */
+export type BackupFileReport = { path: string, size: number, modified: string, ageDays: number, 
+/**
+ * Set for backups that were, or in a dry run would be, deleted
+ */
+reason?: BackupDeletionReason, 
+/**
+ * Why a backup chosen for deletion couldn't be removed
+ */
+error?: string, 
+/**
+ * `modified` in local time
+ */
+localTimes?: { timezone: string } & Record<string, string>, };
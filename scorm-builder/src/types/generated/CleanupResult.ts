@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackupFileReport } from "./BackupFileReport";
+
+/**
+ * What `cleanup_old_backups` deleted and kept, also sent in the `backup-cleanup` event
+ */
+export type CleanupResult = { deletedCount: number, keptCount: number, 
+/**
+ * Nothing was deleted; `deleted` lists what would have been
+ */
+dryRun: boolean, 
+/**
+ * Bytes deleted, or that a dry run would delete
+ */
+freedBytes: number, deleted: Array<BackupFileReport>, kept: Array<BackupFileReport>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a clip can't be played as set
+ */
+export type ClipProblem = "ends_before_start" | "starts_after_video" | "ends_after_video";
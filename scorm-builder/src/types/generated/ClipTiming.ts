@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Clip timing for one item, in seconds; `None` ends are cleared
+ */
+export type ClipTiming = { start?: number | null, end?: number | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClipProblem } from "./ClipProblem";
+
+/**
+ * A clip whose timing doesn't fit its video
+ */
+export type ClipTimingIssue = { 
+/**
+ * Page the media is on, prefixed with the language of a variant
+ */
+page_id: string, media_id: string, video_id: string | null, clip_start: number | null, clip_end: number | null, 
+/**
+ * Length of the video in seconds, when known
+ */
+duration: number | null, problem: ClipProblem, };
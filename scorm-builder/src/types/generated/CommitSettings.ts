@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When the runtime commits progress to the LMS. Saves in quick succession
+ * share one commit, and a heartbeat commits on a timer so a closed or crashed
+ * tab loses little.
+ */
+export type CommitSettings = { 
+/**
+ * Milliseconds a save waits for further changes before committing
+ * (default 1000, at most 10000)
+ */
+debounce_ms?: number, 
+/**
+ * Seconds between heartbeat commits (default 120); 0 turns the heartbeat off
+ */
+heartbeat_seconds?: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How `course_content` is stored on disk
+ */
+export type ContentLayout = "single" | "split";
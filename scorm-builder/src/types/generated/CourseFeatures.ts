@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Parts of the generated interface authors can switch off, or on, without
+ * editing the exported HTML. Unset fields follow the older course settings
+ * named on each, so existing projects build unchanged.
+ */
+export type CourseFeatures = { 
+/**
+ * Sidebar outline of the course pages (default `show_outline`)
+ */
+navigation_menu?: boolean, 
+/**
+ * Progress ring at the top of the navigation menu (default `show_progress`)
+ */
+progress_bar?: boolean, 
+/**
+ * Captions button on narration players that have captions (default true)
+ */
+captions_toggle?: boolean, 
+/**
+ * "Page 3 of 12" between the previous and next buttons (default false)
+ */
+page_numbers?: boolean, 
+/**
+ * Print button in the header (default `printable`); turning it on allows printing
+ */
+print_button?: boolean, };
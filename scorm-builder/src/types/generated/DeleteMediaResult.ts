@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DeleteMediaResult = { deleted: boolean, 
+/**
+ * Pages whose saved content still references the media
+ */
+referenced_by: Array<string>, };
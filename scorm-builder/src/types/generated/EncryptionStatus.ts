@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether a project is encrypted and unlocked, with what to tell its author
+ */
+export type EncryptionStatus = { encrypted: boolean, unlocked: boolean, 
+/**
+ * What the author must be told, starting with the no-recovery warning
+ */
+warnings: Array<string>, };
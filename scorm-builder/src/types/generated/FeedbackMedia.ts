@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackMediaType } from "./FeedbackMediaType";
+
+/**
+ * Media attached to question feedback
+ */
+export type FeedbackMedia = { type: FeedbackMediaType, 
+/**
+ * Media id or path, resolved like `image_url`
+ */
+url: string, 
+/**
+ * Text alternative for an image, or accessible name for the player
+ */
+alt_text?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FeedbackSettings = { 
+/**
+ * https URL reports are posted to
+ */
+webhook_url?: string, 
+/**
+ * Address reports are mailed to when there is no webhook or it fails
+ */
+email?: string, };
@@ -0,0 +1,129 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AnalyticsSettings } from "./AnalyticsSettings";
+import type { Assessment } from "./Assessment";
+import type { AssessmentTimer } from "./AssessmentTimer";
+import type { AudioPolicy } from "./AudioPolicy";
+import type { BankQuestion } from "./BankQuestion";
+import type { CommitSettings } from "./CommitSettings";
+import type { CourseFeatures } from "./CourseFeatures";
+import type { FeedbackSettings } from "./FeedbackSettings";
+import type { InsecureUrlPolicy } from "./InsecureUrlPolicy";
+import type { KeywordSettings } from "./KeywordSettings";
+import type { LanguageVariant } from "./LanguageVariant";
+import type { LmsProfile } from "./LmsProfile";
+import type { NavigationMenu } from "./NavigationMenu";
+import type { ObjectivesPage } from "./ObjectivesPage";
+import type { Topic } from "./Topic";
+import type { WelcomePage } from "./WelcomePage";
+import type { WelcomeStartOptions } from "./WelcomeStartOptions";
+
+export type GenerateScormRequest = { course_title: string, course_description?: string, welcome_page?: WelcomePage, learning_objectives_page?: ObjectivesPage, topics: Array<Topic>, assessment?: Assessment, pass_mark: number, navigation_mode: string, allow_retake: boolean, enable_csp?: boolean, strict_csp?: boolean, require_audio_completion?: boolean, auto_advance?: boolean, allow_previous_review?: boolean, retake_delay?: number, completion_criteria?: string, show_progress?: boolean, show_outline?: boolean, confirm_exit?: boolean, font_size?: string, time_limit?: number, session_timeout?: number, minimum_time_spent?: number, keyboard_navigation?: boolean, printable?: boolean, high_contrast?: boolean, language?: string, 
+/**
+ * Translated copies of the course, packaged under `{language}/` with an
+ * organization of their own in the manifest
+ */
+language_variants?: Array<LanguageVariant>, 
+/**
+ * Package the diagnostics overlay (scripts/diagnostics.js) for LMS triage
+ */
+debug_build?: boolean, 
+/**
+ * Countdowns on the assessment page, separate from `time_limit`
+ */
+assessment_timer?: AssessmentTimer, 
+/**
+ * Per-page notes panel for learners, saved in suspend_data
+ */
+learner_notes?: boolean, 
+/**
+ * Page visits and interaction counts kept in suspend_data, for troubleshooting
+ * completion disputes with `decode_suspend_data`
+ */
+runtime_analytics?: boolean, 
+/**
+ * Search box over the course text, backed by an index in the course data
+ */
+course_search?: boolean, 
+/**
+ * Parent frames searched for the LMS API, `DEFAULT_API_SEARCH_DEPTH` when unset
+ */
+api_search_depth?: number, 
+/**
+ * Matomo or Google Analytics page views, for LMSs without usage reporting
+ */
+analytics?: AnalyticsSettings, 
+/**
+ * "Report a problem with this page" widget; off when unset
+ */
+feedback?: FeedbackSettings, 
+/**
+ * Resume prompt and intro video gate on the welcome page
+ */
+welcome_start?: WelcomeStartOptions, 
+/**
+ * Overview page after the objectives listing every page with completion ticks
+ */
+course_map?: boolean, 
+/**
+ * Transcript of the narration under each narrated topic, and a transcript
+ * page after the assessment
+ */
+transcripts?: boolean, 
+/**
+ * Page images open enlarged in a lightbox when clicked (default true)
+ */
+image_lightbox?: boolean, 
+/**
+ * Narration seeking, speed, autoplay and resume behaviour
+ */
+audio_policy?: AudioPolicy, 
+/**
+ * Mobile-first layout with the outline in a collapsible drawer on small screens (default true)
+ */
+responsive_layout?: boolean, 
+/**
+ * LMS the package is tuned for, `generic` when unset
+ */
+lms_profile?: LmsProfile, 
+/**
+ * Commit debounce and heartbeat
+ */
+commit_settings?: CommitSettings, 
+/**
+ * Package stand-ins for referenced media that is missing, instead of broken links
+ */
+media_placeholders?: boolean, 
+/**
+ * Refuse to build while any image lacks alt text or any video player a name
+ */
+require_alt_text?: boolean, 
+/**
+ * Whether http images and embeds are only reported, upgraded to https or downloaded
+ */
+insecure_urls?: InsecureUrlPolicy, 
+/**
+ * Generated interface features switched on or off individually
+ */
+features?: CourseFeatures, 
+/**
+ * Labels and order of the welcome, objectives and assessment pages in the menu
+ */
+navigation_menu?: NavigationMenu, 
+/**
+ * Version shown in the manifest and `version.json`, `1.0` when unset
+ */
+course_version?: string, 
+/**
+ * Keywords in the manifest metadata for LMS catalog search, derived from
+ * topic titles and objectives unless the author replaces them
+ */
+keywords?: KeywordSettings, 
+/**
+ * Questions knowledge checks and the assessment refer to by `bank_ref`
+ */
+question_bank?: Array<BankQuestion>, 
+/**
+ * Put an encrypted copy of the project in the package, to reopen it if the project file
+ * is lost; it is encrypted with the passphrase saved for the project
+ */
+source_snapshot?: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A bank exported as GIFT
+ */
+export type GiftExport = { gift: string, exported: number, 
+/**
+ * Questions left out, and parts of questions GIFT can't hold
+ */
+warnings: Array<string>, };
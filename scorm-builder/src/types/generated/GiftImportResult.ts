@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What importing a GIFT file changed
+ */
+export type GiftImportResult = { 
+/**
+ * Ids of the questions added to the bank
+ */
+added: Array<string>, 
+/**
+ * Ids of bank questions replaced by a question titled with their id
+ */
+updated: Array<string>, 
+/**
+ * Questions left out, and parts of questions that could not be kept
+ */
+warnings: Array<string>, 
+/**
+ * False for a dry run
+ */
+applied: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where an import has got to
+ */
+export type ImportPhase = "extracting" | "validating" | "copying_media" | "completing";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImportPhase } from "./ImportPhase";
+
+/**
+ * Progress of an import, reported after each entry and media file
+ */
+export type ImportProgress = { phase: ImportPhase, entriesProcessed: number, totalEntries: number, bytesWritten: number, mediaCopied: number, totalMedia: number, currentFile?: string, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaCorrection } from "./MediaCorrection";
+
+export type ImportResult = { projectPath: string, projectId: string, projectName: string, 
+/**
+ * Media moved to its page on import or, with auto-fixing off, that would
+ * have been
+ */
+mediaCorrections: Array<MediaCorrection>, 
+/**
+ * Whether `media_corrections` were applied
+ */
+mediaFixed: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Keywords set by the author
+ */
+export type KeywordSettings = { 
+/**
+ * Listed before the derived keywords
+ */
+keywords?: Array<string>, 
+/**
+ * Add keywords from topic titles and objectives (default true); when false
+ * only `keywords` are used
+ */
+derive?: boolean, };
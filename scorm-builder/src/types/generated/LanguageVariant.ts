@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GenerateScormRequest } from "./GenerateScormRequest";
+
+/**
+ * A language variant of the course. Its pages share the package's media folder;
+ * any variants nested inside `course` are ignored.
+ */
+export type LanguageVariant = { language: string, course: GenerateScormRequest, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How one term should be spoken
+ */
+export type LexiconEntry = { term: string, 
+/**
+ * Text spoken in place of the term, e.g. "sequel" for "SQL"
+ */
+alias?: string, 
+/**
+ * IPA pronunciation, used when there is no alias
+ */
+phoneme?: string, 
+/**
+ * Only match the term with exactly this capitalisation
+ */
+caseSensitive?: boolean, updatedAt?: string, };
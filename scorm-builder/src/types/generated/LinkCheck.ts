@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LinkStatus } from "./LinkStatus";
+
+/**
+ * An external link and what its server said about it
+ */
+export type LinkCheck = { url: string, status: LinkStatus, 
+/**
+ * Status of the last answer, when there was one
+ */
+http_status: number | null, 
+/**
+ * Where the redirects ended, for `redirected` links
+ */
+final_url?: string, 
+/**
+ * Every hop after `url`, in order
+ */
+redirects?: Array<string>, error?: string, 
+/**
+ * Pages the link appears on (`assessment` for the assessment questions)
+ */
+pages: Array<string>, };
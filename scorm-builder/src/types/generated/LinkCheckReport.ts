@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LinkCheck } from "./LinkCheck";
+
+export type LinkCheckReport = { links: Array<LinkCheck>, ok: number, redirected: number, broken: number, blocked: number, };
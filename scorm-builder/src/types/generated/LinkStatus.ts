@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a link's server said about it
+ */
+export type LinkStatus = "ok" | "redirected" | "broken" | "blocked";
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Who made a piece of media and under which license it may be used
+ */
+export type MediaAttribution = { 
+/**
+ * Where it was found: `google`, `unsplash` or `pexels`
+ */
+provider: string, author?: string | null, author_url?: string | null, 
+/**
+ * Page the media is published on
+ */
+source_url?: string | null, license?: string | null, license_url?: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaCorrectionAction } from "./MediaCorrectionAction";
+
+/**
+ * One media item moved, or to be moved, to its page
+ */
+export type MediaCorrection = { pageId: string, mediaId: string, mediaType: string, action: MediaCorrectionAction, };
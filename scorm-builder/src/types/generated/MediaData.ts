@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaMetadata } from "./MediaMetadata";
+
+export type MediaData = { id: string, data: Array<number>, metadata: MediaMetadata, };
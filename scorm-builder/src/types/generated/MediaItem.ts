@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type MediaItem = { id: string, filename: string, base64_data?: string, relative_path?: string, metadata: JsonValue | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaAttribution } from "./MediaAttribution";
+
+export type MediaMetadata = { page_id: string, type: string, original_name: string, mime_type?: string | null, source?: string | null, embed_url?: string | null, title?: string | null, clip_start?: number | null, clip_end?: number | null, 
+/**
+ * Text alternative used for `alt` on images and `aria-label` on players
+ */
+alt_text?: string | null, 
+/**
+ * Author and license of media found through a stock image search
+ */
+attribution?: MediaAttribution, };
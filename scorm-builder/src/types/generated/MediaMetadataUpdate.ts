@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClipTiming } from "./ClipTiming";
+
+/**
+ * Changes to one media item's metadata; unset fields are kept
+ */
+export type MediaMetadataUpdate = { media_id: string, 
+/**
+ * An empty title clears it
+ */
+title?: string | null, 
+/**
+ * Empty alt text clears it
+ */
+alt_text?: string | null, 
+/**
+ * Replaces both ends of the clip
+ */
+clip_timing?: ClipTiming | null, page_id?: string | null, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Per-project media rules applied while building a package. Only the copies
+ * written into the package are transformed; the project's media folder is
+ * never modified.
+ */
+export type MediaTransformSpec = { 
+/**
+ * Downscale videos taller than this (e.g. 720)
+ */
+max_video_height?: number, 
+/**
+ * Re-encode audio at this bitrate (e.g. 128); the result is only kept when smaller
+ */
+max_audio_bitrate_kbps?: number, 
+/**
+ * Convert JPEG and PNG images to WebP
+ */
+convert_images_to_webp: boolean, 
+/**
+ * Also package each MP3 in these encodings ("ogg", "webm") for browsers
+ * that can't play MP3; the audio player offers every encoding
+ */
+audio_fallback_formats?: Array<string>, 
+/**
+ * Package animated GIFs as video in this format ("mp4" or "webm"); pages
+ * play it with controls in place of the image
+ */
+convert_animated_gifs?: string, };
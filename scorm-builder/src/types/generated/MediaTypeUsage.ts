@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Number and total size of a project's media files of one type
+ */
+export type MediaTypeUsage = { media_type: string, files: number, bytes: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MetadataField } from "./MetadataField";
+import type { ViolationAction } from "./ViolationAction";
+
+/**
+ * An item the policy changed or warned about
+ */
+export type MetadataAuditEntry = { timestamp: string, mediaId: string, mediaType: string, action: ViolationAction, fields: Array<MetadataField>, 
+/**
+ * Where the metadata came from: `store`, `bulk_edit` or `import`
+ */
+origin: "store" | "bulk_edit" | "import", };
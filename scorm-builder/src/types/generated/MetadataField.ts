@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A metadata field a rule can forbid
+ */
+export type MetadataField = "youtube_source" | "embed_url" | "clip_start" | "clip_end";
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MetadataField } from "./MetadataField";
+import type { ViolationAction } from "./ViolationAction";
+
+/**
+ * Which metadata fields each media type may carry
+ */
+export type MetadataPolicy = { action: ViolationAction, 
+/**
+ * Fields each media type may not carry. The `*` entry applies to types
+ * without their own; types covered by neither may carry anything.
+ */
+forbidden_fields: { [key in string]?: Array<MetadataField> }, };
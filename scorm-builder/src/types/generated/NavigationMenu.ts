@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the fixed pages appear in the course menu. Unset fields keep the
+ * built-in labels and order, so existing projects build unchanged.
+ */
+export type NavigationMenu = { 
+/**
+ * Menu label of the welcome page (default "Welcome")
+ */
+welcome_label?: string, 
+/**
+ * Menu label and heading of the objectives page (default "Learning Objectives")
+ */
+objectives_label?: string, 
+/**
+ * Menu label and heading of the assessment (default "Assessment")
+ */
+assessment_label?: string, 
+/**
+ * Leave the objectives page out of the package
+ */
+hide_objectives?: boolean, 
+/**
+ * Put the assessment straight after this topic rather than after the last
+ */
+assessment_after_topic?: string, 
+/**
+ * Title of the course's item in the LMS table of contents (default the course title)
+ */
+manifest_item_title?: string, };
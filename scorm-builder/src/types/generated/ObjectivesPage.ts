@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScormMediaItem } from "./ScormMediaItem";
+
+export type ObjectivesPage = { objectives: Array<string>, audio_file?: string, caption_file?: string, image_url?: string, media?: Array<ScormMediaItem>, };
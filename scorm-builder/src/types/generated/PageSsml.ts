@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * SSML of one page's narration
+ */
+export type PageSsml = { pageId: string, title?: string, 
+/**
+ * `<speak>` document with lexicon terms wrapped in `<sub>` or `<phoneme>`
+ */
+ssml: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How an encrypted project's key is derived, kept in its readable header
+ */
+export type ProjectEncryption = { kdf: string, memory_kib: number, iterations: number, parallelism: number, salt: string, 
+/**
+ * `KEY_CHECK` sealed with the key
+ */
+key_check: string, };
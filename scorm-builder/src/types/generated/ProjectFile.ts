@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AiPromptData } from "./AiPromptData";
+import type { AudioSettings } from "./AudioSettings";
+import type { CourseData } from "./CourseData";
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { ProjectMediaData } from "./ProjectMediaData";
+import type { ProjectMetadata } from "./ProjectMetadata";
+import type { ScormConfig } from "./ScormConfig";
+
+export type ProjectFile = { project: ProjectMetadata, course_data: CourseData, ai_prompt: AiPromptData | null, course_content: JsonValue | null, media: ProjectMediaData, audio_settings: AudioSettings, scorm_config: ScormConfig, course_seed_data?: JsonValue, json_import_data?: JsonValue, activities_data?: JsonValue, media_enhancements?: JsonValue, content_edits?: JsonValue, current_step?: string, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaItem } from "./MediaItem";
+import type { VideoItem } from "./VideoItem";
+
+export type ProjectMediaData = { images: Array<MediaItem>, videos: Array<VideoItem>, audio: Array<MediaItem>, captions?: Array<MediaItem>, };
@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContentLayout } from "./ContentLayout";
+import type { ProjectEncryption } from "./ProjectEncryption";
+import type { ProjectStatus } from "./ProjectStatus";
+
+export type ProjectMetadata = { id: string, name: string, created: string, last_modified: string, path?: string, 
+/**
+ * How course_content is stored on disk; absent means inline
+ */
+content_layout?: ContentLayout, 
+/**
+ * Set when the project's content is encrypted (see `project_encryption`)
+ */
+encryption?: ProjectEncryption, 
+/**
+ * Set when loading: whether changes can be saved. Never written to disk.
+ */
+is_writable?: boolean, 
+/**
+ * Set when listing, from the projects index. Never written to the project file.
+ */
+tags?: Array<string>, 
+/**
+ * Set when listing, from the projects index. Never written to the project file.
+ */
+status?: ProjectStatus, 
+/**
+ * Set when listing: `created` and `last_modified` in local time. Never
+ * written to the project file.
+ */
+local_times?: { timezone: string } & Record<string, string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a course is on its way to learners
+ */
+export type ProjectStatus = "draft" | "in_review" | "published";
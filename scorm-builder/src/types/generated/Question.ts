@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackMedia } from "./FeedbackMedia";
+
+export type Question = { type?: string, text?: string, options?: Array<string>, correct_answer?: string, explanation?: string, correct_feedback?: string, incorrect_feedback?: string, 
+/**
+ * Image or audio shown with the correct feedback
+ */
+correct_feedback_media?: FeedbackMedia, 
+/**
+ * Image or audio shown with the incorrect feedback
+ */
+incorrect_feedback_media?: FeedbackMedia, 
+/**
+ * Id of the `question_bank` question this stands for; the other fields
+ * are replaced by the bank's before the course renders
+ */
+bank_ref?: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationKind } from "./OperationKind";
+
+/**
+ * An operation holding a project
+ */
+export type RunningOperation = { operationId: string, projectId: string, kind: OperationKind, startedAt: string, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaTransformSpec } from "./MediaTransformSpec";
+
+export type ScormConfig = { version: string, completion_criteria: string, passing_score: number, 
+/**
+ * Media rules applied to the packaged copies of the media
+ */
+media_transforms?: MediaTransformSpec, 
+/**
+ * Deflate level (0-9) for text assets in the package; already-compressed media is stored
+ */
+zip_compression_level?: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaAttribution } from "./MediaAttribution";
+
+export type ScormMediaItem = { id: string, type: string, url: string, title: string, embed_url?: string, is_youtube?: boolean, clip_start?: number, clip_end?: number, 
+/**
+ * Text alternative for the image, or accessible name for the player
+ */
+alt_text?: string, 
+/**
+ * Author and license, listed on the credits page
+ */
+attribution?: MediaAttribution, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Media size budget of a project, or the workspace default
+ */
+export type StorageBudget = { 
+/**
+ * Media size past which the project statistics warn; never when null
+ */
+warn_bytes: number | null, 
+/**
+ * Media size `store_media` won't take the project past; no limit when null
+ */
+block_bytes: number | null, 
+/**
+ * Largest total per media type (`image`, `audio`, ...), in bytes.
+ * Types without an entry are only limited by `block_bytes`.
+ */
+max_type_bytes: Record<string, number>, };
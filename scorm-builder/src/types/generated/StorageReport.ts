@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BudgetStatus } from "./BudgetStatus";
+import type { MediaTypeUsage } from "./MediaTypeUsage";
+import type { StorageBudget } from "./StorageBudget";
+
+/**
+ * A project's media size against its budget
+ */
+export type StorageReport = { total_bytes: number, budget: StorageBudget, status: BudgetStatus, 
+/**
+ * Largest first
+ */
+by_type: Array<MediaTypeUsage>, 
+/**
+ * What to do about a project past its warning level
+ */
+warning?: { message: string, messageKey: string, params: Record<string, string> }, };
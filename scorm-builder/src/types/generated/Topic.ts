@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { KnowledgeCheck } from "./KnowledgeCheck";
+import type { ScormMediaItem } from "./ScormMediaItem";
+
+export type Topic = { id: string, title: string, content: string, knowledge_check?: KnowledgeCheck, audio_file?: string, caption_file?: string, image_url?: string, media?: Array<ScormMediaItem>, 
+/**
+ * Narration script, used for the topic's transcript
+ */
+narration?: string, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How learners start the course from the welcome page
+ */
+export type WelcomeStartOptions = { 
+/**
+ * Ask returning learners whether to resume at their bookmark or start
+ * over, instead of taking them straight back (default false)
+ */
+resume_prompt?: boolean, 
+/**
+ * Id of a welcome page video (from its `media`) learners have to watch
+ * before they can start the course
+ */
+intro_video_id?: string, 
+/**
+ * Share of the intro video that counts as watched, in percent (default 90)
+ */
+intro_video_min_percent?: number, };
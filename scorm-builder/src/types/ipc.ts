@@ -0,0 +1,81 @@
+// Payloads of the Tauri commands and events, generated from the Rust types in
+// src/types/generated. Run `npm run generate:types` after changing one of them.
+
+export type { AnalyticsProvider } from './generated/AnalyticsProvider'
+export type { AnalyticsSettings } from './generated/AnalyticsSettings'
+export type { AnimatedGifInfo } from './generated/AnimatedGifInfo'
+export type { Assessment } from './generated/Assessment'
+export type { AssessmentTimer } from './generated/AssessmentTimer'
+export type { AudioPolicy } from './generated/AudioPolicy'
+export type { BackupDeletionReason } from './generated/BackupDeletionReason'
+export type { BackupFileReport } from './generated/BackupFileReport'
+export type { BankQuestion } from './generated/BankQuestion'
+export type { BudgetStatus } from './generated/BudgetStatus'
+export type { CleanupResult } from './generated/CleanupResult'
+export type { ClipProblem } from './generated/ClipProblem'
+export type { ClipTiming } from './generated/ClipTiming'
+export type { ClipTimingIssue } from './generated/ClipTimingIssue'
+export type { CommitSettings } from './generated/CommitSettings'
+export type { ContentLayout } from './generated/ContentLayout'
+export type { CourseFeatures } from './generated/CourseFeatures'
+export type { DeleteMediaResult } from './generated/DeleteMediaResult'
+export type { EncryptionStatus } from './generated/EncryptionStatus'
+export type { FeedbackMedia } from './generated/FeedbackMedia'
+export type { FeedbackMediaType } from './generated/FeedbackMediaType'
+export type { FeedbackSettings } from './generated/FeedbackSettings'
+export type { GenerateScormRequest } from './generated/GenerateScormRequest'
+export type { GiftExport } from './generated/GiftExport'
+export type { GiftImportResult } from './generated/GiftImportResult'
+export type { ImportPhase } from './generated/ImportPhase'
+export type { ImportProgress } from './generated/ImportProgress'
+export type { ImportResult } from './generated/ImportResult'
+export type { InsecureUrlPolicy } from './generated/InsecureUrlPolicy'
+export type { KeywordSettings } from './generated/KeywordSettings'
+export type { KnowledgeCheck } from './generated/KnowledgeCheck'
+export type { LanguageVariant } from './generated/LanguageVariant'
+export type { LexiconEntry } from './generated/LexiconEntry'
+export type { LinkCheck } from './generated/LinkCheck'
+export type { LinkCheckReport } from './generated/LinkCheckReport'
+export type { LinkStatus } from './generated/LinkStatus'
+export type { LmsProfile } from './generated/LmsProfile'
+export type { MediaAttribution } from './generated/MediaAttribution'
+export type { MediaCorrection } from './generated/MediaCorrection'
+export type { MediaCorrectionAction } from './generated/MediaCorrectionAction'
+export type { MediaData } from './generated/MediaData'
+export type { MediaMetadata } from './generated/MediaMetadata'
+export type { MediaMetadataInfo } from './generated/MediaMetadataInfo'
+export type { MediaMetadataUpdate } from './generated/MediaMetadataUpdate'
+export type { MediaTransformSpec } from './generated/MediaTransformSpec'
+export type { MediaTypeUsage } from './generated/MediaTypeUsage'
+export type { MetadataAuditEntry } from './generated/MetadataAuditEntry'
+export type { MetadataField } from './generated/MetadataField'
+export type { MetadataPolicy } from './generated/MetadataPolicy'
+export type { NavigationMenu } from './generated/NavigationMenu'
+export type { ObjectivesPage } from './generated/ObjectivesPage'
+export type { OperationKind } from './generated/OperationKind'
+export type { PageSsml } from './generated/PageSsml'
+export type { ProjectEncryption } from './generated/ProjectEncryption'
+export type { ProjectFile } from './generated/ProjectFile'
+export type { ProjectMetadata } from './generated/ProjectMetadata'
+export type { ProjectStatus } from './generated/ProjectStatus'
+export type { Question } from './generated/Question'
+export type { RunningOperation } from './generated/RunningOperation'
+export type { ScormConfig } from './generated/ScormConfig'
+export type { ScormMediaItem } from './generated/ScormMediaItem'
+export type { StorageBudget } from './generated/StorageBudget'
+export type { StorageReport } from './generated/StorageReport'
+export type { Topic } from './generated/Topic'
+export type { ViolationAction } from './generated/ViolationAction'
+export type { WelcomePage } from './generated/WelcomePage'
+export type { WelcomeStartOptions } from './generated/WelcomeStartOptions'
+export type { ZipExportResult } from './generated/ZipExportResult'
+
+/**
+ * A progress event payload with the localized message `progress_event` adds,
+ * and the key and params it was rendered from
+ */
+export type ProgressEvent<T> = T & {
+  message: string
+  messageKey: string
+  params: Record<string, string>
+}
@@ -1,17 +1,9 @@
+import type { MediaAttribution } from './ipc'
+
 // Core types for the new media system
 
-// Media metadata structure that matches the Rust backend
-export interface MediaMetadata {
-  page_id: string
-  type: 'image' | 'video' | 'audio' | 'caption' | 'youtube'
-  original_name: string
-  mime_type?: string
-  source?: 'upload' | 'search' | 'library'
-  embed_url?: string
-  title?: string
-  clip_start?: number // Start time in seconds for video clips
-  clip_end?: number   // End time in seconds for video clips
-}
+// Media metadata as the Rust backend stores it, generated from its struct
+export type { MediaMetadata } from './ipc'
 
 // Legacy metadata format with camelCase field names
 export interface LegacyMediaMetadata {
@@ -22,6 +14,8 @@ export interface LegacyMediaMetadata {
   title?: string
   clipStart?: number // Start time in seconds for video clips
   clipEnd?: number   // End time in seconds for video clips
+  altText?: string
+  attribution?: MediaAttribution
 }
 
 export interface PageContent {
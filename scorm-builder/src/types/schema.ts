@@ -171,6 +171,13 @@ const PageSchema = z.object({
   captionFile: z.string().optional(),
   audioId: z.string().optional(),
   captionId: z.string().optional(),
+  authorNotes: z.string().optional(),
+});
+
+const FeedbackMediaSchema = z.object({
+  mediaId: z.string(),
+  type: z.enum(["image", "audio"]),
+  altText: z.string().optional(),
 });
 
 const TopicSchema = PageSchema.extend({
@@ -186,7 +193,10 @@ const TopicSchema = PageSchema.extend({
       feedback: z.object({
         correct: z.string(),
         incorrect: z.string(),
+        correctMedia: FeedbackMediaSchema.optional(),
+        incorrectMedia: FeedbackMediaSchema.optional(),
       }).optional(),
+      authorNotes: z.string().optional(),
     }))
   }).optional(),
 });